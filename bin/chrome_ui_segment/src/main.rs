@@ -1,16 +1,22 @@
 use active_win_pos_rs::get_active_window;
 use anyhow::{anyhow, Context, Result};
-use chrono::Local;
+use chrono::{Local, Utc};
 use image::{self, DynamicImage, GenericImageView, ImageReader, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::str::FromStr;
 
 fn main() -> Result<()> {
+    let probe = probe_config_from_args(std::env::args().skip(1))?;
     let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let captured_at = Utc::now().to_rfc3339();
+    let active = get_active_window().ok();
     // Resolve app folder from active window (fallback to "unknown")
-    let app_folder = get_active_window()
-        .ok()
+    let app_folder = active
+        .as_ref()
         .map(|w| sanitize_name(&w.app_name))
         .filter(|s| !s.is_empty())
         .unwrap_or_else(|| "unknown".to_string());
@@ -32,32 +38,223 @@ fn main() -> Result<()> {
         .decode()
         .context("Failed to decode image")?;
 
-    // Horizontal probes: detect vertical boundaries from long uniform color runs
-    let (probe_cuts, axis_overlay) = compute_horizontal_probe_debug(&img, 50, 28.0, 6);
+    // Detect vertical boundaries with the selected probe strategy
+    let (probe_cuts, axis_overlay) = compute_vertical_cuts(&img, &probe);
     let _ = axis_overlay.save(out_dir.join("debug_axis.png"));
+    println!("Vertical cuts ({}): {:?}", probe.strategy, probe_cuts);
 
     // Replace pixel-level color blobs with large layout blocks
     let bboxes = segment_layout_blocks(&img);
     // Also save a best-effort visual hierarchy set
     let _ = write_hierarchy_crops(&img, &bboxes, &out_dir);
 
-    for (i, (bx, by, bw, bh)) in bboxes.iter().enumerate() {
-        let crop = image::imageops::crop_imm(&img, *bx, *by, *bw, *bh).to_image();
-        let seg_path = out_dir.join(format!("segment_{:03}.png", i));
-        crop.save(&seg_path)
-            .with_context(|| format!("Failed to save {}", seg_path.display()))?;
-    }
+    let segments = save_segments(&img, &bboxes, &out_dir)?;
+    let manifest = SegmentManifest {
+        source_app: active.as_ref().map(|w| w.app_name.clone()),
+        process_id: active.as_ref().map(|w| w.process_id),
+        timestamp: captured_at,
+        window_bounds: WindowBounds {
+            x,
+            y,
+            width: w,
+            height: h,
+        },
+        display_scale: img.width() as f32 / w.max(1) as f32,
+        probe_strategy: probe.strategy.to_string(),
+        vertical_cuts: probe_cuts,
+        segments,
+    };
+    let manifest_path = write_manifest(&manifest, &out_dir)?;
 
     println!(
-        "Saved {} segment(s) to {}\nOriginal: {}",
+        "Saved {} segment(s) to {}\nOriginal: {}\nManifest: {}",
         bboxes.len(),
         out_dir.display(),
-        original_path.display()
+        original_path.display(),
+        manifest_path.display()
     );
 
     Ok(())
 }
 
+// Machine-readable description of one run, written next to the crops as segments.json.
+// The timestamp and source app tie the crops to the tracker's events.
+#[derive(Debug, Serialize, Deserialize)]
+struct SegmentManifest {
+    source_app: Option<String>,
+    process_id: Option<u64>,
+    // RFC 3339, UTC
+    timestamp: String,
+    // Captured window in screen points
+    window_bounds: WindowBounds,
+    // Image pixels per screen point (2.0 on Retina displays)
+    display_scale: f32,
+    probe_strategy: String,
+    vertical_cuts: Vec<u32>,
+    segments: Vec<SegmentEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WindowBounds {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SegmentEntry {
+    file: String,
+    // Pixel rectangle in original.png
+    bbox: SegmentBox,
+    role: SegmentRole,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct SegmentBox {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SegmentRole {
+    Header,
+    Sidebar,
+    Content,
+}
+
+// Save each block as segment_NNN.png and describe it for the manifest
+fn save_segments(
+    img: &DynamicImage,
+    bboxes: &[(u32, u32, u32, u32)],
+    out_dir: &Path,
+) -> Result<Vec<SegmentEntry>> {
+    let (w, h) = img.dimensions();
+    let layout = classify_blocks(bboxes, w, h);
+    let mut entries = Vec::with_capacity(bboxes.len());
+    for (i, &(bx, by, bw, bh)) in bboxes.iter().enumerate() {
+        let crop = image::imageops::crop_imm(img, bx, by, bw, bh).to_image();
+        let file = format!("segment_{:03}.png", i);
+        let seg_path = out_dir.join(&file);
+        crop.save(&seg_path)
+            .with_context(|| format!("Failed to save {}", seg_path.display()))?;
+        entries.push(SegmentEntry {
+            file,
+            bbox: SegmentBox {
+                x: bx,
+                y: by,
+                width: crop.width(),
+                height: crop.height(),
+            },
+            role: layout.role_of((bx, by, bw, bh)),
+        });
+    }
+    Ok(entries)
+}
+
+fn write_manifest(manifest: &SegmentManifest, out_dir: &Path) -> Result<PathBuf> {
+    let path = out_dir.join("segments.json");
+    let json = serde_json::to_string_pretty(manifest).context("Failed to encode manifest")?;
+    fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+// How vertical boundaries between layout columns are found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProbeStrategy {
+    // Long uniform color runs on a few sampled rows
+    ColorRuns,
+    // Columns whose horizontal color gradient is high on most rows
+    ColumnGradient,
+}
+
+impl std::fmt::Display for ProbeStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProbeStrategy::ColorRuns => write!(f, "runs"),
+            ProbeStrategy::ColumnGradient => write!(f, "gradient"),
+        }
+    }
+}
+
+impl FromStr for ProbeStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "runs" => Ok(ProbeStrategy::ColorRuns),
+            "gradient" => Ok(ProbeStrategy::ColumnGradient),
+            other => Err(anyhow!(
+                "Unknown probe strategy '{}' (expected runs or gradient)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ProbeConfig {
+    strategy: ProbeStrategy,
+    // Pixels of uniform color needed on each side of a boundary (runs)
+    run_len: u32,
+    // RGB distance at which two colors count as different
+    tol: f32,
+    // Rows sampled across the image (runs)
+    num_scans: usize,
+    // Share of rows a column edge must cross to be a boundary (gradient)
+    min_coverage: f32,
+}
+
+impl Default for ProbeConfig {
+    fn default() -> Self {
+        Self {
+            strategy: ProbeStrategy::ColorRuns,
+            run_len: 50,
+            tol: 28.0,
+            num_scans: 6,
+            min_coverage: 0.6,
+        }
+    }
+}
+
+// Read `--probe-strategy runs|gradient`, `--run-len`, `--tolerance`, `--scans` and
+// `--min-coverage`; anything not given keeps its default.
+fn probe_config_from_args(args: impl IntoIterator<Item = String>) -> Result<ProbeConfig> {
+    let mut config = ProbeConfig::default();
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| anyhow!("Missing value for {}", flag))?;
+        let invalid = || format!("Invalid value '{}' for {}", value, flag);
+        match flag.as_str() {
+            "--probe-strategy" => config.strategy = value.parse()?,
+            "--run-len" => config.run_len = value.parse().with_context(invalid)?,
+            "--tolerance" => config.tol = value.parse().with_context(invalid)?,
+            "--scans" => config.num_scans = value.parse().with_context(invalid)?,
+            "--min-coverage" => config.min_coverage = value.parse().with_context(invalid)?,
+            other => return Err(anyhow!("Unknown argument '{}'", other)),
+        }
+    }
+    Ok(config)
+}
+
+// Vertical boundaries by the configured strategy, as sorted x-positions strictly inside
+// the image, with a debug overlay of the hits (red) and the chosen cuts (yellow).
+fn compute_vertical_cuts(img: &DynamicImage, config: &ProbeConfig) -> (Vec<u32>, RgbaImage) {
+    match config.strategy {
+        ProbeStrategy::ColorRuns => {
+            compute_horizontal_probe_debug(img, config.run_len, config.tol, config.num_scans)
+        }
+        ProbeStrategy::ColumnGradient => {
+            compute_column_gradient_debug(img, config.tol, config.min_coverage)
+        }
+    }
+}
+
 // Compute vertical boundaries by scanning a few horizontal probe rows. A boundary exists
 // where N consecutive pixels are near a color A, followed by N consecutive pixels near color B.
 // Returns clustered cut x-positions and a debug overlay image with lines and probe hits.
@@ -87,9 +284,52 @@ fn compute_horizontal_probe_debug(
     let mut xs: Vec<u32> = events.iter().map(|(x, _)| *x).collect();
     xs = cluster_positions(xs, ((w as f32) * 0.015).round() as u32, 2, w);
 
-    // Build overlay: screenshot as background, red dots for raw events, yellow lines for cuts
-    let mut overlay = rgba.clone();
-    for (x, y) in events {
+    let overlay = draw_cut_overlay(rgba, &events, &xs);
+    (xs, overlay)
+}
+
+// Compute vertical boundaries from column edges: a column is an edge on a row when it
+// differs from its left neighbour by more than `tol`, and a boundary when it is an edge
+// on at least `min_coverage` of the rows. Text and icons make edges on a few rows only,
+// while a sidebar or panel border crosses most of them.
+fn compute_column_gradient_debug(
+    img: &DynamicImage,
+    tol: f32,
+    min_coverage: f32,
+) -> (Vec<u32>, RgbaImage) {
+    let rgba = img.to_rgba8();
+    let (w, h) = rgba.dimensions();
+    if w < 2 || h == 0 {
+        return (vec![], rgba);
+    }
+
+    let rgb = |x: u32, y: u32| {
+        let p = rgba.get_pixel(x, y);
+        [p[0] as f32, p[1] as f32, p[2] as f32]
+    };
+    let mut events: Vec<(u32, u32)> = Vec::new();
+    let mut xs: Vec<u32> = Vec::new();
+    for x in 1..w {
+        let edges: Vec<u32> = (0..h)
+            .filter(|&y| rgb_distance(rgb(x - 1, y), rgb(x, y)) > tol)
+            .collect();
+        if edges.len() as f32 >= min_coverage * h as f32 {
+            xs.push(x);
+            events.extend(edges.into_iter().map(|y| (x, y)));
+        }
+    }
+    // Soft edges span a few columns; keep one cut per boundary
+    xs = cluster_positions(xs, ((w as f32) * 0.015).round() as u32, 1, w);
+
+    let overlay = draw_cut_overlay(rgba, &events, &xs);
+    (xs, overlay)
+}
+
+// Build overlay: screenshot as background, red dots for raw events, yellow lines for cuts
+fn draw_cut_overlay(rgba: RgbaImage, events: &[(u32, u32)], xs: &[u32]) -> RgbaImage {
+    let (w, h) = rgba.dimensions();
+    let mut overlay = rgba;
+    for &(x, y) in events {
         let p = overlay.get_pixel_mut(x.min(w - 1), y.min(h - 1));
         // red dot
         p[0] = 255;
@@ -105,7 +345,7 @@ fn compute_horizontal_probe_debug(
             p[1] = 255;
         }
     }
-    (xs, overlay)
+    overlay
 }
 
 fn probe_row(img: &RgbaImage, y: u32, run_len: u32, tol: f32) -> Vec<u32> {
@@ -457,12 +697,29 @@ fn segment_layout_blocks(img: &DynamicImage) -> Vec<(u32, u32, u32, u32)> {
     bboxes
 }
 
-fn write_hierarchy_crops(
-    img: &DynamicImage,
-    blocks: &[(u32, u32, u32, u32)],
-    out_dir: &Path,
-) -> Result<()> {
-    let (w, h) = img.dimensions();
+// The blocks that look like the page header, the sidebars and the main content
+#[derive(Debug)]
+struct LayoutRoles {
+    header: Option<(u32, u32, u32, u32)>,
+    left: Option<(u32, u32, u32, u32)>,
+    right: Option<(u32, u32, u32, u32)>,
+    main: Option<(u32, u32, u32, u32)>,
+}
+
+impl LayoutRoles {
+    // Blocks that are neither header nor sidebar count as content
+    fn role_of(&self, b: (u32, u32, u32, u32)) -> SegmentRole {
+        if self.header == Some(b) {
+            SegmentRole::Header
+        } else if self.left == Some(b) || self.right == Some(b) {
+            SegmentRole::Sidebar
+        } else {
+            SegmentRole::Content
+        }
+    }
+}
+
+fn classify_blocks(blocks: &[(u32, u32, u32, u32)], w: u32, h: u32) -> LayoutRoles {
     let area = |b: &(u32, u32, u32, u32)| -> u64 { b.2 as u64 * b.3 as u64 };
     let mut header: Option<(u32, u32, u32, u32)> = None;
     let mut left: Option<(u32, u32, u32, u32)> = None;
@@ -510,6 +767,27 @@ fn write_hierarchy_crops(
         main = Some(b);
     }
 
+    LayoutRoles {
+        header,
+        left,
+        right,
+        main,
+    }
+}
+
+fn write_hierarchy_crops(
+    img: &DynamicImage,
+    blocks: &[(u32, u32, u32, u32)],
+    out_dir: &Path,
+) -> Result<()> {
+    let (w, h) = img.dimensions();
+    let LayoutRoles {
+        header,
+        left,
+        right,
+        main,
+    } = classify_blocks(blocks, w, h);
+
     let mut save_crop = |name: &str, b: Option<(u32, u32, u32, u32)>| -> Result<()> {
         if let Some((bx, by, bw, bh)) = b {
             let crop = image::imageops::crop_imm(
@@ -534,22 +812,52 @@ fn write_hierarchy_crops(
     Ok(())
 }
 
+// Merge intersecting boxes into their bounding boxes until no two intersect. Boxes are
+// grouped with a union-find over pairwise intersection; a merged box can reach boxes its
+// parts did not, so grouping repeats on the merged set until it is stable. The result
+// depends only on the set of boxes, not their order, and is sorted top-to-bottom,
+// left-to-right.
 fn merge_overlapping_bboxes(boxes: &mut Vec<(u32, u32, u32, u32)>) {
-    let mut changed = true;
-    while changed {
-        changed = false;
-        'outer: for i in 0..boxes.len() {
-            for j in (i + 1)..boxes.len() {
+    loop {
+        let n = boxes.len();
+        let mut parent: Vec<usize> = (0..n).collect();
+        for i in 0..n {
+            for j in (i + 1)..n {
                 if intersects(boxes[i], boxes[j]) {
-                    let merged = merge(boxes[i], boxes[j]);
-                    boxes.remove(j);
-                    boxes[i] = merged;
-                    changed = true;
-                    break 'outer;
+                    let (ri, rj) = (find_root(&mut parent, i), find_root(&mut parent, j));
+                    if ri != rj {
+                        parent[ri.max(rj)] = ri.min(rj);
+                    }
                 }
             }
         }
+
+        let mut groups: BTreeMap<usize, (u32, u32, u32, u32)> = BTreeMap::new();
+        for i in 0..n {
+            let root = find_root(&mut parent, i);
+            groups
+                .entry(root)
+                .and_modify(|b| *b = merge(*b, boxes[i]))
+                .or_insert(boxes[i]);
+        }
+        let mut merged: Vec<(u32, u32, u32, u32)> = groups.into_values().collect();
+        merged.sort_unstable_by_key(|&(x, y, w, h)| (y, x, h, w));
+        merged.dedup();
+
+        let stable = merged.len() == n;
+        *boxes = merged;
+        if stable {
+            break;
+        }
+    }
+}
+
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
     }
+    i
 }
 
 fn intersects(a: (u32, u32, u32, u32), b: (u32, u32, u32, u32)) -> bool {
@@ -596,3 +904,143 @@ fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
     let h_norm = if h < 0.0 { h + 360.0 } else { h } / 360.0;
     (h_norm, s, v)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_boxes_do_not_depend_on_input_order() {
+        // Two chains that only meet once merged, a disjoint sidebar and a duplicate
+        let boxes = vec![
+            (0, 0, 100, 20),
+            (90, 10, 30, 30),
+            (0, 60, 40, 200),
+            (115, 35, 20, 20),
+            (300, 0, 50, 50),
+            (300, 0, 50, 50),
+            (130, 0, 20, 12),
+            (200, 100, 80, 80),
+            (150, 100, 60, 10),
+        ];
+        let mut expected = boxes.clone();
+        merge_overlapping_bboxes(&mut expected);
+        assert_eq!(
+            expected,
+            vec![
+                (0, 0, 150, 55),
+                (300, 0, 50, 50),
+                (0, 60, 40, 200),
+                (150, 100, 130, 80),
+            ]
+        );
+
+        // Every rotation and reversal, plus a fixed shuffle
+        let mut orders = Vec::new();
+        for r in 0..boxes.len() {
+            let mut rotated = boxes.clone();
+            rotated.rotate_left(r);
+            orders.push(rotated.clone());
+            rotated.reverse();
+            orders.push(rotated);
+        }
+        orders.push(
+            [5, 2, 8, 0, 7, 3, 1, 6, 4]
+                .iter()
+                .map(|&i| boxes[i])
+                .collect(),
+        );
+        for mut order in orders {
+            merge_overlapping_bboxes(&mut order);
+            assert_eq!(order, expected);
+        }
+    }
+
+    // A sidebar, a content column and an inspector with boundaries at x=150 and x=450,
+    // with dark "text" lines in the content column
+    fn three_column_page() -> DynamicImage {
+        let img = RgbaImage::from_fn(600, 400, |x, y| {
+            let text = (180..420).contains(&x) && y % 24 < 3 && (40..360).contains(&y);
+            match x {
+                _ if text => Rgba([30, 30, 30, 255]),
+                0..=149 => Rgba([232, 234, 237, 255]),
+                150..=449 => Rgba([255, 255, 255, 255]),
+                _ => Rgba([60, 64, 67, 255]),
+            }
+        });
+        DynamicImage::ImageRgba8(img)
+    }
+
+    #[test]
+    fn both_probe_strategies_find_known_column_boundaries() {
+        let img = three_column_page();
+        let expected = [150u32, 450];
+        for strategy in ["runs", "gradient"] {
+            let args = ["--probe-strategy", strategy, "--tolerance", "12"];
+            let config = probe_config_from_args(args.map(String::from)).unwrap();
+            let (cuts, overlay) = compute_vertical_cuts(&img, &config);
+            assert_eq!(overlay.dimensions(), (600, 400));
+            assert_eq!(cuts.len(), expected.len(), "{}: {:?}", strategy, cuts);
+            for (cut, want) in cuts.iter().zip(expected) {
+                assert!(cut.abs_diff(want) <= 2, "{}: {:?}", strategy, cuts);
+            }
+        }
+
+        assert!(probe_config_from_args(["--probe-strategy", "edges"].map(String::from)).is_err());
+        let defaults = probe_config_from_args(Vec::new()).unwrap();
+        assert_eq!(defaults.strategy, ProbeStrategy::ColorRuns);
+        assert_eq!((defaults.run_len, defaults.num_scans), (50, 6));
+    }
+
+    #[test]
+    fn manifest_lists_every_saved_segment() {
+        let out_dir = std::env::temp_dir().join(format!("segments_{}", std::process::id()));
+        fs::create_dir_all(&out_dir).unwrap();
+        let img = three_column_page();
+        let bboxes = segment_layout_blocks(&img);
+        assert!(!bboxes.is_empty());
+
+        let segments = save_segments(&img, &bboxes, &out_dir).unwrap();
+        let manifest = SegmentManifest {
+            source_app: Some("Google Chrome".to_string()),
+            process_id: Some(412),
+            timestamp: Utc::now().to_rfc3339(),
+            window_bounds: WindowBounds {
+                x: 0,
+                y: 25,
+                width: 300,
+                height: 200,
+            },
+            display_scale: img.width() as f32 / 300.0,
+            probe_strategy: ProbeStrategy::ColorRuns.to_string(),
+            vertical_cuts: vec![150, 450],
+            segments,
+        };
+        let path = write_manifest(&manifest, &out_dir).unwrap();
+
+        let read: SegmentManifest =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let saved = fs::read_dir(&out_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with("segment_"))
+            .count();
+        assert_eq!(read.segments.len(), saved);
+        assert_eq!(read.segments.len(), bboxes.len());
+        assert_eq!(read.display_scale, 2.0);
+        for (entry, &(bx, by, _, _)) in read.segments.iter().zip(&bboxes) {
+            let b = entry.bbox;
+            assert_eq!((b.x, b.y), (bx, by));
+            assert!(b.width > 0 && b.height > 0, "{:?}", entry);
+            assert!(b.x + b.width <= img.width() && b.y + b.height <= img.height());
+            let crop = ImageReader::open(out_dir.join(&entry.file))
+                .unwrap()
+                .decode()
+                .unwrap();
+            assert_eq!(crop.dimensions(), (b.width, b.height));
+        }
+        assert!(read.segments.iter().any(|e| e.role == SegmentRole::Sidebar));
+
+        let _ = fs::remove_dir_all(&out_dir);
+    }
+}
@@ -2,6 +2,9 @@
 //! Flutter Rust Bridge API for clipboard monitoring
 //! This provides a streaming interface to the real AppSwitcher from main.rs
 
+use std::ffi::{c_void, CString};
+use std::os::raw::{c_char, c_int};
+use std::sync::mpsc;
 use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
 use std::time::Duration;
@@ -15,15 +18,17 @@ use objc2_app_kit::{NSApplication, NSApplicationActivationPolicy, NSWorkspace, N
 use objc2_foundation::{NSAutoreleasePool, NSRunLoop, NSDefaultRunLoopMode, NSDate, NSString, NSNotification, NSArray};
 use dispatch::Queue;
 use block2::RcBlock;
+use flutter_rust_bridge::frb;
 use std::ptr::NonNull;
 
 // Import the actual AppSwitcher system from main.rs
 use crate::core::app_switcher::{
-    initialize_app_switcher, AppSwitchEvent, AppSwitchListener, AppSwitchType, AppSwitcher,
+    initialize_app_switcher, AppSwitchEvent, AppSwitchListener, AppSwitcher, PauseControl,
 };
 
 // Import enhanced context modules for rich clipboard context
 use crate::core::accessibility::{extract_accessibility_context};
+use crate::core::preview::truncate_preview;
 use crate::core::spaces::{query_spaces};
 
 // Import StreamSink from FRB generated
@@ -87,15 +92,6 @@ fn get_format_emoji(format_type: &str) -> &'static str {
     }
 }
 
-/// Safe Unicode-aware string truncation
-fn safe_truncate(s: &str, max_chars: usize) -> String {
-    if s.chars().count() <= max_chars {
-        s.to_string()
-    } else {
-        s.chars().take(max_chars).collect::<String>() + "..."
-    }
-}
-
 /// App information for Dart
 #[derive(Debug, Clone)]
 pub struct DartAppInfo {
@@ -212,14 +208,7 @@ fn convert_to_dart_event(event: &AppSwitchEvent) -> DartAppSwitchEventData {
         path: prev.path.clone(),
     });
 
-    let event_type = match event.event_type {
-        AppSwitchType::Foreground => "foreground".to_string(),
-        AppSwitchType::Background => "background".to_string(),
-        AppSwitchType::Launch => "launch".to_string(),
-        AppSwitchType::Terminate => "terminate".to_string(),
-        AppSwitchType::Hide => "hide".to_string(),
-        AppSwitchType::Unhide => "unhide".to_string(),
-    };
+    let event_type = event.event_type.as_str().to_string();
 
     let window_title = event
         .workspace
@@ -279,6 +268,122 @@ where
     }
 }
 
+// Native host callbacks
+//
+// Hosts that are not Dart (Swift, C, C++) register a plain function pointer
+// instead of consuming the stream. Every event delivered to the stream is
+// also passed to the callback as a JSON object with `event_type`, `app`,
+// `previous_app`, `window_title` and `url`; `app` and `previous_app` carry
+// `name`, `bundle_id`, `pid` and `path`.
+//
+// Callbacks always run on one dedicated thread named `app-switch-callback`,
+// one at a time and in delivery order, never on the monitor's run loop
+// thread. The JSON string is only valid for the duration of the call; copy it
+// to keep it. The callback must not block for long, since later events queue
+// behind it, and must not call `set_on_app_switch` itself: that returns -1.
+// Once `set_on_app_switch` returns, the previous callback is no longer
+// running and will not be called again, so its user data may be freed.
+
+/// Receives each app switch as a NUL-terminated JSON string
+pub type AppSwitchCallback = extern "C" fn(event_json: *const c_char, user_data: *mut c_void);
+
+struct NativeCallback {
+    callback: AppSwitchCallback,
+    /// Opaque to us; stored as an address so the registration is `Send`
+    user_data: usize,
+}
+
+struct NativeDispatcher {
+    sender: mpsc::Sender<String>,
+    registration: Arc<Mutex<Option<NativeCallback>>>,
+    thread: thread::ThreadId,
+}
+
+static NATIVE_DISPATCHER: OnceLock<NativeDispatcher> = OnceLock::new();
+
+fn native_dispatcher() -> &'static NativeDispatcher {
+    NATIVE_DISPATCHER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<String>();
+        let registration: Arc<Mutex<Option<NativeCallback>>> = Arc::new(Mutex::new(None));
+        let registered = Arc::clone(&registration);
+        let handle = thread::Builder::new()
+            .name("app-switch-callback".to_string())
+            .spawn(move || {
+                for json in receiver {
+                    let Ok(json) = CString::new(json) else {
+                        continue;
+                    };
+                    // Held during the call so a new registration waits for it
+                    let registered = registered.lock().unwrap();
+                    if let Some(native) = &*registered {
+                        (native.callback)(json.as_ptr(), native.user_data as *mut c_void);
+                    }
+                }
+            })
+            .expect("Failed to spawn the app switch callback thread");
+        NativeDispatcher {
+            sender,
+            registration,
+            thread: handle.thread().id(),
+        }
+    })
+}
+
+/// Register the callback native hosts receive app switches through
+///
+/// `user_data` is passed back unchanged on every call. A null `callback`
+/// removes the registration. Returns 0, or -1 when called from inside the
+/// callback, where replacing it is not allowed.
+#[frb(ignore)]
+#[no_mangle]
+pub extern "C" fn set_on_app_switch(
+    callback: Option<AppSwitchCallback>,
+    user_data: *mut c_void,
+) -> c_int {
+    let dispatcher = native_dispatcher();
+    if thread::current().id() == dispatcher.thread {
+        return -1;
+    }
+    *dispatcher.registration.lock().unwrap() = callback.map(|callback| NativeCallback {
+        callback,
+        user_data: user_data as usize,
+    });
+    0
+}
+
+/// The JSON passed to native callbacks
+fn native_event_json(event: &DartAppSwitchEventData) -> String {
+    let app = |app: &DartAppInfo| {
+        serde_json::json!({
+            "name": app.name,
+            "bundle_id": app.bundle_id,
+            "pid": app.pid,
+            "path": app.path,
+        })
+    };
+    serde_json::json!({
+        "event_type": event.event_type,
+        "app": app(&event.app_info),
+        "previous_app": event.previous_app.as_ref().map(app),
+        "window_title": event.window_title,
+        "url": event.url,
+    })
+    .to_string()
+}
+
+/// Queue an event for the native callback; a no-op until one has been set
+fn notify_native(event: &DartAppSwitchEventData) {
+    if let Some(dispatcher) = NATIVE_DISPATCHER.get() {
+        let _ = dispatcher.sender.send(native_event_json(event));
+    }
+}
+
+/// Deliver an event to the Dart stream and to the native callback
+fn emit(sink: &StreamSink<DartAppSwitchEventData>, event: DartAppSwitchEventData) {
+    notify_native(&event);
+    let _ = sink.add(event);
+}
+
 /// Global monitor state - completely internal
 static MONITOR_STATE: OnceLock<Arc<Mutex<MonitorState>>> = OnceLock::new();
 
@@ -290,6 +395,9 @@ struct MonitorState {
     is_monitoring: bool,
     event_count: u64,
     monitor_thread: Option<thread::JoinHandle<()>>,
+    paused: bool,
+    /// Set when events come through an `AppSwitcher`
+    pause_control: Option<PauseControl>,
 }
 
 /// Initialize the monitor state
@@ -298,6 +406,8 @@ fn init_monitor_state() -> Arc<Mutex<MonitorState>> {
         is_monitoring: false,
         event_count: 0,
         monitor_thread: None,
+        paused: false,
+        pause_control: None,
     }))
 }
 
@@ -439,7 +549,7 @@ fn setup_workspace_monitoring_on_background_thread(sink: StreamSink<DartAppSwitc
         );
         
         // Send the event through the stream
-        let _ = sink_clone.add(dart_event);
+        emit(&sink_clone, dart_event);
     });
     
     // Now try to start monitoring without MainThreadMarker
@@ -530,7 +640,7 @@ fn setup_real_notification_system(sink: StreamSink<DartAppSwitchEventData>) {
                     url: None,
                 };
                 
-                let _ = sink_activate.add(dart_event);
+                emit(&sink_activate, dart_event);
             } else {
                 println!("⚠️  Failed to get current app info");
             }
@@ -561,7 +671,7 @@ fn setup_real_notification_system(sink: StreamSink<DartAppSwitchEventData>) {
                     url: None,
                 };
                 
-                let _ = sink_deactivate.add(dart_event);
+                emit(&sink_deactivate, dart_event);
             }
         });
         
@@ -593,7 +703,7 @@ fn setup_real_notification_system(sink: StreamSink<DartAppSwitchEventData>) {
                     url: None,
                 };
                 
-                let _ = sink_activate2.add(dart_event);
+                emit(&sink_activate2, dart_event);
             }
         });
         
@@ -620,7 +730,7 @@ fn setup_real_notification_system(sink: StreamSink<DartAppSwitchEventData>) {
                     url: None,
                 };
                 
-                let _ = sink_deactivate2.add(dart_event);
+                emit(&sink_deactivate2, dart_event);
             }
         });
         
@@ -711,6 +821,13 @@ fn setup_real_notification_system(sink: StreamSink<DartAppSwitchEventData>) {
                 // Poll every 100ms for responsive detection
                 thread::sleep(Duration::from_millis(100));
                 
+                if is_paused() {
+                    // Forget the last app so the first poll after resuming reports it
+                    last_frontmost_app = None;
+                    last_frontmost_bundle = None;
+                    continue;
+                }
+                
                 // Check current frontmost app
                 if let Ok(current_app) = get_current_frontmost_app() {
                     // Check if this is a different app than before
@@ -742,7 +859,7 @@ fn setup_real_notification_system(sink: StreamSink<DartAppSwitchEventData>) {
                             url: None,
                         };
                         
-                        let _ = polling_sink.add(dart_event);
+                        emit(&polling_sink, dart_event);
                         last_frontmost_app = Some(current_app.name);
                         last_frontmost_bundle = Some(current_app.bundle_id);
                     }
@@ -809,7 +926,7 @@ fn setup_hybrid_detection_system(sink: StreamSink<DartAppSwitchEventData>) {
                     
                     let previous_app = last_frontmost_app.as_ref().map(|name| DartAppInfo {
                         name: name.clone(),
-                        bundle_id: "unknown".to_string(),
+                        bundle_id: String::new(),
                         pid: 0,
                         path: None,
                     });
@@ -822,7 +939,7 @@ fn setup_hybrid_detection_system(sink: StreamSink<DartAppSwitchEventData>) {
                         url: None,
                     };
                     
-                    let _ = sink.add(dart_event);
+                    emit(&sink, dart_event);
                     last_frontmost_app = Some(current_app.name);
                     last_check_time = std::time::Instant::now();
                     
@@ -863,7 +980,7 @@ fn setup_basic_app_detection(sink: StreamSink<DartAppSwitchEventData>) -> Result
                     
                     let previous_app = last_frontmost_app.as_ref().map(|name| DartAppInfo {
                         name: name.clone(),
-                        bundle_id: "unknown".to_string(),
+                        bundle_id: String::new(),
                         pid: 0,
                         path: None,
                     });
@@ -876,7 +993,7 @@ fn setup_basic_app_detection(sink: StreamSink<DartAppSwitchEventData>) -> Result
                         url: None,
                     };
                     
-                    let _ = sink_clone.add(dart_event);
+                    emit(&sink_clone, dart_event);
                     last_frontmost_app = Some(current_app.name);
                 }
             }
@@ -1123,14 +1240,14 @@ fn get_comprehensive_clipboard_data_internal(silent: bool) -> Result<DartClipboa
                 if format_id.contains("text") || format_id.contains("html") || format_id.contains("rtf") {
                     if let Some(string_data) = pasteboard.stringForType(&nsformat) {
                         let full_text = string_data.to_string();
-                        content_preview = safe_truncate(&full_text, 200);
+                        content_preview = truncate_preview(&full_text, 200);
                         
                         if primary_content.is_empty() {
                             primary_content = full_text;
                         }
                         
                         if !silent {
-                            println!("      📝 Content: \"{}\"", safe_truncate(&content_preview, 50));
+                            println!("      📝 Content: \"{}\"", truncate_preview(&content_preview, 50));
                         }
                     }
                 } else if format_id.contains("url") {
@@ -1287,21 +1404,13 @@ fn test_clipboard_monitoring() -> Result<()> {
                            i + 1, 
                            format.format_type,
                            format.data_size,
-                           if format.content_preview.len() > 60 {
-                               safe_truncate(&format.content_preview, 60)
-                           } else {
-                               format.content_preview.clone()
-                           });
+                           truncate_preview(&format.content_preview, 60));
                 }
                 
                 println!("📝 Primary content: {} characters", clipboard_data.primary_content.len());
                 
                 if !clipboard_data.primary_content.is_empty() {
-                    let preview = if clipboard_data.primary_content.len() > 100 {
-                        format!("{}...", &clipboard_data.primary_content[..100])
-                    } else {
-                        clipboard_data.primary_content.clone()
-                    };
+                    let preview = truncate_preview(&clipboard_data.primary_content, 100);
                     println!("📖 Content preview: \"{}\"", preview);
                 }
             },
@@ -1335,7 +1444,7 @@ fn get_current_frontmost_app() -> Result<DartAppInfo> {
                 
             let bundle_id = app.bundleIdentifier()
                 .map(|b| b.to_string())
-                .unwrap_or_else(|| "unknown".to_string());
+                .unwrap_or_default();
                 
             let pid = app.processIdentifier();
             
@@ -1374,7 +1483,7 @@ fn create_and_run_app_switcher(sink: StreamSink<DartAppSwitchEventData>, mtm: Ma
             dart_event.event_type
         );
         
-        let _ = sink_clone.add(dart_event);
+        emit(&sink_clone, dart_event);
     });
     app_switcher.add_listener(listener);
     
@@ -1388,6 +1497,7 @@ fn create_and_run_app_switcher(sink: StreamSink<DartAppSwitchEventData>, mtm: Ma
                 let state = get_monitor_state();
                 let mut state_guard = state.lock().unwrap();
                 state_guard.is_monitoring = true;
+                state_guard.pause_control = Some(app_switcher.pause_control());
             }
             
             println!("🔄 Starting CFRunLoop...");
@@ -1466,6 +1576,8 @@ pub fn stop_monitoring() -> Result<()> {
         }
 
         state_guard.is_monitoring = false;
+        state_guard.paused = false;
+        state_guard.pause_control = None;
         println!("🛑 AppSwitcher monitoring stopped");
     }
 
@@ -1478,6 +1590,49 @@ pub fn is_monitoring() -> bool {
     state.lock().unwrap().is_monitoring
 }
 
+/// Pause app switch events without stopping the monitor
+///
+/// For a pause toggle in the UI: listener state such as time tracking is
+/// kept and the paused interval counts as idle. With `stop_enhanced`,
+/// enhanced context extraction is also switched off until resumed.
+pub fn pause_monitoring(stop_enhanced: bool) -> Result<()> {
+    let control = {
+        let mut state_guard = get_monitor_state().lock().unwrap();
+        if !state_guard.is_monitoring {
+            return Err(anyhow::anyhow!("Not monitoring"));
+        }
+        state_guard.paused = true;
+        state_guard.pause_control.clone()
+    };
+    if let Some(control) = control {
+        control.pause(stop_enhanced);
+    }
+    println!("⏸️  AppSwitcher monitoring paused");
+    Ok(())
+}
+
+/// Resume app switch events; the current app is reported as a new switch
+pub fn resume_monitoring() -> Result<()> {
+    let control = {
+        let mut state_guard = get_monitor_state().lock().unwrap();
+        if !state_guard.paused {
+            return Ok(()); // Not paused
+        }
+        state_guard.paused = false;
+        state_guard.pause_control.clone()
+    };
+    if let Some(control) = control {
+        control.resume();
+    }
+    println!("▶️  AppSwitcher monitoring resumed");
+    Ok(())
+}
+
+/// Check if monitoring is paused
+pub fn is_paused() -> bool {
+    get_monitor_state().lock().unwrap().paused
+}
+
 /// Check accessibility permissions
 pub fn check_accessibility_permissions() -> bool {
     use accessibility_sys::AXIsProcessTrusted;
@@ -1500,7 +1655,7 @@ pub fn test_comprehensive_clipboard_monitoring() -> Result<()> {
             for (i, format) in clipboard_data.formats.iter().enumerate() {
                 println!("  [{}] {}: {} bytes", i + 1, format.format_type, format.data_size);
                 if format.data_size > 0 && format.content_preview.len() > 0 {
-                    let preview = safe_truncate(&format.content_preview, 80);
+                    let preview = truncate_preview(&format.content_preview, 80);
                     println!("      Preview: {}", preview);
                 }
             }
@@ -1561,7 +1716,7 @@ pub fn get_current_app_info() -> Option<DartAppInfo> {
             // For full functionality with enhanced context, use the streaming API
             return Some(DartAppInfo {
                 name: "Current App".to_string(),
-                bundle_id: "unknown".to_string(),
+                bundle_id: String::new(),
                 pid: 0,
                 path: None,
             });
@@ -1570,3 +1725,64 @@ pub fn get_current_app_info() -> Option<DartAppInfo> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    type Received = Mutex<mpsc::Sender<(c_int, String)>>;
+
+    extern "C" fn record(event_json: *const c_char, user_data: *mut c_void) {
+        // SAFETY: `user_data` is the leaked sender registered in the test and
+        // the string is valid for the duration of the call
+        let received = unsafe { &*(user_data as *const Received) };
+        let json = unsafe { CStr::from_ptr(event_json) }
+            .to_string_lossy()
+            .into_owned();
+        let reentrant = set_on_app_switch(None, std::ptr::null_mut());
+        let _ = received.lock().unwrap().send((reentrant, json));
+    }
+
+    #[test]
+    fn test_native_callback_fires_on_injected_events() {
+        let (sender, receiver) = mpsc::channel();
+        let received: &'static Received = Box::leak(Box::new(Mutex::new(sender)));
+        let user_data = received as *const Received as *mut c_void;
+        assert_eq!(set_on_app_switch(Some(record), user_data), 0);
+
+        let event = DartAppSwitchEventData {
+            app_info: DartAppInfo {
+                name: "Safari".to_string(),
+                bundle_id: "com.apple.Safari".to_string(),
+                pid: 42,
+                path: None,
+            },
+            previous_app: Some(DartAppInfo {
+                name: "Xcode".to_string(),
+                bundle_id: "com.apple.dt.Xcode".to_string(),
+                pid: 7,
+                path: Some("/Applications/Xcode.app".to_string()),
+            }),
+            event_type: "foreground".to_string(),
+            window_title: Some("docs.rs".to_string()),
+            url: Some("https://docs.rs".to_string()),
+        };
+        notify_native(&event);
+
+        let (reentrant, json) = receiver.recv_timeout(Duration::from_secs(2)).unwrap();
+        // Replacing the callback from inside it is refused
+        assert_eq!(reentrant, -1);
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["event_type"], "foreground");
+        assert_eq!(json["app"]["bundle_id"], "com.apple.Safari");
+        assert_eq!(json["app"]["pid"], 42);
+        assert_eq!(json["previous_app"]["name"], "Xcode");
+        assert_eq!(json["url"], "https://docs.rs");
+
+        // Once removed, the callback is not called again
+        assert_eq!(set_on_app_switch(None, std::ptr::null_mut()), 0);
+        notify_native(&event);
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+}
@@ -0,0 +1,411 @@
+// src/config.rs
+//! File-based tracker configuration
+//!
+//! Command line flags cover the common switches, but anything that is a
+//! mapping or a list (app tags, rules, schedules) lives in a TOML file passed
+//! via `--config`. Every section is optional and falls back to its defaults,
+//! so an empty file is a valid configuration.
+//!
+//! Because of those defaults a misspelled key would be silently ignored. The
+//! `validate-config` subcommand reports unknown and deprecated keys, type
+//! errors and values out of range before the tracker runs with them; loading
+//! the file at startup runs the same checks, logs the warnings and fails on
+//! the errors.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::core::ax_attributes::AttributeAllowlist;
+use crate::core::clipboard::ClipboardConfig;
+use crate::core::extraction_rules::ExtractionRulesConfig;
+use crate::core::path_redaction::PathRedactionConfig;
+use crate::core::quiet_hours::{QuietHoursConfig, QuietHoursGate};
+use crate::core::self_exclusion::ExclusionConfig;
+use crate::core::title_exclusion::{TitleExclusion, TitleExclusionConfig};
+use crate::core::url_canonicalizer::UrlCanonicalizerConfig;
+use crate::extractors::streak_reminder::ReminderConfig;
+use crate::extractors::tag_aggregator::TagConfig;
+
+/// Keys that are still read but will go away, with what to use instead
+const DEPRECATED_KEYS: &[(&str, &str)] = &[];
+
+/// Largest clipboard thumbnail edge, in pixels
+const MAX_THUMBNAIL_SIZE: u32 = 1024;
+
+/// Root of the TOML configuration file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackerConfig {
+    /// Work/personal tagging of applications
+    pub tags: TagConfig,
+
+    /// Focus streak and distraction reminders
+    pub reminders: ReminderConfig,
+
+    /// URL canonicalization rules
+    pub urls: UrlCanonicalizerConfig,
+
+    /// Processes whose switches are never recorded
+    pub exclusion: ExclusionConfig,
+
+    /// Clipboard change capture
+    pub clipboard: ClipboardConfig,
+
+    /// Accessibility attributes that may be read
+    pub attributes: AttributeAllowlist,
+
+    /// Times when only apps and timing are recorded
+    pub quiet_hours: QuietHoursConfig,
+
+    /// Per-app attribute reads defined by the user
+    pub extraction: ExtractionRulesConfig,
+
+    /// Truncating and hashing of captured file paths
+    pub paths: PathRedactionConfig,
+
+    /// Windows whose content is never recorded, by title
+    pub title_exclusion: TitleExclusionConfig,
+}
+
+impl TrackerConfig {
+    /// Parse a configuration from TOML source
+    pub fn from_toml_str(source: &str) -> Result<Self, String> {
+        toml::from_str(source).map_err(|e| format!("Invalid configuration: {}", e))
+    }
+
+    /// Load a configuration file from disk, logging its warnings; a file
+    /// with errors is refused
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let validation = Self::validate_file(path)?;
+        let mut errors = Vec::new();
+        for diagnostic in &validation.diagnostics {
+            match diagnostic.severity {
+                Severity::Warning => tracing::warn!("{}: {}", path.display(), diagnostic),
+                Severity::Error => errors.push(match &diagnostic.key {
+                    Some(key) => format!("{}: {}", key, diagnostic.message),
+                    None => diagnostic.message.clone(),
+                }),
+            }
+        }
+        match validation.config {
+            Some(config) if errors.is_empty() => Ok(config),
+            _ => Err(format!(
+                "Invalid config {}: {}",
+                path.display(),
+                errors.join("; ")
+            )),
+        }
+    }
+
+    /// Check TOML source without failing on the first problem
+    pub fn validate_toml_str(source: &str) -> ConfigValidation {
+        let mut diagnostics = Vec::new();
+        let syntax_ok = match toml::from_str::<toml::Table>(source) {
+            Ok(table) => {
+                let given = serde_json::to_value(&table).unwrap_or_default();
+                let known = serde_json::to_value(TrackerConfig::default()).unwrap_or_default();
+                if let (Value::Object(given), Value::Object(known)) = (&given, &known) {
+                    check_keys(given, known, "", &mut diagnostics);
+                }
+                true
+            }
+            Err(e) => {
+                diagnostics.push(ConfigDiagnostic::error(None, parse_error(source, &e)));
+                false
+            }
+        };
+        let config = match toml::from_str::<TrackerConfig>(source) {
+            Ok(config) => {
+                diagnostics.extend(config.check_values());
+                Some(config)
+            }
+            Err(e) => {
+                // A syntax error was already reported; this is a type error
+                if syntax_ok {
+                    diagnostics.push(ConfigDiagnostic::error(None, parse_error(source, &e)));
+                }
+                None
+            }
+        };
+        ConfigValidation {
+            config,
+            diagnostics,
+        }
+    }
+
+    /// Check a configuration file on disk
+    pub fn validate_file(path: &Path) -> Result<ConfigValidation, String> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config {}: {}", path.display(), e))?;
+        Ok(Self::validate_toml_str(&source))
+    }
+
+    /// Values that parse but that the tracker would reject or misuse
+    pub fn check_values(&self) -> Vec<ConfigDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut error = |key: &str, message: String| {
+            diagnostics.push(ConfigDiagnostic::error(Some(key), message));
+        };
+        if self.reminders.streak_minutes == 0 {
+            error(
+                "reminders.streak_minutes",
+                "must be at least 1; a zero-minute streak would fire on every switch".to_string(),
+            );
+        }
+        if self.reminders.thrash_window_secs == 0 {
+            error(
+                "reminders.thrash_window_secs",
+                "must be at least 1; no switches fit in an empty window".to_string(),
+            );
+        }
+        if !(1..=MAX_THUMBNAIL_SIZE).contains(&self.clipboard.thumbnail_size) {
+            error(
+                "clipboard.thumbnail_size",
+                format!(
+                    "must be between 1 and {} pixels, got {}",
+                    MAX_THUMBNAIL_SIZE, self.clipboard.thumbnail_size
+                ),
+            );
+        }
+        if self.paths.keep_components == Some(0) {
+            error(
+                "paths.keep_components",
+                "must be at least 1; leave it out to keep paths whole".to_string(),
+            );
+        }
+        if let Err(e) = QuietHoursGate::from_config(&self.quiet_hours) {
+            error("quiet_hours.windows", e);
+        }
+        if let Err(e) = TitleExclusion::from_config(&self.title_exclusion) {
+            error("title_exclusion.patterns", e);
+        }
+        if let Err(e) = self.extraction.validate() {
+            error("extraction.apps", e);
+        }
+        if !self.clipboard.allows_image_types() {
+            if self.clipboard.capture_thumbnails {
+                diagnostics.push(ConfigDiagnostic::warning(
+                    "clipboard.capture_thumbnails",
+                    "no image type is in clipboard.types, so no thumbnails are made; \
+                     add public.png or public.tiff"
+                        .to_string(),
+                ));
+            }
+            if self.clipboard.perceptual_hash {
+                diagnostics.push(ConfigDiagnostic::warning(
+                    "clipboard.perceptual_hash",
+                    "no image type is in clipboard.types, so no images are hashed; \
+                     add public.png or public.tiff"
+                        .to_string(),
+                ));
+            }
+        }
+        diagnostics
+    }
+}
+
+/// How serious a configuration problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The tracker runs, but not as the file suggests
+    Warning,
+    /// The tracker refuses to start with this configuration
+    Error,
+}
+
+/// One problem found in a configuration file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigDiagnostic {
+    pub severity: Severity,
+    /// Dotted key such as `clipboard.thumbnail_size`, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl ConfigDiagnostic {
+    fn warning(key: &str, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            key: Some(key.to_string()),
+            message,
+        }
+    }
+
+    fn error(key: Option<&str>, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            key: key.map(str::to_string),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        match &self.key {
+            Some(key) => write!(f, "{}: {}: {}", severity, key, self.message),
+            None => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+/// The outcome of `TrackerConfig::validate_toml_str`
+#[derive(Debug, Clone)]
+pub struct ConfigValidation {
+    /// The effective configuration, defaults applied; `None` when the file
+    /// does not parse
+    pub config: Option<TrackerConfig>,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl ConfigValidation {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+}
+
+/// A parse or type error with its line, without the source excerpt
+fn parse_error(source: &str, error: &toml::de::Error) -> String {
+    match error.span() {
+        Some(span) => {
+            let line = source[..span.start.min(source.len())].matches('\n').count() + 1;
+            format!("line {}: {}", line, error.message().trim())
+        }
+        None => error.message().trim().to_string(),
+    }
+}
+
+/// Report keys of `given` that the defaults in `known` do not have
+///
+/// Sections whose default is an empty table, such as `tags.apps`, are maps
+/// with user-chosen keys and are not checked; neither are array entries.
+fn check_keys(
+    given: &Map<String, Value>,
+    known: &Map<String, Value>,
+    prefix: &str,
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    for (key, value) in given {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        if let Some((_, instead)) = DEPRECATED_KEYS.iter().find(|(old, _)| *old == path) {
+            diagnostics.push(ConfigDiagnostic::warning(
+                &path,
+                format!("deprecated; use {} instead", instead),
+            ));
+            continue;
+        }
+        match (known.get(key), value) {
+            (None, _) => {
+                let expected: Vec<&str> = known.keys().map(String::as_str).collect();
+                diagnostics.push(ConfigDiagnostic::warning(
+                    &path,
+                    format!(
+                        "unknown key, ignored (expected one of: {})",
+                        expected.join(", ")
+                    ),
+                ));
+            }
+            (Some(Value::Object(known)), Value::Object(given)) if !known.is_empty() => {
+                check_keys(given, known, &path, diagnostics);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_reports_unknown_keys_and_bad_values() {
+        let validation = TrackerConfig::validate_toml_str(
+            r#"
+[clipboard]
+capture_thumbnails = true
+perceptual_hash = true
+thumbnail_sise = 64
+thumbnail_size = 4096
+
+[tags.apps]
+"com.apple.Safari" = "work"
+
+[colours]
+dark = true
+"#,
+        );
+        assert!(validation.has_errors());
+        let found: Vec<(Severity, Option<&str>)> = validation
+            .diagnostics
+            .iter()
+            .map(|d| (d.severity, d.key.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Severity::Warning, Some("clipboard.thumbnail_sise")),
+                (Severity::Warning, Some("colours")),
+                (Severity::Error, Some("clipboard.thumbnail_size")),
+                (Severity::Warning, Some("clipboard.capture_thumbnails")),
+                (Severity::Warning, Some("clipboard.perceptual_hash")),
+            ]
+        );
+        let unknown = &validation.diagnostics[0].message;
+        assert!(unknown.starts_with("unknown key, ignored (expected one of: "));
+        assert!(unknown.contains("thumbnail_size"));
+        assert_eq!(
+            validation.diagnostics[2].to_string(),
+            "error: clipboard.thumbnail_size: must be between 1 and 1024 pixels, got 4096"
+        );
+        // The effective configuration still has defaults for what was left out
+        let config = validation.config.unwrap();
+        assert!(config.clipboard.capture_thumbnails);
+        assert_eq!(config.tags.default_tag, "neutral");
+
+        // A type error is reported with its line and leaves no configuration
+        let validation =
+            TrackerConfig::validate_toml_str("[reminders]\nstreak_minutes = \"long\"\n");
+        assert!(validation.config.is_none());
+        assert_eq!(validation.diagnostics.len(), 1);
+        let message = &validation.diagnostics[0].message;
+        assert!(message.starts_with("line 2: "), "{}", message);
+
+        let empty = TrackerConfig::validate_toml_str("");
+        assert!(empty.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_load_refuses_values_out_of_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tracker.toml");
+
+        std::fs::write(&path, "[reminders]\nstreak_minutes = 0\n").unwrap();
+        let error = TrackerConfig::load(&path).unwrap_err();
+        assert!(
+            error.contains(": reminders.streak_minutes: must be at least 1;"),
+            "{}",
+            error
+        );
+
+        // Warnings alone do not stop the tracker
+        std::fs::write(&path, "[clipboard]\nthumbnail_sise = 64\n").unwrap();
+        assert!(TrackerConfig::load(&path).is_ok());
+
+        assert!(TrackerConfig::load(&dir.path().join("missing.toml")).is_err());
+    }
+}
@@ -11,30 +11,37 @@
 //! run reliably for extended periods.
 
 use std::collections::HashMap;
+use std::time::Instant;
 
-// Import our FFI type definitions
-// Prefer concrete CF types from core-foundation/core-foundation-sys for AX calls
-use core_foundation_sys::base::CFTypeRef as CFTypeRefSys;
-use core_foundation_sys::string::CFStringRef as CFStringRefSys;
+use schemars::JsonSchema;
+use serde::Serialize;
 
-use accessibility_sys::{
-    kAXDescriptionAttribute, kAXDocumentAttribute, kAXErrorSuccess, kAXFocusedUIElementAttribute,
-    kAXFocusedWindowAttribute, kAXRoleAttribute, kAXTitleAttribute, kAXURLAttribute,
-    kAXValueAttribute, AXIsProcessTrusted, AXUIElementCopyAttributeValue,
-    AXUIElementCreateApplication, AXUIElementRef as AXUIElement,
-};
-
-// Remove objc2_application_services imports as it doesn't exist
-// Use accessibility-sys for these
-use core_foundation::base::CFTypeRef as CFTypeRefCF;
-use core_foundation::base::{CFRelease, TCFType};
-use core_foundation::string::CFString as CFStringCore;
-use core_foundation::string::CFStringRef as CFStringRefCF;
-use core_foundation_sys::base::CFGetTypeID;
-use core_foundation_sys::string::CFStringGetTypeID;
-use objc2_core_foundation::{CFString, CGPoint, CGRect, CGSize};
+use accessibility_sys::AXIsProcessTrusted;
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 
 use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent, AppSwitchListener};
+use crate::core::ax_attributes::{AttributeAllowlist, AttributeReader, FilteredReader};
+use crate::core::ax_availability::{is_denial, AxAvailability};
+use crate::core::ax_element::AxElement;
+use crate::core::browser_strategy::{
+    browser_profile_from_title, AppleScriptCache, BrowserPage, BrowserStrategy,
+};
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+use crate::core::extraction_rules::{apply_rules, ExtractionRulesConfig};
+use crate::core::mail::MailContextExtractor;
+use crate::core::note_apps::NoteAppExtractor;
+use crate::core::path_redaction::{
+    PathRedactor, FINDER_SELECTION_PREFIX, FINDER_SELECTION_SEPARATOR,
+};
+use crate::core::quiet_hours::QuietHoursGate;
+use crate::core::recent_documents::{resolve_document_path, PathConfidence, RecentDocuments};
+use crate::core::reference_manager::{CurrentReference, ReferenceManagerExtractor};
+use crate::core::spreadsheet::SpreadsheetExtractor;
+use crate::core::supported_apps::{SupportedApps, CUSTOM_CATEGORY};
+use crate::core::terminal::TerminalCommandExtractor;
+use crate::core::title_exclusion::TitleExclusion;
+use crate::core::xcode::XcodeExtractor;
+use crate::core::process_cache::{ProcessCache, ProcessIdentity};
 
 /// Enhanced context information extracted using accessibility APIs
 ///
@@ -53,12 +60,34 @@ pub struct AccessibilityContext {
     /// Window-level context - what document or content is open
     pub window_title: Option<String>,
     pub document_path: Option<String>,
+    /// `Low` when `document_path` was guessed from the app's recent documents
+    pub document_path_confidence: Option<PathConfidence>,
     pub is_document_modified: Option<bool>,
 
     /// Browser-specific context - the web content they're viewing
     pub current_url: Option<String>,
     pub page_title: Option<String>,
     pub tab_count: Option<usize>,
+    /// Chromium profile named in the window title; `None` for the default profile
+    pub browser_profile: Option<String>,
+
+    /// Spreadsheet context - the sheet and cells they're working in
+    pub sheet_name: Option<String>,
+    /// Selected cell or range, qualified by sheet when known (`Sheet1!A1:B3`)
+    pub selected_range: Option<String>,
+
+    /// Mail context - the mailbox and thread they're reading
+    pub mail_folder: Option<String>,
+    /// Thread subject without reply prefixes; only with content capture
+    pub conversation: Option<String>,
+
+    /// Reference manager context - the paper selected and the collection browsed
+    pub current_reference: Option<CurrentReference>,
+    pub reference_collection: Option<String>,
+
+    /// Terminal context - the foreground command; only the program name
+    /// without content capture
+    pub terminal_command: Option<String>,
 
     /// IDE/Editor context - the code they're working on
     pub active_file_path: Option<String>,
@@ -73,6 +102,12 @@ pub struct AccessibilityContext {
 
     /// Raw accessibility attributes for debugging and future extension
     pub raw_attributes: HashMap<String, String>,
+
+    /// All windows of the app, front to back; only with window capture enabled
+    pub windows: Vec<WindowInfo>,
+
+    /// Values read by the user's `[extraction]` rules, keyed by field name
+    pub custom_fields: HashMap<String, String>,
 }
 
 /// Detailed information about the currently focused UI element
@@ -142,11 +177,37 @@ pub struct AccessibilityContextExtractor {
     trusted: bool,
 
     /// Cache of extracted contexts to avoid redundant API calls
-    /// Using modern HashMap with proper key types
-    context_cache: HashMap<i32, AccessibilityContext>,
+    /// Keyed by (pid, launch_date) so a reused pid never serves stale context
+    context_cache: ProcessCache<AccessibilityContext>,
 
     /// Applications we know how to extract enhanced context from
-    supported_bundles: Vec<String>,
+    supported_bundles: SupportedApps,
+
+    /// Read up to this many windows per app; 0 disables window capture
+    max_windows: usize,
+
+    /// Attributes that may be read from the focused element
+    attribute_allowlist: AttributeAllowlist,
+
+    /// User-defined field reads from the config file
+    extraction_rules: ExtractionRulesConfig,
+
+    /// Apps that refuse accessibility are skipped until a re-probe is due
+    ax_availability: AxAvailability,
+
+    /// Truncates or hashes document and file paths before they are reported
+    path_redactor: Option<PathRedactor>,
+
+    /// Nothing is read or printed during quiet hours
+    quiet_hours: Option<QuietHoursGate>,
+    /// Nothing is printed for windows whose title is excluded
+    title_exclusion: Option<TitleExclusion>,
+    /// Xcode's AppleScript reads, cached per window title
+    xcode: XcodeExtractor,
+    /// Browser tab title and count, cached per window title
+    applescript_cache: AppleScriptCache,
+    /// Each app's recent documents, the fallback for a missing AXDocument
+    recent_documents: RecentDocuments,
 }
 
 impl AccessibilityContextExtractor {
@@ -165,453 +226,74 @@ impl AccessibilityContextExtractor {
                 .to_string());
         }
 
-        // Define applications we have specialized extraction logic for
-        // This comprehensive list represents the complete ecosystem of applications used in
-        // modern research, development, creative, and productivity workflows
-        let supported_bundles = vec![
-            // === Web Browsers - Primary Research and Documentation Tools ===
-            // Chromium-based browsers
-            "com.google.Chrome".to_string(),
-            "com.google.Chrome.beta".to_string(),
-            "com.google.Chrome.dev".to_string(),
-            "com.google.Chrome.canary".to_string(),
-            "com.microsoft.edgemac".to_string(),
-            "com.microsoft.edgemac.Beta".to_string(),
-            "com.microsoft.edgemac.Dev".to_string(),
-            "com.microsoft.edgemac.Canary".to_string(),
-            "com.brave.Browser".to_string(),
-            "com.operasoftware.Opera".to_string(),
-            "com.operasoftware.OperaGX".to_string(),
-            "com.vivaldi.Vivaldi".to_string(),
-            "company.thebrowser.Browser".to_string(), // Arc browser
-            "com.SigmaOS.SigmaOS".to_string(),
-            "com.orionbrowser.Orion".to_string(),
-            "com.sidekick.browser".to_string(),
-            // WebKit-based browsers
-            "com.apple.Safari".to_string(),
-            "com.apple.SafariTechnologyPreview".to_string(),
-            "com.epichrome.core".to_string(),
-            // Gecko-based browsers
-            "org.mozilla.firefox".to_string(),
-            "org.mozilla.firefoxdeveloperedition".to_string(),
-            "org.mozilla.nightly".to_string(),
-            "org.torproject.torbrowser".to_string(),
-            // Specialized browsers
-            "com.electron.min".to_string(),  // Min browser
-            "com.choosy.choosy".to_string(), // Browser dispatcher
-            "com.browserosaurus.browserosaurus".to_string(),
-            // === Development Tools and IDEs ===
-            // JetBrains IDEs - Complete IntelliJ ecosystem
-            "com.jetbrains.intellij".to_string(),
-            "com.jetbrains.intellij.ce".to_string(),
-            "com.jetbrains.pycharm".to_string(),
-            "com.jetbrains.pycharm.ce".to_string(),
-            "com.jetbrains.webstorm".to_string(),
-            "com.jetbrains.PhpStorm".to_string(),
-            "com.jetbrains.rubymine".to_string(),
-            "com.jetbrains.CLion".to_string(),
-            "com.jetbrains.AppCode".to_string(),
-            "com.jetbrains.datagrip".to_string(),
-            "com.jetbrains.rider".to_string(),
-            "com.jetbrains.goland".to_string(),
-            "com.jetbrains.resharper".to_string(),
-            "com.jetbrains.dataspell".to_string(),
-            "com.jetbrains.gateway".to_string(),
-            "com.jetbrains.space.desktop".to_string(),
-            // Microsoft/Electron-based editors
-            "com.microsoft.VSCode".to_string(),
-            "com.microsoft.VSCodeInsiders".to_string(),
-            "com.todesktop.230313mzl4w4u92".to_string(), // Cursor
-            "com.github.GitHubDesktop".to_string(),
-            "com.github.atom".to_string(),
-            "com.microsoft.teams".to_string(),
-            "com.microsoft.teams2".to_string(),
-            // Apple development tools
-            "com.apple.dt.Xcode".to_string(),
-            "com.apple.dt.Instruments".to_string(),
-            "com.apple.CoreSimulator.SimulatorTrampoline".to_string(),
-            "com.apple.dt.MobileDeviceUpdater".to_string(),
-            "com.apple.accessibility.AccessibilityInspector".to_string(),
-            // Text editors and code tools
-            "com.sublimetext.4".to_string(),
-            "com.sublimetext.3".to_string(),
-            "com.macromates.TextMate".to_string(),
-            "com.coteditor.CotEditor".to_string(),
-            "com.barebones.bbedit".to_string(),
-            "com.barebones.textwrangler".to_string(),
-            "com.panic.Nova".to_string(),
-            "com.codeux.apps.textual".to_string(),
-            "com.foldingtext.FoldingText".to_string(),
-            "com.uranusjr.macdown".to_string(),
-            "com.typora.typora".to_string(),
-            "net.codeshot.Mark-Text".to_string(),
-            "io.github.marktext".to_string(),
-            "abnerworks.Typora".to_string(),
-            "com.zettlr.Zettlr".to_string(),
-            // Vim and Emacs
-            "org.vim.MacVim".to_string(),
-            "org.gnu.Emacs".to_string(),
-            "org.gnu.AquamacsEmacs".to_string(),
-            "com.onflapp.NeXTSPace".to_string(),
-            // === Terminal and Command Line Tools ===
-            "com.apple.Terminal".to_string(),
-            "com.googlecode.iterm2".to_string(),
-            "com.github.wez.wezterm".to_string(),
-            "net.kovidgoyal.kitty".to_string(),
-            "io.alacritty".to_string(),
-            "com.ragnarlonn.hyper".to_string(),
-            "com.electron.hyper".to_string(),
-            "com.contextswitcher.SSH".to_string(),
-            "com.panic.Terminal".to_string(),
-            "com.blackhole-media.Termius".to_string(),
-            "com.noodlesoft.SecurePipes".to_string(),
-            "com.royalapplications.royaltsx".to_string(),
-            "com.microsoft.rdc.macos".to_string(),
-            "com.trendmicro.SafeSync".to_string(),
-            // === Note-Taking and Knowledge Management ===
-            // Apple Notes ecosystem
-            "com.apple.Notes".to_string(),
-            "com.apple.notesmigratorservice".to_string(),
-            // Obsidian and PKM tools
-            "md.obsidian".to_string(),
-            "com.logseq.Logseq".to_string(),
-            "net.cozic.joplin-desktop".to_string(),
-            "com.dendronhq.dendron".to_string(),
-            "app.zettelkasten.Zettelkasten".to_string(),
-            "com.literatureandlatte.scrivener3".to_string(),
-            "com.literatureandlatte.scapple".to_string(),
-            "com.devontechnologies.thinkfree.DEVONthink3".to_string(),
-            "com.devontechnologies.thinkfree.DEVONagent3".to_string(),
-            "com.eastgate.Tinderbox".to_string(),
-            // Notion and productivity suites
-            "notion.id".to_string(),
-            "com.notion.NotionMac".to_string(),
-            "com.roamresearch.desktop".to_string(),
-            "com.remnote.RemNote".to_string(),
-            "com.amplenote.desktop".to_string(),
-            "com.craftdocs.mac".to_string(),
-            "com.bear-writer.BearMac".to_string(),
-            "com.dayoneapp.dayone".to_string(),
-            "com.ulyssesapp.mac".to_string(),
-            "com.ia.writer".to_string(),
-            "com.bywordapp.Byword".to_string(),
-            // Research and academic tools
-            "com.zotero.zotero".to_string(),
-            "com.mendeley.Desktop".to_string(),
-            "com.readcube.Papers".to_string(),
-            "com.devontechnologies.thinkfree.DEVONthink3".to_string(),
-            "com.qsrinternational.NVivo".to_string(),
-            "com.atlasti.atlasti".to_string(),
-            // === Communication and Collaboration ===
-            // Slack ecosystem
-            "com.tinyspeck.slackmacgap".to_string(),
-            "com.slack.slack-macos".to_string(),
-            // Discord
-            "com.hnc.Discord".to_string(),
-            "com.discordapp.Discord".to_string(),
-            "com.discordapp.DiscordCanary".to_string(),
-            "com.discordapp.DiscordPTB".to_string(),
-            // Microsoft Teams and Office
-            "com.microsoft.teams".to_string(),
-            "com.microsoft.teams2".to_string(),
-            "com.microsoft.Outlook".to_string(),
-            "com.microsoft.Word".to_string(),
-            "com.microsoft.Excel".to_string(),
-            "com.microsoft.Powerpoint".to_string(),
-            "com.microsoft.onenote.mac".to_string(),
-            "com.microsoft.OneDrive".to_string(),
-            "com.microsoft.OneDrive-mac".to_string(),
-            // Video conferencing
-            "us.zoom.xos".to_string(),
-            "com.cisco.webexmeetingsapp".to_string(),
-            "com.google.Chrome.app.kjgfgldnnfoeklkmfkjfagphfepbbdan".to_string(), // Google Meet
-            "com.skype.skype".to_string(),
-            "com.apple.FaceTime".to_string(),
-            "com.gotomeeting.GoToMeeting".to_string(),
-            "com.bluejeans.Blue".to_string(),
-            "com.ringcentral.meetings".to_string(),
-            "com.8x8.meet".to_string(),
-            // Chat and messaging
-            "com.apple.MobileSMS".to_string(), // Messages
-            "com.apple.iChatAgent".to_string(),
-            "org.whispersystems.signal-desktop".to_string(),
-            "com.telegram.desktop".to_string(),
-            "ru.keepcoder.Telegram".to_string(),
-            "com.facebook.archon.developerID".to_string(), // Messenger
-            "com.viber.osx".to_string(),
-            "com.linecorp.line".to_string(),
-            "com.tencent.qq".to_string(),
-            "com.tencent.wechat".to_string(),
-            "com.wechat.mac".to_string(),
-            // === Document Viewers and Editors ===
-            // PDF and document viewers
-            "com.apple.Preview".to_string(),
-            "com.adobe.Reader".to_string(),
-            "com.adobe.Acrobat.Pro".to_string(),
-            "com.readdle.PDFExpert-Mac".to_string(),
-            "com.pdfpen.pdfpenpro".to_string(),
-            "com.smileonmymac.PDFpenPro".to_string(),
-            "com.skim-app.skim".to_string(),
-            "com.formulate.Highlights".to_string(),
-            "com.goodiis.GoodNotes-5".to_string(),
-            "com.agiletortoise.Notebooks-8".to_string(),
-            // Office suites
-            "org.libreoffice.script".to_string(),
-            "org.openoffice.script".to_string(),
-            "com.apple.iWork.Pages".to_string(),
-            "com.apple.iWork.Numbers".to_string(),
-            "com.apple.iWork.Keynote".to_string(),
-            "com.google.Chrome.app.aohghmighlieiainnegkcijnfilokake".to_string(), // Google Docs
-            "com.nektony.App-Cleaner-Pro".to_string(),
-            // === File Management and System Tools ===
-            // File managers
-            "com.apple.finder".to_string(),
-            "com.panic.Transmit".to_string(),
-            "com.globaldelight.CommandPost".to_string(),
-            "com.binarynights.ForkLift-3".to_string(),
-            "com.cocoatech.PathFinder".to_string(),
-            "com.trankynam.FileHound".to_string(),
-            "com.apple.ArchiveUtility".to_string(),
-            "com.app.CommandPost".to_string(),
-            "com.1blocker.1BlockerMac".to_string(),
-            // Cloud storage
-            "com.dropbox.Dropbox".to_string(),
-            "com.google.GoogleDrive".to_string(),
-            "com.box.desktop".to_string(),
-            "com.amazon.clouddrive.mac".to_string(),
-            "com.getdropbox.dropbox".to_string(),
-            "com.microsoft.OneDrive".to_string(),
-            "com.apple.CloudDocs.MobileDocumentsFileProviderManaged".to_string(),
-            // === Database and Data Tools ===
-            "com.sequelpro.SequelPro".to_string(),
-            "com.tinyapp.TablePlus".to_string(),
-            "com.valentina-db.valentina-studio".to_string(),
-            "com.navicat.NavicatPremium".to_string(),
-            "com.dbvis.DbVisualizer".to_string(),
-            "com.jetbrains.datagrip".to_string(),
-            "com.mongodb.compass".to_string(),
-            "com.robomongo.Robo-3T".to_string(),
-            "com.redis.RedisInsight-V2".to_string(),
-            "com.clickhouse.tabix".to_string(),
-            // === Design and Creative Tools ===
-            // Adobe Creative Suite
-            "com.adobe.Photoshop".to_string(),
-            "com.adobe.Illustrator".to_string(),
-            "com.adobe.InDesign".to_string(),
-            "com.adobe.AfterEffects".to_string(),
-            "com.adobe.PremierePro".to_string(),
-            "com.adobe.Lightroom".to_string(),
-            "com.adobe.LightroomCC".to_string(),
-            "com.adobe.CreativeCloud".to_string(),
-            "com.adobe.XD".to_string(),
-            "com.adobe.dreamweaver".to_string(),
-            // Design tools
-            "com.bohemiancoding.sketch3".to_string(),
-            "com.figma.Desktop".to_string(),
-            "com.framerx.desktop".to_string(),
-            "com.invisionapp.studio".to_string(),
-            "com.zeplin.osx".to_string(),
-            "com.marvel.desktop".to_string(),
-            "com.principle.Principle".to_string(),
-            "com.flinto.flinto-mac".to_string(),
-            // === Media and Entertainment ===
-            // Video players
-            "com.colliderli.iina".to_string(),
-            "org.videolan.vlc".to_string(),
-            "com.movist.MovistPro".to_string(),
-            "com.apple.QuickTimePlayerX".to_string(),
-            "com.apple.DVD Player".to_string(),
-            "com.plex.plexmediaserver".to_string(),
-            "tv.plex.desktop".to_string(),
-            // Audio tools
-            "com.apple.Music".to_string(),
-            "com.spotify.client".to_string(),
-            "com.apple.iTunes".to_string(),
-            "com.soulmen.ulysses3".to_string(),
-            "com.rogueamoeba.AudioHijackPro".to_string(),
-            "com.rogueamoeba.SoundSource".to_string(),
-            // === Developer and System Utilities ===
-            // API and development tools
-            "com.postmanlabs.mac".to_string(),
-            "com.luckymarmot.Paw".to_string(),
-            "com.rapid-api.RapidAPIForMac".to_string(),
-            "com.useproxyapp.Proxyman".to_string(),
-            "com.charlesproxy.charles".to_string(),
-            "com.github.insomnia".to_string(),
-            "com.httpie.desktop".to_string(),
-            // Docker and containers
-            "com.docker.docker".to_string(),
-            "com.getcleaner.Disk-Utility".to_string(),
-            "com.parallels.desktop.console".to_string(),
-            "com.vmware.fusion".to_string(),
-            "org.virtualbox.app.VirtualBox".to_string(),
-            "com.utmapp.UTM".to_string(),
-            // Version control
-            "com.github.GitHubDesktop".to_string(),
-            "com.atlassian.SourceTreeMac".to_string(),
-            "com.git-tower.Tower".to_string(),
-            "com.github.fork".to_string(),
-            "com.gitup.GitUp".to_string(),
-            "com.github.GitXiv".to_string(),
-            // === AI and Machine Learning Tools ===
-            // Jupyter and data science
-            "org.jupyter.JupyterLab-Desktop".to_string(),
-            "com.anaconda.Navigator".to_string(),
-            "com.rstudio.desktop".to_string(),
-            "com.mathworks.matlab".to_string(),
-            "com.wolfram.Mathematica".to_string(),
-            "org.octave.Octave-GUI".to_string(),
-            // AI assistants and tools
-            "com.openai.chat".to_string(),
-            "com.anthropic.claude".to_string(),
-            "com.github.copilot".to_string(),
-            "com.raycast.macos".to_string(),
-            "com.alfredapp.Alfred".to_string(),
-            "com.runningwithcrayons.Alfred".to_string(),
-            // === System and Utility Applications ===
-            // System monitoring
-            "com.apple.ActivityMonitor".to_string(),
-            "com.bjango.istatmenus".to_string(),
-            "com.glyph.MenuMeterPro".to_string(),
-            "com.bresink.system-toolkit.TechTool-Pro".to_string(),
-            "com.app.MenuMeterPro".to_string(),
-            "com.apple.Console".to_string(),
-            "com.apple.SystemPreferences".to_string(),
-            "com.apple.systempreferences".to_string(),
-            // Productivity utilities
-            "com.copilot.desktop".to_string(),
-            "com.1blocker.1BlockerMac".to_string(),
-            "com.culturedcode.ThingsMac".to_string(),
-            "com.omnigroup.OmniFocus3".to_string(),
-            "com.todoist.mac.Todoist".to_string(),
-            "com.any.do.mac".to_string(),
-            "com.ticktick.task.mac".to_string(),
-            "com.flexibits.fantastical2.mac".to_string(),
-            "com.apple.iCal".to_string(),
-            "com.apple.AddressBook".to_string(),
-            // Security and privacy
-            "com.1password.1password7".to_string(),
-            "com.agilebits.onepassword7".to_string(),
-            "com.lastpass.LastPass".to_string(),
-            "com.bitwarden.desktop".to_string(),
-            "com.dashlane.dashlanephonefinal".to_string(),
-            "com.keepassx.keepassxc".to_string(),
-            "net.tunnelbear.mac".to_string(),
-            "com.nordvpn.macos".to_string(),
-            "com.expressvpn.ExpressVPN".to_string(),
-            // === Specialized Research and Academic Tools ===
-            // Citation and reference management
-            "com.zotero.zotero".to_string(),
-            "com.mendeley.Desktop".to_string(),
-            "com.readcube.Papers".to_string(),
-            "com.citeulike.Desktop".to_string(),
-            "com.refworks.refworks".to_string(),
-            // Statistical analysis
-            "com.ibm.SPSS.Statistics".to_string(),
-            "com.sas.jmp".to_string(),
-            "org.R-project.R".to_string(),
-            "com.stata.stata18".to_string(),
-            "com.minitab.Minitab".to_string(),
-            "com.graphpad.prism".to_string(),
-            // Specialized browsers and tools
-            "com.webcatalog.juli".to_string(),
-            "com.choosy.choosy".to_string(),
-            "com.browserosaurus.browserosaurus".to_string(),
-            "com.electron.fiddle".to_string(),
-            "com.github.wez.wezterm-gui".to_string(),
-            // === Content Creation and Publishing ===
-            // Blogging and publishing
-            "com.wordpress.desktop".to_string(),
-            "com.ghost.desktop".to_string(),
-            "com.medium.desktop".to_string(),
-            "com.substack.SubstackDesktop".to_string(),
-            "com.notion.NotionMac".to_string(),
-            // Social media management
-            "com.hootsuite.desktop".to_string(),
-            "com.buffer.desktop".to_string(),
-            "com.tweetdeck.TweetDeck".to_string(),
-            "com.twitter.twitter-mac".to_string(),
-            "com.facebook.FacebookDesktop".to_string(),
-            "com.linkedin.LinkedIn".to_string(),
-            // === Miscellaneous Professional Tools ===
-            // Email clients
-            "com.apple.mail".to_string(),
-            "com.microsoft.Outlook".to_string(),
-            "com.google.Gmail".to_string(),
-            "com.mailmate.MailMate".to_string(),
-            "com.postbox.Postbox".to_string(),
-            "com.thunderbird.Thunderbird".to_string(),
-            "com.sparkmailapp.Spark".to_string(),
-            // Calendar and scheduling
-            "com.apple.iCal".to_string(),
-            "com.microsoft.Outlook".to_string(),
-            "com.google.Calendar".to_string(),
-            "com.flexibits.fantastical2.mac".to_string(),
-            "com.busymac.busycal3".to_string(),
-            // Project management
-            "com.atlassian.Jira".to_string(),
-            "com.asana.desktop".to_string(),
-            "com.trello.desktop".to_string(),
-            "com.monday.desktop".to_string(),
-            "com.clickup.desktop".to_string(),
-            "com.basecamp.basecamp3".to_string(),
-            "com.microsoft.Project".to_string(),
-            // Remote desktop and SSH
-            "com.microsoft.rdc.macos".to_string(),
-            "com.teamviewer.TeamViewer".to_string(),
-            "com.apple.RemoteDesktop".to_string(),
-            "com.panic.Prompt".to_string(),
-            "com.nektony.SSH-Files".to_string(),
-            "com.termius.mac".to_string(),
-            // === Emerging and Specialized Applications ===
-            // Blockchain and crypto
-            "com.coinbase.wallet".to_string(),
-            "io.metamask.MetaMask".to_string(),
-            "com.exodus.desktop".to_string(),
-            "com.electrum.electrum".to_string(),
-            // 3D and CAD
-            "com.autodesk.AutoCAD".to_string(),
-            "com.sketchup.SketchUp".to_string(),
-            "org.blender.blender".to_string(),
-            "com.autodesk.Fusion360".to_string(),
-            "com.solidworks.SolidWorks".to_string(),
-            // Scientific computing
-            "com.wolfram.Mathematica".to_string(),
-            "com.mathworks.matlab".to_string(),
-            "org.gnu.octave".to_string(),
-            "com.maplesoft.Maple".to_string(),
-            "com.originlab.OriginPro".to_string(),
-            // Game development
-            "com.unity3d.UnityEditor5.x".to_string(),
-            "com.epicgames.UnrealEngine".to_string(),
-            "com.godotengine.Godot".to_string(),
-            "com.gamemaker.GameMaker".to_string(),
-            // === Legacy and Alternative Applications ===
-            // Legacy browsers and tools
-            "org.mozilla.camino".to_string(),
-            "com.omnigroup.OmniWeb5".to_string(),
-            "com.flock.Flock".to_string(),
-            "com.roccat.Roccat".to_string(),
-            // Alternative text editors
-            "com.github.atom-editor".to_string(),
-            "com.adobe.Brackets".to_string(),
-            "com.lighttable.LightTable".to_string(),
-            "com.kodgemisi.VimR".to_string(),
-            // Specialized IDEs
-            "com.embarcadero.DelphiCE".to_string(),
-            "com.borland.CBuilder".to_string(),
-            "com.eclipse.Eclipse".to_string(),
-            "org.netbeans.ide.NetBeans".to_string(),
-            "com.jetbrains.toolbox".to_string(),
-        ];
-
         Ok(Self {
             trusted,
-            context_cache: HashMap::new(),
-            supported_bundles,
+            context_cache: ProcessCache::new(),
+            supported_bundles: SupportedApps::builtin(),
+            max_windows: 0,
+            attribute_allowlist: AttributeAllowlist::default(),
+            extraction_rules: ExtractionRulesConfig::default(),
+            ax_availability: AxAvailability::default(),
+            path_redactor: None,
+            quiet_hours: None,
+            title_exclusion: None,
+            xcode: XcodeExtractor::default(),
+            applescript_cache: AppleScriptCache::default(),
+            recent_documents: RecentDocuments::default(),
         })
     }
 
+    /// Populate `AccessibilityContext::windows` with up to `max_windows` windows
+    pub fn with_window_capture(mut self, max_windows: usize) -> Self {
+        self.max_windows = max_windows;
+        self
+    }
+
+    /// Restrict which focused-element attributes are read
+    pub fn with_attribute_allowlist(mut self, allowlist: AttributeAllowlist) -> Self {
+        self.attribute_allowlist = allowlist;
+        self
+    }
+
+    /// Apply user-defined extraction rules; their apps become supported
+    pub fn with_extraction_rules(mut self, rules: ExtractionRulesConfig) -> Self {
+        for bundle_id in rules.bundle_ids() {
+            self.supported_bundles.insert(bundle_id, CUSTOM_CATEGORY);
+        }
+        self.extraction_rules = rules;
+        self
+    }
+
+    /// Share per-app availability, e.g. `AppSwitcher::ax_availability`, so
+    /// denials seen here flag the app's events
+    pub fn with_ax_availability(mut self, availability: AxAvailability) -> Self {
+        self.ax_availability = availability;
+        self
+    }
+
+    /// Redact document, file and Finder paths per the `[paths]` config
+    pub fn with_path_redactor(mut self, redactor: PathRedactor) -> Self {
+        self.path_redactor = Some(redactor);
+        self
+    }
+
+    /// Skip extraction while the gate is quiet
+    pub fn with_quiet_hours(mut self, gate: QuietHoursGate) -> Self {
+        self.quiet_hours = Some(gate);
+        self
+    }
+
+    /// Report nothing for windows whose title is excluded
+    pub fn with_title_exclusion(mut self, exclusion: TitleExclusion) -> Self {
+        self.title_exclusion = Some(exclusion);
+        self
+    }
+
+    /// Drop cached contexts so the next extraction queries the app again
+    pub fn clear_cache(&mut self) {
+        self.context_cache.clear();
+    }
+
     /// Extract rich context from an application using modern objc2 0.6.x patterns
     ///
     /// This method showcases the key improvements in objc2 0.6.x:
@@ -623,6 +305,16 @@ impl AccessibilityContextExtractor {
     /// We start with basic window information that works for all apps, then layer on
     /// application-specific intelligence for apps we understand deeply.
     pub fn extract_context(&mut self, app_info: &AppInfo) -> Result<AccessibilityContext, String> {
+        self.extract_context_with_windows(app_info, None)
+    }
+
+    /// Like `extract_context`, reusing a window list already read for this
+    /// switch (see `WindowListCapture`) instead of reading AXWindows again
+    fn extract_context_with_windows(
+        &mut self,
+        app_info: &AppInfo,
+        known_windows: Option<&[WindowInfo]>,
+    ) -> Result<AccessibilityContext, String> {
         if !self.trusted {
             return Err(
                 "Accessibility not trusted - call check_accessibility_permissions()".to_string(),
@@ -631,7 +323,7 @@ impl AccessibilityContextExtractor {
 
         // Check cache first to avoid redundant API calls
         // This optimization is important for responsive research assistance
-        if let Some(cached) = self.context_cache.get(&app_info.pid) {
+        if let Some(cached) = self.context_cache.get(&ProcessIdentity::of(app_info)) {
             return Ok(cached.clone());
         }
 
@@ -639,12 +331,19 @@ impl AccessibilityContextExtractor {
         // This is crucial for long-running monitoring applications
         let result = objc2::rc::autoreleasepool(|_pool| {
             // Create the accessibility element for this application
-            // In objc2 0.6.x, we still need unsafe for C API calls, but the rest is safe
-            let ax_app = unsafe { AXUIElementCreateApplication(app_info.pid) };
-            if ax_app.is_null() {
+            // Released when the closure returns, including on early errors
+            let ax_app = &AxElement::application(app_info.pid).ok_or_else(|| {
+                format!("Failed to create AXUIElement for PID {}", app_info.pid)
+            })?;
+
+            // Apps refusing accessibility fail every read; stop after one
+            let status = ax_app.probe();
+            self.ax_availability
+                .record_status(app_info.match_key(), status, Instant::now());
+            if is_denial(status) {
                 return Err(format!(
-                    "Failed to create AXUIElement for PID {}",
-                    app_info.pid
+                    "{} refuses accessibility (AX error {})",
+                    app_info.bundle_id, status
                 ));
             }
 
@@ -653,41 +352,101 @@ impl AccessibilityContextExtractor {
                 app_info: app_info.clone(),
                 window_title: None,
                 document_path: None,
+                document_path_confidence: None,
                 is_document_modified: None,
                 current_url: None,
                 page_title: None,
                 tab_count: None,
+                browser_profile: None,
+                sheet_name: None,
+                selected_range: None,
+                mail_folder: None,
+                conversation: None,
+                current_reference: None,
+                reference_collection: None,
+                terminal_command: None,
                 active_file_path: None,
                 project_name: None,
                 selected_text: None,
                 focused_element: None,
                 ui_path: Vec::new(),
                 raw_attributes: HashMap::new(),
+                windows: Vec::new(),
+                custom_fields: HashMap::new(),
             };
 
             // Layer on context using the progressive enhancement pattern
             // Each method builds upon the previous, creating increasingly detailed context
 
+            // Each step runs in a named span so `bench-extract` can time it
+
             // 1. Extract basic window information (works for all applications)
-            self.extract_window_context(ax_app, &mut context)?;
+            tracing::debug_span!("window").in_scope(|| {
+                match known_windows {
+                    Some(windows) => context.windows = windows.to_vec(),
+                    None if self.max_windows > 0 => {
+                        context.windows = read_ax_windows(ax_app, self.max_windows)
+                    }
+                    None => {}
+                }
+                self.extract_window_context(ax_app, &mut context)
+            })?;
 
             // 2. Extract application-specific context based on bundle ID
             if self.is_browser(&app_info.bundle_id) {
-                self.extract_browser_context(ax_app, &mut context)?;
-            } else if self.is_ide(&app_info.bundle_id) {
-                self.extract_ide_context(ax_app, &mut context)?;
-            } else if app_info.bundle_id == "com.apple.finder" {
-                self.extract_finder_context(ax_app, &mut context)?;
-            } else if self.is_document_app(&app_info.bundle_id) {
-                self.extract_document_context(ax_app, &mut context)?;
+                tracing::debug_span!("browser")
+                    .in_scope(|| self.extract_browser_context(ax_app, &mut context))?;
+            } else {
+                let _span = tracing::debug_span!("app_context").entered();
+                if XcodeExtractor::applies_to(&app_info.bundle_id) {
+                    self.xcode.apply(&mut context);
+                } else if self.is_ide(&app_info.bundle_id) {
+                    self.extract_ide_context(ax_app, &mut context)?;
+                } else if app_info.bundle_id == "com.apple.finder" {
+                    self.extract_finder_context(ax_app, &mut context)?;
+                } else if NoteAppExtractor::applies_to(&app_info.bundle_id) {
+                    NoteAppExtractor.apply(&mut context);
+                } else if SpreadsheetExtractor::applies_to(&app_info.bundle_id) {
+                    SpreadsheetExtractor.apply(ax_app, &mut context, &|attribute| {
+                        self.attribute_allowlist.allows(attribute)
+                    });
+                } else if ReferenceManagerExtractor::applies_to(&app_info.bundle_id) {
+                    ReferenceManagerExtractor.apply(ax_app, &mut context, &|attribute| {
+                        self.attribute_allowlist.allows(attribute)
+                    });
+                } else if MailContextExtractor::applies_to(&app_info.bundle_id) {
+                    MailContextExtractor
+                        .apply(&mut context, self.attribute_allowlist.capture_content);
+                } else if TerminalCommandExtractor::applies_to(&app_info.bundle_id) {
+                    TerminalCommandExtractor
+                        .apply(&mut context, self.attribute_allowlist.capture_content);
+                } else if self.is_document_app(&app_info.bundle_id) {
+                    self.extract_document_context(ax_app, &mut context)?;
+                }
             }
 
             // 3. Extract focused element information (universal across all apps)
-            self.extract_focused_element(ax_app, &mut context)?;
+            tracing::debug_span!("focused_element")
+                .in_scope(|| self.extract_focused_element(ax_app, &mut context))?;
+
+            // 4. User-defined rules, subject to the same attribute allowlist
+            context.custom_fields = tracing::debug_span!("custom_rules").in_scope(|| {
+                apply_rules(
+                    &self.extraction_rules,
+                    &app_info.bundle_id,
+                    ax_app,
+                    |attribute| self.attribute_allowlist.allows(attribute),
+                )
+            });
+
+            if let Some(redactor) = &self.path_redactor {
+                redactor.redact_context(&mut context);
+            }
 
             // Cache the result for performance
             // Research assistants need to be responsive, so caching is essential
-            self.context_cache.insert(app_info.pid, context.clone());
+            self.context_cache
+                .insert(ProcessIdentity::of(app_info), context.clone());
 
             Ok(context)
         }); // End of autoreleasepool
@@ -702,28 +461,34 @@ impl AccessibilityContextExtractor {
     /// worrying about when to call CFRelease. This reduces cognitive load and
     /// eliminates a major source of bugs.
     fn extract_window_context(
-        &self,
-        ax_app: AXUIElement,
+        &mut self,
+        ax_app: &AxElement,
         context: &mut AccessibilityContext,
     ) -> Result<(), String> {
         // Get the focused window using the modern pattern
-        if let Some(window) = self.get_ax_element_attribute_by_name(ax_app, "AXFocusedWindow") {
+        if let Some(window) = ax_app.element("AXFocusedWindow") {
             // Extract window title - this is universal across applications
-            context.window_title = self.get_string_attribute_custom(window, "AXTitle");
+            context.window_title = self.get_string_attribute_custom(&window, "AXTitle");
 
             // Extract document path if available - useful for file-based applications
-            context.document_path = self.get_string_attribute_custom(window, "AXDocument");
+            // Apps without AXDocument fall back to a guess from their recent documents
+            let document = resolve_document_path(
+                self.get_string_attribute_custom(&window, "AXDocument"),
+                context.window_title.as_deref(),
+                || self.recent_documents.paths(&context.app_info.bundle_id),
+            );
+            context.document_path_confidence = document.as_ref().map(|d| d.confidence);
+            context.document_path = document.map(|d| d.path);
 
             // Check if document is modified - indicates unsaved work
-            // Skip for now due to type issues
-            context.is_document_modified = None;
+            context.is_document_modified = document_edited(
+                self.get_boolean_attribute(&window, "AXIsDocumentEdited"),
+                context.window_title.as_deref(),
+            );
 
             // Store raw attributes for debugging and future enhancement
             // This gives us visibility into what attributes are available
-            self.extract_all_attributes(window, &mut context.raw_attributes);
-
-            // Release retained window element to prevent leaks
-            unsafe { CFRelease(window as CFTypeRefCF) };
+            self.extract_all_attributes(&window, &mut context.raw_attributes);
         }
 
         Ok(())
@@ -736,34 +501,49 @@ impl AccessibilityContextExtractor {
     /// strategies, ensuring we can get URL information even when the browser's
     /// accessibility implementation varies.
     fn extract_browser_context(
-        &self,
-        ax_app: AXUIElement,
+        &mut self,
+        ax_app: &AxElement,
         context: &mut AccessibilityContext,
     ) -> Result<(), String> {
-        // Strategy 1: Try to find the address bar directly
-        // Modern browsers make the address bar accessible through standard patterns
-        // Skip complex address bar search for now
-        context.current_url = None;
-
-        // Strategy 2: Look for web areas with URLs
-        // Web content areas often contain URL information
+        // Strategy 1: One walk of the focused window for the web area's URL,
+        // or an address field holding one
+        context.current_url = ax_app
+            .element("AXFocusedWindow")
+            .and_then(|window| page_url_in(&window));
+
+        // Tab title and count come from the browser's AppleScript dictionary,
+        // reused while the window title is unchanged; the URL is read there
+        // too and used when accessibility found none
+        let page = match BrowserStrategy::for_bundle_id(&context.app_info.bundle_id) {
+            Some(strategy) => self.applescript_cache.get_or_fetch(
+                &context.app_info.bundle_id,
+                context.window_title.as_deref(),
+                Instant::now(),
+                // The accessibility tree was searched above
+                || BrowserPage {
+                    url: strategy.applescript_url(),
+                    title: strategy.title(),
+                    tab_count: strategy.tab_count(),
+                },
+            ),
+            None => BrowserPage::default(),
+        };
         if context.current_url.is_none() {
-            context.current_url = self.find_web_area_url(ax_app);
+            context.current_url = page.url;
         }
 
-        // Strategy 3: Use AppleScript as a reliable fallback
-        // When accessibility APIs fail, AppleScript provides a consistent interface
-        if context.current_url.is_none() {
-            context.current_url = self.get_browser_url_via_applescript(&context.app_info.bundle_id);
-        }
+        // Page title helps understand what the user is reading or researching
+        context.page_title = page.title;
 
-        // Extract page title from web content
-        // This helps understand what the user is reading or researching
-        context.page_title = self.extract_page_title(ax_app);
+        // Chrome and Edge name a non-default profile in the window title,
+        // which separates work and personal browsing
+        context.browser_profile = context
+            .window_title
+            .as_deref()
+            .and_then(|title| browser_profile_from_title(title, context.page_title.as_deref()));
 
-        // Count tabs if possible
         // Tab count indicates research breadth and multitasking patterns
-        context.tab_count = self.count_browser_tabs(ax_app);
+        context.tab_count = page.tab_count;
 
         Ok(())
     }
@@ -776,23 +556,35 @@ impl AccessibilityContextExtractor {
     /// research assistant focused on technical work.
     fn extract_ide_context(
         &self,
-        ax_app: AXUIElement,
+        _ax_app: &AxElement,
         context: &mut AccessibilityContext,
     ) -> Result<(), String> {
         // Many IDEs encode file information in the window title
-        // Pattern: "filename — project" or "filename - project"
+        // Pattern: "● filename — folder — Workspace (Workspace)" (VS Code family)
+        // or the simpler "filename — project" / "filename - project"
         if let Some(title) = &context.window_title {
-            // Try multiple separator patterns used by different IDEs
-            for separator in &[" — ", " - ", " • "] {
-                let parts: Vec<&str> = title.split(separator).collect();
-                if parts.len() >= 2 {
-                    context.active_file_path = Some(parts[0].to_string());
-                    context.project_name = Some(parts[1].to_string());
-                    break;
+            if let Some(parsed) = parse_ide_title(title) {
+                context.active_file_path = Some(parsed.file);
+                context.project_name = parsed.project;
+
+                // VS Code and Cursor always show the dirty dot, so its absence
+                // means the file is saved; other IDEs are left unknown
+                if parsed.is_dirty {
+                    context.is_document_modified = Some(true);
+                } else if self.is_vscode_family(&context.app_info.bundle_id) {
+                    context.is_document_modified = Some(false);
                 }
             }
         }
 
+        // A file named by the title beats a guess from recent documents
+        if context.active_file_path.is_some()
+            && context.document_path_confidence == Some(PathConfidence::Low)
+        {
+            context.document_path = None;
+            context.document_path_confidence = None;
+        }
+
         // Try to get full file path from document attribute
         // This provides the absolute path, which is more useful than just the filename
         if let Some(doc_path) = &context.document_path {
@@ -822,7 +614,7 @@ impl AccessibilityContextExtractor {
     /// file management, code exploration, or data analysis.
     fn extract_finder_context(
         &self,
-        _ax_app: AXUIElement,
+        _ax_app: &AxElement,
         context: &mut AccessibilityContext,
     ) -> Result<(), String> {
         // Finder stores current location in document attribute
@@ -841,7 +633,11 @@ impl AccessibilityContextExtractor {
         // This tells us what the user is focused on within the directory
         if let Some(selected_items) = self.extract_finder_selection(_ax_app) {
             if !selected_items.is_empty() {
-                context.selected_text = Some(format!("Selected: {}", selected_items.join(", ")));
+                context.selected_text = Some(format!(
+                    "{}{}",
+                    FINDER_SELECTION_PREFIX,
+                    selected_items.join(FINDER_SELECTION_SEPARATOR)
+                ));
             }
         }
 
@@ -854,7 +650,7 @@ impl AccessibilityContextExtractor {
     /// valuable research context about what materials the user is reading.
     fn extract_document_context(
         &self,
-        _ax_app: AXUIElement,
+        _ax_app: &AxElement,
         context: &mut AccessibilityContext,
     ) -> Result<(), String> {
         // For document apps, the window title often contains the document name
@@ -862,8 +658,10 @@ impl AccessibilityContextExtractor {
 
         // Extract selected text if available
         // This can indicate what specific content the user is focusing on
-        if let Some(selected) = self.extract_selected_text(_ax_app) {
-            context.selected_text = Some(selected);
+        if self.attribute_allowlist.allows("AXSelectedText") {
+            if let Some(selected) = self.extract_selected_text(_ax_app) {
+                context.selected_text = Some(selected);
+            }
         }
 
         Ok(())
@@ -877,48 +675,22 @@ impl AccessibilityContextExtractor {
     /// different types of activities within the same application.
     fn extract_focused_element(
         &self,
-        ax_app: AXUIElement,
+        ax_app: &AxElement,
         context: &mut AccessibilityContext,
     ) -> Result<(), String> {
-        if let Some(focused) = self.get_ax_element_attribute_by_name(ax_app, "AXFocusedUIElement") {
-            let element_info = UIElementInfo {
-                role: self.get_string_attribute_custom(focused, "AXRole"),
-                title: self.get_string_attribute_custom(focused, "AXTitle"),
-                value: self.get_string_attribute_custom(focused, "AXValue"),
-                description: self.get_string_attribute_custom(focused, "AXDescription"),
-                url: self.get_string_attribute_custom(focused, "AXURL"),
-                identifier: self.get_string_attribute_custom(focused, "AXIdentifier"),
-                placeholder: self.get_string_attribute_custom(focused, "AXPlaceholderValue"),
-                selected_text: self.get_string_attribute_custom(focused, "AXSelectedText"),
-                position: self.get_point_attribute(focused, "AXPosition"),
-                size: self.get_size_attribute(focused, "AXSize"),
-                frame: self.get_frame_attribute(focused, "AXFrame"),
-                parent: self.get_string_attribute_custom(focused, "AXParent"),
-                children_count: self.get_integer_attribute(focused, "AXChildrenCount"),
-                tab_index: self.get_integer_attribute_i32(focused, "AXTabIndex"),
-                enabled: self.get_boolean_attribute(focused, "AXEnabled"),
-                focused: self.get_boolean_attribute(focused, "AXFocused"),
-                selected: self.get_boolean_attribute(focused, "AXSelected"),
-                expanded: self.get_boolean_attribute(focused, "AXExpanded"),
-                checked: self.get_boolean_attribute(focused, "AXChecked"),
-                pressed: self.get_boolean_attribute(focused, "AXPressed"),
-                text_range: None, // Would need special handling for range tuple
-                insertion_point: self.get_integer_attribute(focused, "AXInsertionPoint"),
-                line_number: self.get_integer_attribute(focused, "AXLineNumber"),
-                column_number: self.get_integer_attribute(focused, "AXColumnNumber"),
-                tag_name: self.get_string_attribute_custom(focused, "AXTagName"),
-                class_name: self.get_string_attribute_custom(focused, "AXClassName"),
-                aria_label: self.get_string_attribute_custom(focused, "AXAriaLabel"),
-                window_title: self.get_string_attribute_custom(focused, "AXWindowTitle"),
-                application_role: self.get_string_attribute_custom(focused, "AXApplicationRole"),
-                help_text: self.get_string_attribute_custom(focused, "AXHelp"),
+        if let Some(focused) = ax_app.element("AXFocusedUIElement") {
+            let reader = ElementAttributes {
+                extractor: self,
+                element: &focused,
             };
+            let element_info =
+                read_focused_element(&FilteredReader::new(&reader, &self.attribute_allowlist));
 
             context.focused_element = Some(element_info);
 
             // Build UI path (hierarchy of parent elements)
             // This helps understand the context of the focused element
-            context.ui_path = self.build_ui_path(focused);
+            context.ui_path = self.build_ui_path(&focused);
         }
 
         Ok(())
@@ -954,243 +726,46 @@ impl AccessibilityContextExtractor {
     // Helper methods for accessibility API interactions
     // These methods encapsulate the patterns for safe interaction with the C APIs
 
-    /// Safely get an accessibility element attribute
-    ///
-    /// This method demonstrates the modern pattern for C API interaction in objc2 0.6.x.
-    /// We still need unsafe for the C call, but the surrounding code is much safer
-    /// thanks to better type checking and automatic memory management.
-    fn copy_attribute_value_raw(
-        &self,
-        element: AXUIElement,
-        attribute: CFStringRefSys,
-    ) -> Option<CFTypeRefSys> {
-        unsafe {
-            let mut value: CFTypeRefSys = std::ptr::null();
-            let status = AXUIElementCopyAttributeValue(element, attribute, &mut value);
-            if status == kAXErrorSuccess && !value.is_null() {
-                Some(value)
-            } else {
-                None
-            }
-        }
-    }
-
-    /// Get a string attribute from an accessibility element using modern patterns
-    ///
-    /// This method shows how objc2 0.6.x improves string handling. The automatic
-    /// conversion from CFString to Rust String eliminates manual encoding concerns
-    /// and memory management issues.
-    fn get_string_attribute(
-        &self,
-        element: AXUIElement,
-        attribute: CFStringRefSys,
-    ) -> Option<String> {
-        unsafe {
-            let value = self.copy_attribute_value_raw(element, attribute)?;
-            // Ensure type is CFString before wrapping
-            if CFGetTypeID(value as *const _) == CFStringGetTypeID() {
-                let cfstr = core_foundation::string::CFString::wrap_under_create_rule(
-                    value as CFStringRefCF,
-                );
-                let s = cfstr.to_string();
-                if s.is_empty() {
-                    None
-                } else {
-                    Some(s)
-                }
-            } else {
-                // Not a string, release and return None
-                CFRelease(value as CFTypeRefCF);
-                None
-            }
-        }
-    }
-
     /// Get a string attribute with custom attribute name
-    fn get_string_attribute_custom(&self, element: AXUIElement, attribute: &str) -> Option<String> {
-        let cf_attr = CFStringCore::new(attribute);
-        // Use concrete CFStringRef for AX API
-        let attr_ptr_cf: CFStringRefCF = cf_attr.as_concrete_TypeRef();
-        self.get_string_attribute(element, attr_ptr_cf as CFStringRefSys)
+    fn get_string_attribute_custom(&self, element: &AxElement, attribute: &str) -> Option<String> {
+        element.string(attribute).filter(|s| !s.is_empty())
     }
 
     /// Get a boolean attribute from an accessibility element
-    fn get_boolean_attribute(&self, _element: AXUIElement, _attribute: &str) -> Option<bool> {
-        None
+    fn get_boolean_attribute(&self, element: &AxElement, attribute: &str) -> Option<bool> {
+        element.boolean(attribute)
     }
 
     /// Get a point attribute (position) from an accessibility element
-    fn get_point_attribute(&self, _element: AXUIElement, _attribute: &str) -> Option<CGPoint> {
+    fn get_point_attribute(&self, _element: &AxElement, _attribute: &str) -> Option<CGPoint> {
         // Implementation would extract CGPoint from accessibility API
         None
     }
 
     /// Get a size attribute from an accessibility element  
-    fn get_size_attribute(&self, _element: AXUIElement, _attribute: &str) -> Option<CGSize> {
+    fn get_size_attribute(&self, _element: &AxElement, _attribute: &str) -> Option<CGSize> {
         // Implementation would extract CGSize from accessibility API
         None
     }
 
     /// Get a frame (rect) attribute from an accessibility element
-    fn get_frame_attribute(&self, _element: AXUIElement, _attribute: &str) -> Option<CGRect> {
+    fn get_frame_attribute(&self, _element: &AxElement, _attribute: &str) -> Option<CGRect> {
         // Implementation would extract CGRect from accessibility API
         None
     }
 
     /// Get an integer attribute from an accessibility element
-    fn get_integer_attribute(&self, _element: AXUIElement, _attribute: &str) -> Option<usize> {
+    fn get_integer_attribute(&self, _element: &AxElement, _attribute: &str) -> Option<usize> {
         // Implementation would extract integer values from accessibility API
         None
     }
 
-    /// Get an i32 integer attribute from an accessibility element
-    fn get_integer_attribute_i32(&self, _element: AXUIElement, _attribute: &str) -> Option<i32> {
-        // Implementation would extract integer values from accessibility API
-        None
-    }
-
-    /// Get an accessibility element attribute by name
-    fn get_ax_element_attribute_by_name(
-        &self,
-        element: AXUIElement,
-        attribute: &str,
-    ) -> Option<AXUIElement> {
-        let cf_attr = CFStringCore::new(attribute);
-        let attr_ptr_cf: CFStringRefCF = cf_attr.as_concrete_TypeRef();
-        self.copy_attribute_value_raw(element, attr_ptr_cf as CFStringRefSys)
-            .map(|v| v as AXUIElement)
-    }
-
     // Application-specific helper methods
     // These methods implement the specialized logic for different application types
 
-    /// Find an element by role and description patterns
-    /// This is a common pattern for finding specific UI elements like address bars
-    fn find_element_by_role_and_description(
-        &self,
-        _ax_app: AXUIElement,
-        _role: &str,
-        _description_contains: &str,
-    ) -> Option<AXUIElement> {
-        // Implementation would recursively search the UI tree
-        // Simplified for this example
-        None
-    }
-
-    /// Find URLs in web areas
-    fn find_web_area_url(&self, _ax_app: AXUIElement) -> Option<String> {
-        // Implementation would search for AXWebArea elements with URLs
-        None
-    }
-
-    /// Get browser URL via AppleScript as fallback
-    fn get_browser_url_via_applescript(&self, _bundle_id: &str) -> Option<String> {
-        use std::process::Command;
-        // Map bundle → AppleScript
-        let (app_name, script) = if _bundle_id.contains("com.google.Chrome") {
-            (
-                "Google Chrome",
-                r#"tell application "Google Chrome" to get URL of active tab of front window"#,
-            )
-        } else if _bundle_id.contains("com.apple.Safari") {
-            (
-                "Safari",
-                r#"tell application "Safari" to get URL of front document"#,
-            )
-        } else if _bundle_id.contains("com.apple.SafariTechnologyPreview") {
-            (
-                "Safari Technology Preview",
-                r#"tell application "Safari Technology Preview" to get URL of front document"#,
-            )
-        } else {
-            return None;
-        };
-
-        let output = Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output()
-            .ok()?;
-        if !output.status.success() {
-            return None;
-        }
-        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if url.is_empty() {
-            None
-        } else {
-            Some(url)
-        }
-    }
-
-    /// Extract page title from web content
-    fn extract_page_title(&self, _ax_app: AXUIElement) -> Option<String> {
-        use std::process::Command;
-        // Best-effort: rely on the front application bundle via AX and call AppleScript accordingly
-        // We don't have the bundle ID in this scope; infer using the cached context later if needed.
-        // As a practical fallback, try both Safari and Chrome quickly; whichever returns non-empty wins.
-        let candidates = [
-            (
-                "Safari",
-                r#"tell application "Safari" to get name of front document"#,
-            ),
-            (
-                "Safari Technology Preview",
-                r#"tell application "Safari Technology Preview" to get name of front document"#,
-            ),
-            (
-                "Google Chrome",
-                r#"tell application "Google Chrome" to get title of active tab of front window"#,
-            ),
-        ];
-        for (_name, script) in candidates.iter() {
-            if let Ok(output) = Command::new("osascript").arg("-e").arg(script).output() {
-                if output.status.success() {
-                    let s = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                    if !s.is_empty() {
-                        return Some(s);
-                    }
-                }
-            }
-        }
-        None
-    }
-
-    /// Count browser tabs
-    fn count_browser_tabs(&self, _ax_app: AXUIElement) -> Option<usize> {
-        use std::process::Command;
-        // Try Chrome first
-        let chrome = Command::new("osascript")
-            .arg("-e")
-            .arg(r#"tell application "Google Chrome" to get (count of tabs of front window)"#)
-            .output();
-        if let Ok(out) = chrome {
-            if out.status.success() {
-                if let Ok(s) = String::from_utf8(out.stdout) {
-                    if let Ok(n) = s.trim().parse::<usize>() {
-                        return Some(n);
-                    }
-                }
-            }
-        }
-        // Then Safari
-        let safari = Command::new("osascript")
-            .arg("-e")
-            .arg(r#"tell application "Safari" to get (count of tabs of front window)"#)
-            .output();
-        if let Ok(out) = safari {
-            if out.status.success() {
-                if let Ok(s) = String::from_utf8(out.stdout) {
-                    if let Ok(n) = s.trim().parse::<usize>() {
-                        return Some(n);
-                    }
-                }
-            }
-        }
-        None
-    }
-
     /// Extract Finder selection
-    fn extract_finder_selection(&self, _ax_app: AXUIElement) -> Option<Vec<String>> {
+    fn extract_finder_selection(&self, _ax_app: &AxElement) -> Option<Vec<String>> {
+        let _span = tracing::debug_span!("applescript").entered();
         use std::process::Command;
         // Return POSIX paths of selected items; if none, current folder of front window
         let script = r#"
@@ -1228,7 +803,7 @@ impl AccessibilityContextExtractor {
     }
 
     /// Extract selected text from document applications
-    fn extract_selected_text(&self, _ax_app: AXUIElement) -> Option<String> {
+    fn extract_selected_text(&self, _ax_app: &AxElement) -> Option<String> {
         // Implementation would find and extract selected text
         None
     }
@@ -1236,7 +811,7 @@ impl AccessibilityContextExtractor {
     /// Extract all available attributes for debugging
     fn extract_all_attributes(
         &self,
-        _element: AXUIElement,
+        _element: &AxElement,
         _attributes: &mut HashMap<String, String>,
     ) {
         // Implementation would iterate through all possible attributes
@@ -1244,7 +819,7 @@ impl AccessibilityContextExtractor {
     }
 
     /// Build the hierarchy path of UI elements
-    fn build_ui_path(&self, _element: AXUIElement) -> Vec<String> {
+    fn build_ui_path(&self, _element: &AxElement) -> Vec<String> {
         let path = Vec::new();
         // Implementation would traverse parent elements using AXParent attribute
         path
@@ -1262,13 +837,16 @@ impl AccessibilityContextExtractor {
     }
 
     fn is_ide(&self, bundle_id: &str) -> bool {
-        bundle_id.contains("VSCode")
-            || bundle_id.contains("cursor")
+        self.is_vscode_family(bundle_id)
             || bundle_id.contains("intellij")
             || bundle_id.contains("Xcode")
             || bundle_id.contains("CotEditor")
     }
 
+    fn is_vscode_family(&self, bundle_id: &str) -> bool {
+        VSCODE_FAMILY_BUNDLE_IDS.contains(&bundle_id)
+    }
+
     fn is_document_app(&self, bundle_id: &str) -> bool {
         bundle_id.contains("Preview")
             || bundle_id.contains("Adobe")
@@ -1278,204 +856,470 @@ impl AccessibilityContextExtractor {
     }
 }
 
+/// Read the focused element's attributes through `reader`
+pub fn read_focused_element(reader: &dyn AttributeReader) -> UIElementInfo {
+    UIElementInfo {
+        role: reader.string("AXRole"),
+        title: reader.string("AXTitle"),
+        value: reader.string("AXValue"),
+        description: reader.string("AXDescription"),
+        url: reader.string("AXURL"),
+        identifier: reader.string("AXIdentifier"),
+        placeholder: reader.string("AXPlaceholderValue"),
+        selected_text: reader.string("AXSelectedText"),
+        position: reader.point("AXPosition"),
+        size: reader.size("AXSize"),
+        frame: reader.frame("AXFrame"),
+        parent: reader.string("AXParent"),
+        children_count: reader.integer("AXChildrenCount"),
+        tab_index: reader.integer("AXTabIndex").map(|v| v as i32),
+        enabled: reader.boolean("AXEnabled"),
+        focused: reader.boolean("AXFocused"),
+        selected: reader.boolean("AXSelected"),
+        expanded: reader.boolean("AXExpanded"),
+        checked: reader.boolean("AXChecked"),
+        pressed: reader.boolean("AXPressed"),
+        text_range: None, // Would need special handling for range tuple
+        insertion_point: reader.integer("AXInsertionPoint"),
+        line_number: reader.integer("AXLineNumber"),
+        column_number: reader.integer("AXColumnNumber"),
+        tag_name: reader.string("AXTagName"),
+        class_name: reader.string("AXClassName"),
+        aria_label: reader.string("AXAriaLabel"),
+        window_title: reader.string("AXWindowTitle"),
+        application_role: reader.string("AXApplicationRole"),
+        help_text: reader.string("AXHelp"),
+    }
+}
+
+/// `AttributeReader` over one element using the extractor's typed getters
+struct ElementAttributes<'a> {
+    extractor: &'a AccessibilityContextExtractor,
+    element: &'a AxElement,
+}
+
+impl AttributeReader for ElementAttributes<'_> {
+    fn string(&self, attribute: &str) -> Option<String> {
+        self.extractor.get_string_attribute_custom(self.element, attribute)
+    }
+
+    fn boolean(&self, attribute: &str) -> Option<bool> {
+        self.extractor.get_boolean_attribute(self.element, attribute)
+    }
+
+    fn integer(&self, attribute: &str) -> Option<usize> {
+        self.extractor.get_integer_attribute(self.element, attribute)
+    }
+
+    fn point(&self, attribute: &str) -> Option<CGPoint> {
+        self.extractor.get_point_attribute(self.element, attribute)
+    }
+
+    fn size(&self, attribute: &str) -> Option<CGSize> {
+        self.extractor.get_size_attribute(self.element, attribute)
+    }
+
+    fn frame(&self, attribute: &str) -> Option<CGRect> {
+        self.extractor.get_frame_attribute(self.element, attribute)
+    }
+}
+
+/// Pieces of an editor window title
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdeTitle {
+    pub file: String,
+    pub project: Option<String>,
+    pub is_dirty: bool,
+}
+
+/// Leading markers editors use for unsaved files
+const IDE_DIRTY_MARKERS: &[char] = &['●', '•'];
+
+/// Whether a window title starts with an unsaved-changes marker
+pub fn title_marks_unsaved(title: &str) -> bool {
+    title.trim_start().starts_with(IDE_DIRTY_MARKERS)
+}
+
+/// Unsaved changes in a window: `AXIsDocumentEdited` when the app exposes
+/// it, otherwise only a dirty dot leading the title (which proves nothing
+/// when missing)
+pub fn document_edited(ax_edited: Option<bool>, title: Option<&str>) -> Option<bool> {
+    ax_edited.or_else(|| title.is_some_and(title_marks_unsaved).then_some(true))
+}
+
+/// VS Code and its forks; Cursor ships under its ToDesktop build id
+const VSCODE_FAMILY_BUNDLE_IDS: &[&str] = &[
+    "com.microsoft.VSCode",
+    "com.microsoft.VSCodeInsiders",
+    "com.vscodium",
+    "com.todesktop.230313mzl4w4u92",
+];
+
+/// Trailing title segments that name the editor rather than the project
+const IDE_APP_SUFFIXES: &[&str] = &[
+    "Visual Studio Code",
+    "Visual Studio Code - Insiders",
+    "Cursor",
+    "VSCodium",
+];
+
+/// Parse an editor window title into file, project and dirty state
+///
+/// Handles the VS Code/Cursor format `● file — folder — Name (Workspace)`:
+/// the dirty dot is stripped, the first segment is the file, and the project is
+/// the last segment that is neither parenthetical nor the editor's own name.
+/// Remote suffixes such as `[SSH: host]` are dropped from the project.
+pub fn parse_ide_title(title: &str) -> Option<IdeTitle> {
+    let trimmed = title.trim();
+    let (is_dirty, rest) = match trimmed.strip_prefix(IDE_DIRTY_MARKERS) {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, trimmed),
+    };
+
+    // Try multiple separator patterns used by different IDEs
+    let segments = [" — ", " - ", " • "].iter().find_map(|separator| {
+        let parts: Vec<&str> = rest
+            .split(separator)
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .collect();
+        if parts.len() >= 2 {
+            Some(parts)
+        } else {
+            None
+        }
+    })?;
+
+    let project = segments[1..]
+        .iter()
+        .rev()
+        .filter(|s| !IDE_APP_SUFFIXES.contains(s))
+        .find(|s| !(s.ends_with(')') && s.contains('(')))
+        .map(|s| match s.rfind(" [") {
+            Some(idx) if s.ends_with(']') => s[..idx].trim().to_string(),
+            _ => s.to_string(),
+        });
+
+    Some(IdeTitle {
+        file: segments[0].to_string(),
+        project,
+        is_dirty,
+    })
+}
+
 /// Extract accessibility context for a given application
 /// This is the main entry point for extracting rich context from any application
+///
+/// Reads no content attributes (AXValue, AXSelectedText, ...); use
+/// `AccessibilityContextExtractor` with an `AttributeAllowlist` for those.
 pub fn extract_accessibility_context(app_info: &crate::core::app_switcher_types::AppInfo) -> Result<AccessibilityContext, String> {
-    use std::ptr;
-    
-    unsafe {
-        // Check if accessibility is trusted
-        if !AXIsProcessTrusted() {
-            return Err("Accessibility not trusted".to_string());
-        }
-        
-        let ax_app = AXUIElementCreateApplication(app_info.pid);
-        if ax_app.is_null() {
-            return Err("Failed to create AX element".to_string());
-        }
-        
-        let mut context = AccessibilityContext {
-            app_info: app_info.clone(),
-            window_title: None,
-            document_path: None,
-            is_document_modified: None,
-            current_url: None,
-            page_title: None,
-            tab_count: None,
-            active_file_path: None,
-            project_name: None,
+    // Check if accessibility is trusted
+    if !unsafe { AXIsProcessTrusted() } {
+        return Err("Accessibility not trusted".to_string());
+    }
+
+    let ax_app =
+        AxElement::application(app_info.pid).ok_or("Failed to create AX element".to_string())?;
+
+    let mut context = AccessibilityContext {
+        app_info: app_info.clone(),
+        window_title: None,
+        document_path: None,
+        document_path_confidence: None,
+        is_document_modified: None,
+        current_url: None,
+        page_title: None,
+        tab_count: None,
+        browser_profile: None,
+        sheet_name: None,
+        selected_range: None,
+        mail_folder: None,
+        conversation: None,
+        current_reference: None,
+        reference_collection: None,
+        terminal_command: None,
+        active_file_path: None,
+        project_name: None,
+        selected_text: None,
+        focused_element: None,
+        ui_path: Vec::new(),
+        raw_attributes: HashMap::new(),
+        windows: Vec::new(),
+        custom_fields: HashMap::new(),
+    };
+
+    // Get window title
+    context.window_title = ax_focused_window_title_quick(app_info.pid);
+    context.is_document_modified = ax_focused_window_document_edited_quick(app_info.pid);
+
+    // Try to get focused element
+    if let Some(focused) = ax_app.element("AXFocusedUIElement") {
+        // Extract focused element information
+        let mut element_info = UIElementInfo {
+            role: None,
+            title: None,
+            value: None,
+            description: None,
+            url: None,
+            identifier: None,
+            placeholder: None,
             selected_text: None,
-            focused_element: None,
-            ui_path: Vec::new(),
-            raw_attributes: HashMap::new(),
+            position: None,
+            size: None,
+            frame: None,
+            parent: None,
+            children_count: None,
+            tab_index: None,
+            enabled: None,
+            focused: Some(true), // It's the focused element
+            selected: None,
+            expanded: None,
+            checked: None,
+            pressed: None,
+            text_range: None,
+            insertion_point: None,
+            line_number: None,
+            column_number: None,
+            tag_name: None,
+            class_name: None,
+            aria_label: None,
+            window_title: None,
+            application_role: None,
+            help_text: None,
         };
-        
-        // Get window title
-        context.window_title = ax_focused_window_title_quick(app_info.pid);
-        
-        // Try to get focused element
-        let focused_attr = CFStringCore::new("AXFocusedUIElement");
-        let mut focused_value: CFTypeRefSys = ptr::null();
-        
-        if AXUIElementCopyAttributeValue(
-            ax_app,
-            focused_attr.as_concrete_TypeRef() as *const _,
-            &mut focused_value
-        ) == kAXErrorSuccess && !focused_value.is_null() {
-            // Extract focused element information
-            let mut element_info = UIElementInfo {
-                role: None,
-                title: None,
-                value: None,
-                description: None,
-                url: None,
-                identifier: None,
-                placeholder: None,
-                selected_text: None,
-                position: None,
-                size: None,
-                frame: None,
-                parent: None,
-                children_count: None,
-                tab_index: None,
-                enabled: None,
-                focused: Some(true),  // It's the focused element
-                selected: None,
-                expanded: None,
-                checked: None,
-                pressed: None,
-                text_range: None,
-                insertion_point: None,
-                line_number: None,
-                column_number: None,
-                tag_name: None,
-                class_name: None,
-                aria_label: None,
-                window_title: None,
-                application_role: None,
-                help_text: None,
-            };
-            
-            // Get role
-            let role_attr = CFStringCore::new("AXRole");
-            let mut role_value: CFTypeRefSys = ptr::null();
-            if AXUIElementCopyAttributeValue(
-                focused_value as AXUIElement,
-                role_attr.as_concrete_TypeRef() as *const _,
-                &mut role_value
-            ) == kAXErrorSuccess && !role_value.is_null() {
-                let role_str = CFStringCore::wrap_under_get_rule(role_value as _);
-                element_info.role = Some(role_str.to_string());
-                CFRelease(role_value);
-            }
-            
-            // Get value
-            let value_attr = CFStringCore::new("AXValue");
-            let mut value_value: CFTypeRefSys = ptr::null();
-            if AXUIElementCopyAttributeValue(
-                focused_value as AXUIElement,
-                value_attr.as_concrete_TypeRef() as *const _,
-                &mut value_value
-            ) == kAXErrorSuccess && !value_value.is_null() {
-                // Check if it's a string
-                if CFGetTypeID(value_value) == CFStringGetTypeID() {
-                    let value_str = CFStringCore::wrap_under_get_rule(value_value as _);
-                    element_info.value = Some(value_str.to_string());
-                    
-                    // If this is from a text field, it might be selected text
-                    if element_info.role.as_ref().map_or(false, |r| r.contains("Text")) {
-                        context.selected_text = Some(value_str.to_string());
-                    }
-                }
-                CFRelease(value_value);
-            }
-            
-            context.focused_element = Some(element_info);
-            CFRelease(focused_value);
-        }
-        
-        // For browsers, try to get URL
-        if app_info.bundle_id.contains("chrome") || 
-           app_info.bundle_id.contains("safari") || 
-           app_info.bundle_id.contains("firefox") {
-            // Try to get document/URL attribute
-            let doc_attr = CFStringCore::new("AXDocument");
-            let mut doc_value: CFTypeRefSys = ptr::null();
-            
-            if AXUIElementCopyAttributeValue(
-                ax_app,
-                doc_attr.as_concrete_TypeRef() as *const _,
-                &mut doc_value
-            ) == kAXErrorSuccess && !doc_value.is_null() {
-                if CFGetTypeID(doc_value) == CFStringGetTypeID() {
-                    let url_str = CFStringCore::wrap_under_get_rule(doc_value as _);
-                    context.current_url = Some(url_str.to_string());
-                }
-                CFRelease(doc_value);
-            }
-        }
-        
-        CFRelease(ax_app as _);
-        
-        Ok(context)
+
+        // Only the role: this path has no content-capture setting, so the
+        // content attributes (`ax_attributes::CONTENT_ATTRIBUTES`) are never read
+        element_info.role = focused.string("AXRole");
+
+        context.focused_element = Some(element_info);
+    }
+
+    // For browsers, try to get URL
+    if app_info.bundle_id.contains("chrome")
+        || app_info.bundle_id.contains("safari")
+        || app_info.bundle_id.contains("firefox")
+    {
+        // Try to get document/URL attribute
+        context.current_url = ax_app.string("AXDocument");
     }
+
+    Ok(context)
 }
 
 /// Quick AX helper to fetch the focused window title for a process by PID.
 /// Safe to call without constructing the full extractor; requires Accessibility permission.
 pub fn ax_focused_window_title_quick(pid: i32) -> Option<String> {
-    unsafe {
-        let ax_app = AXUIElementCreateApplication(pid);
-        if ax_app.is_null() {
-            return None;
-        }
+    AxElement::application(pid)?
+        .element("AXFocusedWindow")?
+        .string("AXTitle")
+        .filter(|title| !title.is_empty())
+}
 
-        // Focused window
-        let focused_attr = CFStringCore::new("AXFocusedWindow");
-        let focused_attr_ref: CFStringRefCF = focused_attr.as_concrete_TypeRef();
-        let mut window_val: CFTypeRefSys = std::ptr::null();
-        let st1 = AXUIElementCopyAttributeValue(
-            ax_app,
-            focused_attr_ref as CFStringRefSys,
-            &mut window_val,
-        );
-        if st1 != kAXErrorSuccess || window_val.is_null() {
-            CFRelease(ax_app as CFTypeRefCF);
-            return None;
-        }
+/// Whether the focused window of `pid` has unsaved changes; see
+/// `document_edited`
+pub fn ax_focused_window_document_edited_quick(pid: i32) -> Option<bool> {
+    let window = AxElement::application(pid)?.element("AXFocusedWindow")?;
+    document_edited(
+        window.boolean("AXIsDocumentEdited"),
+        window.string("AXTitle").as_deref(),
+    )
+}
 
-        // Title
-        let title_attr = CFStringCore::new("AXTitle");
-        let title_attr_ref: CFStringRefCF = title_attr.as_concrete_TypeRef();
-        let mut title_val: CFTypeRefSys = std::ptr::null();
-        let st2 = AXUIElementCopyAttributeValue(
-            window_val as AXUIElement,
-            title_attr_ref as CFStringRefSys,
-            &mut title_val,
-        );
-        let title = if st2 == kAXErrorSuccess && !title_val.is_null() {
-            if CFGetTypeID(title_val as *const _) == CFStringGetTypeID() {
-                let cfstr = core_foundation::string::CFString::wrap_under_create_rule(
-                    title_val as CFStringRefCF,
-                );
-                let s = cfstr.to_string();
-                if s.is_empty() {
-                    None
-                } else {
-                    Some(s)
-                }
-            } else {
-                CFRelease(title_val as CFTypeRefCF);
-                None
+/// AXDocument of the focused window of `pid`, usually a file URL
+pub fn ax_focused_window_document_quick(pid: i32) -> Option<String> {
+    AxElement::application(pid)?
+        .element("AXFocusedWindow")?
+        .string("AXDocument")
+        .filter(|document| !document.is_empty())
+}
+
+/// AXSelectedText of the focused element of `pid`; this is content, so
+/// callers gate it on content capture
+pub fn ax_selected_text_quick(pid: i32) -> Option<String> {
+    AxElement::application(pid)?
+        .element("AXFocusedUIElement")?
+        .string("AXSelectedText")
+        .filter(|text| !text.is_empty())
+}
+
+/// Read AXFullScreen from the focused window of `pid`
+///
+/// Returns None when the app has no focused window or does not expose the
+/// attribute; callers fall back to comparing window and display bounds.
+pub fn ax_focused_window_fullscreen_quick(pid: i32) -> Option<bool> {
+    AxElement::application(pid)?
+        .element("AXFocusedWindow")?
+        .boolean("AXFullScreen")
+}
+
+/// CGWindowID of the focused window of `pid`, which may be on a background
+/// Space, unlike the on-screen windows CGWindowList reports
+pub fn ax_focused_window_id_quick(pid: i32) -> Option<u32> {
+    AxElement::application(pid)?
+        .element("AXFocusedWindow")?
+        .window_id()
+}
+
+/// AXSubrole of each of the app's windows, skipping windows without one
+pub fn ax_window_subroles_quick(pid: i32) -> Vec<String> {
+    AxElement::application(pid)
+        .map(|app| read_raw_ax_windows(&app, DEFAULT_MAX_WINDOWS))
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|window| window.subrole)
+        .collect()
+}
+
+/// Default cap on the number of windows read per app
+pub const DEFAULT_MAX_WINDOWS: usize = 20;
+
+/// One window of an application, as listed by AXWindows
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct WindowInfo {
+    pub title: Option<String>,
+    pub bounds: Option<WindowFrame>,
+    pub minimized: bool,
+    /// The app's main window (AXMain)
+    pub main: bool,
+}
+
+/// Window position and size in global screen coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
+pub struct WindowFrame {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Attributes read from one AXWindows entry
+#[derive(Debug, Clone, Default)]
+pub struct RawAxWindow {
+    pub title: Option<String>,
+    pub position: Option<(f64, f64)>,
+    pub size: Option<(f64, f64)>,
+    pub minimized: Option<bool>,
+    pub main: Option<bool>,
+    pub subrole: Option<String>,
+}
+
+/// Turn raw AXWindows entries into window infos, keeping at most `max`
+pub fn assemble_windows(raw: &[RawAxWindow], max: usize) -> Vec<WindowInfo> {
+    raw.iter()
+        .take(max)
+        .map(|window| {
+            let minimized = window.minimized.unwrap_or(false);
+            WindowInfo {
+                title: window.title.clone().filter(|t| !t.is_empty()),
+                bounds: match (window.position, window.size) {
+                    (Some((x, y)), Some((width, height))) => Some(WindowFrame {
+                        x,
+                        y,
+                        width,
+                        height,
+                    }),
+                    _ => None,
+                },
+                minimized,
+                // A minimized window can still carry a stale AXMain
+                main: window.main.unwrap_or(false) && !minimized,
             }
-        } else {
-            None
-        };
+        })
+        .collect()
+}
 
-        // Release objects
-        CFRelease(window_val as CFTypeRefCF);
-        CFRelease(ax_app as CFTypeRefCF);
-        title
+/// Read the windows of `pid` through AXWindows; requires Accessibility permission
+pub fn ax_app_windows(pid: i32, max: usize) -> Vec<WindowInfo> {
+    match AxElement::application(pid) {
+        Some(ax_app) => read_ax_windows(&ax_app, max),
+        None => Vec::new(),
+    }
+}
+
+fn read_ax_windows(ax_app: &AxElement, max: usize) -> Vec<WindowInfo> {
+    assemble_windows(&read_raw_ax_windows(ax_app, max), max)
+}
+
+/// The attributes of up to `max` AXWindows entries
+fn read_raw_ax_windows(ax_app: &AxElement, max: usize) -> Vec<RawAxWindow> {
+    ax_app
+        .elements("AXWindows")
+        .iter()
+        .take(max)
+        .map(|window| RawAxWindow {
+            title: window.string("AXTitle"),
+            position: window.point("AXPosition"),
+            size: window.size("AXSize"),
+            minimized: window.boolean("AXMinimized"),
+            main: window.boolean("AXMain"),
+            subrole: window.string("AXSubrole"),
+        })
+        .collect()
+}
+
+/// Upper bounds for the web area search, to keep it cheap on large pages
+const WEB_AREA_MAX_DEPTH: usize = 12;
+const WEB_AREA_MAX_NODES: usize = 400;
+
+/// URL of a browser window's web content, found in one bounded walk
+///
+/// Looks for an AXWebArea exposing AXURL (Chromium-based browsers such as
+/// Arc); a text field holding a URL, such as the address bar or Arc's
+/// command bar, is used when no web area has one.
+pub fn page_url_in(window: &AxElement) -> Option<String> {
+    let mut text_field_url = None;
+    let web_area = window.find_descendant(WEB_AREA_MAX_DEPTH, WEB_AREA_MAX_NODES, |element| {
+        match element.string("AXRole").as_deref() {
+            Some("AXWebArea") => element.url("AXURL").is_some(),
+            Some("AXTextField") if text_field_url.is_none() => {
+                text_field_url = element
+                    .string("AXValue")
+                    .filter(|v| v.starts_with("https://") || v.starts_with("http://"));
+                false
+            }
+            _ => false,
+        }
+    });
+    web_area.and_then(|area| area.url("AXURL")).or(text_field_url)
+}
+
+/// URL of the focused window's web content, read from the AX tree
+pub fn ax_web_area_url(pid: i32) -> Option<String> {
+    page_url_in(&AxElement::application(pid)?.element("AXFocusedWindow")?)
+}
+
+/// Role of the app's focused UI element, e.g. `AXTextArea`
+pub fn ax_focused_role(pid: i32) -> Option<String> {
+    AxElement::application(pid)?
+        .element("AXFocusedUIElement")?
+        .string("AXRole")
+}
+
+/// Attaches the foreground app's window list to switch events
+pub struct WindowListCapture {
+    max_windows: usize,
+}
+
+impl WindowListCapture {
+    pub fn new(max_windows: usize) -> Self {
+        Self { max_windows }
+    }
+}
+
+impl EnrichmentStage for WindowListCapture {
+    fn name(&self) -> &str {
+        "window_list"
+    }
+
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Annotate
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        if event.event_type.is_foreground() {
+            event.windows = Some(ax_app_windows(event.app_info.pid, self.max_windows));
+        }
     }
 }
 
@@ -1489,13 +1333,45 @@ impl AppSwitchListener for AccessibilityContextExtractor {
         // Clear cache for the previous app to ensure fresh data
         // This prevents stale context from affecting research insights
         if let Some(prev_app) = &event.previous_app {
-            self.context_cache.remove(&prev_app.pid);
+            self.context_cache.remove(prev_app.pid);
+        }
+
+        if event.redacted || self.quiet_hours.as_ref().is_some_and(|gate| gate.is_quiet()) {
+            tracing::debug!(
+                "Skipping context extraction for {}: content withheld",
+                event.app_info.bundle_id
+            );
+            return;
+        }
+
+        // Apps refusing accessibility are skipped until a re-probe is due
+        if !self
+            .ax_availability
+            .should_attempt(event.app_info.match_key(), Instant::now())
+        {
+            tracing::debug!(
+                "Skipping context extraction for {}: accessibility unavailable",
+                event.app_info.bundle_id
+            );
+            return;
         }
 
         // Extract context for the new app if we support it
-        if self.supported_bundles.contains(&event.app_info.bundle_id) {
-            match self.extract_context(&event.app_info) {
+        if self.supported_bundles.is_supported(event.app_info.match_key()) {
+            match self.extract_context_with_windows(&event.app_info, event.windows.as_deref()) {
                 Ok(context) => {
+                    let excluded = self
+                        .title_exclusion
+                        .as_ref()
+                        .is_some_and(|exclusion| exclusion.matches_context(&context));
+                    if excluded {
+                        tracing::debug!(
+                            "Skipping context output for {}: title excluded",
+                            event.app_info.bundle_id
+                        );
+                        return;
+                    }
+
                     // Log the enhanced context in a research-friendly format
                     println!("🔍 Enhanced Context Extracted:");
                     println!(
@@ -1507,13 +1383,53 @@ impl AppSwitchListener for AccessibilityContextExtractor {
                         println!("   📍 URL: {}", url);
                     }
 
+                    if let Some(profile) = &context.browser_profile {
+                        println!("   👤 Profile: {}", profile);
+                    }
+
+                    if let Some(range) = &context.selected_range {
+                        println!("   📊 Range: {}", range);
+                    } else if let Some(sheet) = &context.sheet_name {
+                        println!("   📊 Sheet: {}", sheet);
+                    }
+
+                    if let Some(folder) = &context.mail_folder {
+                        println!("   📬 Mailbox: {}", folder);
+                    }
+                    if let Some(conversation) = &context.conversation {
+                        println!("   ✉️  Conversation: {}", conversation);
+                    }
+                    if let Some(reference) = &context.current_reference {
+                        println!("   📚 Reference: {}", reference.title);
+                        if !reference.authors.is_empty() {
+                            println!("      Authors: {}", reference.authors.join("; "));
+                        }
+                        if let Some(year) = &reference.year {
+                            println!("      Year: {}", year);
+                        }
+                    }
+                    if let Some(collection) = &context.reference_collection {
+                        println!("   🗂️  Collection: {}", collection);
+                    }
+                    if let Some(command) = &context.terminal_command {
+                        println!("   💻 Command: {}", command);
+                    }
+
                     if let Some(file) = &context.active_file_path {
-                        println!("   📄 File: {}", file);
+                        if context.document_path_confidence == Some(PathConfidence::Low) {
+                            println!("   📄 File: {} (low confidence)", file);
+                        } else {
+                            println!("   📄 File: {}", file);
+                        }
                         if let Some(project) = &context.project_name {
                             println!("      Project: {}", project);
                         }
                     }
 
+                    if context.is_document_modified == Some(true) {
+                        println!("   💾 Unsaved changes");
+                    }
+
                     if let Some(element) = &context.focused_element {
                         if let Some(role) = &element.role {
                             println!("   🎯 Focused: {} element", role);
@@ -1528,6 +1444,12 @@ impl AppSwitchListener for AccessibilityContextExtractor {
                     if let Some(selected) = &context.selected_text {
                         println!("   ✏️  Selected: {}", selected);
                     }
+
+                    let mut custom: Vec<_> = context.custom_fields.iter().collect();
+                    custom.sort();
+                    for (name, value) in custom {
+                        println!("   🧩 {}: {}", name, value);
+                    }
                 }
                 Err(e) => {
                     eprintln!(
@@ -1563,3 +1485,101 @@ impl AppSwitchListener for AccessibilityContextExtractor {
         self.context_cache.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assemble_windows() {
+        let raw = vec![
+            RawAxWindow {
+                title: Some("main.rs — crate".to_string()),
+                position: Some((0.0, 25.0)),
+                size: Some((1440.0, 875.0)),
+                minimized: Some(false),
+                main: Some(true),
+                subrole: Some("AXStandardWindow".to_string()),
+            },
+            RawAxWindow {
+                title: Some(String::new()),
+                position: Some((100.0, 100.0)),
+                size: None,
+                minimized: None,
+                main: Some(false),
+                subrole: None,
+            },
+            RawAxWindow {
+                title: Some("Settings".to_string()),
+                position: Some((0.0, 0.0)),
+                size: Some((600.0, 400.0)),
+                minimized: Some(true),
+                main: Some(true),
+                subrole: None,
+            },
+        ];
+
+        let windows = assemble_windows(&raw, DEFAULT_MAX_WINDOWS);
+        assert_eq!(
+            windows[0],
+            WindowInfo {
+                title: Some("main.rs — crate".to_string()),
+                bounds: Some(WindowFrame {
+                    x: 0.0,
+                    y: 25.0,
+                    width: 1440.0,
+                    height: 875.0,
+                }),
+                minimized: false,
+                main: true,
+            }
+        );
+        assert_eq!(windows[1].title, None);
+        assert_eq!(windows[1].bounds, None, "bounds need both position and size");
+        assert!(!windows[1].main);
+        assert!(windows[2].minimized);
+        assert!(!windows[2].main, "a minimized window is never main");
+
+        assert_eq!(assemble_windows(&raw, 2).len(), 2);
+    }
+
+    #[test]
+    fn test_parse_ide_title() {
+        let cases = [
+            ("main.rs — tracker", "main.rs", Some("tracker"), false),
+            ("● main.rs — tracker", "main.rs", Some("tracker"), true),
+            ("lib.rs — tracker [SSH: devbox]", "lib.rs", Some("tracker"), false),
+            (
+                "● api.rs — server — monorepo (Workspace)",
+                "api.rs",
+                Some("server"),
+                true,
+            ),
+            ("notes.md — docs — Cursor", "notes.md", Some("docs"), false),
+            ("AppDelegate.swift - MyApp", "AppDelegate.swift", Some("MyApp"), false),
+        ];
+
+        for (title, file, project, dirty) in cases {
+            let parsed = parse_ide_title(title).expect(title);
+            assert_eq!(parsed.file, file, "{}", title);
+            assert_eq!(parsed.project.as_deref(), project, "{}", title);
+            assert_eq!(parsed.is_dirty, dirty, "{}", title);
+        }
+
+        assert_eq!(parse_ide_title("Welcome"), None);
+    }
+
+    #[test]
+    fn test_document_edited() {
+        // AXIsDocumentEdited is authoritative
+        assert_eq!(document_edited(Some(true), Some("report.md")), Some(true));
+        assert_eq!(document_edited(Some(false), Some("● report.md")), Some(false));
+
+        // Otherwise a leading dirty dot or bullet
+        assert_eq!(document_edited(None, Some("● main.rs — crate")), Some(true));
+        assert_eq!(document_edited(None, Some("  • notes.txt")), Some(true));
+        assert_eq!(document_edited(None, Some("main.rs — crate")), None);
+        assert_eq!(document_edited(None, Some("Budget • Q3")), None);
+        assert_eq!(document_edited(None, None), None);
+    }
+}
@@ -0,0 +1,116 @@
+// src/core/app_lifecycle.rs
+//! App launch and termination from NSWorkspace notifications
+//!
+//! Activation only covers apps the user brings to the front. Launch and
+//! terminate notifications also cover apps that start or quit in the
+//! background (login items, helpers, apps quit from the Dock), so analytics
+//! can see app lifetimes and not just focus. The workspace observer turns
+//! both notifications into events with `lifecycle_event` and hands them to the
+//! hub; both observers read the app from `userInfo` through the helpers here.
+
+use objc2::rc::Retained;
+use objc2_app_kit::NSRunningApplication;
+use objc2_foundation::{NSDictionary, NSString};
+
+use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent, AppSwitchType};
+
+pub const WORKSPACE_DID_LAUNCH: &str = "NSWorkspaceDidLaunchApplicationNotification";
+pub const WORKSPACE_DID_TERMINATE: &str = "NSWorkspaceDidTerminateApplicationNotification";
+
+/// `userInfo` key holding the `NSRunningApplication`
+const APPLICATION_KEY: &str = "NSWorkspaceApplicationKey";
+
+/// Event type for a lifecycle notification name
+pub fn lifecycle_event_type(notification_name: &str) -> Option<AppSwitchType> {
+    match notification_name {
+        WORKSPACE_DID_LAUNCH => Some(AppSwitchType::Launch),
+        WORKSPACE_DID_TERMINATE => Some(AppSwitchType::Terminate),
+        _ => None,
+    }
+}
+
+/// The app a workspace notification is about; `None` if missing or not an app
+pub fn running_app_from_user_info(
+    user_info: &NSDictionary,
+) -> Option<Retained<NSRunningApplication>> {
+    let key = NSString::from_str(APPLICATION_KEY);
+    user_info
+        .objectForKey(&key)?
+        .downcast::<NSRunningApplication>()
+        .ok()
+}
+
+pub fn app_info_from_running_app(app: &NSRunningApplication) -> AppInfo {
+    unsafe {
+        let mut info = AppInfo::new(
+            app.localizedName()
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            app.bundleIdentifier()
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            app.processIdentifier(),
+        );
+        // Bare executables have no bundle; their path names them instead
+        info.path = app
+            .bundleURL()
+            .or_else(|| app.executableURL())
+            .and_then(|url| url.path())
+            .map(|p| p.to_string());
+        info
+    }
+}
+
+/// Map a launch/terminate notification to an event
+pub fn lifecycle_event(
+    notification_name: &str,
+    user_info: &NSDictionary,
+) -> Option<AppSwitchEvent> {
+    let event_type = lifecycle_event_type(notification_name)?;
+    let app = running_app_from_user_info(user_info)?;
+    let mut event = AppSwitchEvent::new(event_type, app_info_from_running_app(&app));
+    // Reported directly by NSWorkspace
+    event.confidence = Some(1.0);
+    Some(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use objc2::runtime::AnyObject;
+
+    fn user_info(key: &str, value: &AnyObject) -> Retained<NSDictionary> {
+        let dict = NSDictionary::<NSString, AnyObject>::from_slices(
+            &[&*NSString::from_str(key)],
+            &[value],
+        );
+        // Same object; only the static key type is widened to `AnyObject`
+        unsafe { Retained::cast_unchecked(dict) }
+    }
+
+    #[test]
+    fn test_lifecycle_notifications_map_to_events() {
+        let current = unsafe { NSRunningApplication::currentApplication() };
+        let info = user_info(APPLICATION_KEY, &current);
+
+        let launch = lifecycle_event(WORKSPACE_DID_LAUNCH, &info).unwrap();
+        assert_eq!(launch.event_type, AppSwitchType::Launch);
+        assert_eq!(launch.app_info.pid, std::process::id() as i32);
+        assert_eq!(launch.confidence, Some(1.0));
+
+        let terminate = lifecycle_event(WORKSPACE_DID_TERMINATE, &info).unwrap();
+        assert_eq!(terminate.event_type, AppSwitchType::Terminate);
+
+        assert!(lifecycle_event("NSWorkspaceDidActivateApplicationNotification", &info).is_none());
+    }
+
+    #[test]
+    fn test_user_info_without_app_is_ignored() {
+        let current = unsafe { NSRunningApplication::currentApplication() };
+        let wrong_key = user_info("SomethingElse", &current);
+        assert!(lifecycle_event(WORKSPACE_DID_LAUNCH, &wrong_key).is_none());
+
+        let not_an_app = user_info(APPLICATION_KEY, &NSString::from_str("Safari"));
+        assert!(lifecycle_event(WORKSPACE_DID_LAUNCH, &not_an_app).is_none());
+    }
+}
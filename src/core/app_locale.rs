@@ -0,0 +1,172 @@
+// src/core/app_locale.rs
+//! UI language of the frontmost application
+//!
+//! Users can run individual apps in a different language from the system
+//! (System Settings → Language & Region → Applications), which is stored as
+//! `AppleLanguages` in the app's own preferences domain. Knowing the language
+//! helps interpret window titles in non-English apps.
+//!
+//! Fallback chain: the app's `AppleLanguages` → the global `AppleLanguages`
+//! → the current `CFLocale` identifier.
+
+use core_foundation::array::{
+    CFArrayGetCount, CFArrayGetTypeID, CFArrayGetValueAtIndex, CFArrayRef,
+};
+use core_foundation::base::{CFGetTypeID, CFRelease, CFTypeRef, TCFType};
+use core_foundation::string::{CFString, CFStringGetTypeID, CFStringRef};
+use core_foundation_sys::locale::{CFLocaleCopyCurrent, CFLocaleGetIdentifier};
+use core_foundation_sys::preferences::{
+    kCFPreferencesAnyApplication, kCFPreferencesAnyHost, kCFPreferencesCurrentUser,
+    CFPreferencesCopyValue,
+};
+
+/// Where locale information comes from; mocked in tests
+pub trait LocaleSource {
+    /// Preferred languages set for one app only, most preferred first
+    fn app_languages(&self, bundle_id: &str) -> Option<Vec<String>>;
+
+    /// System-wide preferred languages, most preferred first
+    fn system_languages(&self) -> Option<Vec<String>>;
+
+    /// Identifier of the current locale, e.g. `en_US`
+    fn system_locale(&self) -> Option<String>;
+}
+
+/// Resolve the effective UI language for an app
+pub fn app_locale(source: &dyn LocaleSource, bundle_id: &str) -> Option<String> {
+    let first =
+        |langs: Option<Vec<String>>| langs.and_then(|l| l.into_iter().find(|s| !s.is_empty()));
+
+    if !bundle_id.is_empty() {
+        if let Some(lang) = first(source.app_languages(bundle_id)) {
+            return Some(lang);
+        }
+    }
+    first(source.system_languages()).or_else(|| source.system_locale().filter(|s| !s.is_empty()))
+}
+
+/// Reads CFPreferences and CFLocale
+pub struct SystemLocaleSource;
+
+impl LocaleSource for SystemLocaleSource {
+    fn app_languages(&self, bundle_id: &str) -> Option<Vec<String>> {
+        let domain = CFString::new(bundle_id);
+        copy_languages(domain.as_concrete_TypeRef())
+    }
+
+    fn system_languages(&self) -> Option<Vec<String>> {
+        copy_languages(unsafe { kCFPreferencesAnyApplication })
+    }
+
+    fn system_locale(&self) -> Option<String> {
+        unsafe {
+            let locale = CFLocaleCopyCurrent();
+            if locale.is_null() {
+                return None;
+            }
+            let identifier = CFLocaleGetIdentifier(locale);
+            let result = if identifier.is_null() {
+                None
+            } else {
+                Some(CFString::wrap_under_get_rule(identifier).to_string())
+            };
+            CFRelease(locale as CFTypeRef);
+            result
+        }
+    }
+}
+
+/// Read `AppleLanguages` from exactly one preferences domain (no search list,
+/// so an app without its own setting does not inherit the global one here)
+fn copy_languages(domain: CFStringRef) -> Option<Vec<String>> {
+    unsafe {
+        let key = CFString::new("AppleLanguages");
+        let value = CFPreferencesCopyValue(
+            key.as_concrete_TypeRef(),
+            domain,
+            kCFPreferencesCurrentUser,
+            kCFPreferencesAnyHost,
+        );
+        if value.is_null() {
+            return None;
+        }
+
+        let mut languages = Vec::new();
+        if CFGetTypeID(value) == CFArrayGetTypeID() {
+            let array = value as CFArrayRef;
+            for i in 0..CFArrayGetCount(array) {
+                let item = CFArrayGetValueAtIndex(array, i);
+                if !item.is_null() && CFGetTypeID(item) == CFStringGetTypeID() {
+                    languages.push(CFString::wrap_under_get_rule(item as CFStringRef).to_string());
+                }
+            }
+        }
+        CFRelease(value);
+        if languages.is_empty() {
+            None
+        } else {
+            Some(languages)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockSource {
+        app: Option<Vec<String>>,
+        system: Option<Vec<String>>,
+        locale: Option<String>,
+    }
+
+    impl LocaleSource for MockSource {
+        fn app_languages(&self, _bundle_id: &str) -> Option<Vec<String>> {
+            self.app.clone()
+        }
+
+        fn system_languages(&self) -> Option<Vec<String>> {
+            self.system.clone()
+        }
+
+        fn system_locale(&self) -> Option<String> {
+            self.locale.clone()
+        }
+    }
+
+    #[test]
+    fn test_app_without_locale_falls_back_to_system() {
+        let source = MockSource {
+            app: None,
+            system: Some(vec!["de-DE".to_string(), "en-US".to_string()]),
+            locale: Some("de_DE".to_string()),
+        };
+        assert_eq!(
+            app_locale(&source, "com.apple.Safari"),
+            Some("de-DE".to_string())
+        );
+
+        let no_languages = MockSource {
+            app: None,
+            system: None,
+            locale: Some("fr_FR".to_string()),
+        };
+        assert_eq!(
+            app_locale(&no_languages, "com.apple.Safari"),
+            Some("fr_FR".to_string())
+        );
+    }
+
+    #[test]
+    fn test_app_override_wins() {
+        let source = MockSource {
+            app: Some(vec!["ja".to_string()]),
+            system: Some(vec!["en-US".to_string()]),
+            locale: Some("en_US".to_string()),
+        };
+        assert_eq!(
+            app_locale(&source, "com.apple.Notes"),
+            Some("ja".to_string())
+        );
+    }
+}
@@ -3,10 +3,11 @@
 //! provides a simple, high-level switcher used by `main.rs`.
 
 use std::collections::HashMap;
-use std::process::Command;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, Once, Weak};
 use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use objc2::MainThreadMarker;
 
 use crate::core::accessibility::ax_focused_window_title_quick;
@@ -16,9 +17,24 @@ use crate::core::app_switcher_enhanced::{
 use crate::core::app_switcher_workspace::{
     WorkspaceAppMonitor, WorkspaceAppSwitchEvent, WorkspaceAppSwitchListener,
 };
+use crate::core::ax_availability::AxAvailability;
+use crate::core::browser_strategy::{
+    browser_profile_from_title, AppleScriptCache, BrowserPage, BrowserStrategy,
+};
+use crate::core::confidence::ConfidenceFilter;
+use crate::core::enrichment::{EnrichmentPipeline, EnrichmentStage};
+use crate::core::event_coalescer::EventCoalescer;
+use crate::core::menu_context::MenuContext;
+use crate::core::navigation::NavigationFilter;
+use crate::core::self_exclusion::SelfExclusion;
+use crate::core::significant_change::SignificantChangeFilter;
+use crate::core::startup_warmup::{StartupWarmup, DEFAULT_STARTUP_WARMUP};
+use crate::core::url_canonicalizer::site_domain;
+use crate::extractors::ContextExtractor;
 
 pub use crate::core::app_switcher_types::{
-    AppInfo, AppSwitchEvent, AppSwitchListener, AppSwitchType, EnhancedSummary, WorkspaceSummary,
+    AppInfo, AppSwitchEvent, AppSwitchListener, AppSwitchType, EnhancedSummary, SleepPeriod,
+    WorkspaceSummary,
 };
 
 /// Initialize any global state needed before creating a switcher.
@@ -27,62 +43,153 @@ pub fn initialize_app_switcher(_mtm: MainThreadMarker) -> Result<(), String> {
     Ok(())
 }
 
+/// Longest the coalescing timer sleeps before checking the hub is still alive
+const COALESCE_TIMER_IDLE: Duration = Duration::from_secs(1);
+
 struct FusionHub {
     listeners: Arc<Mutex<Vec<Box<dyn AppSwitchListener>>>>,
-    pending: Arc<Mutex<HashMap<(i32, AppSwitchType), (AppSwitchEvent, Instant)>>>,
-    fuse_window: Duration,
+    /// Listeners that only run while enhanced extraction is enabled
+    enhanced_listeners: Mutex<Vec<Box<dyn AppSwitchListener>>>,
+    enhanced_enabled: AtomicBool,
+    coalescer: Mutex<EventCoalescer>,
+    /// Wakes the coalescing timer when an event is queued
+    coalesce_wakeup: Condvar,
+    /// Starts the coalescing timer with the first queued event
+    coalesce_timer: Once,
+    enrichment: Mutex<EnrichmentPipeline>,
+    exclusion: Mutex<SelfExclusion>,
+    confidence: Mutex<ConfidenceFilter>,
+    navigation: Mutex<NavigationFilter>,
+    /// Absorbs resamples that found nothing new, once extraction has run
+    significant_change: Mutex<SignificantChangeFilter>,
+    /// Apps that refuse accessibility, shared with the context extractor
+    ax_availability: AxAvailability,
+    /// Open menu path, fed by a `MenuContextObserver` when one is running
+    menu_context: MenuContext,
+    /// Library-provided extractors run on every enhanced event
+    context_extractors: Mutex<Vec<Box<dyn ContextExtractor>>>,
+    /// Holds the first event until extraction has warmed up
+    warmup: Mutex<StartupWarmup>,
+    /// Set while delivery is paused
+    paused: AtomicBool,
+    /// Whether pausing turned enhanced extraction off, so resuming restores it
+    paused_enhanced: AtomicBool,
+    /// Latest admitted foreground app, announced again on resume
+    last_foreground: Mutex<Option<AppInfo>>,
+    /// When the system went to sleep, until it wakes
+    asleep_since: Mutex<Option<DateTime<Utc>>>,
 }
 
 impl FusionHub {
     fn new(listeners: Arc<Mutex<Vec<Box<dyn AppSwitchListener>>>>) -> Arc<Self> {
         Arc::new(Self {
             listeners,
-            pending: Arc::new(Mutex::new(HashMap::new())),
-            fuse_window: Duration::from_millis(300),
+            enhanced_listeners: Mutex::new(Vec::new()),
+            enhanced_enabled: AtomicBool::new(true),
+            coalescer: Mutex::new(EventCoalescer::default()),
+            coalesce_wakeup: Condvar::new(),
+            coalesce_timer: Once::new(),
+            enrichment: Mutex::new(EnrichmentPipeline::new()),
+            exclusion: Mutex::new(SelfExclusion::default()),
+            confidence: Mutex::new(ConfidenceFilter::default()),
+            navigation: Mutex::new(NavigationFilter::default()),
+            significant_change: Mutex::new(SignificantChangeFilter::default()),
+            ax_availability: AxAvailability::default(),
+            menu_context: MenuContext::default(),
+            context_extractors: Mutex::new(Vec::new()),
+            warmup: Mutex::new(StartupWarmup::default()),
+            paused: AtomicBool::new(false),
+            paused_enhanced: AtomicBool::new(false),
+            last_foreground: Mutex::new(None),
+            asleep_since: Mutex::new(None),
         })
     }
 
-    fn emit_or_merge(self: &Arc<Self>, mut incoming: AppSwitchEvent) {
-        let key_pid = incoming.app_info.pid;
-        let key_kind = incoming.event_type.clone();
-        let now = Instant::now();
-        let mut pending = self.pending.lock().unwrap();
-        if let Some((existing, _ts)) = pending.remove(&(key_pid, key_kind.clone())) {
-            // Merge summaries and confidence
-            incoming.workspace = incoming.workspace.or(existing.workspace);
-            incoming.enhanced = incoming.enhanced.or(existing.enhanced);
-            incoming.confidence = match (incoming.confidence, existing.confidence) {
-                (Some(a), Some(b)) => Some(a.max(b)),
-                (Some(a), None) => Some(a),
-                (None, Some(b)) => Some(b),
-                (None, None) => None,
-            };
-            drop(pending);
-            self.dispatch(incoming);
-        } else {
-            // Insert and schedule flush
-            pending.insert((key_pid, key_kind.clone()), (incoming, now));
-            drop(pending);
-            let hub = Arc::clone(self);
-            std::thread::spawn(move || {
-                std::thread::sleep(hub.fuse_window);
-                let mut pending = hub.pending.lock().unwrap();
-                if let Some((evt, ts)) = pending.remove(&(key_pid, key_kind.clone())) {
-                    if ts.elapsed() >= hub.fuse_window {
-                        drop(pending);
-                        hub.dispatch(evt);
-                    } else {
-                        // put back if not elapsed (unlikely due to sleep)
-                        pending.insert((key_pid, key_kind), (evt, ts));
-                    }
+    /// Queue an event; the coalescing timer dispatches it once no event for
+    /// the same app and type has arrived for the coalescing window
+    fn emit_or_merge(self: &Arc<Self>, incoming: AppSwitchEvent) {
+        self.coalescer
+            .lock()
+            .unwrap()
+            .push_at(incoming, Instant::now());
+        self.coalesce_wakeup.notify_one();
+        self.coalesce_timer.call_once(|| {
+            let hub = Arc::downgrade(self);
+            std::thread::Builder::new()
+                .name("coalesce-timer".to_string())
+                .spawn(move || Self::run_coalesce_timer(hub))
+                .expect("failed to spawn the coalescing timer");
+        });
+    }
+
+    /// Dispatch coalesced events as they fall due, on one thread; returns
+    /// once the hub is dropped
+    fn run_coalesce_timer(hub: Weak<Self>) {
+        while let Some(hub) = hub.upgrade() {
+            let due = {
+                let mut coalescer = hub.coalescer.lock().unwrap();
+                let wait = coalescer
+                    .next_due()
+                    .map_or(COALESCE_TIMER_IDLE, |due| {
+                        due.saturating_duration_since(Instant::now())
+                    })
+                    .min(COALESCE_TIMER_IDLE);
+                if !wait.is_zero() {
+                    coalescer = hub.coalesce_wakeup.wait_timeout(coalescer, wait).unwrap().0;
                 }
-            });
+                coalescer.drain_due(Instant::now())
+            };
+            for event in due {
+                hub.dispatch(event);
+            }
         }
     }
 
     fn dispatch(&self, event: AppSwitchEvent) {
+        self.deliver(event, true);
+    }
+
+    /// Filter, fuse and enrich an event, then hand it to the listeners;
+    /// `ax_title_fallback` allows asking the app for its window title
+    fn deliver(&self, event: AppSwitchEvent, ax_title_fallback: bool) {
+        if self.exclusion.lock().unwrap().excludes(&event) {
+            return;
+        }
+        if !self.confidence.lock().unwrap().admit(&event) {
+            return;
+        }
+        if !self.navigation.lock().unwrap().admit(&event) {
+            return;
+        }
+        // Keep following the frontmost app while paused so resume knows it
+        if event.event_type.is_foreground() {
+            *self.last_foreground.lock().unwrap() = Some(event.app_info.clone());
+        }
+        if self.paused.load(Ordering::SeqCst) {
+            return;
+        }
+        let at = event.timestamp;
+        let admitted = self.warmup.lock().unwrap().admit(event, at);
+        for event in admitted {
+            self.fuse_and_notify(event, ax_title_fallback);
+        }
+    }
+
+    /// Release the events held during the startup warm-up
+    fn end_warmup(&self, ax_title_fallback: bool) {
+        let held = self.warmup.lock().unwrap().finish();
+        for event in held {
+            self.fuse_and_notify(event, ax_title_fallback);
+        }
+    }
+
+    fn fuse_and_notify(&self, event: AppSwitchEvent, ax_title_fallback: bool) {
         // Build a richer title for Human/Research by fusing from multiple sources
         let mut fused = event;
+        let enhanced_enabled = self.enhanced_enabled.load(Ordering::SeqCst);
+        if !enhanced_enabled {
+            fused.enhanced = None;
+        }
         if fused
             .workspace
             .as_ref()
@@ -102,8 +209,11 @@ impl FusionHub {
                         }
                     }
                 }
-                // Final fallback: AX focused window title (covers Electron apps like Cursor)
-                if fused
+                // Final fallback: AX focused window title (covers Electron apps like Cursor);
+                // the enhanced switcher probed the app when it built the event
+                if ax_title_fallback
+                    && self.ax_availability.is_available(fused.app_info.match_key())
+                    && fused
                     .workspace
                     .as_ref()
                     .and_then(|w| w.focused_title.clone())
@@ -130,9 +240,139 @@ impl FusionHub {
                 }
             }
         }
+        self.ax_availability.annotate(&mut fused);
+        self.menu_context.annotate(&mut fused);
+        if let Some(enh) = &mut fused.enhanced {
+            for extractor in &*self.context_extractors.lock().unwrap() {
+                if extractor.applies_to(fused.app_info.match_key()) {
+                    enh.extracted_context
+                        .extend(extractor.extract_context(&fused.app_info));
+                }
+            }
+        }
+        if !self.significant_change.lock().unwrap().admit(&fused) {
+            return;
+        }
+        self.enrichment.lock().unwrap().apply(&mut fused);
         for l in &mut *self.listeners.lock().unwrap() {
             l.on_app_switch(&fused);
         }
+        if enhanced_enabled {
+            for l in &mut *self.enhanced_listeners.lock().unwrap() {
+                l.on_app_switch(&fused);
+            }
+        }
+    }
+
+    fn pause(&self, stop_enhanced: bool) {
+        if self.paused.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        if stop_enhanced && self.enhanced_enabled.swap(false, Ordering::SeqCst) {
+            self.paused_enhanced.store(true, Ordering::SeqCst);
+        }
+        for l in &mut *self.listeners.lock().unwrap() {
+            l.on_monitoring_paused();
+        }
+        for l in &mut *self.enhanced_listeners.lock().unwrap() {
+            l.on_monitoring_paused();
+        }
+    }
+
+    /// Close open periods before the system sleeps, then report the sleep
+    /// against the frontmost app
+    fn system_sleep(&self, at: DateTime<Utc>) {
+        {
+            let mut asleep_since = self.asleep_since.lock().unwrap();
+            if asleep_since.is_some() {
+                return;
+            }
+            *asleep_since = Some(at);
+        }
+        for l in &mut *self.listeners.lock().unwrap() {
+            l.on_system_sleep();
+        }
+        for l in &mut *self.enhanced_listeners.lock().unwrap() {
+            l.on_system_sleep();
+        }
+        let current = self.last_foreground.lock().unwrap().clone();
+        if let Some(app) = current {
+            self.dispatch(AppSwitchEvent::new(AppSwitchType::Sleep, app));
+        }
+    }
+
+    /// Report the sleep interval, then start fresh with a wake event for
+    /// the frontmost app
+    fn system_wake(&self, at: DateTime<Utc>) {
+        let Some(start) = self.asleep_since.lock().unwrap().take() else {
+            return;
+        };
+        let asleep = SleepPeriod {
+            start,
+            end: at.max(start),
+        };
+        for l in &mut *self.listeners.lock().unwrap() {
+            l.on_system_wake(&asleep);
+        }
+        for l in &mut *self.enhanced_listeners.lock().unwrap() {
+            l.on_system_wake(&asleep);
+        }
+        let current = self.last_foreground.lock().unwrap().clone();
+        if let Some(app) = current {
+            self.dispatch(AppSwitchEvent::new(AppSwitchType::Wake, app));
+        }
+    }
+
+    /// Deliver events still being coalesced, then let listeners close open
+    /// periods and write their summaries
+    fn stopped(&self) {
+        let pending = self.coalescer.lock().unwrap().drain_all();
+        for event in pending {
+            self.dispatch(event);
+        }
+        self.end_warmup(true);
+        for l in &mut *self.listeners.lock().unwrap() {
+            l.on_monitoring_stopped();
+        }
+        for l in &mut *self.enhanced_listeners.lock().unwrap() {
+            l.on_monitoring_stopped();
+        }
+    }
+
+    fn resume(&self) {
+        if !self.paused.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        if self.paused_enhanced.swap(false, Ordering::SeqCst) {
+            self.enhanced_enabled.store(true, Ordering::SeqCst);
+        }
+        let current = self.last_foreground.lock().unwrap().clone();
+        if let Some(app) = current {
+            self.dispatch(AppSwitchEvent::new(AppSwitchType::Foreground, app));
+        }
+    }
+}
+
+/// Handle for pausing and resuming a switcher from another thread,
+/// e.g. a toggle in the Flutter UI
+#[derive(Clone)]
+pub struct PauseControl {
+    hub: Arc<FusionHub>,
+}
+
+impl PauseControl {
+    /// See `AppSwitcher::pause`
+    pub fn pause(&self, stop_enhanced: bool) {
+        self.hub.pause(stop_enhanced);
+    }
+
+    /// See `AppSwitcher::resume`
+    pub fn resume(&self) {
+        self.hub.resume();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.hub.paused.load(Ordering::SeqCst)
     }
 }
 
@@ -142,6 +382,7 @@ pub struct AppSwitcher {
     enhanced: Option<EnhancedAppSwitcher>,
     listeners: Arc<Mutex<Vec<Box<dyn AppSwitchListener>>>>,
     hub: Arc<FusionHub>,
+    startup_warmup: Duration,
 }
 
 impl AppSwitcher {
@@ -150,9 +391,12 @@ impl AppSwitcher {
         let hub = FusionHub::new(listeners.clone());
         Self {
             workspace: WorkspaceAppMonitor::new(),
-            enhanced: Some(EnhancedAppSwitcher::new()),
+            enhanced: Some(
+                EnhancedAppSwitcher::new().with_ax_availability(hub.ax_availability.clone()),
+            ),
             listeners,
             hub,
+            startup_warmup: DEFAULT_STARTUP_WARMUP,
         }
     }
 
@@ -160,7 +404,148 @@ impl AppSwitcher {
         self.listeners.lock().unwrap().push(Box::new(listener));
     }
 
+    /// Add an already boxed listener, e.g. one built from configuration
+    pub fn add_boxed_listener(&mut self, listener: Box<dyn AppSwitchListener>) {
+        self.listeners.lock().unwrap().push(listener);
+    }
+
+    /// Add a listener that is paused while enhanced extraction is disabled
+    pub fn add_enhanced_listener<T: AppSwitchListener + 'static>(&mut self, listener: T) {
+        self.hub
+            .enhanced_listeners
+            .lock()
+            .unwrap()
+            .push(Box::new(listener));
+    }
+
+    /// Turn enhanced extraction on or off without restarting
+    ///
+    /// While disabled, events carry no `enhanced` summary, the enhanced
+    /// source's reports are dropped (it keeps sampling, so re-enabling needs
+    /// no restart), and enhanced listeners are not called.
+    pub fn set_enhanced_enabled(&self, enabled: bool) {
+        self.hub.enhanced_enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn is_enhanced_enabled(&self) -> bool {
+        self.hub.enhanced_enabled.load(Ordering::SeqCst)
+    }
+
+    /// Add a transform applied to every event before it reaches any listener
+    pub fn add_enrichment_stage<T: EnrichmentStage + 'static>(&mut self, stage: T) {
+        self.hub.enrichment.lock().unwrap().add_stage(Box::new(stage));
+    }
+
+    /// Add an extractor whose keys land in `EnhancedSummary::extracted_context`
+    ///
+    /// Extractors run in registration order on every event that carries an
+    /// enhanced summary, after the workspace and enhanced sources are fused
+    /// and before enrichment stages and listeners. The built-in
+    /// `AccessibilityContextExtractor` is a listener, so it always runs after
+    /// them; later extractors overwrite keys set by earlier ones.
+    pub fn add_context_extractor(&mut self, extractor: Box<dyn ContextExtractor>) {
+        self.hub.context_extractors.lock().unwrap().push(extractor);
+    }
+
+    /// How long to wait for further updates to an app before emitting its event
+    pub fn set_coalesce_window(&mut self, window: Duration) {
+        self.hub.coalescer.lock().unwrap().set_window(window);
+    }
+
+    /// Drop switches to the given processes before any listener sees them
+    pub fn set_self_exclusion(&mut self, exclusion: SelfExclusion) {
+        *self.hub.exclusion.lock().unwrap() = exclusion;
+    }
+
+    /// Drop (or divert) events whose confidence is below the filter's threshold
+    pub fn set_confidence_filter(&mut self, filter: ConfidenceFilter) {
+        *self.hub.confidence.lock().unwrap() = filter;
+    }
+
+    /// Decide which URL changes within the app in focus are emitted
+    pub fn set_navigation_filter(&mut self, filter: NavigationFilter) {
+        *self.hub.navigation.lock().unwrap() = filter;
+    }
+
+    /// Decide whether foreground events for the app in focus are emitted
+    /// when their context is unchanged
+    pub fn set_significant_change_filter(&mut self, filter: SignificantChangeFilter) {
+        *self.hub.significant_change.lock().unwrap() = filter;
+    }
+
+    /// Hold the first foreground event for `warmup` after monitoring starts,
+    /// merging later updates for the same app into it; pair it with a
+    /// `resample_now` shortly after startup so the held event picks up the
+    /// context a warmed-up extraction provides
+    pub fn set_startup_warmup(&mut self, warmup: Duration) {
+        self.startup_warmup = warmup;
+    }
+
+    /// Per-app accessibility availability; pass it to the context extractor
+    /// so its denials flag the events of apps that refuse accessibility
+    pub fn ax_availability(&self) -> AxAvailability {
+        self.hub.ax_availability.clone()
+    }
+
+    /// The open menu path attached to events; pass it to a
+    /// `MenuContextObserver` to fill it
+    pub fn menu_context(&self) -> MenuContext {
+        self.hub.menu_context.clone()
+    }
+
+    /// Run a synthetic event through the same coalescing, exclusion,
+    /// confidence, extraction and enrichment steps as a reported one
+    ///
+    /// No macOS API is called: the event's `timestamp` drives the coalescing
+    /// window and the AX window-title fallback is skipped. Events still held
+    /// by the coalescer are delivered by `flush_injected`.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn inject_event(&self, event: AppSwitchEvent) {
+        let at = event.timestamp;
+        let due = {
+            let mut coalescer = self.hub.coalescer.lock().unwrap();
+            coalescer.push_at(event, at);
+            coalescer.drain_due(at)
+        };
+        for event in due {
+            self.hub.deliver(event, false);
+        }
+    }
+
+    /// Deliver every injected event the coalescer or the startup warm-up
+    /// is still holding
+    #[cfg(any(test, feature = "testing"))]
+    pub fn flush_injected(&self) {
+        let pending = self.hub.coalescer.lock().unwrap().drain_all();
+        for event in pending {
+            self.hub.deliver(event, false);
+        }
+        self.hub.end_warmup(false);
+    }
+
+    /// Start the startup warm-up at `at` instead of when monitoring starts
+    #[cfg(any(test, feature = "testing"))]
+    pub fn begin_startup_warmup_at(&self, at: Instant) {
+        self.hub.warmup.lock().unwrap().begin(at, self.startup_warmup);
+    }
+
     pub fn start_monitoring(&mut self, mtm: MainThreadMarker) -> Result<(), String> {
+        // The initial state is reported while the sources start, so the
+        // warm-up must already be running
+        if !self.startup_warmup.is_zero() {
+            self.hub
+                .warmup
+                .lock()
+                .unwrap()
+                .begin(Instant::now(), self.startup_warmup);
+            let hub = Arc::clone(&self.hub);
+            let warmup = self.startup_warmup;
+            std::thread::spawn(move || {
+                std::thread::sleep(warmup);
+                hub.end_warmup(true);
+            });
+        }
+
         // Register workspace adapter
         let adapter = WorkspaceAdapter {
             hub: Arc::clone(&self.hub),
@@ -172,6 +557,7 @@ impl AppSwitcher {
         if let Some(enh) = &mut self.enhanced {
             let adapter = EnhancedAdapter {
                 hub: Arc::clone(&self.hub),
+                applescript_cache: AppleScriptCache::default(),
             };
             enh.add_listener(adapter);
             let _ = enh.start_monitoring(mtm);
@@ -180,6 +566,33 @@ impl AppSwitcher {
         Ok(())
     }
 
+    /// Stop delivering events without stopping the sources
+    ///
+    /// Listeners get `on_monitoring_paused` and keep their state; trackers
+    /// close their open period so the paused interval counts as idle. With
+    /// `stop_enhanced`, enhanced extraction is also switched off until
+    /// `resume`.
+    pub fn pause(&self, stop_enhanced: bool) {
+        self.hub.pause(stop_enhanced);
+    }
+
+    /// Deliver events again, starting with a `Foreground` event for the
+    /// app that is frontmost now so listeners open a fresh period for it
+    pub fn resume(&self) {
+        self.hub.resume();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.hub.paused.load(Ordering::SeqCst)
+    }
+
+    /// A `Send` handle for pausing from another thread
+    pub fn pause_control(&self) -> PauseControl {
+        PauseControl {
+            hub: Arc::clone(&self.hub),
+        }
+    }
+
     /// Trigger a best-effort resample of the current foreground app and window context
     pub fn resample_now(&self) {
         self.workspace.resample_now();
@@ -188,11 +601,13 @@ impl AppSwitcher {
         }
     }
 
+    /// Stop the sources; listeners then get `on_monitoring_stopped`
     pub fn stop_monitoring(&mut self) {
         self.workspace.stop_monitoring();
         if let Some(enh) = &mut self.enhanced {
             enh.stop_monitoring();
         }
+        self.hub.stopped();
     }
 
     pub fn current_app(&self) -> Option<AppInfo> {
@@ -255,6 +670,14 @@ impl WorkspaceAdapter {
             workspace: Some(workspace),
             enhanced: None,
             confidence: Some(evt.confidence_score),
+            windows: None,
+            diff: None,
+            previous_context: None,
+            session_id: None,
+            power: None,
+            screenshot_path: None,
+            input_source: None,
+            redacted: false,
         }
     }
 }
@@ -264,14 +687,32 @@ impl WorkspaceAppSwitchListener for WorkspaceAdapter {
         let basic = Self::to_basic_event(event);
         self.hub.emit_or_merge(basic);
     }
+
+    fn on_system_sleep(&mut self) {
+        self.hub.system_sleep(Utc::now());
+    }
+
+    fn on_system_wake(&mut self) {
+        self.hub.system_wake(Utc::now());
+    }
+
+    fn on_app_lifecycle(&mut self, event: &AppSwitchEvent) {
+        self.hub.emit_or_merge(event.clone());
+    }
 }
 
 struct EnhancedAdapter {
     hub: Arc<FusionHub>,
+    /// Browser URL/title reads, reused while the window title is unchanged
+    applescript_cache: AppleScriptCache,
 }
 
 impl EnhancedAdapter {
-    fn to_basic_event(evt: &EnhancedAppSwitchEvent) -> AppSwitchEvent {
+    fn to_basic_event(
+        evt: &EnhancedAppSwitchEvent,
+        applescript_cache: &mut AppleScriptCache,
+        ax_availability: &AxAvailability,
+    ) -> AppSwitchEvent {
         let app = AppInfo {
             name: evt.app_info.name.clone(),
             bundle_id: evt.app_info.bundle_id.clone(),
@@ -305,15 +746,41 @@ impl EnhancedAdapter {
             }
             crate::core::app_switcher_enhanced::AppSwitchType::Hide => AppSwitchType::Hide,
             crate::core::app_switcher_enhanced::AppSwitchType::Unhide => AppSwitchType::Unhide,
-            _ => AppSwitchType::Foreground,
+            // Only reported when the frontmost app changed across the
+            // transition; listeners treat them as foreground switches
+            crate::core::app_switcher_enhanced::AppSwitchType::SpaceChange => {
+                AppSwitchType::SpaceChange
+            }
+            crate::core::app_switcher_enhanced::AppSwitchType::SessionChange => {
+                AppSwitchType::SessionChange
+            }
+            crate::core::app_switcher_enhanced::AppSwitchType::WakeFromSleep => {
+                AppSwitchType::Wake
+            }
         };
         // Best-effort enrichment for browsers via AppleScript (non-AX)
-        let browser_url = best_effort_browser_url(&evt.app_info.bundle_id);
-        let browser_title = if browser_url.is_some() {
-            best_effort_browser_title(&evt.app_info.bundle_id)
-        } else {
-            None
-        };
+        let BrowserPage {
+            url: browser_url,
+            title: browser_title,
+            ..
+        } = best_effort_browser_page(
+            applescript_cache,
+            &evt.app_info.bundle_id,
+            evt.app_info.pid,
+            ax_availability.is_available(app.match_key()),
+            evt.app_info
+                .frontmost_window
+                .as_ref()
+                .and_then(|w| w.title.as_deref()),
+        );
+
+        let window_title = evt
+            .app_info
+            .frontmost_window
+            .as_ref()
+            .and_then(|w| w.title.as_deref());
+        let browser_profile = window_title
+            .and_then(|title| browser_profile_from_title(title, browser_title.as_deref()));
 
         let enhanced = EnhancedSummary {
             activation_count: evt.app_info.activation_count,
@@ -339,6 +806,15 @@ impl EnhancedAdapter {
             space_type: evt.desktop_state.active_space_type.clone(),
             space_name: evt.desktop_state.active_space_name.clone(),
             space_label: evt.desktop_state.active_space_label.clone(),
+            is_fullscreen: evt.app_info.is_fullscreen,
+            window_on_active_space: evt.app_info.window_on_active_space,
+            has_floating_window: evt.app_info.has_floating_window,
+            document_edited: evt.app_info.document_edited,
+            app_locale: evt.app_info.app_locale.clone(),
+            // Set by the hub, which knows which apps refuse accessibility
+            ax_available: None,
+            menu_context: None,
+            domain: browser_url.as_deref().and_then(site_domain),
             url: browser_url,
             tab_title: browser_title.or_else(|| {
                 evt.app_info
@@ -346,6 +822,8 @@ impl EnhancedAdapter {
                     .as_ref()
                     .and_then(|w| w.title.clone())
             }),
+            browser_profile,
+            extracted_context: HashMap::new(),
         };
         AppSwitchEvent {
             timestamp: evt.timestamp,
@@ -355,57 +833,351 @@ impl EnhancedAdapter {
             workspace: None,
             enhanced: Some(enhanced),
             confidence: Some(evt.confidence_score),
+            windows: None,
+            diff: None,
+            previous_context: None,
+            session_id: None,
+            power: None,
+            screenshot_path: None,
+            input_source: None,
+            redacted: false,
         }
     }
 }
 
 impl EnhancedAppSwitchListener for EnhancedAdapter {
     fn on_app_switch(&mut self, event: &EnhancedAppSwitchEvent) {
-        let basic = Self::to_basic_event(event);
+        // The workspace source still reports the switch
+        if !self.hub.enhanced_enabled.load(Ordering::SeqCst) {
+            return;
+        }
+        let basic = Self::to_basic_event(
+            event,
+            &mut self.applescript_cache,
+            &self.hub.ax_availability,
+        );
         self.hub.emit_or_merge(basic);
     }
 }
 
 // --- Local helpers ----------------------------------------------------------
 
-fn best_effort_browser_url(bundle_id: &str) -> Option<String> {
-    let script = if bundle_id.contains("com.google.Chrome") {
-        Some(r#"tell application "Google Chrome" to get URL of active tab of front window"#)
-    } else if bundle_id.contains("com.apple.SafariTechnologyPreview") {
-        Some(r#"tell application "Safari Technology Preview" to get URL of front document"#)
-    } else if bundle_id.contains("com.apple.Safari") {
-        Some(r#"tell application "Safari" to get URL of front document"#)
-    } else {
-        None
-    }?;
-    if let Ok(out) = Command::new("osascript").arg("-e").arg(script).output() {
-        if out.status.success() {
-            let s = String::from_utf8_lossy(&out.stdout).trim().to_string();
-            if !s.is_empty() {
-                return Some(s);
-            }
+/// URL and tab title for browsers, keyed in the cache by the focused window
+/// title (CGWindow's when known, else AX's); AX is left alone unless `ax`
+fn best_effort_browser_page(
+    cache: &mut AppleScriptCache,
+    bundle_id: &str,
+    pid: i32,
+    ax: bool,
+    window_title: Option<&str>,
+) -> BrowserPage {
+    let Some(strategy) = BrowserStrategy::for_bundle_id(bundle_id) else {
+        return BrowserPage::default();
+    };
+    let window_title = window_title
+        .map(str::to_string)
+        .or_else(|| ax.then(|| ax_focused_window_title_quick(pid)).flatten());
+    cache.get_or_fetch(bundle_id, window_title.as_deref(), Instant::now(), || {
+        let url = if ax {
+            strategy.url(pid)
+        } else {
+            strategy.applescript_url()
+        };
+        let title = if url.is_some() { strategy.title() } else { None };
+        BrowserPage {
+            url,
+            title,
+            tab_count: None,
         }
-    }
-    None
+    })
 }
 
-fn best_effort_browser_title(bundle_id: &str) -> Option<String> {
-    let script = if bundle_id.contains("com.google.Chrome") {
-        Some(r#"tell application "Google Chrome" to get title of active tab of front window"#)
-    } else if bundle_id.contains("com.apple.SafariTechnologyPreview") {
-        Some(r#"tell application "Safari Technology Preview" to get name of front document"#)
-    } else if bundle_id.contains("com.apple.Safari") {
-        Some(r#"tell application "Safari" to get name of front document"#)
-    } else {
-        None
-    }?;
-    if let Ok(out) = Command::new("osascript").arg("-e").arg(script).output() {
-        if out.status.success() {
-            let s = String::from_utf8_lossy(&out.stdout).trim().to_string();
-            if !s.is_empty() {
-                return Some(s);
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::significant_change::ResamplePolicy;
+    use crate::extractors::ContextValue;
+
+    /// Tags one bundle with a fixed key
+    struct TaggingExtractor;
+
+    impl ContextExtractor for TaggingExtractor {
+        fn extract_context(&self, app_info: &AppInfo) -> HashMap<String, ContextValue> {
+            HashMap::from([(
+                "lab.notebook".to_string(),
+                ContextValue::Text(format!("{} entry", app_info.name)),
+            )])
+        }
+
+        fn applies_to(&self, bundle_id: &str) -> bool {
+            bundle_id == "org.example.Notebook"
+        }
+
+        fn name(&self) -> &str {
+            "tagging"
         }
     }
-    None
+
+    struct Recorder(Arc<Mutex<Vec<AppSwitchEvent>>>);
+
+    impl AppSwitchListener for Recorder {
+        fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    struct PauseCounter(Arc<Mutex<usize>>);
+
+    impl AppSwitchListener for PauseCounter {
+        fn on_app_switch(&mut self, _event: &AppSwitchEvent) {}
+
+        fn on_monitoring_paused(&mut self) {
+            *self.0.lock().unwrap() += 1;
+        }
+    }
+
+    /// Writes one comma-separated row per event: type, bundle, title and
+    /// the extracted notebook key
+    struct CsvSink(Arc<Mutex<Vec<String>>>);
+
+    impl AppSwitchListener for CsvSink {
+        fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+            let title = event.workspace.as_ref().and_then(|w| w.focused_title.clone());
+            let notebook = event
+                .enhanced
+                .as_ref()
+                .and_then(|e| e.extracted_context.get("lab.notebook"))
+                .map(|value| match value {
+                    ContextValue::Text(text) => text.clone(),
+                    other => format!("{:?}", other),
+                });
+            self.0.lock().unwrap().push(format!(
+                "{:?},{},{},{}",
+                event.event_type,
+                event.app_info.bundle_id,
+                title.unwrap_or_default(),
+                notebook.unwrap_or_default()
+            ));
+        }
+    }
+
+    fn event(bundle_id: &str) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Notebook".to_string(), bundle_id.to_string(), 42),
+        );
+        event.workspace = Some(WorkspaceSummary {
+            window_count: 1,
+            focused_title: Some("Lab".to_string()),
+            total_screen_coverage: None,
+            is_fullscreen: None,
+            is_minimized: None,
+            tab_titles: Vec::new(),
+            active_file_paths: Vec::new(),
+            primary_url: None,
+        });
+        event.enhanced = Some(EnhancedSummary::default());
+        event
+    }
+
+    fn event_with_enhanced() -> AppSwitchEvent {
+        let mut event = event("com.apple.Safari");
+        event.enhanced.as_mut().unwrap().url = Some("https://example.com".to_string());
+        event
+    }
+
+    #[test]
+    fn test_toggling_enhanced_extraction() {
+        let mut switcher = AppSwitcher::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let enhanced_seen = Arc::new(Mutex::new(Vec::new()));
+        switcher.add_listener(Recorder(seen.clone()));
+        switcher.add_enhanced_listener(Recorder(enhanced_seen.clone()));
+
+        switcher.hub.dispatch(event_with_enhanced());
+        switcher.set_enhanced_enabled(false);
+        assert!(!switcher.is_enhanced_enabled());
+        switcher.hub.dispatch(event_with_enhanced());
+        switcher.set_enhanced_enabled(true);
+        switcher.hub.dispatch(event_with_enhanced());
+
+        let has_enhanced = |events: &Vec<AppSwitchEvent>| -> Vec<bool> {
+            events.iter().map(|e| e.enhanced.is_some()).collect()
+        };
+        assert_eq!(has_enhanced(&seen.lock().unwrap()), vec![true, false, true]);
+        assert_eq!(has_enhanced(&enhanced_seen.lock().unwrap()), vec![true, true]);
+    }
+
+    #[test]
+    fn test_custom_extractor_keys_reach_listeners() {
+        let mut switcher = AppSwitcher::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        switcher.add_listener(Recorder(seen.clone()));
+        switcher.add_context_extractor(Box::new(TaggingExtractor));
+
+        switcher.hub.dispatch(event("org.example.Notebook"));
+        switcher.hub.dispatch(event("com.apple.Safari"));
+
+        let seen = seen.lock().unwrap();
+        let tagged = &seen[0].enhanced.as_ref().unwrap().extracted_context;
+        assert!(matches!(
+            tagged.get("lab.notebook"),
+            Some(ContextValue::Text(text)) if text == "Notebook entry"
+        ));
+        assert!(seen[1].enhanced.as_ref().unwrap().extracted_context.is_empty());
+    }
+
+    #[test]
+    fn test_no_events_delivered_while_paused() {
+        let mut switcher = AppSwitcher::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let pauses = Arc::new(Mutex::new(0));
+        switcher.add_listener(Recorder(seen.clone()));
+        switcher.add_listener(PauseCounter(pauses.clone()));
+
+        switcher.hub.dispatch(event("org.example.Notebook"));
+        switcher.pause(true);
+        switcher.pause(true);
+        assert!(switcher.is_paused());
+        assert!(!switcher.is_enhanced_enabled());
+
+        switcher.hub.dispatch(event("com.apple.Safari"));
+        switcher.hub.dispatch(event("com.apple.Terminal"));
+        assert_eq!(seen.lock().unwrap().len(), 1);
+        assert_eq!(*pauses.lock().unwrap(), 1);
+
+        // Resuming announces the app that came forward during the pause
+        switcher.pause_control().resume();
+        assert!(!switcher.is_paused());
+        assert!(switcher.is_enhanced_enabled());
+        let bundles: Vec<String> = seen
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.app_info.bundle_id.clone())
+            .collect();
+        assert_eq!(bundles, vec!["org.example.Notebook", "com.apple.Terminal"]);
+    }
+
+    #[test]
+    fn test_injected_events_reach_a_csv_sink() {
+        let mut switcher = AppSwitcher::new();
+        let rows = Arc::new(Mutex::new(Vec::new()));
+        switcher.add_listener(CsvSink(rows.clone()));
+        switcher.add_context_extractor(Box::new(TaggingExtractor));
+        switcher.set_self_exclusion(SelfExclusion::new([], ["com.example.tracker".to_string()]));
+
+        let start = Instant::now();
+        let at = |bundle_id: &str, pid: i32, title: Option<&str>, millis: u64| {
+            let mut event = event(bundle_id);
+            event.app_info.pid = pid;
+            event.workspace.as_mut().unwrap().focused_title = title.map(str::to_string);
+            event.timestamp = start + Duration::from_millis(millis);
+            event
+        };
+
+        switcher.inject_event(at("org.example.Notebook", 42, Some("Lab"), 0));
+        // A title update inside the coalescing window is merged, not repeated
+        switcher.inject_event(at("org.example.Notebook", 42, Some("Lab draft"), 50));
+        switcher.inject_event(at("com.example.tracker", 9, Some("Tracker"), 1_000));
+        switcher.inject_event(at("com.apple.Safari", 7, None, 2_000));
+        assert_eq!(rows.lock().unwrap().len(), 1);
+        switcher.flush_injected();
+
+        assert_eq!(
+            *rows.lock().unwrap(),
+            vec![
+                "Foreground,org.example.Notebook,Lab draft,Notebook entry",
+                "Foreground,com.apple.Safari,,",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_startup_warmup_holds_first_event_for_reextraction() {
+        let urls_seen = |warmup: Duration| {
+            let mut switcher = AppSwitcher::new();
+            let seen = Arc::new(Mutex::new(Vec::new()));
+            switcher.add_listener(Recorder(seen.clone()));
+            switcher.set_coalesce_window(Duration::ZERO);
+            switcher.set_startup_warmup(warmup);
+
+            let start = Instant::now();
+            switcher.begin_startup_warmup_at(start);
+            let at = |bundle_id: &str, pid: i32, url: Option<&str>, millis: u64| {
+                let mut event = event(bundle_id);
+                event.app_info.pid = pid;
+                event.enhanced.as_mut().unwrap().url = url.map(str::to_string);
+                event.timestamp = start + Duration::from_millis(millis);
+                event
+            };
+
+            // The initial extraction comes back empty; the re-extraction
+            // requested after startup finds the page
+            switcher.inject_event(at("org.example.Notebook", 42, None, 0));
+            switcher.inject_event(at(
+                "org.example.Notebook",
+                42,
+                Some("https://lab.example.org/entry/7"),
+                500,
+            ));
+            switcher.inject_event(at("com.apple.Safari", 7, None, 3_000));
+            switcher.flush_injected();
+
+            let seen = seen.lock().unwrap();
+            seen.iter()
+                .map(|e| {
+                    let url = e.enhanced.as_ref().and_then(|enh| enh.url.clone());
+                    (e.app_info.bundle_id.clone(), url, e.timestamp - start)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let warmed = urls_seen(Duration::from_secs(2));
+        assert_eq!(warmed.len(), 2);
+        assert_eq!(warmed[0].0, "org.example.Notebook");
+        assert_eq!(
+            warmed[0].1.as_deref(),
+            Some("https://lab.example.org/entry/7")
+        );
+        // The merged event keeps the time the app was first seen
+        assert_eq!(warmed[0].2, Duration::ZERO);
+        assert_eq!(warmed[1].0, "com.apple.Safari");
+
+        // Without a warm-up the first event goes out context-poor
+        let cold = urls_seen(Duration::ZERO);
+        assert_eq!(cold.len(), 3);
+        assert_eq!(cold[0].1, None);
+    }
+
+    #[test]
+    fn test_unchanged_resamples_are_absorbed() {
+        let mut switcher = AppSwitcher::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        switcher.add_listener(Recorder(seen.clone()));
+        switcher.add_context_extractor(Box::new(TaggingExtractor));
+        switcher.set_significant_change_filter(SignificantChangeFilter::new(
+            ResamplePolicy::SignificantChange,
+        ));
+
+        for _ in 0..4 {
+            switcher.hub.dispatch(event("org.example.Notebook"));
+        }
+        assert_eq!(seen.lock().unwrap().len(), 1);
+
+        let mut renamed = event("org.example.Notebook");
+        renamed.workspace.as_mut().unwrap().focused_title = Some("Lab 2".to_string());
+        switcher.hub.dispatch(renamed.clone());
+        switcher.hub.dispatch(renamed);
+        let titles: Vec<Option<String>> = seen
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.workspace.as_ref().unwrap().focused_title.clone())
+            .collect();
+        assert_eq!(
+            titles,
+            vec![Some("Lab".to_string()), Some("Lab 2".to_string())]
+        );
+    }
 }
@@ -16,14 +16,22 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime};
 
 use objc2::rc::Retained;
-use objc2::{define_class, msg_send, sel, AnyThread, MainThreadMarker, Message};
+use objc2::{define_class, msg_send, sel, AnyThread, MainThreadMarker};
 use objc2_app_kit::{NSImage, NSRunningApplication, NSWorkspace};
 use objc2_foundation::{
     NSNotification, NSNotificationCenter, NSObject, NSObjectProtocol, NSString,
 };
 
 // Import core-foundation traits
-use crate::core::spaces::{query_spaces, SpacesSnapshot};
+use crate::core::accessibility::{
+    ax_focused_window_document_edited_quick, ax_focused_window_fullscreen_quick,
+    ax_focused_window_id_quick, ax_window_subroles_quick,
+};
+use crate::core::app_lifecycle::{app_info_from_running_app, running_app_from_user_info};
+use crate::core::app_locale::{app_locale, SystemLocaleSource};
+use crate::core::ax_availability::AxAvailability;
+use crate::core::process_cache::{launch_instant, ProcessCache, ProcessIdentity};
+use crate::core::spaces::{is_window_on_active_space, query_spaces};
 use core_foundation::array::CFArray;
 use core_foundation::base::{CFType, FromVoid, TCFType, ToVoid};
 use core_foundation::boolean::CFBoolean;
@@ -119,6 +127,48 @@ pub struct WindowBounds {
     pub height: f64,
 }
 
+/// Slack for menu-bar/notch rounding when comparing window and display rects
+const FULLSCREEN_TOLERANCE: f64 = 2.0;
+
+/// Whether a window covers the whole display it is on
+pub fn bounds_fill_display(window: &WindowBounds, display: &CGDisplayRect) -> bool {
+    (window.x - display.origin.x).abs() <= FULLSCREEN_TOLERANCE
+        && (window.y - display.origin.y).abs() <= FULLSCREEN_TOLERANCE
+        && (window.width - display.size.width).abs() <= FULLSCREEN_TOLERANCE
+        && (window.height - display.size.height).abs() <= FULLSCREEN_TOLERANCE
+}
+
+/// Window levels that keep a window above normal ones: floating panels
+/// (kCGFloatingWindowLevel, 3) and utility windows (kCGUtilityWindowLevel,
+/// 19). Modal panels (8) and the Dock (20) sit above normal windows too but
+/// do not float over the user's work.
+const FLOATING_LEVELS: &[i32] = &[3, 19];
+
+/// Subroles AppKit gives panels that stay above other windows
+const FLOATING_SUBROLES: &[&str] = &["AXFloatingWindow", "AXSystemFloatingWindow"];
+
+/// Whether a window floats above normal windows, such as a video
+/// Picture-in-Picture window or a meeting app's floating toolbar
+pub fn is_floating_window(layer: i32, subrole: Option<&str>) -> bool {
+    FLOATING_LEVELS.contains(&layer) || subrole.is_some_and(|s| FLOATING_SUBROLES.contains(&s))
+}
+
+/// Whether any on-screen window of an app floats; `None` without windows
+///
+/// CGWindow levels and AX subroles are listed separately and cannot be
+/// matched up reliably, so either source is enough.
+pub fn has_floating_window(windows: &[WindowInfo], subroles: &[String]) -> Option<bool> {
+    if windows.is_empty() && subroles.is_empty() {
+        return None;
+    }
+    Some(
+        windows
+            .iter()
+            .any(|w| w.is_onscreen && is_floating_window(w.layer, None))
+            || subroles.iter().any(|s| is_floating_window(0, Some(s))),
+    )
+}
+
 /// Process information from system
 #[derive(Debug, Clone)]
 pub struct ProcessInfo {
@@ -181,6 +231,17 @@ pub struct ExtendedAppInfo {
 
     // Display info for front window
     pub front_window_display_id: Option<u32>,
+    pub is_fullscreen: Option<bool>,
+    /// AX's focused window is on a space currently shown (not a background
+    /// Space); `None` when its window id or spaces are unknown
+    pub window_on_active_space: Option<bool>,
+    /// A window floats above others (Picture-in-Picture, floating toolbar)
+    pub has_floating_window: Option<bool>,
+    /// Focused window has unsaved changes (AXIsDocumentEdited or a dirty dot)
+    pub document_edited: Option<bool>,
+
+    // UI language the app runs in (per-app override, else system)
+    pub app_locale: Option<String>,
 
     // Process info
     pub process_info: Option<ProcessInfo>,
@@ -323,10 +384,62 @@ struct EnhancedState {
     notification_center: Option<Retained<NSNotificationCenter>>,
     activation_counts: HashMap<String, u32>,
     last_event_time: Instant,
-    system_info: System,
+    resource_sampler: ResourceSampler,
+    /// Apps that refuse accessibility; their per-switch AX reads are skipped
+    ax_availability: AxAvailability,
     desktop_state: DesktopState,
 }
 
+/// sysinfo-backed CPU/memory sampler that tolerates pid reuse
+///
+/// sysinfo derives CPU usage from the delta against the previous refresh of
+/// the same pid. When the pid now belongs to a different process that delta
+/// is meaningless, so the first sample after reuse reports zero CPU.
+struct ResourceSampler {
+    system: System,
+    sampled: ProcessCache<()>,
+}
+
+impl ResourceSampler {
+    fn new() -> Self {
+        Self {
+            system: System::new_all(),
+            sampled: ProcessCache::new(),
+        }
+    }
+
+    fn refresh_all(&mut self) {
+        self.system.refresh_all();
+    }
+
+    fn sample(&mut self, identity: ProcessIdentity) -> Option<ProcessInfo> {
+        let reused = self.sampled.is_stale(&identity);
+        self.sampled.insert(identity, ());
+
+        let pid = SysPid::from(identity.pid as usize);
+        self.system
+            .refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        self.system.process(pid).map(|proc| {
+            ProcessInfo {
+                cpu_usage: if reused { 0.0 } else { proc.cpu_usage() },
+                memory_bytes: proc.memory(),
+                virtual_memory_bytes: proc.virtual_memory(),
+                num_threads: 0, // Not available in sysinfo
+                start_time: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(proc.start_time())),
+                parent_pid: proc.parent().map(|p| p.as_u32() as i32),
+                environment: HashMap::new(), // Convert environment properly
+                command_line: proc
+                    .cmd()
+                    .iter()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .collect(),
+                executable_path: proc.exe().map(|p| p.to_path_buf()),
+                working_directory: proc.cwd().map(|p| p.to_path_buf()),
+            }
+        })
+    }
+}
+
 /// Enhanced app switcher with multi-layer monitoring
 pub struct EnhancedAppSwitcher {
     state: Arc<Mutex<EnhancedState>>,
@@ -346,7 +459,8 @@ impl EnhancedAppSwitcher {
             notification_center: None,
             activation_counts: HashMap::new(),
             last_event_time: Instant::now(),
-            system_info: System::new_all(),
+            resource_sampler: ResourceSampler::new(),
+            ax_availability: AxAvailability::default(),
             desktop_state: DesktopState {
                 active_space_id: None,
                 display_count: 0,
@@ -370,6 +484,13 @@ impl EnhancedAppSwitcher {
         Self { state }
     }
 
+    /// Share per-app availability, e.g. the hub's, so the AX reads made for
+    /// each switch record and respect denials
+    pub fn with_ax_availability(self, availability: AxAvailability) -> Self {
+        self.state.lock().unwrap().ax_availability = availability;
+        self
+    }
+
     pub fn add_listener<T: EnhancedAppSwitchListener + 'static>(&mut self, listener: T) {
         let mut state = self.state.lock().unwrap();
         state.listeners.push(Box::new(listener));
@@ -461,7 +582,12 @@ impl EnhancedAppSwitcher {
 
         // Seed with current frontmost app
         if let Some(frontmost) = unsafe { workspace.frontmostApplication() } {
-            let app_info = Self::extract_extended_app_info(&frontmost, &mut state.system_info);
+            let availability = state.ax_availability.clone();
+            let app_info = Self::extract_extended_app_info(
+                &frontmost,
+                &mut state.resource_sampler,
+                &availability,
+            );
             state.current_app = Some(app_info.clone());
 
             // Notify listeners of initial state
@@ -525,12 +651,16 @@ impl EnhancedAppSwitcher {
         ];
     }
 
-    fn extract_extended_app_info(app: &NSRunningApplication, sys: &mut System) -> ExtendedAppInfo {
+    fn extract_extended_app_info(
+        app: &NSRunningApplication,
+        sampler: &mut ResourceSampler,
+        availability: &AxAvailability,
+    ) -> ExtendedAppInfo {
         unsafe {
             let bundle_id = app
                 .bundleIdentifier()
                 .map(|id| id.to_string())
-                .unwrap_or_else(|| "unknown".to_string());
+                .unwrap_or_default();
 
             let name = app
                 .localizedName()
@@ -540,18 +670,22 @@ impl EnhancedAppSwitcher {
             let pid = app.processIdentifier();
 
             let bundle_url = app.bundleURL();
-            let path = bundle_url
+            let executable_url = app.executableURL();
+            let executable_path = executable_url
                 .as_ref()
                 .and_then(|url| url.path())
                 .map(|p| p.to_string());
 
-            let executable_url = app.executableURL();
-            let executable_path = executable_url
+            // Bare executables have no bundle; their path names them instead
+            let path = bundle_url
                 .as_ref()
                 .and_then(|url| url.path())
-                .map(|p| p.to_string());
+                .map(|p| p.to_string())
+                .or_else(|| executable_path.clone());
 
-            let launch_date = app.launchDate().map(|_| Instant::now());
+            let launch_date = app
+                .launchDate()
+                .and_then(|d| launch_instant(-d.timeIntervalSinceNow()));
 
             // Get icon
             let icon_data = app.icon().and_then(|icon| {
@@ -567,28 +701,37 @@ impl EnhancedAppSwitcher {
                 .as_ref()
                 .and_then(|w| Self::display_id_for_window(&w.bounds).map(|id| id));
 
-            // Get process info - using proper sysinfo API
-            sys.refresh_processes(ProcessesToUpdate::Some(&[SysPid::from(pid as usize)]), true);
-            let process_info = sys.process(SysPid::from(pid as usize)).map(|proc| {
-                ProcessInfo {
-                    cpu_usage: proc.cpu_usage(),
-                    memory_bytes: proc.memory(),
-                    virtual_memory_bytes: proc.virtual_memory(),
-                    num_threads: 0, // Not available in sysinfo
-                    start_time: Some(
-                        SystemTime::UNIX_EPOCH + Duration::from_secs(proc.start_time()),
-                    ),
-                    parent_pid: proc.parent().map(|p| p.as_u32() as i32),
-                    environment: HashMap::new(), // Convert environment properly
-                    command_line: proc
-                        .cmd()
-                        .iter()
-                        .map(|s| s.to_string_lossy().to_string())
-                        .collect(),
-                    executable_path: proc.exe().map(|p| p.to_path_buf()),
-                    working_directory: proc.cwd().map(|p| p.to_path_buf()),
-                }
-            });
+            // Apps refusing accessibility would fail each AX read below, so
+            // one probe decides whether they are made
+            let key = app_info_from_running_app(app).match_key().to_string();
+            let ax = availability.probe_app(&key, pid, Instant::now());
+
+            let is_fullscreen = Self::detect_fullscreen(
+                pid,
+                ax,
+                frontmost_window.as_ref(),
+                front_window_display_id,
+            );
+            // The on-screen windows listed above are on a shown space by
+            // definition; AX's focused window need not be
+            let window_on_active_space = ax
+                .then(|| ax_focused_window_id_quick(pid))
+                .flatten()
+                .and_then(is_window_on_active_space);
+            let subroles = if ax {
+                ax_window_subroles_quick(pid)
+            } else {
+                Vec::new()
+            };
+            let has_floating_window = has_floating_window(&windows, &subroles);
+            let document_edited = ax
+                .then(|| ax_focused_window_document_edited_quick(pid))
+                .flatten();
+
+            let app_locale = app_locale(&SystemLocaleSource, &bundle_id);
+
+            // Get process info, discarding CPU deltas across pid reuse
+            let process_info = sampler.sample(ProcessIdentity::new(pid, launch_date));
 
             // Get activation policy
             let activation_policy = if app.activationPolicy()
@@ -621,6 +764,11 @@ impl EnhancedAppSwitcher {
                 frontmost_window,
                 window_count,
                 front_window_display_id,
+                is_fullscreen,
+                window_on_active_space,
+                has_floating_window,
+                document_edited,
+                app_locale,
                 process_info,
                 bundle_version: None,
                 bundle_short_version: None,
@@ -787,6 +935,31 @@ impl EnhancedAppSwitcher {
         windows
     }
 
+    /// Whether the front window is in fullscreen
+    ///
+    /// AXFullScreen is authoritative whenever the focused window exposes it.
+    /// Several apps (games, video players, some Electron builds) do not, and
+    /// only for those is a front window covering its whole display treated as
+    /// fullscreen. An app that reports AXFullScreen false while covering the
+    /// display itself is taken at its word. `ax` is false when AX is not
+    /// read for the app.
+    fn detect_fullscreen(
+        pid: i32,
+        ax: bool,
+        front_window: Option<&WindowInfo>,
+        display_id: Option<u32>,
+    ) -> Option<bool> {
+        if let Some(fullscreen) = ax
+            .then(|| ax_focused_window_fullscreen_quick(pid))
+            .flatten()
+        {
+            return Some(fullscreen);
+        }
+        let window = front_window?;
+        let display = unsafe { CGDisplayBounds(display_id?) };
+        Some(bounds_fill_display(&window.bounds, &display))
+    }
+
     /// Determine which display contains the center of the given window bounds
     fn display_id_for_window(bounds: &WindowBounds) -> Option<u32> {
         unsafe {
@@ -893,7 +1066,7 @@ impl EnhancedAppSwitcher {
                 unsafe {
                     if let Some(global) = &GLOBAL_STATE {
                         let mut state = global.lock().unwrap();
-                        state.system_info.refresh_all();
+                        state.resource_sampler.refresh_all();
                         state.desktop_state = Self::capture_desktop_state();
 
                         // Clone listeners to avoid borrow issues
@@ -1030,9 +1203,11 @@ impl EnhancedWorkspaceObserver {
                 let app = Self::get_app_from_notification(notification);
                 if let Some(app) = app {
                     let mut state = global.lock().unwrap();
+                    let availability = state.ax_availability.clone();
                     let app_info = EnhancedAppSwitcher::extract_extended_app_info(
                         &app,
-                        &mut state.system_info,
+                        &mut state.resource_sampler,
+                        &availability,
                     );
                     let now = Instant::now();
 
@@ -1183,9 +1358,11 @@ impl EnhancedWorkspaceObserver {
                 if let Some(global) = &GLOBAL_STATE {
                     let mut state = global.lock().unwrap();
 
+                    let availability = state.ax_availability.clone();
                     let app_info = EnhancedAppSwitcher::extract_extended_app_info(
                         &frontmost,
-                        &mut state.system_info,
+                        &mut state.resource_sampler,
+                        &availability,
                     );
 
                     // Update activation count
@@ -1239,16 +1416,8 @@ impl EnhancedWorkspaceObserver {
     unsafe fn get_app_from_notification(
         notification: &NSNotification,
     ) -> Option<Retained<NSRunningApplication>> {
-        if let Some(user_info) = unsafe { notification.userInfo() } {
-            let key = NSString::from_str("NSWorkspaceApplicationKey");
-            if let Some(app_obj) = user_info.objectForKey(&key) {
-                // Convert AnyObject to NSRunningApplication properly
-                let app: Retained<NSRunningApplication> =
-                    unsafe { Retained::cast_unchecked(app_obj.retain()) };
-                return Some(app);
-            }
-        }
-        None
+        let user_info = unsafe { notification.userInfo() }?;
+        running_app_from_user_info(&user_info)
     }
 }
 
@@ -1313,3 +1482,92 @@ impl EnhancedAppSwitchListener for DebugListener {
         println!("  Locked: {}", state.screen_locked);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(x: f64, y: f64, width: f64, height: f64) -> CGDisplayRect {
+        CGDisplayRect {
+            origin: CGDisplayPoint { x, y },
+            size: CGDisplaySize { width, height },
+        }
+    }
+
+    #[test]
+    fn test_bounds_fill_display() {
+        let secondary = display(1512.0, 0.0, 2560.0, 1440.0);
+
+        let covering = WindowBounds {
+            x: 1512.0,
+            y: 0.0,
+            width: 2560.0,
+            height: 1440.0,
+        };
+        assert!(bounds_fill_display(&covering, &secondary));
+
+        // Maximized (zoomed) windows stop below the menu bar
+        let zoomed = WindowBounds {
+            x: 1512.0,
+            y: 25.0,
+            width: 2560.0,
+            height: 1415.0,
+        };
+        assert!(!bounds_fill_display(&zoomed, &secondary));
+
+        // Same size but on another display
+        let elsewhere = WindowBounds {
+            x: 0.0,
+            y: 0.0,
+            width: 2560.0,
+            height: 1440.0,
+        };
+        assert!(!bounds_fill_display(&elsewhere, &secondary));
+    }
+
+    fn window(layer: i32, is_onscreen: bool) -> WindowInfo {
+        WindowInfo {
+            window_id: 1,
+            title: None,
+            bounds: WindowBounds {
+                x: 0.0,
+                y: 0.0,
+                width: 480.0,
+                height: 270.0,
+            },
+            layer,
+            alpha: 1.0,
+            memory_usage: None,
+            sharing_state: None,
+            backing_store_type: None,
+            is_onscreen,
+        }
+    }
+
+    #[test]
+    fn test_floating_window_detection() {
+        // Floating panel level (NSFloatingWindowLevel) or subrole
+        assert!(is_floating_window(3, None));
+        assert!(is_floating_window(0, Some("AXFloatingWindow")));
+        assert!(is_floating_window(0, Some("AXSystemFloatingWindow")));
+        assert!(!is_floating_window(0, Some("AXStandardWindow")));
+        assert!(is_floating_window(19, None));
+        // Modal panels, the Dock, and the menu bar and above are not floating
+        assert!(!is_floating_window(8, None));
+        assert!(!is_floating_window(20, None));
+        assert!(!is_floating_window(25, None));
+
+        let pip = [window(0, true), window(3, true)];
+        assert_eq!(has_floating_window(&pip, &[]), Some(true));
+        assert_eq!(
+            has_floating_window(&[window(0, true)], &["AXFloatingWindow".to_string()]),
+            Some(true)
+        );
+        // A floating window that is not on screen does not count
+        assert_eq!(
+            has_floating_window(&[window(0, true), window(3, false)], &[]),
+            Some(false)
+        );
+        assert_eq!(has_floating_window(&[], &[]), None);
+    }
+}
@@ -826,7 +826,7 @@ impl EnhancedAppSwitcher {
             let bundle_id = app
                 .bundleIdentifier()
                 .map(|id| id.to_string())
-                .unwrap_or_else(|| "unknown".to_string());
+                .unwrap_or_default();
 
             let name = app
                 .localizedName()
@@ -1,9 +1,20 @@
 // src/core/app_switcher.rs
 //! Common types and traits for app switching detection
 
+use std::collections::HashMap;
 use std::fmt;
 use std::hash::Hash;
-use std::time::Instant;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::accessibility::WindowInfo;
+use crate::core::context_diff::ContextDiff;
+use crate::core::power_state::PowerState;
+use crate::core::previous_context::EnhancedContextSnapshot;
+use crate::extractors::ContextValue;
 
 /// Information about an application
 #[derive(Debug, Clone)]
@@ -31,6 +42,43 @@ impl AppInfo {
             activation_count: 0,
         }
     }
+
+    /// An app named `name` with bundle id `com.example.<name>`, for tests
+    #[cfg(test)]
+    pub fn example(name: &str) -> Self {
+        Self::new(
+            name.to_string(),
+            format!("com.example.{}", name.to_lowercase()),
+            100,
+        )
+    }
+
+    /// `false` for helper processes and command-line tools brought to front
+    pub fn has_bundle_id(&self) -> bool {
+        !self.bundle_id.trim().is_empty()
+    }
+
+    /// Last component of the bundle or executable path, without `.app`
+    pub fn executable_name(&self) -> Option<&str> {
+        let file = self
+            .path
+            .as_deref()?
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()?;
+        let name = file.strip_suffix(".app").unwrap_or(file);
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// What to match and classify the app by: the bundle ID, or for apps
+    /// without one the executable name, then the display name
+    pub fn match_key(&self) -> &str {
+        if self.has_bundle_id() {
+            &self.bundle_id
+        } else {
+            self.executable_name().unwrap_or(&self.name)
+        }
+    }
 }
 
 impl fmt::Display for AppInfo {
@@ -40,7 +88,16 @@ impl fmt::Display for AppInfo {
 }
 
 /// Type of app switch event
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Serialized as the snake_case names `as_str` returns (`foreground`,
+/// `background`, `launch`, `terminate`, `hide`, `unhide`, `space_change`,
+/// `session_change`, `sleep`, `wake`, `heartbeat`); these are stable.
+///
+/// A Space change, session change or wake that leaves a different app in
+/// front is reported with its own type; `is_foreground` tells listeners
+/// that the event's app is now frontmost, as for a `Foreground` switch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AppSwitchType {
     /// App came to foreground
     Foreground,
@@ -54,6 +111,79 @@ pub enum AppSwitchType {
     Hide,
     /// App was unhidden
     Unhide,
+    /// The frontmost app after a switch to another Space
+    SpaceChange,
+    /// The frontmost app after the user session became active again
+    SessionChange,
+    /// The system is going to sleep; the app is the one that was in front
+    Sleep,
+    /// The frontmost app after the system woke
+    Wake,
+    /// The current app re-announced after a quiet interval; not a switch
+    Heartbeat,
+}
+
+impl AppSwitchType {
+    pub const ALL: [AppSwitchType; 11] = [
+        AppSwitchType::Foreground,
+        AppSwitchType::Background,
+        AppSwitchType::Launch,
+        AppSwitchType::Terminate,
+        AppSwitchType::Hide,
+        AppSwitchType::Unhide,
+        AppSwitchType::SpaceChange,
+        AppSwitchType::SessionChange,
+        AppSwitchType::Sleep,
+        AppSwitchType::Wake,
+        AppSwitchType::Heartbeat,
+    ];
+
+    /// Whether the event's app has just come to the front: a switch, or a
+    /// Space change, session change or wake that left it there
+    pub fn is_foreground(&self) -> bool {
+        matches!(
+            self,
+            AppSwitchType::Foreground
+                | AppSwitchType::SpaceChange
+                | AppSwitchType::SessionChange
+                | AppSwitchType::Wake
+        )
+    }
+
+    /// The stable name of the event type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AppSwitchType::Foreground => "foreground",
+            AppSwitchType::Background => "background",
+            AppSwitchType::Launch => "launch",
+            AppSwitchType::Terminate => "terminate",
+            AppSwitchType::Hide => "hide",
+            AppSwitchType::Unhide => "unhide",
+            AppSwitchType::SpaceChange => "space_change",
+            AppSwitchType::SessionChange => "session_change",
+            AppSwitchType::Sleep => "sleep",
+            AppSwitchType::Wake => "wake",
+            AppSwitchType::Heartbeat => "heartbeat",
+        }
+    }
+}
+
+impl fmt::Display for AppSwitchType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AppSwitchType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        AppSwitchType::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == name)
+            .ok_or_else(|| format!("Unknown app switch type '{}'", s.trim()))
+    }
 }
 
 /// Workspace (CGWindow) summary data for convenience
@@ -70,7 +200,7 @@ pub struct WorkspaceSummary {
 }
 
 /// Enhanced (NSWorkspace/process/desktop) summary data
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct EnhancedSummary {
     pub activation_count: u32,
     pub front_window_title: Option<String>,
@@ -87,9 +217,31 @@ pub struct EnhancedSummary {
     pub space_type: Option<String>,
     pub space_name: Option<String>,
     pub space_label: Option<String>,
+    /// Front window is fullscreen: AXFullScreen, or when the window does not
+    /// expose it, bounds covering the display
+    pub is_fullscreen: Option<bool>,
+    /// Focused window is on a space currently shown; `None` when unknown
+    pub window_on_active_space: Option<bool>,
+    /// The app has a floating window such as Picture-in-Picture video
+    pub has_floating_window: Option<bool>,
+    /// Focused window has unsaved changes; `None` when the app does not say
+    pub document_edited: Option<bool>,
+    /// Effective UI language of the app, e.g. `de-DE`
+    pub app_locale: Option<String>,
+    /// `false` while the app refuses accessibility and extraction is skipped
+    pub ax_available: Option<bool>,
+    /// Path of the open (or just chosen) menu item, e.g. `File › Export › PDF`
+    pub menu_context: Option<String>,
     // Browser/IDE context
     pub url: Option<String>,
+    /// Registrable domain of `url`, e.g. `google.com` for `docs.google.com`
+    pub domain: Option<String>,
     pub tab_title: Option<String>,
+    /// Chromium profile named in the window title; `None` for the default
+    /// profile or when it cannot be told apart from the tab title
+    pub browser_profile: Option<String>,
+    /// Keys from extractors registered with `AppSwitcher::add_context_extractor`
+    pub extracted_context: HashMap<String, ContextValue>,
 }
 
 /// An app switch event
@@ -103,8 +255,26 @@ pub struct AppSwitchEvent {
     pub workspace: Option<WorkspaceSummary>,
     /// Optional enhanced (NSWorkspace/process/desktop) summary when available
     pub enhanced: Option<EnhancedSummary>,
-    /// Optional confidence score when derived from multiple sources
+    /// How sure the source is that this switch happened, `0.0..=1.0`;
+    /// see `core::confidence` for how it is derived
     pub confidence: Option<f32>,
+    /// All windows of the app, when window capture is enabled
+    pub windows: Option<Vec<WindowInfo>>,
+    /// Fields changed since the previous foreground event, when diffs are enabled
+    pub diff: Option<ContextDiff>,
+    /// Last-known context of `previous_app`, when previous contexts are enabled
+    pub previous_context: Option<EnhancedContextSnapshot>,
+    /// Id of the tracker session that produced the event
+    pub session_id: Option<String>,
+    /// Latest power sample, when power capture is enabled
+    pub power: Option<PowerState>,
+    /// PNG of the focused window, when screenshot capture is enabled
+    pub screenshot_path: Option<String>,
+    /// Keyboard layout or input method id, when input sources are tracked
+    pub input_source: Option<String>,
+    /// Content was withheld by a redact stage (quiet hours, title
+    /// exclusion); derive stages attach nothing to such events
+    pub redacted: bool,
 }
 
 impl AppSwitchEvent {
@@ -117,6 +287,14 @@ impl AppSwitchEvent {
             workspace: None,
             enhanced: None,
             confidence: None,
+            windows: None,
+            diff: None,
+            previous_context: None,
+            session_id: None,
+            power: None,
+            screenshot_path: None,
+            input_source: None,
+            redacted: false,
         }
     }
 
@@ -129,20 +307,58 @@ impl AppSwitchEvent {
             workspace: None,
             enhanced: None,
             confidence: None,
+            windows: None,
+            diff: None,
+            previous_context: None,
+            session_id: None,
+            power: None,
+            screenshot_path: None,
+            input_source: None,
+            redacted: false,
         }
     }
 }
 
+/// Wall-clock interval the system spent asleep, reported on wake
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SleepPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl SleepPeriod {
+    pub fn duration(&self) -> Duration {
+        (self.end - self.start).to_std().unwrap_or_default()
+    }
+}
+
 /// Trait for app switch event listeners
 pub trait AppSwitchListener: Send + Sync {
     /// Called when an app switch occurs
     fn on_app_switch(&mut self, event: &AppSwitchEvent);
 
+    /// Name used in diagnostics; the implementing type's name by default
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
     /// Called when monitoring starts
     fn on_monitoring_started(&mut self) {}
 
     /// Called when monitoring stops
     fn on_monitoring_stopped(&mut self) {}
+
+    /// Called when delivery is paused; no events arrive until it resumes,
+    /// starting with a `Foreground` event for the app that is frontmost then
+    fn on_monitoring_paused(&mut self) {}
+
+    /// Called when the system is about to sleep; open periods should close
+    /// so the sleep is not counted as app time
+    fn on_system_sleep(&mut self) {}
+
+    /// Called on wake with the interval spent asleep, followed by a
+    /// `Foreground` event for the app that is frontmost then
+    fn on_system_wake(&mut self, _asleep: &SleepPeriod) {}
 }
 
 /// Main app switcher trait that all implementations should follow
@@ -159,3 +375,54 @@ pub trait AppSwitcher {
     /// Get current app if available
     fn current_app(&self) -> Option<AppInfo>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_types_have_stable_names() {
+        let documented = [
+            (AppSwitchType::Foreground, "foreground"),
+            (AppSwitchType::Background, "background"),
+            (AppSwitchType::Launch, "launch"),
+            (AppSwitchType::Terminate, "terminate"),
+            (AppSwitchType::Hide, "hide"),
+            (AppSwitchType::Unhide, "unhide"),
+            (AppSwitchType::SpaceChange, "space_change"),
+            (AppSwitchType::SessionChange, "session_change"),
+            (AppSwitchType::Sleep, "sleep"),
+            (AppSwitchType::Wake, "wake"),
+            (AppSwitchType::Heartbeat, "heartbeat"),
+        ];
+        assert_eq!(documented.len(), AppSwitchType::ALL.len());
+        for (kind, name) in documented {
+            assert_eq!(kind.as_str(), name);
+            assert_eq!(kind.to_string(), name);
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(json, format!("\"{}\"", name));
+            assert_eq!(serde_json::from_str::<AppSwitchType>(&json).unwrap(), kind);
+            assert_eq!(name.parse::<AppSwitchType>().unwrap(), kind);
+        }
+        assert_eq!(
+            " Launch ".parse::<AppSwitchType>(),
+            Ok(AppSwitchType::Launch)
+        );
+        assert!("space".parse::<AppSwitchType>().is_err());
+        assert!(serde_json::from_str::<AppSwitchType>("\"Foreground\"").is_err());
+
+        let in_front: Vec<_> = AppSwitchType::ALL
+            .into_iter()
+            .filter(AppSwitchType::is_foreground)
+            .collect();
+        assert_eq!(
+            in_front,
+            vec![
+                AppSwitchType::Foreground,
+                AppSwitchType::SpaceChange,
+                AppSwitchType::SessionChange,
+                AppSwitchType::Wake,
+            ]
+        );
+    }
+}
@@ -15,7 +15,7 @@ use std::thread;
 use std::time::{Duration, Instant, SystemTime};
 
 use objc2::rc::Retained;
-use objc2::{define_class, msg_send, sel, AnyThread, MainThreadMarker, Message};
+use objc2::{define_class, msg_send, sel, AnyThread, MainThreadMarker};
 use objc2_app_kit::{NSRunningApplication, NSWorkspace};
 use objc2_foundation::{
     NSNotification, NSNotificationCenter, NSObject, NSObjectProtocol, NSString,
@@ -41,7 +41,11 @@ extern "C" {
 }
 
 // Import shared types
+use crate::core::app_lifecycle::{
+    lifecycle_event, running_app_from_user_info, WORKSPACE_DID_LAUNCH, WORKSPACE_DID_TERMINATE,
+};
 use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent, AppSwitchListener, AppSwitchType};
+use crate::core::process_cache::launch_instant;
 
 // CGWindow functions
 use core_foundation::array::CFArrayRef;
@@ -187,6 +191,12 @@ pub trait WorkspaceAppSwitchListener: Send + Sync {
     fn on_window_change(&mut self, change: &WindowChangeInfo) {}
     fn on_tab_change(&mut self, app: &str, tabs: &[TabInfo]) {}
     fn on_file_change(&mut self, app: &str, files: &[String]) {}
+    /// `NSWorkspaceWillSleepNotification`
+    fn on_system_sleep(&mut self) {}
+    /// `NSWorkspaceDidWakeNotification`
+    fn on_system_wake(&mut self) {}
+    /// An app launched or quit, possibly in the background; see `app_lifecycle`
+    fn on_app_lifecycle(&mut self, _event: &AppSwitchEvent) {}
 }
 
 // NSWorkspace observer class with Objective-C bridged handlers
@@ -355,13 +365,13 @@ impl WorkspaceAppMonitor {
                 &notification_center,
                 &observer,
                 sel!(workspaceDidLaunchApplication:),
-                "NSWorkspaceDidLaunchApplicationNotification",
+                WORKSPACE_DID_LAUNCH,
             );
             Self::register_notification(
                 &notification_center,
                 &observer,
                 sel!(workspaceDidTerminateApplication:),
-                "NSWorkspaceDidTerminateApplicationNotification",
+                WORKSPACE_DID_TERMINATE,
             );
             Self::register_notification(
                 &notification_center,
@@ -516,6 +526,14 @@ impl WorkspaceAppMonitor {
                     workspace: Some(basic_workspace),
                     enhanced: None,
                     confidence: Some(1.0),
+                    windows: None,
+                    diff: None,
+                    previous_context: None,
+                    session_id: None,
+                    power: None,
+                    screenshot_path: None,
+                    input_source: None,
+                    redacted: false,
                 };
 
                 for listener in &mut state.basic_listeners {
@@ -535,7 +553,7 @@ impl WorkspaceAppMonitor {
             let bundle_id = app
                 .bundleIdentifier()
                 .map(|id| id.to_string())
-                .unwrap_or_else(|| "unknown".to_string());
+                .unwrap_or_default();
 
             let name = app
                 .localizedName()
@@ -544,8 +562,10 @@ impl WorkspaceAppMonitor {
 
             let pid = app.processIdentifier();
 
+            // Bare executables have no bundle; their path names them instead
             let path = app
                 .bundleURL()
+                .or_else(|| app.executableURL())
                 .and_then(|url| url.path())
                 .map(|p| p.to_string());
 
@@ -554,7 +574,9 @@ impl WorkspaceAppMonitor {
                 bundle_id: bundle_id.clone(),
                 pid,
                 path,
-                launch_date: app.launchDate().map(|_| Instant::now()),
+                launch_date: app
+                    .launchDate()
+                    .and_then(|d| launch_instant(-d.timeIntervalSinceNow())),
                 icon_base64: None,
                 icon_path: None,
                 activation_count: 0,
@@ -1332,6 +1354,31 @@ impl WorkspaceObserver {
     fn handle_notification(notification: &NSNotification, event_type: &str) {
         unsafe {
             if let Some(global) = &WORKSPACE_GLOBAL_STATE {
+                // Sleep and wake carry no app
+                if event_type == "sleep" || event_type == "wake" {
+                    let mut state = global.lock().unwrap();
+                    for listener in &mut state.listeners {
+                        if event_type == "sleep" {
+                            listener.on_system_sleep();
+                        } else {
+                            listener.on_system_wake();
+                        }
+                    }
+                    return;
+                }
+                if event_type == "launch" || event_type == "terminate" {
+                    let name = notification.name().to_string();
+                    let event = notification
+                        .userInfo()
+                        .and_then(|info| lifecycle_event(&name, &info));
+                    if let Some(event) = event {
+                        let mut state = global.lock().unwrap();
+                        for listener in &mut state.listeners {
+                            listener.on_app_lifecycle(&event);
+                        }
+                    }
+                    return;
+                }
                 let app = Self::get_app_from_notification(notification);
                 if let Some(app) = app {
                     let mut state = global.lock().unwrap();
@@ -1346,8 +1393,6 @@ impl WorkspaceObserver {
                         event_type: match event_type {
                             "activate" => AppSwitchType::Foreground,
                             "deactivate" => AppSwitchType::Background,
-                            "launch" => AppSwitchType::Launch,
-                            "terminate" => AppSwitchType::Terminate,
                             "hide" => AppSwitchType::Hide,
                             "unhide" => AppSwitchType::Unhide,
                             _ => AppSwitchType::Foreground,
@@ -1369,7 +1414,10 @@ impl WorkspaceObserver {
                         listener.on_workspace_app_switch(&event);
                     }
 
-                    state.current_app = Some(app_info);
+                    // Hiding or deactivating an app does not make it current
+                    if event_type == "activate" {
+                        state.current_app = Some(app_info);
+                    }
                 }
             }
         }
@@ -1378,15 +1426,8 @@ impl WorkspaceObserver {
     unsafe fn get_app_from_notification(
         notification: &NSNotification,
     ) -> Option<Retained<NSRunningApplication>> {
-        if let Some(user_info) = unsafe { notification.userInfo() } {
-            let key = NSString::from_str("NSWorkspaceApplicationKey");
-            if let Some(app_obj) = user_info.objectForKey(&key) {
-                let app: Retained<NSRunningApplication> =
-                    unsafe { Retained::cast_unchecked(app_obj.retain()) };
-                return Some(app);
-            }
-        }
-        None
+        let user_info = unsafe { notification.userInfo() }?;
+        running_app_from_user_info(&user_info)
     }
 }
 
@@ -1437,6 +1478,13 @@ impl WorkspaceAppSwitchListener for WorkspaceDebugListener {
         }
     }
 
+    fn on_app_lifecycle(&mut self, event: &AppSwitchEvent) {
+        println!(
+            "🚀 App {:?}: {} ({})",
+            event.event_type, event.app_info.name, event.app_info.bundle_id
+        );
+    }
+
     fn on_window_change(&mut self, _change: &WindowChangeInfo) {}
 
     fn on_tab_change(&mut self, _app: &str, _tabs: &[TabInfo]) {}
@@ -0,0 +1,254 @@
+// src/core/appearance.rs
+//! System appearance (light or dark mode)
+//!
+//! Screenshots and UI segmentation look different in dark mode, so the
+//! session manifest records the appearance at startup and
+//! `AppearanceObserver` reports changes while running. The global
+//! `AppleInterfaceStyle` preference is `Dark` in dark mode and absent in
+//! light mode; macOS posts `AppleInterfaceThemeChangedNotification` on the
+//! distributed notification center whenever it flips, including the
+//! automatic switch at sunset.
+
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
+
+use block2::RcBlock;
+use chrono::{DateTime, Utc};
+use core_foundation::base::{CFGetTypeID, CFRelease, TCFType};
+use core_foundation::string::{CFString, CFStringGetTypeID, CFStringRef};
+use core_foundation_sys::preferences::{
+    kCFPreferencesAnyApplication, kCFPreferencesAnyHost, kCFPreferencesCurrentUser,
+    CFPreferencesCopyValue,
+};
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_foundation::{
+    NSDistributedNotificationCenter, NSNotification, NSObjectProtocol, NSString,
+};
+use serde::{Deserialize, Serialize};
+
+const THEME_CHANGED_NOTIFICATION: &str = "AppleInterfaceThemeChangedNotification";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Appearance {
+    Light,
+    Dark,
+}
+
+impl Appearance {
+    /// Map an `NSAppearance` name (`NSAppearanceNameDarkAqua`,
+    /// `NSAppearanceNameAccessibilityHighContrastAqua`, ...) or an
+    /// `AppleInterfaceStyle` value (`Dark`) to an appearance
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let lower = name.to_ascii_lowercase();
+        if lower.contains("dark") {
+            Some(Self::Dark)
+        } else if lower.contains("aqua") || lower.contains("light") {
+            Some(Self::Light)
+        } else {
+            None
+        }
+    }
+}
+
+/// The current system appearance; light when `AppleInterfaceStyle` is unset
+pub fn current_appearance() -> Option<Appearance> {
+    match interface_style() {
+        Some(style) => Appearance::from_name(&style),
+        None => Some(Appearance::Light),
+    }
+}
+
+/// `AppleInterfaceStyle` from the global preferences domain
+fn interface_style() -> Option<String> {
+    unsafe {
+        let key = CFString::new("AppleInterfaceStyle");
+        let value = CFPreferencesCopyValue(
+            key.as_concrete_TypeRef(),
+            kCFPreferencesAnyApplication,
+            kCFPreferencesCurrentUser,
+            kCFPreferencesAnyHost,
+        );
+        if value.is_null() {
+            return None;
+        }
+        let style = if CFGetTypeID(value) == CFStringGetTypeID() {
+            Some(CFString::wrap_under_get_rule(value as CFStringRef).to_string())
+        } else {
+            None
+        };
+        CFRelease(value);
+        style
+    }
+}
+
+/// The system switched between light and dark mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppearanceChange {
+    pub timestamp: DateTime<Utc>,
+    pub previous: Option<Appearance>,
+    pub appearance: Appearance,
+}
+
+/// Receives appearance changes
+pub trait AppearanceListener: Send {
+    fn on_appearance_change(&mut self, event: &AppearanceChange);
+}
+
+struct ObserverState {
+    listeners: Vec<Box<dyn AppearanceListener>>,
+    last: Option<Appearance>,
+}
+
+impl ObserverState {
+    /// Report `appearance` if it differs from the last one seen
+    fn update(&mut self, appearance: Appearance, timestamp: DateTime<Utc>) {
+        if self.last == Some(appearance) {
+            return;
+        }
+        let event = AppearanceChange {
+            timestamp,
+            previous: self.last.replace(appearance),
+            appearance,
+        };
+        for listener in self.listeners.iter_mut() {
+            listener.on_appearance_change(&event);
+        }
+    }
+}
+
+/// Watches for light/dark mode changes
+///
+/// Notifications are delivered on the main thread's run loop, so `start`
+/// must be called from the main thread while that loop is running.
+pub struct AppearanceObserver {
+    state: Arc<Mutex<ObserverState>>,
+    token: Option<Retained<ProtocolObject<dyn NSObjectProtocol>>>,
+}
+
+impl AppearanceObserver {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ObserverState {
+                listeners: Vec::new(),
+                last: current_appearance(),
+            })),
+            token: None,
+        }
+    }
+
+    pub fn add_listener<T: AppearanceListener + 'static>(&self, listener: T) {
+        self.state
+            .lock()
+            .unwrap()
+            .listeners
+            .push(Box::new(listener));
+    }
+
+    /// The appearance last seen
+    pub fn appearance(&self) -> Option<Appearance> {
+        self.state.lock().unwrap().last
+    }
+
+    pub fn start(&mut self) {
+        if self.token.is_some() {
+            return;
+        }
+        let state = Arc::clone(&self.state);
+        let block = RcBlock::new(move |_notification: NonNull<NSNotification>| {
+            if let Some(appearance) = current_appearance() {
+                if let Ok(mut state) = state.lock() {
+                    state.update(appearance, Utc::now());
+                }
+            }
+        });
+        let name = NSString::from_str(THEME_CHANGED_NOTIFICATION);
+        let token = unsafe {
+            NSDistributedNotificationCenter::defaultCenter()
+                .addObserverForName_object_queue_usingBlock(Some(&name), None, None, &block)
+        };
+        self.token = Some(token);
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(token) = self.token.take() {
+            unsafe {
+                NSDistributedNotificationCenter::defaultCenter().removeObserver(token.as_ref());
+            }
+        }
+    }
+}
+
+impl Default for AppearanceObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for AppearanceObserver {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_appearance_from_name() {
+        assert_eq!(
+            Appearance::from_name("NSAppearanceNameAqua"),
+            Some(Appearance::Light)
+        );
+        assert_eq!(
+            Appearance::from_name("NSAppearanceNameDarkAqua"),
+            Some(Appearance::Dark)
+        );
+        assert_eq!(
+            Appearance::from_name("NSAppearanceNameVibrantDark"),
+            Some(Appearance::Dark)
+        );
+        assert_eq!(
+            Appearance::from_name("NSAppearanceNameAccessibilityHighContrastAqua"),
+            Some(Appearance::Light)
+        );
+        assert_eq!(
+            Appearance::from_name("NSAppearanceNameAccessibilityHighContrastDarkAqua"),
+            Some(Appearance::Dark)
+        );
+        assert_eq!(Appearance::from_name("Dark"), Some(Appearance::Dark));
+        assert_eq!(Appearance::from_name(""), None);
+        assert_eq!(Appearance::from_name("Graphite"), None);
+    }
+
+    #[test]
+    fn test_only_changes_are_reported() {
+        struct Recorder(Arc<Mutex<Vec<AppearanceChange>>>);
+        impl AppearanceListener for Recorder {
+            fn on_appearance_change(&mut self, event: &AppearanceChange) {
+                self.0.lock().unwrap().push(event.clone());
+            }
+        }
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut state = ObserverState {
+            listeners: vec![Box::new(Recorder(Arc::clone(&seen)))],
+            last: Some(Appearance::Light),
+        };
+        let now = Utc::now();
+        state.update(Appearance::Light, now);
+        state.update(Appearance::Dark, now);
+        state.update(Appearance::Dark, now);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].previous, Some(Appearance::Light));
+        assert_eq!(seen[0].appearance, Appearance::Dark);
+    }
+}
@@ -0,0 +1,193 @@
+// src/core/ax_attributes.rs
+//! Which accessibility attributes may be read
+//!
+//! Focused-element extraction reads a few dozen attributes, some of which
+//! carry what the user is typing or has selected. Every read goes through an
+//! `AttributeAllowlist`: content attributes are skipped unless content capture
+//! is enabled (`--capture-content`), and the config file can narrow the set
+//! further with an explicit allow or deny list.
+
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use serde::{Deserialize, Serialize};
+
+/// Attributes holding user content rather than UI structure
+pub const CONTENT_ATTRIBUTES: &[&str] = &[
+    "AXValue",
+    "AXSelectedText",
+    "AXSelectedTextRange",
+    "AXVisibleText",
+];
+
+/// Attribute read policy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttributeAllowlist {
+    /// Read `CONTENT_ATTRIBUTES`; off by default
+    pub capture_content: bool,
+    /// When non-empty, only these attributes are read
+    pub allow: Vec<String>,
+    /// Never read these attributes
+    pub deny: Vec<String>,
+}
+
+impl AttributeAllowlist {
+    pub fn allows(&self, attribute: &str) -> bool {
+        if self.deny.iter().any(|a| a == attribute) {
+            return false;
+        }
+        if !self.capture_content && CONTENT_ATTRIBUTES.contains(&attribute) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|a| a == attribute)
+    }
+}
+
+/// Typed attribute reads on one element; mocked in tests
+pub trait AttributeReader {
+    fn string(&self, attribute: &str) -> Option<String>;
+    fn boolean(&self, attribute: &str) -> Option<bool>;
+    fn integer(&self, attribute: &str) -> Option<usize>;
+    fn point(&self, attribute: &str) -> Option<CGPoint>;
+    fn size(&self, attribute: &str) -> Option<CGSize>;
+    fn frame(&self, attribute: &str) -> Option<CGRect>;
+}
+
+/// Forwards only the reads an allowlist permits
+pub struct FilteredReader<'a> {
+    inner: &'a dyn AttributeReader,
+    allowlist: &'a AttributeAllowlist,
+}
+
+impl<'a> FilteredReader<'a> {
+    pub fn new(inner: &'a dyn AttributeReader, allowlist: &'a AttributeAllowlist) -> Self {
+        Self { inner, allowlist }
+    }
+}
+
+impl AttributeReader for FilteredReader<'_> {
+    fn string(&self, attribute: &str) -> Option<String> {
+        self.allowlist
+            .allows(attribute)
+            .then(|| self.inner.string(attribute))?
+    }
+
+    fn boolean(&self, attribute: &str) -> Option<bool> {
+        self.allowlist
+            .allows(attribute)
+            .then(|| self.inner.boolean(attribute))?
+    }
+
+    fn integer(&self, attribute: &str) -> Option<usize> {
+        self.allowlist
+            .allows(attribute)
+            .then(|| self.inner.integer(attribute))?
+    }
+
+    fn point(&self, attribute: &str) -> Option<CGPoint> {
+        self.allowlist
+            .allows(attribute)
+            .then(|| self.inner.point(attribute))?
+    }
+
+    fn size(&self, attribute: &str) -> Option<CGSize> {
+        self.allowlist
+            .allows(attribute)
+            .then(|| self.inner.size(attribute))?
+    }
+
+    fn frame(&self, attribute: &str) -> Option<CGRect> {
+        self.allowlist
+            .allows(attribute)
+            .then(|| self.inner.frame(attribute))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::accessibility::read_focused_element;
+    use std::cell::RefCell;
+
+    /// Answers every string read and records what was asked for
+    #[derive(Default)]
+    struct RecordingReader {
+        requested: RefCell<Vec<String>>,
+    }
+
+    impl RecordingReader {
+        fn record(&self, attribute: &str) {
+            self.requested.borrow_mut().push(attribute.to_string());
+        }
+
+        fn requested(&self, attribute: &str) -> bool {
+            self.requested.borrow().iter().any(|a| a == attribute)
+        }
+    }
+
+    impl AttributeReader for RecordingReader {
+        fn string(&self, attribute: &str) -> Option<String> {
+            self.record(attribute);
+            Some(format!("{} value", attribute))
+        }
+
+        fn boolean(&self, attribute: &str) -> Option<bool> {
+            self.record(attribute);
+            None
+        }
+
+        fn integer(&self, attribute: &str) -> Option<usize> {
+            self.record(attribute);
+            None
+        }
+
+        fn point(&self, attribute: &str) -> Option<CGPoint> {
+            self.record(attribute);
+            None
+        }
+
+        fn size(&self, attribute: &str) -> Option<CGSize> {
+            self.record(attribute);
+            None
+        }
+
+        fn frame(&self, attribute: &str) -> Option<CGRect> {
+            self.record(attribute);
+            None
+        }
+    }
+
+    #[test]
+    fn test_content_attributes_never_requested_by_default() {
+        let reader = RecordingReader::default();
+        let allowlist = AttributeAllowlist::default();
+        let info = read_focused_element(&FilteredReader::new(&reader, &allowlist));
+
+        assert!(reader.requested("AXRole"));
+        assert!(!reader.requested("AXValue"));
+        assert!(!reader.requested("AXSelectedText"));
+        assert_eq!(info.role.as_deref(), Some("AXRole value"));
+        assert_eq!(info.value, None);
+        assert_eq!(info.selected_text, None);
+
+        let reader = RecordingReader::default();
+        let allowlist = AttributeAllowlist {
+            capture_content: true,
+            ..AttributeAllowlist::default()
+        };
+        let info = read_focused_element(&FilteredReader::new(&reader, &allowlist));
+        assert!(reader.requested("AXValue"));
+        assert_eq!(info.selected_text.as_deref(), Some("AXSelectedText value"));
+    }
+
+    #[test]
+    fn test_allow_and_deny_lists() {
+        let allowlist = AttributeAllowlist {
+            capture_content: true,
+            allow: vec!["AXRole".to_string(), "AXValue".to_string()],
+            deny: vec!["AXValue".to_string()],
+        };
+        assert!(allowlist.allows("AXRole"));
+        assert!(!allowlist.allows("AXTitle"));
+        assert!(!allowlist.allows("AXValue"));
+    }
+}
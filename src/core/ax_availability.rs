@@ -0,0 +1,216 @@
+// src/core/ax_availability.rs
+//! Apps that refuse accessibility
+//!
+//! Even with the tracker trusted, some apps answer every AX request with
+//! `kAXErrorAPIDisabled` (secure input fields, hardened password managers,
+//! some games) or do not implement AX at all. Reading from them fails every
+//! time and each attempt can wait for the AX messaging timeout. After
+//! `failure_threshold` denials in a row an app is marked unavailable: the
+//! per-switch AX reads and context extraction are skipped and its events
+//! carry `ax_available: false`. Once `reprobe_after` has passed, the next
+//! event tries again, so an app that starts answering is picked up.
+//!
+//! `kAXErrorCannotComplete` is what a busy app returns too, so it skips the
+//! reads at hand but is not counted. Apps never probed have
+//! `ax_available: None`. Apps are keyed by `AppInfo::match_key`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use accessibility_sys::{
+    kAXErrorAPIDisabled, kAXErrorCannotComplete, kAXErrorNotImplemented, AXError,
+};
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::ax_element::AxElement;
+
+/// Denials in a row before a bundle is skipped
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// How long a bundle is skipped before it is probed again
+pub const DEFAULT_REPROBE_AFTER: Duration = Duration::from_secs(300);
+
+/// Whether an AX status means the app is refusing accessibility, as opposed
+/// to a missing attribute, an element that went away or a busy app
+pub fn is_denial(status: AXError) -> bool {
+    status == kAXErrorAPIDisabled || status == kAXErrorNotImplemented
+}
+
+#[derive(Debug, Default)]
+struct BundleStatus {
+    failures: u32,
+    /// Set once the threshold is reached; reset by a successful probe
+    unavailable_since: Option<Instant>,
+    /// Whether the app has answered a probe
+    answered: bool,
+}
+
+struct AvailabilityState {
+    failure_threshold: u32,
+    reprobe_after: Duration,
+    bundles: HashMap<String, BundleStatus>,
+}
+
+/// Per-bundle AX availability, shared between the switcher and the extractor
+#[derive(Clone)]
+pub struct AxAvailability {
+    state: Arc<Mutex<AvailabilityState>>,
+}
+
+impl Default for AxAvailability {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_REPROBE_AFTER)
+    }
+}
+
+impl AxAvailability {
+    pub fn new(failure_threshold: u32, reprobe_after: Duration) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(AvailabilityState {
+                failure_threshold: failure_threshold.max(1),
+                reprobe_after,
+                bundles: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Whether to try AX for `bundle_id` now: always, unless it is marked
+    /// unavailable and not yet due for a re-probe
+    pub fn should_attempt(&self, bundle_id: &str, now: Instant) -> bool {
+        let state = self.state.lock().unwrap();
+        match state
+            .bundles
+            .get(bundle_id)
+            .and_then(|status| status.unavailable_since)
+        {
+            Some(since) => now.saturating_duration_since(since) >= state.reprobe_after,
+            None => true,
+        }
+    }
+
+    /// `false` once the bundle has been marked unavailable
+    pub fn is_available(&self, bundle_id: &str) -> bool {
+        self.availability(bundle_id) != Some(false)
+    }
+
+    /// `Some(false)` once the bundle has been marked unavailable,
+    /// `Some(true)` once it answered, `None` while nothing is known
+    pub fn availability(&self, bundle_id: &str) -> Option<bool> {
+        let state = self.state.lock().unwrap();
+        let status = state.bundles.get(bundle_id)?;
+        if status.unavailable_since.is_some() {
+            Some(false)
+        } else {
+            status.answered.then_some(true)
+        }
+    }
+
+    /// The app answered; forget earlier denials
+    pub fn record_success(&self, bundle_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        *state.bundles.entry(bundle_id.to_string()).or_default() = BundleStatus {
+            answered: true,
+            ..BundleStatus::default()
+        };
+    }
+
+    /// The app refused; a failed re-probe restarts the skip period
+    pub fn record_denial(&self, bundle_id: &str, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        let threshold = state.failure_threshold;
+        let status = state.bundles.entry(bundle_id.to_string()).or_default();
+        status.failures += 1;
+        if status.failures >= threshold {
+            status.unavailable_since = Some(now);
+        }
+    }
+
+    /// Record the outcome of a probe by its AX status; a busy app's
+    /// `kAXErrorCannotComplete` is not recorded
+    pub fn record_status(&self, bundle_id: &str, status: AXError, now: Instant) {
+        if is_denial(status) {
+            self.record_denial(bundle_id, now);
+        } else if status != kAXErrorCannotComplete {
+            self.record_success(bundle_id);
+        }
+    }
+
+    /// Probe `pid` before a batch of AX reads, recording the outcome under
+    /// `key`; `false` when the reads should be skipped
+    pub fn probe_app(&self, key: &str, pid: i32, now: Instant) -> bool {
+        if !self.should_attempt(key, now) {
+            return false;
+        }
+        let Some(app) = AxElement::application(pid) else {
+            return false;
+        };
+        let status = app.probe();
+        self.record_status(key, status, now);
+        !is_denial(status) && status != kAXErrorCannotComplete
+    }
+
+    /// Set `ax_available` on the event's enhanced summary
+    pub fn annotate(&self, event: &mut AppSwitchEvent) {
+        let available = self.availability(event.app_info.match_key());
+        if let Some(enh) = &mut event.enhanced {
+            enh.ax_available = available;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType, EnhancedSummary};
+
+    #[test]
+    fn test_repeated_denials_are_cached_and_flagged() {
+        let availability = AxAvailability::new(3, Duration::from_secs(300));
+        let bundle = "com.example.vault";
+        let t0 = Instant::now();
+
+        for i in 0..2 {
+            assert!(availability.should_attempt(bundle, t0));
+            availability.record_status(bundle, kAXErrorAPIDisabled, t0 + Duration::from_secs(i));
+        }
+        assert!(availability.is_available(bundle), "below the threshold");
+        // A busy app is not counted
+        availability.record_status(bundle, kAXErrorCannotComplete, t0 + Duration::from_secs(2));
+        assert!(availability.should_attempt(bundle, t0 + Duration::from_secs(3)));
+        assert_eq!(availability.availability(bundle), None, "never answered");
+        availability.record_status(bundle, kAXErrorNotImplemented, t0 + Duration::from_secs(2));
+
+        // Skipped until the re-probe is due
+        assert!(!availability.should_attempt(bundle, t0 + Duration::from_secs(60)));
+        assert!(!availability.is_available(bundle));
+        assert!(availability.should_attempt("com.apple.Safari", t0));
+
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Vault".to_string(), bundle.to_string(), 7),
+        );
+        event.enhanced = Some(EnhancedSummary::default());
+        availability.annotate(&mut event);
+        assert_eq!(event.enhanced.as_ref().unwrap().ax_available, Some(false));
+
+        // A failed re-probe keeps it skipped; a successful one clears it
+        let reprobe = t0 + Duration::from_secs(302);
+        assert!(availability.should_attempt(bundle, reprobe));
+        availability.record_status(bundle, kAXErrorAPIDisabled, reprobe);
+        assert!(!availability.should_attempt(bundle, reprobe + Duration::from_secs(10)));
+        availability.record_status(bundle, 0, reprobe + Duration::from_secs(400));
+        assert!(availability.is_available(bundle));
+        availability.annotate(&mut event);
+        assert_eq!(event.enhanced.as_ref().unwrap().ax_available, Some(true));
+
+        // Nothing is claimed for an app never probed
+        let mut unknown = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Notes".to_string(), "com.apple.Notes".to_string(), 8),
+        );
+        unknown.enhanced = Some(EnhancedSummary::default());
+        availability.annotate(&mut unknown);
+        assert_eq!(unknown.enhanced.as_ref().unwrap().ax_available, None);
+    }
+}
@@ -0,0 +1,384 @@
+// src/core/ax_element.rs
+//! Owned accessibility elements
+//!
+//! `AxElement` owns one reference to an `AXUIElementRef` and releases it when
+//! dropped, so elements can be returned early, stored and passed around
+//! without pairing every copy with a `CFRelease`. Attribute values are wrapped
+//! in core-foundation types as soon as they are copied, which releases them
+//! the same way. All unsafe accessibility calls live in this module.
+
+use std::collections::VecDeque;
+use std::ffi::c_void;
+
+use accessibility_sys::{
+    kAXErrorSuccess, kAXValueTypeCFRange, kAXValueTypeCGPoint, kAXValueTypeCGSize, AXError,
+    AXUIElementCopyAttributeValue, AXUIElementCreateApplication, AXUIElementGetTypeID,
+    AXUIElementRef, AXValueGetValue, AXValueRef,
+};
+use core_foundation::array::CFArray;
+use core_foundation::base::{
+    CFGetTypeID, CFRelease, CFRetain, CFType, CFTypeID, CFTypeRef, TCFType,
+};
+use core_foundation::boolean::CFBoolean;
+use core_foundation::string::CFString;
+use core_foundation::url::CFURL;
+use core_foundation_sys::base::CFRange;
+use objc2_core_foundation::{CGPoint, CGSize};
+
+extern "C" {
+    /// Private but long-stable: the CGWindowID behind a window element
+    fn _AXUIElementGetWindow(element: AXUIElementRef, window_id: *mut u32) -> AXError;
+}
+
+/// Releases one reference; replaced in tests to count releases
+type ReleaseFn = unsafe fn(CFTypeRef);
+
+unsafe fn cf_release(reference: CFTypeRef) {
+    unsafe { CFRelease(reference) }
+}
+
+/// An `AXUIElementRef` released exactly once, on drop
+pub struct AxElement {
+    raw: AXUIElementRef,
+    release: ReleaseFn,
+}
+
+// AXUIElementRef is a CF object; the AX API may be called from any thread
+unsafe impl Send for AxElement {}
+
+impl AxElement {
+    /// The application element for `pid`
+    pub fn application(pid: i32) -> Option<Self> {
+        // SAFETY: AXUIElementCreateApplication returns a +1 reference or null
+        unsafe { Self::from_create_rule(AXUIElementCreateApplication(pid)) }
+    }
+
+    /// Take ownership of a reference the caller owns (`Create`/`Copy` rule)
+    ///
+    /// # Safety
+    /// `raw` must be null or an owned `AXUIElementRef` not released elsewhere.
+    pub unsafe fn from_create_rule(raw: AXUIElementRef) -> Option<Self> {
+        unsafe { Self::with_release(raw, cf_release) }
+    }
+
+    /// Retain a borrowed reference (`Get` rule)
+    ///
+    /// # Safety
+    /// `raw` must be null or a valid `AXUIElementRef`.
+    pub unsafe fn from_get_rule(raw: AXUIElementRef) -> Option<Self> {
+        if raw.is_null() {
+            return None;
+        }
+        unsafe {
+            CFRetain(raw as CFTypeRef);
+            Self::from_create_rule(raw)
+        }
+    }
+
+    unsafe fn with_release(raw: AXUIElementRef, release: ReleaseFn) -> Option<Self> {
+        if raw.is_null() {
+            None
+        } else {
+            Some(Self { raw, release })
+        }
+    }
+
+    /// The underlying reference, still owned by `self`
+    pub fn as_raw(&self) -> AXUIElementRef {
+        self.raw
+    }
+
+    /// Copy an attribute value; released when the returned value drops
+    pub fn attribute(&self, attribute: &str) -> Option<CFType> {
+        let name = CFString::new(attribute);
+        let mut value: CFTypeRef = std::ptr::null();
+        // SAFETY: `self.raw` is a live element and `value` receives a +1 reference
+        let status = unsafe {
+            AXUIElementCopyAttributeValue(self.raw, name.as_concrete_TypeRef(), &mut value)
+        };
+        if status == kAXErrorSuccess && !value.is_null() {
+            Some(unsafe { CFType::wrap_under_create_rule(value) })
+        } else {
+            None
+        }
+    }
+
+    /// Status of reading `AXRole`, which every responsive element has; tells
+    /// an app refusing accessibility apart from a merely missing attribute
+    pub fn probe(&self) -> AXError {
+        let name = CFString::from_static_string("AXRole");
+        let mut value: CFTypeRef = std::ptr::null();
+        // SAFETY: `self.raw` is a live element and `value` receives a +1 reference
+        let status = unsafe {
+            AXUIElementCopyAttributeValue(self.raw, name.as_concrete_TypeRef(), &mut value)
+        };
+        if !value.is_null() {
+            // SAFETY: the copied value is owned here and not used afterwards
+            unsafe { CFRelease(value) };
+        }
+        status
+    }
+
+    /// CGWindowID of a window element, for matching it against window-server
+    /// data such as spaces
+    pub fn window_id(&self) -> Option<u32> {
+        let mut window_id = 0u32;
+        // SAFETY: `self.raw` is a live element and `window_id` is writable
+        let status = unsafe { _AXUIElementGetWindow(self.raw, &mut window_id) };
+        (status == kAXErrorSuccess && window_id != 0).then_some(window_id)
+    }
+
+    /// An element-valued attribute such as `AXFocusedWindow`
+    pub fn element(&self, attribute: &str) -> Option<AxElement> {
+        let value = self.attribute(attribute)?;
+        // SAFETY: the value is a live CF object; retain it for the new owner
+        unsafe { Self::from_get_rule(value.as_CFTypeRef() as AXUIElementRef) }
+    }
+
+    /// Elements of an array-valued attribute such as `AXChildren`
+    pub fn elements(&self, attribute: &str) -> Vec<AxElement> {
+        match self
+            .attribute(attribute)
+            .and_then(|v| v.downcast_into::<CFArray>())
+        {
+            // SAFETY: AXUIElementGetTypeID has no preconditions
+            Some(array) => elements_of_type(&array, unsafe { AXUIElementGetTypeID() }),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn children(&self) -> Vec<AxElement> {
+        self.elements("AXChildren")
+    }
+
+    /// First descendant, breadth first, for which `matches` holds
+    ///
+    /// The search stops `max_depth` levels below `self` or after visiting
+    /// `max_nodes` elements, so it stays cheap on large web pages.
+    pub fn find_descendant(
+        &self,
+        max_depth: usize,
+        max_nodes: usize,
+        mut matches: impl FnMut(&AxElement) -> bool,
+    ) -> Option<AxElement> {
+        let mut queue: VecDeque<(AxElement, usize)> = self
+            .children()
+            .into_iter()
+            .map(|child| (child, 1))
+            .collect();
+        let mut visited = 0;
+        while let Some((element, depth)) = queue.pop_front() {
+            visited += 1;
+            if visited > max_nodes {
+                break;
+            }
+            if matches(&element) {
+                return Some(element);
+            }
+            if depth < max_depth {
+                queue.extend(
+                    element
+                        .children()
+                        .into_iter()
+                        .map(|child| (child, depth + 1)),
+                );
+            }
+        }
+        None
+    }
+
+    /// A string attribute; `None` if missing or not a string
+    pub fn string(&self, attribute: &str) -> Option<String> {
+        self.attribute(attribute)?
+            .downcast_into::<CFString>()
+            .map(|s| s.to_string())
+    }
+
+    /// A boolean attribute such as `AXIsDocumentEdited`; `None` if missing
+    /// or not a CFBoolean
+    pub fn boolean(&self, attribute: &str) -> Option<bool> {
+        cf_boolean(self.attribute(attribute)?)
+    }
+
+    /// A URL attribute, which may be a CFURL or a plain string
+    pub fn url(&self, attribute: &str) -> Option<String> {
+        let value = self.attribute(attribute)?;
+        let url = match value.downcast::<CFURL>() {
+            Some(url) => url.get_string().to_string(),
+            None => value.downcast_into::<CFString>()?.to_string(),
+        };
+        Some(url).filter(|u| !u.is_empty())
+    }
+
+    pub fn point(&self, attribute: &str) -> Option<(f64, f64)> {
+        let value = self.attribute(attribute)?;
+        let mut point = CGPoint::new(0.0, 0.0);
+        // SAFETY: AXValueGetValue checks the value type before writing
+        let ok = unsafe {
+            AXValueGetValue(
+                value.as_CFTypeRef() as AXValueRef,
+                kAXValueTypeCGPoint,
+                &mut point as *mut CGPoint as *mut c_void,
+            )
+        };
+        ok.then_some((point.x, point.y))
+    }
+
+    pub fn size(&self, attribute: &str) -> Option<(f64, f64)> {
+        let value = self.attribute(attribute)?;
+        let mut size = CGSize::new(0.0, 0.0);
+        // SAFETY: AXValueGetValue checks the value type before writing
+        let ok = unsafe {
+            AXValueGetValue(
+                value.as_CFTypeRef() as AXValueRef,
+                kAXValueTypeCGSize,
+                &mut size as *mut CGSize as *mut c_void,
+            )
+        };
+        ok.then_some((size.width, size.height))
+    }
+
+    /// A range attribute such as `AXRowIndexRange`, as `(location, length)`
+    pub fn range(&self, attribute: &str) -> Option<(usize, usize)> {
+        let value = self.attribute(attribute)?;
+        let mut range = CFRange::init(0, 0);
+        // SAFETY: AXValueGetValue checks the value type before writing
+        let ok = unsafe {
+            AXValueGetValue(
+                value.as_CFTypeRef() as AXValueRef,
+                kAXValueTypeCFRange,
+                &mut range as *mut CFRange as *mut c_void,
+            )
+        };
+        (ok && range.location >= 0 && range.length >= 0)
+            .then_some((range.location as usize, range.length as usize))
+    }
+}
+
+impl Drop for AxElement {
+    fn drop(&mut self) {
+        // SAFETY: `raw` is owned by this value and released only here
+        unsafe { (self.release)(self.raw as CFTypeRef) }
+    }
+}
+
+/// Owned wrappers for the items of `array` whose CF type is `type_id`
+///
+/// The array keeps its items alive only as long as it lives, so each item
+/// is retained for its wrapper. Items of another type, such as values mixed
+/// into a malformed attribute, are skipped rather than treated as elements.
+fn elements_of_type(array: &CFArray, type_id: CFTypeID) -> Vec<AxElement> {
+    array
+        .iter()
+        .filter(|item| !item.is_null())
+        // SAFETY: non-null array items are live CF objects for the array's lifetime
+        .filter(|item| unsafe { CFGetTypeID(**item) } == type_id)
+        .filter_map(|item| unsafe { AxElement::from_get_rule(*item as AXUIElementRef) })
+        .collect()
+}
+
+fn cf_boolean(value: CFType) -> Option<bool> {
+    value.downcast_into::<CFBoolean>().map(bool::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_foundation::base::CFGetRetainCount;
+    use core_foundation::number::CFNumber;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static RELEASED: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+    }
+
+    unsafe fn record_release(reference: CFTypeRef) {
+        RELEASED.with(|r| r.borrow_mut().push(reference as usize));
+    }
+
+    fn released() -> Vec<usize> {
+        RELEASED.with(|r| r.borrow().clone())
+    }
+
+    /// An element over a fake reference, released into `RELEASED`
+    fn fake(address: usize) -> AxElement {
+        unsafe { AxElement::with_release(address as AXUIElementRef, record_release) }.unwrap()
+    }
+
+    #[test]
+    fn test_release_called_once_per_element() {
+        {
+            let first = fake(0x10);
+            let moved = vec![fake(0x20), fake(0x30)];
+            let kept = moved;
+            assert_eq!(first.as_raw() as usize, 0x10);
+            assert!(released().is_empty());
+            drop(kept);
+            assert_eq!(released(), vec![0x20, 0x30]);
+        }
+        assert_eq!(released(), vec![0x20, 0x30, 0x10]);
+    }
+
+    #[test]
+    fn test_early_return_releases_and_null_is_not_owned() {
+        fn first_even(addresses: &[usize]) -> Option<usize> {
+            for &address in addresses {
+                let element = fake(address);
+                if address % 2 == 0 {
+                    return Some(element.as_raw() as usize);
+                }
+            }
+            None
+        }
+        assert_eq!(first_even(&[0x41, 0x42, 0x44]), Some(0x42));
+        assert_eq!(released(), vec![0x41, 0x42]);
+
+        let null = unsafe { AxElement::with_release(std::ptr::null_mut(), record_release) };
+        assert!(null.is_none());
+        assert_eq!(released().len(), 2);
+    }
+
+    #[test]
+    fn test_array_items_are_retained_in_order_and_filtered_by_type() {
+        // Long enough not to be tagged pointers, so retain counts are real
+        let first = CFString::new("first child of the mocked AXChildren array");
+        let second = CFString::new("second child of the mocked AXChildren array");
+        let items = [
+            first.as_CFType(),
+            CFNumber::from(7).as_CFType(),
+            second.as_CFType(),
+        ];
+        let array = CFArray::from_CFTypes(&items).into_untyped();
+        let retains = |s: &CFString| unsafe { CFGetRetainCount(s.as_CFTypeRef()) };
+        let before = (retains(&first), retains(&second));
+
+        let children = elements_of_type(&array, CFString::type_id());
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].as_raw() as CFTypeRef, first.as_CFTypeRef());
+        assert_eq!(children[1].as_raw() as CFTypeRef, second.as_CFTypeRef());
+        assert_eq!(
+            (retains(&first), retains(&second)),
+            (before.0 + 1, before.1 + 1)
+        );
+
+        // The wrappers keep the items alive after the array is gone and
+        // release only what they retained
+        drop(array);
+        assert_eq!((retains(&first), retains(&second)), before);
+        drop(children);
+        assert_eq!(
+            (retains(&first), retains(&second)),
+            (before.0 - 1, before.1 - 1)
+        );
+    }
+
+    #[test]
+    fn test_booleans_are_read_from_cf_booleans_only() {
+        assert_eq!(cf_boolean(CFBoolean::true_value().as_CFType()), Some(true));
+        assert_eq!(
+            cf_boolean(CFBoolean::false_value().as_CFType()),
+            Some(false)
+        );
+        assert_eq!(cf_boolean(CFString::new("true").as_CFType()), None);
+    }
+}
@@ -0,0 +1,449 @@
+// src/core/browser_strategy.rs
+//! Per-browser URL and title extraction strategies
+//!
+//! Each supported browser has its own AppleScript dictionary: Chromium-style
+//! browsers address the active tab of the front window, Safari the front
+//! document. Arc is Chromium-based but has its own dictionary and a sidebar /
+//! command-bar layout, so when its AppleScript fails the URL is read from the
+//! accessibility tree instead.
+//!
+//! Chromium browsers with several profiles also name the active profile in
+//! the window title, which `browser_profile_from_title` reads back.
+//!
+//! Every `osascript` run costs tens of milliseconds, and resampling asks for
+//! the same window over and over. `AppleScriptCache` reuses the last result
+//! for a browser while its focused window title is unchanged.
+
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::core::accessibility::ax_web_area_url;
+
+/// A browser with a known extraction strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrowserStrategy {
+    Chrome,
+    Edge,
+    Safari,
+    SafariTechnologyPreview,
+    Arc,
+}
+
+impl BrowserStrategy {
+    /// Pick the strategy for a bundle id
+    pub fn for_bundle_id(bundle_id: &str) -> Option<Self> {
+        // Safari Technology Preview shares Safari's bundle id prefix
+        if bundle_id.starts_with("com.google.Chrome") {
+            Some(Self::Chrome)
+        } else if bundle_id.starts_with("com.microsoft.edgemac") {
+            Some(Self::Edge)
+        } else if bundle_id.starts_with("com.apple.SafariTechnologyPreview") {
+            Some(Self::SafariTechnologyPreview)
+        } else if bundle_id.starts_with("com.apple.Safari") {
+            Some(Self::Safari)
+        } else if bundle_id == "company.thebrowser.Browser" {
+            Some(Self::Arc)
+        } else {
+            None
+        }
+    }
+
+    /// Name used in AppleScript `tell application` blocks
+    pub fn app_name(&self) -> &'static str {
+        match self {
+            Self::Chrome => "Google Chrome",
+            Self::Edge => "Microsoft Edge",
+            Self::Safari => "Safari",
+            Self::SafariTechnologyPreview => "Safari Technology Preview",
+            Self::Arc => "Arc",
+        }
+    }
+
+    pub fn url_script(&self) -> String {
+        match self {
+            Self::Chrome | Self::Edge | Self::Arc => format!(
+                r#"tell application "{}" to get URL of active tab of front window"#,
+                self.app_name()
+            ),
+            Self::Safari | Self::SafariTechnologyPreview => format!(
+                r#"tell application "{}" to get URL of front document"#,
+                self.app_name()
+            ),
+        }
+    }
+
+    pub fn title_script(&self) -> String {
+        match self {
+            Self::Chrome | Self::Edge | Self::Arc => format!(
+                r#"tell application "{}" to get title of active tab of front window"#,
+                self.app_name()
+            ),
+            Self::Safari | Self::SafariTechnologyPreview => format!(
+                r#"tell application "{}" to get name of front document"#,
+                self.app_name()
+            ),
+        }
+    }
+
+    pub fn tab_count_script(&self) -> String {
+        format!(
+            r#"tell application "{}" to get (count of tabs of front window)"#,
+            self.app_name()
+        )
+    }
+
+    /// A script answering `incognito` or `true` for a private front window;
+    /// Safari's dictionary has no such property
+    pub fn private_window_script(&self) -> Option<String> {
+        match self {
+            Self::Chrome | Self::Edge => Some(format!(
+                r#"tell application "{}" to get mode of front window"#,
+                self.app_name()
+            )),
+            Self::Arc => Some(format!(
+                r#"tell application "{}" to get incognito of front window"#,
+                self.app_name()
+            )),
+            Self::Safari | Self::SafariTechnologyPreview => None,
+        }
+    }
+
+    /// Whether the front window is private or incognito; `None` when the
+    /// browser cannot tell or did not answer
+    pub fn is_private_window(&self) -> Option<bool> {
+        let answer = run_applescript(&self.private_window_script()?)
+            .and_then(|out| parse_applescript_output(&out))?;
+        Some(matches!(answer.as_str(), "incognito" | "true"))
+    }
+
+    /// Whether the accessibility tree is tried when AppleScript yields nothing
+    pub fn has_ax_fallback(&self) -> bool {
+        matches!(self, Self::Arc)
+    }
+
+    /// Current URL from AppleScript alone
+    pub fn applescript_url(&self) -> Option<String> {
+        run_applescript(&self.url_script()).and_then(|out| parse_applescript_output(&out))
+    }
+
+    /// Current URL: AppleScript first, then the accessibility fallback
+    pub fn url(&self, pid: i32) -> Option<String> {
+        self.applescript_url().or_else(|| {
+            if self.has_ax_fallback() {
+                ax_web_area_url(pid)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn title(&self) -> Option<String> {
+        run_applescript(&self.title_script()).and_then(|out| parse_applescript_output(&out))
+    }
+
+    pub fn tab_count(&self) -> Option<usize> {
+        run_applescript(&self.tab_count_script())
+            .and_then(|out| parse_applescript_output(&out))
+            .and_then(|count| count.parse().ok())
+    }
+}
+
+/// How long a cached AppleScript result is reused by default
+pub const APPLESCRIPT_CACHE_TTL: Duration = Duration::from_secs(1);
+
+/// URL, tab title and tab count of a browser's front window
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BrowserPage {
+    pub url: Option<String>,
+    pub title: Option<String>,
+    /// Only read by the accessibility extractor
+    pub tab_count: Option<usize>,
+}
+
+/// AppleScript results keyed by bundle id and focused window title
+///
+/// A browser holds one entry; a different title replaces it, so a
+/// navigation or tab switch that changes the title always reads afresh.
+/// Without a window title nothing is cached.
+#[derive(Debug)]
+pub struct AppleScriptCache {
+    ttl: Duration,
+    entries: HashMap<String, (String, Instant, BrowserPage)>,
+}
+
+impl Default for AppleScriptCache {
+    fn default() -> Self {
+        Self::new(APPLESCRIPT_CACHE_TTL)
+    }
+}
+
+impl AppleScriptCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// The cached page for `(bundle_id, window_title)` if still fresh,
+    /// otherwise the result of `fetch`, which is then cached
+    pub fn get_or_fetch(
+        &mut self,
+        bundle_id: &str,
+        window_title: Option<&str>,
+        now: Instant,
+        fetch: impl FnOnce() -> BrowserPage,
+    ) -> BrowserPage {
+        let Some(window_title) = window_title else {
+            self.entries.remove(bundle_id);
+            return fetch();
+        };
+        if let Some((title, at, page)) = self.entries.get(bundle_id) {
+            if title == window_title && now.saturating_duration_since(*at) < self.ttl {
+                return page.clone();
+            }
+        }
+        let page = fetch();
+        self.entries.insert(
+            bundle_id.to_string(),
+            (window_title.to_string(), now, page.clone()),
+        );
+        page
+    }
+}
+
+/// Chrome-style names, longest first; the profile follows the name:
+/// `Page - Google Chrome - Work`
+const CHROME_STYLE_NAMES: &[&str] = &[
+    "Google Chrome Canary",
+    "Google Chrome Beta",
+    "Google Chrome Dev",
+    "Google Chrome",
+    "Chromium",
+];
+
+/// Edge-style names; the profile precedes the name: `Page - Work - Microsoft Edge`
+const EDGE_STYLE_NAMES: &[&str] = &[
+    "Microsoft Edge Canary",
+    "Microsoft Edge Beta",
+    "Microsoft Edge Dev",
+    "Microsoft Edge",
+];
+
+/// The profile named in a Chromium window title; `None` for the default
+/// profile, which the browser leaves out
+///
+/// Edge puts the profile between the tab title and its own name, and may add
+/// "and N more pages" after the tab title, so the profile is the last segment
+/// after `page_title`. A tab title can itself contain ` - `, so without
+/// `page_title` an Edge title is ambiguous and no profile is reported.
+pub fn browser_profile_from_title(window_title: &str, page_title: Option<&str>) -> Option<String> {
+    // Edge writes its name with a zero-width space: "Microsoft\u{200B} Edge"
+    let title = window_title.replace('\u{200B}', "");
+    let title = title.trim();
+    let profile = |segment: &str| {
+        let segment = segment.trim();
+        (!segment.is_empty()).then(|| segment.to_string())
+    };
+
+    for name in CHROME_STYLE_NAMES {
+        let marker = format!(" - {}", name);
+        if let Some(at) = title.rfind(&marker) {
+            let rest = &title[at + marker.len()..];
+            if rest.is_empty() {
+                return None;
+            }
+            if let Some(suffix) = rest.strip_prefix(" - ") {
+                return profile(suffix);
+            }
+        }
+    }
+
+    for name in EDGE_STYLE_NAMES {
+        let Some(head) = title.strip_suffix(&format!(" - {}", name)) else {
+            continue;
+        };
+        let page = page_title.map(str::trim).filter(|p| !p.is_empty())?;
+        let rest = head.strip_prefix(page)?;
+        return rest.rsplit_once(" - ").and_then(|(_, last)| profile(last));
+    }
+    None
+}
+
+/// Clean up `osascript` output; `missing value` means no window or tab
+pub fn parse_applescript_output(output: &str) -> Option<String> {
+    let value = output.trim().trim_matches('"').trim();
+    if value.is_empty() || value == "missing value" {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+pub(crate) fn run_applescript(script: &str) -> Option<String> {
+    let _span = tracing::debug_span!("applescript").entered();
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arc_bundle_selects_arc_strategy() {
+        let arc = BrowserStrategy::for_bundle_id("company.thebrowser.Browser").unwrap();
+        assert_eq!(arc, BrowserStrategy::Arc);
+        assert_eq!(
+            arc.url_script(),
+            r#"tell application "Arc" to get URL of active tab of front window"#
+        );
+        assert!(arc.has_ax_fallback());
+
+        assert_eq!(
+            parse_applescript_output("https://arc.net/blog\n"),
+            Some("https://arc.net/blog".to_string())
+        );
+        assert_eq!(parse_applescript_output("missing value\n"), None);
+        assert_eq!(parse_applescript_output("  \n"), None);
+    }
+
+    #[test]
+    fn test_other_browsers() {
+        assert_eq!(
+            BrowserStrategy::for_bundle_id("com.apple.SafariTechnologyPreview"),
+            Some(BrowserStrategy::SafariTechnologyPreview)
+        );
+        assert_eq!(
+            BrowserStrategy::for_bundle_id("com.google.Chrome.canary"),
+            Some(BrowserStrategy::Chrome)
+        );
+        assert!(!BrowserStrategy::Chrome.has_ax_fallback());
+        assert_eq!(
+            BrowserStrategy::for_bundle_id("com.microsoft.edgemac.Beta"),
+            Some(BrowserStrategy::Edge)
+        );
+        assert_eq!(
+            BrowserStrategy::Edge.tab_count_script(),
+            r#"tell application "Microsoft Edge" to get (count of tabs of front window)"#
+        );
+        assert_eq!(BrowserStrategy::for_bundle_id("org.mozilla.firefox"), None);
+    }
+
+    #[test]
+    fn test_profile_from_chrome_titles() {
+        assert_eq!(
+            browser_profile_from_title("GitHub - Google Chrome - Work", None),
+            Some("Work".to_string())
+        );
+        assert_eq!(
+            browser_profile_from_title("Rust - Wikipedia - Google Chrome - Profile 1", None),
+            Some("Profile 1".to_string())
+        );
+        assert_eq!(
+            browser_profile_from_title("New Tab - Google Chrome Canary - Personal", None),
+            Some("Personal".to_string())
+        );
+        // The default profile has no suffix
+        assert_eq!(
+            browser_profile_from_title("Rust - Wikipedia - Google Chrome", None),
+            None
+        );
+        assert_eq!(browser_profile_from_title("Google Chrome", None), None);
+    }
+
+    #[test]
+    fn test_profile_from_edge_titles() {
+        assert_eq!(
+            browser_profile_from_title("Inbox - Work - Microsoft\u{200B} Edge", Some("Inbox")),
+            Some("Work".to_string())
+        );
+        assert_eq!(
+            browser_profile_from_title(
+                "Docs and 3 more pages - Profile 1 - Microsoft Edge",
+                Some("Docs")
+            ),
+            Some("Profile 1".to_string())
+        );
+        // Dashes in the tab title are not mistaken for a profile
+        assert_eq!(
+            browser_profile_from_title(
+                "Rust - Wikipedia - Microsoft Edge",
+                Some("Rust - Wikipedia")
+            ),
+            None
+        );
+        assert_eq!(
+            browser_profile_from_title(
+                "Rust - Wikipedia - Personal - Microsoft Edge",
+                Some("Rust - Wikipedia")
+            ),
+            Some("Personal".to_string())
+        );
+        assert_eq!(
+            browser_profile_from_title("Inbox - Microsoft Edge", Some("Inbox")),
+            None
+        );
+
+        // Without the tab title the last segment may be part of it
+        assert_eq!(
+            browser_profile_from_title("Rust - Wikipedia - Microsoft Edge", None),
+            None
+        );
+        // A title for another tab says nothing about this one
+        assert_eq!(
+            browser_profile_from_title("Inbox - Work - Microsoft Edge", Some("GitHub")),
+            None
+        );
+        assert_eq!(browser_profile_from_title("Inbox - Safari", None), None);
+    }
+
+    #[test]
+    fn test_cache_hit_avoids_second_invocation() {
+        let mut cache = AppleScriptCache::default();
+        let start = Instant::now();
+        let mut invocations = 0;
+        let mut fetch = |url: &str| {
+            invocations += 1;
+            BrowserPage {
+                url: Some(url.to_string()),
+                ..BrowserPage::default()
+            }
+        };
+
+        let first = cache.get_or_fetch("com.google.Chrome", Some("Docs"), start, || {
+            fetch("https://a.example/")
+        });
+        let again = cache.get_or_fetch(
+            "com.google.Chrome",
+            Some("Docs"),
+            start + Duration::from_millis(500),
+            || fetch("https://b.example/"),
+        );
+        assert_eq!(again, first);
+
+        // A new title, or the TTL running out, reads again
+        let renamed = cache.get_or_fetch(
+            "com.google.Chrome",
+            Some("Mail"),
+            start + Duration::from_millis(600),
+            || fetch("https://mail.example/"),
+        );
+        assert_eq!(renamed.url.as_deref(), Some("https://mail.example/"));
+        cache.get_or_fetch(
+            "com.google.Chrome",
+            Some("Mail"),
+            start + Duration::from_millis(1600),
+            || fetch("https://mail.example/"),
+        );
+        assert_eq!(invocations, 3);
+    }
+}
@@ -0,0 +1,241 @@
+// src/core/buffered_sink.rs
+//! Buffered writing for file-like sinks
+//!
+//! Writing and flushing a file on every event is slow at high event rates, so
+//! file sinks write through a `BufWriter` and flush according to a shared
+//! `FlushPolicy`. Whatever the policy, buffered data is flushed when the sink
+//! is dropped, and the event log also flushes when monitoring stops, so
+//! nothing is lost on shutdown.
+
+use std::fmt;
+use std::io::{self, BufWriter, Write};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// When buffered output is flushed to the underlying writer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlushPolicy {
+    /// Flush after every event
+    PerEvent,
+    /// Flush once this many events are buffered
+    EveryEvents(usize),
+    /// Flush on a background timer with this period in milliseconds
+    EveryMillis(u64),
+}
+
+impl Default for FlushPolicy {
+    fn default() -> Self {
+        FlushPolicy::EveryMillis(100)
+    }
+}
+
+impl fmt::Display for FlushPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlushPolicy::PerEvent => write!(f, "per-event"),
+            FlushPolicy::EveryEvents(n) => write!(f, "{}events", n),
+            FlushPolicy::EveryMillis(ms) => write!(f, "{}ms", ms),
+        }
+    }
+}
+
+impl FromStr for FlushPolicy {
+    type Err = String;
+
+    /// Accepts `per-event`, `<N>events` or `<N>ms`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "per-event" {
+            return Ok(FlushPolicy::PerEvent);
+        }
+        let parse = |n: &str| {
+            n.parse::<u64>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("Invalid flush policy '{}'", s))
+        };
+        if let Some(n) = s.strip_suffix("events") {
+            return parse(n).map(|n| FlushPolicy::EveryEvents(n as usize));
+        }
+        if let Some(n) = s.strip_suffix("ms") {
+            return parse(n).map(FlushPolicy::EveryMillis);
+        }
+        Err(format!(
+            "Invalid flush policy '{}' (expected per-event, <N>events or <N>ms)",
+            s
+        ))
+    }
+}
+
+/// Blocks until the next timed flush is due; returning `false` stops the timer
+pub type FlushTicker = Box<dyn FnMut() -> bool + Send>;
+
+struct SinkState<W: Write> {
+    writer: BufWriter<W>,
+    pending: usize,
+}
+
+impl<W: Write> SinkState<W> {
+    fn flush(&mut self) -> io::Result<()> {
+        self.pending = 0;
+        self.writer.flush()
+    }
+}
+
+/// Line-oriented writer that flushes according to a `FlushPolicy`
+pub struct BufferedSink<W: Write + Send + 'static> {
+    state: Arc<Mutex<SinkState<W>>>,
+    policy: FlushPolicy,
+}
+
+impl<W: Write + Send + 'static> BufferedSink<W> {
+    pub fn new(writer: W, policy: FlushPolicy) -> Self {
+        let period = match policy {
+            FlushPolicy::EveryMillis(ms) => Duration::from_millis(ms),
+            _ => Duration::ZERO,
+        };
+        Self::with_ticker(
+            writer,
+            policy,
+            Box::new(move || {
+                std::thread::sleep(period);
+                true
+            }),
+        )
+    }
+
+    /// Like `new`, but an `EveryMillis` timer fires whenever `ticker` returns
+    /// instead of after sleeping for the period
+    pub fn with_ticker(writer: W, policy: FlushPolicy, ticker: FlushTicker) -> Self {
+        let state = Arc::new(Mutex::new(SinkState {
+            writer: BufWriter::new(writer),
+            pending: 0,
+        }));
+        if let FlushPolicy::EveryMillis(_) = policy {
+            Self::start_flush_timer(Arc::downgrade(&state), ticker);
+        }
+        Self { state, policy }
+    }
+
+    /// The timer holds only a weak reference and exits once the sink is gone
+    fn start_flush_timer(state: Weak<Mutex<SinkState<W>>>, mut ticker: FlushTicker) {
+        std::thread::spawn(move || loop {
+            if !ticker() {
+                break;
+            }
+            let Some(state) = state.upgrade() else {
+                break;
+            };
+            let mut state = state.lock().unwrap();
+            if state.pending > 0 {
+                let _ = state.flush();
+            }
+        });
+    }
+
+    pub fn policy(&self) -> FlushPolicy {
+        self.policy
+    }
+
+    /// Write one line, flushing if the policy says so
+    pub fn write_line(&self, line: &str) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        writeln!(state.writer, "{}", line)?;
+        state.pending += 1;
+        match self.policy {
+            FlushPolicy::PerEvent => state.flush(),
+            FlushPolicy::EveryEvents(n) if state.pending >= n => state.flush(),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        self.state.lock().unwrap().flush()
+    }
+}
+
+impl<W: Write + Send + 'static> Drop for BufferedSink<W> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            let _ = state.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writer whose contents stay observable after it is moved into the sink
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_timer_flushes_below_event_threshold() {
+        // A rendezvous channel: a send returns once the timer thread takes the
+        // tick, and it only takes the next one after flushing for this one
+        let (tick, ticks) = std::sync::mpsc::sync_channel::<()>(0);
+        let buffer = SharedBuffer::default();
+        let sink = BufferedSink::with_ticker(
+            buffer.clone(),
+            FlushPolicy::EveryMillis(10),
+            Box::new(move || ticks.recv().is_ok()),
+        );
+
+        sink.write_line("first").unwrap();
+        assert_eq!(buffer.contents(), "");
+
+        tick.send(()).unwrap();
+        tick.send(()).unwrap();
+        assert_eq!(buffer.contents(), "first\n");
+    }
+
+    #[test]
+    fn test_drop_flushes() {
+        let buffer = SharedBuffer::default();
+        let sink = BufferedSink::new(buffer.clone(), FlushPolicy::EveryEvents(100));
+
+        sink.write_line("a").unwrap();
+        sink.write_line("b").unwrap();
+        assert_eq!(buffer.contents(), "");
+
+        drop(sink);
+        assert_eq!(buffer.contents(), "a\nb\n");
+    }
+
+    #[test]
+    fn test_event_threshold_and_parsing() {
+        let buffer = SharedBuffer::default();
+        let sink = BufferedSink::new(buffer.clone(), "2events".parse().unwrap());
+        sink.write_line("a").unwrap();
+        assert_eq!(buffer.contents(), "");
+        sink.write_line("b").unwrap();
+        assert_eq!(buffer.contents(), "a\nb\n");
+
+        assert_eq!("per-event".parse(), Ok(FlushPolicy::PerEvent));
+        assert_eq!("250ms".parse(), Ok(FlushPolicy::EveryMillis(250)));
+        assert!("0ms".parse::<FlushPolicy>().is_err());
+        assert!("soon".parse::<FlushPolicy>().is_err());
+    }
+}
@@ -0,0 +1,323 @@
+// src/core/click_tap.rs
+//! Mouse-click listener built on a listen-only CGEventTap
+//!
+//! Mirrors the scroll tap: a dedicated thread owns the tap and its run loop,
+//! mouse-down events are reduced to a `ClickEvent` tagged with the frontmost
+//! app, and registered `ClickListener`s are called from that thread. Clicks
+//! closer together than the minimum interval are dropped, except the later
+//! presses of a double or triple click, which carry the higher click count.
+//!
+//! The tap lives as long as the `ClickTap`; dropping it stops the run loop,
+//! disables the tap and releases its Core Foundation objects.
+
+use std::os::raw::c_void;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use objc2_app_kit::NSWorkspace;
+use objc2_core_foundation::{kCFRunLoopDefaultMode, CFMachPort, CFRunLoop, CGPoint};
+use objc2_core_graphics::{
+    CGEvent, CGEventField, CGEventMask, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement,
+    CGEventTapProxy, CGEventType,
+};
+
+use crate::core::event_tap::MouseButton;
+
+const CLICK_TYPES: [CGEventType; 3] = [
+    CGEventType::LeftMouseDown,
+    CGEventType::RightMouseDown,
+    CGEventType::OtherMouseDown,
+];
+
+/// How long the tap thread waits on its run loop before checking for shutdown
+const RUN_LOOP_SLICE_SECS: f64 = 1.0;
+
+/// A mouse button press
+#[derive(Debug, Clone)]
+pub struct ClickEvent {
+    pub timestamp: Instant,
+    pub button: MouseButton,
+    /// Global display coordinates, origin at the top left of the main display
+    pub position: CGPoint,
+    /// 1 for a single click, 2 for the second press of a double click, ...
+    pub click_count: u32,
+    pub frontmost_pid: Option<i32>,
+    pub frontmost_bundle_id: Option<String>,
+    pub frontmost_name: Option<String>,
+}
+
+pub trait ClickListener: Send + Sync {
+    fn on_click(&mut self, event: &ClickEvent);
+}
+
+struct ClickState {
+    listeners: Vec<Box<dyn ClickListener>>,
+    min_interval: Duration,
+    /// When the last click was emitted and its click count
+    last_emit: Option<(Instant, u32)>,
+}
+
+impl ClickState {
+    fn admits(&self, now: Instant, click_count: u32) -> bool {
+        match self.last_emit {
+            Some((at, count)) => {
+                now.saturating_duration_since(at) >= self.min_interval || click_count > count
+            }
+            None => true,
+        }
+    }
+}
+
+/// State shared with the tap callback through its `user_info` pointer
+struct Shared {
+    state: Mutex<ClickState>,
+    stopping: AtomicBool,
+}
+
+/// A running click tap; dropping it tears the tap down
+pub struct ClickTap {
+    shared: Arc<Shared>,
+    run_loop: usize,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ClickTap {
+    /// Create the tap on its own thread
+    ///
+    /// Fails when the tap cannot be created, usually because Input
+    /// Monitoring permission has not been granted.
+    pub fn start(min_interval: Duration) -> Result<Self, String> {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(ClickState {
+                listeners: Vec::new(),
+                min_interval,
+                last_emit: None,
+            }),
+            stopping: AtomicBool::new(false),
+        });
+        let user_info = Arc::as_ptr(&shared) as usize;
+        let (ready_tx, ready_rx) = mpsc::channel();
+
+        let thread = thread::Builder::new()
+            .name("click_tap".into())
+            // SAFETY: `shared` outlives the thread; `Drop` joins it first
+            .spawn(move || unsafe { run_tap(user_info as *mut c_void, ready_tx) })
+            .map_err(|e| e.to_string())?;
+
+        match ready_rx.recv() {
+            Ok(Ok(run_loop)) => Ok(Self {
+                shared,
+                run_loop,
+                thread: Some(thread),
+            }),
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
+            }
+            Err(_) => Err("[click_tap] tap thread exited during setup".to_string()),
+        }
+    }
+
+    pub fn add_listener<T: ClickListener + 'static>(&self, listener: T) {
+        if let Ok(mut state) = self.shared.state.lock() {
+            state.listeners.push(Box::new(listener));
+        }
+    }
+}
+
+impl Drop for ClickTap {
+    fn drop(&mut self) {
+        self.shared.stopping.store(true, Ordering::SeqCst);
+        // SAFETY: the tap thread keeps its run loop retained until it exits,
+        // and it is joined below; CFRunLoopStop may be called from any thread
+        let run_loop = unsafe { &*(self.run_loop as *const CFRunLoop) };
+        run_loop.stop();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Body of the tap thread; reports its run loop (or an error) on `ready`
+///
+/// # Safety
+/// `user_info` must point to a `Shared` that outlives the thread.
+unsafe fn run_tap(user_info: *mut c_void, ready: mpsc::Sender<Result<usize, String>>) {
+    let mask: CGEventMask = CLICK_TYPES.iter().fold(0, |mask, ty| mask | (1 << ty.0));
+    let tap = unsafe {
+        CGEvent::tap_create(
+            CGEventTapLocation::SessionEventTap,
+            CGEventTapPlacement::HeadInsertEventTap,
+            CGEventTapOptions::ListenOnly,
+            mask,
+            Some(tap_callback),
+            user_info,
+        )
+    };
+    let Some(tap) = tap else {
+        let _ = ready.send(Err(
+            "[click_tap] CGEventTapCreate failed (permission?)".to_string()
+        ));
+        return;
+    };
+    let Some(source) = CFMachPort::new_run_loop_source(None, Some(&tap), 0) else {
+        let _ = ready.send(Err(
+            "[click_tap] CFMachPortCreateRunLoopSource failed".to_string()
+        ));
+        return;
+    };
+    let Some(run_loop) = CFRunLoop::current() else {
+        let _ = ready.send(Err("[click_tap] no run loop for tap thread".to_string()));
+        return;
+    };
+
+    // SAFETY: guaranteed by the caller
+    let shared = unsafe { &*(user_info as *const Shared) };
+    let mode = unsafe { kCFRunLoopDefaultMode };
+    run_loop.add_source(Some(&source), mode);
+    unsafe { CGEvent::tap_enable(&tap, true) };
+    let _ = ready.send(Ok(&*run_loop as *const CFRunLoop as usize));
+
+    // A stop requested before the loop starts running would be lost, so
+    // run in slices and check the flag between them
+    while !shared.stopping.load(Ordering::SeqCst) {
+        CFRunLoop::run_in_mode(mode, RUN_LOOP_SLICE_SECS, false);
+    }
+
+    unsafe { CGEvent::tap_enable(&tap, false) };
+    run_loop.remove_source(Some(&source), mode);
+    // `source`, `tap` and `run_loop` are released as they go out of scope
+}
+
+fn button_from_number(number: i64) -> MouseButton {
+    match number {
+        0 => MouseButton::Left,
+        1 => MouseButton::Right,
+        2 => MouseButton::Middle,
+        n => MouseButton::Other(n.clamp(0, u8::MAX as i64) as u8),
+    }
+}
+
+/// Button, position and click count of a mouse-down event
+fn parse_click(event: &CGEvent) -> (MouseButton, CGPoint, u32) {
+    let (number, clicks, position) = unsafe {
+        (
+            CGEvent::integer_value_field(Some(event), CGEventField::MouseEventButtonNumber),
+            CGEvent::integer_value_field(Some(event), CGEventField::MouseEventClickState),
+            CGEvent::location(Some(event)),
+        )
+    };
+    (
+        button_from_number(number),
+        position,
+        clicks.clamp(1, u32::MAX as i64) as u32,
+    )
+}
+
+unsafe extern "C-unwind" fn tap_callback(
+    _proxy: CGEventTapProxy,
+    ty: CGEventType,
+    event: NonNull<CGEvent>,
+    user_info: *mut c_void,
+) -> *mut CGEvent {
+    if !CLICK_TYPES.contains(&ty) || user_info.is_null() {
+        return event.as_ptr();
+    }
+    // SAFETY: `user_info` is the `Shared` owned by the `ClickTap`, which joins
+    // this thread before releasing it; the event is valid for the callback
+    let shared = unsafe { &*(user_info as *const Shared) };
+    let (button, position, click_count) = parse_click(unsafe { event.as_ref() });
+
+    let now = Instant::now();
+    if let Ok(mut state) = shared.state.lock() {
+        if !state.admits(now, click_count) {
+            return event.as_ptr();
+        }
+        state.last_emit = Some((now, click_count));
+        let (pid, bundle_id, name) = frontmost_triplet();
+        let click = ClickEvent {
+            timestamp: now,
+            button,
+            position,
+            click_count,
+            frontmost_pid: pid,
+            frontmost_bundle_id: bundle_id,
+            frontmost_name: name,
+        };
+        for listener in state.listeners.iter_mut() {
+            listener.on_click(&click);
+        }
+    }
+    event.as_ptr()
+}
+
+fn frontmost_triplet() -> (Option<i32>, Option<String>, Option<String>) {
+    unsafe {
+        let workspace = NSWorkspace::sharedWorkspace();
+        match workspace.frontmostApplication() {
+            Some(front) => (
+                Some(front.processIdentifier()),
+                front.bundleIdentifier().map(|s| s.to_string()),
+                front.localizedName().map(|s| s.to_string()),
+            ),
+            None => (None, None, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use objc2_core_foundation::CFRetained;
+    use objc2_core_graphics::CGMouseButton;
+
+    /// A synthetic mouse-down that is never posted
+    fn mouse_down(ty: CGEventType, button: u32, click_count: i64) -> CFRetained<CGEvent> {
+        let position = CGPoint { x: 120.0, y: 48.0 };
+        unsafe {
+            let event = CGEvent::new_mouse_event(None, ty, position, CGMouseButton(button))
+                .expect("CGEventCreateMouseEvent");
+            CGEvent::set_integer_value_field(
+                Some(&event),
+                CGEventField::MouseEventClickState,
+                click_count,
+            );
+            event
+        }
+    }
+
+    #[test]
+    fn test_parses_button_and_click_count_from_event() {
+        let (button, position, clicks) = parse_click(&mouse_down(CGEventType::LeftMouseDown, 0, 2));
+        assert_eq!(button, MouseButton::Left);
+        assert_eq!(clicks, 2);
+        assert_eq!((position.x, position.y), (120.0, 48.0));
+
+        let (button, _, clicks) = parse_click(&mouse_down(CGEventType::OtherMouseDown, 2, 1));
+        assert_eq!(button, MouseButton::Middle);
+        assert_eq!(clicks, 1);
+
+        let back = mouse_down(CGEventType::OtherMouseDown, 3, 1);
+        assert_eq!(parse_click(&back).0, MouseButton::Other(3));
+    }
+
+    #[test]
+    fn test_debounce_keeps_multi_click_presses() {
+        let mut state = ClickState {
+            listeners: Vec::new(),
+            min_interval: Duration::from_millis(250),
+            last_emit: None,
+        };
+        let start = Instant::now();
+        assert!(state.admits(start, 1));
+        state.last_emit = Some((start, 1));
+
+        // The second press of a double click gets through, a repeat does not
+        assert!(state.admits(start + Duration::from_millis(100), 2));
+        assert!(!state.admits(start + Duration::from_millis(100), 1));
+        assert!(state.admits(start + Duration::from_millis(250), 1));
+    }
+}
@@ -0,0 +1,900 @@
+// src/core/clipboard.rs
+//! Clipboard changes as an event source
+//!
+//! NSPasteboard has no change notification, so the monitor polls the general
+//! pasteboard's `changeCount` and reports each new value with the available
+//! types and the app that was frontmost when the copy happened.
+//!
+//! Images are summarized rather than discarded: format, byte size and
+//! dimensions (read from the header only) are always reported. Decoding the
+//! full image to produce a small PNG thumbnail is opt-in because of its cost,
+//! and is skipped for payloads above `max_image_bytes`. The same goes for the
+//! perceptual hash (dHash), which stays nearly the same when an image is
+//! re-encoded or rescaled, so one image can be followed across apps and
+//! repeated copies deduplicated.
+//!
+//! Only the pasteboard types in `ClipboardConfig::types` are ever read;
+//! others appear in the event's type list but their data is not requested.
+//! The default is plain text and file URLs, so images (and their summary)
+//! and rich text need to be allowed explicitly.
+//!
+//! Copies that are likely secrets record metadata only (types and length,
+//! never an image summary or thumbnail): those marked with the
+//! nspasteboard.org concealed or auto-generated types, and anything copied
+//! while a password manager was frontmost.
+//!
+//! With `capture_context`, the source window's title, URL, file and (with
+//! content capture) selection are read the moment a change is seen, before
+//! the pasteboard itself, so a switch right after the copy cannot replace
+//! them with the next app's.
+
+use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use objc2_app_kit::NSPasteboard;
+use objc2_foundation::NSString;
+use serde::{Deserialize, Serialize};
+
+use crate::core::accessibility::{
+    ax_focused_window_document_quick, ax_focused_window_title_quick, ax_selected_text_quick,
+    ax_web_area_url,
+};
+use crate::core::browser_strategy::BrowserStrategy;
+use crate::core::path_redaction::PathRedactor;
+use crate::core::running_apps::{frontmost_app, RunningAppEntry};
+
+/// Pasteboard image types that can be decoded, in preference order
+pub const IMAGE_TYPES: &[(&str, ImageFormat)] = &[
+    ("public.png", ImageFormat::Png),
+    ("public.jpeg", ImageFormat::Jpeg),
+];
+
+/// Pasteboard types read by default
+pub const DEFAULT_ALLOWED_TYPES: &[&str] = &["public.utf8-plain-text", "public.file-url"];
+
+/// Marker types password managers put on copied secrets (nspasteboard.org)
+pub const CONCEALED_TYPES: &[&str] = &[
+    "org.nspasteboard.ConcealedType",
+    "org.nspasteboard.AutoGeneratedType",
+];
+
+/// Apps whose copies are treated as secrets even without a marker type
+pub const PASSWORD_MANAGER_BUNDLE_IDS: &[&str] = &[
+    "com.1password.1password",
+    "com.agilebits.onepassword7",
+    "com.bitwarden.desktop",
+    "com.apple.Passwords",
+    "com.apple.keychainaccess",
+    "org.keepassxc.keepassxc",
+    "com.lastpass.LastPass",
+];
+
+/// Clipboard capture settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    /// Decode copied images and keep a small PNG thumbnail
+    pub capture_thumbnails: bool,
+    /// Longest edge of a thumbnail in pixels
+    pub thumbnail_size: u32,
+    /// Decode copied images and store their perceptual hash
+    pub perceptual_hash: bool,
+    /// Images larger than this are never decoded
+    pub max_image_bytes: usize,
+    /// Pasteboard types (UTIs) whose data may be read
+    pub types: Vec<String>,
+    /// Record the source window's context with each copy
+    pub capture_context: bool,
+}
+
+impl ClipboardConfig {
+    pub fn allows_type(&self, pasteboard_type: &str) -> bool {
+        self.types.iter().any(|t| t == pasteboard_type)
+    }
+
+    /// Whether any decodable image type may be read
+    pub fn allows_image_types(&self) -> bool {
+        IMAGE_TYPES
+            .iter()
+            .any(|(pasteboard_type, _)| self.allows_type(pasteboard_type))
+    }
+
+    /// Allow the decodable image types, e.g. when thumbnails are requested
+    pub fn allow_image_types(&mut self) {
+        for (pasteboard_type, _) in IMAGE_TYPES {
+            if !self.allows_type(pasteboard_type) {
+                self.types.push(pasteboard_type.to_string());
+            }
+        }
+    }
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            capture_thumbnails: false,
+            thumbnail_size: 128,
+            perceptual_hash: false,
+            max_image_bytes: 20 * 1024 * 1024,
+            types: DEFAULT_ALLOWED_TYPES.iter().map(|t| t.to_string()).collect(),
+            capture_context: false,
+        }
+    }
+}
+
+/// Summary of an image on the pasteboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipboardImage {
+    /// Pasteboard type, e.g. `public.png`
+    pub format: String,
+    pub byte_size: usize,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Base64 PNG, only with thumbnail capture enabled
+    pub thumbnail_base64: Option<String>,
+    pub thumbnail_width: Option<u32>,
+    pub thumbnail_height: Option<u32>,
+    /// 64-bit difference hash as 16 hex digits, only with hashing enabled
+    #[serde(default)]
+    pub dhash: Option<String>,
+}
+
+impl ClipboardImage {
+    /// Differing bits between the two images' hashes; `None` unless both
+    /// were hashed
+    pub fn dhash_distance(&self, other: &ClipboardImage) -> Option<u32> {
+        let parse = |hash: &Option<String>| u64::from_str_radix(hash.as_deref()?, 16).ok();
+        Some(hash_distance(parse(&self.dhash)?, parse(&other.dhash)?))
+    }
+}
+
+/// Why a copy was treated as a secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitiveReason {
+    /// The pasteboard carried a concealed or auto-generated marker type
+    ConcealedType,
+    /// A password manager was frontmost at copy time
+    PasswordManager,
+}
+
+/// The pasteboard contents changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardEvent {
+    pub timestamp: DateTime<Utc>,
+    pub change_count: isize,
+    pub types: Vec<String>,
+    pub source_app: Option<String>,
+    pub source_bundle_id: Option<String>,
+    pub image: Option<ClipboardImage>,
+    /// Set when only metadata was recorded
+    #[serde(default)]
+    pub sensitive: Option<SensitiveReason>,
+    /// Byte length of the first item type, recorded for sensitive copies
+    #[serde(default)]
+    pub content_length: Option<usize>,
+    /// The source window at copy time; only with context capture
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<CopyContext>,
+}
+
+/// What the source app was showing when the copy happened
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CopyContext {
+    pub window_title: Option<String>,
+    pub url: Option<String>,
+    pub file: Option<String>,
+    /// Selected text of the focused element; only with content capture
+    pub selection: Option<String>,
+}
+
+impl CopyContext {
+    pub fn is_empty(&self) -> bool {
+        self.window_title.is_none()
+            && self.url.is_none()
+            && self.file.is_none()
+            && self.selection.is_none()
+    }
+}
+
+/// Reads the source app's context when a copy is seen; mocked in tests
+pub trait CopyContextSource: Send {
+    fn capture(&mut self, source: &RunningAppEntry) -> CopyContext;
+}
+
+/// Reads the source app's focused window and element over accessibility
+pub struct AxCopyContext {
+    /// Also read the selected text
+    pub capture_content: bool,
+    /// Truncates or hashes the source window's file
+    pub path_redactor: Option<PathRedactor>,
+}
+
+impl CopyContextSource for AxCopyContext {
+    fn capture(&mut self, source: &RunningAppEntry) -> CopyContext {
+        let document = ax_focused_window_document_quick(source.pid);
+        let file = document
+            .as_deref()
+            .filter(|d| d.starts_with("file://"))
+            .and_then(|d| url::Url::parse(d).ok()?.to_file_path().ok())
+            .map(|path| path.to_string_lossy().into_owned());
+        let url = document
+            .filter(|d| d.starts_with("https://") || d.starts_with("http://"))
+            .or_else(|| {
+                BrowserStrategy::for_bundle_id(&source.bundle_id)
+                    .and_then(|_| ax_web_area_url(source.pid))
+            });
+        let mut context = CopyContext {
+            window_title: ax_focused_window_title_quick(source.pid),
+            url,
+            file,
+            selection: self
+                .capture_content
+                .then(|| ax_selected_text_quick(source.pid))
+                .flatten(),
+        };
+        if let Some(redactor) = &self.path_redactor {
+            redactor.redact_copy_context(&mut context);
+        }
+        context
+    }
+}
+
+/// Receives clipboard events
+pub trait ClipboardListener: Send {
+    fn on_clipboard_change(&mut self, event: &ClipboardEvent);
+}
+
+/// Read access to a pasteboard; mocked in tests
+pub trait PasteboardReader {
+    fn change_count(&self) -> isize;
+    fn types(&self) -> Vec<String>;
+    fn data_for_type(&self, pasteboard_type: &str) -> Option<Vec<u8>>;
+
+    /// Size of the data for a type, without copying it out
+    fn data_length(&self, pasteboard_type: &str) -> Option<usize> {
+        self.data_for_type(pasteboard_type).map(|data| data.len())
+    }
+}
+
+/// The system-wide general pasteboard
+pub struct GeneralPasteboard;
+
+impl PasteboardReader for GeneralPasteboard {
+    fn change_count(&self) -> isize {
+        unsafe { NSPasteboard::generalPasteboard().changeCount() }
+    }
+
+    fn types(&self) -> Vec<String> {
+        unsafe {
+            NSPasteboard::generalPasteboard()
+                .types()
+                .map(|types| types.iter().map(|t| t.to_string()).collect())
+                .unwrap_or_default()
+        }
+    }
+
+    fn data_for_type(&self, pasteboard_type: &str) -> Option<Vec<u8>> {
+        unsafe {
+            NSPasteboard::generalPasteboard()
+                .dataForType(&NSString::from_str(pasteboard_type))
+                .map(|data| data.to_vec())
+        }
+    }
+
+    fn data_length(&self, pasteboard_type: &str) -> Option<usize> {
+        unsafe {
+            NSPasteboard::generalPasteboard()
+                .dataForType(&NSString::from_str(pasteboard_type))
+                .map(|data| data.length())
+        }
+    }
+}
+
+/// Whether a copy should be treated as a secret
+pub fn sensitive_reason(
+    types: &[String],
+    source_bundle_id: Option<&str>,
+) -> Option<SensitiveReason> {
+    if types.iter().any(|t| CONCEALED_TYPES.contains(&t.as_str())) {
+        Some(SensitiveReason::ConcealedType)
+    } else if source_bundle_id.is_some_and(|id| PASSWORD_MANAGER_BUNDLE_IDS.contains(&id)) {
+        Some(SensitiveReason::PasswordManager)
+    } else {
+        None
+    }
+}
+
+/// Describe the current pasteboard contents as an event
+pub fn read_event(
+    reader: &dyn PasteboardReader,
+    change_count: isize,
+    source: Option<RunningAppEntry>,
+    config: &ClipboardConfig,
+) -> ClipboardEvent {
+    let types = reader.types();
+    let source_bundle_id = source.as_ref().map(|app| app.bundle_id.clone());
+    let sensitive = sensitive_reason(&types, source_bundle_id.as_deref());
+    let (image, content_length) = match sensitive {
+        // Only the length of the secret, never its bytes or a thumbnail
+        Some(_) => {
+            let length = types
+                .iter()
+                .filter(|t| !CONCEALED_TYPES.contains(&t.as_str()))
+                .find(|t| config.allows_type(t))
+                .and_then(|t| reader.data_length(t));
+            (None, length)
+        }
+        None => (read_image(reader, config), None),
+    };
+    ClipboardEvent {
+        timestamp: Utc::now(),
+        change_count,
+        types,
+        source_app: source.map(|app| app.name),
+        source_bundle_id,
+        image,
+        sensitive,
+        content_length,
+        context: None,
+    }
+}
+
+/// Capture the source's context, then describe the pasteboard
+///
+/// The context comes first: reading the pasteboard, an image especially,
+/// takes long enough for the user to have switched away. The selection of a
+/// sensitive copy is the secret itself and is dropped.
+pub fn read_copy(
+    reader: &dyn PasteboardReader,
+    change_count: isize,
+    source: Option<RunningAppEntry>,
+    config: &ClipboardConfig,
+    context_source: Option<&mut dyn CopyContextSource>,
+) -> ClipboardEvent {
+    let context = match (context_source, &source) {
+        (Some(context_source), Some(app)) => Some(context_source.capture(app)),
+        _ => None,
+    };
+    let mut event = read_event(reader, change_count, source, config);
+    event.context = context
+        .map(|mut context| {
+            if event.sensitive.is_some() {
+                context.selection = None;
+            }
+            context
+        })
+        .filter(|context| !context.is_empty());
+    event
+}
+
+/// Summarize the first allowed, decodable image on the pasteboard, if any
+pub fn read_image(
+    reader: &dyn PasteboardReader,
+    config: &ClipboardConfig,
+) -> Option<ClipboardImage> {
+    let types = reader.types();
+    let (pasteboard_type, format) = IMAGE_TYPES.iter().find(|(t, _)| {
+        config.allows_type(t) && types.iter().any(|available| available == t)
+    })?;
+    let byte_size = reader.data_length(pasteboard_type)?;
+
+    let mut image = ClipboardImage {
+        format: pasteboard_type.to_string(),
+        byte_size,
+        width: None,
+        height: None,
+        thumbnail_base64: None,
+        thumbnail_width: None,
+        thumbnail_height: None,
+        dhash: None,
+    };
+    // Oversized images are only measured, never copied out of the pasteboard
+    if byte_size > config.max_image_bytes {
+        return Some(image);
+    }
+    let Some(bytes) = reader.data_for_type(pasteboard_type) else {
+        return Some(image);
+    };
+
+    if let Ok((width, height)) =
+        ImageReader::with_format(Cursor::new(&bytes), *format).into_dimensions()
+    {
+        image.width = Some(width);
+        image.height = Some(height);
+    }
+    if !config.capture_thumbnails && !config.perceptual_hash {
+        return Some(image);
+    }
+
+    let decoded = match image::load_from_memory_with_format(&bytes, *format) {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            tracing::debug!("Clipboard image decode failed: {}", e);
+            return Some(image);
+        }
+    };
+    if config.perceptual_hash {
+        image.dhash = Some(format!("{:016x}", dhash(&decoded)));
+    }
+    if config.capture_thumbnails {
+        match encode_thumbnail(&decoded, config.thumbnail_size) {
+            Ok((png, width, height)) => {
+                image.thumbnail_base64 =
+                    Some(base64::engine::general_purpose::STANDARD.encode(png));
+                image.thumbnail_width = Some(width);
+                image.thumbnail_height = Some(height);
+            }
+            Err(e) => tracing::debug!("Clipboard thumbnail failed: {}", e),
+        }
+    }
+    Some(image)
+}
+
+/// Difference hash: the image shrunk to 9x8 grey pixels, one bit per pair of
+/// horizontal neighbours, set when the left pixel is darker
+pub fn dhash(image: &DynamicImage) -> u64 {
+    let small = image
+        .grayscale()
+        .resize_exact(9, 8, FilterType::Triangle)
+        .to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left < right);
+        }
+    }
+    hash
+}
+
+/// Number of differing bits; copies of one image are typically within a few
+pub fn hash_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Decode an image and re-encode it as a PNG fitting in `max_edge` pixels
+pub fn make_thumbnail(
+    bytes: &[u8],
+    format: ImageFormat,
+    max_edge: u32,
+) -> Result<(Vec<u8>, u32, u32), String> {
+    let decoded = image::load_from_memory_with_format(bytes, format).map_err(|e| e.to_string())?;
+    encode_thumbnail(&decoded, max_edge)
+}
+
+fn encode_thumbnail(decoded: &DynamicImage, max_edge: u32) -> Result<(Vec<u8>, u32, u32), String> {
+    let thumbnail = decoded.thumbnail(max_edge, max_edge);
+
+    let mut png = Vec::new();
+    thumbnail
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok((png, thumbnail.width(), thumbnail.height()))
+}
+
+/// Polls the general pasteboard for changes
+pub struct ClipboardMonitor {
+    config: ClipboardConfig,
+    listeners: Arc<Mutex<Vec<Box<dyn ClipboardListener>>>>,
+    context_source: Arc<Mutex<Option<Box<dyn CopyContextSource>>>>,
+    running: Arc<AtomicBool>,
+}
+
+impl ClipboardMonitor {
+    pub fn new(config: ClipboardConfig) -> Self {
+        Self {
+            config,
+            listeners: Arc::new(Mutex::new(Vec::new())),
+            context_source: Arc::new(Mutex::new(None)),
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn add_listener<T: ClipboardListener + 'static>(&self, listener: T) {
+        self.listeners.lock().unwrap().push(Box::new(listener));
+    }
+
+    /// Where copy contexts come from when `capture_context` is set
+    pub fn set_context_source<T: CopyContextSource + 'static>(&self, source: T) {
+        *self.context_source.lock().unwrap() = Some(Box::new(source));
+    }
+
+    /// Start polling on a background thread; existing contents are not reported
+    pub fn start(&self, poll_interval: Duration) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let config = self.config.clone();
+        let listeners = Arc::clone(&self.listeners);
+        let context_source = Arc::clone(&self.context_source);
+        let running = Arc::clone(&self.running);
+
+        std::thread::spawn(move || {
+            let pasteboard = GeneralPasteboard;
+            let mut last_change = pasteboard.change_count();
+            while running.load(Ordering::SeqCst) {
+                std::thread::sleep(poll_interval);
+
+                let change_count = pasteboard.change_count();
+                if change_count == last_change {
+                    continue;
+                }
+                last_change = change_count;
+
+                let mut source_guard = context_source.lock().unwrap();
+                let source = match source_guard.as_mut() {
+                    Some(source) if config.capture_context => {
+                        Some(source.as_mut() as &mut dyn CopyContextSource)
+                    }
+                    _ => None,
+                };
+                let event = read_copy(&pasteboard, change_count, frontmost_app(), &config, source);
+                drop(source_guard);
+                for listener in listeners.lock().unwrap().iter_mut() {
+                    listener.on_clipboard_change(&event);
+                }
+            }
+        });
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Drop for ClipboardMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    struct MockPasteboard {
+        items: HashMap<String, Vec<u8>>,
+        /// Types whose data was requested
+        queried: RefCell<Vec<String>>,
+    }
+
+    impl PasteboardReader for MockPasteboard {
+        fn change_count(&self) -> isize {
+            1
+        }
+
+        fn types(&self) -> Vec<String> {
+            self.items.keys().cloned().collect()
+        }
+
+        fn data_for_type(&self, pasteboard_type: &str) -> Option<Vec<u8>> {
+            self.queried.borrow_mut().push(pasteboard_type.to_string());
+            self.items.get(pasteboard_type).cloned()
+        }
+
+        fn data_length(&self, pasteboard_type: &str) -> Option<usize> {
+            self.items.get(pasteboard_type).map(Vec::len)
+        }
+    }
+
+    fn mock(items: HashMap<String, Vec<u8>>) -> MockPasteboard {
+        MockPasteboard {
+            items,
+            queried: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The default types plus images
+    fn with_images(mut config: ClipboardConfig) -> ClipboardConfig {
+        config.allow_image_types();
+        config
+    }
+
+    fn png_pasteboard(width: u32, height: u32) -> MockPasteboard {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            image::Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+        }));
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let mut items = HashMap::new();
+        items.insert("public.png".to_string(), png);
+        items.insert("public.utf8-plain-text".to_string(), b"ignored".to_vec());
+        mock(items)
+    }
+
+    #[test]
+    fn test_png_thumbnail_is_scaled_to_fit() {
+        let pasteboard = png_pasteboard(512, 256);
+        let config = with_images(ClipboardConfig {
+            capture_thumbnails: true,
+            ..ClipboardConfig::default()
+        });
+
+        let image = read_image(&pasteboard, &config).unwrap();
+        assert_eq!(image.format, "public.png");
+        assert_eq!((image.width, image.height), (Some(512), Some(256)));
+        assert_eq!(
+            (image.thumbnail_width, image.thumbnail_height),
+            (Some(128), Some(64))
+        );
+
+        let png = base64::engine::general_purpose::STANDARD
+            .decode(image.thumbnail_base64.unwrap())
+            .unwrap();
+        let decoded = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (128, 64));
+    }
+
+    #[test]
+    fn test_thumbnails_are_opt_in_and_size_guarded() {
+        let pasteboard = png_pasteboard(300, 300);
+
+        let image = read_image(&pasteboard, &with_images(ClipboardConfig::default())).unwrap();
+        assert_eq!(image.width, Some(300));
+        assert_eq!(image.thumbnail_base64, None);
+
+        let guarded = with_images(ClipboardConfig {
+            capture_thumbnails: true,
+            max_image_bytes: 16,
+            ..ClipboardConfig::default()
+        });
+        pasteboard.queried.borrow_mut().clear();
+        let image = read_image(&pasteboard, &guarded).unwrap();
+        assert!(image.byte_size > 16);
+        assert_eq!(image.width, None);
+        assert_eq!(image.thumbnail_base64, None);
+        assert!(
+            pasteboard.queried.borrow().is_empty(),
+            "an oversized image is not read"
+        );
+    }
+
+    fn scene(width: u32, height: u32, phase: f32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            // Resolution-independent, so scaled copies show the same picture
+            let u = x as f32 / width as f32 * 6.0 + phase;
+            let v = y as f32 / height as f32 * 4.0;
+            let level = (128.0 + 100.0 * u.sin() * v.cos()) as u8;
+            image::Rgb([level, level / 2, 255 - level])
+        }))
+    }
+
+    fn pasteboard_with(image: &DynamicImage, format: ImageFormat) -> MockPasteboard {
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .unwrap();
+        let pasteboard_type = IMAGE_TYPES.iter().find(|(_, f)| *f == format).unwrap().0;
+        let mut items = HashMap::new();
+        items.insert(pasteboard_type.to_string(), bytes);
+        mock(items)
+    }
+
+    #[test]
+    fn test_perceptual_hash_survives_reencoding() {
+        let config = with_images(ClipboardConfig {
+            perceptual_hash: true,
+            ..ClipboardConfig::default()
+        });
+        let hashed = |image: &DynamicImage, format| {
+            read_image(&pasteboard_with(image, format), &config).unwrap()
+        };
+
+        let original = hashed(&scene(320, 240, 0.0), ImageFormat::Png);
+        let as_jpeg = hashed(&scene(320, 240, 0.0), ImageFormat::Jpeg);
+        let scaled = hashed(&scene(200, 150, 0.0), ImageFormat::Jpeg);
+        let other = hashed(&scene(320, 240, 2.0), ImageFormat::Png);
+        assert_eq!(original.dhash.as_ref().map(String::len), Some(16));
+        assert_eq!(original.thumbnail_base64, None);
+
+        assert!(original.dhash_distance(&as_jpeg).unwrap() <= 4);
+        assert!(original.dhash_distance(&scaled).unwrap() <= 4);
+        assert!(original.dhash_distance(&other).unwrap() > 16);
+
+        // Off by default
+        let unhashed = read_image(
+            &pasteboard_with(&scene(32, 32, 0.0), ImageFormat::Png),
+            &with_images(ClipboardConfig::default()),
+        )
+        .unwrap();
+        assert_eq!(unhashed.dhash, None);
+        assert_eq!(unhashed.dhash_distance(&original), None);
+    }
+
+    fn entry(name: &str, bundle_id: &str) -> RunningAppEntry {
+        RunningAppEntry {
+            name: name.to_string(),
+            bundle_id: bundle_id.to_string(),
+            pid: 1,
+            category: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_concealed_entries_store_no_content() {
+        let mut pasteboard = png_pasteboard(64, 64);
+        pasteboard.items.remove("public.utf8-plain-text");
+        pasteboard
+            .items
+            .insert("org.nspasteboard.ConcealedType".to_string(), Vec::new());
+        let png_length = pasteboard.items["public.png"].len();
+        let config = with_images(ClipboardConfig {
+            capture_thumbnails: true,
+            ..ClipboardConfig::default()
+        });
+
+        let event = read_event(
+            &pasteboard,
+            1,
+            Some(entry("Notes", "com.apple.Notes")),
+            &config,
+        );
+        assert_eq!(event.sensitive, Some(SensitiveReason::ConcealedType));
+        assert!(event.image.is_none());
+        assert_eq!(event.content_length, Some(png_length));
+        assert!(event
+            .types
+            .contains(&"org.nspasteboard.ConcealedType".to_string()));
+        // The length comes from the type's size; no data is ever read
+        assert!(pasteboard.queried.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_password_manager_source_is_sensitive() {
+        let mut items = HashMap::new();
+        items.insert("public.utf8-plain-text".to_string(), b"hunter2".to_vec());
+        let pasteboard = mock(items);
+        let config = ClipboardConfig::default();
+
+        let event = read_event(
+            &pasteboard,
+            1,
+            Some(entry("Bitwarden", "com.bitwarden.desktop")),
+            &config,
+        );
+        assert_eq!(event.sensitive, Some(SensitiveReason::PasswordManager));
+        assert_eq!(event.content_length, Some(7));
+
+        let event = read_event(
+            &pasteboard,
+            1,
+            Some(entry("Notes", "com.apple.Notes")),
+            &config,
+        );
+        assert_eq!(event.sensitive, None);
+        assert_eq!(event.content_length, None);
+    }
+
+    #[test]
+    fn test_disallowed_image_types_are_never_read() {
+        let pasteboard = png_pasteboard(64, 64);
+        let config = ClipboardConfig {
+            capture_thumbnails: true,
+            ..ClipboardConfig::default()
+        };
+        assert!(!config.allows_type("public.png"));
+
+        let event = read_event(
+            &pasteboard,
+            1,
+            Some(entry("Preview", "com.apple.Preview")),
+            &config,
+        );
+        assert!(event.image.is_none());
+        assert!(event.types.contains(&"public.png".to_string()));
+        assert!(!pasteboard
+            .queried
+            .borrow()
+            .iter()
+            .any(|t| IMAGE_TYPES.iter().any(|(image_type, _)| image_type == t)));
+
+        // Allowing images enables the summary
+        let event = read_event(&pasteboard, 1, None, &with_images(config));
+        assert_eq!(event.image.unwrap().format, "public.png");
+        assert!(pasteboard.queried.borrow().contains(&"public.png".to_string()));
+    }
+
+    /// Stands in for the frontmost window, which the user may change at any time
+    struct FrontWindow(Arc<Mutex<CopyContext>>);
+
+    impl CopyContextSource for FrontWindow {
+        fn capture(&mut self, _source: &RunningAppEntry) -> CopyContext {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    /// Switches the front window as soon as the pasteboard is read
+    struct SwitchOnRead {
+        inner: MockPasteboard,
+        front: Arc<Mutex<CopyContext>>,
+        switched_to: CopyContext,
+    }
+
+    impl PasteboardReader for SwitchOnRead {
+        fn change_count(&self) -> isize {
+            self.inner.change_count()
+        }
+
+        fn types(&self) -> Vec<String> {
+            *self.front.lock().unwrap() = self.switched_to.clone();
+            self.inner.types()
+        }
+
+        fn data_for_type(&self, pasteboard_type: &str) -> Option<Vec<u8>> {
+            self.inner.data_for_type(pasteboard_type)
+        }
+    }
+
+    #[test]
+    fn test_copy_carries_the_context_current_at_copy_time() {
+        let docs = CopyContext {
+            window_title: Some("Spec - Google Docs".to_string()),
+            url: Some("https://docs.google.com/document/d/1".to_string()),
+            file: None,
+            selection: Some("quoted text".to_string()),
+        };
+        let slack = CopyContext {
+            window_title: Some("general".to_string()),
+            ..CopyContext::default()
+        };
+        let front = Arc::new(Mutex::new(docs.clone()));
+        let mut items = HashMap::new();
+        items.insert(
+            "public.utf8-plain-text".to_string(),
+            b"quoted text".to_vec(),
+        );
+        let pasteboard = SwitchOnRead {
+            inner: mock(items),
+            front: Arc::clone(&front),
+            switched_to: slack.clone(),
+        };
+        let mut source = FrontWindow(Arc::clone(&front));
+        let config = ClipboardConfig::default();
+
+        let event = read_copy(
+            &pasteboard,
+            1,
+            Some(entry("Chrome", "com.google.Chrome")),
+            &config,
+            Some(&mut source),
+        );
+        assert_eq!(*front.lock().unwrap(), slack);
+        assert_eq!(event.context, Some(docs.clone()));
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            json["context"]["url"],
+            "https://docs.google.com/document/d/1"
+        );
+
+        // The selection of a secret is the secret
+        *front.lock().unwrap() = docs.clone();
+        let event = read_copy(
+            &pasteboard,
+            1,
+            Some(entry("Bitwarden", "com.bitwarden.desktop")),
+            &config,
+            Some(&mut source),
+        );
+        let context = event.context.unwrap();
+        assert_eq!(context.selection, None);
+        assert_eq!(context.window_title, docs.window_title);
+
+        let event = read_copy(&pasteboard, 1, None, &config, Some(&mut source));
+        assert_eq!(event.context, None);
+        assert!(!serde_json::to_string(&event).unwrap().contains("context"));
+    }
+}
@@ -0,0 +1,223 @@
+// src/core/compaction.rs
+//! Collapsing repeated JSONL records
+//!
+//! Resampling (on scroll, wake or Space changes) re-emits the current app
+//! even when nothing changed, so long idle stretches leave runs of records
+//! that differ only in their timestamps. Compaction keeps the first record of
+//! each run of consecutive records that agree on the key fields, and adds
+//! `repeat_count` and `last_timestamp` to it. Records that are not part of a
+//! run, and lines that are not JSON objects, are copied unchanged.
+
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use serde_json::Value;
+
+/// Fields that identify "the same context" by default, as dotted paths
+pub const DEFAULT_KEY_FIELDS: &[&str] = &[
+    "event_type",
+    "app.bundle_id",
+    "workspace.focused_title",
+    "workspace.primary_url",
+    "enhanced.front_window_title",
+];
+
+/// Counts from one compaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionReport {
+    pub records_in: usize,
+    pub records_out: usize,
+}
+
+/// Look up a dotted path such as `app.bundle_id`
+fn field<'a>(record: &'a Value, path: &str) -> &'a Value {
+    path.split('.')
+        .try_fold(record, |value, key| value.get(key))
+        .unwrap_or(&Value::Null)
+}
+
+fn key_of(record: &Value, keys: &[String]) -> Option<Vec<Value>> {
+    record
+        .is_object()
+        .then(|| keys.iter().map(|k| field(record, k).clone()).collect())
+}
+
+/// A run being collapsed: its first record, key and last timestamp
+struct Run {
+    first: Value,
+    key: Vec<Value>,
+    count: usize,
+    last_timestamp: Value,
+}
+
+impl Run {
+    fn finish(mut self) -> Value {
+        if self.count > 1 {
+            self.first["repeat_count"] = Value::from(self.count);
+            self.first["last_timestamp"] = self.last_timestamp;
+        }
+        self.first
+    }
+}
+
+/// Compact JSONL from `input` into `output`
+pub fn compact_jsonl<R: BufRead, W: Write>(
+    input: R,
+    mut output: W,
+    keys: &[String],
+) -> Result<CompactionReport, String> {
+    let mut report = CompactionReport {
+        records_in: 0,
+        records_out: 0,
+    };
+    let mut run: Option<Run> = None;
+    let mut emit = |line: &str, report: &mut CompactionReport| {
+        report.records_out += 1;
+        writeln!(output, "{}", line).map_err(|e| format!("Failed to write output: {}", e))
+    };
+
+    for line in input.lines() {
+        let line = line.map_err(|e| format!("Failed to read input: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        report.records_in += 1;
+
+        let record: Option<Value> = serde_json::from_str(&line).ok();
+        let key = record.as_ref().and_then(|r| key_of(r, keys));
+        match (record, key) {
+            (Some(record), Some(key)) => {
+                if let Some(current) = &mut run {
+                    if current.key == key {
+                        current.count += 1;
+                        current.last_timestamp = field(&record, "timestamp").clone();
+                        continue;
+                    }
+                }
+                if let Some(done) = run.take() {
+                    emit(&done.finish().to_string(), &mut report)?;
+                }
+                run = Some(Run {
+                    last_timestamp: field(&record, "timestamp").clone(),
+                    first: record,
+                    key,
+                    count: 1,
+                });
+            }
+            _ => {
+                if let Some(done) = run.take() {
+                    emit(&done.finish().to_string(), &mut report)?;
+                }
+                emit(&line, &mut report)?;
+            }
+        }
+    }
+    if let Some(done) = run.take() {
+        emit(&done.finish().to_string(), &mut report)?;
+    }
+    Ok(report)
+}
+
+/// Compact the file at `input` into a new file at `output`
+pub fn compact_file(
+    input: &Path,
+    output: &Path,
+    keys: &[String],
+) -> Result<CompactionReport, String> {
+    if input == output {
+        return Err("Output must differ from input".to_string());
+    }
+    let reader = std::fs::File::open(input)
+        .map(std::io::BufReader::new)
+        .map_err(|e| format!("Failed to open {}: {}", input.display(), e))?;
+    let writer = std::fs::File::create(output)
+        .map(std::io::BufWriter::new)
+        .map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+    compact_jsonl(reader, writer, keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_keys() -> Vec<String> {
+        DEFAULT_KEY_FIELDS.iter().map(|k| k.to_string()).collect()
+    }
+
+    fn record(second: u32, bundle_id: &str, title: &str) -> String {
+        serde_json::json!({
+            "timestamp": format!("2026-10-16T09:00:{:02}Z", second),
+            "event_type": "foreground",
+            "app": { "name": "App", "bundle_id": bundle_id, "pid": 42 },
+            "workspace": { "window_count": 1, "focused_title": title, "primary_url": null },
+            "confidence": 0.85
+        })
+        .to_string()
+    }
+
+    fn compact(lines: &[String], keys: &[String]) -> (Vec<Value>, CompactionReport) {
+        let input = lines.join("\n");
+        let mut output = Vec::new();
+        let report = compact_jsonl(input.as_bytes(), &mut output, keys).unwrap();
+        let records = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        (records, report)
+    }
+
+    #[test]
+    fn test_run_of_five_compacts_to_one() {
+        let mut lines: Vec<String> = (0..5)
+            .map(|s| record(s, "com.apple.Safari", "Docs"))
+            .collect();
+        lines.push(record(10, "com.apple.Terminal", "zsh"));
+
+        let (records, report) = compact(&lines, &default_keys());
+        assert_eq!(
+            report,
+            CompactionReport {
+                records_in: 6,
+                records_out: 2
+            }
+        );
+        assert_eq!(records[0]["repeat_count"], 5);
+        assert_eq!(records[0]["timestamp"], "2026-10-16T09:00:00Z");
+        assert_eq!(records[0]["last_timestamp"], "2026-10-16T09:00:04Z");
+        assert!(records[1].get("repeat_count").is_none());
+    }
+
+    #[test]
+    fn test_key_fields_are_configurable_and_runs_are_consecutive() {
+        let lines = vec![
+            record(0, "com.apple.Safari", "Docs"),
+            record(1, "com.apple.Safari", "Mail"),
+            record(2, "com.apple.Terminal", "zsh"),
+            record(3, "com.apple.Safari", "Docs"),
+        ];
+
+        // By default a title change breaks the run
+        let (records, _) = compact(&lines, &default_keys());
+        assert_eq!(records.len(), 4);
+
+        // Keyed on the app alone, the first two merge but the last does not
+        let (records, _) = compact(&lines, &["app.bundle_id".to_string()]);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["repeat_count"], 2);
+    }
+
+    #[test]
+    fn test_non_json_lines_are_kept_and_break_runs() {
+        let lines = [
+            record(0, "com.apple.Safari", "Docs"),
+            "not json".to_string(),
+            record(1, "com.apple.Safari", "Docs"),
+        ];
+        let input = lines.join("\n");
+        let mut output = Vec::new();
+        let report = compact_jsonl(input.as_bytes(), &mut output, &default_keys()).unwrap();
+        assert_eq!(report.records_out, 3);
+        assert!(String::from_utf8(output).unwrap().contains("\nnot json\n"));
+    }
+}
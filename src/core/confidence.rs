@@ -0,0 +1,143 @@
+// src/core/confidence.rs
+//! Gating events on how sure the sources are that a switch happened
+//!
+//! Every event from the built-in sources carries a confidence in `0.0..=1.0`
+//! that reflects how the switch was observed:
+//!
+//! | Confidence | Observation |
+//! |-----------:|-------------|
+//! | 1.0  | A direct NSWorkspace notification (activate, launch, terminate, hide, unhide) |
+//! | 0.95 | A deactivate/activate pair merged because they arrived within 250ms |
+//! | 0.9  | A frontmost change found by polling, or a deactivate with no matching activate |
+//! | 0.85 | The frontmost app resampled after a Space change, session change or wake |
+//!
+//! When the coalescer merges several updates into one event, the highest
+//! confidence wins. Events injected without a confidence are never filtered.
+
+use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchListener};
+
+/// Parse a confidence threshold, which must lie in `0.0..=1.0`
+pub fn parse_confidence(s: &str) -> Result<f32, String> {
+    let value: f32 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid confidence '{}' (expected a number)", s))?;
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!(
+            "Confidence {} is out of range (expected 0.0 to 1.0)",
+            value
+        ))
+    }
+}
+
+/// Drops events below a minimum confidence, optionally handing them to a sink
+pub struct ConfidenceFilter {
+    min_confidence: f32,
+    low_confidence_sink: Option<Box<dyn AppSwitchListener>>,
+}
+
+impl Default for ConfidenceFilter {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+impl ConfidenceFilter {
+    pub fn new(min_confidence: f32) -> Self {
+        Self {
+            min_confidence,
+            low_confidence_sink: None,
+        }
+    }
+
+    /// Route rejected events to `sink` instead of dropping them
+    ///
+    /// The sink sees events as they leave the coalescer, before title fusion
+    /// and enrichment.
+    pub fn with_low_confidence_sink<L: AppSwitchListener + 'static>(mut self, sink: L) -> Self {
+        self.low_confidence_sink = Some(Box::new(sink));
+        self
+    }
+
+    pub fn min_confidence(&self) -> f32 {
+        self.min_confidence
+    }
+
+    /// Whether `event` meets the threshold
+    pub fn passes(&self, event: &AppSwitchEvent) -> bool {
+        event
+            .confidence
+            .map_or(true, |confidence| confidence >= self.min_confidence)
+    }
+
+    /// Pass `event` through, or hand it to the low-confidence sink and reject it
+    pub fn admit(&mut self, event: &AppSwitchEvent) -> bool {
+        if self.passes(event) {
+            return true;
+        }
+        if let Some(sink) = &mut self.low_confidence_sink {
+            sink.on_app_switch(event);
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType};
+    use std::sync::{Arc, Mutex};
+
+    struct Recorder(Arc<Mutex<Vec<Option<f32>>>>);
+
+    impl AppSwitchListener for Recorder {
+        fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+            self.0.lock().unwrap().push(event.confidence);
+        }
+    }
+
+    fn event(confidence: Option<f32>) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 42),
+        );
+        event.confidence = confidence;
+        event
+    }
+
+    #[test]
+    fn test_only_events_at_or_above_threshold_pass() {
+        let low = Arc::new(Mutex::new(Vec::new()));
+        let mut filter = ConfidenceFilter::new(0.9).with_low_confidence_sink(Recorder(low.clone()));
+
+        let passed: Vec<Option<f32>> = [Some(1.0), Some(0.95), Some(0.9), Some(0.85), None]
+            .into_iter()
+            .map(event)
+            .filter(|e| filter.admit(e))
+            .map(|e| e.confidence)
+            .collect();
+
+        assert_eq!(passed, vec![Some(1.0), Some(0.95), Some(0.9), None]);
+        assert_eq!(*low.lock().unwrap(), vec![Some(0.85)]);
+    }
+
+    #[test]
+    fn test_threshold_must_be_in_range() {
+        assert_eq!(parse_confidence("0.9"), Ok(0.9));
+        assert_eq!(parse_confidence("0"), Ok(0.0));
+        assert_eq!(parse_confidence("1.0"), Ok(1.0));
+        assert!(parse_confidence("1.5").is_err());
+        assert!(parse_confidence("-0.1").is_err());
+        assert!(parse_confidence("NaN").is_err());
+        assert!(parse_confidence("high").is_err());
+    }
+
+    #[test]
+    fn test_default_passes_everything() {
+        let filter = ConfidenceFilter::default();
+        assert!(filter.passes(&event(Some(0.0))));
+        assert!(filter.passes(&event(Some(0.85))));
+    }
+}
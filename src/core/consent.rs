@@ -0,0 +1,358 @@
+// src/core/consent.rs
+//! Consent for content capture
+//!
+//! Titles, URLs, clipboard contents, selected text and screenshots can
+//! reveal what the user reads and writes. Each of these scopes needs
+//! recorded consent; for a scope without it the tracker runs in
+//! category/timing-only mode, keeping the app, its category and timing and
+//! dropping the content, whatever the other flags ask for. Consent is
+//! recorded in a JSON file with the time it was given and the scopes it
+//! covers, by answering the first-run prompt or by `--grant-consent`. Like
+//! quiet hours, the gate runs as a redaction stage so no logger or derived
+//! value sees content it may not record.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::clipboard::{ClipboardEvent, ClipboardListener};
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+
+/// Where consent is recorded unless `--consent-file` says otherwise,
+/// relative to the home directory
+const DEFAULT_CONSENT_FILE: &str =
+    "Library/Application Support/research-assistant-tracker/consent.json";
+
+/// A kind of content that needs consent before it is captured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentScope {
+    /// Page URLs and their domains
+    Urls,
+    /// Window, tab, document and menu titles, file paths and extractor output
+    Titles,
+    /// Copied images and their hashes
+    Clipboard,
+    /// Text of the focused element and the selection
+    Selection,
+    /// Screenshots of the focused window
+    Screenshots,
+}
+
+impl ConsentScope {
+    pub const ALL: [ConsentScope; 5] = [
+        ConsentScope::Urls,
+        ConsentScope::Titles,
+        ConsentScope::Clipboard,
+        ConsentScope::Selection,
+        ConsentScope::Screenshots,
+    ];
+}
+
+impl fmt::Display for ConsentScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsentScope::Urls => write!(f, "urls"),
+            ConsentScope::Titles => write!(f, "titles"),
+            ConsentScope::Clipboard => write!(f, "clipboard"),
+            ConsentScope::Selection => write!(f, "selection"),
+            ConsentScope::Screenshots => write!(f, "screenshots"),
+        }
+    }
+}
+
+impl FromStr for ConsentScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "urls" | "url" => Ok(ConsentScope::Urls),
+            "titles" | "title" => Ok(ConsentScope::Titles),
+            "clipboard" => Ok(ConsentScope::Clipboard),
+            "selection" => Ok(ConsentScope::Selection),
+            "screenshots" | "screenshot" => Ok(ConsentScope::Screenshots),
+            other => Err(format!(
+                "Unknown consent scope '{}' (expected urls, titles, clipboard, selection or screenshots)",
+                other
+            )),
+        }
+    }
+}
+
+/// The acknowledgment stored in the consent file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsentRecord {
+    pub acknowledged_at: DateTime<Utc>,
+    pub scopes: Vec<ConsentScope>,
+}
+
+impl ConsentRecord {
+    pub fn new(scopes: impl IntoIterator<Item = ConsentScope>, at: DateTime<Utc>) -> Self {
+        let mut record = Self {
+            acknowledged_at: at,
+            scopes: Vec::new(),
+        };
+        for scope in scopes {
+            if !record.scopes.contains(&scope) {
+                record.scopes.push(scope);
+            }
+        }
+        record
+    }
+
+    /// Read a recorded consent; a missing file means none was given
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read consent {}: {}", path.display(), e)),
+        };
+        serde_json::from_str(&source)
+            .map(Some)
+            .map_err(|e| format!("Invalid consent file {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to encode consent: {}", e))?;
+        std::fs::write(path, json)
+            .map_err(|e| format!("Failed to write consent {}: {}", path.display(), e))
+    }
+}
+
+/// The consent file in the user's Application Support directory
+pub fn default_consent_path() -> Option<PathBuf> {
+    Some(PathBuf::from(std::env::var_os("HOME")?).join(DEFAULT_CONSENT_FILE))
+}
+
+/// Drops content the user has not consented to from events
+#[derive(Debug, Clone, Default)]
+pub struct ContentConsent {
+    scopes: Vec<ConsentScope>,
+}
+
+impl ContentConsent {
+    /// Consent as recorded; no record allows no content at all
+    pub fn from_record(record: Option<&ConsentRecord>) -> Self {
+        Self {
+            scopes: record.map(|r| r.scopes.clone()).unwrap_or_default(),
+        }
+    }
+
+    pub fn allows(&self, scope: ConsentScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    /// Only apps, categories and timing are recorded
+    pub fn is_timing_only(&self) -> bool {
+        self.scopes.is_empty()
+    }
+
+    /// Clear the URLs, titles and screenshot of an event unless their scope
+    /// is allowed
+    pub fn redact_event(&self, event: &mut AppSwitchEvent) {
+        let urls = self.allows(ConsentScope::Urls);
+        let titles = self.allows(ConsentScope::Titles);
+        if let Some(ws) = &mut event.workspace {
+            if !urls {
+                ws.primary_url = None;
+            }
+            if !titles {
+                ws.focused_title = None;
+                ws.tab_titles.clear();
+                ws.active_file_paths.clear();
+            }
+        }
+        if let Some(enh) = &mut event.enhanced {
+            if !urls {
+                enh.url = None;
+                enh.domain = None;
+            }
+            if !titles {
+                enh.front_window_title = None;
+                enh.tab_title = None;
+                enh.browser_profile = None;
+                enh.menu_context = None;
+                enh.extracted_context.clear();
+            }
+        }
+        if !titles {
+            for window in event.windows.iter_mut().flatten() {
+                window.title = None;
+            }
+        }
+        if !self.allows(ConsentScope::Screenshots) {
+            event.screenshot_path = None;
+        }
+    }
+
+    /// Clear a copy's image content and the parts of its source context
+    /// whose scope is not allowed
+    pub fn redact_clipboard(&self, event: &mut ClipboardEvent) {
+        if !self.allows(ConsentScope::Clipboard) {
+            if let Some(image) = &mut event.image {
+                image.thumbnail_base64 = None;
+                image.thumbnail_width = None;
+                image.thumbnail_height = None;
+                image.dhash = None;
+            }
+        }
+        if let Some(context) = &mut event.context {
+            if !self.allows(ConsentScope::Urls) {
+                context.url = None;
+            }
+            if !self.allows(ConsentScope::Titles) {
+                context.window_title = None;
+                context.file = None;
+            }
+            if !self.allows(ConsentScope::Selection) {
+                context.selection = None;
+            }
+        }
+        if event.context.as_ref().is_some_and(|c| c.is_empty()) {
+            event.context = None;
+        }
+    }
+
+    /// Wrap a clipboard listener so it only sees consented content
+    pub fn clipboard_listener<L: ClipboardListener>(
+        &self,
+        inner: L,
+    ) -> ConsentClipboardListener<L> {
+        ConsentClipboardListener {
+            consent: self.clone(),
+            inner,
+        }
+    }
+}
+
+impl EnrichmentStage for ContentConsent {
+    fn name(&self) -> &str {
+        "content_consent"
+    }
+
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Redact
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        self.redact_event(event);
+    }
+}
+
+/// Clipboard listener behind a `ContentConsent`
+pub struct ConsentClipboardListener<L> {
+    consent: ContentConsent,
+    inner: L,
+}
+
+impl<L: ClipboardListener> ClipboardListener for ConsentClipboardListener<L> {
+    fn on_clipboard_change(&mut self, event: &ClipboardEvent) {
+        let mut event = event.clone();
+        self.consent.redact_clipboard(&mut event);
+        self.inner.on_clipboard_change(&event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{
+        AppInfo, AppSwitchType, EnhancedSummary, WorkspaceSummary,
+    };
+    use crate::core::clipboard::CopyContext;
+
+    fn full_event() -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 7),
+        );
+        event.workspace = Some(WorkspaceSummary {
+            window_count: 1,
+            focused_title: Some("Quarterly review".to_string()),
+            total_screen_coverage: None,
+            is_fullscreen: None,
+            is_minimized: None,
+            tab_titles: vec!["Quarterly review".to_string()],
+            active_file_paths: vec!["/Users/ada/review.key".to_string()],
+            primary_url: Some("https://intranet.example.com/review".to_string()),
+        });
+        event.enhanced = Some(EnhancedSummary {
+            front_window_title: Some("Quarterly review".to_string()),
+            url: Some("https://intranet.example.com/review".to_string()),
+            domain: Some("example.com".to_string()),
+            menu_context: Some("File › Export".to_string()),
+            ..EnhancedSummary::default()
+        });
+        event
+    }
+
+    #[test]
+    fn test_content_is_suppressed_without_recorded_consent() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("consent.json");
+
+        // Nothing recorded: timing only, whatever the capture flags asked for
+        let record = ConsentRecord::load(&path).unwrap();
+        assert!(record.is_none());
+        let mut consent = ContentConsent::from_record(record.as_ref());
+        assert!(consent.is_timing_only());
+        assert!(ConsentScope::ALL.iter().all(|s| !consent.allows(*s)));
+
+        let mut event = full_event();
+        consent.enrich(&mut event);
+        let ws = event.workspace.as_ref().unwrap();
+        assert_eq!(ws.focused_title, None);
+        assert_eq!(ws.primary_url, None);
+        assert!(ws.tab_titles.is_empty() && ws.active_file_paths.is_empty());
+        let enh = event.enhanced.as_ref().unwrap();
+        assert_eq!(enh.url, None);
+        assert_eq!(enh.domain, None);
+        assert_eq!(enh.front_window_title, None);
+        assert_eq!(enh.menu_context, None);
+        // App and timing are kept
+        assert_eq!(event.app_info.bundle_id, "com.apple.Safari");
+
+        let mut copy = ClipboardEvent {
+            timestamp: Utc::now(),
+            change_count: 3,
+            types: vec!["public.utf8-plain-text".to_string()],
+            source_app: Some("Safari".to_string()),
+            source_bundle_id: Some("com.apple.Safari".to_string()),
+            image: None,
+            sensitive: None,
+            content_length: None,
+            context: Some(CopyContext {
+                window_title: Some("Quarterly review".to_string()),
+                url: Some("https://intranet.example.com/review".to_string()),
+                file: None,
+                selection: Some("Revenue fell".to_string()),
+            }),
+        };
+        consent.redact_clipboard(&mut copy);
+        assert_eq!(copy.context, None);
+        assert_eq!(copy.source_app.as_deref(), Some("Safari"));
+
+        // Recording consent for URLs keeps them; titles stay suppressed
+        let scopes = "urls".split(',').map(|s| s.parse().unwrap());
+        ConsentRecord::new(scopes, Utc::now()).save(&path).unwrap();
+        let record = ConsentRecord::load(&path).unwrap().unwrap();
+        assert_eq!(record.scopes, vec![ConsentScope::Urls]);
+        let mut event = full_event();
+        ContentConsent::from_record(Some(&record)).redact_event(&mut event);
+        let ws = event.workspace.as_ref().unwrap();
+        assert_eq!(
+            ws.primary_url.as_deref(),
+            Some("https://intranet.example.com/review")
+        );
+        assert_eq!(ws.focused_title, None);
+    }
+}
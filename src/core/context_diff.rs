@@ -0,0 +1,271 @@
+// src/core/context_diff.rs
+//! What changed between consecutive foreground switches
+//!
+//! Consumers that track changes do not need the full context on every event.
+//! A `ContextDiff` lists only the fields that differ from the previous
+//! foreground event, with their old and new values. The first event is
+//! diffed against an empty context, so it carries everything known.
+//!
+//! JSON output of an event with a diff leaves the diffable fields out of the
+//! workspace and enhanced summaries (see `without_diffed_fields`): what
+//! changed is in the diff, and what did not is in an earlier event.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+
+/// The diffable fields of a switch event
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextSnapshot {
+    pub app: Option<String>,
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub file: Option<String>,
+    pub space: Option<String>,
+}
+
+impl ContextSnapshot {
+    /// Collect the fields from whichever summaries the event carries
+    pub fn of(event: &AppSwitchEvent) -> Self {
+        let ws = event.workspace.as_ref();
+        let enh = event.enhanced.as_ref();
+        Self {
+            app: Some(event.app_info.bundle_id.clone()).filter(|id| !id.is_empty()),
+            url: enh
+                .and_then(|e| e.url.clone())
+                .or_else(|| ws.and_then(|w| w.primary_url.clone())),
+            title: ws
+                .and_then(|w| w.focused_title.clone())
+                .or_else(|| enh.and_then(|e| e.front_window_title.clone())),
+            file: ws.and_then(|w| w.active_file_paths.first().cloned()),
+            space: enh.and_then(|e| {
+                e.space_label
+                    .clone()
+                    .or_else(|| e.space_id.map(|id| id.to_string()))
+            }),
+        }
+    }
+
+    fn fields(&self) -> [(&'static str, &Option<String>); 5] {
+        [
+            ("app", &self.app),
+            ("url", &self.url),
+            ("title", &self.title),
+            ("file", &self.file),
+            ("space", &self.space),
+        ]
+    }
+}
+
+/// One changed field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Fields that changed between two snapshots
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ContextDiff {
+    pub changes: Vec<FieldChange>,
+}
+
+impl ContextDiff {
+    pub fn between(old: &ContextSnapshot, new: &ContextSnapshot) -> Self {
+        let changes = old
+            .fields()
+            .into_iter()
+            .zip(new.fields())
+            .filter(|((_, before), (_, after))| before != after)
+            .map(|((field, before), (_, after))| FieldChange {
+                field,
+                old: before.clone(),
+                new: after.clone(),
+            })
+            .collect();
+        Self { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn changed_fields(&self) -> Vec<&'static str> {
+        self.changes.iter().map(|c| c.field).collect()
+    }
+}
+
+/// A copy of `event` for serializing next to its diff: the url, title, file
+/// and space fields are cleared from the summaries, since the diff carries
+/// those that changed
+pub fn without_diffed_fields(event: &AppSwitchEvent) -> AppSwitchEvent {
+    let mut event = event.clone();
+    if let Some(ws) = &mut event.workspace {
+        ws.focused_title = None;
+        ws.primary_url = None;
+        ws.tab_titles.clear();
+        ws.active_file_paths.clear();
+    }
+    if let Some(enh) = &mut event.enhanced {
+        enh.url = None;
+        enh.domain = None;
+        enh.front_window_title = None;
+        enh.tab_title = None;
+        enh.space_id = None;
+        enh.space_uuid = None;
+        enh.space_index = None;
+        enh.space_type = None;
+        enh.space_name = None;
+        enh.space_label = None;
+    }
+    event
+}
+
+/// Attaches a diff against the previous foreground event
+#[derive(Default)]
+pub struct ContextDiffer {
+    last: ContextSnapshot,
+}
+
+impl ContextDiffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl EnrichmentStage for ContextDiffer {
+    fn name(&self) -> &str {
+        "context_diff"
+    }
+
+    /// Runs last so the diff shows the values consumers actually receive
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Derive
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        if !event.event_type.is_foreground() {
+            return;
+        }
+        if event.redacted {
+            // The next event carries everything again
+            self.last = ContextSnapshot::default();
+            return;
+        }
+        let snapshot = ContextSnapshot::of(event);
+        event.diff = Some(ContextDiff::between(&self.last, &snapshot));
+        self.last = snapshot;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(url: &str, title: &str, file: Option<&str>) -> ContextSnapshot {
+        ContextSnapshot {
+            app: Some("com.microsoft.VSCode".to_string()),
+            url: Some(url.to_string()),
+            title: Some(title.to_string()),
+            file: file.map(str::to_string),
+            space: Some("Desktop 2".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_diff_lists_exactly_changed_fields() {
+        let old = snapshot("https://a.example", "main.rs", Some("/src/main.rs"));
+        let new = snapshot("https://a.example", "lib.rs", None);
+
+        let diff = ContextDiff::between(&old, &new);
+        assert_eq!(diff.changed_fields(), vec!["title", "file"]);
+        assert_eq!(
+            diff.changes[1],
+            FieldChange {
+                field: "file",
+                old: Some("/src/main.rs".to_string()),
+                new: None,
+            }
+        );
+
+        assert!(ContextDiff::between(&new, &new.clone()).is_empty());
+        assert_eq!(
+            ContextDiff::between(&ContextSnapshot::default(), &new).changed_fields(),
+            vec!["app", "url", "title", "space"]
+        );
+    }
+
+    #[test]
+    fn test_serialized_event_carries_only_the_diff() {
+        use crate::core::app_switcher_types::{
+            AppInfo, AppSwitchType, EnhancedSummary, WorkspaceSummary,
+        };
+
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 1),
+        );
+        event.workspace = Some(WorkspaceSummary {
+            window_count: 2,
+            focused_title: Some("Docs".to_string()),
+            total_screen_coverage: None,
+            is_fullscreen: None,
+            is_minimized: None,
+            tab_titles: vec!["Docs".to_string()],
+            active_file_paths: Vec::new(),
+            primary_url: Some("https://example.com/docs".to_string()),
+        });
+        event.enhanced = Some(EnhancedSummary {
+            url: Some("https://example.com/docs".to_string()),
+            space_label: Some("Desktop 2".to_string()),
+            cpu_usage: Some(3.5),
+            ..EnhancedSummary::default()
+        });
+
+        let mut differ = ContextDiffer::new();
+        differ.enrich(&mut event);
+        let compact = without_diffed_fields(&event);
+
+        let ws = compact.workspace.unwrap();
+        assert_eq!((ws.focused_title, ws.primary_url), (None, None));
+        assert!(ws.tab_titles.is_empty());
+        assert_eq!(ws.window_count, 2);
+        let enh = compact.enhanced.unwrap();
+        assert_eq!((enh.url, enh.space_label), (None, None));
+        assert_eq!(enh.cpu_usage, Some(3.5));
+        // The values themselves are in the diff
+        assert_eq!(
+            compact.diff.unwrap().changed_fields(),
+            vec!["app", "url", "title", "space"]
+        );
+    }
+
+    #[test]
+    fn test_redacted_events_get_no_diff_and_reset_the_baseline() {
+        use crate::core::app_switcher_types::{AppInfo, AppSwitchType, EnhancedSummary};
+
+        let safari = AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 1);
+        let page = |url: &str| {
+            let mut event = AppSwitchEvent::new(AppSwitchType::Foreground, safari.clone());
+            event.enhanced = Some(EnhancedSummary {
+                url: Some(url.to_string()),
+                ..EnhancedSummary::default()
+            });
+            event
+        };
+        let mut differ = ContextDiffer::new();
+        differ.enrich(&mut page("https://example.com/a"));
+
+        let mut quiet = page("https://example.com/a");
+        quiet.redacted = true;
+        differ.enrich(&mut quiet);
+        assert!(quiet.diff.is_none());
+
+        let mut after = page("https://example.com/a");
+        differ.enrich(&mut after);
+        assert_eq!(after.diff.unwrap().changed_fields(), vec!["app", "url"]);
+    }
+}
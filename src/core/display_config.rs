@@ -0,0 +1,360 @@
+// src/core/display_config.rs
+//! Display configuration over time
+//!
+//! Docking and undocking a laptop, mirroring for a presentation or moving a
+//! monitor in the Displays settings change where windows can live, which
+//! explains many bursts of window moves and switches. Quartz calls the
+//! reconfiguration callback once before a change (with the begin flag) and
+//! then once per affected display, often several times over while a display
+//! is connected. The calls are collected into a `ReconfigurationBurst` and,
+//! once none arrived for `RECONFIGURATION_SETTLE`, become a single
+//! `DisplayConfigChanged` carrying the displays involved, what changed, and
+//! the complete arrangement afterwards.
+
+use std::ffi::c_void;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_enhanced::CGDisplayRect;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGDisplayRegisterReconfigurationCallback(
+        callback: DisplayReconfigurationCallback,
+        user_info: *mut c_void,
+    ) -> i32;
+    fn CGDisplayRemoveReconfigurationCallback(
+        callback: DisplayReconfigurationCallback,
+        user_info: *mut c_void,
+    ) -> i32;
+    fn CGGetActiveDisplayList(
+        max_displays: u32,
+        active_displays: *mut u32,
+        display_count: *mut u32,
+    ) -> i32;
+    fn CGMainDisplayID() -> u32;
+    fn CGDisplayBounds(display: u32) -> CGDisplayRect;
+    fn CGDisplayPixelsWide(display: u32) -> usize;
+    fn CGDisplayPixelsHigh(display: u32) -> usize;
+    fn CGDisplayIsBuiltin(display: u32) -> u32;
+}
+
+type DisplayReconfigurationCallback =
+    extern "C" fn(display: u32, flags: u32, user_info: *mut c_void);
+
+/// Most displays read from the active display list
+const MAX_DISPLAYS: usize = 16;
+
+/// Quiet time after a callback before the reconfiguration is reported
+pub const RECONFIGURATION_SETTLE: Duration = Duration::from_millis(500);
+
+// CGDisplayChangeSummaryFlags
+const BEGIN_CONFIGURATION_FLAG: u32 = 1 << 0;
+
+/// Names of the change flags, in the order they are reported
+const CHANGE_FLAGS: &[(u32, &str)] = &[
+    (1 << 1, "moved"),
+    (1 << 2, "set_main"),
+    (1 << 3, "set_mode"),
+    (1 << 4, "added"),
+    (1 << 5, "removed"),
+    (1 << 8, "enabled"),
+    (1 << 9, "disabled"),
+    (1 << 10, "mirrored"),
+    (1 << 11, "unmirrored"),
+    (1 << 12, "desktop_shape_changed"),
+];
+
+/// One active display and where it sits in the global coordinate space
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayInfo {
+    pub display_id: u32,
+    /// Origin in points; the main display's top-left corner is 0,0
+    pub x: f64,
+    pub y: f64,
+    /// Size in points
+    pub width: f64,
+    pub height: f64,
+    /// Resolution of the current mode in pixels
+    pub pixel_width: usize,
+    pub pixel_height: usize,
+    pub is_main: bool,
+    pub is_builtin: bool,
+}
+
+/// The display setup changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayConfigChanged {
+    pub timestamp: DateTime<Utc>,
+    /// The displays the change was reported for
+    pub display_ids: Vec<u32>,
+    /// What changed on any of them, e.g. `added`, `removed`, `moved`
+    pub changes: Vec<String>,
+    pub display_count: u32,
+    /// Every active display after the change
+    pub displays: Vec<DisplayInfo>,
+}
+
+/// The callbacks of one reconfiguration, merged into one event
+#[derive(Debug, Default)]
+pub struct ReconfigurationBurst {
+    display_ids: Vec<u32>,
+    flags: u32,
+}
+
+impl ReconfigurationBurst {
+    /// Add the parameters of one callback; the call announcing that a
+    /// change is about to begin carries no change
+    pub fn push(&mut self, display_id: u32, flags: u32) {
+        if flags & BEGIN_CONFIGURATION_FLAG != 0 {
+            return;
+        }
+        if !self.display_ids.contains(&display_id) {
+            self.display_ids.push(display_id);
+        }
+        self.flags |= flags;
+    }
+
+    /// The merged event, leaving the burst empty; `None` if no change was
+    /// reported
+    pub fn take(
+        &mut self,
+        displays: Vec<DisplayInfo>,
+        timestamp: DateTime<Utc>,
+    ) -> Option<DisplayConfigChanged> {
+        let burst = std::mem::take(self);
+        if burst.display_ids.is_empty() {
+            return None;
+        }
+        let changes = CHANGE_FLAGS
+            .iter()
+            .filter(|(flag, _)| burst.flags & flag != 0)
+            .map(|(_, name)| name.to_string())
+            .collect();
+        Some(DisplayConfigChanged {
+            timestamp,
+            display_ids: burst.display_ids,
+            changes,
+            display_count: displays.len() as u32,
+            displays,
+        })
+    }
+}
+
+/// The active displays and their arrangement
+pub fn current_displays() -> Vec<DisplayInfo> {
+    let mut ids = [0u32; MAX_DISPLAYS];
+    let mut count: u32 = 0;
+    // SAFETY: `ids` has room for MAX_DISPLAYS entries
+    let rc = unsafe { CGGetActiveDisplayList(MAX_DISPLAYS as u32, ids.as_mut_ptr(), &mut count) };
+    if rc != 0 {
+        return Vec::new();
+    }
+    // SAFETY: plain queries on display IDs the system just returned
+    let main = unsafe { CGMainDisplayID() };
+    ids.iter()
+        .take(count as usize)
+        .map(|&display_id| unsafe {
+            let bounds = CGDisplayBounds(display_id);
+            DisplayInfo {
+                display_id,
+                x: bounds.origin.x,
+                y: bounds.origin.y,
+                width: bounds.size.width,
+                height: bounds.size.height,
+                pixel_width: CGDisplayPixelsWide(display_id),
+                pixel_height: CGDisplayPixelsHigh(display_id),
+                is_main: display_id == main,
+                is_builtin: CGDisplayIsBuiltin(display_id) != 0,
+            }
+        })
+        .collect()
+}
+
+/// Receives display configuration changes
+pub trait DisplayConfigListener: Send {
+    fn on_display_config_changed(&mut self, event: &DisplayConfigChanged);
+}
+
+type Listeners = Mutex<Vec<Box<dyn DisplayConfigListener>>>;
+
+/// Where callbacks send their parameters; `None` once stopped
+type CallbackSender = Mutex<Option<Sender<(u32, u32)>>>;
+
+extern "C" fn reconfiguration_callback(display: u32, flags: u32, user_info: *mut c_void) {
+    // SAFETY: `user_info` is the sender registered by `start`, kept alive by
+    // the observer until the callback is removed
+    let sender = unsafe { &*(user_info as *const CallbackSender) };
+    if let Ok(sender) = sender.lock() {
+        if let Some(sender) = sender.as_ref() {
+            let _ = sender.send((display, flags));
+        }
+    }
+}
+
+/// Merge callbacks into bursts and report each once it settles
+fn report_bursts(callbacks: mpsc::Receiver<(u32, u32)>, listeners: &Listeners) {
+    while let Ok((display, flags)) = callbacks.recv() {
+        let mut burst = ReconfigurationBurst::default();
+        burst.push(display, flags);
+        loop {
+            match callbacks.recv_timeout(RECONFIGURATION_SETTLE) {
+                Ok((display, flags)) => burst.push(display, flags),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        let Some(event) = burst.take(current_displays(), Utc::now()) else {
+            continue;
+        };
+        if let Ok(mut listeners) = listeners.lock() {
+            for listener in listeners.iter_mut() {
+                listener.on_display_config_changed(&event);
+            }
+        }
+    }
+}
+
+/// Watches for display reconfiguration
+///
+/// Callbacks are delivered on the main thread's run loop, so `start` must be
+/// called from the main thread while that loop is running. Listeners are
+/// called on a background thread once a reconfiguration settles.
+pub struct DisplayConfigObserver {
+    listeners: Arc<Listeners>,
+    sender: Arc<CallbackSender>,
+    registered: bool,
+}
+
+impl DisplayConfigObserver {
+    pub fn new() -> Self {
+        Self {
+            listeners: Arc::new(Mutex::new(Vec::new())),
+            sender: Arc::new(Mutex::new(None)),
+            registered: false,
+        }
+    }
+
+    pub fn add_listener<T: DisplayConfigListener + 'static>(&self, listener: T) {
+        self.listeners.lock().unwrap().push(Box::new(listener));
+    }
+
+    fn user_info(&self) -> *mut c_void {
+        Arc::as_ptr(&self.sender) as *mut c_void
+    }
+
+    pub fn start(&mut self) -> Result<(), String> {
+        if self.registered {
+            return Ok(());
+        }
+        let (sender, callbacks) = mpsc::channel();
+        *self.sender.lock().unwrap() = Some(sender);
+        // SAFETY: the sender outlives the registration, which `stop` (also
+        // run on drop) removes
+        let rc = unsafe {
+            CGDisplayRegisterReconfigurationCallback(reconfiguration_callback, self.user_info())
+        };
+        if rc != 0 {
+            self.sender.lock().unwrap().take();
+            return Err(format!(
+                "Failed to register display reconfiguration callback (error {})",
+                rc
+            ));
+        }
+        let listeners = Arc::clone(&self.listeners);
+        std::thread::spawn(move || report_bursts(callbacks, &listeners));
+        self.registered = true;
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        if !self.registered {
+            return;
+        }
+        // SAFETY: removes the registration made by `start` with the same arguments
+        unsafe {
+            CGDisplayRemoveReconfigurationCallback(reconfiguration_callback, self.user_info());
+        }
+        // Ends the reporting thread
+        self.sender.lock().unwrap().take();
+        self.registered = false;
+    }
+}
+
+impl Default for DisplayConfigObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for DisplayConfigObserver {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(display_id: u32, x: f64, width: f64, is_main: bool) -> DisplayInfo {
+        DisplayInfo {
+            display_id,
+            x,
+            y: 0.0,
+            width,
+            height: 900.0,
+            pixel_width: (width * 2.0) as usize,
+            pixel_height: 1800,
+            is_main,
+            is_builtin: display_id == 1,
+        }
+    }
+
+    #[test]
+    fn test_callbacks_of_one_reconfiguration_merge_into_one_event() {
+        let now = Utc::now();
+        let displays = vec![
+            display(1, 0.0, 1440.0, true),
+            display(7, 1440.0, 2560.0, false),
+        ];
+
+        // The begin call only announces the change
+        let mut burst = ReconfigurationBurst::default();
+        burst.push(7, 0x1);
+        assert_eq!(burst.take(displays.clone(), now), None);
+
+        // An external display plugged in to the right of the laptop: the
+        // laptop's display moves, the new one is added, in several calls
+        burst.push(1, 0x1);
+        burst.push(1, 1 << 1);
+        burst.push(7, (1 << 4) | (1 << 12));
+        burst.push(7, 1 << 1);
+        let event = burst.take(displays, now).unwrap();
+        assert_eq!(event.display_ids, vec![1, 7]);
+        assert_eq!(
+            event.changes,
+            vec!["moved", "added", "desktop_shape_changed"]
+        );
+        assert_eq!(event.display_count, 2);
+        assert_eq!(event.displays[1].x, 1440.0);
+        assert_eq!(event.displays[1].pixel_width, 5120);
+        assert_eq!(event.timestamp, now);
+
+        // Undocking: the external display is gone
+        burst.push(7, 1 << 5);
+        let event = burst
+            .take(vec![display(1, 0.0, 1440.0, true)], now)
+            .unwrap();
+        assert_eq!(
+            (event.display_ids, event.changes),
+            (vec![7], vec!["removed".to_string()])
+        );
+        assert_eq!(event.display_count, 1);
+        assert!(event.displays[0].is_main && event.displays[0].is_builtin);
+    }
+}
@@ -0,0 +1,147 @@
+// src/core/enrichment.rs
+//! Ordered event enrichment
+//!
+//! Transforms that rewrite events (annotation, URL canonicalization,
+//! redaction, anonymization) run once, centrally, before an event fans out to
+//! the listeners. Every stage declares the phase it belongs to and the
+//! pipeline always runs phases in the order
+//! annotate → canonicalize → redact → anonymize → derive, so that, for example,
+//! redaction sees canonical URLs and nothing is anonymized before it has been
+//! annotated. Derive stages compute values from the final event. Stages within
+//! one phase run in the order they were added.
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::url_canonicalizer::UrlCanonicalizer;
+
+/// Position of a stage in the pipeline, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EnrichmentPhase {
+    Annotate,
+    Canonicalize,
+    Redact,
+    Anonymize,
+    Derive,
+}
+
+/// A single in-place transform of an event
+pub trait EnrichmentStage: Send {
+    fn name(&self) -> &str;
+    fn phase(&self) -> EnrichmentPhase;
+    fn enrich(&mut self, event: &mut AppSwitchEvent);
+}
+
+/// Stages applied to every event before dispatch
+#[derive(Default)]
+pub struct EnrichmentPipeline {
+    stages: Vec<Box<dyn EnrichmentStage>>,
+}
+
+impl EnrichmentPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a stage after any existing stages of the same or earlier phase
+    pub fn add_stage(&mut self, stage: Box<dyn EnrichmentStage>) {
+        let index = self
+            .stages
+            .iter()
+            .position(|s| s.phase() > stage.phase())
+            .unwrap_or(self.stages.len());
+        self.stages.insert(index, stage);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Stage names in execution order
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|s| s.name()).collect()
+    }
+
+    pub fn apply(&mut self, event: &mut AppSwitchEvent) {
+        for stage in &mut self.stages {
+            stage.enrich(event);
+        }
+    }
+}
+
+impl EnrichmentStage for UrlCanonicalizer {
+    fn name(&self) -> &str {
+        "url_canonicalizer"
+    }
+
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Canonicalize
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        if let Some(ws) = &mut event.workspace {
+            ws.primary_url = ws.primary_url.as_deref().map(|u| self.canonicalize(u));
+        }
+        if let Some(enh) = &mut event.enhanced {
+            enh.url = enh.url.as_deref().map(|u| self.canonicalize(u));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType};
+    use std::sync::{Arc, Mutex};
+
+    /// Appends its tag to the app name and records when it ran
+    struct TagStage {
+        tag: &'static str,
+        phase: EnrichmentPhase,
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl EnrichmentStage for TagStage {
+        fn name(&self) -> &str {
+            self.tag
+        }
+
+        fn phase(&self) -> EnrichmentPhase {
+            self.phase
+        }
+
+        fn enrich(&mut self, event: &mut AppSwitchEvent) {
+            self.log.lock().unwrap().push(self.tag);
+            event.app_info.name.push_str(self.tag);
+        }
+    }
+
+    #[test]
+    fn test_stages_run_in_phase_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let stage = |tag, phase| {
+            Box::new(TagStage {
+                tag,
+                phase,
+                log: log.clone(),
+            })
+        };
+
+        // Registered out of order on purpose
+        let mut pipeline = EnrichmentPipeline::new();
+        pipeline.add_stage(stage("+anon", EnrichmentPhase::Anonymize));
+        pipeline.add_stage(stage("+redact", EnrichmentPhase::Redact));
+        pipeline.add_stage(stage("+dwell", EnrichmentPhase::Annotate));
+        pipeline.add_stage(stage("+canon", EnrichmentPhase::Canonicalize));
+        pipeline.add_stage(stage("+label", EnrichmentPhase::Annotate));
+
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 1),
+        );
+        pipeline.apply(&mut event);
+
+        let expected = vec!["+dwell", "+label", "+canon", "+redact", "+anon"];
+        assert_eq!(pipeline.stage_names(), expected);
+        assert_eq!(*log.lock().unwrap(), expected);
+        assert_eq!(event.app_info.name, "Safari+dwell+label+canon+redact+anon");
+    }
+}
@@ -0,0 +1,395 @@
+// src/core/event_coalescer.rs
+//! Debounced merging of events for the same app
+//!
+//! One user action often reaches the switcher several times: the workspace
+//! and enhanced sources both report an activation, and a page load changes
+//! the title and the URL in two separate notifications. Events with the same
+//! pid and type are held while more keep arriving and merged field by field,
+//! and a single event carrying the final state is emitted once none has
+//! arrived for the coalescing window, or once it has been held for the
+//! maximum delay. Events come out in the order their keys were last
+//! updated, so a quick A→B→A ends on A.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::core::app_switcher_types::{
+    AppSwitchEvent, AppSwitchType, EnhancedSummary, WorkspaceSummary,
+};
+
+/// Default quiet period before a pending event is emitted
+pub const DEFAULT_COALESCE_WINDOW: Duration = Duration::from_millis(150);
+
+/// Default longest time an event is held while updates keep arriving
+pub const DEFAULT_MAX_COALESCE_DELAY: Duration = Duration::from_secs(1);
+
+struct Pending {
+    event: AppSwitchEvent,
+    first_seen: Instant,
+    last_update: Instant,
+    /// Order of the latest push, across all keys
+    seq: u64,
+}
+
+/// Holds events until their key has been quiet for `window`
+pub struct EventCoalescer {
+    window: Duration,
+    max_delay: Duration,
+    pending: HashMap<(i32, AppSwitchType), Pending>,
+    next_seq: u64,
+}
+
+impl Default for EventCoalescer {
+    fn default() -> Self {
+        Self::new(DEFAULT_COALESCE_WINDOW)
+    }
+}
+
+impl EventCoalescer {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            max_delay: DEFAULT_MAX_COALESCE_DELAY,
+            pending: HashMap::new(),
+            next_seq: 0,
+        }
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    /// Add an event, merging it into a pending one with the same key
+    ///
+    /// The merged event keeps the time it was first seen, unless another
+    /// key was pushed since: then the user left and came back, and the
+    /// return is what gets reported.
+    pub fn push_at(&mut self, event: AppSwitchEvent, now: Instant) {
+        let key = (event.app_info.pid, event.event_type.clone());
+        let latest = self.next_seq.checked_sub(1);
+        let (event, first_seen) = match self.pending.remove(&key) {
+            Some(existing) if Some(existing.seq) == latest => {
+                (merge_events(existing.event, event), existing.first_seen)
+            }
+            Some(existing) => {
+                let timestamp = event.timestamp;
+                let mut merged = merge_events(existing.event, event);
+                merged.timestamp = timestamp;
+                (merged, existing.first_seen)
+            }
+            None => (event, now),
+        };
+        self.pending.insert(
+            key,
+            Pending {
+                event,
+                first_seen,
+                last_update: now,
+                seq: self.next_seq,
+            },
+        );
+        self.next_seq += 1;
+    }
+
+    fn due_at(&self, pending: &Pending) -> Instant {
+        (pending.last_update + self.window).min(pending.first_seen + self.max_delay)
+    }
+
+    /// When the next pending event becomes due; `None` when nothing is pending
+    pub fn next_due(&self) -> Option<Instant> {
+        self.pending.values().map(|p| self.due_at(p)).min()
+    }
+
+    /// Remove and return the events that have been quiet for the window or
+    /// held for the maximum delay
+    pub fn drain_due(&mut self, now: Instant) -> Vec<AppSwitchEvent> {
+        let due: Vec<_> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| self.due_at(p) <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut pending: Vec<Pending> = due
+            .into_iter()
+            .filter_map(|key| self.pending.remove(&key))
+            .collect();
+        pending.sort_by_key(|p| p.seq);
+        pending.into_iter().map(|p| p.event).collect()
+    }
+
+    /// Remove and return every pending event, due or not
+    pub fn drain_all(&mut self) -> Vec<AppSwitchEvent> {
+        let mut pending: Vec<Pending> = self.pending.drain().map(|(_, p)| p).collect();
+        pending.sort_by_key(|p| p.seq);
+        pending.into_iter().map(|p| p.event).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Merge a later event into an earlier one; later values win, missing
+/// values fall back to the earlier event
+pub fn merge_events(existing: AppSwitchEvent, mut incoming: AppSwitchEvent) -> AppSwitchEvent {
+    incoming.workspace = match (incoming.workspace, existing.workspace) {
+        (Some(new), Some(old)) => Some(merge_workspace(new, old)),
+        (new, old) => new.or(old),
+    };
+    incoming.enhanced = match (incoming.enhanced, existing.enhanced) {
+        (Some(new), Some(old)) => Some(merge_enhanced(new, old)),
+        (new, old) => new.or(old),
+    };
+    incoming.previous_app = incoming.previous_app.or(existing.previous_app);
+    incoming.windows = incoming.windows.or(existing.windows);
+    incoming.confidence = match (incoming.confidence, existing.confidence) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    };
+    incoming.diff = incoming.diff.or(existing.diff);
+    incoming.previous_context = incoming.previous_context.or(existing.previous_context);
+    incoming.session_id = incoming.session_id.or(existing.session_id);
+    incoming.power = incoming.power.or(existing.power);
+    incoming.screenshot_path = incoming.screenshot_path.or(existing.screenshot_path);
+    incoming.input_source = incoming.input_source.or(existing.input_source);
+    incoming.redacted |= existing.redacted;
+    // The event happened when it was first seen
+    incoming.timestamp = existing.timestamp;
+    incoming
+}
+
+fn merge_workspace(mut new: WorkspaceSummary, old: WorkspaceSummary) -> WorkspaceSummary {
+    // Exhaustive, so a new field cannot be forgotten here
+    let WorkspaceSummary {
+        window_count: _,
+        focused_title,
+        total_screen_coverage,
+        is_fullscreen,
+        is_minimized,
+        tab_titles,
+        active_file_paths,
+        primary_url,
+    } = old;
+    new.focused_title = new.focused_title.or(focused_title);
+    new.total_screen_coverage = new.total_screen_coverage.or(total_screen_coverage);
+    new.is_fullscreen = new.is_fullscreen.or(is_fullscreen);
+    new.is_minimized = new.is_minimized.or(is_minimized);
+    if new.tab_titles.is_empty() {
+        new.tab_titles = tab_titles;
+    }
+    if new.active_file_paths.is_empty() {
+        new.active_file_paths = active_file_paths;
+    }
+    new.primary_url = new.primary_url.or(primary_url);
+    new
+}
+
+fn merge_enhanced(mut new: EnhancedSummary, old: EnhancedSummary) -> EnhancedSummary {
+    // Exhaustive, so a new field cannot be forgotten here
+    let EnhancedSummary {
+        activation_count,
+        front_window_title,
+        cpu_usage,
+        memory_bytes,
+        session_active,
+        screen_locked,
+        display_count,
+        display_id,
+        space_id,
+        space_uuid,
+        space_index,
+        space_type,
+        space_name,
+        space_label,
+        is_fullscreen,
+        window_on_active_space,
+        has_floating_window,
+        document_edited,
+        app_locale,
+        ax_available,
+        menu_context,
+        url,
+        domain,
+        tab_title,
+        browser_profile,
+        extracted_context,
+    } = old;
+    new.activation_count = new.activation_count.max(activation_count);
+    new.front_window_title = new.front_window_title.or(front_window_title);
+    new.cpu_usage = new.cpu_usage.or(cpu_usage);
+    new.memory_bytes = new.memory_bytes.or(memory_bytes);
+    new.session_active = new.session_active.or(session_active);
+    new.screen_locked = new.screen_locked.or(screen_locked);
+    new.display_count = new.display_count.or(display_count);
+    new.display_id = new.display_id.or(display_id);
+    new.space_id = new.space_id.or(space_id);
+    new.space_uuid = new.space_uuid.or(space_uuid);
+    new.space_index = new.space_index.or(space_index);
+    new.space_type = new.space_type.or(space_type);
+    new.space_name = new.space_name.or(space_name);
+    new.space_label = new.space_label.or(space_label);
+    new.is_fullscreen = new.is_fullscreen.or(is_fullscreen);
+    new.window_on_active_space = new.window_on_active_space.or(window_on_active_space);
+    new.has_floating_window = new.has_floating_window.or(has_floating_window);
+    new.document_edited = new.document_edited.or(document_edited);
+    new.app_locale = new.app_locale.or(app_locale);
+    new.ax_available = new.ax_available.or(ax_available);
+    new.menu_context = new.menu_context.or(menu_context);
+    // The domain belongs to whichever URL is kept
+    if new.url.is_none() {
+        new.url = url;
+        new.domain = domain;
+    }
+    new.tab_title = new.tab_title.or(tab_title);
+    new.browser_profile = new.browser_profile.or(browser_profile);
+    for (key, value) in extracted_context {
+        new.extracted_context.entry(key).or_insert(value);
+    }
+    new
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, EnhancedSummary};
+
+    fn tab_change(title: Option<&str>, url: Option<&str>) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 42),
+        );
+        event.enhanced = Some(EnhancedSummary {
+            tab_title: title.map(str::to_string),
+            url: url.map(str::to_string),
+            ..EnhancedSummary::default()
+        });
+        event
+    }
+
+    #[test]
+    fn test_title_then_url_coalesce_into_one_event() {
+        let mut coalescer = EventCoalescer::default();
+        let t0 = Instant::now();
+
+        coalescer.push_at(tab_change(Some("Rust Blog"), None), t0);
+        let t1 = t0 + Duration::from_millis(50);
+        coalescer.push_at(tab_change(None, Some("https://blog.rust-lang.org/")), t1);
+
+        // Still inside the window of the second change
+        assert!(coalescer
+            .drain_due(t0 + Duration::from_millis(160))
+            .is_empty());
+
+        let events = coalescer.drain_due(t1 + DEFAULT_COALESCE_WINDOW);
+        assert_eq!(events.len(), 1);
+        let enh = events[0].enhanced.as_ref().unwrap();
+        assert_eq!(enh.tab_title.as_deref(), Some("Rust Blog"));
+        assert_eq!(enh.url.as_deref(), Some("https://blog.rust-lang.org/"));
+        assert!(coalescer.is_empty());
+    }
+
+    #[test]
+    fn test_later_values_win_and_other_apps_stay_separate() {
+        let mut coalescer = EventCoalescer::new(Duration::from_millis(100));
+        let t0 = Instant::now();
+
+        coalescer.push_at(tab_change(Some("Loading…"), None), t0);
+        coalescer.push_at(tab_change(Some("Docs"), None), t0);
+        let mut other = tab_change(Some("Mail"), None);
+        other.app_info.pid = 7;
+        coalescer.push_at(other, t0);
+
+        let events = coalescer.drain_due(t0 + Duration::from_millis(100));
+        assert_eq!(events.len(), 2);
+        let safari = events.iter().find(|e| e.app_info.pid == 42).unwrap();
+        assert_eq!(
+            safari.enhanced.as_ref().unwrap().tab_title.as_deref(),
+            Some("Docs")
+        );
+    }
+
+    #[test]
+    fn test_fields_only_on_the_earlier_event_are_kept() {
+        let mut coalescer = EventCoalescer::default();
+        let t0 = Instant::now();
+
+        let mut first = tab_change(Some("Docs"), None);
+        first.session_id = Some("session".to_string());
+        let enh = first.enhanced.as_mut().unwrap();
+        enh.space_label = Some("Desktop 2".to_string());
+        enh.cpu_usage = Some(4.0);
+        coalescer.push_at(first, t0);
+        coalescer.push_at(tab_change(None, Some("https://docs.rs/")), t0);
+
+        let merged = coalescer.drain_all().remove(0);
+        assert_eq!(merged.session_id.as_deref(), Some("session"));
+        let enh = merged.enhanced.unwrap();
+        assert_eq!(enh.space_label.as_deref(), Some("Desktop 2"));
+        assert_eq!(enh.cpu_usage, Some(4.0));
+        assert_eq!(enh.tab_title.as_deref(), Some("Docs"));
+        assert_eq!(enh.url.as_deref(), Some("https://docs.rs/"));
+    }
+
+    #[test]
+    fn test_quick_return_ends_on_the_latest_app() {
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+        let mut mail = tab_change(Some("Mail"), None);
+        mail.app_info.pid = 7;
+        let at = |mut event: AppSwitchEvent, millis| {
+            event.timestamp = ms(millis);
+            event
+        };
+
+        // Safari → Mail → Safari
+        let mut coalescer = EventCoalescer::default();
+        coalescer.push_at(at(tab_change(Some("Docs"), None), 0), ms(0));
+        coalescer.push_at(at(mail.clone(), 20), ms(20));
+        coalescer.push_at(at(tab_change(Some("Docs"), None), 40), ms(40));
+        let events = coalescer.drain_all();
+        let pids: Vec<i32> = events.iter().map(|e| e.app_info.pid).collect();
+        assert_eq!(pids, vec![7, 42]);
+        assert_eq!(events[1].timestamp, ms(40));
+
+        // Two sources each reporting Safari → Mail stay in that order
+        let mut coalescer = EventCoalescer::default();
+        coalescer.push_at(at(tab_change(Some("Docs"), None), 0), ms(0));
+        coalescer.push_at(at(mail.clone(), 20), ms(20));
+        coalescer.push_at(at(tab_change(Some("Docs"), None), 0), ms(25));
+        coalescer.push_at(at(mail, 20), ms(30));
+        let events = coalescer.drain_all();
+        let pids: Vec<i32> = events.iter().map(|e| e.app_info.pid).collect();
+        assert_eq!(pids, vec![42, 7]);
+    }
+
+    #[test]
+    fn test_steady_updates_are_emitted_after_the_max_delay() {
+        let mut coalescer = EventCoalescer::new(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(300));
+        let t0 = Instant::now();
+        for millis in (0..=250).step_by(50) {
+            coalescer.push_at(
+                tab_change(Some("Loading…"), None),
+                t0 + Duration::from_millis(millis),
+            );
+        }
+        assert_eq!(coalescer.next_due(), Some(t0 + Duration::from_millis(300)));
+        assert!(coalescer
+            .drain_due(t0 + Duration::from_millis(299))
+            .is_empty());
+        assert_eq!(
+            coalescer.drain_due(t0 + Duration::from_millis(300)).len(),
+            1
+        );
+        assert_eq!(coalescer.next_due(), None);
+    }
+}
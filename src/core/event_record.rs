@@ -0,0 +1,228 @@
+// src/core/event_record.rs
+//! The JSON lines event log record
+//!
+//! `EventRecord` is the envelope the file logger writes for every app switch.
+//! It derives `JsonSchema`, so `research-tracker schema` can publish the
+//! contract for consumers written in other languages.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::Serialize;
+
+use crate::core::accessibility::WindowInfo;
+use crate::core::app_switcher_types::{AppSwitchEvent, SleepPeriod};
+use crate::core::context_diff::{without_diffed_fields, FieldChange};
+use crate::core::power_state::PowerState;
+use crate::core::previous_context::EnhancedContextSnapshot;
+use crate::core::timestamp_format::TimestampFormat;
+use crate::extractors::ContextValue;
+
+/// One app switch, as written to the event log
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EventRecord {
+    /// When the event was logged, in the configured timestamp format
+    #[schemars(schema_with = "timestamp_schema")]
+    pub timestamp: serde_json::Value,
+    /// `foreground`, `background`, `launch`, `terminate`, `hide`, `unhide`,
+    /// `space_change`, `session_change`, `sleep`, `wake` or `heartbeat`
+    pub event_type: String,
+    pub session_id: Option<String>,
+    pub app: AppRecord,
+    pub previous_app: Option<PreviousAppRecord>,
+    pub workspace: Option<WorkspaceRecord>,
+    pub enhanced: Option<EnhancedRecord>,
+    /// How sure the source is that the switch happened, `0.0..=1.0`
+    pub confidence: Option<f32>,
+    /// All windows of the app; only with window capture enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub windows: Option<Vec<WindowInfo>>,
+    /// Fields changed since the previous foreground event; only with diffs enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<Vec<FieldChange>>,
+    /// Last-known context of the previous app; only with previous contexts enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_context: Option<EnhancedContextSnapshot>,
+    /// Latest power sample; only with power capture enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub power: Option<PowerState>,
+    /// PNG of the focused window; only with screenshot capture enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub screenshot_path: Option<String>,
+    /// Keyboard layout or input method id; only with input source tracking
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_source: Option<String>,
+    /// The system sleep that ended just before this event; the time is not
+    /// any app's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_asleep: Option<SleepRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SleepRecord {
+    #[schemars(schema_with = "timestamp_schema")]
+    pub start: serde_json::Value,
+    #[schemars(schema_with = "timestamp_schema")]
+    pub end: serde_json::Value,
+    pub duration_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AppRecord {
+    pub name: String,
+    pub bundle_id: String,
+    /// `false` for processes without a bundle ID, which are matched and
+    /// classified by their executable name
+    pub has_bundle_id: bool,
+    pub pid: i32,
+    pub path: Option<String>,
+    pub icon_path: Option<String>,
+    /// Set when the launch date is known, in the configured timestamp format
+    #[schemars(schema_with = "optional_timestamp_schema")]
+    pub launch_date: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PreviousAppRecord {
+    pub name: String,
+    pub bundle_id: String,
+    pub pid: i32,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct WorkspaceRecord {
+    pub window_count: usize,
+    pub focused_title: Option<String>,
+    pub primary_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EnhancedRecord {
+    pub activation_count: u32,
+    pub front_window_title: Option<String>,
+    pub cpu_usage: Option<f32>,
+    pub memory_bytes: Option<u64>,
+    pub session_active: Option<bool>,
+    pub screen_locked: Option<bool>,
+    pub is_fullscreen: Option<bool>,
+    pub window_on_active_space: Option<bool>,
+    pub has_floating_window: Option<bool>,
+    pub document_edited: Option<bool>,
+    pub display_count: Option<u32>,
+    /// Display the front window is on
+    pub display_id: Option<u32>,
+    /// Whether the app answers accessibility; `None` until it was probed
+    pub ax_available: Option<bool>,
+    /// Path of the open menu, e.g. `File › Export › PDF`
+    pub menu_context: Option<String>,
+    pub app_locale: Option<String>,
+    /// Registrable domain of the browser URL, e.g. `google.com`
+    pub domain: Option<String>,
+    /// Chromium profile named in the window title
+    pub browser_profile: Option<String>,
+    pub extracted_context: HashMap<String, ContextValue>,
+}
+
+/// RFC 3339 strings, or a number for epoch milliseconds
+fn timestamp_schema(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({ "type": ["string", "integer"] })
+}
+
+/// Wall-clock time of `instant`, given that `reference` happened at `at`
+fn wall_time(instant: Instant, reference: Instant, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let ago = chrono::Duration::from_std(reference.saturating_duration_since(instant)).ok()?;
+    at.checked_sub_signed(ago)
+}
+
+fn optional_timestamp_schema(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({ "type": ["string", "integer", "null"] })
+}
+
+impl EventRecord {
+    pub fn new(event: &AppSwitchEvent, timestamps: &TimestampFormat, now: DateTime<Utc>) -> Self {
+        let compact;
+        let event = if event.diff.is_some() {
+            compact = without_diffed_fields(event);
+            &compact
+        } else {
+            event
+        };
+        let app = &event.app_info;
+        Self {
+            timestamp: timestamps.json(now),
+            event_type: event.event_type.as_str().to_string(),
+            session_id: event.session_id.clone(),
+            app: AppRecord {
+                name: app.name.clone(),
+                bundle_id: app.bundle_id.clone(),
+                has_bundle_id: app.has_bundle_id(),
+                pid: app.pid,
+                path: app.path.clone(),
+                icon_path: app.icon_path.clone(),
+                launch_date: app
+                    .launch_date
+                    .and_then(|launched| wall_time(launched, event.timestamp, now))
+                    .map(|launched| timestamps.json(launched)),
+            },
+            previous_app: event.previous_app.as_ref().map(|prev| PreviousAppRecord {
+                name: prev.name.clone(),
+                bundle_id: prev.bundle_id.clone(),
+                pid: prev.pid,
+            }),
+            workspace: event.workspace.as_ref().map(|w| WorkspaceRecord {
+                window_count: w.window_count,
+                focused_title: w.focused_title.clone(),
+                primary_url: w.primary_url.clone(),
+            }),
+            enhanced: event.enhanced.as_ref().map(|e| EnhancedRecord {
+                activation_count: e.activation_count,
+                front_window_title: e.front_window_title.clone(),
+                cpu_usage: e.cpu_usage,
+                memory_bytes: e.memory_bytes,
+                session_active: e.session_active,
+                screen_locked: e.screen_locked,
+                is_fullscreen: e.is_fullscreen,
+                window_on_active_space: e.window_on_active_space,
+                has_floating_window: e.has_floating_window,
+                document_edited: e.document_edited,
+                display_count: e.display_count,
+                display_id: e.display_id,
+                ax_available: e.ax_available,
+                menu_context: e.menu_context.clone(),
+                app_locale: e.app_locale.clone(),
+                domain: e.domain.clone(),
+                browser_profile: e.browser_profile.clone(),
+                extracted_context: e.extracted_context.clone(),
+            }),
+            confidence: event.confidence,
+            windows: event.windows.clone(),
+            diff: event.diff.as_ref().map(|d| d.changes.clone()),
+            previous_context: event.previous_context.clone(),
+            power: event.power.clone(),
+            screenshot_path: event.screenshot_path.clone(),
+            input_source: event.input_source.clone(),
+            system_asleep: None,
+        }
+    }
+
+    /// Report a sleep that ended before this event
+    pub fn with_system_asleep(
+        mut self,
+        asleep: &SleepPeriod,
+        timestamps: &TimestampFormat,
+    ) -> Self {
+        self.system_asleep = Some(SleepRecord {
+            start: timestamps.json(asleep.start),
+            end: timestamps.json(asleep.end),
+            duration_secs: asleep.duration().as_secs(),
+        });
+        self
+    }
+
+    /// The JSON Schema for a record and every nested type
+    pub fn schema() -> Schema {
+        schemars::schema_for!(EventRecord)
+    }
+}
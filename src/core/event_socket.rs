@@ -0,0 +1,353 @@
+// src/core/event_socket.rs
+//! Live event stream over a Unix socket
+//!
+//! With `--socket`, a running tracker publishes every event as an
+//! `EventRecord` JSON line to each client connected to the socket. The
+//! `follow` command is such a client: it prints the stream as it arrives,
+//! like `tail -f` on an output file but without file logging, and reconnects
+//! when the tracker restarts.
+//!
+//! Clients are accepted when the next event is published, and a client that
+//! cannot keep up or has gone away is dropped rather than blocking the
+//! listener. Events carry titles and URLs, so the socket is only accessible
+//! to its owner.
+
+use std::fmt;
+use std::fs::Permissions;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchListener};
+use crate::core::event_record::EventRecord;
+use crate::core::timestamp_format::TimestampFormat;
+
+/// How long a client may block a write before it is dropped
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Publishes events to the clients of a Unix socket
+pub struct SocketEventLogger {
+    listener: UnixListener,
+    path: PathBuf,
+    clients: Vec<UnixStream>,
+    timestamps: TimestampFormat,
+}
+
+impl SocketEventLogger {
+    /// Listen on `path`, replacing a socket left behind by an earlier run;
+    /// anything else at the path is left alone and an error returned
+    pub fn bind(path: &Path) -> io::Result<Self> {
+        if let Ok(metadata) = std::fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path.display()),
+                ));
+            }
+            match UnixStream::connect(path) {
+                Ok(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AddrInUse,
+                        format!("another tracker is listening on {}", path.display()),
+                    ))
+                }
+                Err(_) => std::fs::remove_file(path)?,
+            }
+        }
+        let listener = UnixListener::bind(path)?;
+        std::fs::set_permissions(path, Permissions::from_mode(0o600))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            path: path.to_path_buf(),
+            clients: Vec::new(),
+            timestamps: TimestampFormat::default(),
+        })
+    }
+
+    pub fn with_timestamps(mut self, timestamps: TimestampFormat) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Take the clients that connected since the last event
+    fn accept_pending(&mut self) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    let configured = stream
+                        .set_nonblocking(false)
+                        .and_then(|_| stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT)));
+                    if configured.is_ok() {
+                        self.clients.push(stream);
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    tracing::warn!("Failed to accept socket client: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Send one record to every client, dropping those that fail
+    pub fn publish(&mut self, event: &AppSwitchEvent, now: DateTime<Utc>) {
+        self.accept_pending();
+        if self.clients.is_empty() {
+            return;
+        }
+        let record = EventRecord::new(event, &self.timestamps, now);
+        let mut line = serde_json::to_string(&record).unwrap();
+        line.push('\n');
+        self.clients
+            .retain_mut(|client| client.write_all(line.as_bytes()).is_ok());
+    }
+}
+
+impl AppSwitchListener for SocketEventLogger {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        self.publish(event, Utc::now());
+    }
+
+    fn name(&self) -> &str {
+        "socket"
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        self.clients.clear();
+    }
+}
+
+impl Drop for SocketEventLogger {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// How `follow` prints records
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowFormat {
+    /// One readable line per event
+    #[default]
+    Human,
+    /// The records as received
+    Json,
+}
+
+impl fmt::Display for FollowFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FollowFormat::Human => write!(f, "human"),
+            FollowFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for FollowFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "human" => Ok(FollowFormat::Human),
+            "json" => Ok(FollowFormat::Json),
+            other => Err(format!(
+                "Invalid follow format '{}' (expected human or json)",
+                other
+            )),
+        }
+    }
+}
+
+/// Render a received record; `None` for lines that are not records
+pub fn format_record_line(line: &str, format: FollowFormat) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let record: Value = serde_json::from_str(line).ok()?;
+    match format {
+        FollowFormat::Json => Some(line.to_string()),
+        FollowFormat::Human => {
+            let timestamp = match &record["timestamp"] {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            let mut out = format!(
+                "{} {:<10} {} ({})",
+                timestamp,
+                record["event_type"].as_str().unwrap_or("?"),
+                record["app"]["name"].as_str().unwrap_or("?"),
+                record["app"]["bundle_id"].as_str().unwrap_or(""),
+            );
+            let title = record["workspace"]["focused_title"]
+                .as_str()
+                .or_else(|| record["enhanced"]["front_window_title"].as_str());
+            if let Some(title) = title {
+                out.push_str(&format!(" — {}", title));
+            }
+            if let Some(url) = record["workspace"]["primary_url"].as_str() {
+                out.push_str(&format!(" <{}>", url));
+            }
+            Some(out)
+        }
+    }
+}
+
+/// Print the records from one connection until the tracker closes it
+pub fn follow_stream(
+    stream: UnixStream,
+    out: &mut impl Write,
+    format: FollowFormat,
+) -> io::Result<u64> {
+    let mut printed = 0;
+    for line in BufReader::new(stream).lines() {
+        if let Some(formatted) = format_record_line(&line?, format) {
+            writeln!(out, "{}", formatted)?;
+            out.flush()?;
+            printed += 1;
+        }
+    }
+    Ok(printed)
+}
+
+/// Follow the tracker on `path`; with `reconnect`, wait for it to come
+/// (back) up instead of returning when it is not running or stops
+pub fn follow(
+    path: &Path,
+    out: &mut impl Write,
+    format: FollowFormat,
+    reconnect: Option<Duration>,
+) -> io::Result<()> {
+    let mut waiting = false;
+    loop {
+        match UnixStream::connect(path) {
+            Ok(stream) => {
+                waiting = false;
+                eprintln!("📡 Following {}", path.display());
+                match follow_stream(stream, out, format) {
+                    Ok(_) => {}
+                    // The reader went away, e.g. `follow | head`
+                    Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+                    Err(e) if reconnect.is_none() => return Err(e),
+                    Err(_) => {}
+                }
+                if reconnect.is_some() {
+                    eprintln!("🔌 Tracker disconnected");
+                }
+            }
+            Err(e) if reconnect.is_none() => return Err(e),
+            Err(_) => {}
+        }
+        let Some(delay) = reconnect else {
+            return Ok(());
+        };
+        if !waiting {
+            eprintln!("⏳ Waiting for a tracker on {}", path.display());
+            waiting = true;
+        }
+        std::thread::sleep(delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType, WorkspaceSummary};
+
+    fn switch(name: &str, bundle_id: &str, title: &str) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new(name.to_string(), bundle_id.to_string(), 1),
+        );
+        event.workspace = Some(WorkspaceSummary {
+            window_count: 1,
+            focused_title: Some(title.to_string()),
+            total_screen_coverage: None,
+            is_fullscreen: None,
+            is_minimized: None,
+            tab_titles: Vec::new(),
+            active_file_paths: Vec::new(),
+            primary_url: None,
+        });
+        event
+    }
+
+    #[test]
+    fn test_events_flow_from_tracker_to_follower() {
+        let path = std::env::temp_dir().join(format!("event_socket_{}.sock", std::process::id()));
+        let mut server = SocketEventLogger::bind(&path).unwrap();
+        assert!(SocketEventLogger::bind(&path).is_err());
+
+        let json_client = UnixStream::connect(&path).unwrap();
+        let human_client = UnixStream::connect(&path).unwrap();
+        let follower = |stream, format| {
+            std::thread::spawn(move || {
+                let mut out = Vec::new();
+                follow_stream(stream, &mut out, format).unwrap();
+                String::from_utf8(out).unwrap()
+            })
+        };
+        let json = follower(json_client, FollowFormat::Json);
+        let human = follower(human_client, FollowFormat::Human);
+
+        let now = Utc::now();
+        server.publish(&switch("Safari", "com.apple.Safari", "Docs"), now);
+        server.publish(&switch("Terminal", "com.apple.Terminal", "zsh"), now);
+        assert_eq!(server.client_count(), 2);
+        // Stopping the tracker ends the stream
+        drop(server);
+        assert!(!path.exists());
+
+        let json = json.join().unwrap();
+        let records: Vec<Value> = json
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["app"]["bundle_id"], "com.apple.Safari");
+        assert_eq!(records[1]["workspace"]["focused_title"], "zsh");
+
+        let human = human.join().unwrap();
+        let lines: Vec<&str> = human.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("foreground Safari (com.apple.Safari) — Docs"));
+        assert!(lines[1].ends_with("foreground Terminal (com.apple.Terminal) — zsh"));
+    }
+
+    #[test]
+    fn test_bind_keeps_other_files_and_owner_only_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, "keep me").unwrap();
+        let refused = SocketEventLogger::bind(&notes).err().unwrap();
+        assert_eq!(refused.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&notes).unwrap(), "keep me");
+
+        let path = dir.path().join("events.sock");
+        let _server = SocketEventLogger::bind(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_follow_without_reconnect_fails_when_no_tracker_runs() {
+        let path = std::env::temp_dir().join(format!("no_tracker_{}.sock", std::process::id()));
+        let mut out = Vec::new();
+        assert!(follow(&path, &mut out, FollowFormat::Json, None).is_err());
+        assert_eq!(format_record_line("not json", FollowFormat::Json), None);
+        assert_eq!(format_record_line("", FollowFormat::Human), None);
+    }
+}
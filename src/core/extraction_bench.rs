@@ -0,0 +1,296 @@
+// src/core/extraction_bench.rs
+//! Extraction latency benchmark
+//!
+//! Backs the `bench-extract` command. Context extraction is repeated against
+//! the frontmost app while a tracing layer times the named sub-step spans
+//! emitted by `AccessibilityContextExtractor` (`window`, `browser`,
+//! `app_context`, `focused_element`, `applescript`). Step times are exclusive:
+//! an AppleScript call inside the browser step is charged to `applescript`
+//! only, so the dominant step is the one actually worth optimizing.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use objc2_app_kit::NSWorkspace;
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::core::accessibility::AccessibilityContextExtractor;
+use crate::core::app_switcher_types::AppInfo;
+
+/// Span names timed by the benchmark, in report order
+pub const EXTRACTION_STEPS: &[&str] = &[
+    "window",
+    "browser",
+    "app_context",
+    "focused_element",
+    "applescript",
+];
+
+/// Summary of a set of latency samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+    pub total: Duration,
+}
+
+impl LatencyStats {
+    /// Nearest-rank percentiles; `None` for an empty sample set
+    pub fn from_durations(durations: &[Duration]) -> Option<Self> {
+        if durations.is_empty() {
+            return None;
+        }
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        Some(Self {
+            samples: sorted.len(),
+            min: sorted[0],
+            median: percentile(&sorted, 50.0),
+            p95: percentile(&sorted, 95.0),
+            max: sorted[sorted.len() - 1],
+            total: sorted.iter().sum(),
+        })
+    }
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Result of a benchmark run
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub app_name: String,
+    pub bundle_id: String,
+    pub overall: LatencyStats,
+    /// Exclusive time per sub-step, only for steps that ran
+    pub steps: Vec<(String, LatencyStats)>,
+}
+
+impl BenchReport {
+    /// Build a report from per-iteration totals and step timings
+    pub fn from_samples(
+        app: &AppInfo,
+        totals: &[Duration],
+        step_samples: &HashMap<String, Vec<Duration>>,
+    ) -> Option<Self> {
+        let steps = EXTRACTION_STEPS
+            .iter()
+            .filter_map(|name| {
+                let stats = LatencyStats::from_durations(step_samples.get(*name)?)?;
+                Some((name.to_string(), stats))
+            })
+            .collect();
+        Some(Self {
+            app_name: app.name.clone(),
+            bundle_id: app.bundle_id.clone(),
+            overall: LatencyStats::from_durations(totals)?,
+            steps,
+        })
+    }
+
+    /// The step with the most cumulative time
+    pub fn dominant_step(&self) -> Option<&str> {
+        self.steps
+            .iter()
+            .max_by_key(|(_, stats)| stats.total)
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn format(&self) -> String {
+        let ms = |d: Duration| format!("{:>9.2}", d.as_secs_f64() * 1000.0);
+        let row = |label: &str, s: &LatencyStats| {
+            format!(
+                "{:<16} {} {} {} {} {:>6}\n",
+                label,
+                ms(s.min),
+                ms(s.median),
+                ms(s.p95),
+                ms(s.max),
+                s.samples
+            )
+        };
+
+        let mut out = format!(
+            "Extraction latency for {} ({}), {} iterations\n\n",
+            self.app_name, self.bundle_id, self.overall.samples
+        );
+        out.push_str(&format!(
+            "{:<16} {:>9} {:>9} {:>9} {:>9} {:>6}\n",
+            "STEP", "MIN ms", "MEDIAN ms", "P95 ms", "MAX ms", "RUNS"
+        ));
+        out.push_str(&row("total", &self.overall));
+        for (name, stats) in &self.steps {
+            out.push_str(&row(name, stats));
+        }
+        if let Some(step) = self.dominant_step() {
+            out.push_str(&format!("\nDominant step: {}\n", step));
+        }
+        out
+    }
+}
+
+/// Start time and time spent in child spans, stored in span extensions
+struct SpanTiming {
+    started: Instant,
+    children: Duration,
+}
+
+/// Records exclusive time of extraction step spans
+struct StepTimingLayer {
+    timings: Arc<Mutex<HashMap<String, Duration>>>,
+}
+
+impl<S> Layer<S> for StepTimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanTiming {
+                started: Instant::now(),
+                children: Duration::ZERO,
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some((inclusive, children)) = span
+            .extensions()
+            .get::<SpanTiming>()
+            .map(|t| (t.started.elapsed(), t.children))
+        else {
+            return;
+        };
+
+        if let Some(parent) = span.parent() {
+            if let Some(timing) = parent.extensions_mut().get_mut::<SpanTiming>() {
+                timing.children += inclusive;
+            }
+        }
+        if EXTRACTION_STEPS.contains(&span.name()) {
+            *self
+                .timings
+                .lock()
+                .unwrap()
+                .entry(span.name().to_string())
+                .or_default() += inclusive.saturating_sub(children);
+        }
+    }
+}
+
+fn frontmost_app() -> Option<AppInfo> {
+    unsafe {
+        let front = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+        Some(AppInfo::new(
+            front
+                .localizedName()
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            front
+                .bundleIdentifier()
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            front.processIdentifier(),
+        ))
+    }
+}
+
+/// Run full context extraction `iterations` times against the frontmost app
+pub fn run_extraction_bench(iterations: usize) -> Result<BenchReport, String> {
+    if iterations == 0 {
+        return Err("iterations must be at least 1".to_string());
+    }
+    let app = frontmost_app().ok_or("No frontmost application")?;
+    let mut extractor = AccessibilityContextExtractor::new()?;
+
+    let timings = Arc::new(Mutex::new(HashMap::new()));
+    let subscriber = tracing_subscriber::registry().with(StepTimingLayer {
+        timings: Arc::clone(&timings),
+    });
+
+    let mut totals = Vec::with_capacity(iterations);
+    let mut step_samples: HashMap<String, Vec<Duration>> = HashMap::new();
+    tracing::subscriber::with_default(subscriber, || {
+        for _ in 0..iterations {
+            // Every iteration must do the full work, not hit the context cache
+            extractor.clear_cache();
+            timings.lock().unwrap().clear();
+
+            let started = Instant::now();
+            extractor.extract_context(&app)?;
+            totals.push(started.elapsed());
+
+            for (step, elapsed) in timings.lock().unwrap().drain() {
+                step_samples.entry(step).or_default().push(elapsed);
+            }
+        }
+        Ok::<(), String>(())
+    })?;
+
+    BenchReport::from_samples(&app, &totals, &step_samples)
+        .ok_or_else(|| "No samples recorded".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|v| Duration::from_millis(*v)).collect()
+    }
+
+    #[test]
+    fn test_percentiles_of_synthetic_durations() {
+        // 1..=100 ms, shuffled
+        let mut values: Vec<u64> = (1..=100).collect();
+        values.reverse();
+        values.swap(3, 70);
+        let stats = LatencyStats::from_durations(&ms(&values)).unwrap();
+
+        assert_eq!(stats.samples, 100);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(50));
+        assert_eq!(stats.p95, Duration::from_millis(95));
+        assert_eq!(stats.max, Duration::from_millis(100));
+        assert_eq!(stats.total, Duration::from_millis(5050));
+
+        let few = LatencyStats::from_durations(&ms(&[30, 10, 20])).unwrap();
+        assert_eq!(few.median, Duration::from_millis(20));
+        assert_eq!(few.p95, Duration::from_millis(30));
+
+        let one = LatencyStats::from_durations(&ms(&[7])).unwrap();
+        assert_eq!(one.median, Duration::from_millis(7));
+        assert_eq!(one.p95, Duration::from_millis(7));
+
+        assert_eq!(LatencyStats::from_durations(&[]), None);
+    }
+
+    #[test]
+    fn test_dominant_step_uses_cumulative_time() {
+        let app = AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 1);
+        let mut steps = HashMap::new();
+        steps.insert("window".to_string(), ms(&[2, 2, 2]));
+        steps.insert("applescript".to_string(), ms(&[40, 35, 60]));
+        steps.insert("focused_element".to_string(), ms(&[1, 90, 1]));
+
+        let report = BenchReport::from_samples(&app, &ms(&[50, 130, 70]), &steps).unwrap();
+        assert_eq!(report.overall.median, Duration::from_millis(70));
+        assert_eq!(report.dominant_step(), Some("applescript"));
+        let order: Vec<&str> = report.steps.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(order, vec!["window", "focused_element", "applescript"]);
+    }
+}
@@ -0,0 +1,324 @@
+// src/core/extraction_rules.rs
+//! User-defined extraction rules
+//!
+//! Support for a new app usually means reading one or two attributes from a
+//! known place in its UI. Rules in the `[extraction]` config section describe
+//! those reads without code changes:
+//!
+//! ```toml
+//! [[extraction.apps]]
+//! bundle_id = "com.example.Notebook"
+//!
+//! [[extraction.apps.fields]]
+//! name = "notebook"
+//! path = "AXFocusedWindow/AXToolbar/AXPopUpButton[1]"
+//! attribute = "AXTitle"
+//! ```
+//!
+//! A path is walked from the application element, one `/`-separated step at
+//! a time. A step first follows the element-valued attribute of that name
+//! (`AXFocusedWindow`, `AXFocusedUIElement`); otherwise it selects a child by
+//! role, the first one unless an index is given (`AXGroup[2]` is the third
+//! `AXGroup` child). Values land in `AccessibilityContext::custom_fields`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::ax_element::AxElement;
+
+/// The `[extraction]` config section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtractionRulesConfig {
+    pub apps: Vec<AppRules>,
+}
+
+impl ExtractionRulesConfig {
+    /// Fields configured for `bundle_id`
+    pub fn fields_for<'a>(
+        &'a self,
+        bundle_id: &'a str,
+    ) -> impl Iterator<Item = &'a FieldRule> + 'a {
+        self.apps
+            .iter()
+            .filter(move |app| app.bundle_id == bundle_id)
+            .flat_map(|app| app.fields.iter())
+    }
+
+    pub fn bundle_ids(&self) -> impl Iterator<Item = &str> {
+        self.apps.iter().map(|app| app.bundle_id.as_str())
+    }
+
+    /// Check every path up front so a typo fails at startup
+    pub fn validate(&self) -> Result<(), String> {
+        for field in self.apps.iter().flat_map(|app| app.fields.iter()) {
+            field.path.parse::<AxPath>()?;
+        }
+        Ok(())
+    }
+}
+
+/// Rules for one application
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppRules {
+    pub bundle_id: String,
+    pub fields: Vec<FieldRule>,
+}
+
+/// Read `attribute` from the element at `path` into the field `name`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldRule {
+    pub name: String,
+    pub path: String,
+    pub attribute: String,
+}
+
+/// One step of an `AxPath`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathStep {
+    pub name: String,
+    pub index: usize,
+}
+
+/// A parsed rule path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AxPath(pub Vec<PathStep>);
+
+impl FromStr for AxPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid AX path '{}'", s);
+        let mut steps = Vec::new();
+        for segment in s.split('/').map(str::trim).filter(|seg| !seg.is_empty()) {
+            let step = match segment.strip_suffix(']') {
+                Some(rest) => {
+                    let (name, index) = rest.split_once('[').ok_or_else(invalid)?;
+                    PathStep {
+                        name: name.to_string(),
+                        index: index.parse().map_err(|_| invalid())?,
+                    }
+                }
+                None => PathStep {
+                    name: segment.to_string(),
+                    index: 0,
+                },
+            };
+            if step.name.is_empty() || step.name.contains('[') {
+                return Err(invalid());
+            }
+            steps.push(step);
+        }
+        if steps.is_empty() {
+            return Err(invalid());
+        }
+        Ok(AxPath(steps))
+    }
+}
+
+impl fmt::Display for AxPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<String> = self
+            .0
+            .iter()
+            .map(|step| match step.index {
+                0 => step.name.clone(),
+                index => format!("{}[{}]", step.name, index),
+            })
+            .collect();
+        write!(f, "{}", steps.join("/"))
+    }
+}
+
+/// The element operations a rule needs; mocked in tests
+pub trait AxNode: Sized {
+    fn element(&self, attribute: &str) -> Option<Self>;
+    fn children(&self) -> Vec<Self>;
+    fn string(&self, attribute: &str) -> Option<String>;
+}
+
+impl AxNode for AxElement {
+    fn element(&self, attribute: &str) -> Option<Self> {
+        AxElement::element(self, attribute)
+    }
+
+    fn children(&self) -> Vec<Self> {
+        AxElement::children(self)
+    }
+
+    fn string(&self, attribute: &str) -> Option<String> {
+        AxElement::string(self, attribute)
+    }
+}
+
+/// Walk `path` from `root`; `None` as soon as a step finds nothing
+pub fn resolve<N: AxNode>(root: &N, path: &AxPath) -> Option<N> {
+    let (first, rest) = path.0.split_first()?;
+    let mut current = follow(root, first)?;
+    for step in rest {
+        current = follow(&current, step)?;
+    }
+    Some(current)
+}
+
+fn follow<N: AxNode>(element: &N, step: &PathStep) -> Option<N> {
+    if step.index == 0 {
+        if let Some(next) = element.element(&step.name) {
+            return Some(next);
+        }
+    }
+    element
+        .children()
+        .into_iter()
+        .filter(|child| child.string("AXRole").as_deref() == Some(step.name.as_str()))
+        .nth(step.index)
+}
+
+/// Apply the rules for `bundle_id`, skipping attributes `allowed` rejects
+pub fn apply_rules<N: AxNode>(
+    config: &ExtractionRulesConfig,
+    bundle_id: &str,
+    app: &N,
+    allowed: impl Fn(&str) -> bool,
+) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    for rule in config.fields_for(bundle_id) {
+        if !allowed(&rule.attribute) {
+            continue;
+        }
+        let Ok(path) = rule.path.parse::<AxPath>() else {
+            continue;
+        };
+        let value = resolve(app, &path)
+            .and_then(|element| element.string(&rule.attribute))
+            .filter(|value| !value.is_empty());
+        if let Some(value) = value {
+            fields.insert(rule.name.clone(), value);
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    /// In-memory element tree
+    #[derive(Default)]
+    struct Node {
+        attributes: HashMap<String, String>,
+        elements: HashMap<String, Rc<Node>>,
+        children: Vec<Rc<Node>>,
+    }
+
+    #[derive(Clone)]
+    struct MockElement(Rc<Node>);
+
+    impl AxNode for MockElement {
+        fn element(&self, attribute: &str) -> Option<Self> {
+            self.0.elements.get(attribute).cloned().map(MockElement)
+        }
+
+        fn children(&self) -> Vec<Self> {
+            self.0.children.iter().cloned().map(MockElement).collect()
+        }
+
+        fn string(&self, attribute: &str) -> Option<String> {
+            self.0.attributes.get(attribute).cloned()
+        }
+    }
+
+    fn node(role: &str, attributes: &[(&str, &str)], children: Vec<Rc<Node>>) -> Rc<Node> {
+        let mut map: HashMap<String, String> = attributes
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        map.insert("AXRole".to_string(), role.to_string());
+        Rc::new(Node {
+            attributes: map,
+            children,
+            ..Node::default()
+        })
+    }
+
+    fn notebook_app() -> MockElement {
+        let toolbar = node(
+            "AXToolbar",
+            &[],
+            vec![
+                node("AXPopUpButton", &[("AXTitle", "Default")], Vec::new()),
+                node(
+                    "AXPopUpButton",
+                    &[("AXTitle", "Lab Notes 2026")],
+                    Vec::new(),
+                ),
+            ],
+        );
+        let window = node("AXWindow", &[("AXTitle", "Notebook")], vec![toolbar]);
+        MockElement(Rc::new(Node {
+            elements: HashMap::from([("AXFocusedWindow".to_string(), window)]),
+            ..Node::default()
+        }))
+    }
+
+    fn config(path: &str, attribute: &str) -> ExtractionRulesConfig {
+        ExtractionRulesConfig {
+            apps: vec![AppRules {
+                bundle_id: "com.example.Notebook".to_string(),
+                fields: vec![FieldRule {
+                    name: "notebook".to_string(),
+                    path: path.to_string(),
+                    attribute: attribute.to_string(),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_rule_populates_custom_field() {
+        let config = config("AXFocusedWindow/AXToolbar/AXPopUpButton[1]", "AXTitle");
+        assert!(config.validate().is_ok());
+
+        let fields = apply_rules(&config, "com.example.Notebook", &notebook_app(), |_| true);
+        assert_eq!(
+            fields.get("notebook").map(String::as_str),
+            Some("Lab Notes 2026")
+        );
+
+        // Other apps and denied attributes are left alone
+        assert!(apply_rules(&config, "com.apple.Safari", &notebook_app(), |_| true).is_empty());
+        assert!(
+            apply_rules(&config, "com.example.Notebook", &notebook_app(), |a| a
+                != "AXTitle")
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_missing_step_yields_no_field() {
+        let config = config("AXFocusedWindow/AXSplitGroup/AXTextArea", "AXValue");
+        assert!(apply_rules(&config, "com.example.Notebook", &notebook_app(), |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_path_syntax() {
+        let path: AxPath = "AXFocusedWindow / AXGroup[2]/AXButton".parse().unwrap();
+        assert_eq!(path.0.len(), 3);
+        assert_eq!(
+            path.0[1],
+            PathStep {
+                name: "AXGroup".to_string(),
+                index: 2
+            }
+        );
+        assert_eq!(path.to_string(), "AXFocusedWindow/AXGroup[2]/AXButton");
+        assert!("AXGroup[x]".parse::<AxPath>().is_err());
+        assert!("AXGroup]".parse::<AxPath>().is_err());
+        assert!("/".parse::<AxPath>().is_err());
+    }
+}
@@ -0,0 +1,84 @@
+// src/core/flat_json.rs
+//! Single-level JSON records
+//!
+//! Nested records (`app.*`, `workspace.*`, `enhanced.*`) are awkward to load
+//! into tables or to select from in jq pipelines. `flatten` turns a record
+//! into one object with dotted keys, so `{"app": {"bundle_id": ..}}` becomes
+//! `{"app.bundle_id": ..}`. Arrays are kept as values.
+//!
+//! The schema stays the same from one record to the next: every key in the
+//! caller's stable key list is present, `null` when the record has no value
+//! for it, and a section that is `null` as a whole (an event without
+//! `workspace`) yields `null` for each of its keys rather than a key of its
+//! own. Keys outside the list, such as extracted context, appear when set.
+
+use serde_json::{Map, Value};
+
+/// Flatten `value` to dotted keys, with every key in `stable_keys` present
+pub fn flatten(value: &Value, stable_keys: &[&str]) -> Map<String, Value> {
+    let mut flat = Map::new();
+    flatten_into(&mut flat, "", value, stable_keys);
+    for key in stable_keys {
+        flat.entry(key.to_string()).or_insert(Value::Null);
+    }
+    flat
+}
+
+fn flatten_into(flat: &mut Map<String, Value>, prefix: &str, value: &Value, stable_keys: &[&str]) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_into(flat, &key, value, stable_keys);
+            }
+        }
+        // An absent section: its stable keys are filled in with null
+        Value::Null if is_section(prefix, stable_keys) => {}
+        value => {
+            flat.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+/// Whether `prefix` holds nested stable keys
+fn is_section(prefix: &str, stable_keys: &[&str]) -> bool {
+    stable_keys.iter().any(|key| {
+        key.strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_missing_sections_keep_their_keys() {
+        let keys = ["id", "app.name", "app.pid", "workspace.focused_title"];
+        let flat = flatten(
+            &json!({
+                "id": 1,
+                "app": {"name": "Safari", "pid": 42, "tabs": ["a", "b"]},
+                "workspace": null,
+                "enhanced": {"context": {"url": "https://example.com"}},
+            }),
+            &keys,
+        );
+        assert_eq!(
+            Value::Object(flat),
+            json!({
+                "id": 1,
+                "app.name": "Safari",
+                "app.pid": 42,
+                "app.tabs": ["a", "b"],
+                "workspace.focused_title": null,
+                "enhanced.context.url": "https://example.com",
+            })
+        );
+    }
+}
@@ -0,0 +1,135 @@
+// src/core/foreground_follow.rs
+//! Following the foreground app from the switcher's events
+//!
+//! Observers that attach AX notifications to the frontmost app run their own
+//! run loop on a background thread. Rather than asking for the frontmost app
+//! on a timer, they add a `ForegroundFollower` to the switcher: it records
+//! each foreground switch (and each launch or termination, which changes the
+//! set of running apps) and stops the observer's run loop, so the observer
+//! re-attaches as soon as the switch is reported and sleeps otherwise.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use core_foundation::base::{CFRelease, CFRetain, CFTypeRef};
+use core_foundation_sys::runloop::{
+    kCFRunLoopDefaultMode, CFRunLoopGetCurrent, CFRunLoopRef, CFRunLoopRunInMode, CFRunLoopStop,
+};
+
+use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent, AppSwitchListener, AppSwitchType};
+
+/// An observer thread's run loop, retained so other threads can stop it
+struct RunLoopHandle(CFRunLoopRef);
+
+// SAFETY: CFRunLoopStop may be called from any thread
+unsafe impl Send for RunLoopHandle {}
+
+impl RunLoopHandle {
+    fn current() -> Self {
+        // SAFETY: the current run loop is live; retained for this handle
+        Self(unsafe { CFRetain(CFRunLoopGetCurrent() as CFTypeRef) } as CFRunLoopRef)
+    }
+}
+
+impl Drop for RunLoopHandle {
+    fn drop(&mut self) {
+        // SAFETY: retained in `current`
+        unsafe { CFRelease(self.0 as CFTypeRef) };
+    }
+}
+
+#[derive(Default)]
+struct FollowState {
+    /// Foreground app reported since the observer last looked
+    foreground: Mutex<Option<AppInfo>>,
+    /// An app launched or terminated since the observer last looked
+    apps_changed: AtomicBool,
+    run_loop: Mutex<Option<RunLoopHandle>>,
+}
+
+/// What the switcher reported, shared by an observer thread and its follower
+#[derive(Clone, Default)]
+pub struct ForegroundFollow {
+    state: Arc<FollowState>,
+}
+
+impl ForegroundFollow {
+    /// A switcher listener feeding this follow; `name` is its diagnostic name
+    pub fn follower(&self, name: &'static str) -> ForegroundFollower {
+        ForegroundFollower {
+            follow: self.clone(),
+            name,
+        }
+    }
+
+    /// Make the calling thread the one `wake` stops; call on the observer
+    /// thread before its first `wait`
+    pub fn enter(&self) {
+        *self.state.run_loop.lock().unwrap() = Some(RunLoopHandle::current());
+    }
+
+    /// Undo `enter` before the observer thread ends
+    pub fn leave(&self) {
+        self.state.run_loop.lock().unwrap().take();
+    }
+
+    /// Run the current thread's run loop until a source was handled, `wake`
+    /// was called or `seconds` passed
+    pub fn wait(&self, seconds: f64) {
+        // SAFETY: runs this thread's run loop, delivering its sources' callbacks
+        unsafe {
+            CFRunLoopRunInMode(kCFRunLoopDefaultMode, seconds, 1);
+        }
+    }
+
+    /// Stop the entered thread's run loop; a wake before `wait` makes the
+    /// next `wait` return at once
+    pub fn wake(&self) {
+        if let Some(run_loop) = &*self.state.run_loop.lock().unwrap() {
+            // SAFETY: the handle keeps the run loop alive
+            unsafe { CFRunLoopStop(run_loop.0) };
+        }
+    }
+
+    /// The foreground app reported since the last call, if any
+    pub fn take_foreground(&self) -> Option<AppInfo> {
+        self.state.foreground.lock().unwrap().take()
+    }
+
+    /// Whether an app launched or terminated since the last call
+    pub fn take_apps_changed(&self) -> bool {
+        self.state.apps_changed.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// Feeds a `ForegroundFollow` from the switcher's events
+pub struct ForegroundFollower {
+    follow: ForegroundFollow,
+    name: &'static str,
+}
+
+impl AppSwitchListener for ForegroundFollower {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        match event.event_type {
+            AppSwitchType::Foreground
+            | AppSwitchType::SpaceChange
+            | AppSwitchType::SessionChange
+            | AppSwitchType::Wake => {
+                *self.follow.state.foreground.lock().unwrap() = Some(event.app_info.clone());
+            }
+            AppSwitchType::Launch | AppSwitchType::Terminate => {
+                self.follow.state.apps_changed.store(true, Ordering::SeqCst);
+            }
+            AppSwitchType::Background
+            | AppSwitchType::Hide
+            | AppSwitchType::Unhide
+            | AppSwitchType::Sleep
+            | AppSwitchType::Heartbeat => return,
+        }
+        self.follow.wake();
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+}
@@ -0,0 +1,288 @@
+// src/core/heartbeat.rs
+//! Periodic liveness events
+//!
+//! Consumers that track dwell time or show "current context" go stale while
+//! the user stays in one app, since nothing is emitted until the next switch.
+//! `HeartbeatEmitter` sits in front of the real listeners, passes every event
+//! through, and re-announces the current foreground context as a `Heartbeat`
+//! event once no event has gone out for the configured interval.
+//!
+//! Heartbeats are not switches: trackers that only react to `Foreground`
+//! ignore them, and the basic logger does not count them.
+//!
+//! A heartbeat carries the app and the session, never content. Titles, URLs
+//! and the rest were redacted for the moment of the switch; replaying them
+//! later could leak them into quiet hours or past a consent change.
+
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use crate::core::app_switcher_types::{
+    AppSwitchEvent, AppSwitchListener, AppSwitchType, SleepPeriod,
+};
+
+/// Default time between heartbeats
+pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+struct HeartbeatState {
+    interval: Duration,
+    listeners: Vec<Box<dyn AppSwitchListener>>,
+    /// Heartbeat for the latest foreground app; `None` while nothing is
+    /// frontmost
+    current: Option<AppSwitchEvent>,
+    /// When the last event (switch or heartbeat) went out
+    last_emit: Option<Instant>,
+}
+
+impl HeartbeatState {
+    fn record(&mut self, event: &AppSwitchEvent, at: Instant) {
+        match event.event_type {
+            AppSwitchType::Foreground
+            | AppSwitchType::SpaceChange
+            | AppSwitchType::SessionChange
+            | AppSwitchType::Wake => self.current = Some(heartbeat_for(event)),
+            AppSwitchType::Terminate => {
+                let quit = self
+                    .current
+                    .as_ref()
+                    .is_some_and(|current| current.app_info.pid == event.app_info.pid);
+                if quit {
+                    self.current = None;
+                }
+            }
+            AppSwitchType::Background
+            | AppSwitchType::Launch
+            | AppSwitchType::Hide
+            | AppSwitchType::Unhide
+            | AppSwitchType::Sleep
+            | AppSwitchType::Heartbeat => {}
+        }
+        self.last_emit = Some(at);
+        for listener in self.listeners.iter_mut() {
+            listener.on_app_switch(event);
+        }
+    }
+
+    fn tick(&mut self, now: Instant) {
+        let due = self
+            .last_emit
+            .is_some_and(|last| now.saturating_duration_since(last) >= self.interval);
+        if !due {
+            return;
+        }
+        let Some(current) = &self.current else {
+            return;
+        };
+        let mut heartbeat = current.clone();
+        heartbeat.timestamp = now;
+        self.last_emit = Some(now);
+        for listener in self.listeners.iter_mut() {
+            listener.on_app_switch(&heartbeat);
+        }
+    }
+
+    /// Nothing is announced until the next foreground event
+    fn reset(&mut self) {
+        self.current = None;
+        self.last_emit = None;
+    }
+}
+
+/// The app and session of a foreground event, without any of its content
+fn heartbeat_for(event: &AppSwitchEvent) -> AppSwitchEvent {
+    let mut heartbeat = AppSwitchEvent::new(AppSwitchType::Heartbeat, event.app_info.clone());
+    heartbeat.session_id = event.session_id.clone();
+    heartbeat
+}
+
+/// A listener that forwards events and adds heartbeats in quiet periods
+///
+/// The wrapped listeners are called from the switch thread and from the
+/// ticker started by `start_ticker`, one at a time.
+#[derive(Clone)]
+pub struct HeartbeatEmitter {
+    state: Arc<Mutex<HeartbeatState>>,
+}
+
+impl HeartbeatEmitter {
+    pub fn new(interval: Duration, listeners: Vec<Box<dyn AppSwitchListener>>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(HeartbeatState {
+                interval: interval.max(Duration::from_secs(1)),
+                listeners,
+                current: None,
+                last_emit: None,
+            })),
+        }
+    }
+
+    /// Forward an event received at the given instant
+    pub fn record_at(&self, event: &AppSwitchEvent, at: Instant) {
+        self.state.lock().unwrap().record(event, at);
+    }
+
+    /// Emit a heartbeat if the interval has passed since the last event
+    pub fn tick(&self, now: Instant) {
+        self.state.lock().unwrap().tick(now);
+    }
+
+    /// Check for due heartbeats on a background thread until the emitter is
+    /// dropped
+    pub fn start_ticker(&self) {
+        let state: Weak<Mutex<HeartbeatState>> = Arc::downgrade(&self.state);
+        let poll = (self.state.lock().unwrap().interval / 10).max(Duration::from_millis(100));
+        std::thread::spawn(move || loop {
+            std::thread::sleep(poll);
+            match state.upgrade() {
+                Some(state) => state.lock().unwrap().tick(Instant::now()),
+                None => break,
+            }
+        });
+    }
+}
+
+impl AppSwitchListener for HeartbeatEmitter {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        self.record_at(event, Instant::now());
+    }
+
+    fn name(&self) -> &str {
+        "heartbeat"
+    }
+
+    fn on_monitoring_started(&mut self) {
+        for listener in self.state.lock().unwrap().listeners.iter_mut() {
+            listener.on_monitoring_started();
+        }
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.reset();
+        for listener in state.listeners.iter_mut() {
+            listener.on_monitoring_stopped();
+        }
+    }
+
+    fn on_monitoring_paused(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.reset();
+        for listener in state.listeners.iter_mut() {
+            listener.on_monitoring_paused();
+        }
+    }
+
+    fn on_system_sleep(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.reset();
+        for listener in state.listeners.iter_mut() {
+            listener.on_system_sleep();
+        }
+    }
+
+    fn on_system_wake(&mut self, asleep: &SleepPeriod) {
+        for listener in self.state.lock().unwrap().listeners.iter_mut() {
+            listener.on_system_wake(asleep);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, EnhancedSummary};
+
+    struct Recorder(Arc<Mutex<Vec<AppSwitchType>>>);
+
+    impl AppSwitchListener for Recorder {
+        fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+            self.0.lock().unwrap().push(event.event_type.clone());
+        }
+    }
+
+    #[test]
+    fn test_heartbeats_follow_the_interval_without_switches() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let emitter = HeartbeatEmitter::new(
+            DEFAULT_HEARTBEAT_INTERVAL,
+            vec![Box::new(Recorder(Arc::clone(&seen)))],
+        );
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        // Nothing to announce before the first foreground event
+        emitter.tick(at(120));
+        assert!(seen.lock().unwrap().is_empty());
+
+        let xcode = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Xcode".to_string(), "com.apple.dt.Xcode".to_string(), 7),
+        );
+        emitter.record_at(&xcode, at(0));
+        for secs in (10..=200).step_by(10) {
+            emitter.tick(at(secs));
+        }
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                AppSwitchType::Foreground,
+                AppSwitchType::Heartbeat,
+                AppSwitchType::Heartbeat,
+                AppSwitchType::Heartbeat,
+            ]
+        );
+
+        // A real switch restarts the interval
+        seen.lock().unwrap().clear();
+        emitter.record_at(&xcode, at(210));
+        emitter.tick(at(260));
+        emitter.tick(at(270));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![AppSwitchType::Foreground, AppSwitchType::Heartbeat]
+        );
+    }
+
+    struct Capture(Arc<Mutex<Vec<AppSwitchEvent>>>);
+
+    impl AppSwitchListener for Capture {
+        fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[test]
+    fn test_heartbeats_carry_no_content() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let emitter = HeartbeatEmitter::new(
+            DEFAULT_HEARTBEAT_INTERVAL,
+            vec![Box::new(Capture(Arc::clone(&seen)))],
+        );
+        let start = Instant::now();
+
+        let mut safari = AppSwitchEvent::with_previous(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 42),
+            AppInfo::new("Mail".to_string(), "com.apple.mail".to_string(), 9),
+        );
+        safari.session_id = Some("session".to_string());
+        safari.enhanced = Some(EnhancedSummary {
+            front_window_title: Some("Bank statement".to_string()),
+            url: Some("https://bank.example.com/".to_string()),
+            ..EnhancedSummary::default()
+        });
+        safari.screenshot_path = Some("/tmp/shot.png".to_string());
+        emitter.record_at(&safari, start);
+        emitter.tick(start + DEFAULT_HEARTBEAT_INTERVAL);
+
+        let seen = seen.lock().unwrap();
+        let heartbeat = &seen[1];
+        assert_eq!(heartbeat.event_type, AppSwitchType::Heartbeat);
+        assert_eq!(heartbeat.app_info.bundle_id, "com.apple.Safari");
+        assert_eq!(heartbeat.session_id.as_deref(), Some("session"));
+        assert_eq!(heartbeat.timestamp, start + DEFAULT_HEARTBEAT_INTERVAL);
+        assert!(heartbeat.previous_app.is_none());
+        assert!(heartbeat.enhanced.is_none());
+        assert!(heartbeat.screenshot_path.is_none());
+    }
+}
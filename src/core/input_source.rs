@@ -0,0 +1,287 @@
+// src/core/input_source.rs
+//! Keyboard layout and input method
+//!
+//! For multilingual typing, the selected input source tells which language
+//! text is typed in: `com.apple.keylayout.German`, or an input method such
+//! as `com.apple.inputmethod.SCIM.ITABC` for Pinyin. It is read with
+//! `TISCopyCurrentKeyboardInputSource`; macOS posts
+//! `TISNotifySelectedKeyboardInputSourceChanged` on the distributed
+//! notification center when it changes. `InputSourceObserver` reports each
+//! change and stamps the current source id on every event.
+
+use std::ffi::c_void;
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
+
+use block2::RcBlock;
+use chrono::{DateTime, Utc};
+use core_foundation::base::{CFRelease, TCFType};
+use core_foundation::string::{CFString, CFStringRef};
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_foundation::{
+    NSDistributedNotificationCenter, NSNotification, NSObjectProtocol, NSString,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+
+/// `kTISNotifySelectedKeyboardInputSourceChanged`
+const INPUT_SOURCE_CHANGED_NOTIFICATION: &str =
+    "com.apple.Carbon.TISNotifySelectedKeyboardInputSourceChanged";
+
+type TISInputSourceRef = *mut c_void;
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn TISCopyCurrentKeyboardInputSource() -> TISInputSourceRef;
+    fn TISGetInputSourceProperty(source: TISInputSourceRef, key: CFStringRef) -> *const c_void;
+    static kTISPropertyInputSourceID: CFStringRef;
+    static kTISPropertyLocalizedName: CFStringRef;
+}
+
+/// Labels of input sources whose id does not read as their name
+const KNOWN_LABELS: &[(&str, &str)] = &[
+    ("com.apple.keylayout.US", "U.S."),
+    (
+        "com.apple.keylayout.USInternational-PC",
+        "U.S. International - PC",
+    ),
+    ("com.apple.keylayout.British", "British"),
+    ("com.apple.keylayout.ABC", "ABC"),
+    ("com.apple.inputmethod.SCIM.ITABC", "Pinyin - Simplified"),
+    ("com.apple.inputmethod.TCIM.Zhuyin", "Zhuyin - Traditional"),
+    (
+        "com.apple.inputmethod.Kotoeri.RomajiTyping.Japanese",
+        "Japanese - Romaji",
+    ),
+    ("com.apple.inputmethod.Korean.2SetKorean", "Korean - 2-Set"),
+];
+
+/// A human-readable label for an input source id
+///
+/// Known ids map to the names shown in the menu bar; others are derived
+/// from the last component of the id, so `com.apple.keylayout.Russian-Phonetic`
+/// becomes `Russian Phonetic`.
+pub fn input_source_label(id: &str) -> String {
+    if let Some((_, label)) = KNOWN_LABELS.iter().find(|(known, _)| *known == id) {
+        return label.to_string();
+    }
+    let last = id.rsplit('.').next().unwrap_or(id);
+    let label = last
+        .split(['-', '_'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if label.is_empty() {
+        id.to_string()
+    } else {
+        label
+    }
+}
+
+/// The selected keyboard layout or input method
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputSource {
+    /// `com.apple.keylayout.German`
+    pub id: String,
+    /// The localized name, or one derived from the id
+    pub label: String,
+}
+
+/// A string property of an input source
+///
+/// # Safety
+/// `source` must be a live input source
+unsafe fn string_property(source: TISInputSourceRef, key: CFStringRef) -> Option<String> {
+    let value = unsafe { TISGetInputSourceProperty(source, key) };
+    // SAFETY: the value is owned by the input source
+    (!value.is_null())
+        .then(|| unsafe { CFString::wrap_under_get_rule(value as CFStringRef) }.to_string())
+}
+
+/// The current keyboard input source
+pub fn current_input_source() -> Option<InputSource> {
+    // SAFETY: returns a +1 input source or null; the properties are read
+    // before it is released
+    unsafe {
+        let source = TISCopyCurrentKeyboardInputSource();
+        if source.is_null() {
+            return None;
+        }
+        let id = string_property(source, kTISPropertyInputSourceID);
+        let name = string_property(source, kTISPropertyLocalizedName);
+        CFRelease(source as *const c_void);
+        let id = id?;
+        let label = name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| input_source_label(&id));
+        Some(InputSource { id, label })
+    }
+}
+
+/// The user switched keyboard layout or input method
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputSourceChange {
+    pub timestamp: DateTime<Utc>,
+    pub previous: Option<InputSource>,
+    pub current: InputSource,
+}
+
+/// Receives input source changes
+pub trait InputSourceListener: Send {
+    fn on_input_source_change(&mut self, event: &InputSourceChange);
+}
+
+struct ObserverState {
+    listeners: Vec<Box<dyn InputSourceListener>>,
+    last: Option<InputSource>,
+}
+
+impl ObserverState {
+    /// Report `current` if its id differs from the last one seen
+    fn update(&mut self, current: InputSource, timestamp: DateTime<Utc>) {
+        if self.last.as_ref().is_some_and(|last| last.id == current.id) {
+            return;
+        }
+        let event = InputSourceChange {
+            timestamp,
+            previous: self.last.replace(current.clone()),
+            current,
+        };
+        for listener in self.listeners.iter_mut() {
+            listener.on_input_source_change(&event);
+        }
+    }
+}
+
+/// Watches for keyboard layout and input method switches
+///
+/// Notifications are delivered on the main thread's run loop, so `start`
+/// must be called from the main thread while that loop is running.
+pub struct InputSourceObserver {
+    state: Arc<Mutex<ObserverState>>,
+    token: Option<Retained<ProtocolObject<dyn NSObjectProtocol>>>,
+}
+
+impl InputSourceObserver {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ObserverState {
+                listeners: Vec::new(),
+                last: current_input_source(),
+            })),
+            token: None,
+        }
+    }
+
+    pub fn add_listener<T: InputSourceListener + 'static>(&self, listener: T) {
+        self.state
+            .lock()
+            .unwrap()
+            .listeners
+            .push(Box::new(listener));
+    }
+
+    /// The input source last seen
+    pub fn input_source(&self) -> Option<InputSource> {
+        self.state.lock().unwrap().last.clone()
+    }
+
+    /// An enrichment stage stamping the current input source id on events
+    pub fn tagger(&self) -> InputSourceTagger {
+        InputSourceTagger {
+            state: Arc::clone(&self.state),
+        }
+    }
+
+    pub fn start(&mut self) {
+        if self.token.is_some() {
+            return;
+        }
+        let state = Arc::clone(&self.state);
+        let block = RcBlock::new(move |_notification: NonNull<NSNotification>| {
+            if let Some(source) = current_input_source() {
+                if let Ok(mut state) = state.lock() {
+                    state.update(source, Utc::now());
+                }
+            }
+        });
+        let name = NSString::from_str(INPUT_SOURCE_CHANGED_NOTIFICATION);
+        let token = unsafe {
+            NSDistributedNotificationCenter::defaultCenter()
+                .addObserverForName_object_queue_usingBlock(Some(&name), None, None, &block)
+        };
+        self.token = Some(token);
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(token) = self.token.take() {
+            unsafe {
+                NSDistributedNotificationCenter::defaultCenter().removeObserver(token.as_ref());
+            }
+        }
+    }
+}
+
+impl Default for InputSourceObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for InputSourceObserver {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Stamps the current input source on every event
+pub struct InputSourceTagger {
+    state: Arc<Mutex<ObserverState>>,
+}
+
+impl EnrichmentStage for InputSourceTagger {
+    fn name(&self) -> &str {
+        "input_source"
+    }
+
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Annotate
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        event.input_source = self
+            .state
+            .lock()
+            .unwrap()
+            .last
+            .as_ref()
+            .map(|source| source.id.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_source_labels() {
+        assert_eq!(input_source_label("com.apple.keylayout.US"), "U.S.");
+        assert_eq!(
+            input_source_label("com.apple.inputmethod.SCIM.ITABC"),
+            "Pinyin - Simplified"
+        );
+        assert_eq!(input_source_label("com.apple.keylayout.German"), "German");
+        assert_eq!(
+            input_source_label("com.apple.keylayout.Russian-Phonetic"),
+            "Russian Phonetic"
+        );
+        assert_eq!(
+            input_source_label("org.sil.ukelele.keyboardlayout.custom.Dvorak_Left"),
+            "Dvorak Left"
+        );
+        assert_eq!(input_source_label(""), "");
+    }
+}
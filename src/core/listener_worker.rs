@@ -0,0 +1,453 @@
+// src/core/listener_worker.rs
+//! Listener delivery off the run-loop thread
+//!
+//! Listeners run on the thread that observed the switch, normally the main
+//! CFRunLoop thread, so one slow sink (a database, a socket, an AppleScript
+//! lookup) delays every notification after it. `ListenerWorker` is a single
+//! listener that only clones the event into a bounded queue; a dedicated
+//! thread owns the real listeners and calls them one event at a time, in
+//! order.
+//!
+//! When the queue is full the `OverflowPolicy` decides: block the sender
+//! until there is room, drop the oldest queued event or drop the new one.
+//! Lifecycle calls (started, paused, stopped, sleep and wake) are never
+//! dropped.
+//!
+//! A listener that panics is logged and removed; the others keep receiving
+//! events. This relies on panics unwinding, which is why the release profile
+//! sets `panic = "unwind"`. Should the worker thread end anyway, the queue is
+//! closed so a blocked sender or a `flush` returns instead of waiting forever.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::str::FromStr;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchListener, SleepPeriod};
+
+/// Default number of events that may wait for the worker
+pub const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+
+/// What happens to an event sent while the queue is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Wait for the worker to make room
+    #[default]
+    Block,
+    /// Discard the oldest queued event to make room
+    DropOldest,
+    /// Discard the event being sent
+    DropNew,
+}
+
+impl fmt::Display for OverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverflowPolicy::Block => write!(f, "block"),
+            OverflowPolicy::DropOldest => write!(f, "drop-oldest"),
+            OverflowPolicy::DropNew => write!(f, "drop-new"),
+        }
+    }
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "block" => Ok(OverflowPolicy::Block),
+            "drop-oldest" => Ok(OverflowPolicy::DropOldest),
+            "drop-new" => Ok(OverflowPolicy::DropNew),
+            other => Err(format!(
+                "Invalid overflow policy '{}' (expected block, drop-oldest or drop-new)",
+                other
+            )),
+        }
+    }
+}
+
+enum Message {
+    Event(Box<AppSwitchEvent>),
+    Started,
+    Paused,
+    Stopped,
+    Sleep,
+    Wake(SleepPeriod),
+}
+
+impl Message {
+    fn is_event(&self) -> bool {
+        matches!(self, Message::Event(_))
+    }
+}
+
+struct QueueState {
+    items: VecDeque<Message>,
+    events: usize,
+    /// Messages queued and messages handled, for `flush`
+    sent: u64,
+    handled: u64,
+    dropped: u64,
+    closed: bool,
+}
+
+/// Bounded queue with a configurable overflow policy
+struct Queue {
+    state: Mutex<QueueState>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    /// Signalled when a message is queued or the queue closes
+    not_empty: Condvar,
+    /// Signalled when an event leaves the queue or a message is handled
+    progress: Condvar,
+}
+
+impl Queue {
+    fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                items: VecDeque::new(),
+                events: 0,
+                sent: 0,
+                handled: 0,
+                dropped: 0,
+                closed: false,
+            }),
+            capacity: capacity.max(1),
+            policy,
+            not_empty: Condvar::new(),
+            progress: Condvar::new(),
+        }
+    }
+
+    fn push(&self, message: Message) {
+        let mut state = self.state.lock().unwrap();
+        if message.is_event() {
+            while state.events >= self.capacity && !state.closed {
+                match self.policy {
+                    OverflowPolicy::Block => state = self.progress.wait(state).unwrap(),
+                    OverflowPolicy::DropNew => {
+                        state.dropped += 1;
+                        return;
+                    }
+                    OverflowPolicy::DropOldest => {
+                        if let Some(at) = state.items.iter().position(Message::is_event) {
+                            state.items.remove(at);
+                            state.events -= 1;
+                            // Counted as handled so a pending `flush` does not wait for it
+                            state.handled += 1;
+                            state.dropped += 1;
+                            self.progress.notify_all();
+                        }
+                    }
+                }
+            }
+            if state.closed {
+                return;
+            }
+            state.events += 1;
+        }
+        state.items.push_back(message);
+        state.sent += 1;
+        self.not_empty.notify_one();
+    }
+
+    /// Next message, waiting for one; `None` once closed and drained
+    fn pop(&self) -> Option<Message> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(message) = state.items.pop_front() {
+                if message.is_event() {
+                    state.events -= 1;
+                    self.progress.notify_all();
+                }
+                return Some(message);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self.not_empty.wait(state).unwrap();
+        }
+    }
+
+    fn mark_handled(&self) {
+        self.state.lock().unwrap().handled += 1;
+        self.progress.notify_all();
+    }
+
+    /// Wait until everything queued so far has been handled
+    fn flush(&self) {
+        let mut state = self.state.lock().unwrap();
+        let target = state.sent;
+        while state.handled < target && !state.closed {
+            state = self.progress.wait(state).unwrap();
+        }
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.not_empty.notify_all();
+        self.progress.notify_all();
+    }
+}
+
+/// A listener that hands events to a worker thread owning the real listeners
+pub struct ListenerWorker {
+    queue: Arc<Queue>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ListenerWorker {
+    /// Start the worker thread with the listeners it delivers to
+    pub fn spawn(
+        mut listeners: Vec<Box<dyn AppSwitchListener>>,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Self {
+        let queue = Arc::new(Queue::new(capacity, policy));
+        let worker_queue = Arc::clone(&queue);
+        let thread = thread::Builder::new()
+            .name("listener_worker".into())
+            .spawn(move || {
+                let _close = CloseOnExit(Arc::clone(&worker_queue));
+                while let Some(message) = worker_queue.pop() {
+                    listeners.retain_mut(|listener| deliver(listener.as_mut(), &message));
+                    worker_queue.mark_handled();
+                }
+            })
+            .expect("failed to spawn listener worker");
+        Self {
+            queue,
+            thread: Some(thread),
+        }
+    }
+
+    /// Events discarded by the overflow policy so far
+    pub fn dropped(&self) -> u64 {
+        self.queue.state.lock().unwrap().dropped
+    }
+
+    /// Wait until every event sent so far has been delivered
+    pub fn flush(&self) {
+        self.queue.flush();
+    }
+}
+
+/// Hand one message to a listener; `false` when it panicked
+fn deliver(listener: &mut dyn AppSwitchListener, message: &Message) -> bool {
+    let result = catch_unwind(AssertUnwindSafe(|| match message {
+        Message::Event(event) => listener.on_app_switch(event),
+        Message::Started => listener.on_monitoring_started(),
+        Message::Paused => listener.on_monitoring_paused(),
+        Message::Stopped => listener.on_monitoring_stopped(),
+        Message::Sleep => listener.on_system_sleep(),
+        Message::Wake(asleep) => listener.on_system_wake(asleep),
+    }));
+    if result.is_err() {
+        tracing::error!("Listener {} panicked and was removed", listener.name());
+    }
+    result.is_ok()
+}
+
+/// Closes the queue when the worker thread ends, however it ends
+struct CloseOnExit(Arc<Queue>);
+
+impl Drop for CloseOnExit {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+impl AppSwitchListener for ListenerWorker {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        self.queue.push(Message::Event(Box::new(event.clone())));
+    }
+
+    fn name(&self) -> &str {
+        "listener_worker"
+    }
+
+    fn on_monitoring_started(&mut self) {
+        self.queue.push(Message::Started);
+    }
+
+    fn on_monitoring_paused(&mut self) {
+        self.queue.push(Message::Paused);
+    }
+
+    fn on_system_sleep(&mut self) {
+        self.queue.push(Message::Sleep);
+    }
+
+    fn on_system_wake(&mut self, asleep: &SleepPeriod) {
+        self.queue.push(Message::Wake(asleep.clone()));
+    }
+
+    /// Sinks finish their work (final sessions, flushes) before this returns
+    fn on_monitoring_stopped(&mut self) {
+        self.queue.push(Message::Stopped);
+        self.queue.flush();
+    }
+}
+
+impl Drop for ListenerWorker {
+    fn drop(&mut self) {
+        self.queue.close();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType};
+
+    fn event(n: usize) -> AppSwitchEvent {
+        AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new(
+                format!("App {}", n),
+                format!("com.example.app{}", n),
+                n as i32,
+            ),
+        )
+    }
+
+    fn queued_pids(queue: &Queue) -> Vec<i32> {
+        queue
+            .state
+            .lock()
+            .unwrap()
+            .items
+            .iter()
+            .filter_map(|m| match m {
+                Message::Event(e) => Some(e.app_info.pid),
+                _ => None,
+            })
+            .collect()
+    }
+
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl AppSwitchListener for Recorder {
+        fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(event.app_info.bundle_id.clone());
+        }
+
+        fn on_monitoring_stopped(&mut self) {
+            self.0.lock().unwrap().push("stopped".to_string());
+        }
+    }
+
+    #[test]
+    fn test_worker_delivers_in_order() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut worker = ListenerWorker::spawn(
+            vec![Box::new(Recorder(Arc::clone(&seen)))],
+            4,
+            OverflowPolicy::Block,
+        );
+        for n in 0..50 {
+            worker.on_app_switch(&event(n));
+        }
+        worker.on_monitoring_stopped();
+
+        let seen = seen.lock().unwrap();
+        let mut expected: Vec<String> = (0..50).map(|n| format!("com.example.app{}", n)).collect();
+        expected.push("stopped".to_string());
+        assert_eq!(*seen, expected);
+        assert_eq!(worker.dropped(), 0);
+    }
+
+    struct Panicker;
+
+    impl AppSwitchListener for Panicker {
+        fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+            if event.app_info.pid == 1 {
+                panic!("sink failed");
+            }
+        }
+    }
+
+    #[test]
+    fn test_panicking_listener_is_removed() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut worker = ListenerWorker::spawn(
+            vec![Box::new(Panicker), Box::new(Recorder(Arc::clone(&seen)))],
+            1,
+            OverflowPolicy::Block,
+        );
+        // With a queue of one, a dead worker would leave these blocked
+        for n in 0..5 {
+            worker.on_app_switch(&event(n));
+        }
+        worker.on_monitoring_stopped();
+
+        assert_eq!(seen.lock().unwrap().len(), 6);
+        assert!(worker.thread.as_ref().is_some_and(|t| !t.is_finished()));
+    }
+
+    #[test]
+    fn test_flush_returns_after_the_worker_ends() {
+        let queue = Arc::new(Queue::new(1, OverflowPolicy::Block));
+        queue.push(Message::Event(Box::new(event(0))));
+        let worker = {
+            let queue = Arc::clone(&queue);
+            thread::spawn(move || {
+                let _close = CloseOnExit(queue);
+                panic!("worker died");
+            })
+        };
+        assert!(worker.join().is_err());
+
+        // Neither waits for a worker that is gone
+        queue.push(Message::Event(Box::new(event(1))));
+        queue.flush();
+    }
+
+    #[test]
+    fn test_overflow_policies() {
+        let drop_new = Queue::new(2, OverflowPolicy::DropNew);
+        for n in 0..3 {
+            drop_new.push(Message::Event(Box::new(event(n))));
+        }
+        assert_eq!(queued_pids(&drop_new), vec![0, 1]);
+
+        let drop_oldest = Queue::new(2, OverflowPolicy::DropOldest);
+        drop_oldest.push(Message::Started);
+        for n in 0..3 {
+            drop_oldest.push(Message::Event(Box::new(event(n))));
+        }
+        assert_eq!(queued_pids(&drop_oldest), vec![1, 2]);
+        // Lifecycle messages are kept
+        assert!(matches!(
+            drop_oldest.state.lock().unwrap().items.front(),
+            Some(Message::Started)
+        ));
+        assert_eq!(drop_oldest.state.lock().unwrap().dropped, 1);
+
+        // A blocked sender continues once the worker takes an event
+        let block = Arc::new(Queue::new(1, OverflowPolicy::Block));
+        block.push(Message::Event(Box::new(event(0))));
+        let sender = {
+            let block = Arc::clone(&block);
+            thread::spawn(move || block.push(Message::Event(Box::new(event(1)))))
+        };
+        thread::sleep(std::time::Duration::from_millis(50));
+        assert_eq!(queued_pids(&block), vec![0]);
+        assert!(block.pop().is_some());
+        sender.join().unwrap();
+        assert_eq!(queued_pids(&block), vec![1]);
+        assert_eq!(block.state.lock().unwrap().dropped, 0);
+    }
+}
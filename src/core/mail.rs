@@ -0,0 +1,365 @@
+// src/core/mail.rs
+//! Mailbox and thread subject in Mail and Outlook
+//!
+//! The message viewer names the selected mailbox in its window title. Mail
+//! writes `Inbox (1,204 messages, 3 unread)`, or `Inbox – 1,204 messages,
+//! 3 unread` since Big Sur moved the counts into a subtitle; Outlook writes
+//! `Inbox • user@example.com` (new Outlook) or `Inbox - user@example.com`.
+//! A message or draft opened in its own window is titled with its subject.
+//! When the viewer is frontmost, the selected message's subject and mailbox
+//! come from AppleScript.
+//!
+//! Subjects are message content: they are only read with content capture
+//! enabled, and reply and forward prefixes are stripped so every message of
+//! a thread reports the same `conversation`.
+
+use crate::core::accessibility::AccessibilityContext;
+use crate::core::browser_strategy::{parse_applescript_output, run_applescript};
+
+/// A supported mail client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailApp {
+    Mail,
+    Outlook,
+}
+
+/// What a mail window is showing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MailWindow {
+    /// The viewer, with this mailbox or folder selected
+    Mailbox(String),
+    /// A message or draft in its own window, titled with the subject
+    Message(String),
+}
+
+/// Standard mailboxes, recognised without a message count or account
+const STANDARD_MAILBOXES: &[&str] = &[
+    "Inbox",
+    "All Inboxes",
+    "VIPs",
+    "Flagged",
+    "Drafts",
+    "Sent",
+    "Sent Messages",
+    "Sent Items",
+    "Outbox",
+    "Archive",
+    "Junk",
+    "Junk Email",
+    "Trash",
+    "Deleted Items",
+];
+
+/// Titles of compose windows before a subject is typed
+const UNTITLED_DRAFTS: &[&str] = &["New Message", "Untitled"];
+
+/// Reply and forward markers in English, German, Dutch, French and the
+/// Scandinavian languages
+const REPLY_PREFIXES: &[&str] = &["re", "fw", "fwd", "aw", "wg", "sv", "vs", "antw", "tr"];
+
+impl MailApp {
+    pub fn for_bundle_id(bundle_id: &str) -> Option<Self> {
+        match bundle_id {
+            "com.apple.mail" => Some(Self::Mail),
+            "com.microsoft.Outlook" => Some(Self::Outlook),
+            _ => None,
+        }
+    }
+
+    /// AppleScript returning the subject and mailbox of the first selected
+    /// message on two lines, or nothing without a selection. Without
+    /// `with_subject` the subject line is left empty and never read.
+    pub fn selection_script(&self, with_subject: bool) -> &'static str {
+        match (self, with_subject) {
+            (Self::Mail, true) => {
+                r#"tell application "Mail"
+    set msgs to selection
+    if msgs is {} then return ""
+    set m to item 1 of msgs
+    return (subject of m) & linefeed & (name of mailbox of m)
+end tell"#
+            }
+            (Self::Mail, false) => {
+                r#"tell application "Mail"
+    set msgs to selection
+    if msgs is {} then return ""
+    set m to item 1 of msgs
+    return "" & linefeed & (name of mailbox of m)
+end tell"#
+            }
+            (Self::Outlook, true) => {
+                r#"tell application "Microsoft Outlook"
+    set msgs to selected objects
+    if msgs is {} then return ""
+    set m to item 1 of msgs
+    return (subject of m) & linefeed & (name of folder of m)
+end tell"#
+            }
+            (Self::Outlook, false) => {
+                r#"tell application "Microsoft Outlook"
+    set msgs to selected objects
+    if msgs is {} then return ""
+    set m to item 1 of msgs
+    return "" & linefeed & (name of folder of m)
+end tell"#
+            }
+        }
+    }
+
+    pub fn parse_title(&self, title: &str) -> Option<MailWindow> {
+        let title = title.trim();
+        if title.is_empty() || UNTITLED_DRAFTS.contains(&title) {
+            return None;
+        }
+        let mailbox = match self {
+            Self::Mail => parse_mail_viewer_title(title),
+            Self::Outlook => parse_outlook_viewer_title(title),
+        };
+        Some(match mailbox {
+            Some(mailbox) => MailWindow::Mailbox(mailbox),
+            None => MailWindow::Message(title.to_string()),
+        })
+    }
+}
+
+/// `12 messages, 3 unread`, `1 message`, `1.204 messages`
+fn is_message_count(s: &str) -> bool {
+    s.trim().starts_with(|c: char| c.is_ascii_digit()) && s.to_lowercase().contains("message")
+}
+
+fn is_standard_mailbox(name: &str) -> bool {
+    STANDARD_MAILBOXES
+        .iter()
+        .any(|mailbox| mailbox.eq_ignore_ascii_case(name))
+}
+
+/// Mailbox named by a Mail viewer title, `None` for a message window
+pub fn parse_mail_viewer_title(title: &str) -> Option<String> {
+    // `Inbox (12 messages, 3 unread)`
+    if let Some((mailbox, count)) = title
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(" ("))
+    {
+        if is_message_count(count) && !mailbox.trim().is_empty() {
+            return Some(mailbox.trim().to_string());
+        }
+    }
+    // `Inbox – 12 messages, 3 unread` or `Inbox — iCloud`
+    for separator in [" – ", " — ", " - "] {
+        if let Some((mailbox, rest)) = title.rsplit_once(separator) {
+            let mailbox = mailbox.trim();
+            if !mailbox.is_empty() && (is_message_count(rest) || is_standard_mailbox(mailbox)) {
+                return Some(mailbox.to_string());
+            }
+        }
+    }
+    Some(title.to_string()).filter(|title| is_standard_mailbox(title))
+}
+
+/// Folder named by an Outlook viewer title, `None` for a message window
+pub fn parse_outlook_viewer_title(title: &str) -> Option<String> {
+    // `Inbox • user@example.com` or `Inbox - user@example.com`
+    for separator in [" • ", " - "] {
+        if let Some((folder, account)) = title.rsplit_once(separator) {
+            let folder = folder.trim();
+            if !folder.is_empty() && (account.contains('@') || is_standard_mailbox(folder)) {
+                return Some(folder.to_string());
+            }
+        }
+    }
+    Some(title.to_string()).filter(|title| is_standard_mailbox(title))
+}
+
+/// Subject without `Re:`, `Fwd:`, `AW:`, `RE[2]:` and similar prefixes
+pub fn thread_subject(subject: &str) -> Option<String> {
+    let mut rest = subject.trim();
+    'strip: loop {
+        for prefix in REPLY_PREFIXES {
+            let Some(head) = rest.get(..prefix.len()) else {
+                continue;
+            };
+            if !head.eq_ignore_ascii_case(prefix) {
+                continue;
+            }
+            let mut tail = rest[prefix.len()..].trim_start();
+            // Outlook numbers repeated replies: `RE[2]:`
+            if let Some(counted) = tail.strip_prefix('[') {
+                if let Some((digits, after)) = counted.split_once(']') {
+                    if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+                        tail = after.trim_start();
+                    }
+                }
+            }
+            if let Some(after) = tail.strip_prefix(':').or_else(|| tail.strip_prefix('：')) {
+                rest = after.trim_start();
+                continue 'strip;
+            }
+        }
+        break;
+    }
+    Some(rest.trim().to_string()).filter(|subject| !subject.is_empty())
+}
+
+/// Subject and mailbox from the output of `MailApp::selection_script`
+pub fn parse_selection(output: &str) -> (Option<String>, Option<String>) {
+    let output = output.trim_end_matches(['\r', '\n']);
+    let (subject, mailbox) = output.split_once('\n').unwrap_or((output, ""));
+    (
+        parse_applescript_output(subject),
+        parse_applescript_output(mailbox),
+    )
+}
+
+/// Fills `mail_folder` and `conversation` for Mail and Outlook windows
+pub struct MailContextExtractor;
+
+impl MailContextExtractor {
+    pub fn applies_to(bundle_id: &str) -> bool {
+        MailApp::for_bundle_id(bundle_id).is_some()
+    }
+
+    /// The window title first; for the viewer, the selected message fills
+    /// in what the title leaves out. Subjects need `capture_content`.
+    pub fn apply(&self, context: &mut AccessibilityContext, capture_content: bool) {
+        let Some(app) = MailApp::for_bundle_id(&context.app_info.bundle_id) else {
+            return;
+        };
+        let window = context
+            .window_title
+            .as_deref()
+            .and_then(|title| app.parse_title(title));
+        match window {
+            Some(MailWindow::Message(subject)) => {
+                if capture_content {
+                    context.conversation = thread_subject(&subject);
+                }
+                return;
+            }
+            Some(MailWindow::Mailbox(mailbox)) => context.mail_folder = Some(mailbox),
+            None => {}
+        }
+
+        if !capture_content && context.mail_folder.is_some() {
+            return;
+        }
+        let Some(output) = run_applescript(app.selection_script(capture_content)) else {
+            return;
+        };
+        let (subject, mailbox) = parse_selection(&output);
+        if context.mail_folder.is_none() {
+            context.mail_folder = mailbox;
+        }
+        if capture_content {
+            context.conversation = subject.as_deref().and_then(thread_subject);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mailbox(name: &str) -> Option<MailWindow> {
+        Some(MailWindow::Mailbox(name.to_string()))
+    }
+
+    fn message(subject: &str) -> Option<MailWindow> {
+        Some(MailWindow::Message(subject.to_string()))
+    }
+
+    #[test]
+    fn test_mail_titles() {
+        let mail = MailApp::Mail;
+        assert_eq!(
+            mail.parse_title("Inbox (1,204 messages, 3 unread)"),
+            mailbox("Inbox")
+        );
+        assert_eq!(
+            mail.parse_title("Lab Notes (1 message)"),
+            mailbox("Lab Notes")
+        );
+        assert_eq!(
+            mail.parse_title("All Inboxes – 12 messages, 3 unread"),
+            mailbox("All Inboxes")
+        );
+        assert_eq!(mail.parse_title("Sent — iCloud"), mailbox("Sent"));
+        assert_eq!(mail.parse_title("Drafts"), mailbox("Drafts"));
+
+        // Message windows, including subjects with dashes and parentheses
+        assert_eq!(
+            mail.parse_title("Re: Grant report – final draft"),
+            message("Re: Grant report – final draft")
+        );
+        assert_eq!(
+            mail.parse_title("Meeting notes (draft)"),
+            message("Meeting notes (draft)")
+        );
+        assert_eq!(mail.parse_title("New Message"), None);
+        assert_eq!(mail.parse_title("  "), None);
+    }
+
+    #[test]
+    fn test_outlook_titles() {
+        let outlook = MailApp::Outlook;
+        assert_eq!(
+            outlook.parse_title("Inbox • ada@example.com"),
+            mailbox("Inbox")
+        );
+        assert_eq!(
+            outlook.parse_title("Project Alpha - ada@example.com"),
+            mailbox("Project Alpha")
+        );
+        assert_eq!(
+            outlook.parse_title("Deleted Items - On My Computer"),
+            mailbox("Deleted Items")
+        );
+        assert_eq!(outlook.parse_title("Inbox"), mailbox("Inbox"));
+        assert_eq!(
+            outlook.parse_title("RE: Budget - Q3 numbers"),
+            message("RE: Budget - Q3 numbers")
+        );
+        assert_eq!(outlook.parse_title("Untitled"), None);
+    }
+
+    #[test]
+    fn test_thread_subject_strips_reply_prefixes() {
+        let subject = |s| thread_subject(s).unwrap();
+        assert_eq!(subject("Re: Grant report"), "Grant report");
+        assert_eq!(subject("RE: FW: Re: Grant report"), "Grant report");
+        assert_eq!(subject("Fwd:Grant report"), "Grant report");
+        assert_eq!(subject("AW: WG: Antrag"), "Antrag");
+        assert_eq!(subject("RE[2]: Budget"), "Budget");
+        // Words that merely start like a prefix are kept
+        assert_eq!(subject("Review: chapter 3"), "Review: chapter 3");
+        assert_eq!(subject("Results"), "Results");
+        assert_eq!(thread_subject("Re: "), None);
+    }
+
+    #[test]
+    fn test_selection_script_reads_subject_only_with_content() {
+        for app in [MailApp::Mail, MailApp::Outlook] {
+            assert!(app.selection_script(true).contains("subject of m"));
+            assert!(!app.selection_script(false).contains("subject"));
+        }
+        assert_eq!(
+            parse_selection("\nInbox"),
+            (None, Some("Inbox".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(
+            parse_selection("Re: Grant report\nInbox\n"),
+            (
+                Some("Re: Grant report".to_string()),
+                Some("Inbox".to_string())
+            )
+        );
+        assert_eq!(
+            parse_selection("missing value\nArchive"),
+            (None, Some("Archive".to_string()))
+        );
+        assert_eq!(parse_selection(""), (None, None));
+    }
+}
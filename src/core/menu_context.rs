@@ -0,0 +1,483 @@
+// src/core/menu_context.rs
+//! The open menu as context
+//!
+//! Much of the work in Mac apps goes through menus (Format › Align ›
+//! Center, File › Export › PDF), and menu-bar-only apps have no other UI
+//! than the menu of their menu extra. Neither shows up in window titles.
+//! The observer listens for AXMenuOpened, AXMenuItemSelected and
+//! AXMenuClosed on the frontmost app and on every menu-bar-only app, and
+//! assembles the path of the highlighted item from the titles of its
+//! ancestors. It follows the switcher's events (see `ForegroundFollow`):
+//! foreground switches move it to the new frontmost app, and launches and
+//! terminations refresh the menu-bar-only apps. Events carry that path as `menu_context` while a menu is open
+//! and for a moment after it closes, so the switch a menu command causes
+//! (a Preferences window, an export dialog) is tied to the command.
+//!
+//! Menus of a menu-bar-only app are prefixed with the app's name, since that
+//! app is not the one the event is for.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use accessibility_sys::{
+    kAXErrorSuccess, kAXMenuClosedNotification, kAXMenuItemSelectedNotification,
+    kAXMenuOpenedNotification, AXObserverAddNotification, AXObserverCreate,
+    AXObserverGetRunLoopSource, AXObserverRef, AXUIElementGetPid, AXUIElementRef,
+};
+use core_foundation::base::{CFRelease, CFTypeRef, TCFType};
+use core_foundation::string::{CFString, CFStringRef};
+use core_foundation_sys::runloop::{
+    kCFRunLoopDefaultMode, CFRunLoopAddSource, CFRunLoopGetCurrent, CFRunLoopRemoveSource,
+};
+
+use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent};
+use crate::core::ax_element::AxElement;
+use crate::core::foreground_follow::{ForegroundFollow, ForegroundFollower};
+use crate::core::running_apps::{frontmost_app, menu_bar_apps, RunningAppEntry};
+
+/// Separator between the levels of a menu path
+pub const MENU_PATH_SEPARATOR: &str = " › ";
+
+/// How long the last path is kept after its menu closes
+pub const DEFAULT_MENU_LINGER: Duration = Duration::from_secs(2);
+
+/// Longest the observer's run loop sleeps; app switches and `stop` wake it
+/// sooner
+const IDLE_WAIT: f64 = 5.0;
+
+/// Ancestors walked up from a menu or menu item
+const MAX_MENU_DEPTH: usize = 16;
+
+/// One element on the way from a menu item up to the menu bar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuAncestor {
+    pub role: String,
+    pub title: Option<String>,
+}
+
+/// Titles of the menu bar item and menu items in `chain`, outermost first;
+/// `chain` starts at the notified element and goes up through its parents
+pub fn assemble_menu_path(chain: &[MenuAncestor]) -> Vec<String> {
+    let mut path: Vec<String> = chain
+        .iter()
+        .take_while(|ancestor| !matches!(ancestor.role.as_str(), "AXMenuBar" | "AXApplication"))
+        .filter(|ancestor| matches!(ancestor.role.as_str(), "AXMenuItem" | "AXMenuBarItem"))
+        .filter_map(|ancestor| ancestor.title.as_deref())
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .map(str::to_string)
+        .collect();
+    path.reverse();
+    path
+}
+
+/// A menu notification, with the path of the notified menu or item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuEvent {
+    /// A menu or submenu opened; the path leads to the item that opened it
+    Opened(Vec<String>),
+    /// An item was highlighted or chosen
+    ItemSelected(Vec<String>),
+    /// A menu or submenu closed
+    Closed,
+}
+
+/// The path of the highlighted item, followed across notifications
+#[derive(Debug)]
+pub struct MenuPathTracker {
+    linger: Duration,
+    /// Menus and submenus currently open
+    open: usize,
+    path: Vec<String>,
+    /// When the last open menu closed
+    closed_at: Option<Instant>,
+}
+
+impl Default for MenuPathTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_MENU_LINGER)
+    }
+}
+
+impl MenuPathTracker {
+    pub fn new(linger: Duration) -> Self {
+        Self {
+            linger,
+            open: 0,
+            path: Vec::new(),
+            closed_at: None,
+        }
+    }
+
+    pub fn push(&mut self, event: MenuEvent, at: Instant) {
+        match event {
+            MenuEvent::Opened(path) => {
+                if self.open == 0 {
+                    self.path.clear();
+                    self.closed_at = None;
+                }
+                self.open += 1;
+                if !path.is_empty() {
+                    self.path = path;
+                }
+            }
+            MenuEvent::ItemSelected(path) => {
+                if !path.is_empty() {
+                    self.path = path;
+                }
+            }
+            MenuEvent::Closed => {
+                self.open = self.open.saturating_sub(1);
+                if self.open == 0 {
+                    self.closed_at = Some(at);
+                }
+            }
+        }
+    }
+
+    /// The open menu path, or the last one if its menu closed within the
+    /// linger period
+    pub fn current_at(&self, now: Instant) -> Option<String> {
+        if self.path.is_empty() {
+            return None;
+        }
+        let shown = self.open > 0
+            || self
+                .closed_at
+                .is_some_and(|closed| now.saturating_duration_since(closed) <= self.linger);
+        shown.then(|| self.path.join(MENU_PATH_SEPARATOR))
+    }
+}
+
+/// The current menu path, shared between the observer and the switcher
+#[derive(Clone, Default)]
+pub struct MenuContext {
+    tracker: Arc<Mutex<MenuPathTracker>>,
+}
+
+impl MenuContext {
+    pub fn push(&self, event: MenuEvent, at: Instant) {
+        self.tracker.lock().unwrap().push(event, at);
+    }
+
+    pub fn current(&self) -> Option<String> {
+        self.tracker.lock().unwrap().current_at(Instant::now())
+    }
+
+    /// Set `menu_context` on the event's enhanced summary
+    pub fn annotate(&self, event: &mut AppSwitchEvent) {
+        let current = self.current();
+        if let Some(enh) = &mut event.enhanced {
+            enh.menu_context = current;
+        }
+    }
+}
+
+fn ancestor(element: &AxElement) -> MenuAncestor {
+    MenuAncestor {
+        role: element.string("AXRole").unwrap_or_default(),
+        // Menu extras often have only a description
+        title: element
+            .string("AXTitle")
+            .filter(|title| !title.trim().is_empty())
+            .or_else(|| element.string("AXDescription")),
+    }
+}
+
+/// The notified element and its parents, up to the menu bar
+fn ancestors(element: &AxElement) -> Vec<MenuAncestor> {
+    let mut chain = vec![ancestor(element)];
+    let mut current = element.element("AXParent");
+    while let Some(parent) = current {
+        if chain.len() >= MAX_MENU_DEPTH {
+            break;
+        }
+        chain.push(ancestor(&parent));
+        current = parent.element("AXParent");
+    }
+    chain
+}
+
+/// Notifications received by the callback with the pid of their app,
+/// drained after each run loop turn
+type NotificationQueue = RefCell<Vec<(i32, MenuEvent)>>;
+
+/// An AXObserver on one app's menu notifications, attached to the current
+/// thread's run loop
+struct AppObserver {
+    observer: AXObserverRef,
+    _app: AxElement,
+}
+
+impl AppObserver {
+    /// Observe `pid`; `queue` must outlive the observer
+    fn attach(pid: i32, queue: &NotificationQueue) -> Option<Self> {
+        let app = AxElement::application(pid)?;
+        let mut observer: AXObserverRef = ptr::null_mut();
+        // SAFETY: `observer` receives a +1 reference on success
+        let status = unsafe { AXObserverCreate(pid, on_menu_notification, &mut observer) };
+        if status != kAXErrorSuccess || observer.is_null() {
+            return None;
+        }
+        let refcon = queue as *const NotificationQueue as *mut c_void;
+        for name in [
+            kAXMenuOpenedNotification,
+            kAXMenuItemSelectedNotification,
+            kAXMenuClosedNotification,
+        ] {
+            let name = CFString::new(name);
+            // SAFETY: the observer and app are live; the queue outlives the observer
+            unsafe {
+                AXObserverAddNotification(
+                    observer,
+                    app.as_raw(),
+                    name.as_concrete_TypeRef(),
+                    refcon,
+                );
+            }
+        }
+        // SAFETY: the source belongs to the live observer
+        unsafe {
+            CFRunLoopAddSource(
+                CFRunLoopGetCurrent(),
+                AXObserverGetRunLoopSource(observer),
+                kCFRunLoopDefaultMode,
+            );
+        }
+        Some(Self {
+            observer,
+            _app: app,
+        })
+    }
+}
+
+impl Drop for AppObserver {
+    fn drop(&mut self) {
+        // SAFETY: the observer is owned by this value and was added to this
+        // thread's run loop in `attach`
+        unsafe {
+            CFRunLoopRemoveSource(
+                CFRunLoopGetCurrent(),
+                AXObserverGetRunLoopSource(self.observer),
+                kCFRunLoopDefaultMode,
+            );
+            CFRelease(self.observer as CFTypeRef);
+        }
+    }
+}
+
+unsafe extern "C" fn on_menu_notification(
+    _observer: AXObserverRef,
+    element: AXUIElementRef,
+    notification: CFStringRef,
+    refcon: *mut c_void,
+) {
+    // SAFETY: the notification name is a live string for the callback's duration
+    let name = unsafe { CFString::wrap_under_get_rule(notification) }.to_string();
+    // SAFETY: the element is live for the callback's duration; retained for the wrapper
+    let Some(element) = (unsafe { AxElement::from_get_rule(element) }) else {
+        return;
+    };
+    let event = if name == kAXMenuOpenedNotification {
+        MenuEvent::Opened(assemble_menu_path(&ancestors(&element)))
+    } else if name == kAXMenuItemSelectedNotification {
+        MenuEvent::ItemSelected(assemble_menu_path(&ancestors(&element)))
+    } else if name == kAXMenuClosedNotification {
+        MenuEvent::Closed
+    } else {
+        return;
+    };
+    let mut pid = 0;
+    // SAFETY: the element is live; `pid` is a valid out pointer
+    unsafe {
+        AXUIElementGetPid(element.as_raw(), &mut pid);
+    }
+    // SAFETY: refcon is the queue passed to `AppObserver::attach`, which
+    // outlives the observer, and callbacks run on the observer's thread
+    let queue = unsafe { &*(refcon as *const NotificationQueue) };
+    queue.borrow_mut().push((pid, event));
+}
+
+/// Follows the menus of the frontmost app and of menu-bar-only apps
+pub struct MenuContextObserver {
+    context: MenuContext,
+    running: Arc<AtomicBool>,
+    follow: ForegroundFollow,
+}
+
+impl MenuContextObserver {
+    /// Paths are pushed to `context`, e.g. `AppSwitcher::menu_context`
+    pub fn new(context: MenuContext) -> Self {
+        Self {
+            context,
+            running: Arc::new(AtomicBool::new(false)),
+            follow: ForegroundFollow::default(),
+        }
+    }
+
+    /// A switcher listener that moves this observer to each foreground app
+    /// and refreshes the menu-bar-only apps on launches and terminations
+    pub fn follower(&self) -> ForegroundFollower {
+        self.follow.follower("menu_context")
+    }
+
+    /// Start observing on a background thread; requires Accessibility permission
+    pub fn start(&self) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let context = self.context.clone();
+        let running = Arc::clone(&self.running);
+        let follow = self.follow.clone();
+
+        std::thread::spawn(move || {
+            let queue: NotificationQueue = RefCell::new(Vec::new());
+            let mut front: Option<(AppInfo, AppObserver)> = None;
+            let mut menu_bar: HashMap<i32, (RunningAppEntry, AppObserver)> = HashMap::new();
+            let mut target =
+                frontmost_app().map(|app| AppInfo::new(app.name, app.bundle_id, app.pid));
+            let mut refresh_menu_bar = true;
+            follow.enter();
+
+            while running.load(Ordering::SeqCst) {
+                if let Some(next) = follow.take_foreground() {
+                    target = Some(next);
+                }
+                let front_pid = target.as_ref().map(|app| app.pid);
+                if front.as_ref().map(|(app, _)| app.pid) != front_pid {
+                    drop(front.take());
+                    front = target.clone().and_then(|app| {
+                        let observer = AppObserver::attach(app.pid, &queue)?;
+                        Some((app, observer))
+                    });
+                }
+                if std::mem::take(&mut refresh_menu_bar) | follow.take_apps_changed() {
+                    let apps = menu_bar_apps();
+                    menu_bar.retain(|pid, _| apps.iter().any(|app| app.pid == *pid));
+                    for app in apps {
+                        if menu_bar.contains_key(&app.pid) {
+                            continue;
+                        }
+                        if let Some(observer) = AppObserver::attach(app.pid, &queue) {
+                            menu_bar.insert(app.pid, (app, observer));
+                        }
+                    }
+                }
+
+                // Sleep until a menu notification, an app switch or `stop`
+                follow.wait(IDLE_WAIT);
+
+                let notifications: Vec<(i32, MenuEvent)> = queue.borrow_mut().drain(..).collect();
+                for (pid, event) in notifications {
+                    let owner = menu_bar
+                        .get(&pid)
+                        .filter(|_| Some(pid) != front_pid)
+                        .map(|(app, _)| app.name.clone());
+                    let event = match (owner, event) {
+                        (Some(name), MenuEvent::Opened(path)) => {
+                            MenuEvent::Opened(prefixed(name, path))
+                        }
+                        (Some(name), MenuEvent::ItemSelected(path)) => {
+                            MenuEvent::ItemSelected(prefixed(name, path))
+                        }
+                        (_, event) => event,
+                    };
+                    context.push(event, Instant::now());
+                }
+            }
+            follow.leave();
+        });
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        self.follow.wake();
+    }
+}
+
+impl Drop for MenuContextObserver {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// A menu extra's path, led by the app that owns it unless it already is
+fn prefixed(app_name: String, mut path: Vec<String>) -> Vec<String> {
+    if path.is_empty() || path.first() == Some(&app_name) {
+        return path;
+    }
+    path.insert(0, app_name);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ancestor(role: &str, title: &str) -> MenuAncestor {
+        MenuAncestor {
+            role: role.to_string(),
+            title: Some(title.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_menu_path_from_item_selected_notifications() {
+        // File › Export › PDF, as walked up from the highlighted item
+        let pdf = [
+            ancestor("AXMenuItem", "PDF"),
+            ancestor("AXMenu", ""),
+            ancestor("AXMenuItem", "Export"),
+            ancestor("AXMenu", ""),
+            ancestor("AXMenuBarItem", "File"),
+            ancestor("AXMenuBar", ""),
+            ancestor("AXApplication", "Preview"),
+        ];
+        assert_eq!(assemble_menu_path(&pdf), vec!["File", "Export", "PDF"]);
+        // The menu that opened leads to the item that opened it
+        assert_eq!(assemble_menu_path(&pdf[3..]), vec!["File"]);
+
+        let t0 = Instant::now();
+        let at = |ms: u64| t0 + Duration::from_millis(ms);
+        let mut tracker = MenuPathTracker::new(Duration::from_secs(2));
+        assert_eq!(tracker.current_at(t0), None);
+
+        tracker.push(MenuEvent::Opened(assemble_menu_path(&pdf[3..])), at(0));
+        assert_eq!(tracker.current_at(at(10)).as_deref(), Some("File"));
+        tracker.push(
+            MenuEvent::ItemSelected(assemble_menu_path(&pdf[2..])),
+            at(100),
+        );
+        tracker.push(MenuEvent::Opened(assemble_menu_path(&pdf[1..])), at(150));
+        tracker.push(MenuEvent::ItemSelected(assemble_menu_path(&pdf)), at(300));
+        assert_eq!(
+            tracker.current_at(at(310)).as_deref(),
+            Some("File › Export › PDF")
+        );
+
+        // Choosing the item closes the submenu and the menu; the path stays
+        // long enough to reach the switch it causes
+        tracker.push(MenuEvent::Closed, at(400));
+        tracker.push(MenuEvent::Closed, at(400));
+        assert_eq!(
+            tracker.current_at(at(1500)).as_deref(),
+            Some("File › Export › PDF")
+        );
+        assert_eq!(tracker.current_at(at(2500)), None);
+
+        // A new menu starts a new path
+        tracker.push(MenuEvent::Opened(vec!["Edit".to_string()]), at(3000));
+        assert_eq!(tracker.current_at(at(3010)).as_deref(), Some("Edit"));
+
+        // Menu extras are led by their app's name
+        assert_eq!(
+            prefixed("Dropbox".to_string(), vec!["Preferences…".to_string()]),
+            vec!["Dropbox", "Preferences…"]
+        );
+    }
+}
@@ -1,10 +1,64 @@
 pub mod accessibility;
+pub mod app_lifecycle;
+pub mod app_locale;
+pub mod appearance;
 pub mod app_switcher;
 pub mod app_switcher_enhanced;
 pub mod app_switcher_types;
 pub mod app_switcher_workspace;
+pub mod ax_attributes;
+pub mod ax_availability;
+pub mod ax_element;
+pub mod browser_strategy;
+pub mod buffered_sink;
+pub mod click_tap;
+pub mod clipboard;
+pub mod compaction;
+pub mod confidence;
+pub mod consent;
+pub mod context_diff;
+pub mod display_config;
+pub mod enrichment;
+pub mod event_coalescer;
+pub mod event_record;
+pub mod event_socket;
 pub mod event_tap;
+pub mod extraction_bench;
+pub mod extraction_rules;
+pub mod flat_json;
 pub mod ffi_types;
+pub mod foreground_follow;
+pub mod heartbeat;
+pub mod input_source;
+pub mod listener_worker;
+pub mod mail;
+pub mod menu_context;
+pub mod note_apps;
+pub mod navigation;
+pub mod notification_observer;
+pub mod path_redaction;
+pub mod power_state;
+pub mod preview;
+pub mod previous_context;
+pub mod recent_documents;
+pub mod reference_manager;
+pub mod process_cache;
+pub mod quiet_hours;
+pub mod running_apps;
+pub mod self_exclusion;
+pub mod session;
+pub mod session_deadline;
+pub mod significant_change;
 pub mod spaces;
+pub mod spreadsheet;
+pub mod startup_warmup;
+pub mod supported_apps;
+pub mod terminal;
 pub mod time_tracker;
+pub mod timestamp_format;
+pub mod title_exclusion;
+pub mod url_canonicalizer;
+pub mod window_geometry;
+pub mod window_screenshot;
 pub mod window_state_detector;
+pub mod xcode;
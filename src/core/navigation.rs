@@ -0,0 +1,189 @@
+// src/core/navigation.rs
+//! Deciding which URL changes are navigation
+//!
+//! Single-page apps rewrite the address bar as their state changes: a
+//! `#section` fragment, a `?tab=2` query or a tracking parameter appended
+//! after load. Each rewrite is read as a new page in the same app and would
+//! reach listeners as another foreground event. Under the strict policy a
+//! URL change only counts as navigation when the canonical form (see
+//! `UrlCanonicalizer`) changed; other changes update the remembered URL
+//! silently. The any-change policy emits on every change, as before. An
+//! event whose window title changed is emitted under either policy.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::url_canonicalizer::UrlCanonicalizer;
+
+/// Which URL changes within the app in focus are emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NavigationPolicy {
+    /// Only changes to the canonicalized URL
+    Strict,
+    /// Every change to the raw URL
+    AnyChange,
+}
+
+impl fmt::Display for NavigationPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NavigationPolicy::Strict => write!(f, "strict"),
+            NavigationPolicy::AnyChange => write!(f, "any"),
+        }
+    }
+}
+
+impl FromStr for NavigationPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "strict" => Ok(NavigationPolicy::Strict),
+            "any" => Ok(NavigationPolicy::AnyChange),
+            other => Err(format!(
+                "Invalid navigation policy '{}' (expected strict or any)",
+                other
+            )),
+        }
+    }
+}
+
+/// Whether going from `old` to `new` is navigation under `policy`
+pub fn is_navigation(
+    policy: NavigationPolicy,
+    canonicalizer: &UrlCanonicalizer,
+    old: &str,
+    new: &str,
+) -> bool {
+    match policy {
+        NavigationPolicy::AnyChange => old != new,
+        NavigationPolicy::Strict => {
+            canonicalizer.canonicalize(old) != canonicalizer.canonicalize(new)
+        }
+    }
+}
+
+/// Drops foreground events that only change the URL of the app in focus
+/// without navigating
+pub struct NavigationFilter {
+    policy: NavigationPolicy,
+    canonicalizer: UrlCanonicalizer,
+    /// Match key, URL and window title of the latest foreground event
+    current: Option<(String, Option<String>, Option<String>)>,
+}
+
+impl Default for NavigationFilter {
+    fn default() -> Self {
+        Self::new(NavigationPolicy::AnyChange, UrlCanonicalizer::default())
+    }
+}
+
+impl NavigationFilter {
+    pub fn new(policy: NavigationPolicy, canonicalizer: UrlCanonicalizer) -> Self {
+        Self {
+            policy,
+            canonicalizer,
+            current: None,
+        }
+    }
+
+    pub fn policy(&self) -> NavigationPolicy {
+        self.policy
+    }
+
+    /// Whether `event` is emitted; a URL change that is not navigation and
+    /// leaves the title as it was is remembered and rejected
+    pub fn admit(&mut self, event: &AppSwitchEvent) -> bool {
+        if !event.event_type.is_foreground() {
+            return true;
+        }
+        let key = event.app_info.match_key().to_string();
+        let url = event_url(event);
+        let title = event_title(event);
+        let admit = match (&self.current, &url) {
+            (Some((current_key, Some(old), old_title)), Some(new))
+                if *current_key == key && old != new && *old_title == title =>
+            {
+                is_navigation(self.policy, &self.canonicalizer, old, new)
+            }
+            _ => true,
+        };
+        self.current = Some((key, url, title));
+        admit
+    }
+}
+
+/// The page URL an event carries, from either source
+fn event_url(event: &AppSwitchEvent) -> Option<String> {
+    event
+        .workspace
+        .as_ref()
+        .and_then(|w| w.primary_url.clone())
+        .or_else(|| event.enhanced.as_ref().and_then(|e| e.url.clone()))
+}
+
+/// The window title an event carries, from either source
+fn event_title(event: &AppSwitchEvent) -> Option<String> {
+    event
+        .workspace
+        .as_ref()
+        .and_then(|w| w.focused_title.clone())
+        .or_else(|| {
+            event
+                .enhanced
+                .as_ref()
+                .and_then(|e| e.front_window_title.clone())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType, EnhancedSummary};
+
+    fn page(bundle_id: &str, url: &str) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new(bundle_id.to_string(), bundle_id.to_string(), 42),
+        );
+        event.enhanced = Some(EnhancedSummary {
+            url: Some(url.to_string()),
+            ..EnhancedSummary::default()
+        });
+        event
+    }
+
+    #[test]
+    fn test_fragment_change_is_not_navigation_under_strict() {
+        let mut strict =
+            NavigationFilter::new(NavigationPolicy::Strict, UrlCanonicalizer::default());
+        assert!(strict.admit(&page("com.apple.Safari", "https://docs.rs/serde")));
+        assert!(!strict.admit(&page("com.apple.Safari", "https://docs.rs/serde#derive")));
+        assert!(!strict.admit(&page("com.apple.Safari", "https://docs.rs/serde#traits")));
+        assert!(strict.admit(&page("com.apple.Safari", "https://docs.rs/tokio")));
+        // Another app, or coming back, is a switch whatever the URL
+        assert!(strict.admit(&page("com.google.Chrome", "https://docs.rs/tokio#x")));
+        assert!(strict.admit(&page("com.apple.Safari", "https://docs.rs/tokio#y")));
+
+        // A fragment change that retitles the window is emitted all the same
+        let mut retitled = page("com.apple.Safari", "https://docs.rs/tokio#z");
+        retitled.enhanced.as_mut().unwrap().front_window_title = Some("Runtime".to_string());
+        assert!(strict.admit(&retitled));
+
+        // Routes of hash-routed apps are navigation
+        assert!(strict.admit(&page("com.apple.Safari", "https://mail.example/#/inbox")));
+        assert!(strict.admit(&page("com.apple.Safari", "https://mail.example/#/sent")));
+
+        let mut any =
+            NavigationFilter::new(NavigationPolicy::AnyChange, UrlCanonicalizer::default());
+        assert!(any.admit(&page("com.apple.Safari", "https://docs.rs/serde")));
+        assert!(any.admit(&page("com.apple.Safari", "https://docs.rs/serde#derive")));
+
+        assert_eq!("any".parse(), Ok(NavigationPolicy::AnyChange));
+        assert!("loose".parse::<NavigationPolicy>().is_err());
+    }
+}
@@ -0,0 +1,293 @@
+// src/core/note_apps.rs
+//! Current note and vault in Obsidian and Logseq
+//!
+//! Obsidian titles its window `Note - Vault - Obsidian v1.5.3` (older builds
+//! omit the version); Logseq uses the page name, optionally followed by
+//! ` - Logseq`. Both also show non-note views (graph, canvas, journals) whose
+//! titles must not be reported as note names.
+//!
+//! The vault path is only known when the window exposes its document: the
+//! vault root is the nearest ancestor holding the app's marker directory
+//! (`.obsidian` for Obsidian vaults, `logseq` for Logseq graphs).
+
+use std::path::{Path, PathBuf};
+
+use crate::core::accessibility::AccessibilityContext;
+
+/// A supported note-taking app
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteApp {
+    Obsidian,
+    Logseq,
+}
+
+/// What the window is showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteView {
+    Note,
+    Canvas,
+    Graph,
+    /// Journals, all pages and other app-level views
+    Overview,
+}
+
+impl NoteView {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Note => "note",
+            Self::Canvas => "canvas",
+            Self::Graph => "graph",
+            Self::Overview => "overview",
+        }
+    }
+}
+
+/// Parsed window title
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteTitle {
+    /// Note, canvas or local-graph subject; `None` for app-level views
+    pub note: Option<String>,
+    /// Vault name, when the title carries it (Obsidian only)
+    pub vault: Option<String>,
+    pub view: NoteView,
+}
+
+/// Logseq views that are not pages
+const LOGSEQ_OVERVIEWS: &[&str] = &["Journals", "All pages", "Whiteboards", "Flashcards"];
+
+impl NoteApp {
+    pub fn for_bundle_id(bundle_id: &str) -> Option<Self> {
+        match bundle_id {
+            "md.obsidian" => Some(Self::Obsidian),
+            "com.logseq.Logseq" => Some(Self::Logseq),
+            _ => None,
+        }
+    }
+
+    /// Directory that marks the root of a vault or graph
+    pub fn vault_marker(&self) -> &'static str {
+        match self {
+            Self::Obsidian => ".obsidian",
+            Self::Logseq => "logseq",
+        }
+    }
+
+    pub fn parse_title(&self, title: &str) -> Option<NoteTitle> {
+        match self {
+            Self::Obsidian => parse_obsidian_title(title),
+            Self::Logseq => parse_logseq_title(title),
+        }
+    }
+}
+
+/// Parse `Note - Vault - Obsidian[ vX.Y.Z]`
+pub fn parse_obsidian_title(title: &str) -> Option<NoteTitle> {
+    let (rest, app) = title.trim().rsplit_once(" - ")?;
+    if app != "Obsidian" && !app.starts_with("Obsidian v") {
+        return None;
+    }
+    // Note names may contain " - " themselves; the vault is the last segment
+    let (note, vault) = match rest.rsplit_once(" - ") {
+        Some((note, vault)) => (Some(note.trim()), vault.trim()),
+        None => (None, rest.trim()),
+    };
+    let vault = Some(vault.to_string()).filter(|v| !v.is_empty());
+
+    let Some(note) = note.filter(|n| !n.is_empty()) else {
+        return Some(NoteTitle {
+            note: None,
+            vault,
+            view: NoteView::Overview,
+        });
+    };
+    let (note, view) = if note == "Graph view" {
+        (None, NoteView::Graph)
+    } else if let Some(subject) = note.strip_prefix("Graph of ") {
+        (Some(subject), NoteView::Graph)
+    } else if let Some(name) = note.strip_suffix(".canvas") {
+        (Some(name), NoteView::Canvas)
+    } else {
+        (Some(note), NoteView::Note)
+    };
+    Some(NoteTitle {
+        note: note.map(str::to_string),
+        vault,
+        view,
+    })
+}
+
+/// Parse `Page[ - Logseq]`
+pub fn parse_logseq_title(title: &str) -> Option<NoteTitle> {
+    let page = title.trim();
+    let page = page.strip_suffix(" - Logseq").unwrap_or(page).trim();
+    if page.is_empty() || page == "Logseq" {
+        return None;
+    }
+    let (note, view) = if page == "Graph view" || page == "Graph" {
+        (None, NoteView::Graph)
+    } else if LOGSEQ_OVERVIEWS.contains(&page) {
+        (None, NoteView::Overview)
+    } else {
+        (Some(page.to_string()), NoteView::Note)
+    };
+    Some(NoteTitle {
+        note,
+        vault: None,
+        view,
+    })
+}
+
+/// Nearest ancestor of `document` for which `is_vault_root` holds
+pub fn find_vault_root(document: &Path, is_vault_root: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+    document
+        .ancestors()
+        .skip(1)
+        .find(|dir| is_vault_root(dir))
+        .map(Path::to_path_buf)
+}
+
+/// Fills note and vault fields for Obsidian and Logseq windows
+pub struct NoteAppExtractor;
+
+impl NoteAppExtractor {
+    pub fn applies_to(bundle_id: &str) -> bool {
+        NoteApp::for_bundle_id(bundle_id).is_some()
+    }
+
+    /// Populate `active_file_path` (note) and `project_name` (vault)
+    pub fn apply(&self, context: &mut AccessibilityContext) {
+        let Some(app) = NoteApp::for_bundle_id(&context.app_info.bundle_id) else {
+            return;
+        };
+        let parsed = context
+            .window_title
+            .as_deref()
+            .and_then(|title| app.parse_title(title));
+        let document = context
+            .document_path
+            .as_deref()
+            .and_then(document_file_path);
+
+        let vault_root = document
+            .as_deref()
+            .and_then(|doc| find_vault_root(doc, |dir| dir.join(app.vault_marker()).is_dir()));
+
+        if let Some(parsed) = &parsed {
+            context.active_file_path = parsed.note.clone();
+            context.project_name = parsed.vault.clone();
+            context
+                .raw_attributes
+                .insert("note_view".to_string(), parsed.view.as_str().to_string());
+        }
+        if let Some(document) = &document {
+            if document.extension().is_some_and(|ext| ext == "canvas") {
+                context.raw_attributes.insert(
+                    "note_view".to_string(),
+                    NoteView::Canvas.as_str().to_string(),
+                );
+            }
+            context.active_file_path = Some(document.to_string_lossy().into_owned());
+        }
+        if let Some(root) = vault_root {
+            if context.project_name.is_none() {
+                context.project_name = root
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned());
+            }
+            context.raw_attributes.insert(
+                "vault_path".to_string(),
+                root.to_string_lossy().into_owned(),
+            );
+        }
+    }
+}
+
+/// `AXDocument` is a file URL or, in some apps, a plain path
+fn document_file_path(document: &str) -> Option<PathBuf> {
+    if document.starts_with("file://") {
+        url::Url::parse(document).ok()?.to_file_path().ok()
+    } else if document.starts_with('/') {
+        Some(PathBuf::from(document))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(note: Option<&str>, vault: Option<&str>, view: NoteView) -> Option<NoteTitle> {
+        Some(NoteTitle {
+            note: note.map(str::to_string),
+            vault: vault.map(str::to_string),
+            view,
+        })
+    }
+
+    #[test]
+    fn test_obsidian_titles() {
+        assert_eq!(
+            parse_obsidian_title("Reading list - Research - Obsidian v1.5.3"),
+            note(Some("Reading list"), Some("Research"), NoteView::Note)
+        );
+        assert_eq!(
+            parse_obsidian_title("Q3 - plan - draft - Work - Obsidian"),
+            note(Some("Q3 - plan - draft"), Some("Work"), NoteView::Note)
+        );
+        assert_eq!(
+            parse_obsidian_title("Graph view - Research - Obsidian v1.5.3"),
+            note(None, Some("Research"), NoteView::Graph)
+        );
+        assert_eq!(
+            parse_obsidian_title("Graph of Reading list - Research - Obsidian v1.5.3"),
+            note(Some("Reading list"), Some("Research"), NoteView::Graph)
+        );
+        assert_eq!(
+            parse_obsidian_title("Architecture.canvas - Work - Obsidian"),
+            note(Some("Architecture"), Some("Work"), NoteView::Canvas)
+        );
+        assert_eq!(
+            parse_obsidian_title("Research - Obsidian v1.5.3"),
+            note(None, Some("Research"), NoteView::Overview)
+        );
+        assert_eq!(
+            parse_obsidian_title("main.rs - crate - Visual Studio Code"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_logseq_titles() {
+        assert_eq!(
+            parse_logseq_title("Rust async - Logseq"),
+            note(Some("Rust async"), None, NoteView::Note)
+        );
+        assert_eq!(
+            parse_logseq_title("Oct 16th, 2026"),
+            note(Some("Oct 16th, 2026"), None, NoteView::Note)
+        );
+        assert_eq!(
+            parse_logseq_title("Journals - Logseq"),
+            note(None, None, NoteView::Overview)
+        );
+        assert_eq!(
+            parse_logseq_title("Graph view"),
+            note(None, None, NoteView::Graph)
+        );
+        assert_eq!(parse_logseq_title("Logseq"), None);
+    }
+
+    #[test]
+    fn test_vault_root_is_nearest_marked_ancestor() {
+        let doc = Path::new("/Users/me/Vaults/Research/papers/attention.md");
+        let root = find_vault_root(doc, |dir| dir == Path::new("/Users/me/Vaults/Research"));
+        assert_eq!(root, Some(PathBuf::from("/Users/me/Vaults/Research")));
+        assert_eq!(find_vault_root(doc, |_| false), None);
+
+        assert_eq!(
+            document_file_path("file:///Users/me/My%20Vault/Note.md"),
+            Some(PathBuf::from("/Users/me/My Vault/Note.md"))
+        );
+    }
+}
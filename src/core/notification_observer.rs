@@ -0,0 +1,301 @@
+// src/core/notification_observer.rs
+//! Notification banners as an event source
+//!
+//! macOS does not broadcast "a banner was shown", and the notification
+//! database needs Full Disk Access. Banners are, however, regular AX elements
+//! inside the NotificationCenter process, so the observer polls that process'
+//! windows for banner/alert subroles and reports each new one.
+//!
+//! Only the source app and the time are captured. A banner's AXDescription
+//! reads "App, Title, Body"; everything after the app name is discarded.
+
+use std::collections::HashSet;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use accessibility_sys::{
+    kAXErrorSuccess, AXUIElementCopyAttributeValue, AXUIElementCreateApplication, AXUIElementRef,
+};
+use chrono::{DateTime, Utc};
+use core_foundation::array::{
+    CFArrayGetCount, CFArrayGetTypeID, CFArrayGetValueAtIndex, CFArrayRef,
+};
+use core_foundation::base::{CFGetTypeID, CFRelease, CFRetain, CFTypeRef, TCFType};
+use core_foundation::string::{CFString, CFStringGetTypeID, CFStringRef};
+use serde::{Deserialize, Serialize};
+
+use crate::core::running_apps::{list_running_apps, RunningAppEntry};
+
+const NOTIFICATION_CENTER_NAME: &str = "NotificationCenter";
+const BANNER_SUBROLES: &[&str] = &["AXNotificationCenterBanner", "AXNotificationCenterAlert"];
+const MAX_SEARCH_DEPTH: usize = 6;
+
+/// A notification banner appeared
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationEvent {
+    pub timestamp: DateTime<Utc>,
+    pub app_name: String,
+    pub bundle_id: Option<String>,
+}
+
+/// AX attributes read from a candidate banner element
+#[derive(Debug, Clone, Default)]
+pub struct BannerPayload {
+    pub subrole: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Receives notification events
+pub trait NotificationListener: Send {
+    fn on_notification(&mut self, event: &NotificationEvent);
+}
+
+/// Turn a banner payload into an event, keeping only the source app
+pub fn map_banner(
+    payload: &BannerPayload,
+    timestamp: DateTime<Utc>,
+    resolve_bundle_id: impl Fn(&str) -> Option<String>,
+) -> Option<NotificationEvent> {
+    let subrole = payload.subrole.as_deref()?;
+    if !BANNER_SUBROLES.contains(&subrole) {
+        return None;
+    }
+    let app_name = payload
+        .description
+        .as_deref()?
+        .split(", ")
+        .next()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())?
+        .to_string();
+
+    Some(NotificationEvent {
+        timestamp,
+        bundle_id: resolve_bundle_id(&app_name),
+        app_name,
+    })
+}
+
+/// Polls NotificationCenter for new banners
+pub struct NotificationObserver {
+    listeners: Arc<Mutex<Vec<Box<dyn NotificationListener>>>>,
+    running: Arc<AtomicBool>,
+}
+
+impl NotificationObserver {
+    pub fn new() -> Self {
+        Self {
+            listeners: Arc::new(Mutex::new(Vec::new())),
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn add_listener<T: NotificationListener + 'static>(&self, listener: T) {
+        self.listeners.lock().unwrap().push(Box::new(listener));
+    }
+
+    /// Start polling on a background thread; requires Accessibility permission
+    pub fn start(&self, poll_interval: Duration) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let listeners = Arc::clone(&self.listeners);
+        let running = Arc::clone(&self.running);
+
+        std::thread::spawn(move || {
+            // Banners stay on screen for several polls; report each one once
+            let mut visible: HashSet<String> = HashSet::new();
+            while running.load(Ordering::SeqCst) {
+                // One snapshot per poll serves both the pid and bundle id lookups
+                let apps = list_running_apps(true);
+                let banners = notification_center_pid(&apps)
+                    .map(read_banners)
+                    .unwrap_or_default();
+
+                let mut now_visible = HashSet::new();
+                for payload in banners {
+                    let key = payload.description.clone().unwrap_or_default();
+                    if !visible.contains(&key) {
+                        let event =
+                            map_banner(&payload, Utc::now(), |name| resolve_bundle_id(&apps, name));
+                        if let Some(event) = event {
+                            for listener in listeners.lock().unwrap().iter_mut() {
+                                listener.on_notification(&event);
+                            }
+                        }
+                    }
+                    now_visible.insert(key);
+                }
+                visible = now_visible;
+
+                std::thread::sleep(poll_interval);
+            }
+        });
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Default for NotificationObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for NotificationObserver {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn resolve_bundle_id(apps: &[RunningAppEntry], app_name: &str) -> Option<String> {
+    apps.iter()
+        .find(|app| app.name == app_name)
+        .map(|app| app.bundle_id.clone())
+        .filter(|id| !id.is_empty())
+}
+
+fn notification_center_pid(apps: &[RunningAppEntry]) -> Option<i32> {
+    apps.iter()
+        .find(|app| app.name == NOTIFICATION_CENTER_NAME)
+        .map(|app| app.pid)
+}
+
+/// Collect banner payloads from all NotificationCenter windows
+fn read_banners(pid: i32) -> Vec<BannerPayload> {
+    let mut banners = Vec::new();
+    unsafe {
+        let app = AXUIElementCreateApplication(pid);
+        if app.is_null() {
+            return banners;
+        }
+        for window in copy_children(app, "AXWindows") {
+            collect_banners(window, 0, &mut banners);
+            CFRelease(window as CFTypeRef);
+        }
+        CFRelease(app as CFTypeRef);
+    }
+    banners
+}
+
+unsafe fn collect_banners(element: AXUIElementRef, depth: usize, out: &mut Vec<BannerPayload>) {
+    unsafe {
+        let subrole = copy_string(element, "AXSubrole");
+        if subrole
+            .as_deref()
+            .map(|s| BANNER_SUBROLES.contains(&s))
+            .unwrap_or(false)
+        {
+            out.push(BannerPayload {
+                subrole,
+                description: copy_string(element, "AXDescription"),
+            });
+            return;
+        }
+        if depth >= MAX_SEARCH_DEPTH {
+            return;
+        }
+        for child in copy_children(element, "AXChildren") {
+            collect_banners(child, depth + 1, out);
+            CFRelease(child as CFTypeRef);
+        }
+    }
+}
+
+/// Copy a string attribute
+unsafe fn copy_string(element: AXUIElementRef, attribute: &str) -> Option<String> {
+    unsafe {
+        let attr = CFString::new(attribute);
+        let mut value: CFTypeRef = ptr::null();
+        if AXUIElementCopyAttributeValue(element, attr.as_concrete_TypeRef(), &mut value)
+            != kAXErrorSuccess
+            || value.is_null()
+        {
+            return None;
+        }
+        if CFGetTypeID(value) == CFStringGetTypeID() {
+            Some(CFString::wrap_under_create_rule(value as CFStringRef).to_string())
+        } else {
+            CFRelease(value);
+            None
+        }
+    }
+}
+
+/// Copy an array-of-elements attribute; each returned element is retained
+unsafe fn copy_children(element: AXUIElementRef, attribute: &str) -> Vec<AXUIElementRef> {
+    unsafe {
+        let attr = CFString::new(attribute);
+        let mut value: CFTypeRef = ptr::null();
+        if AXUIElementCopyAttributeValue(element, attr.as_concrete_TypeRef(), &mut value)
+            != kAXErrorSuccess
+            || value.is_null()
+        {
+            return Vec::new();
+        }
+        let mut children = Vec::new();
+        if CFGetTypeID(value) == CFArrayGetTypeID() {
+            let array = value as CFArrayRef;
+            for i in 0..CFArrayGetCount(array) {
+                let child = CFArrayGetValueAtIndex(array, i);
+                if !child.is_null() {
+                    CFRetain(child);
+                    children.push(child as AXUIElementRef);
+                }
+            }
+        }
+        CFRelease(value);
+        children
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver(name: &str) -> Option<String> {
+        (name == "Slack").then(|| "com.tinyspeck.slackmacgap".to_string())
+    }
+
+    #[test]
+    fn test_banner_maps_to_source_app_only() {
+        let now = Utc::now();
+        let payload = BannerPayload {
+            subrole: Some("AXNotificationCenterBanner".to_string()),
+            description: Some("Slack, Jane Doe, lunch at 12?".to_string()),
+        };
+
+        let event = map_banner(&payload, now, resolver).unwrap();
+        assert_eq!(
+            event,
+            NotificationEvent {
+                timestamp: now,
+                app_name: "Slack".to_string(),
+                bundle_id: Some("com.tinyspeck.slackmacgap".to_string()),
+            }
+        );
+        assert!(!serde_json::to_string(&event).unwrap().contains("lunch"));
+    }
+
+    #[test]
+    fn test_non_banner_elements_ignored() {
+        let now = Utc::now();
+        let button = BannerPayload {
+            subrole: Some("AXCloseButton".to_string()),
+            description: Some("Close".to_string()),
+        };
+        assert_eq!(map_banner(&button, now, resolver), None);
+
+        let unknown_app = BannerPayload {
+            subrole: Some("AXNotificationCenterAlert".to_string()),
+            description: Some("Calendar, Standup".to_string()),
+        };
+        let event = map_banner(&unknown_app, now, resolver).unwrap();
+        assert_eq!(event.app_name, "Calendar");
+        assert_eq!(event.bundle_id, None);
+    }
+}
@@ -0,0 +1,380 @@
+// src/core/path_redaction.rs
+//! Redacting file paths
+//!
+//! File paths name clients, projects and people (`~/Clients/Acme/Layoffs
+//! 2025.xlsx`). The `[paths]` config section limits what is kept:
+//!
+//! - `hash_below`: roots such as `~/Projects` whose contents are replaced by
+//!   a hash, so `~/Projects/acme/src/main.rs` becomes
+//!   `~/Projects/3f2a9c1b04de.rs`. The extension is kept.
+//! - `keep_components`: any other path is cut to its last N components,
+//!   e.g. `…/src/main.rs` for 2.
+//!
+//! Hashes are salted with a random per-session key: the same file hashes the
+//! same throughout a session, so switches between files can still be told
+//! apart, but hashes cannot be compared across sessions or looked up. The
+//! redactor runs as a redaction stage on the paths in events, on the
+//! document, file and Finder selection paths of extracted accessibility
+//! contexts and on the file of a copy's source window.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::core::accessibility::AccessibilityContext;
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::clipboard::CopyContext;
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+
+/// Hex digits of the hash kept in a redacted path
+const HASH_LEN: usize = 12;
+
+/// Leads a path cut to its last components
+const TRUNCATED_PREFIX: &str = "…";
+
+const FINDER_BUNDLE_ID: &str = "com.apple.finder";
+
+/// Leads Finder's selected paths in `selected_text`
+pub const FINDER_SELECTION_PREFIX: &str = "Selected: ";
+
+/// Separates Finder's selected paths in `selected_text`
+pub const FINDER_SELECTION_SEPARATOR: &str = ", ";
+
+/// The `[paths]` config section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathRedactionConfig {
+    /// Keep only the last N components of paths outside `hash_below`
+    pub keep_components: Option<usize>,
+    /// Roots below which everything is hashed; `~` is the home directory
+    pub hash_below: Vec<String>,
+}
+
+impl PathRedactionConfig {
+    pub fn is_empty(&self) -> bool {
+        self.keep_components.is_none() && self.hash_below.is_empty()
+    }
+}
+
+/// A `hash_below` root, as configured and as an absolute path
+#[derive(Debug, Clone)]
+struct HashRoot {
+    shown: String,
+    absolute: String,
+}
+
+/// Truncates and hashes file paths per `PathRedactionConfig`
+#[derive(Debug, Clone)]
+pub struct PathRedactor {
+    keep_components: Option<usize>,
+    roots: Vec<HashRoot>,
+    salt: [u8; 16],
+}
+
+impl PathRedactor {
+    /// `home` expands `~` in the roots and in captured paths
+    pub fn new(config: &PathRedactionConfig, salt: [u8; 16], home: Option<&str>) -> Self {
+        let home = home.map(|h| h.trim_end_matches('/'));
+        let mut roots: Vec<HashRoot> = config
+            .hash_below
+            .iter()
+            .map(|root| root.trim().trim_end_matches('/'))
+            .filter(|root| !root.is_empty())
+            .map(|root| HashRoot {
+                shown: root.to_string(),
+                absolute: expand_home(root, home),
+            })
+            .collect();
+        // Nested roots: the deepest one wins
+        roots.sort_by_key(|root| std::cmp::Reverse(root.absolute.len()));
+        Self {
+            keep_components: config.keep_components,
+            roots,
+            salt,
+        }
+    }
+
+    /// A redactor with a fresh random salt, for one session
+    pub fn for_session(config: &PathRedactionConfig) -> Self {
+        let mut salt = [0u8; 16];
+        if getrandom::getrandom(&mut salt).is_err() {
+            let nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64;
+            salt[..8].copy_from_slice(&nanos.to_le_bytes());
+        }
+        let home = std::env::var("HOME").ok();
+        Self::new(config, salt, home.as_deref())
+    }
+
+    /// Redact a path or `file://` URL; other strings are returned unchanged
+    pub fn redact(&self, path: &str) -> String {
+        if let Some(rest) = path.strip_prefix("file://") {
+            let decoded = urlencoding::decode(rest)
+                .map(|p| p.into_owned())
+                .unwrap_or_else(|_| rest.to_string());
+            return format!("file://{}", self.redact_path(&decoded));
+        }
+        self.redact_path(path)
+    }
+
+    fn redact_path(&self, path: &str) -> String {
+        if !path.starts_with('/') && !path.starts_with('~') {
+            return path.to_string();
+        }
+        for root in &self.roots {
+            let below = path
+                .strip_prefix(&root.absolute)
+                .or_else(|| path.strip_prefix(&root.shown));
+            if let Some(below) = below.and_then(|b| b.strip_prefix('/')) {
+                if below.is_empty() {
+                    break;
+                }
+                return format!("{}/{}", root.shown, self.hash(below));
+            }
+        }
+        match self.keep_components {
+            Some(keep) => truncate(path, keep),
+            None => path.to_string(),
+        }
+    }
+
+    /// Salted hash of the path below a root, keeping the file's extension
+    fn hash(&self, below: &str) -> String {
+        let digest = Sha256::new()
+            .chain_update(self.salt)
+            .chain_update(below.trim_end_matches('/').as_bytes())
+            .finalize();
+        let hash: String = digest
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+            .chars()
+            .take(HASH_LEN)
+            .collect();
+        match Path::new(below).extension().and_then(|e| e.to_str()) {
+            Some(extension) => format!("{}.{}", hash, extension),
+            None => hash,
+        }
+    }
+
+    /// Redact the document, file and Finder paths of an extracted context
+    pub fn redact_context(&self, context: &mut AccessibilityContext) {
+        for path in [&mut context.document_path, &mut context.active_file_path]
+            .into_iter()
+            .flatten()
+        {
+            *path = self.redact(path);
+        }
+        if let Some(document) = context.raw_attributes.get_mut("AXDocument") {
+            *document = self.redact(document);
+        }
+        if context.app_info.bundle_id == FINDER_BUNDLE_ID {
+            if let Some(selected) = &mut context.selected_text {
+                if let Some(paths) = selected.strip_prefix(FINDER_SELECTION_PREFIX) {
+                    *selected = format!(
+                        "{}{}",
+                        FINDER_SELECTION_PREFIX,
+                        self.redact_selection(paths)
+                    );
+                }
+            }
+        }
+    }
+
+    /// Redact Finder's selected paths, joined by `FINDER_SELECTION_SEPARATOR`
+    ///
+    /// Every selected item is an absolute path, so a piece that does not
+    /// start one belongs to a file name containing the separator.
+    fn redact_selection(&self, paths: &str) -> String {
+        let mut items: Vec<String> = Vec::new();
+        for piece in paths.split(FINDER_SELECTION_SEPARATOR) {
+            match items.last_mut() {
+                Some(item) if !piece.starts_with('/') => {
+                    item.push_str(FINDER_SELECTION_SEPARATOR);
+                    item.push_str(piece);
+                }
+                _ => items.push(piece.to_string()),
+            }
+        }
+        items
+            .iter()
+            .map(|item| self.redact(item))
+            .collect::<Vec<_>>()
+            .join(FINDER_SELECTION_SEPARATOR)
+    }
+
+    /// Redact the file of a copy's source window
+    pub fn redact_copy_context(&self, context: &mut CopyContext) {
+        if let Some(file) = &mut context.file {
+            *file = self.redact(file);
+        }
+    }
+}
+
+fn expand_home(path: &str, home: Option<&str>) -> String {
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{}", home, rest)
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// The last `keep` components of `path`, led by `…` when any were dropped
+fn truncate(path: &str, keep: usize) -> String {
+    let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    if components.len() <= keep {
+        return path.to_string();
+    }
+    let kept = &components[components.len() - keep..];
+    if kept.is_empty() {
+        return TRUNCATED_PREFIX.to_string();
+    }
+    format!("{}/{}", TRUNCATED_PREFIX, kept.join("/"))
+}
+
+impl EnrichmentStage for PathRedactor {
+    fn name(&self) -> &str {
+        "path_redaction"
+    }
+
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Redact
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        if let Some(ws) = &mut event.workspace {
+            for path in &mut ws.active_file_paths {
+                *path = self.redact(path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::AppInfo;
+    use std::collections::HashMap;
+
+    fn configured(keep_components: Option<usize>, hash_below: &[&str]) -> PathRedactor {
+        let config = PathRedactionConfig {
+            keep_components,
+            hash_below: hash_below.iter().map(|r| r.to_string()).collect(),
+        };
+        PathRedactor::new(&config, [7; 16], Some("/Users/ada"))
+    }
+
+    #[test]
+    fn test_depth_truncation() {
+        let redactor = configured(Some(2), &[]);
+        assert_eq!(
+            redactor.redact("/Users/ada/Clients/Acme/notes/plan.md"),
+            "…/notes/plan.md"
+        );
+        assert_eq!(redactor.redact("/tmp/a.txt"), "/tmp/a.txt");
+        assert_eq!(
+            redactor.redact("file:///Users/ada/Clients/Acme%20Co/plan.md"),
+            "file://…/Acme Co/plan.md"
+        );
+        // Not a path
+        assert_eq!(redactor.redact("Untitled 3"), "Untitled 3");
+        assert_eq!(configured(Some(0), &[]).redact("/Users/ada/x.md"), "…");
+        assert_eq!(
+            configured(None, &[]).redact("/Users/ada/x.md"),
+            "/Users/ada/x.md"
+        );
+    }
+
+    #[test]
+    fn test_root_relative_hashing() {
+        let redactor = configured(Some(1), &["~/Projects", "~/Projects/public/"]);
+        let main = redactor.redact("/Users/ada/Projects/acme/src/main.rs");
+        assert!(main.starts_with("~/Projects/"), "{}", main);
+        assert!(main.ends_with(".rs"));
+        assert!(!main.contains("acme") && !main.contains("main"));
+        assert_eq!(main.len(), "~/Projects/".len() + HASH_LEN + ".rs".len());
+
+        // Stable within the session, distinct per file
+        assert_eq!(redactor.redact("~/Projects/acme/src/main.rs"), main);
+        assert_ne!(redactor.redact("/Users/ada/Projects/acme/src/lib.rs"), main);
+        // The deepest matching root is kept
+        assert!(redactor
+            .redact("/Users/ada/Projects/public/readme.md")
+            .starts_with("~/Projects/public/"));
+        // Outside the roots, truncation applies; the root itself is not hashed
+        assert_eq!(redactor.redact("/Users/ada/Desktop/todo.txt"), "…/todo.txt");
+        assert_eq!(redactor.redact("/Users/ada/Projects"), "…/Projects");
+
+        // Another session salts differently
+        let other = PathRedactor::new(
+            &PathRedactionConfig {
+                keep_components: None,
+                hash_below: vec!["~/Projects".to_string()],
+            },
+            [8; 16],
+            Some("/Users/ada"),
+        );
+        assert_ne!(other.redact("/Users/ada/Projects/acme/src/main.rs"), main);
+    }
+
+    fn with_selection(bundle_id: &str, selected: &str) -> AccessibilityContext {
+        AccessibilityContext {
+            app_info: AppInfo::new("App".to_string(), bundle_id.to_string(), 1),
+            window_title: None,
+            document_path: None,
+            document_path_confidence: None,
+            is_document_modified: None,
+            current_url: None,
+            page_title: None,
+            tab_count: None,
+            browser_profile: None,
+            sheet_name: None,
+            selected_range: None,
+            mail_folder: None,
+            conversation: None,
+            current_reference: None,
+            reference_collection: None,
+            terminal_command: None,
+            active_file_path: None,
+            project_name: None,
+            selected_text: Some(selected.to_string()),
+            focused_element: None,
+            ui_path: Vec::new(),
+            raw_attributes: HashMap::new(),
+            windows: Vec::new(),
+            custom_fields: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_finder_selection_and_copy_file() {
+        let redactor = configured(Some(1), &[]);
+        let mut context = with_selection(
+            FINDER_BUNDLE_ID,
+            "Selected: /Users/ada/Clients/Acme/Smith, John.pdf, /Users/ada/Clients/Acme/plan.md",
+        );
+        redactor.redact_context(&mut context);
+        assert_eq!(
+            context.selected_text.as_deref(),
+            Some("Selected: …/Smith, John.pdf, …/plan.md")
+        );
+
+        // Other apps' selected text is not a list of paths
+        let mut context = with_selection("com.apple.Notes", "Selected: /Users/ada/x.md");
+        redactor.redact_context(&mut context);
+        assert_eq!(
+            context.selected_text.as_deref(),
+            Some("Selected: /Users/ada/x.md")
+        );
+
+        let mut copy = CopyContext {
+            file: Some("/Users/ada/Clients/Acme/plan.md".to_string()),
+            window_title: Some("plan.md".to_string()),
+            ..CopyContext::default()
+        };
+        redactor.redact_copy_context(&mut copy);
+        assert_eq!(copy.file.as_deref(), Some("…/plan.md"));
+        assert_eq!(copy.window_title.as_deref(), Some("plan.md"));
+    }
+}
@@ -0,0 +1,542 @@
+// src/core/power_state.rs
+//! Power source and battery state
+//!
+//! In field studies on laptops, whether the machine was on battery, how full
+//! it was and whether Low Power Mode was on shape how people work: fewer
+//! apps open, dimmer screens, sessions cut short by a dying battery. The
+//! state is read from the IOKit power source APIs and Low Power Mode from
+//! `NSProcessInfo`. A monitor re-reads it whenever IOKit reports a power
+//! source change or `NSProcessInfo` a Low Power Mode change, stamps the
+//! latest reading on every event and reports a `PowerStateChanged` when the
+//! power source or Low Power Mode changes, e.g. when the laptop is unplugged.
+
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use block2::RcBlock;
+use chrono::{DateTime, Utc};
+use core_foundation::array::{CFArray, CFArrayRef};
+use core_foundation::base::{CFRelease, CFType, CFTypeRef, TCFType};
+use core_foundation::boolean::CFBoolean;
+use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+use core_foundation::number::CFNumber;
+use core_foundation::string::{CFString, CFStringRef};
+use core_foundation_sys::runloop::{
+    kCFRunLoopDefaultMode, CFRunLoopAddSource, CFRunLoopGetCurrent, CFRunLoopRemoveSource,
+    CFRunLoopRunInMode, CFRunLoopSourceRef,
+};
+use objc2::rc::Retained;
+use objc2::runtime::{NSObjectProtocol, ProtocolObject};
+use objc2_foundation::{
+    NSNotification, NSNotificationCenter, NSProcessInfo,
+    NSProcessInfoPowerStateDidChangeNotification,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+    fn IOPSCopyPowerSourcesList(blob: CFTypeRef) -> CFArrayRef;
+    fn IOPSGetPowerSourceDescription(blob: CFTypeRef, source: CFTypeRef) -> CFDictionaryRef;
+    fn IOPSGetProvidingPowerSourceType(snapshot: CFTypeRef) -> CFStringRef;
+    fn IOPSNotificationCreateRunLoopSource(
+        callback: extern "C" fn(*mut c_void),
+        context: *mut c_void,
+    ) -> CFRunLoopSourceRef;
+}
+
+/// Seconds the notification thread waits before checking whether to stop
+const IDLE_WAIT: f64 = 5.0;
+
+// IOPSKeys.h
+const KEY_POWER_SOURCE_STATE: &str = "Power Source State";
+const KEY_CURRENT_CAPACITY: &str = "Current Capacity";
+const KEY_MAX_CAPACITY: &str = "Max Capacity";
+const KEY_IS_CHARGING: &str = "Is Charging";
+const KEY_TYPE: &str = "Type";
+const TYPE_INTERNAL_BATTERY: &str = "InternalBattery";
+
+/// Where the machine draws power from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    Ups,
+    Unknown,
+}
+
+impl PowerSource {
+    /// From an IOKit power source state such as `AC Power`
+    fn from_state(state: &str) -> Self {
+        match state {
+            "AC Power" => PowerSource::Ac,
+            "Battery Power" => PowerSource::Battery,
+            "UPS Power" => PowerSource::Ups,
+            _ => PowerSource::Unknown,
+        }
+    }
+}
+
+/// A value in an IOKit power source description
+#[derive(Debug, Clone, PartialEq)]
+pub enum PowerValue {
+    String(String),
+    Number(i64),
+    Bool(bool),
+}
+
+/// One power source description, keyed as in IOPSKeys.h
+pub type PowerSourceDescription = HashMap<String, PowerValue>;
+
+/// Power source, battery level and Low Power Mode at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PowerState {
+    pub source: PowerSource,
+    /// Charge of the internal battery, `None` on machines without one
+    pub battery_percent: Option<u8>,
+    pub charging: Option<bool>,
+    pub low_power_mode: bool,
+}
+
+impl PowerState {
+    /// Map the providing power source type and the source descriptions
+    /// returned by IOKit; desktops have no descriptions at all
+    pub fn from_descriptions(
+        providing: Option<&str>,
+        sources: &[PowerSourceDescription],
+        low_power_mode: bool,
+    ) -> Self {
+        let string = |source: &PowerSourceDescription, key: &str| match source.get(key) {
+            Some(PowerValue::String(value)) => Some(value.clone()),
+            _ => None,
+        };
+        let number = |source: &PowerSourceDescription, key: &str| match source.get(key) {
+            Some(PowerValue::Number(value)) => Some(*value),
+            _ => None,
+        };
+        let battery = sources
+            .iter()
+            .find(|source| string(source, KEY_TYPE).as_deref() == Some(TYPE_INTERNAL_BATTERY))
+            .or_else(|| sources.first());
+
+        let source = providing
+            .map(PowerSource::from_state)
+            .filter(|source| *source != PowerSource::Unknown)
+            .or_else(|| {
+                battery
+                    .and_then(|b| string(b, KEY_POWER_SOURCE_STATE))
+                    .map(|state| PowerSource::from_state(&state))
+            })
+            .unwrap_or(PowerSource::Unknown);
+        let battery_percent = battery.and_then(|b| {
+            let current = number(b, KEY_CURRENT_CAPACITY)?;
+            let max = number(b, KEY_MAX_CAPACITY)
+                .filter(|max| *max > 0)
+                .unwrap_or(100);
+            Some((current * 100 / max).clamp(0, 100) as u8)
+        });
+        let charging = battery.and_then(|b| match b.get(KEY_IS_CHARGING) {
+            Some(PowerValue::Bool(charging)) => Some(*charging),
+            _ => None,
+        });
+        Self {
+            source,
+            battery_percent,
+            charging,
+            low_power_mode,
+        }
+    }
+}
+
+/// The power source or Low Power Mode changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerStateChanged {
+    pub timestamp: DateTime<Utc>,
+    /// `plugged_in`, `unplugged` (now on battery), `on_ups`,
+    /// `source_unknown`, `low_power_on` or `low_power_off`
+    pub changes: Vec<String>,
+    pub previous: PowerState,
+    pub current: PowerState,
+}
+
+impl PowerStateChanged {
+    /// `None` when only the battery level or charging changed
+    pub fn between(
+        previous: &PowerState,
+        current: &PowerState,
+        timestamp: DateTime<Utc>,
+    ) -> Option<Self> {
+        let mut changes = Vec::new();
+        if previous.source != current.source {
+            changes.push(
+                match current.source {
+                    PowerSource::Ac => "plugged_in",
+                    PowerSource::Battery => "unplugged",
+                    PowerSource::Ups => "on_ups",
+                    PowerSource::Unknown => "source_unknown",
+                }
+                .to_string(),
+            );
+        }
+        if previous.low_power_mode != current.low_power_mode {
+            changes.push(
+                if current.low_power_mode {
+                    "low_power_on"
+                } else {
+                    "low_power_off"
+                }
+                .to_string(),
+            );
+        }
+        if changes.is_empty() {
+            return None;
+        }
+        Some(Self {
+            timestamp,
+            changes,
+            previous: previous.clone(),
+            current: current.clone(),
+        })
+    }
+}
+
+fn power_value(value: &CFType) -> Option<PowerValue> {
+    if let Some(string) = value.downcast::<CFString>() {
+        Some(PowerValue::String(string.to_string()))
+    } else if let Some(boolean) = value.downcast::<CFBoolean>() {
+        Some(PowerValue::Bool(boolean.into()))
+    } else {
+        value
+            .downcast::<CFNumber>()
+            .and_then(|number| number.to_i64())
+            .map(PowerValue::Number)
+    }
+}
+
+fn description(dictionary: CFDictionaryRef) -> PowerSourceDescription {
+    if dictionary.is_null() {
+        return HashMap::new();
+    }
+    // SAFETY: the description is owned by the snapshot, which outlives this call
+    let dictionary: CFDictionary<CFString, CFType> =
+        unsafe { CFDictionary::wrap_under_get_rule(dictionary) };
+    let (keys, values) = dictionary.get_keys_and_values();
+    keys.into_iter()
+        .zip(values)
+        .filter_map(|(key, value)| {
+            // SAFETY: keys and values are live objects of the dictionary
+            let key = unsafe { CFString::wrap_under_get_rule(key as CFStringRef) };
+            let value = unsafe { CFType::wrap_under_get_rule(value as CFTypeRef) };
+            Some((key.to_string(), power_value(&value)?))
+        })
+        .collect()
+}
+
+/// Whether Low Power Mode is on
+pub fn low_power_mode() -> bool {
+    // SAFETY: a plain property read on the shared process info
+    unsafe { NSProcessInfo::processInfo().isLowPowerModeEnabled() }
+}
+
+/// The current power state; `None` if IOKit has no power source information
+pub fn current_power_state() -> Option<PowerState> {
+    // SAFETY: returns a +1 snapshot or null
+    let blob = unsafe { IOPSCopyPowerSourcesInfo() };
+    if blob.is_null() {
+        return None;
+    }
+    // SAFETY: the snapshot is owned here and released when it drops
+    let blob = unsafe { CFType::wrap_under_create_rule(blob) };
+    // SAFETY: the providing type is owned by the snapshot
+    let providing = unsafe { IOPSGetProvidingPowerSourceType(blob.as_CFTypeRef()) };
+    let providing = (!providing.is_null())
+        // SAFETY: a live string owned by the snapshot
+        .then(|| unsafe { CFString::wrap_under_get_rule(providing) }.to_string());
+    // SAFETY: returns a +1 array or null
+    let list = unsafe { IOPSCopyPowerSourcesList(blob.as_CFTypeRef()) };
+    let sources: Vec<PowerSourceDescription> = if list.is_null() {
+        Vec::new()
+    } else {
+        // SAFETY: the array is owned here
+        let list: CFArray<CFType> = unsafe { CFArray::wrap_under_create_rule(list) };
+        list.iter()
+            .map(|source| {
+                // SAFETY: the source and snapshot are live
+                description(unsafe {
+                    IOPSGetPowerSourceDescription(blob.as_CFTypeRef(), source.as_CFTypeRef())
+                })
+            })
+            .collect()
+    };
+    Some(PowerState::from_descriptions(
+        providing.as_deref(),
+        &sources,
+        low_power_mode(),
+    ))
+}
+
+/// Receives power source and Low Power Mode transitions
+pub trait PowerStateListener: Send {
+    fn on_power_state_changed(&mut self, event: &PowerStateChanged);
+}
+
+/// The latest reading and the listeners to tell about changes
+#[derive(Clone)]
+struct Sampler {
+    latest: Arc<Mutex<Option<PowerState>>>,
+    listeners: Arc<Mutex<Vec<Box<dyn PowerStateListener>>>>,
+}
+
+impl Sampler {
+    /// Re-read the power state and report a transition, if any
+    fn sample(&self) {
+        let Some(current) = current_power_state() else {
+            return;
+        };
+        let previous = self.latest.lock().unwrap().replace(current.clone());
+        let Some(event) = previous
+            .and_then(|previous| PowerStateChanged::between(&previous, &current, Utc::now()))
+        else {
+            return;
+        };
+        for listener in self.listeners.lock().unwrap().iter_mut() {
+            listener.on_power_state_changed(&event);
+        }
+    }
+}
+
+extern "C" fn power_sources_changed(context: *mut c_void) {
+    // SAFETY: the context is the sampler boxed by the notification thread,
+    // which outlives its run loop source
+    let sampler = unsafe { &*(context as *const Sampler) };
+    sampler.sample();
+}
+
+/// Follows power source and Low Power Mode changes
+///
+/// Power source changes (plugging in, unplugging, battery level) arrive
+/// through an IOKit run loop source on a background thread; Low Power Mode
+/// changes through `NSProcessInfoPowerStateDidChangeNotification`.
+pub struct PowerMonitor {
+    sampler: Sampler,
+    running: Arc<AtomicBool>,
+    token: Option<Retained<ProtocolObject<dyn NSObjectProtocol>>>,
+}
+
+impl PowerMonitor {
+    pub fn new() -> Self {
+        Self {
+            sampler: Sampler {
+                latest: Arc::new(Mutex::new(current_power_state())),
+                listeners: Arc::new(Mutex::new(Vec::new())),
+            },
+            running: Arc::new(AtomicBool::new(false)),
+            token: None,
+        }
+    }
+
+    pub fn add_listener<T: PowerStateListener + 'static>(&self, listener: T) {
+        self.sampler
+            .listeners
+            .lock()
+            .unwrap()
+            .push(Box::new(listener));
+    }
+
+    /// The most recent reading
+    pub fn latest(&self) -> Option<PowerState> {
+        self.sampler.latest.lock().unwrap().clone()
+    }
+
+    /// An enrichment stage stamping the most recent reading on events
+    pub fn tagger(&self) -> PowerStateTagger {
+        PowerStateTagger {
+            latest: Arc::clone(&self.sampler.latest),
+        }
+    }
+
+    /// Start following power source and Low Power Mode changes
+    pub fn start(&mut self) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let sampler = self.sampler.clone();
+        let running = Arc::clone(&self.running);
+        std::thread::spawn(move || {
+            let context = Box::into_raw(Box::new(sampler));
+            // SAFETY: the context stays valid until the source is removed below
+            let source = unsafe {
+                IOPSNotificationCreateRunLoopSource(power_sources_changed, context.cast())
+            };
+            if source.is_null() {
+                eprintln!("⚠️  Power source notifications are unavailable");
+            } else {
+                // SAFETY: the source is live and added to this thread's run loop
+                unsafe { CFRunLoopAddSource(CFRunLoopGetCurrent(), source, kCFRunLoopDefaultMode) };
+                while running.load(Ordering::SeqCst) {
+                    // SAFETY: runs this thread's run loop, delivering the source's callback
+                    unsafe { CFRunLoopRunInMode(kCFRunLoopDefaultMode, IDLE_WAIT, 0) };
+                }
+                // SAFETY: the source was created (+1) and added above
+                unsafe {
+                    CFRunLoopRemoveSource(CFRunLoopGetCurrent(), source, kCFRunLoopDefaultMode);
+                    CFRelease(source as CFTypeRef);
+                }
+            }
+            // SAFETY: boxed above; the callback can no longer run
+            drop(unsafe { Box::from_raw(context) });
+        });
+
+        let sampler = self.sampler.clone();
+        let block = RcBlock::new(move |_notification: NonNull<NSNotification>| sampler.sample());
+        let token = unsafe {
+            NSNotificationCenter::defaultCenter().addObserverForName_object_queue_usingBlock(
+                Some(NSProcessInfoPowerStateDidChangeNotification),
+                None,
+                None,
+                &block,
+            )
+        };
+        self.token = Some(token);
+    }
+
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(token) = self.token.take() {
+            unsafe {
+                NSNotificationCenter::defaultCenter().removeObserver(token.as_ref());
+            }
+        }
+    }
+}
+
+impl Default for PowerMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for PowerMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Stamps the latest power reading on every event
+pub struct PowerStateTagger {
+    latest: Arc<Mutex<Option<PowerState>>>,
+}
+
+impl EnrichmentStage for PowerStateTagger {
+    fn name(&self) -> &str {
+        "power"
+    }
+
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Annotate
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        event.power = self.latest.lock().unwrap().clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battery(state: &str, current: i64, charging: bool) -> PowerSourceDescription {
+        HashMap::from([
+            (
+                KEY_TYPE.to_string(),
+                PowerValue::String(TYPE_INTERNAL_BATTERY.to_string()),
+            ),
+            (
+                KEY_POWER_SOURCE_STATE.to_string(),
+                PowerValue::String(state.to_string()),
+            ),
+            (
+                KEY_CURRENT_CAPACITY.to_string(),
+                PowerValue::Number(current),
+            ),
+            (KEY_MAX_CAPACITY.to_string(), PowerValue::Number(100)),
+            (KEY_IS_CHARGING.to_string(), PowerValue::Bool(charging)),
+            (
+                "Name".to_string(),
+                PowerValue::String("InternalBattery-0".to_string()),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_power_dictionary_maps_to_state() {
+        let plugged = PowerState::from_descriptions(
+            Some("AC Power"),
+            &[battery("AC Power", 80, true)],
+            false,
+        );
+        assert_eq!(
+            plugged,
+            PowerState {
+                source: PowerSource::Ac,
+                battery_percent: Some(80),
+                charging: Some(true),
+                low_power_mode: false,
+            }
+        );
+
+        let unplugged = PowerState::from_descriptions(
+            Some("Battery Power"),
+            &[battery("Battery Power", 79, false)],
+            true,
+        );
+        assert_eq!(unplugged.source, PowerSource::Battery);
+        assert_eq!(unplugged.battery_percent, Some(79));
+        assert!(unplugged.low_power_mode);
+
+        // A desktop: no battery, only the providing type
+        let desktop = PowerState::from_descriptions(Some("AC Power"), &[], false);
+        assert_eq!(desktop.source, PowerSource::Ac);
+        assert_eq!(desktop.battery_percent, None);
+        assert_eq!(desktop.charging, None);
+
+        // Without a providing type the battery's state is used
+        let mut half = battery("Battery Power", 1500, false);
+        half.insert(KEY_MAX_CAPACITY.to_string(), PowerValue::Number(3000));
+        let state = PowerState::from_descriptions(None, &[half], false);
+        assert_eq!(state.source, PowerSource::Battery);
+        assert_eq!(state.battery_percent, Some(50));
+
+        let now = Utc::now();
+        let event = PowerStateChanged::between(&plugged, &unplugged, now).unwrap();
+        assert_eq!(event.changes, vec!["unplugged", "low_power_on"]);
+        let event = PowerStateChanged::between(&unplugged, &plugged, now).unwrap();
+        assert_eq!(event.changes, vec!["plugged_in", "low_power_off"]);
+        // A UPS or an unreadable source is not reported as unplugged
+        let ups = PowerState {
+            source: PowerSource::Ups,
+            ..plugged.clone()
+        };
+        let event = PowerStateChanged::between(&plugged, &ups, now).unwrap();
+        assert_eq!(event.changes, vec!["on_ups"]);
+        let unknown = PowerState {
+            source: PowerSource::Unknown,
+            ..plugged.clone()
+        };
+        let event = PowerStateChanged::between(&plugged, &unknown, now).unwrap();
+        assert_eq!(event.changes, vec!["source_unknown"]);
+        let drained = PowerState {
+            battery_percent: Some(60),
+            ..unplugged.clone()
+        };
+        assert_eq!(PowerStateChanged::between(&unplugged, &drained, now), None);
+    }
+}
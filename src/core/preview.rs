@@ -0,0 +1,82 @@
+// src/core/preview.rs
+//! Short previews of user content
+//!
+//! Clipboard text and focused-element values are printed as previews. Slicing
+//! them by bytes (`&text[..100]`) panics when the cut lands inside a
+//! multi-byte character, so every preview goes through `truncate_preview`,
+//! which counts characters and keeps simple grapheme clusters (combining
+//! accents, emoji modifiers, ZWJ sequences) together.
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+/// Characters that attach to the one before them
+fn extends_previous(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'     // combining diacritical marks
+        | '\u{1AB0}'..='\u{1AFF}'   // combining diacritical marks extended
+        | '\u{20D0}'..='\u{20FF}'   // combining marks for symbols (keycaps)
+        | '\u{FE00}'..='\u{FE0F}'   // variation selectors
+        | '\u{1F3FB}'..='\u{1F3FF}' // skin tone modifiers
+        | '\u{E0020}'..='\u{E007F}' // tag characters (subdivision flags)
+        | ZERO_WIDTH_JOINER)
+}
+
+/// At most `max_chars` characters of `s`, with `...` appended when cut
+///
+/// The cut never splits a character, and is moved past any characters that
+/// belong to the last one kept, so the preview may run a few characters over.
+pub fn truncate_preview(s: &str, max_chars: usize) -> String {
+    let mut chars = s.char_indices().peekable();
+    let mut end = 0;
+    let mut count = 0;
+    let mut previous = None;
+    while let Some(&(index, c)) = chars.peek() {
+        let attached = extends_previous(c) || previous == Some(ZERO_WIDTH_JOINER);
+        if count >= max_chars && !(attached && count > 0) {
+            break;
+        }
+        end = index + c.len_utf8();
+        count += 1;
+        previous = Some(c);
+        chars.next();
+    }
+    if end >= s.len() {
+        s.to_string()
+    } else {
+        format!("{}...", &s[..end])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_strings_are_unchanged() {
+        assert_eq!(truncate_preview("hello", 5), "hello");
+        assert_eq!(truncate_preview("", 3), "");
+        assert_eq!(truncate_preview("hello world", 5), "hello...");
+    }
+
+    #[test]
+    fn test_multibyte_boundaries_do_not_panic() {
+        // Each CJK character is 3 bytes, so a byte slice at 4 would panic
+        assert_eq!(truncate_preview("研究助手追跡", 2), "研究...");
+        assert_eq!(truncate_preview("ab😀cd", 3), "ab😀...");
+        assert_eq!(truncate_preview("😀😀😀", 3), "😀😀😀");
+
+        for max in 0..12 {
+            truncate_preview("é🇩🇪 中文 👩‍💻 text", max);
+        }
+    }
+
+    #[test]
+    fn test_clusters_stay_together() {
+        // e + combining acute accent
+        assert_eq!(truncate_preview("cafe\u{301} au lait", 4), "cafe\u{301}...");
+        // thumbs up + skin tone
+        assert_eq!(truncate_preview("ok 👍🏽 done", 4), "ok 👍🏽...");
+        // woman + ZWJ + laptop
+        assert_eq!(truncate_preview("👩‍💻 coding", 1), "👩‍💻...");
+    }
+}
@@ -0,0 +1,206 @@
+// src/core/previous_context.rs
+//! The outgoing app's last-known context
+//!
+//! `previous_app` names the app the user left, but not what they were doing
+//! there. `PreviousContextTracker` remembers the latest context seen for each
+//! app and attaches the outgoing app's to every foreground switch, so a
+//! consumer can tell that the user left a GitHub pull request for Slack
+//! without replaying the whole stream.
+//!
+//! The tracker runs in the derive phase: it keeps what listeners actually
+//! received, after canonicalization and redaction. A redacted event clears
+//! everything remembered, so context from before quiet hours or from an
+//! excluded window never resurfaces on a later switch.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchType};
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+use crate::extractors::ContextValue;
+
+/// What an app was showing when it was last seen
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct EnhancedContextSnapshot {
+    pub bundle_id: String,
+    pub url: Option<String>,
+    pub domain: Option<String>,
+    pub title: Option<String>,
+    pub file: Option<String>,
+    pub extracted_context: HashMap<String, ContextValue>,
+}
+
+impl EnhancedContextSnapshot {
+    /// The event's context; `None` when it carries none
+    pub fn of(event: &AppSwitchEvent) -> Option<Self> {
+        let ws = event.workspace.as_ref();
+        let enh = event.enhanced.as_ref();
+        let snapshot = Self {
+            bundle_id: event.app_info.bundle_id.clone(),
+            url: enh
+                .and_then(|e| e.url.clone())
+                .or_else(|| ws.and_then(|w| w.primary_url.clone())),
+            domain: enh.and_then(|e| e.domain.clone()),
+            title: ws
+                .and_then(|w| w.focused_title.clone())
+                .or_else(|| enh.and_then(|e| e.front_window_title.clone())),
+            file: ws.and_then(|w| w.active_file_paths.first().cloned()),
+            extracted_context: enh.map(|e| e.extracted_context.clone()).unwrap_or_default(),
+        };
+        let empty = snapshot.url.is_none()
+            && snapshot.title.is_none()
+            && snapshot.file.is_none()
+            && snapshot.extracted_context.is_empty();
+        (!empty).then_some(snapshot)
+    }
+}
+
+/// Attaches the outgoing app's last context to foreground switches
+#[derive(Default)]
+pub struct PreviousContextTracker {
+    last: HashMap<String, EnhancedContextSnapshot>,
+}
+
+impl PreviousContextTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Latest context seen for an app
+    pub fn last_for(&self, bundle_id: &str) -> Option<&EnhancedContextSnapshot> {
+        self.last.get(bundle_id)
+    }
+}
+
+impl EnrichmentStage for PreviousContextTracker {
+    fn name(&self) -> &str {
+        "previous_context"
+    }
+
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Derive
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        if event.redacted {
+            self.last.clear();
+            event.previous_context = None;
+            return;
+        }
+        let bundle_id = event.app_info.bundle_id.clone();
+        match event.event_type {
+            AppSwitchType::Foreground
+            | AppSwitchType::SpaceChange
+            | AppSwitchType::SessionChange
+            | AppSwitchType::Wake => {
+                event.previous_context = event
+                    .previous_app
+                    .as_ref()
+                    .filter(|prev| prev.bundle_id != bundle_id)
+                    .and_then(|prev| self.last.get(&prev.bundle_id))
+                    .cloned();
+            }
+            AppSwitchType::Heartbeat => {}
+            AppSwitchType::Terminate => {
+                self.last.remove(&bundle_id);
+                return;
+            }
+            AppSwitchType::Background
+            | AppSwitchType::Launch
+            | AppSwitchType::Hide
+            | AppSwitchType::Unhide
+            | AppSwitchType::Sleep => return,
+        }
+        if let Some(snapshot) = EnhancedContextSnapshot::of(event) {
+            self.last.insert(bundle_id, snapshot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, EnhancedSummary};
+
+    fn app(name: &str, bundle_id: &str) -> AppInfo {
+        AppInfo::new(name.to_string(), bundle_id.to_string(), 1)
+    }
+
+    fn switch(to: &AppInfo, from: Option<&AppInfo>, url: Option<&str>) -> AppSwitchEvent {
+        let mut event = match from {
+            Some(from) => {
+                AppSwitchEvent::with_previous(AppSwitchType::Foreground, to.clone(), from.clone())
+            }
+            None => AppSwitchEvent::new(AppSwitchType::Foreground, to.clone()),
+        };
+        event.enhanced = Some(EnhancedSummary {
+            url: url.map(str::to_string),
+            front_window_title: Some(format!("{} window", to.name)),
+            ..EnhancedSummary::default()
+        });
+        event
+    }
+
+    #[test]
+    fn test_previous_context_is_the_outgoing_apps_last_context() {
+        let safari = app("Safari", "com.apple.Safari");
+        let slack = app("Slack", "com.tinyspeck.slackmacgap");
+        let mut tracker = PreviousContextTracker::new();
+
+        let mut first = switch(&safari, None, Some("https://github.com/org/repo/pull/1"));
+        tracker.enrich(&mut first);
+        assert_eq!(first.previous_context, None);
+
+        // A heartbeat after navigating updates Safari's last context
+        let mut heartbeat = switch(&safari, None, Some("https://github.com/org/repo/pull/2"));
+        heartbeat.event_type = AppSwitchType::Heartbeat;
+        tracker.enrich(&mut heartbeat);
+        let last_safari = tracker.last_for("com.apple.Safari").cloned().unwrap();
+
+        let mut to_slack = switch(&slack, Some(&safari), None);
+        tracker.enrich(&mut to_slack);
+        assert_eq!(to_slack.previous_context.as_ref(), Some(&last_safari));
+        assert_eq!(
+            last_safari.url.as_deref(),
+            Some("https://github.com/org/repo/pull/2")
+        );
+        assert_eq!(last_safari.title.as_deref(), Some("Safari window"));
+
+        let mut back = switch(&safari, Some(&slack), Some("https://github.com/"));
+        tracker.enrich(&mut back);
+        let previous = back.previous_context.unwrap();
+        assert_eq!(previous.bundle_id, "com.tinyspeck.slackmacgap");
+        assert_eq!(previous.title.as_deref(), Some("Slack window"));
+
+        // Nothing is remembered for an app after it quits
+        let mut quit = AppSwitchEvent::new(AppSwitchType::Terminate, slack.clone());
+        tracker.enrich(&mut quit);
+        let mut after_quit = switch(&safari, Some(&slack), None);
+        tracker.enrich(&mut after_quit);
+        assert_eq!(after_quit.previous_context, None);
+    }
+
+    #[test]
+    fn test_redacted_events_clear_what_was_remembered() {
+        let safari = app("Safari", "com.apple.Safari");
+        let slack = app("Slack", "com.tinyspeck.slackmacgap");
+        let mut tracker = PreviousContextTracker::new();
+
+        let mut before = switch(&safari, None, Some("https://example.com/private"));
+        tracker.enrich(&mut before);
+
+        // Quiet hours start while Slack is frontmost
+        let mut quiet = switch(&slack, Some(&safari), None);
+        quiet.redacted = true;
+        tracker.enrich(&mut quiet);
+        assert_eq!(quiet.previous_context, None);
+        assert_eq!(tracker.last_for("com.apple.Safari"), None);
+
+        // After they end, the context from before them is gone
+        let mut after = switch(&safari, Some(&slack), None);
+        tracker.enrich(&mut after);
+        assert_eq!(after.previous_context, None);
+    }
+}
@@ -0,0 +1,147 @@
+// src/core/process_cache.rs
+//! Pid-keyed caching that survives pid reuse
+//!
+//! macOS recycles pids, so a cache keyed on the pid alone can hand the context
+//! of a quit application to whatever launches next under the same number.
+//! Entries here are keyed on the pid but remember the launch date they were
+//! recorded for; a lookup with a different launch date is a miss and evicts
+//! the stale entry.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::core::app_switcher_types::AppInfo;
+
+/// Launch dates are reconstructed from `NSDate` offsets on every sample, so
+/// two readings for the same process differ by a few microseconds.
+const LAUNCH_DATE_TOLERANCE: Duration = Duration::from_secs(1);
+
+/// Convert "seconds since launch" into a monotonic launch instant
+pub fn launch_instant(seconds_since_launch: f64) -> Option<Instant> {
+    if !seconds_since_launch.is_finite() {
+        return None;
+    }
+    Instant::now().checked_sub(Duration::from_secs_f64(seconds_since_launch.max(0.0)))
+}
+
+/// A running process identified by pid plus launch date
+#[derive(Debug, Clone, Copy)]
+pub struct ProcessIdentity {
+    pub pid: i32,
+    pub launch_date: Option<Instant>,
+}
+
+impl ProcessIdentity {
+    pub fn new(pid: i32, launch_date: Option<Instant>) -> Self {
+        Self { pid, launch_date }
+    }
+
+    pub fn of(app: &AppInfo) -> Self {
+        Self::new(app.pid, app.launch_date)
+    }
+
+    /// Whether both identities refer to the same process instance
+    ///
+    /// When either side lacks a launch date we cannot tell a reused pid apart,
+    /// so the pid alone decides.
+    pub fn same_process(&self, other: &Self) -> bool {
+        if self.pid != other.pid {
+            return false;
+        }
+        match (self.launch_date, other.launch_date) {
+            (Some(a), Some(b)) => a.max(b).duration_since(a.min(b)) <= LAUNCH_DATE_TOLERANCE,
+            _ => true,
+        }
+    }
+}
+
+/// Cache keyed by pid that invalidates entries when the pid is reused
+#[derive(Debug)]
+pub struct ProcessCache<V> {
+    entries: HashMap<i32, (ProcessIdentity, V)>,
+}
+
+impl<V> Default for ProcessCache<V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<V> ProcessCache<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look up the entry for this process, evicting it if the pid was reused
+    pub fn get(&mut self, identity: &ProcessIdentity) -> Option<&V> {
+        if self.is_stale(identity) {
+            self.entries.remove(&identity.pid);
+            return None;
+        }
+        self.entries.get(&identity.pid).map(|(_, value)| value)
+    }
+
+    /// Whether an entry exists for this pid but belongs to another process
+    pub fn is_stale(&self, identity: &ProcessIdentity) -> bool {
+        self.entries
+            .get(&identity.pid)
+            .map(|(cached, _)| !cached.same_process(identity))
+            .unwrap_or(false)
+    }
+
+    pub fn insert(&mut self, identity: ProcessIdentity, value: V) {
+        self.entries.insert(identity.pid, (identity, value));
+    }
+
+    pub fn remove(&mut self, pid: i32) -> Option<V> {
+        self.entries.remove(&pid).map(|(_, value)| value)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reused_pid_misses() {
+        let mut cache = ProcessCache::new();
+        let first_launch = Instant::now();
+        let second_launch = first_launch + Duration::from_secs(3600);
+
+        let original = ProcessIdentity::new(4242, Some(first_launch));
+        cache.insert(original, "Safari");
+        assert_eq!(cache.get(&original), Some(&"Safari"));
+
+        let reused = ProcessIdentity::new(4242, Some(second_launch));
+        assert!(cache.is_stale(&reused));
+        assert_eq!(cache.get(&reused), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_launch_date_jitter_hits() {
+        let mut cache = ProcessCache::new();
+        let launch = Instant::now();
+
+        cache.insert(ProcessIdentity::new(7, Some(launch)), 1);
+        let resampled = ProcessIdentity::new(7, Some(launch + Duration::from_micros(40)));
+        assert_eq!(cache.get(&resampled), Some(&1));
+
+        // Unknown launch dates fall back to the pid
+        assert_eq!(cache.get(&ProcessIdentity::new(7, None)), Some(&1));
+    }
+}
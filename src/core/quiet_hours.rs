@@ -0,0 +1,292 @@
+// src/core/quiet_hours.rs
+//! Quiet hours: timing without content
+//!
+//! During configured local-time windows (evenings, weekends) events are still
+//! recorded, so app usage and durations stay complete, but everything that
+//! reveals what the user was looking at is dropped: window and tab titles,
+//! URLs, file paths, menu paths, screenshots, extractor output and clipboard
+//! thumbnails. The gate runs as a redaction stage and marks the event
+//! `redacted`, so derived values (diffs, previous contexts) are not attached.
+//! The accessibility extractor checks the gate itself and reads nothing.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{Datelike, Local, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::clipboard::{ClipboardEvent, ClipboardListener};
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+
+/// The `[quiet_hours]` config section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietHoursConfig {
+    /// Daily local-time windows such as `"18:00-09:00"`; may wrap midnight
+    pub windows: Vec<String>,
+    /// Saturday and Sunday are quiet all day
+    pub weekends: bool,
+}
+
+impl QuietHoursConfig {
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty() && !self.weekends
+    }
+}
+
+/// One daily window; `start == end` covers the whole day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietWindow {
+    /// Start is inclusive, end exclusive
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl fmt::Display for QuietWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+impl FromStr for QuietWindow {
+    type Err = String;
+
+    /// Accepts `HH:MM-HH:MM`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid quiet hours window '{}' (expected HH:MM-HH:MM)", s);
+        let (start, end) = s.trim().split_once('-').ok_or_else(invalid)?;
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+        Ok(QuietWindow {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+}
+
+/// Source of the current local time; replaced in tests
+pub type Clock = Arc<dyn Fn() -> NaiveDateTime + Send + Sync>;
+
+/// Drops content fields from events recorded during quiet hours
+#[derive(Clone)]
+pub struct QuietHoursGate {
+    windows: Vec<QuietWindow>,
+    weekends: bool,
+    clock: Clock,
+}
+
+impl QuietHoursGate {
+    pub fn from_config(config: &QuietHoursConfig) -> Result<Self, String> {
+        let windows = config
+            .windows
+            .iter()
+            .map(|w| w.parse())
+            .collect::<Result<Vec<QuietWindow>, String>>()?;
+        Ok(Self {
+            windows,
+            weekends: config.weekends,
+            clock: Arc::new(|| Local::now().naive_local()),
+        })
+    }
+
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn is_quiet_at(&self, at: NaiveDateTime) -> bool {
+        if self.weekends && matches!(at.weekday(), Weekday::Sat | Weekday::Sun) {
+            return true;
+        }
+        self.windows.iter().any(|w| w.contains(at.time()))
+    }
+
+    pub fn is_quiet(&self) -> bool {
+        self.is_quiet_at((self.clock)())
+    }
+
+    /// Clear every content field, keeping app and timing
+    pub fn redact_event(event: &mut AppSwitchEvent) {
+        if let Some(ws) = &mut event.workspace {
+            ws.focused_title = None;
+            ws.tab_titles.clear();
+            ws.active_file_paths.clear();
+            ws.primary_url = None;
+        }
+        if let Some(enh) = &mut event.enhanced {
+            enh.front_window_title = None;
+            enh.url = None;
+            enh.domain = None;
+            enh.tab_title = None;
+            enh.browser_profile = None;
+            enh.menu_context = None;
+            enh.extracted_context.clear();
+        }
+        if let Some(windows) = &mut event.windows {
+            for window in windows {
+                window.title = None;
+            }
+        }
+        event.diff = None;
+        event.previous_context = None;
+        event.screenshot_path = None;
+        event.redacted = true;
+    }
+
+    /// Keep the clipboard change and its types, drop the image thumbnail
+    /// and the source window's context
+    pub fn redact_clipboard(event: &mut ClipboardEvent) {
+        if let Some(image) = &mut event.image {
+            image.thumbnail_base64 = None;
+        }
+        event.context = None;
+    }
+
+    /// Wrap a clipboard listener so it only sees redacted events in quiet hours
+    pub fn clipboard_listener<L: ClipboardListener>(&self, inner: L) -> QuietClipboardListener<L> {
+        QuietClipboardListener {
+            gate: self.clone(),
+            inner,
+        }
+    }
+}
+
+impl EnrichmentStage for QuietHoursGate {
+    fn name(&self) -> &str {
+        "quiet_hours"
+    }
+
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Redact
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        if self.is_quiet() {
+            Self::redact_event(event);
+        }
+    }
+}
+
+/// Clipboard listener behind a `QuietHoursGate`
+pub struct QuietClipboardListener<L> {
+    gate: QuietHoursGate,
+    inner: L,
+}
+
+impl<L: ClipboardListener> ClipboardListener for QuietClipboardListener<L> {
+    fn on_clipboard_change(&mut self, event: &ClipboardEvent) {
+        if self.gate.is_quiet() {
+            let mut event = event.clone();
+            QuietHoursGate::redact_clipboard(&mut event);
+            self.inner.on_clipboard_change(&event);
+        } else {
+            self.inner.on_clipboard_change(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType, EnhancedSummary};
+    use chrono::NaiveDate;
+    use std::sync::Mutex;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // October 2026: the 16th is a Friday, the 17th a Saturday
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn gate(now: Arc<Mutex<NaiveDateTime>>) -> QuietHoursGate {
+        let config = QuietHoursConfig {
+            windows: vec!["18:00-09:00".to_string()],
+            weekends: true,
+        };
+        QuietHoursGate::from_config(&config)
+            .unwrap()
+            .with_clock(Arc::new(move || *now.lock().unwrap()))
+    }
+
+    fn event() -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 42),
+        );
+        event.enhanced = Some(EnhancedSummary {
+            front_window_title: Some("Bank statement".to_string()),
+            url: Some("https://bank.example/statement".to_string()),
+            tab_title: Some("Bank statement".to_string()),
+            menu_context: Some("History › Bank statement".to_string()),
+            ..EnhancedSummary::default()
+        });
+        event.screenshot_path = Some("/tmp/shots/safari.png".to_string());
+        event
+    }
+
+    #[test]
+    fn test_windows_wrap_midnight() {
+        let window: QuietWindow = "18:00-09:00".parse().unwrap();
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert!(window.contains(time(18, 0)));
+        assert!(window.contains(time(2, 30)));
+        assert!(!window.contains(time(9, 0)));
+        assert!(!window.contains(time(12, 0)));
+        assert_eq!(window.to_string(), "18:00-09:00");
+
+        let lunch: QuietWindow = "12:00-13:00".parse().unwrap();
+        assert!(lunch.contains(time(12, 30)));
+        assert!(!lunch.contains(time(13, 0)));
+
+        assert!("18:00".parse::<QuietWindow>().is_err());
+        assert!("25:00-09:00".parse::<QuietWindow>().is_err());
+    }
+
+    #[test]
+    fn test_content_suppressed_only_inside_quiet_hours() {
+        let now = Arc::new(Mutex::new(at(16, 11, 0)));
+        let mut gate = gate(now.clone());
+
+        // Friday late morning: full capture
+        let mut working = event();
+        gate.enrich(&mut working);
+        let enh = working.enhanced.as_ref().unwrap();
+        assert_eq!(enh.url.as_deref(), Some("https://bank.example/statement"));
+        assert_eq!(enh.front_window_title.as_deref(), Some("Bank statement"));
+
+        // Friday evening: app and timing kept, content dropped
+        *now.lock().unwrap() = at(16, 19, 30);
+        let mut evening = event();
+        gate.enrich(&mut evening);
+        let enh = evening.enhanced.as_ref().unwrap();
+        assert_eq!(enh.url, None);
+        assert_eq!(enh.front_window_title, None);
+        assert_eq!(enh.tab_title, None);
+        assert_eq!(enh.menu_context, None);
+        assert_eq!(evening.screenshot_path, None);
+        assert!(evening.redacted);
+        assert_eq!(evening.app_info.bundle_id, "com.apple.Safari");
+
+        // Saturday midday is quiet because of the weekend rule
+        *now.lock().unwrap() = at(17, 12, 0);
+        assert!(gate.is_quiet());
+    }
+}
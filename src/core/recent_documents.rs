@@ -0,0 +1,305 @@
+// src/core/recent_documents.rs
+//! Recent documents as a fallback for the active file
+//!
+//! Many apps do not expose `AXDocument` on their windows, so the file they
+//! are editing is unknown. macOS keeps each app's recent documents (the
+//! "Open Recent" menu that NSDocumentController maintains) in a shared file
+//! list under `~/Library/Application Support/com.apple.sharedfilelist`.
+//! When AX has no document, the most recent of those whose name appears as a
+//! whole word in the window title is taken as the active file. Resolving the
+//! list's bookmarks touches the file system, so `RecentDocuments` keeps each
+//! app's paths until its list file changes.
+//!
+//! That is a guess, so such paths are marked `PathConfidence::Low`; paths
+//! read from AX are `High`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::time::SystemTime;
+
+use core_foundation::array::CFArray;
+use core_foundation::base::{kCFAllocatorDefault, CFType, CFTypeRef, TCFType};
+use core_foundation::data::CFData;
+use core_foundation::dictionary::CFDictionary;
+use core_foundation::propertylist::{create_with_data, kCFPropertyListImmutable, CFPropertyList};
+use core_foundation::string::CFString;
+use core_foundation::url::CFURL;
+use core_foundation_sys::base::Boolean;
+use core_foundation_sys::url::{
+    kCFURLBookmarkResolutionWithoutMountingMask, kCFURLBookmarkResolutionWithoutUIMask,
+    CFURLCreateByResolvingBookmarkData,
+};
+use serde::{Deserialize, Serialize};
+
+/// Per-app recent document lists, relative to the home directory
+const RECENT_DOCUMENTS_DIR: &str = "Library/Application Support/com.apple.sharedfilelist/com.apple.LSSharedFileList.ApplicationRecentDocuments";
+
+/// Shared file list formats, newest first
+const STORE_EXTENSIONS: &[&str] = &["sfl3", "sfl2"];
+
+/// Bookmark data starts with this tag
+const BOOKMARK_MAGIC: &[u8] = b"book";
+
+/// Shortest file name or stem matched against a window title
+const MIN_TITLE_MATCH_LEN: usize = 3;
+
+/// How a document path was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathConfidence {
+    /// Read from the window's `AXDocument`
+    High,
+    /// Guessed from the app's recent documents
+    Low,
+}
+
+/// A document path and how far it can be trusted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentPath {
+    pub path: String,
+    pub confidence: PathConfidence,
+}
+
+/// The window's document: the AX one when present, otherwise the most
+/// recent document named in the window title
+///
+/// `recent` is only called when AX has no document.
+pub fn resolve_document_path(
+    ax_document: Option<String>,
+    window_title: Option<&str>,
+    recent: impl FnOnce() -> Vec<String>,
+) -> Option<DocumentPath> {
+    if let Some(path) = ax_document.filter(|d| !d.is_empty()) {
+        return Some(DocumentPath {
+            path,
+            confidence: PathConfidence::High,
+        });
+    }
+    let title = window_title.map(str::trim).filter(|t| !t.is_empty())?;
+    recent()
+        .into_iter()
+        .find(|path| title_names(title, Path::new(path)))
+        .map(|path| DocumentPath {
+            path,
+            confidence: PathConfidence::Low,
+        })
+}
+
+/// Whether the title shows the file's name, with or without extension
+fn title_names(title: &str, path: &Path) -> bool {
+    [path.file_name(), path.file_stem()]
+        .into_iter()
+        .flatten()
+        .filter_map(|name| name.to_str())
+        .any(|name| name.chars().count() >= MIN_TITLE_MATCH_LEN && contains_word(title, name))
+}
+
+/// Whether `name` appears in `title` on its own, not inside a longer word
+/// ("app" is not in "WhatsApp")
+fn contains_word(title: &str, name: &str) -> bool {
+    title.match_indices(name).any(|(start, _)| {
+        let before = title[..start].chars().next_back();
+        let after = title[start + name.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// The shared file list holding the app's recent documents, if it has one
+fn store_path(bundle_id: &str) -> Option<PathBuf> {
+    if bundle_id.is_empty() {
+        return None;
+    }
+    let dir = PathBuf::from(std::env::var_os("HOME")?).join(RECENT_DOCUMENTS_DIR);
+    let name = bundle_id.to_lowercase();
+    STORE_EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", name, ext)))
+        .find(|path| path.exists())
+}
+
+/// Recent documents per app, resolved again only when the app's list changes
+#[derive(Default)]
+pub struct RecentDocuments {
+    /// List file, its modification time and its paths, per bundle ID
+    cached: HashMap<String, (PathBuf, SystemTime, Vec<String>)>,
+}
+
+impl RecentDocuments {
+    /// Paths of the app's recent documents, most recent first
+    pub fn paths(&mut self, bundle_id: &str) -> Vec<String> {
+        match store_path(bundle_id) {
+            Some(store) => self.get_or_read(bundle_id, &store, read_store),
+            None => Vec::new(),
+        }
+    }
+
+    /// The cached paths for `store`, or `read`'s while it has a new mtime
+    fn get_or_read(
+        &mut self,
+        bundle_id: &str,
+        store: &Path,
+        read: impl FnOnce(&Path) -> Vec<String>,
+    ) -> Vec<String> {
+        let Ok(modified) = std::fs::metadata(store).and_then(|m| m.modified()) else {
+            return Vec::new();
+        };
+        if let Some((path, at, paths)) = self.cached.get(bundle_id) {
+            if path == store && *at == modified {
+                return paths.clone();
+            }
+        }
+        let paths = read(store);
+        self.cached.insert(
+            bundle_id.to_string(),
+            (store.to_path_buf(), modified, paths.clone()),
+        );
+        paths
+    }
+}
+
+/// Resolve the bookmarks of a shared file list, in stored order
+///
+/// The list is a keyed archive; its bookmarks are the data objects in
+/// `$objects`, so those are resolved without interpreting the archive.
+fn read_store(path: &Path) -> Vec<String> {
+    let Ok(bytes) = std::fs::read(path) else {
+        return Vec::new();
+    };
+    let Ok((plist, _)) = create_with_data(CFData::from_buffer(&bytes), kCFPropertyListImmutable)
+    else {
+        return Vec::new();
+    };
+    // SAFETY: create_with_data returns a +1 reference
+    let plist = unsafe { CFPropertyList::wrap_under_create_rule(plist) };
+    let Some(root) = plist.downcast_into::<CFDictionary>() else {
+        return Vec::new();
+    };
+    let key = CFString::from_static_string("$objects");
+    let Some(objects) = root
+        .find(key.as_CFTypeRef())
+        // SAFETY: dictionary values are live CF objects for the dictionary's lifetime
+        .map(|value| unsafe { CFType::wrap_under_get_rule(*value as CFTypeRef) })
+        .and_then(|value| value.downcast_into::<CFArray>())
+    else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<String> = Vec::new();
+    for item in objects.iter() {
+        // SAFETY: array items are live CF objects for the array's lifetime
+        let item = unsafe { CFType::wrap_under_get_rule(*item as CFTypeRef) };
+        let Some(data) = item.downcast_into::<CFData>() else {
+            continue;
+        };
+        if !data.bytes().starts_with(BOOKMARK_MAGIC) {
+            continue;
+        }
+        if let Some(path) = resolve_bookmark(&data) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// The file a bookmark points to, without prompting or mounting volumes
+fn resolve_bookmark(bookmark: &CFData) -> Option<String> {
+    let mut stale: Boolean = 0;
+    // SAFETY: the bookmark is a live CFData; the result is a +1 reference or null
+    let url = unsafe {
+        CFURLCreateByResolvingBookmarkData(
+            kCFAllocatorDefault,
+            bookmark.as_concrete_TypeRef(),
+            kCFURLBookmarkResolutionWithoutUIMask | kCFURLBookmarkResolutionWithoutMountingMask,
+            ptr::null(),
+            ptr::null(),
+            &mut stale,
+            ptr::null_mut(),
+        )
+    };
+    if url.is_null() {
+        return None;
+    }
+    // SAFETY: `url` is an owned, non-null CFURL
+    let url = unsafe { CFURL::wrap_under_create_rule(url) };
+    url.to_path()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recent() -> Vec<String> {
+        vec![
+            "/Users/me/Notes/todo.txt".to_string(),
+            "/Users/me/Papers/Grant report.pages".to_string(),
+            "/Users/me/Papers/Draft.pages".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_recent_document_fills_in_for_a_missing_ax_path() {
+        let guessed = resolve_document_path(None, Some("Grant report — Edited"), recent);
+        assert_eq!(
+            guessed,
+            Some(DocumentPath {
+                path: "/Users/me/Papers/Grant report.pages".to_string(),
+                confidence: PathConfidence::Low,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(PathConfidence::Low).unwrap(),
+            serde_json::json!("low")
+        );
+
+        // The AX path wins, and the recent list is not read at all
+        let ax = resolve_document_path(
+            Some("file:///Users/me/Papers/Draft.pages".to_string()),
+            Some("Grant report"),
+            || panic!("recent documents read although AX had a path"),
+        );
+        assert_eq!(ax.unwrap().confidence, PathConfidence::High);
+
+        // No guess without a title naming one of the documents
+        assert_eq!(
+            resolve_document_path(None, Some("Untitled 3"), recent),
+            None
+        );
+        // Names only match whole words of the title
+        let apps = || vec!["/Users/me/Code/app".to_string()];
+        assert_eq!(resolve_document_path(None, Some("WhatsApp"), apps), None);
+        assert!(resolve_document_path(None, Some("app — Code"), apps).is_some());
+        assert_eq!(resolve_document_path(None, None, recent), None);
+        assert_eq!(
+            resolve_document_path(Some(String::new()), Some(""), recent),
+            None
+        );
+    }
+
+    #[test]
+    fn test_recent_documents_are_read_again_only_when_the_list_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("com.example.editor.sfl3");
+        std::fs::write(&store, b"list").unwrap();
+        let mut recent = RecentDocuments::default();
+
+        let first = recent.get_or_read("com.example.editor", &store, |_| {
+            vec!["/Users/me/a.txt".to_string()]
+        });
+        let cached = recent.get_or_read("com.example.editor", &store, |_| {
+            panic!("read again although the list is unchanged")
+        });
+        assert_eq!(first, cached);
+
+        let file = std::fs::File::options().write(true).open(&store).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+        let changed = recent.get_or_read("com.example.editor", &store, |_| {
+            vec!["/Users/me/b.txt".to_string()]
+        });
+        assert_eq!(changed, ["/Users/me/b.txt".to_string()]);
+    }
+}
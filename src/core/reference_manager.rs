@@ -0,0 +1,338 @@
+// src/core/reference_manager.rs
+//! Selected reference and collection in Zotero, Mendeley and Papers
+//!
+//! Reference managers show the library as two outlines: a source list of
+//! libraries and collections on the left (`My Library`, `Unfiled Items`, a
+//! user's `Thesis` collection) and the items table next to it, with one
+//! column per field. None of them names the selection in its window title or
+//! answers AppleScript, so both come from the accessibility tree: the
+//! selected row of the items table is read cell by cell against the column
+//! headers into a `CurrentReference`, and the selected row of the source
+//! list is the collection being browsed. Reading sessions can then be tied
+//! to the paper that was open.
+
+use crate::core::accessibility::AccessibilityContext;
+use crate::core::ax_element::AxElement;
+
+/// How deep below the focused window outlines are looked for
+const OUTLINE_SEARCH_DEPTH: usize = 8;
+
+/// Elements visited while looking for outlines
+const OUTLINE_SEARCH_NODES: usize = 400;
+
+/// A supported reference manager
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceManagerApp {
+    Zotero,
+    Mendeley,
+    Papers,
+}
+
+impl ReferenceManagerApp {
+    pub fn for_bundle_id(bundle_id: &str) -> Option<Self> {
+        match bundle_id.to_lowercase().as_str() {
+            "org.zotero.zotero" | "com.zotero.zotero" => Some(Self::Zotero),
+            "com.elsevier.mendeley" | "com.mendeley.desktop" => Some(Self::Mendeley),
+            "com.readcube.papers" => Some(Self::Papers),
+            _ => None,
+        }
+    }
+}
+
+/// The item selected in the items table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentReference {
+    pub title: String,
+    /// As listed, without `et al.`; Zotero only lists the first two
+    pub authors: Vec<String>,
+    pub year: Option<String>,
+}
+
+/// Column headers and selected rows of one outline or table, as text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutlineSnapshot {
+    pub columns: Vec<String>,
+    /// Cell texts of each selected row, in column order
+    pub selected_rows: Vec<Vec<String>>,
+}
+
+impl OutlineSnapshot {
+    /// The items table has a title column; the source list has none
+    fn is_items_table(&self) -> bool {
+        self.column_index(&["title"]).is_some()
+    }
+
+    fn column_index(&self, names: &[&str]) -> Option<usize> {
+        self.columns.iter().position(|column| {
+            let column = column.trim().to_lowercase();
+            names.iter().any(|name| column == *name)
+        })
+    }
+}
+
+/// The reference in the first selected row of the items table
+pub fn parse_selected_item(outline: &OutlineSnapshot) -> Option<CurrentReference> {
+    let row = outline.selected_rows.first()?;
+    let cell = |names: &[&str]| {
+        outline
+            .column_index(names)
+            .and_then(|i| row.get(i))
+            .map(|text| text.trim())
+            .filter(|text| !text.is_empty())
+    };
+    let title = cell(&["title"])?.to_string();
+    let authors = cell(&["creator", "creators", "author", "authors"])
+        .map(parse_authors)
+        .unwrap_or_default();
+    let year = cell(&["year", "date", "published"]).and_then(parse_year);
+    Some(CurrentReference {
+        title,
+        authors,
+        year,
+    })
+}
+
+/// Zotero's creator summary (`Smith and Jones`, `Smith et al.`) or a
+/// Mendeley/Papers author list (`Smith J., Jones K.` or `Smith, J.; Jones, K.`)
+pub fn parse_authors(text: &str) -> Vec<String> {
+    let text = text.trim();
+    let text = text
+        .strip_suffix("et al.")
+        .or_else(|| text.strip_suffix("et al"))
+        .unwrap_or(text)
+        .trim()
+        .trim_end_matches(',');
+    let separators: &[&str] = if text.contains(';') {
+        &[";"]
+    } else {
+        &[",", " and ", " & "]
+    };
+    let mut authors = vec![text.to_string()];
+    for separator in separators {
+        authors = authors
+            .iter()
+            .flat_map(|author| author.split(separator))
+            .map(str::to_string)
+            .collect();
+    }
+    authors
+        .into_iter()
+        .map(|author| author.trim().to_string())
+        .filter(|author| !author.is_empty())
+        .collect()
+}
+
+/// The first four-digit year in a date cell
+fn parse_year(text: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .windows(4)
+        .enumerate()
+        .find(|(i, window)| {
+            window.iter().all(|c| c.is_ascii_digit())
+                && !chars.get(i + 4).is_some_and(|c| c.is_ascii_digit())
+                && !(*i > 0 && chars[i - 1].is_ascii_digit())
+        })
+        .map(|(_, window)| window.iter().collect())
+}
+
+/// The selected reference and the collection being browsed
+pub fn parse_outlines(outlines: &[OutlineSnapshot]) -> (Option<CurrentReference>, Option<String>) {
+    let reference = outlines
+        .iter()
+        .filter(|outline| outline.is_items_table())
+        .find_map(parse_selected_item);
+    let collection = outlines
+        .iter()
+        .filter(|outline| !outline.is_items_table())
+        .filter_map(|outline| outline.selected_rows.first())
+        .filter_map(|row| row.iter().map(|text| text.trim()).find(|t| !t.is_empty()))
+        .map(str::to_string)
+        .next();
+    (reference, collection)
+}
+
+/// Text of a cell: its own value or title, or that of its text child
+fn cell_text(cell: &AxElement) -> Option<String> {
+    let own = cell
+        .string("AXValue")
+        .or_else(|| cell.string("AXTitle"))
+        .filter(|text| !text.trim().is_empty());
+    own.or_else(|| {
+        cell.children().iter().find_map(|child| {
+            child
+                .string("AXValue")
+                .or_else(|| child.string("AXTitle"))
+                .filter(|text| !text.trim().is_empty())
+        })
+    })
+}
+
+/// Column titles, from the columns themselves or the header buttons
+fn column_titles(outline: &AxElement) -> Vec<String> {
+    let titles: Vec<String> = outline
+        .elements("AXColumns")
+        .iter()
+        .map(|column| column.string("AXTitle").unwrap_or_default())
+        .collect();
+    if titles.iter().any(|title| !title.is_empty()) {
+        return titles;
+    }
+    outline
+        .element("AXHeader")
+        .map(|header| {
+            header
+                .children()
+                .iter()
+                .map(|button| cell_text(button).unwrap_or_default())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn snapshot(outline: &AxElement) -> OutlineSnapshot {
+    OutlineSnapshot {
+        columns: column_titles(outline),
+        selected_rows: outline
+            .elements("AXSelectedRows")
+            .iter()
+            .map(|row| {
+                row.children()
+                    .iter()
+                    .map(|cell| cell_text(cell).unwrap_or_default())
+                    .collect()
+            })
+            .collect(),
+    }
+}
+
+/// Outlines and tables in the focused window
+fn ax_outlines(ax_app: &AxElement) -> Vec<OutlineSnapshot> {
+    let Some(window) = ax_app.element("AXFocusedWindow") else {
+        return Vec::new();
+    };
+    let mut outlines = Vec::new();
+    window.find_descendant(OUTLINE_SEARCH_DEPTH, OUTLINE_SEARCH_NODES, |element| {
+        if matches!(
+            element.string("AXRole").as_deref(),
+            Some("AXOutline" | "AXTable")
+        ) {
+            outlines.push(snapshot(element));
+        }
+        false
+    });
+    outlines
+}
+
+/// Fills `current_reference` and `reference_collection` for reference
+/// manager windows
+pub struct ReferenceManagerExtractor;
+
+impl ReferenceManagerExtractor {
+    pub fn applies_to(bundle_id: &str) -> bool {
+        ReferenceManagerApp::for_bundle_id(bundle_id).is_some()
+    }
+
+    /// Selected rows are read subject to the attribute allowlist
+    pub fn apply(
+        &self,
+        ax_app: &AxElement,
+        context: &mut AccessibilityContext,
+        allowed: &dyn Fn(&str) -> bool,
+    ) {
+        if !Self::applies_to(&context.app_info.bundle_id) || !allowed("AXSelectedRows") {
+            return;
+        }
+        let (reference, collection) = parse_outlines(&ax_outlines(ax_app));
+        context.current_reference = reference;
+        context.reference_collection = collection;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outline(columns: &[&str], selected: &[&[&str]]) -> OutlineSnapshot {
+        OutlineSnapshot {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            selected_rows: selected
+                .iter()
+                .map(|row| row.iter().map(|c| c.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_zotero_selection_and_collection() {
+        let outlines = [
+            outline(&[""], &[&["Thesis"]]),
+            outline(
+                &["Title", "Creator", "Date", "Item Type"],
+                &[
+                    &[
+                        "Attention Is All You Need",
+                        "Vaswani et al.",
+                        "2017-06-12",
+                        "Preprint",
+                    ],
+                    &["Deep Residual Learning", "He and Zhang", "2016", "Paper"],
+                ],
+            ),
+        ];
+        let (reference, collection) = parse_outlines(&outlines);
+        assert_eq!(
+            reference,
+            Some(CurrentReference {
+                title: "Attention Is All You Need".to_string(),
+                authors: vec!["Vaswani".to_string()],
+                year: Some("2017".to_string()),
+            })
+        );
+        assert_eq!(collection.as_deref(), Some("Thesis"));
+
+        // Browsing a collection with nothing selected in it
+        let (reference, collection) = parse_outlines(&[
+            outline(&[], &[&["My Library"]]),
+            outline(&["Title", "Creator"], &[]),
+        ]);
+        assert_eq!(reference, None);
+        assert_eq!(collection.as_deref(), Some("My Library"));
+    }
+
+    #[test]
+    fn test_mendeley_and_papers_columns() {
+        let mendeley = outline(
+            &["Authors", "Title", "Year", "Source"],
+            &[&["Smith J., Jones K.", "On Graphs", "2021", "Nature"]],
+        );
+        let reference = parse_selected_item(&mendeley).unwrap();
+        assert_eq!(reference.title, "On Graphs");
+        assert_eq!(reference.authors, vec!["Smith J.", "Jones K."]);
+        assert_eq!(reference.year.as_deref(), Some("2021"));
+
+        let papers = outline(
+            &["Title", "Authors", "Journal", "Published"],
+            &[&[
+                "Protein Folding",
+                "Lee, A.; Kim, B.",
+                "Science",
+                "March 2019",
+            ]],
+        );
+        let reference = parse_selected_item(&papers).unwrap();
+        assert_eq!(reference.authors, vec!["Lee, A.", "Kim, B."]);
+        assert_eq!(reference.year.as_deref(), Some("2019"));
+
+        // A row without a title is not a reference
+        assert_eq!(
+            parse_selected_item(&outline(&["Title", "Creator"], &[&["", "Smith"]])),
+            None
+        );
+        assert_eq!(parse_authors("Curie & Curie"), vec!["Curie", "Curie"]);
+        assert_eq!(
+            ReferenceManagerApp::for_bundle_id("org.zotero.zotero"),
+            Some(ReferenceManagerApp::Zotero)
+        );
+    }
+}
@@ -0,0 +1,295 @@
+// src/core/running_apps.rs
+//! Snapshot of running applications
+//!
+//! Backs the `list-apps` command, which helps users find the bundle ids to put
+//! in `--filter` and the config file. Only NSWorkspace is consulted, so this
+//! works without accessibility permission.
+
+use objc2_app_kit::{NSApplicationActivationPolicy, NSRunningApplication, NSWorkspace};
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_lifecycle::app_info_from_running_app;
+use crate::core::app_switcher_types::AppInfo;
+
+/// A running application as shown by `list-apps`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunningAppEntry {
+    pub name: String,
+    pub bundle_id: String,
+    pub pid: i32,
+    pub category: String,
+}
+
+/// Apps whose bundle id does not name them
+const CATEGORY_BUNDLE_IDS: &[(&str, &str)] = &[
+    ("com.microsoft.edgemac", "browser"),
+    ("com.todesktop.230313mzl4w4u92", "ide"),
+    ("com.sublimetext.4", "ide"),
+    ("com.googlecode.iterm2", "ide"),
+    ("com.tinyspeck.slackmacgap", "communication"),
+    ("com.apple.MobileSMS", "communication"),
+];
+
+/// Whole words, compared case-insensitively, that mark each category
+const CATEGORY_WORDS: &[(&str, &[&str])] = &[
+    (
+        "browser",
+        &[
+            "safari", "chrome", "firefox", "edge", "brave", "opera", "vivaldi", "browser",
+        ],
+    ),
+    (
+        "ide",
+        &[
+            "vscode",
+            "cursor",
+            "xcode",
+            "jetbrains",
+            "intellij",
+            "coteditor",
+            "sublime",
+            "sublimetext",
+            "zed",
+            "terminal",
+            "iterm",
+        ],
+    ),
+    (
+        "communication",
+        &[
+            "slack", "mail", "messages", "teams", "zoom", "discord", "telegram",
+        ],
+    ),
+    (
+        "productivity",
+        &[
+            "pages",
+            "numbers",
+            "keynote",
+            "word",
+            "excel",
+            "powerpoint",
+            "notion",
+            "obsidian",
+            "preview",
+            "notes",
+        ],
+    ),
+];
+
+/// Infer a coarse category from the bundle id
+///
+/// Categories line up with the values accepted by `--filter`. Known bundle ids
+/// are looked up first; otherwise the id (or executable name) is split into
+/// words, so `word` matches `com.microsoft.Word` but not 1Password. A version
+/// number at the end of a word is ignored, so `teams` matches
+/// `com.microsoft.teams2`.
+pub fn infer_category(bundle_id: &str) -> &'static str {
+    if let Some((_, category)) = CATEGORY_BUNDLE_IDS.iter().find(|(id, _)| *id == bundle_id) {
+        return category;
+    }
+    let words: Vec<String> = bundle_id
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|word| word.trim_end_matches(|c: char| c.is_ascii_digit()))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    CATEGORY_WORDS
+        .iter()
+        .find(|(_, needles)| words.iter().any(|word| needles.contains(&word.as_str())))
+        .map(|(category, _)| *category)
+        .unwrap_or("other")
+}
+
+/// Category of an app, by bundle id or for apps without one by executable name
+pub fn infer_app_category(app: &AppInfo) -> &'static str {
+    infer_category(app.match_key())
+}
+
+/// A `list-apps` row, classified the same way as switch events
+fn entry_for(app: &NSRunningApplication) -> RunningAppEntry {
+    let info = app_info_from_running_app(app);
+    RunningAppEntry {
+        category: infer_app_category(&info).to_string(),
+        name: info.name,
+        bundle_id: info.bundle_id,
+        pid: info.pid,
+    }
+}
+
+/// Enumerate running applications, sorted by name
+///
+/// By default only regular (Dock-visible) apps are returned, since those are
+/// the ones that produce switch events; `include_background` adds accessory
+/// and agent processes.
+pub fn list_running_apps(include_background: bool) -> Vec<RunningAppEntry> {
+    let mut apps = running_apps_with(|policy| {
+        include_background || policy == NSApplicationActivationPolicy::Regular
+    });
+    apps.sort_by_key(|a| a.name.to_lowercase());
+    apps
+}
+
+/// Menu-bar-only apps: accessory processes with no Dock icon, which show
+/// their UI as a menu extra and open menus without becoming frontmost
+pub fn menu_bar_apps() -> Vec<RunningAppEntry> {
+    running_apps_with(|policy| policy == NSApplicationActivationPolicy::Accessory)
+}
+
+/// Running apps whose activation policy passes `keep`, in workspace order
+fn running_apps_with(keep: impl Fn(NSApplicationActivationPolicy) -> bool) -> Vec<RunningAppEntry> {
+    let mut apps = Vec::new();
+    unsafe {
+        let workspace = NSWorkspace::sharedWorkspace();
+        for app in workspace.runningApplications().iter() {
+            if keep(app.activationPolicy()) {
+                apps.push(entry_for(&app));
+            }
+        }
+    }
+    apps
+}
+
+/// The app currently in the foreground
+pub fn frontmost_app() -> Option<RunningAppEntry> {
+    unsafe {
+        let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+        Some(entry_for(&app))
+    }
+}
+
+/// Render apps as an aligned text table
+pub fn format_app_table(apps: &[RunningAppEntry]) -> String {
+    let name_width = apps
+        .iter()
+        .map(|a| a.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let bundle_width = apps
+        .iter()
+        .map(|a| a.bundle_id.len())
+        .max()
+        .unwrap_or(0)
+        .max(9);
+
+    let mut out = format!(
+        "{:<name_width$}  {:<bundle_width$}  {:>7}  {}\n",
+        "NAME", "BUNDLE ID", "PID", "CATEGORY"
+    );
+    for app in apps {
+        out.push_str(&format!(
+            "{:<name_width$}  {:<bundle_width$}  {:>7}  {}\n",
+            app.name, app.bundle_id, app.pid, app.category
+        ));
+    }
+    out
+}
+
+/// Render apps as a JSON array
+pub fn format_app_json(apps: &[RunningAppEntry]) -> String {
+    serde_json::to_string_pretty(apps).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<RunningAppEntry> {
+        [
+            ("Safari", "com.apple.Safari", 412),
+            ("Slack", "com.tinyspeck.slackmacgap", 988),
+        ]
+        .iter()
+        .map(|(name, bundle_id, pid)| RunningAppEntry {
+            name: name.to_string(),
+            bundle_id: bundle_id.to_string(),
+            pid: *pid,
+            category: infer_category(bundle_id).to_string(),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_format_app_table() {
+        let table = format_app_table(&sample());
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "NAME    BUNDLE ID                      PID  CATEGORY"
+        );
+        assert_eq!(
+            lines[1],
+            "Safari  com.apple.Safari               412  browser"
+        );
+        assert_eq!(
+            lines[2],
+            "Slack   com.tinyspeck.slackmacgap      988  communication"
+        );
+    }
+
+    #[test]
+    fn test_format_app_json_round_trips() {
+        let apps = sample();
+        let parsed: Vec<RunningAppEntry> = serde_json::from_str(&format_app_json(&apps)).unwrap();
+        assert_eq!(parsed, apps);
+        assert!(format_app_json(&apps).contains("\"bundle_id\": \"com.apple.Safari\""));
+    }
+
+    #[test]
+    fn test_infer_category_matches_whole_words() {
+        let cases = [
+            ("com.microsoft.Word", "productivity"),
+            ("com.1password.1password", "other"),
+            ("com.agilebits.onepassword7", "other"),
+            ("com.microsoft.edgemac", "browser"),
+            ("com.example.knowledge-base", "other"),
+            ("com.apple.mail", "communication"),
+            ("com.example.mailchimp", "other"),
+            ("com.todesktop.230313mzl4w4u92", "ide"),
+            ("com.apple.dt.Xcode", "ide"),
+            ("Google Chrome", "browser"),
+        ];
+        for (bundle_id, category) in cases {
+            assert_eq!(infer_category(bundle_id), category, "{}", bundle_id);
+        }
+    }
+
+    #[test]
+    fn test_infer_category_ignores_version_suffixes() {
+        let cases = [
+            ("com.microsoft.teams2", "communication"),
+            ("com.sublimetext.3", "ide"),
+            ("com.sublimetext.4", "ide"),
+            ("com.googlecode.iterm2", "ide"),
+            ("us.zoom.xos", "communication"),
+        ];
+        for (bundle_id, category) in cases {
+            assert_eq!(infer_category(bundle_id), category, "{}", bundle_id);
+        }
+    }
+
+    #[test]
+    fn test_app_without_bundle_id_is_classified_by_executable() {
+        let mut chrome = AppInfo::new("Chrome Helper".to_string(), String::new(), 7);
+        chrome.path =
+            Some("/Applications/Google Chrome.app/Contents/MacOS/Google Chrome".to_string());
+        assert!(!chrome.has_bundle_id());
+        assert_eq!(chrome.match_key(), "Google Chrome");
+        assert_eq!(infer_app_category(&chrome), "browser");
+
+        let mut iterm = AppInfo::new("iTerm2".to_string(), " ".to_string(), 8);
+        iterm.path = Some("/Applications/iTerm.app/".to_string());
+        assert_eq!(iterm.match_key(), "iTerm");
+        assert_eq!(infer_app_category(&iterm), "ide");
+
+        // Without a path the display name is all there is
+        let tool = AppInfo::new("Slack".to_string(), String::new(), 9);
+        assert_eq!(infer_app_category(&tool), "communication");
+
+        let safari = AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 1);
+        assert!(safari.has_bundle_id());
+        assert_eq!(safari.match_key(), "com.apple.Safari");
+    }
+}
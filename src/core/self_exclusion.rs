@@ -0,0 +1,146 @@
+// src/core/self_exclusion.rs
+//! Keep the tracker out of its own data
+//!
+//! When the tracker (or the screenshot tooling) comes to the front, or the
+//! user glances at the Terminal it runs in, those switches say nothing about
+//! the work being tracked. Switches to excluded processes are dropped before
+//! any listener sees them. Only the tracker's own process is excluded by
+//! default; the launching terminal/IDE and extra bundle ids are opt-in.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid as SysPid, ProcessesToUpdate, System};
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+
+/// Settings for the `[exclusion]` config section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExclusionConfig {
+    /// Drop switches to the tracker's own process
+    pub exclude_self: bool,
+
+    /// Also drop switches to the app the tracker was launched from
+    /// (every ancestor process, e.g. Terminal or the IDE's terminal)
+    pub exclude_launcher: bool,
+
+    /// Additional bundle ids to drop, e.g. the chrome_ui_segment tool;
+    /// processes without one are named by executable
+    pub bundle_ids: Vec<String>,
+}
+
+impl Default for ExclusionConfig {
+    fn default() -> Self {
+        Self {
+            exclude_self: true,
+            exclude_launcher: false,
+            bundle_ids: Vec::new(),
+        }
+    }
+}
+
+/// Resolved set of processes whose switches are dropped
+#[derive(Debug, Clone, Default)]
+pub struct SelfExclusion {
+    pids: HashSet<i32>,
+    bundle_ids: HashSet<String>,
+}
+
+impl SelfExclusion {
+    pub fn new(
+        pids: impl IntoIterator<Item = i32>,
+        bundle_ids: impl IntoIterator<Item = String>,
+    ) -> Self {
+        Self {
+            pids: pids.into_iter().collect(),
+            bundle_ids: bundle_ids.into_iter().collect(),
+        }
+    }
+
+    /// Resolve the configuration against the running process tree
+    pub fn from_config(config: &ExclusionConfig) -> Self {
+        let own_pid = std::process::id() as i32;
+        let mut pids = Vec::new();
+        if config.exclude_self {
+            pids.push(own_pid);
+        }
+        if config.exclude_launcher {
+            pids.extend(ancestor_pids(own_pid));
+        }
+        Self::new(pids, config.bundle_ids.iter().cloned())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pids.is_empty() && self.bundle_ids.is_empty()
+    }
+
+    /// Whether this event should be dropped
+    pub fn excludes(&self, event: &AppSwitchEvent) -> bool {
+        self.pids.contains(&event.app_info.pid)
+            || self.bundle_ids.contains(event.app_info.match_key())
+    }
+}
+
+/// Parent, grandparent, ... of `pid`, stopping before launchd
+fn ancestor_pids(pid: i32) -> Vec<i32> {
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+
+    let mut ancestors = Vec::new();
+    let mut current = SysPid::from(pid as usize);
+    while let Some(parent) = system.process(current).and_then(|p| p.parent()) {
+        let parent_pid = parent.as_u32() as i32;
+        if parent_pid <= 1 || ancestors.contains(&parent_pid) {
+            break;
+        }
+        ancestors.push(parent_pid);
+        current = parent;
+    }
+    ancestors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType};
+
+    fn event(pid: i32, bundle_id: &str) -> AppSwitchEvent {
+        AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("App".to_string(), bundle_id.to_string(), pid),
+        )
+    }
+
+    #[test]
+    fn test_own_pid_dropped() {
+        let exclusion = SelfExclusion::from_config(&ExclusionConfig::default());
+        let own_pid = std::process::id() as i32;
+
+        assert!(exclusion.excludes(&event(own_pid, "")));
+        assert!(!exclusion.excludes(&event(own_pid + 1, "com.apple.Safari")));
+
+        let disabled = SelfExclusion::from_config(&ExclusionConfig {
+            exclude_self: false,
+            ..ExclusionConfig::default()
+        });
+        assert!(!disabled.excludes(&event(own_pid, "")));
+    }
+
+    #[test]
+    fn test_extra_bundle_ids_dropped() {
+        let exclusion = SelfExclusion::new([], ["dev.tracker.chrome-ui-segment".to_string()]);
+        assert!(exclusion.excludes(&event(10, "dev.tracker.chrome-ui-segment")));
+        assert!(!exclusion.excludes(&event(10, "com.apple.Terminal")));
+    }
+
+    #[test]
+    fn test_apps_without_bundle_id_dropped_by_executable() {
+        let exclusion = SelfExclusion::new([], ["chrome-ui-segment".to_string()]);
+        let mut tool = event(10, "");
+        tool.app_info.path = Some("/usr/local/bin/chrome-ui-segment".to_string());
+        assert!(exclusion.excludes(&tool));
+        // Other bundle-less processes are not caught by the empty id
+        assert!(!exclusion.excludes(&event(11, "")));
+    }
+}
@@ -0,0 +1,257 @@
+// src/core/session.rs
+//! Session manifest and session ids
+//!
+//! Every run of the tracker is a session with a random UUID. The manifest,
+//! written once at startup, records what produced the events: tracker
+//! version, start time, macOS version, host, enabled features, the display
+//! layout and whether the system was in light or dark mode. Every event carries the session id, so event logs split across
+//! files can be joined back to their manifest. With power capture enabled it
+//! also records the power source and battery level at startup.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use objc2_core_foundation::CGRect;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::appearance::{current_appearance, Appearance};
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+use crate::core::power_state::PowerState;
+
+extern "C" {
+    fn CGMainDisplayID() -> u32;
+    fn CGDisplayBounds(display: u32) -> CGRect;
+    fn CGGetActiveDisplayList(
+        maxDisplays: u32,
+        activeDisplays: *mut u32,
+        displayCount: *mut u32,
+    ) -> i32;
+}
+
+/// Random version 4 UUID identifying one tracker run
+pub fn new_session_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// One active display, in global screen coordinates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayInfo {
+    pub id: u32,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub main: bool,
+}
+
+/// Active displays, main display included
+pub fn active_displays() -> Vec<DisplayInfo> {
+    unsafe {
+        let max_displays = 32;
+        let mut display_count: u32 = 0;
+        let mut display_ids = vec![0u32; max_displays];
+        if CGGetActiveDisplayList(
+            max_displays as u32,
+            display_ids.as_mut_ptr(),
+            &mut display_count,
+        ) != 0
+        {
+            return Vec::new();
+        }
+
+        let main_id = CGMainDisplayID();
+        display_ids
+            .into_iter()
+            .take(display_count as usize)
+            .map(|id| {
+                let bounds = CGDisplayBounds(id);
+                DisplayInfo {
+                    id,
+                    x: bounds.origin.x,
+                    y: bounds.origin.y,
+                    width: bounds.size.width,
+                    height: bounds.size.height,
+                    main: id == main_id,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Hex SHA-256 of a hostname, for manifests that must not name the machine
+pub fn hash_hostname(hostname: &str) -> String {
+    Sha256::digest(hostname.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Description of one tracker session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionManifest {
+    pub session_id: String,
+    pub tracker_version: String,
+    pub started_at: DateTime<Utc>,
+    pub os_version: Option<String>,
+    /// Plain or SHA-256 hex, see `hostname_hashed`
+    pub hostname: Option<String>,
+    pub hostname_hashed: bool,
+    /// Names of the enabled command line features
+    pub features: Vec<String>,
+    pub displays: Vec<DisplayInfo>,
+    /// Light or dark mode at startup
+    pub system_appearance: Option<Appearance>,
+    /// Power source and battery at startup; only with power capture enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub power: Option<PowerState>,
+}
+
+impl SessionManifest {
+    /// Manifest from explicit environment values
+    pub fn new(
+        session_id: &str,
+        os_version: Option<String>,
+        hostname: Option<String>,
+        hash_host: bool,
+        features: Vec<String>,
+        displays: Vec<DisplayInfo>,
+        system_appearance: Option<Appearance>,
+    ) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            tracker_version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: Utc::now(),
+            os_version,
+            hostname: if hash_host {
+                hostname.as_deref().map(hash_hostname)
+            } else {
+                hostname
+            },
+            hostname_hashed: hash_host,
+            features,
+            displays,
+            system_appearance,
+            power: None,
+        }
+    }
+
+    /// Record the power state at startup
+    pub fn with_power(mut self, power: Option<PowerState>) -> Self {
+        self.power = power;
+        self
+    }
+
+    /// Manifest describing the current machine
+    pub fn collect(session_id: &str, features: Vec<String>, hash_host: bool) -> Self {
+        Self::new(
+            session_id,
+            sysinfo::System::long_os_version(),
+            sysinfo::System::host_name(),
+            hash_host,
+            features,
+            active_displays(),
+            current_appearance(),
+        )
+    }
+
+    pub fn write_to(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+    }
+}
+
+/// Stamps the session id on every event
+pub struct SessionTagger {
+    session_id: String,
+}
+
+impl SessionTagger {
+    pub fn new(session_id: &str) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+        }
+    }
+}
+
+impl EnrichmentStage for SessionTagger {
+    fn name(&self) -> &str {
+        "session"
+    }
+
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Annotate
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        event.session_id = Some(self.session_id.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType};
+
+    #[test]
+    fn test_session_ids_are_uuid_v4() {
+        let id = new_session_id();
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(id, new_session_id());
+        assert_eq!(Uuid::parse_str(&id).unwrap().get_version_num(), 4);
+    }
+
+    #[test]
+    fn test_manifest_keys_and_event_session_id() {
+        let session_id = new_session_id();
+        let manifest = SessionManifest::new(
+            &session_id,
+            Some("macOS 15.1 Sequoia".to_string()),
+            Some("studio.local".to_string()),
+            true,
+            vec!["enhanced".to_string(), "focus_blocks".to_string()],
+            vec![DisplayInfo {
+                id: 1,
+                x: 0.0,
+                y: 0.0,
+                width: 1512.0,
+                height: 982.0,
+                main: true,
+            }],
+            Some(Appearance::Dark),
+        );
+
+        let json = serde_json::to_value(&manifest).unwrap();
+        for key in [
+            "session_id",
+            "tracker_version",
+            "started_at",
+            "os_version",
+            "hostname",
+            "hostname_hashed",
+            "features",
+            "displays",
+            "system_appearance",
+        ] {
+            assert!(json.get(key).is_some(), "manifest is missing {}", key);
+        }
+        assert_eq!(json["hostname"], hash_hostname("studio.local"));
+        assert_eq!(json["displays"][0]["main"], true);
+        assert_eq!(json["system_appearance"], "dark");
+
+        let mut tagger = SessionTagger::new(&session_id);
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 42),
+        );
+        tagger.enrich(&mut event);
+        assert_eq!(
+            event.session_id.as_deref(),
+            Some(json["session_id"].as_str().unwrap())
+        );
+    }
+}
@@ -0,0 +1,198 @@
+// src/core/session_deadline.rs
+//! Automatic stop after a maximum monitoring duration
+//!
+//! Bounded research sessions end with `--max-duration`. A one-shot timer on
+//! the main CFRunLoop fires at the deadline and runs the shutdown action,
+//! which stops the loop so the tracker takes the same graceful path as any
+//! other stop: sources are stopped, listeners get `on_monitoring_stopped`
+//! and write their summaries, and buffered sinks flush. Unlike an external
+//! `kill`, nothing buffered is lost.
+
+use std::ffi::c_void;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop, CFRunLoopTimer};
+use core_foundation_sys::date::CFAbsoluteTimeGetCurrent;
+use core_foundation_sys::runloop::{
+    CFRunLoopTimerContext, CFRunLoopTimerRef, CFRunLoopTimerSetNextFireDate,
+};
+
+/// Parse `90m`, `2h`, `1h30m`, `45s` or a plain number of seconds
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let invalid = || {
+        format!(
+            "Invalid duration '{}' (expected e.g. 90m, 2h, 1h30m or 45s)",
+            s
+        )
+    };
+    let total = match s.parse::<u64>() {
+        Ok(secs) => secs,
+        Err(_) => {
+            let mut total: u64 = 0;
+            let mut digits = String::new();
+            for c in s.chars() {
+                if c.is_ascii_digit() {
+                    digits.push(c);
+                    continue;
+                }
+                let unit = match c {
+                    'h' => 3600,
+                    'm' => 60,
+                    's' => 1,
+                    _ => return Err(invalid()),
+                };
+                let n: u64 = digits.parse().map_err(|_| invalid())?;
+                total = n
+                    .checked_mul(unit)
+                    .and_then(|secs| total.checked_add(secs))
+                    .ok_or_else(invalid)?;
+                digits.clear();
+            }
+            if !digits.is_empty() {
+                return Err(invalid());
+            }
+            total
+        }
+    };
+    if total == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// Runs a shutdown action once the session has lasted its maximum duration
+pub struct SessionDeadline {
+    deadline: Instant,
+    on_expire: Mutex<Option<Box<dyn FnOnce() + Send>>>,
+}
+
+impl SessionDeadline {
+    pub fn new(
+        start: Instant,
+        max_duration: Duration,
+        on_expire: impl FnOnce() + Send + 'static,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            deadline: start + max_duration,
+            on_expire: Mutex::new(Some(Box::new(on_expire))),
+        })
+    }
+
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    pub fn remaining_at(&self, now: Instant) -> Duration {
+        self.deadline.saturating_duration_since(now)
+    }
+
+    /// Run the shutdown action if the deadline has passed; `true` only for
+    /// the call that ran it
+    pub fn check_at(&self, now: Instant) -> bool {
+        if now < self.deadline {
+            return false;
+        }
+        let action = self.on_expire.lock().unwrap().take();
+        match action {
+            Some(action) => {
+                action();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Add a one-shot timer for the deadline to the main run loop
+    ///
+    /// The action runs on the main thread, where it may stop the loop.
+    pub fn schedule_on_main_run_loop(self: &Arc<Self>) -> CFRunLoopTimer {
+        let mut context = CFRunLoopTimerContext {
+            version: 0,
+            // Released by the timer when it is deallocated
+            info: Arc::into_raw(Arc::clone(self)) as *mut c_void,
+            retain: None,
+            release: Some(release_deadline),
+            copyDescription: None,
+        };
+        let fire_at =
+            unsafe { CFAbsoluteTimeGetCurrent() } + self.remaining_at(Instant::now()).as_secs_f64();
+        let timer = CFRunLoopTimer::new(fire_at, 0.0, 0, 0, fire_deadline, &mut context);
+        CFRunLoop::get_main().add_timer(&timer, unsafe { kCFRunLoopCommonModes });
+        timer
+    }
+}
+
+extern "C" fn fire_deadline(timer: CFRunLoopTimerRef, info: *mut c_void) {
+    let deadline = unsafe { &*(info as *const SessionDeadline) };
+    let now = Instant::now();
+    if !deadline.check_at(now) && deadline.remaining_at(now) > Duration::ZERO {
+        // The CF clock ran ahead of the monotonic one; try again
+        let fire_at =
+            unsafe { CFAbsoluteTimeGetCurrent() } + deadline.remaining_at(now).as_secs_f64();
+        unsafe { CFRunLoopTimerSetNextFireDate(timer, fire_at) };
+    }
+}
+
+extern "C" fn release_deadline(info: *const c_void) {
+    drop(unsafe { Arc::from_raw(info as *const SessionDeadline) });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher::{AppSwitchEvent, AppSwitchListener, AppSwitcher};
+
+    /// Stands in for the time tracker's summary on stop
+    struct SummaryWriter(Arc<Mutex<Vec<String>>>);
+
+    impl AppSwitchListener for SummaryWriter {
+        fn on_app_switch(&mut self, _event: &AppSwitchEvent) {}
+
+        fn on_monitoring_stopped(&mut self) {
+            self.0.lock().unwrap().push("summary".to_string());
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90m"), Ok(Duration::from_secs(90 * 60)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("1m30s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration(" 3600 "), Ok(Duration::from_secs(3600)));
+
+        for invalid in ["", "0", "0m", "h", "1d", "1h30", "-5m", "1.5h"] {
+            assert!(parse_duration(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_shutdown_runs_once_at_the_deadline() {
+        let summaries = Arc::new(Mutex::new(Vec::new()));
+        let switcher = Arc::new(Mutex::new(AppSwitcher::new()));
+        switcher
+            .lock()
+            .unwrap()
+            .add_listener(SummaryWriter(Arc::clone(&summaries)));
+
+        let start = Instant::now();
+        let shutdown = Arc::clone(&switcher);
+        let deadline = SessionDeadline::new(start, Duration::from_secs(30 * 60), move || {
+            shutdown.lock().unwrap().stop_monitoring();
+        });
+
+        let just_before = start + Duration::from_secs(30 * 60 - 1);
+        assert!(!deadline.check_at(just_before));
+        assert_eq!(deadline.remaining_at(just_before), Duration::from_secs(1));
+        assert!(summaries.lock().unwrap().is_empty());
+
+        assert!(deadline.check_at(start + Duration::from_secs(30 * 60)));
+        assert_eq!(*summaries.lock().unwrap(), vec!["summary"]);
+
+        // Later checks do not stop again
+        assert!(!deadline.check_at(start + Duration::from_secs(31 * 60)));
+        assert_eq!(summaries.lock().unwrap().len(), 1);
+    }
+}
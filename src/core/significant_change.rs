@@ -0,0 +1,219 @@
+// src/core/significant_change.rs
+//! Emitting resamples only when the context changed
+//!
+//! Scrolling asks the switcher to resample the frontmost app several times a
+//! second, and each resample re-extracts the same app, page and document.
+//! Under the significant-change policy a foreground event for the app
+//! already in focus is compared with the last one emitted through a
+//! fingerprint of its URL, title, file and extractor output (which carries
+//! the selection), and only emitted when the fingerprint differs; other
+//! resamples are absorbed. A switch to another app is always emitted, and so
+//! is the app's next foreground event once it was hidden, sent to the
+//! background, quit or relaunched or the system slept. The always policy
+//! emits every resample, as before.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchType};
+
+/// Which foreground events for the app in focus are emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResamplePolicy {
+    /// Every event, whether or not anything changed
+    Always,
+    /// Only events whose context fingerprint changed
+    SignificantChange,
+}
+
+impl fmt::Display for ResamplePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResamplePolicy::Always => write!(f, "always"),
+            ResamplePolicy::SignificantChange => write!(f, "significant"),
+        }
+    }
+}
+
+impl FromStr for ResamplePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "always" => Ok(ResamplePolicy::Always),
+            "significant" => Ok(ResamplePolicy::SignificantChange),
+            other => Err(format!(
+                "Invalid resample policy '{}' (expected always or significant)",
+                other
+            )),
+        }
+    }
+}
+
+/// A hash of the parts of an event's context worth emitting again for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextFingerprint(u64);
+
+impl ContextFingerprint {
+    pub fn of(event: &AppSwitchEvent) -> Self {
+        let ws = event.workspace.as_ref();
+        let enh = event.enhanced.as_ref();
+        let mut hasher = DefaultHasher::new();
+        enh.and_then(|e| e.url.as_deref())
+            .or_else(|| ws.and_then(|w| w.primary_url.as_deref()))
+            .hash(&mut hasher);
+        ws.and_then(|w| w.focused_title.as_deref())
+            .or_else(|| enh.and_then(|e| e.front_window_title.as_deref()))
+            .hash(&mut hasher);
+        enh.and_then(|e| e.tab_title.as_deref()).hash(&mut hasher);
+        ws.and_then(|w| w.active_file_paths.first())
+            .hash(&mut hasher);
+        if let Some(enh) = enh {
+            let mut extracted: Vec<_> = enh.extracted_context.iter().collect();
+            extracted.sort_by(|a, b| a.0.cmp(b.0));
+            for (key, value) in extracted {
+                key.hash(&mut hasher);
+                format!("{:?}", value).hash(&mut hasher);
+            }
+        }
+        Self(hasher.finish())
+    }
+}
+
+/// Drops foreground events for the app in focus whose context did not change
+pub struct SignificantChangeFilter {
+    policy: ResamplePolicy,
+    /// Match key and fingerprint of the latest admitted foreground event
+    last: Option<(String, ContextFingerprint)>,
+}
+
+impl Default for SignificantChangeFilter {
+    fn default() -> Self {
+        Self::new(ResamplePolicy::Always)
+    }
+}
+
+impl SignificantChangeFilter {
+    pub fn new(policy: ResamplePolicy) -> Self {
+        Self { policy, last: None }
+    }
+
+    pub fn policy(&self) -> ResamplePolicy {
+        self.policy
+    }
+
+    /// Whether `event` is emitted; an unchanged resample is rejected
+    pub fn admit(&mut self, event: &AppSwitchEvent) -> bool {
+        if self.policy == ResamplePolicy::Always || event.event_type == AppSwitchType::Heartbeat {
+            return true;
+        }
+        if !event.event_type.is_foreground() {
+            // The app left the foreground, so its return is not a resample
+            let left = self
+                .last
+                .as_ref()
+                .is_some_and(|(key, _)| key == event.app_info.match_key());
+            if left {
+                self.last = None;
+            }
+            return true;
+        }
+        let current = (
+            event.app_info.match_key().to_string(),
+            ContextFingerprint::of(event),
+        );
+        if self.last.as_ref() == Some(&current) {
+            return false;
+        }
+        self.last = Some(current);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, EnhancedSummary};
+    use crate::extractors::ContextValue;
+
+    fn resample(bundle_id: &str, url: &str, selection: Option<&str>) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new(bundle_id.to_string(), bundle_id.to_string(), 42),
+        );
+        let mut enhanced = EnhancedSummary {
+            url: Some(url.to_string()),
+            front_window_title: Some("Docs".to_string()),
+            ..EnhancedSummary::default()
+        };
+        if let Some(selection) = selection {
+            enhanced.extracted_context.insert(
+                "selected_text".to_string(),
+                ContextValue::Text(selection.to_string()),
+            );
+        }
+        event.enhanced = Some(enhanced);
+        event
+    }
+
+    #[test]
+    fn test_identical_resamples_are_not_emitted() {
+        let mut filter = SignificantChangeFilter::new(ResamplePolicy::SignificantChange);
+        let page = "https://docs.rs/serde";
+        assert!(filter.admit(&resample("com.apple.Safari", page, None)));
+        for _ in 0..5 {
+            assert!(!filter.admit(&resample("com.apple.Safari", page, None)));
+        }
+        // A new selection or page is emitted, once
+        assert!(filter.admit(&resample("com.apple.Safari", page, Some("derive"))));
+        assert!(!filter.admit(&resample("com.apple.Safari", page, Some("derive"))));
+        assert!(filter.admit(&resample("com.apple.Safari", "https://docs.rs/tokio", None)));
+        // Another app, or coming back, is a switch whatever the context
+        assert!(filter.admit(&resample("com.google.Chrome", page, None)));
+        assert!(filter.admit(&resample("com.apple.Safari", "https://docs.rs/tokio", None)));
+        // Other event types pass
+        let mut hide = resample("com.apple.Safari", "https://docs.rs/tokio", None);
+        hide.event_type = AppSwitchType::Hide;
+        assert!(filter.admit(&hide));
+
+        let mut always = SignificantChangeFilter::default();
+        assert!(always.admit(&resample("com.apple.Safari", page, None)));
+        assert!(always.admit(&resample("com.apple.Safari", page, None)));
+
+        assert_eq!("significant".parse(), Ok(ResamplePolicy::SignificantChange));
+        assert!("sometimes".parse::<ResamplePolicy>().is_err());
+    }
+
+    #[test]
+    fn test_return_after_leaving_is_emitted() {
+        let mut filter = SignificantChangeFilter::new(ResamplePolicy::SignificantChange);
+        let page = "https://docs.rs/serde";
+        for left in [
+            AppSwitchType::Hide,
+            AppSwitchType::Background,
+            AppSwitchType::Terminate,
+            AppSwitchType::Launch,
+        ] {
+            assert!(filter.admit(&resample("com.apple.Safari", page, None)));
+            assert!(!filter.admit(&resample("com.apple.Safari", page, None)));
+            let mut event = resample("com.apple.Safari", page, None);
+            event.event_type = left;
+            assert!(filter.admit(&event));
+        }
+
+        // Another app leaving, or a heartbeat, does not reset the app in focus
+        assert!(filter.admit(&resample("com.apple.Safari", page, None)));
+        let mut other = resample("com.google.Chrome", page, None);
+        other.event_type = AppSwitchType::Hide;
+        assert!(filter.admit(&other));
+        let mut heartbeat = resample("com.apple.Safari", page, None);
+        heartbeat.event_type = AppSwitchType::Heartbeat;
+        assert!(filter.admit(&heartbeat));
+        assert!(!filter.admit(&resample("com.apple.Safari", page, None)));
+    }
+}
@@ -21,6 +21,8 @@ type CFTypeRef = *const c_void;
 #[derive(Debug, Clone)]
 pub struct DisplaySpaceInfo {
     pub display_uuid: String,
+    /// Numeric managed space id (`id64`), as used by window-to-space queries
+    pub current_space_id: Option<u64>,
     pub current_space_uuid: Option<String>,
     pub current_space_index: Option<u32>,
     pub current_space_type: Option<String>,
@@ -36,6 +38,14 @@ pub struct SpacesSnapshot {
 }
 
 impl SpacesSnapshot {
+    /// Ids of the space currently shown on each display
+    pub fn current_space_ids(&self) -> Vec<u64> {
+        self.displays
+            .iter()
+            .filter_map(|d| d.current_space_id)
+            .collect()
+    }
+
     /// Compute a human-friendly label for a display's current space.
     /// If `name` is present, use it. Otherwise, derive from type/index.
     pub fn label_for_display(&self, display_idx: usize) -> Option<String> {
@@ -64,13 +74,30 @@ impl SpacesSnapshot {
     }
 }
 
+/// Whether a window is on one of the spaces currently shown.
+/// `window_spaces` are the spaces the window belongs to (several for windows
+/// assigned to all desktops); `current_spaces` has one entry per display.
+/// Returns None when either side is unknown.
+pub fn window_on_active_space(window_spaces: &[u64], current_spaces: &[u64]) -> Option<bool> {
+    if window_spaces.is_empty() || current_spaces.is_empty() {
+        return None;
+    }
+    Some(window_spaces.iter().any(|s| current_spaces.contains(s)))
+}
+
 // Runtime-loaded SkyLight functions
 type CopyManagedDisplaySpacesFn = unsafe extern "C" fn(i32) -> CFArrayRef;
 type MainConnectionIDFn = unsafe extern "C" fn() -> i32;
+type CopySpacesForWindowsFn = unsafe extern "C" fn(i32, i32, CFArrayRef) -> CFArrayRef;
+
+/// Selector for `CopySpacesForWindows`: current, other and user spaces
+const SPACES_FOR_WINDOWS_ALL: i32 = 0x7;
 
 struct SkyLightFns {
     copy_managed_display_spaces: CopyManagedDisplaySpacesFn,
     main_connection_id: MainConnectionIDFn,
+    /// Optional: only needed for window-to-space lookups
+    copy_spaces_for_windows: Option<CopySpacesForWindowsFn>,
 }
 
 fn load_skylight() -> Option<SkyLightFns> {
@@ -112,12 +139,29 @@ fn load_skylight() -> Option<SkyLightFns> {
             }
         })?;
 
+        let sym_window_spaces = [
+            CString::new("CGSCopySpacesForWindows").unwrap(),
+            CString::new("SLSCopySpacesForWindows").unwrap(),
+        ]
+        .into_iter()
+        .find_map(|name| {
+            let p = libc::dlsym(handle, name.as_ptr());
+            if p.is_null() {
+                None
+            } else {
+                Some(p)
+            }
+        });
+
         let copy_managed_display_spaces: CopyManagedDisplaySpacesFn = transmute(sym_copy);
         let main_connection_id: MainConnectionIDFn = transmute(sym_conn);
+        let copy_spaces_for_windows = sym_window_spaces
+            .map(|p| transmute::<*mut c_void, CopySpacesForWindowsFn>(p));
 
         Some(SkyLightFns {
             copy_managed_display_spaces,
             main_connection_id,
+            copy_spaces_for_windows,
         })
     }
 }
@@ -157,6 +201,7 @@ pub fn query_spaces() -> Option<SpacesSnapshot> {
 
                 let (mut current_space_uuid, mut current_space_type, mut current_space_name) =
                     (None, None, None);
+                let mut current_space_id: Option<u64> = None;
                 let mut current_space_index: Option<u32> = None;
 
                 if let Some(cs) = &current_space_dict {
@@ -165,6 +210,13 @@ pub fn query_spaces() -> Option<SpacesSnapshot> {
                         .find(&CFString::from("uuid"))
                         .map(|s| unsafe { CFString::from_void(*s) }.to_string());
 
+                    // numeric id
+                    current_space_id = cs
+                        .find(CFString::from("id64"))
+                        .or_else(|| cs.find(CFString::from("ManagedSpaceID")))
+                        .and_then(|n| CFNumber::from_void(*n).to_i64())
+                        .map(|n| n as u64);
+
                     // name (if any)
                     current_space_name = cs
                         .find(&CFString::from("name"))
@@ -210,6 +262,7 @@ pub fn query_spaces() -> Option<SpacesSnapshot> {
 
                 displays_out.push(DisplaySpaceInfo {
                     display_uuid,
+                    current_space_id,
                     current_space_uuid,
                     current_space_index,
                     current_space_type,
@@ -225,3 +278,73 @@ pub fn query_spaces() -> Option<SpacesSnapshot> {
         })
     }
 }
+
+/// Ids of the spaces a window belongs to. Returns None if SkyLight or the
+/// lookup symbol is unavailable.
+pub fn spaces_for_window(window_id: u32) -> Option<Vec<u64>> {
+    let fns = load_skylight()?;
+    let copy_spaces_for_windows = fns.copy_spaces_for_windows?;
+    unsafe {
+        let conn = (fns.main_connection_id)();
+        let ids = CFArray::from_CFTypes(&[CFNumber::from(window_id as i64)]);
+        let arr = copy_spaces_for_windows(
+            conn,
+            SPACES_FOR_WINDOWS_ALL,
+            ids.as_concrete_TypeRef() as CFArrayRef,
+        );
+        if arr.is_null() {
+            return None;
+        }
+
+        let spaces: CFArray<CFNumber> = CFArray::wrap_under_create_rule(arr as *const _);
+        Some(
+            spaces
+                .iter()
+                .filter_map(|n| n.to_i64())
+                .map(|n| n as u64)
+                .collect(),
+        )
+    }
+}
+
+/// Whether `window_id` is on a space currently shown on some display
+pub fn is_window_on_active_space(window_id: u32) -> Option<bool> {
+    let snapshot = query_spaces()?;
+    let window_spaces = spaces_for_window(window_id)?;
+    window_on_active_space(&window_spaces, &snapshot.current_space_ids())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(space_id: Option<u64>) -> DisplaySpaceInfo {
+        DisplaySpaceInfo {
+            display_uuid: "display".to_string(),
+            current_space_id: space_id,
+            current_space_uuid: None,
+            current_space_index: None,
+            current_space_type: None,
+            current_space_name: None,
+        }
+    }
+
+    #[test]
+    fn test_window_on_active_space() {
+        let snapshot = SpacesSnapshot {
+            displays: vec![display(Some(3)), display(None), display(Some(9))],
+            visible_space_uuids: Vec::new(),
+            active_space_uuid: None,
+        };
+        let current = snapshot.current_space_ids();
+        assert_eq!(current, vec![3, 9]);
+
+        assert_eq!(window_on_active_space(&[3], &current), Some(true));
+        assert_eq!(window_on_active_space(&[9], &current), Some(true));
+        assert_eq!(window_on_active_space(&[4], &current), Some(false));
+        // Window assigned to all desktops
+        assert_eq!(window_on_active_space(&[1, 4, 9], &current), Some(true));
+        assert_eq!(window_on_active_space(&[], &current), None);
+        assert_eq!(window_on_active_space(&[3], &[]), None);
+    }
+}
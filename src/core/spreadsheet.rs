@@ -0,0 +1,284 @@
+// src/core/spreadsheet.rs
+//! Active sheet and selected range in Excel and Numbers
+//!
+//! Excel answers AppleScript with the sheet name and an absolute address
+//! (`Sheet1!$A$1:$B$3`). Numbers only names its active sheet through
+//! AppleScript; the selection comes from the focused table's
+//! `AXSelectedCells`, whose cells carry zero-based row and column index
+//! ranges that are folded into one bounding range. Google Sheets runs inside
+//! a browser and exposes neither.
+//!
+//! Ranges are written without `$` markers, prefixed by the sheet when it is
+//! known: `Sheet1!A1:B3`.
+
+use crate::core::accessibility::AccessibilityContext;
+use crate::core::ax_element::AxElement;
+use crate::core::browser_strategy::{parse_applescript_output, run_applescript};
+
+/// A supported spreadsheet app
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpreadsheetApp {
+    Excel,
+    Numbers,
+}
+
+impl SpreadsheetApp {
+    pub fn for_bundle_id(bundle_id: &str) -> Option<Self> {
+        match bundle_id {
+            "com.microsoft.Excel" => Some(Self::Excel),
+            "com.apple.iWork.Numbers" => Some(Self::Numbers),
+            _ => None,
+        }
+    }
+
+    /// AppleScript returning `<sheet>!<address>` (Excel) or the sheet name
+    pub fn selection_script(&self) -> &'static str {
+        match self {
+            Self::Excel => {
+                r#"tell application "Microsoft Excel" to return (name of active sheet) & "!" & (get address of selection)"#
+            }
+            Self::Numbers => {
+                r#"tell application "Numbers" to get name of active sheet of front document"#
+            }
+        }
+    }
+}
+
+/// Sheet and range of the current selection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetSelection {
+    pub sheet_name: Option<String>,
+    /// Cell or range without `$` markers, e.g. `A1:B3`
+    pub range: String,
+}
+
+impl SheetSelection {
+    /// `Sheet1!A1:B3`, or just the range without a sheet
+    pub fn qualified_range(&self) -> String {
+        match &self.sheet_name {
+            Some(sheet) => format!("{}!{}", sheet, self.range),
+            None => self.range.clone(),
+        }
+    }
+}
+
+/// Parse an Excel address: `$A$1:$B$3`, `Sheet1!$A$1`, the external form
+/// `[Book1.xlsx]Sheet1!$A$1:$B$3`, or a quoted sheet `'My Sheet'!$C$2`
+pub fn parse_excel_address(output: &str) -> Option<SheetSelection> {
+    let address = output.trim().trim_matches('"').trim();
+    let (sheet, range) = match address.rsplit_once('!') {
+        Some((sheet, range)) => (Some(sheet), range),
+        None => (None, address),
+    };
+    let range: String = range.chars().filter(|c| *c != '$').collect();
+    let valid = !range.is_empty()
+        && range
+            .split([':', ','])
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()));
+    if !valid {
+        return None;
+    }
+
+    let sheet_name = sheet
+        .map(|sheet| sheet.trim().trim_matches('\''))
+        // Drop the workbook of the external form
+        .map(|sheet| sheet.rsplit_once(']').map_or(sheet, |(_, name)| name))
+        .map(|sheet| sheet.replace("''", "'"))
+        .filter(|sheet| !sheet.is_empty());
+    Some(SheetSelection { sheet_name, range })
+}
+
+/// Spreadsheet column name for a zero-based index: 0 → A, 25 → Z, 26 → AA
+pub fn column_name(index: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        name.push(b'A' + rem as u8);
+        n = (n - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Position of one selected cell, from `AXRowIndexRange` and
+/// `AXColumnIndexRange`; merged cells span more than one row or column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellSpan {
+    pub row: usize,
+    pub rows: usize,
+    pub column: usize,
+    pub columns: usize,
+}
+
+/// Bounding range of the selected cells in A1 notation
+pub fn range_from_cells(cells: &[CellSpan]) -> Option<String> {
+    let first_row = cells.iter().map(|c| c.row).min()?;
+    let first_column = cells.iter().map(|c| c.column).min()?;
+    let last_row = cells.iter().map(|c| c.row + c.rows.max(1) - 1).max()?;
+    let last_column = cells
+        .iter()
+        .map(|c| c.column + c.columns.max(1) - 1)
+        .max()?;
+
+    let start = format!("{}{}", column_name(first_column), first_row + 1);
+    if (first_row, first_column) == (last_row, last_column) {
+        Some(start)
+    } else {
+        Some(format!(
+            "{}:{}{}",
+            start,
+            column_name(last_column),
+            last_row + 1
+        ))
+    }
+}
+
+/// Selected cells of the focused table, or of the table around a focused cell
+fn ax_selected_cells(ax_app: &AxElement, allowed: &dyn Fn(&str) -> bool) -> Vec<CellSpan> {
+    if !allowed("AXSelectedCells") {
+        return Vec::new();
+    }
+    let Some(focused) = ax_app.element("AXFocusedUIElement") else {
+        return Vec::new();
+    };
+    let mut cells = focused.elements("AXSelectedCells");
+    if cells.is_empty() {
+        if let Some(parent) = focused.element("AXParent") {
+            cells = parent.elements("AXSelectedCells");
+        }
+    }
+    cells
+        .iter()
+        .filter_map(|cell| {
+            let (row, rows) = cell.range("AXRowIndexRange")?;
+            let (column, columns) = cell.range("AXColumnIndexRange")?;
+            Some(CellSpan {
+                row,
+                rows,
+                column,
+                columns,
+            })
+        })
+        .collect()
+}
+
+/// Fills `sheet_name` and `selected_range` for spreadsheet windows
+pub struct SpreadsheetExtractor;
+
+impl SpreadsheetExtractor {
+    pub fn applies_to(bundle_id: &str) -> bool {
+        SpreadsheetApp::for_bundle_id(bundle_id).is_some()
+    }
+
+    /// AppleScript first; the accessibility tree supplies the range when the
+    /// script yields none, subject to the attribute allowlist
+    pub fn apply(
+        &self,
+        ax_app: &AxElement,
+        context: &mut AccessibilityContext,
+        allowed: &dyn Fn(&str) -> bool,
+    ) {
+        let Some(app) = SpreadsheetApp::for_bundle_id(&context.app_info.bundle_id) else {
+            return;
+        };
+        let output =
+            run_applescript(app.selection_script()).and_then(|out| parse_applescript_output(&out));
+
+        let mut selection = match app {
+            SpreadsheetApp::Excel => output.as_deref().and_then(parse_excel_address),
+            SpreadsheetApp::Numbers => None,
+        };
+        if selection.is_none() {
+            selection =
+                range_from_cells(&ax_selected_cells(ax_app, allowed)).map(|range| SheetSelection {
+                    sheet_name: match app {
+                        SpreadsheetApp::Numbers => output.clone(),
+                        SpreadsheetApp::Excel => None,
+                    },
+                    range,
+                });
+        }
+
+        if let Some(selection) = selection {
+            context.sheet_name = selection.sheet_name.clone();
+            context.selected_range = Some(selection.qualified_range());
+        } else if app == SpreadsheetApp::Numbers {
+            context.sheet_name = output;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selection(sheet: Option<&str>, range: &str) -> Option<SheetSelection> {
+        Some(SheetSelection {
+            sheet_name: sheet.map(str::to_string),
+            range: range.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_parse_excel_address() {
+        assert_eq!(
+            parse_excel_address("Sheet1!$A$1:$B$3\n"),
+            selection(Some("Sheet1"), "A1:B3")
+        );
+        assert_eq!(parse_excel_address("$C$7"), selection(None, "C7"));
+        assert_eq!(
+            parse_excel_address("[Budget 2026.xlsx]Q1!$A$1:$AB$40"),
+            selection(Some("Q1"), "A1:AB40")
+        );
+        assert_eq!(
+            parse_excel_address("'Raw data'!$A:$A"),
+            selection(Some("Raw data"), "A:A")
+        );
+        // Multi-area selections keep every area
+        assert_eq!(
+            parse_excel_address("Sheet1!$A$1,$C$3:$D$4"),
+            selection(Some("Sheet1"), "A1,C3:D4")
+        );
+        assert_eq!(
+            selection(Some("Sheet1"), "A1:B3")
+                .unwrap()
+                .qualified_range(),
+            "Sheet1!A1:B3"
+        );
+
+        assert_eq!(parse_excel_address(""), None);
+        assert_eq!(parse_excel_address("missing value"), None);
+        assert_eq!(parse_excel_address("Sheet1!"), None);
+    }
+
+    #[test]
+    fn test_range_from_selected_cells() {
+        let cell = |row, column| CellSpan {
+            row,
+            rows: 1,
+            column,
+            columns: 1,
+        };
+        assert_eq!(range_from_cells(&[cell(0, 0)]), Some("A1".to_string()));
+        assert_eq!(
+            range_from_cells(&[cell(0, 0), cell(0, 1), cell(2, 0), cell(2, 1)]),
+            Some("A1:B3".to_string())
+        );
+        // A merged cell covering C5:D6
+        let merged = CellSpan {
+            row: 4,
+            rows: 2,
+            column: 2,
+            columns: 2,
+        };
+        assert_eq!(range_from_cells(&[merged]), Some("C5:D6".to_string()));
+        assert_eq!(range_from_cells(&[]), None);
+
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+    }
+}
@@ -0,0 +1,143 @@
+// src/core/startup_warmup.rs
+//! Holding the first event while extraction warms up
+//!
+//! The first event of a session describes the app that was frontmost at
+//! launch and is built before AppleScript and accessibility have answered
+//! once: the first AppleScript call starts the scripting bridge and often
+//! times out, so the event arrives without a URL or document. During the
+//! warm-up the first `Foreground` event is held, later events for the same
+//! app (typically a re-extraction requested shortly after startup) are
+//! merged into it, and the merged event is released when the warm-up ends.
+//! Other events (hides, launches) are queued behind the held one so they are
+//! delivered in order. A switch to another app ends the warm-up early, as
+//! does a `Foreground` for the held app after a queued event, which must not
+//! be merged ahead of it.
+
+use std::time::{Duration, Instant};
+
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::event_coalescer::merge_events;
+
+/// No warm-up: the first event is emitted as soon as it is built
+pub const DEFAULT_STARTUP_WARMUP: Duration = Duration::ZERO;
+
+/// Holds the first foreground event until the warm-up deadline
+#[derive(Default)]
+pub struct StartupWarmup {
+    until: Option<Instant>,
+    held: Option<AppSwitchEvent>,
+    /// Events after the held one, delivered behind it
+    queued: Vec<AppSwitchEvent>,
+}
+
+impl StartupWarmup {
+    /// Start holding events until `warmup` after `now`; a zero warm-up
+    /// leaves events untouched
+    pub fn begin(&mut self, now: Instant, warmup: Duration) {
+        self.until = (!warmup.is_zero()).then(|| now + warmup);
+    }
+
+    /// Pass an event through, returning the events to deliver now
+    pub fn admit(&mut self, event: AppSwitchEvent, now: Instant) -> Vec<AppSwitchEvent> {
+        let Some(until) = self.until else {
+            return vec![event];
+        };
+        if now >= until {
+            return self.finish().into_iter().chain([event]).collect();
+        }
+        let Some(first) = self.held.take() else {
+            if event.event_type.is_foreground() {
+                self.held = Some(event);
+                return Vec::new();
+            }
+            return vec![event];
+        };
+        if !event.event_type.is_foreground() {
+            self.held = Some(first);
+            self.queued.push(event);
+            return Vec::new();
+        }
+        if first.app_info.pid == event.app_info.pid && self.queued.is_empty() {
+            self.held = Some(merge_events(first, event));
+            return Vec::new();
+        }
+        self.held = Some(first);
+        self.finish().into_iter().chain([event]).collect()
+    }
+
+    /// End the warm-up, returning the held event and those queued behind it
+    pub fn finish(&mut self) -> Vec<AppSwitchEvent> {
+        self.until = None;
+        self.held
+            .take()
+            .into_iter()
+            .chain(std::mem::take(&mut self.queued))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType};
+
+    fn event(event_type: AppSwitchType, pid: i32) -> AppSwitchEvent {
+        let app = AppInfo::new(format!("App {}", pid), format!("com.example.{}", pid), pid);
+        AppSwitchEvent::new(event_type, app)
+    }
+
+    fn kinds(events: &[AppSwitchEvent]) -> Vec<(AppSwitchType, i32)> {
+        events
+            .iter()
+            .map(|e| (e.event_type.clone(), e.app_info.pid))
+            .collect()
+    }
+
+    #[test]
+    fn test_other_events_wait_behind_the_held_event() {
+        let start = Instant::now();
+        let at = |millis: u64| start + Duration::from_millis(millis);
+        let mut warmup = StartupWarmup::default();
+        warmup.begin(start, Duration::from_secs(2));
+
+        assert!(warmup
+            .admit(event(AppSwitchType::Foreground, 1), at(0))
+            .is_empty());
+        assert!(warmup
+            .admit(event(AppSwitchType::Launch, 2), at(100))
+            .is_empty());
+        assert!(warmup
+            .admit(event(AppSwitchType::Background, 1), at(200))
+            .is_empty());
+        assert_eq!(
+            kinds(&warmup.finish()),
+            vec![
+                (AppSwitchType::Foreground, 1),
+                (AppSwitchType::Launch, 2),
+                (AppSwitchType::Background, 1),
+            ]
+        );
+
+        // Coming back to the held app after a queued event is not merged
+        // into it, so the events stay in order
+        warmup.begin(start, Duration::from_secs(2));
+        warmup.admit(event(AppSwitchType::Foreground, 1), at(0));
+        warmup.admit(event(AppSwitchType::Hide, 1), at(100));
+        let released = warmup.admit(event(AppSwitchType::Foreground, 1), at(200));
+        assert_eq!(
+            kinds(&released),
+            vec![
+                (AppSwitchType::Foreground, 1),
+                (AppSwitchType::Hide, 1),
+                (AppSwitchType::Foreground, 1),
+            ]
+        );
+        // The warm-up has ended
+        assert_eq!(
+            warmup
+                .admit(event(AppSwitchType::Foreground, 2), at(300))
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,140 @@
+// src/core/supported_apps.rs
+//! Applications with specialized context extraction
+//!
+//! The list lives in `supported_apps.toml`, one array of bundle IDs per
+//! category, and is embedded at compile time. It is loaded once into a map
+//! from bundle ID to category, so the check on every app switch is a hash
+//! lookup rather than a scan of several hundred strings.
+
+use std::collections::{BTreeMap, HashMap};
+
+/// The embedded category table
+const SUPPORTED_APPS_TOML: &str = include_str!("supported_apps.toml");
+
+/// Category of apps that are supported through user extraction rules
+pub const CUSTOM_CATEGORY: &str = "custom";
+
+/// Set of supported bundle IDs, each with its category
+#[derive(Debug, Clone, Default)]
+pub struct SupportedApps {
+    categories: HashMap<String, String>,
+}
+
+impl SupportedApps {
+    /// The built-in table
+    pub fn builtin() -> Self {
+        Self::from_toml(SUPPORTED_APPS_TOML).expect("embedded supported_apps.toml is valid")
+    }
+
+    /// Load `category = ["bundle.id", ...]` tables; a bundle ID listed under
+    /// several categories keeps the one that sorts first
+    pub fn from_toml(source: &str) -> Result<Self, String> {
+        let table: toml::Table =
+            toml::from_str(source).map_err(|e| format!("Invalid supported apps table: {}", e))?;
+        let mut apps = Self::default();
+        for (category, bundles) in table {
+            let bundles: Vec<String> = bundles
+                .try_into()
+                .map_err(|e| format!("Invalid bundle list for '{}': {}", category, e))?;
+            for bundle_id in bundles {
+                apps.insert(bundle_id, &category);
+            }
+        }
+        Ok(apps)
+    }
+
+    /// Add an app unless it is already supported
+    pub fn insert(&mut self, bundle_id: impl Into<String>, category: &str) {
+        self.categories
+            .entry(bundle_id.into())
+            .or_insert_with(|| category.to_string());
+    }
+
+    pub fn is_supported(&self, bundle_id: &str) -> bool {
+        self.categories.contains_key(bundle_id)
+    }
+
+    pub fn category(&self, bundle_id: &str) -> Option<&str> {
+        self.categories.get(bundle_id).map(String::as_str)
+    }
+
+    /// Number of supported apps per category
+    pub fn category_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for category in self.categories.values() {
+            *counts.entry(category.as_str()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn len(&self) -> usize {
+        self.categories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_embedded_table_has_no_duplicates() {
+        let table: BTreeMap<String, Vec<String>> = toml::from_str(SUPPORTED_APPS_TOML).unwrap();
+        let mut seen = HashSet::new();
+        let duplicates: Vec<&String> = table
+            .values()
+            .flatten()
+            .filter(|bundle_id| !seen.insert(*bundle_id))
+            .collect();
+        assert!(duplicates.is_empty(), "listed twice: {:?}", duplicates);
+
+        let apps = SupportedApps::builtin();
+        assert_eq!(apps.len(), seen.len());
+        assert_eq!(apps.category_counts().len(), table.len());
+    }
+
+    #[test]
+    fn test_known_bundles_are_classified() {
+        let apps = SupportedApps::builtin();
+        for (bundle_id, category) in [
+            ("com.google.Chrome", "browsers"),
+            ("com.apple.Safari", "browsers"),
+            ("com.apple.dt.Xcode", "development"),
+            ("com.apple.Terminal", "terminals"),
+            ("md.obsidian", "notes"),
+            ("com.microsoft.teams", "communication"),
+            ("com.apple.Preview", "documents"),
+            ("com.zotero.zotero", "research"),
+            ("com.devontechnologies.thinkfree.DEVONthink3", "notes"),
+        ] {
+            assert!(apps.is_supported(bundle_id), "{}", bundle_id);
+            assert_eq!(apps.category(bundle_id), Some(category), "{}", bundle_id);
+        }
+        assert!(!apps.is_supported("com.example.unknown"));
+        assert_eq!(apps.category("com.example.unknown"), None);
+    }
+
+    #[test]
+    fn test_insert_keeps_the_first_category() {
+        let mut apps = SupportedApps::from_toml(
+            r#"
+            browsers = ["com.apple.Safari"]
+            research = ["com.zotero.zotero", "com.apple.Safari"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(apps.len(), 2);
+        assert_eq!(apps.category("com.apple.Safari"), Some("browsers"));
+
+        apps.insert("com.zotero.zotero", CUSTOM_CATEGORY);
+        apps.insert("com.example.lab", CUSTOM_CATEGORY);
+        assert_eq!(apps.category("com.zotero.zotero"), Some("research"));
+        assert_eq!(apps.category("com.example.lab"), Some(CUSTOM_CATEGORY));
+
+        assert!(SupportedApps::from_toml("browsers = \"com.apple.Safari\"").is_err());
+    }
+}
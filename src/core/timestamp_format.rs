@@ -0,0 +1,186 @@
+// src/core/timestamp_format.rs
+//! How event timestamps are written
+//!
+//! Every logger formats its timestamps through `TimestampFormat` so that one
+//! `--timestamp-format` flag applies to human, JSON, research and file
+//! output alike. RFC 3339 in UTC with chrono's automatic sub-second
+//! precision is the default and matches the historical output.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Sub-second digits in RFC 3339 output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampPrecision {
+    /// As many digits as needed (chrono's default)
+    Auto,
+    Secs,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl TimestampPrecision {
+    fn seconds_format(&self) -> SecondsFormat {
+        match self {
+            TimestampPrecision::Auto => SecondsFormat::AutoSi,
+            TimestampPrecision::Secs => SecondsFormat::Secs,
+            TimestampPrecision::Millis => SecondsFormat::Millis,
+            TimestampPrecision::Micros => SecondsFormat::Micros,
+            TimestampPrecision::Nanos => SecondsFormat::Nanos,
+        }
+    }
+
+    fn suffix(&self) -> &'static str {
+        match self {
+            TimestampPrecision::Auto => "",
+            TimestampPrecision::Secs => "-s",
+            TimestampPrecision::Millis => "-ms",
+            TimestampPrecision::Micros => "-us",
+            TimestampPrecision::Nanos => "-ns",
+        }
+    }
+}
+
+/// Output format for event timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    /// RFC 3339 in UTC, e.g. `2026-10-16T09:30:00.123+00:00`
+    Rfc3339(TimestampPrecision),
+    /// RFC 3339 in the local timezone, e.g. `2026-10-16T11:30:00.123+02:00`
+    Local(TimestampPrecision),
+    /// Milliseconds since the Unix epoch
+    EpochMillis,
+}
+
+impl Default for TimestampFormat {
+    fn default() -> Self {
+        TimestampFormat::Rfc3339(TimestampPrecision::Auto)
+    }
+}
+
+impl TimestampFormat {
+    /// Format a timestamp; the single formatting path for every logger
+    pub fn format(&self, at: DateTime<Utc>) -> String {
+        match self {
+            TimestampFormat::Rfc3339(precision) => {
+                at.to_rfc3339_opts(precision.seconds_format(), false)
+            }
+            TimestampFormat::Local(precision) => at
+                .with_timezone(&Local)
+                .to_rfc3339_opts(precision.seconds_format(), false),
+            TimestampFormat::EpochMillis => at.timestamp_millis().to_string(),
+        }
+    }
+
+    /// Timestamp as a JSON value: a number for epoch millis, a string otherwise
+    pub fn json(&self, at: DateTime<Utc>) -> serde_json::Value {
+        match self {
+            TimestampFormat::EpochMillis => serde_json::json!(at.timestamp_millis()),
+            _ => serde_json::json!(self.format(at)),
+        }
+    }
+
+    /// Format the current time
+    pub fn now(&self) -> String {
+        self.format(Utc::now())
+    }
+}
+
+impl fmt::Display for TimestampFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampFormat::Rfc3339(p) => write!(f, "rfc3339{}", p.suffix()),
+            TimestampFormat::Local(p) => write!(f, "local{}", p.suffix()),
+            TimestampFormat::EpochMillis => write!(f, "epoch-ms"),
+        }
+    }
+}
+
+impl FromStr for TimestampFormat {
+    type Err = String;
+
+    /// Accepts `rfc3339[-s|-ms|-us|-ns]`, `local[-s|-ms|-us|-ns]` or `epoch-ms`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "epoch-ms" {
+            return Ok(TimestampFormat::EpochMillis);
+        }
+        let (base, precision) = match s.split_once('-') {
+            Some((base, suffix)) => (base, suffix),
+            None => (s, ""),
+        };
+        let precision = match precision {
+            "" => TimestampPrecision::Auto,
+            "s" => TimestampPrecision::Secs,
+            "ms" => TimestampPrecision::Millis,
+            "us" => TimestampPrecision::Micros,
+            "ns" => TimestampPrecision::Nanos,
+            _ => return Err(format!("Invalid timestamp precision in '{}'", s)),
+        };
+        match base {
+            "rfc3339" => Ok(TimestampFormat::Rfc3339(precision)),
+            "local" => Ok(TimestampFormat::Local(precision)),
+            _ => Err(format!(
+                "Invalid timestamp format '{}' (expected rfc3339, local or epoch-ms, \
+                 optionally with -s, -ms, -us or -ns)",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 16, 9, 30, 5).unwrap()
+            + chrono::Duration::microseconds(123_456)
+    }
+
+    #[test]
+    fn test_rfc3339_precisions() {
+        let at = sample();
+        let format = |s: &str| s.parse::<TimestampFormat>().unwrap().format(at);
+
+        assert_eq!(format("rfc3339"), at.to_rfc3339());
+        assert_eq!(format("rfc3339-s"), "2026-10-16T09:30:05+00:00");
+        assert_eq!(format("rfc3339-ms"), "2026-10-16T09:30:05.123+00:00");
+        assert_eq!(format("rfc3339-us"), "2026-10-16T09:30:05.123456+00:00");
+        assert_eq!(format("rfc3339-ns"), "2026-10-16T09:30:05.123456000+00:00");
+    }
+
+    #[test]
+    fn test_local_and_epoch() {
+        let at = sample();
+
+        let local = TimestampFormat::Local(TimestampPrecision::Millis).format(at);
+        let parsed = DateTime::parse_from_rfc3339(&local).unwrap();
+        assert_eq!(
+            parsed.with_timezone(&Utc),
+            at - chrono::Duration::microseconds(456)
+        );
+        assert!(!local.ends_with('Z'));
+
+        let epoch = TimestampFormat::EpochMillis;
+        assert_eq!(epoch.format(at), "1792143005123");
+        assert_eq!(epoch.json(at), serde_json::json!(1792143005123i64));
+        assert!(TimestampFormat::default().json(at).is_string());
+    }
+
+    #[test]
+    fn test_parse_round_trips() {
+        for s in ["rfc3339", "rfc3339-ms", "local", "local-ns", "epoch-ms"] {
+            assert_eq!(s.parse::<TimestampFormat>().unwrap().to_string(), s);
+        }
+        assert!("unix".parse::<TimestampFormat>().is_err());
+        assert!("rfc3339-min".parse::<TimestampFormat>().is_err());
+    }
+}
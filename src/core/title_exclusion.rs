@@ -0,0 +1,235 @@
+// src/core/title_exclusion.rs
+//! Excluding windows by title
+//!
+//! App-level exclusion is too coarse for a banking tab in the browser or one
+//! confidential document in an editor. The `[title_exclusion]` config section
+//! lists regular expressions over window and tab titles; when one matches,
+//! the event keeps its app and timing but loses its titles, URLs, paths and
+//! extractor output (including the selection), and no screenshot is taken.
+//! The exclusion runs as the first redaction stage, so it still sees the
+//! title it matches on, and copies from a matching window lose their source
+//! context. The accessibility extractor checks the titles it reads itself,
+//! which the event may not carry, before reporting anything.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::core::accessibility::AccessibilityContext;
+use crate::core::app_switcher_types::AppSwitchEvent;
+use crate::core::clipboard::{ClipboardEvent, ClipboardListener};
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+use crate::core::quiet_hours::QuietHoursGate;
+
+/// The `[title_exclusion]` config section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TitleExclusionConfig {
+    /// Regular expressions matched anywhere in a title; `(?i)` ignores case
+    pub patterns: Vec<String>,
+}
+
+impl TitleExclusionConfig {
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+/// Drops the content of events whose window or tab title matches
+#[derive(Debug, Clone)]
+pub struct TitleExclusion {
+    patterns: Vec<Regex>,
+}
+
+impl TitleExclusion {
+    pub fn from_config(config: &TitleExclusionConfig) -> Result<Self, String> {
+        let patterns = config
+            .patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .map_err(|e| format!("Invalid title exclusion pattern '{}': {}", pattern, e))
+            })
+            .collect::<Result<Vec<Regex>, String>>()?;
+        Ok(Self { patterns })
+    }
+
+    pub fn is_excluded(&self, title: &str) -> bool {
+        self.patterns.iter().any(|pattern| pattern.is_match(title))
+    }
+
+    /// Whether any title the event carries is excluded
+    pub fn matches_event(&self, event: &AppSwitchEvent) -> bool {
+        let workspace = event
+            .workspace
+            .iter()
+            .flat_map(|ws| ws.focused_title.iter().chain(ws.tab_titles.iter()));
+        let enhanced = event
+            .enhanced
+            .iter()
+            .flat_map(|enh| enh.front_window_title.iter().chain(enh.tab_title.iter()));
+        workspace
+            .chain(enhanced)
+            .any(|title| self.is_excluded(title))
+    }
+
+    /// Whether the window or page title of an extracted context is excluded
+    pub fn matches_context(&self, context: &AccessibilityContext) -> bool {
+        context
+            .window_title
+            .iter()
+            .chain(context.page_title.iter())
+            .any(|title| self.is_excluded(title))
+    }
+
+    /// Wrap a clipboard listener so copies from excluded windows lose their
+    /// source context
+    pub fn clipboard_listener<L: ClipboardListener>(
+        &self,
+        inner: L,
+    ) -> TitleExclusionClipboardListener<L> {
+        TitleExclusionClipboardListener {
+            exclusion: self.clone(),
+            inner,
+        }
+    }
+}
+
+impl EnrichmentStage for TitleExclusion {
+    fn name(&self) -> &str {
+        "title_exclusion"
+    }
+
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Redact
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        if self.matches_event(event) {
+            QuietHoursGate::redact_event(event);
+        }
+    }
+}
+
+/// Clipboard listener behind a `TitleExclusion`
+pub struct TitleExclusionClipboardListener<L> {
+    exclusion: TitleExclusion,
+    inner: L,
+}
+
+impl<L: ClipboardListener> ClipboardListener for TitleExclusionClipboardListener<L> {
+    fn on_clipboard_change(&mut self, event: &ClipboardEvent) {
+        let excluded = event
+            .context
+            .as_ref()
+            .and_then(|context| context.window_title.as_deref())
+            .is_some_and(|title| self.exclusion.is_excluded(title));
+        if excluded {
+            let mut event = event.clone();
+            event.context = None;
+            self.inner.on_clipboard_change(&event);
+        } else {
+            self.inner.on_clipboard_change(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType, EnhancedSummary};
+
+    fn safari(title: &str) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 7),
+        );
+        event.enhanced = Some(EnhancedSummary {
+            front_window_title: Some(title.to_string()),
+            tab_title: Some(title.to_string()),
+            url: Some("https://bank.example.com/accounts".to_string()),
+            domain: Some("example.com".to_string()),
+            ..EnhancedSummary::default()
+        });
+        event
+    }
+
+    #[test]
+    fn test_matching_titles_lose_their_content() {
+        let mut exclusion = TitleExclusion::from_config(&TitleExclusionConfig {
+            patterns: vec![
+                "(?i)online banking".to_string(),
+                r"^Salaries \d{4}".to_string(),
+            ],
+        })
+        .unwrap();
+
+        let mut event = safari("Example Bank – Online Banking");
+        exclusion.enrich(&mut event);
+        let enh = event.enhanced.as_ref().unwrap();
+        assert_eq!(enh.front_window_title, None);
+        assert_eq!(enh.tab_title, None);
+        assert_eq!(enh.url, None);
+        assert_eq!(enh.domain, None);
+        // App and timing are kept
+        assert_eq!(event.app_info.bundle_id, "com.apple.Safari");
+        assert_eq!(event.event_type, AppSwitchType::Foreground);
+
+        // Other titles pass untouched
+        let mut event = safari("Weekly salaries report");
+        exclusion.enrich(&mut event);
+        let enh = event.enhanced.as_ref().unwrap();
+        assert_eq!(
+            enh.front_window_title.as_deref(),
+            Some("Weekly salaries report")
+        );
+        assert_eq!(
+            enh.url.as_deref(),
+            Some("https://bank.example.com/accounts")
+        );
+
+        assert!(TitleExclusion::from_config(&TitleExclusionConfig {
+            patterns: vec!["(unclosed".to_string()],
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_extracted_titles_are_matched() {
+        use std::collections::HashMap;
+
+        let exclusion = TitleExclusion::from_config(&TitleExclusionConfig {
+            patterns: vec!["(?i)online banking".to_string()],
+        })
+        .unwrap();
+        let mut context = AccessibilityContext {
+            app_info: AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 7),
+            window_title: Some("Accounts".to_string()),
+            document_path: None,
+            document_path_confidence: None,
+            is_document_modified: None,
+            current_url: Some("https://bank.example.com/accounts".to_string()),
+            page_title: Some("Example Bank – Online Banking".to_string()),
+            tab_count: None,
+            browser_profile: None,
+            sheet_name: None,
+            selected_range: None,
+            mail_folder: None,
+            conversation: None,
+            current_reference: None,
+            reference_collection: None,
+            terminal_command: None,
+            active_file_path: None,
+            project_name: None,
+            selected_text: Some("IBAN".to_string()),
+            focused_element: None,
+            ui_path: Vec::new(),
+            raw_attributes: HashMap::new(),
+            windows: Vec::new(),
+            custom_fields: HashMap::new(),
+        };
+        assert!(exclusion.matches_context(&context));
+
+        context.page_title = Some("Weekly report".to_string());
+        assert!(!exclusion.matches_context(&context));
+    }
+}
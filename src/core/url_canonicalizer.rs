@@ -0,0 +1,258 @@
+// src/core/url_canonicalizer.rs
+//! URL canonicalization
+//!
+//! Captures of the same page rarely agree byte for byte: one has a trailing
+//! slash, another a `www.` prefix, a third a pile of `utm_` parameters or a
+//! `#section` fragment. Left alone these fragment per-URL analytics, so URLs
+//! are normalized through a set of individually switchable rules before they
+//! are aggregated (and, optionally, before they are logged).
+//!
+//! `site_domain` reduces a URL further, to the site it belongs to, so
+//! consumers can group by site without parsing URLs themselves.
+
+use serde::{Deserialize, Serialize};
+use url::{Host, Url};
+
+/// Rules applied by the canonicalizer, read from the `[urls]` config section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrlCanonicalizerConfig {
+    pub lowercase_host: bool,
+    pub strip_www: bool,
+
+    /// Drop query parameters whose name starts with one of these prefixes
+    pub tracking_param_prefixes: Vec<String>,
+
+    /// Drop `#section` anchors; routes of hash-routed apps (`#/inbox`,
+    /// `#!/inbox`) are kept, as they name different pages
+    pub drop_fragment: bool,
+    pub sort_query: bool,
+    pub strip_trailing_slash: bool,
+
+    /// Also canonicalize URLs on events before listeners see them
+    pub apply_to_logs: bool,
+}
+
+impl Default for UrlCanonicalizerConfig {
+    fn default() -> Self {
+        Self {
+            lowercase_host: true,
+            strip_www: true,
+            tracking_param_prefixes: ["utm_", "fbclid", "gclid", "msclkid", "mc_cid", "mc_eid"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            drop_fragment: true,
+            sort_query: true,
+            strip_trailing_slash: true,
+            apply_to_logs: false,
+        }
+    }
+}
+
+/// Normalizes URLs so different captures of the same page compare equal
+#[derive(Debug, Clone, Default)]
+pub struct UrlCanonicalizer {
+    config: UrlCanonicalizerConfig,
+}
+
+impl UrlCanonicalizer {
+    pub fn new(config: UrlCanonicalizerConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &UrlCanonicalizerConfig {
+        &self.config
+    }
+
+    /// Canonicalize a URL; input that does not parse is returned unchanged
+    pub fn canonicalize(&self, raw: &str) -> String {
+        let mut url = match Url::parse(raw.trim()) {
+            Ok(url) => url,
+            Err(_) => return raw.to_string(),
+        };
+
+        if let Some(host) = url.host_str().map(str::to_string) {
+            let mut new_host = host.clone();
+            if self.config.lowercase_host {
+                new_host = new_host.to_lowercase();
+            }
+            // `WWW.` counts too, whether or not the host is lowercased
+            if self.config.strip_www && new_host.to_lowercase().starts_with("www.") {
+                new_host = new_host["www.".len()..].to_string();
+            }
+            if new_host != host {
+                let _ = url.set_host(Some(&new_host));
+            }
+        }
+
+        if self.config.drop_fragment && !url.fragment().is_some_and(is_route) {
+            url.set_fragment(None);
+        }
+
+        // Parameters are kept as written: decoding and re-encoding them would
+        // turn `%20` into `+` and change what the page receives
+        if let Some(query) = url.query().map(str::to_string) {
+            let mut params: Vec<&str> = query
+                .split('&')
+                .filter(|param| !param.is_empty())
+                .filter(|param| !self.is_tracking_param(param.split('=').next().unwrap_or("")))
+                .collect();
+            if self.config.sort_query {
+                params.sort();
+            }
+            if params.is_empty() {
+                url.set_query(None);
+            } else {
+                url.set_query(Some(&params.join("&")));
+            }
+        }
+
+        if self.config.strip_trailing_slash {
+            let path = url.path();
+            if path.len() > 1 && path.ends_with('/') {
+                let trimmed = path.trim_end_matches('/').to_string();
+                url.set_path(if trimmed.is_empty() { "/" } else { &trimmed });
+            }
+        }
+
+        url.to_string()
+    }
+
+    fn is_tracking_param(&self, key: &str) -> bool {
+        self.config
+            .tracking_param_prefixes
+            .iter()
+            .any(|prefix| key.starts_with(prefix.as_str()))
+    }
+}
+
+/// Whether a fragment is a single-page app route rather than an anchor
+fn is_route(fragment: &str) -> bool {
+    fragment.starts_with('/') || fragment.starts_with("!/")
+}
+
+/// Site a URL belongs to, for grouping: the registrable domain (eTLD+1)
+/// under the ICANN section of the public suffix list
+///
+/// `docs.google.com` and `mail.google.com` both give `google.com`, and
+/// `news.bbc.co.uk` gives `bbc.co.uk`. Shared-hosting suffixes from the
+/// list's private section are not treated as suffixes, so user sites group
+/// under their host: `foo.github.io` gives `github.io`. IP addresses and
+/// single-label hosts such as `localhost` are returned as they are.
+pub fn site_domain(raw: &str) -> Option<String> {
+    let url = Url::parse(raw.trim()).ok()?;
+    let host = match url.host()? {
+        Host::Domain(domain) => domain.trim_end_matches('.').to_lowercase(),
+        Host::Ipv4(ip) => return Some(ip.to_string()),
+        Host::Ipv6(ip) => return Some(ip.to_string()),
+    };
+    if !host.contains('.') {
+        return (!host.is_empty()).then_some(host);
+    }
+
+    let mut suffix = addr::parse_domain_name(&host).ok()?.suffix();
+    // Walk up from private suffixes (github.io) to the ICANN one (io)
+    while addr::parse_domain_name(suffix).ok()?.is_private() {
+        suffix = suffix.split_once('.')?.1;
+        suffix = addr::parse_domain_name(suffix).ok()?.suffix();
+    }
+
+    let rest = host.strip_suffix(suffix)?.strip_suffix('.')?;
+    let label = rest.rsplit('.').next()?;
+    Some(format!("{}.{}", label, suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(rule: impl FnOnce(&mut UrlCanonicalizerConfig)) -> UrlCanonicalizer {
+        let mut config = UrlCanonicalizerConfig {
+            lowercase_host: false,
+            strip_www: false,
+            tracking_param_prefixes: Vec::new(),
+            drop_fragment: false,
+            sort_query: false,
+            strip_trailing_slash: false,
+            apply_to_logs: false,
+        };
+        rule(&mut config);
+        UrlCanonicalizer::new(config)
+    }
+
+    #[test]
+    fn test_individual_rules() {
+        let c = only(|c| c.lowercase_host = true);
+        assert_eq!(c.canonicalize("myapp://Docs.Example/a"), "myapp://docs.example/a");
+
+        let c = only(|c| c.strip_www = true);
+        assert_eq!(c.canonicalize("https://www.rust-lang.org/learn"), "https://rust-lang.org/learn");
+        assert_eq!(c.canonicalize("myapp://WWW.Docs.Example/a"), "myapp://Docs.Example/a");
+
+        let c = only(|c| c.tracking_param_prefixes = vec!["utm_".into(), "fbclid".into()]);
+        assert_eq!(
+            c.canonicalize("https://a.com/p?id=3&utm_source=x&fbclid=abc&utm_medium=y"),
+            "https://a.com/p?id=3"
+        );
+        assert_eq!(c.canonicalize("https://a.com/p?utm_source=x"), "https://a.com/p");
+
+        let c = only(|c| c.drop_fragment = true);
+        assert_eq!(c.canonicalize("https://a.com/p#intro"), "https://a.com/p");
+        assert_eq!(c.canonicalize("https://a.com/p#"), "https://a.com/p");
+        assert_eq!(c.canonicalize("https://a.com/#/inbox"), "https://a.com/#/inbox");
+        assert_eq!(c.canonicalize("https://a.com/#!/inbox"), "https://a.com/#!/inbox");
+
+        let c = only(|c| c.sort_query = true);
+        assert_eq!(c.canonicalize("https://a.com/?b=2&a=1"), "https://a.com/?a=1&b=2");
+        // Encoded parameters are left as given
+        assert_eq!(
+            c.canonicalize("https://a.com/s?q=a%20b&c=x%2By&a=%E2%9C%93"),
+            "https://a.com/s?a=%E2%9C%93&c=x%2By&q=a%20b"
+        );
+
+        let c = only(|c| c.strip_trailing_slash = true);
+        assert_eq!(c.canonicalize("https://a.com/docs/"), "https://a.com/docs");
+        assert_eq!(c.canonicalize("https://a.com/"), "https://a.com/");
+    }
+
+    #[test]
+    fn test_variants_collapse_and_idempotent() {
+        let c = UrlCanonicalizer::default();
+        let variants = [
+            "https://www.Example.com/article/?utm_source=news&ref=1#comments",
+            "https://example.com/article?ref=1",
+            "https://example.com/article/?ref=1#",
+        ];
+        let canonical: Vec<String> = variants.iter().map(|u| c.canonicalize(u)).collect();
+        assert!(canonical.iter().all(|u| u == "https://example.com/article?ref=1"));
+
+        for url in variants.iter().chain(["not a url", "file:///Users/me/notes.md/"].iter()) {
+            let once = c.canonicalize(url);
+            assert_eq!(c.canonicalize(&once), once, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_site_domain() {
+        let cases = [
+            ("https://docs.google.com/document/d/1", Some("google.com")),
+            ("https://www.google.com/search?q=rust", Some("google.com")),
+            ("https://news.bbc.co.uk/sport", Some("bbc.co.uk")),
+            ("https://foo.github.io/project/", Some("github.io")),
+            ("https://rust-lang.github.io/book", Some("github.io")),
+            ("https://user.blogspot.com/", Some("blogspot.com")),
+            ("https://en.m.wikipedia.org/wiki/Rust", Some("wikipedia.org")),
+            ("https://Example.COM./path", Some("example.com")),
+            ("https://a.b.example.com.au/", Some("example.com.au")),
+            ("http://localhost:3000/", Some("localhost")),
+            ("http://127.0.0.1:8080/", Some("127.0.0.1")),
+            ("https://co.uk/", None),
+            ("file:///Users/me/notes.txt", None),
+            ("not a url", None),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(site_domain(url).as_deref(), expected, "{}", url);
+        }
+    }
+}
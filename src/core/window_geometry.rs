@@ -0,0 +1,552 @@
+// src/core/window_geometry.rs
+//! Window moves and resizes as an event source
+//!
+//! The frontmost app's AXWindowMoved and AXWindowResized notifications fire
+//! continuously while a window is dragged, dozens of times per second. They
+//! are collected by a `GeometryDebouncer`, which reports one
+//! `WindowGeometryChanged` per drag once the window has been still for the
+//! settle interval, carrying the final bounds. Window-management analytics
+//! (tiling, snapping, how often windows are rearranged) need the settled
+//! layouts, not the frames in between.
+//!
+//! The observer runs its own run loop on a background thread. It sleeps
+//! until a notification arrives or a pending change is due, and follows the
+//! switcher's foreground events (see `ForegroundFollow`) to re-attach to the
+//! new frontmost app. Window titles are withheld in quiet hours and for
+//! excluded windows, like the titles on app switch events.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use accessibility_sys::{
+    kAXErrorSuccess, kAXWindowMovedNotification, kAXWindowResizedNotification,
+    AXObserverAddNotification, AXObserverCreate, AXObserverGetRunLoopSource, AXObserverRef,
+    AXUIElementRef,
+};
+use chrono::{DateTime, Utc};
+use core_foundation::base::{CFHash, CFRelease, CFTypeRef, TCFType};
+use core_foundation::string::{CFString, CFStringRef};
+use core_foundation_sys::runloop::{
+    kCFRunLoopDefaultMode, CFRunLoopAddSource, CFRunLoopGetCurrent, CFRunLoopRemoveSource,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_types::AppInfo;
+use crate::core::ax_element::AxElement;
+use crate::core::foreground_follow::{ForegroundFollow, ForegroundFollower};
+use crate::core::quiet_hours::QuietHoursGate;
+use crate::core::running_apps::frontmost_app;
+use crate::core::title_exclusion::TitleExclusion;
+
+/// How long a window must be still before its move or resize is reported;
+/// a live resize pauses for well under this between frames
+pub const DEFAULT_GEOMETRY_SETTLE: Duration = Duration::from_millis(750);
+
+/// Longest the observer's run loop sleeps with no change pending; app
+/// switches and `stop` wake it sooner
+const IDLE_WAIT: f64 = 5.0;
+
+/// Position and size of a window, in screen points
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl WindowBounds {
+    /// Bounds of a window element; `None` if it has no frame
+    pub fn of(window: &AxElement) -> Option<Self> {
+        let (x, y) = window.point("AXPosition")?;
+        let (width, height) = window.size("AXSize")?;
+        Some(Self {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+}
+
+/// A window settled at new bounds after being moved or resized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometryChanged {
+    pub timestamp: DateTime<Utc>,
+    pub app_name: String,
+    pub bundle_id: String,
+    pub pid: i32,
+    pub window_title: Option<String>,
+    pub bounds: WindowBounds,
+    /// Bounds before the change, when the window was seen settled before
+    pub previous: Option<WindowBounds>,
+    pub moved: bool,
+    pub resized: bool,
+}
+
+/// Receives settled window geometry changes
+pub trait WindowGeometryListener: Send {
+    fn on_window_geometry_changed(&mut self, event: &WindowGeometryChanged);
+}
+
+/// Which AX notification reported a change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeometryKind {
+    Moved,
+    Resized,
+}
+
+/// One AXWindowMoved/AXWindowResized notification
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeometryNotification {
+    /// Identity of the window element (its CFHash)
+    pub window: usize,
+    pub title: Option<String>,
+    pub bounds: WindowBounds,
+    pub kind: GeometryKind,
+    pub at: Instant,
+}
+
+/// A drag or resize of one window, reported once it has settled
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettledGeometry {
+    pub window: usize,
+    pub title: Option<String>,
+    pub bounds: WindowBounds,
+    pub previous: Option<WindowBounds>,
+    pub moved: bool,
+    pub resized: bool,
+}
+
+/// Coalesces bursts of notifications into one change per drag
+pub struct GeometryDebouncer {
+    settle: Duration,
+    pending: Option<(SettledGeometry, Instant)>,
+
+    /// Last settled bounds per window
+    settled: HashMap<usize, WindowBounds>,
+}
+
+impl GeometryDebouncer {
+    pub fn new(settle: Duration) -> Self {
+        Self {
+            settle,
+            pending: None,
+            settled: HashMap::new(),
+        }
+    }
+
+    /// Record a window's current bounds without reporting them
+    pub fn remember(&mut self, window: usize, bounds: WindowBounds) {
+        self.settled.insert(window, bounds);
+    }
+
+    /// Add a notification; returns the change of another window it ends
+    pub fn push(&mut self, notification: GeometryNotification) -> Option<SettledGeometry> {
+        let ended = match &self.pending {
+            Some((pending, _)) if pending.window != notification.window => self.flush(),
+            _ => None,
+        };
+        let (change, last) = self.pending.get_or_insert_with(|| {
+            (
+                SettledGeometry {
+                    window: notification.window,
+                    title: None,
+                    bounds: notification.bounds,
+                    previous: self.settled.get(&notification.window).copied(),
+                    moved: false,
+                    resized: false,
+                },
+                notification.at,
+            )
+        });
+        change.title = notification.title;
+        change.bounds = notification.bounds;
+        match notification.kind {
+            GeometryKind::Moved => change.moved = true,
+            GeometryKind::Resized => change.resized = true,
+        }
+        *last = notification.at;
+        ended
+    }
+
+    /// When the pending change settles, if one is pending
+    pub fn deadline(&self) -> Option<Instant> {
+        let (_, last) = self.pending.as_ref()?;
+        Some(*last + self.settle)
+    }
+
+    /// The pending change once no notification arrived for the settle interval
+    pub fn poll(&mut self, now: Instant) -> Option<SettledGeometry> {
+        let (_, last) = self.pending.as_ref()?;
+        if now.saturating_duration_since(*last) < self.settle {
+            return None;
+        }
+        self.flush()
+    }
+
+    /// The pending change, settled or not; `None` when the window ended
+    /// where it started
+    pub fn flush(&mut self) -> Option<SettledGeometry> {
+        let (change, _) = self.pending.take()?;
+        self.settled.insert(change.window, change.bounds);
+        (change.previous != Some(change.bounds)).then_some(change)
+    }
+}
+
+/// Notifications received by the callback, drained after each run loop turn
+type NotificationQueue = RefCell<Vec<GeometryNotification>>;
+
+/// An AXObserver on one app's move/resize notifications, attached to the
+/// current thread's run loop
+struct AppObserver {
+    observer: AXObserverRef,
+    _app: AxElement,
+}
+
+impl AppObserver {
+    /// Observe `pid`; `queue` must outlive the observer
+    fn attach(pid: i32, queue: &NotificationQueue) -> Option<Self> {
+        let app = AxElement::application(pid)?;
+        let mut observer: AXObserverRef = ptr::null_mut();
+        // SAFETY: `observer` receives a +1 reference on success
+        let status = unsafe { AXObserverCreate(pid, on_geometry_notification, &mut observer) };
+        if status != kAXErrorSuccess || observer.is_null() {
+            return None;
+        }
+        let refcon = queue as *const NotificationQueue as *mut c_void;
+        for name in [kAXWindowMovedNotification, kAXWindowResizedNotification] {
+            let name = CFString::new(name);
+            // SAFETY: the observer and app are live; the queue outlives the observer
+            unsafe {
+                AXObserverAddNotification(
+                    observer,
+                    app.as_raw(),
+                    name.as_concrete_TypeRef(),
+                    refcon,
+                );
+            }
+        }
+        // SAFETY: the source belongs to the live observer
+        unsafe {
+            CFRunLoopAddSource(
+                CFRunLoopGetCurrent(),
+                AXObserverGetRunLoopSource(observer),
+                kCFRunLoopDefaultMode,
+            );
+        }
+        Some(Self {
+            observer,
+            _app: app,
+        })
+    }
+}
+
+impl Drop for AppObserver {
+    fn drop(&mut self) {
+        // SAFETY: the observer is owned by this value and was added to this
+        // thread's run loop in `attach`
+        unsafe {
+            CFRunLoopRemoveSource(
+                CFRunLoopGetCurrent(),
+                AXObserverGetRunLoopSource(self.observer),
+                kCFRunLoopDefaultMode,
+            );
+            CFRelease(self.observer as CFTypeRef);
+        }
+    }
+}
+
+unsafe extern "C" fn on_geometry_notification(
+    _observer: AXObserverRef,
+    element: AXUIElementRef,
+    notification: CFStringRef,
+    refcon: *mut c_void,
+) {
+    // SAFETY: the notification name is a live string for the callback's duration
+    let name = unsafe { CFString::wrap_under_get_rule(notification) }.to_string();
+    let kind = if name == kAXWindowMovedNotification {
+        GeometryKind::Moved
+    } else if name == kAXWindowResizedNotification {
+        GeometryKind::Resized
+    } else {
+        return;
+    };
+    // SAFETY: the element is live for the callback's duration; retained for the wrapper
+    let Some(window) = (unsafe { AxElement::from_get_rule(element) }) else {
+        return;
+    };
+    let Some(bounds) = WindowBounds::of(&window) else {
+        return;
+    };
+    // SAFETY: refcon is the queue passed to `AppObserver::attach`, which
+    // outlives the observer, and callbacks run on the observer's thread
+    let queue = unsafe { &*(refcon as *const NotificationQueue) };
+    queue.borrow_mut().push(GeometryNotification {
+        // SAFETY: the element is a live CF object
+        window: unsafe { CFHash(window.as_raw() as CFTypeRef) } as usize,
+        title: window.string("AXTitle"),
+        bounds,
+        kind,
+        at: Instant::now(),
+    });
+}
+
+/// Reports settled moves and resizes of the frontmost app's windows
+pub struct WindowGeometryObserver {
+    settle: Duration,
+    listeners: Arc<Mutex<Vec<Box<dyn WindowGeometryListener>>>>,
+    running: Arc<AtomicBool>,
+    follow: ForegroundFollow,
+    quiet_hours: Option<QuietHoursGate>,
+    title_exclusion: Option<TitleExclusion>,
+}
+
+impl WindowGeometryObserver {
+    /// `settle` is how long a window must be still before its change is reported
+    pub fn new(settle: Duration) -> Self {
+        Self {
+            settle,
+            listeners: Arc::new(Mutex::new(Vec::new())),
+            running: Arc::new(AtomicBool::new(false)),
+            follow: ForegroundFollow::default(),
+            quiet_hours: None,
+            title_exclusion: None,
+        }
+    }
+
+    /// Withhold window titles while `gate` is quiet
+    pub fn with_quiet_hours(mut self, gate: QuietHoursGate) -> Self {
+        self.quiet_hours = Some(gate);
+        self
+    }
+
+    /// Withhold the titles of excluded windows
+    pub fn with_title_exclusion(mut self, exclusion: TitleExclusion) -> Self {
+        self.title_exclusion = Some(exclusion);
+        self
+    }
+
+    pub fn add_listener<T: WindowGeometryListener + 'static>(&self, listener: T) {
+        self.listeners.lock().unwrap().push(Box::new(listener));
+    }
+
+    /// A switcher listener that moves this observer to each foreground app
+    pub fn follower(&self) -> ForegroundFollower {
+        self.follow.follower("window_geometry")
+    }
+
+    /// Start observing on a background thread; requires Accessibility permission
+    pub fn start(&self) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let listeners = Arc::clone(&self.listeners);
+        let running = Arc::clone(&self.running);
+        let follow = self.follow.clone();
+        let settle = self.settle;
+        let quiet_hours = self.quiet_hours.clone();
+        let title_exclusion = self.title_exclusion.clone();
+
+        std::thread::spawn(move || {
+            let queue: NotificationQueue = RefCell::new(Vec::new());
+            let mut debouncer = GeometryDebouncer::new(settle);
+            let mut attached: Option<(AppInfo, AppObserver)> = None;
+            let mut target =
+                frontmost_app().map(|app| AppInfo::new(app.name, app.bundle_id, app.pid));
+            follow.enter();
+
+            let emit = |app: &AppInfo, change: SettledGeometry| {
+                let withheld = quiet_hours.as_ref().is_some_and(|gate| gate.is_quiet())
+                    || change.title.as_deref().is_some_and(|title| {
+                        title_exclusion
+                            .as_ref()
+                            .is_some_and(|exclusion| exclusion.is_excluded(title))
+                    });
+                let event = WindowGeometryChanged {
+                    timestamp: Utc::now(),
+                    app_name: app.name.clone(),
+                    bundle_id: app.bundle_id.clone(),
+                    pid: app.pid,
+                    window_title: if withheld { None } else { change.title },
+                    bounds: change.bounds,
+                    previous: change.previous,
+                    moved: change.moved,
+                    resized: change.resized,
+                };
+                for listener in listeners.lock().unwrap().iter_mut() {
+                    listener.on_window_geometry_changed(&event);
+                }
+            };
+
+            while running.load(Ordering::SeqCst) {
+                if let Some(next) = follow.take_foreground() {
+                    target = Some(next);
+                }
+                let target_pid = target.as_ref().map(|app| app.pid);
+                if attached.as_ref().map(|(app, _)| app.pid) != target_pid {
+                    if let Some((app, observer)) = attached.take() {
+                        drop(observer);
+                        for notification in queue.borrow_mut().drain(..) {
+                            if let Some(change) = debouncer.push(notification) {
+                                emit(&app, change);
+                            }
+                        }
+                        if let Some(change) = debouncer.flush() {
+                            emit(&app, change);
+                        }
+                    }
+                    attached = target.clone().and_then(|app| {
+                        let observer = AppObserver::attach(app.pid, &queue)?;
+                        if let Some(window) = AxElement::application(app.pid)
+                            .and_then(|a| a.element("AXFocusedWindow"))
+                        {
+                            if let Some(bounds) = WindowBounds::of(&window) {
+                                // SAFETY: the element is a live CF object
+                                let key = unsafe { CFHash(window.as_raw() as CFTypeRef) };
+                                debouncer.remember(key as usize, bounds);
+                            }
+                        }
+                        Some((app, observer))
+                    });
+                }
+
+                // Sleep until a notification, the pending change's deadline,
+                // an app switch or `stop`
+                follow.wait(debouncer.deadline().map_or(IDLE_WAIT, |deadline| {
+                    deadline
+                        .saturating_duration_since(Instant::now())
+                        .as_secs_f64()
+                }));
+
+                if let Some((app, _)) = &attached {
+                    let notifications: Vec<GeometryNotification> =
+                        queue.borrow_mut().drain(..).collect();
+                    for notification in notifications {
+                        if let Some(change) = debouncer.push(notification) {
+                            emit(app, change);
+                        }
+                    }
+                    if let Some(change) = debouncer.poll(Instant::now()) {
+                        emit(app, change);
+                    }
+                }
+            }
+            follow.leave();
+        });
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        self.follow.wake();
+    }
+}
+
+impl Drop for WindowGeometryObserver {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(x: f64, y: f64, width: f64, height: f64) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn notification(
+        window: usize,
+        kind: GeometryKind,
+        bounds: WindowBounds,
+        at: Instant,
+    ) -> GeometryNotification {
+        GeometryNotification {
+            window,
+            title: Some("Notes".to_string()),
+            bounds,
+            kind,
+            at,
+        }
+    }
+
+    #[test]
+    fn test_resize_burst_settles_into_one_change() {
+        let settle = Duration::from_millis(500);
+        let mut debouncer = GeometryDebouncer::new(settle);
+        let start = bounds(0.0, 25.0, 800.0, 600.0);
+        debouncer.remember(1, start);
+
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+        // Dragging the left edge: a resize and a move every frame
+        for frame in 0..40u64 {
+            let width = 800.0 + 10.0 * (frame + 1) as f64;
+            let x = -10.0 * (frame + 1) as f64;
+            let frame_bounds = bounds(x, 25.0, width, 600.0);
+            let at = ms(frame * 16);
+            assert_eq!(
+                debouncer.push(notification(1, GeometryKind::Resized, frame_bounds, at)),
+                None
+            );
+            assert_eq!(
+                debouncer.push(notification(1, GeometryKind::Moved, frame_bounds, at)),
+                None
+            );
+            assert_eq!(debouncer.poll(at), None);
+        }
+        let last = ms(39 * 16);
+        assert_eq!(debouncer.deadline(), Some(last + settle));
+        assert_eq!(debouncer.poll(last + Duration::from_millis(499)), None);
+
+        let settled = debouncer.poll(last + settle).unwrap();
+        assert_eq!(settled.bounds, bounds(-400.0, 25.0, 1200.0, 600.0));
+        assert_eq!(settled.previous, Some(start));
+        assert!(settled.moved && settled.resized);
+        assert_eq!(settled.title.as_deref(), Some("Notes"));
+        assert_eq!(debouncer.poll(last + settle * 2), None);
+        assert_eq!(debouncer.deadline(), None);
+
+        // A drag back to where the window was settled reports nothing
+        let next = last + settle * 3;
+        debouncer.push(notification(
+            1,
+            GeometryKind::Moved,
+            bounds(5.0, 25.0, 1200.0, 600.0),
+            next,
+        ));
+        debouncer.push(notification(1, GeometryKind::Moved, settled.bounds, next));
+        assert_eq!(debouncer.poll(next + settle), None);
+
+        // Moving another window ends the pending drag right away
+        debouncer.push(notification(
+            1,
+            GeometryKind::Moved,
+            bounds(9.0, 25.0, 1200.0, 600.0),
+            next,
+        ));
+        let ended = debouncer
+            .push(notification(
+                2,
+                GeometryKind::Moved,
+                bounds(1.0, 1.0, 300.0, 200.0),
+                next,
+            ))
+            .unwrap();
+        assert_eq!(ended.window, 1);
+        assert!(ended.moved && !ended.resized);
+        let other = debouncer.flush().unwrap();
+        assert_eq!((other.window, other.previous), (2, None));
+    }
+}
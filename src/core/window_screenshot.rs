@@ -0,0 +1,553 @@
+// src/core/window_screenshot.rs
+//! Screenshots of the focused window on each switch
+//!
+//! A lightweight version of the `chrome_ui_segment` capture: on a foreground
+//! event the region of the app's focused window is captured with
+//! `screencapture` to a PNG named after the capture time and bundle id, and
+//! the event carries the file's path. Captures are rate-limited, and skipped
+//! for excluded titles and during quiet hours.
+//!
+//! Private and incognito browser windows are never captured. Chrome, Edge
+//! and Arc are asked over AppleScript; for other browsers (Safari has no
+//! scriptable private state) only a title marker can tell, so their windows
+//! are skipped unless they are known not to be private. Likewise a window
+//! without a title is skipped when titles are excluded.
+//!
+//! `screencapture` runs on a capture thread so it does not hold up event
+//! delivery; the event names the file before it is written, and a failed
+//! capture leaves no file. `screencapture` needs Screen Recording
+//! permission; without it the image shows only the desktop, so capture is
+//! switched off unless the permission is granted at startup.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Sender};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent};
+use crate::core::ax_element::AxElement;
+use crate::core::browser_strategy::BrowserStrategy;
+use crate::core::enrichment::{EnrichmentPhase, EnrichmentStage};
+use crate::core::quiet_hours::QuietHoursGate;
+use crate::core::running_apps::infer_app_category;
+use crate::core::title_exclusion::TitleExclusion;
+use crate::core::window_geometry::WindowBounds;
+
+/// Minimum time between two screenshots
+pub const DEFAULT_SCREENSHOT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Title fragments browsers use for private windows
+const PRIVATE_WINDOW_MARKERS: &[&str] = &["Incognito", "Private Browsing", "InPrivate"];
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+    fn CGRequestScreenCaptureAccess() -> bool;
+}
+
+/// The Screen Recording permission; mocked in tests
+pub trait ScreenRecordingPermission {
+    /// Whether the process may capture the screen, without prompting
+    fn preflight(&self) -> bool;
+
+    /// Ask for the permission; macOS prompts at most once per app and
+    /// usually only applies a grant after a relaunch
+    fn request(&self) -> bool;
+}
+
+/// The permission as macOS reports it
+pub struct SystemScreenRecording;
+
+impl ScreenRecordingPermission for SystemScreenRecording {
+    fn preflight(&self) -> bool {
+        // SAFETY: takes no arguments and only reads the TCC state
+        unsafe { CGPreflightScreenCaptureAccess() }
+    }
+
+    fn request(&self) -> bool {
+        // SAFETY: takes no arguments; shows the system prompt if needed
+        unsafe { CGRequestScreenCaptureAccess() }
+    }
+}
+
+/// Whether window screenshots will be taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotCapability {
+    /// `--capture-screenshots` was not given
+    Off,
+    Enabled,
+    /// Asked for, but Screen Recording is not granted
+    PermissionDenied,
+}
+
+impl ScreenshotCapability {
+    /// Check the permission when screenshots are wanted, asking for it if
+    /// it has not been granted yet
+    pub fn resolve(requested: bool, permission: &dyn ScreenRecordingPermission) -> Self {
+        if !requested {
+            ScreenshotCapability::Off
+        } else if permission.preflight() || permission.request() {
+            ScreenshotCapability::Enabled
+        } else {
+            ScreenshotCapability::PermissionDenied
+        }
+    }
+
+    pub fn is_enabled(self) -> bool {
+        self == ScreenshotCapability::Enabled
+    }
+}
+
+/// Whether a window title marks a private or incognito browser window
+pub fn is_private_window(title: &str) -> bool {
+    PRIVATE_WINDOW_MARKERS
+        .iter()
+        .any(|marker| title.contains(marker))
+}
+
+/// `20250314T091502.123Z_com.apple.Safari.png`; characters other than
+/// letters, digits, `.`, `-` and `_` in the bundle id become `-`
+pub fn screenshot_file_name(at: DateTime<Utc>, bundle_id: &str) -> String {
+    let bundle: String = bundle_id
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let bundle = if bundle.is_empty() {
+        "unknown"
+    } else {
+        &bundle
+    };
+    format!("{}_{}.png", at.format("%Y%m%dT%H%M%S%.3fZ"), bundle)
+}
+
+/// Allows one capture per interval
+#[derive(Debug)]
+pub struct ScreenshotRateLimiter {
+    min_interval: Duration,
+    last: Option<Instant>,
+}
+
+impl ScreenshotRateLimiter {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last: None,
+        }
+    }
+
+    /// Whether a capture at `now` is allowed; an allowed capture starts
+    /// the next interval
+    pub fn allow(&mut self, now: Instant) -> bool {
+        if let Some(last) = self.last {
+            if now.saturating_duration_since(last) < self.min_interval {
+                return false;
+            }
+        }
+        self.last = Some(now);
+        true
+    }
+}
+
+/// Finds the focused window and whether it is private; mocked in tests
+pub trait WindowLookup: Send {
+    /// Bounds of the focused window of `pid`, in screen points
+    fn focused_window_bounds(&mut self, pid: i32) -> Option<WindowBounds>;
+
+    /// Whether the focused window of `app` is private; `None` when unknown
+    fn is_private(&mut self, app: &AppInfo) -> Option<bool>;
+}
+
+/// AXFocusedWindow bounds and the browser's private window state
+pub struct AxWindowLookup;
+
+impl WindowLookup for AxWindowLookup {
+    fn focused_window_bounds(&mut self, pid: i32) -> Option<WindowBounds> {
+        let window = AxElement::application(pid)?.element("AXFocusedWindow")?;
+        WindowBounds::of(&window)
+    }
+
+    fn is_private(&mut self, app: &AppInfo) -> Option<bool> {
+        if infer_app_category(app) != "browser" {
+            return Some(false);
+        }
+        BrowserStrategy::for_bundle_id(&app.bundle_id)?.is_private_window()
+    }
+}
+
+/// Writes a window capture; runs on the capture thread, mocked in tests
+pub trait WindowCapture: Send {
+    /// Write a PNG of `bounds` to `path`
+    fn capture(&mut self, bounds: &WindowBounds, path: &Path) -> Result<(), String>;
+}
+
+/// `screencapture -R`
+pub struct ScreencaptureCommand;
+
+impl WindowCapture for ScreencaptureCommand {
+    fn capture(&mut self, bounds: &WindowBounds, path: &Path) -> Result<(), String> {
+        let rect = format!(
+            "{},{},{},{}",
+            bounds.x.round() as i64,
+            bounds.y.round() as i64,
+            bounds.width.round() as i64,
+            bounds.height.round() as i64
+        );
+        let status = Command::new("screencapture")
+            .args(["-x", "-o", "-R", &rect])
+            .arg(path)
+            .status()
+            .map_err(|e| format!("Failed to run screencapture: {}", e))?;
+        if !status.success() {
+            return Err(format!("screencapture exited with {}", status));
+        }
+        Ok(())
+    }
+}
+
+/// Attaches a screenshot of the focused window to foreground events
+pub struct WindowScreenshotter {
+    dir: PathBuf,
+    limiter: ScreenshotRateLimiter,
+    lookup: Box<dyn WindowLookup>,
+    /// Captures for the capture thread, which ends when this is dropped
+    captures: Sender<(WindowBounds, PathBuf)>,
+    quiet_hours: Option<QuietHoursGate>,
+    title_exclusion: Option<TitleExclusion>,
+}
+
+impl WindowScreenshotter {
+    /// Screenshots are written to `dir`, at most one per `min_interval`
+    pub fn new(dir: PathBuf, min_interval: Duration) -> Self {
+        Self::with_capture(
+            dir,
+            min_interval,
+            Box::new(AxWindowLookup),
+            Box::new(ScreencaptureCommand),
+        )
+    }
+
+    pub fn with_capture(
+        dir: PathBuf,
+        min_interval: Duration,
+        lookup: Box<dyn WindowLookup>,
+        mut capture: Box<dyn WindowCapture>,
+    ) -> Self {
+        let (captures, pending) = mpsc::channel::<(WindowBounds, PathBuf)>();
+        std::thread::spawn(move || {
+            for (bounds, path) in pending {
+                if let Err(e) = capture.capture(&bounds, &path) {
+                    tracing::debug!("Window screenshot failed: {}", e);
+                }
+            }
+        });
+        Self {
+            dir,
+            limiter: ScreenshotRateLimiter::new(min_interval),
+            lookup,
+            captures,
+            quiet_hours: None,
+            title_exclusion: None,
+        }
+    }
+
+    /// Take no screenshots while `gate` is quiet
+    pub fn with_quiet_hours(mut self, gate: QuietHoursGate) -> Self {
+        self.quiet_hours = Some(gate);
+        self
+    }
+
+    /// Take no screenshots of windows whose title is excluded
+    pub fn with_title_exclusion(mut self, exclusion: TitleExclusion) -> Self {
+        self.title_exclusion = Some(exclusion);
+        self
+    }
+
+    /// The window title as the event reports it, from either source
+    fn title(event: &AppSwitchEvent) -> Option<&str> {
+        event
+            .workspace
+            .as_ref()
+            .and_then(|w| w.focused_title.as_deref())
+            .or_else(|| {
+                event
+                    .enhanced
+                    .as_ref()
+                    .and_then(|e| e.front_window_title.as_deref())
+            })
+    }
+
+    /// Whether the window may be captured; unknown private state or an
+    /// unknown title under title exclusion counts as no
+    fn may_capture(&mut self, event: &AppSwitchEvent) -> bool {
+        let title = Self::title(event);
+        if title.is_some_and(is_private_window) {
+            return false;
+        }
+        if let Some(exclusion) = &self.title_exclusion {
+            match title {
+                Some(title) if !exclusion.is_excluded(title) => {}
+                _ => return false,
+            }
+        }
+        self.lookup.is_private(&event.app_info) == Some(false)
+    }
+}
+
+impl EnrichmentStage for WindowScreenshotter {
+    fn name(&self) -> &str {
+        "window_screenshot"
+    }
+
+    /// Before redaction, which may remove the title that marks a window
+    /// as private
+    fn phase(&self) -> EnrichmentPhase {
+        EnrichmentPhase::Annotate
+    }
+
+    fn enrich(&mut self, event: &mut AppSwitchEvent) {
+        if !event.event_type.is_foreground() {
+            return;
+        }
+        if self
+            .quiet_hours
+            .as_ref()
+            .is_some_and(|gate| gate.is_quiet())
+        {
+            return;
+        }
+        if !self.may_capture(event) {
+            return;
+        }
+        let Some(bounds) = self.lookup.focused_window_bounds(event.app_info.pid) else {
+            return;
+        };
+        if bounds.width < 1.0 || bounds.height < 1.0 {
+            return;
+        }
+        if !self.limiter.allow(event.timestamp) {
+            return;
+        }
+        let path = self
+            .dir
+            .join(screenshot_file_name(Utc::now(), &event.app_info.bundle_id));
+        event.screenshot_path = Some(path.display().to_string());
+        if self.captures.send((bounds, path)).is_err() {
+            tracing::debug!("Window screenshot failed: the capture thread has ended");
+            event.screenshot_path = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppSwitchType, EnhancedSummary};
+    use crate::core::title_exclusion::TitleExclusionConfig;
+    use chrono::TimeZone;
+    use std::cell::Cell;
+
+    /// A window of fixed size; apps named `Private` are private, `Unknown`
+    /// cannot tell
+    struct MockLookup;
+
+    impl WindowLookup for MockLookup {
+        fn focused_window_bounds(&mut self, pid: i32) -> Option<WindowBounds> {
+            (pid != 0).then_some(WindowBounds {
+                x: 10.0,
+                y: 40.0,
+                width: 1200.0,
+                height: 800.0,
+            })
+        }
+
+        fn is_private(&mut self, app: &AppInfo) -> Option<bool> {
+            match app.name.as_str() {
+                "Private" => Some(true),
+                "Unknown" => None,
+                _ => Some(false),
+            }
+        }
+    }
+
+    /// Reports where it was asked to capture
+    struct MockCapture(mpsc::Sender<(WindowBounds, PathBuf)>);
+
+    impl WindowCapture for MockCapture {
+        fn capture(&mut self, bounds: &WindowBounds, path: &Path) -> Result<(), String> {
+            self.0.send((*bounds, path.to_path_buf())).unwrap();
+            Ok(())
+        }
+    }
+
+    fn switch(name: &str, title: Option<&str>, at: Instant) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new(name.to_string(), "com.google.Chrome".to_string(), 7),
+        );
+        event.enhanced = Some(EnhancedSummary {
+            front_window_title: title.map(str::to_string),
+            ..EnhancedSummary::default()
+        });
+        event.timestamp = at;
+        event
+    }
+
+    #[test]
+    fn test_screenshots_are_named_and_rate_limited() {
+        let at = Utc.with_ymd_and_hms(2025, 3, 14, 9, 15, 2).unwrap();
+        assert_eq!(
+            screenshot_file_name(at, "com.apple.Safari"),
+            "20250314T091502.000Z_com.apple.Safari.png"
+        );
+        assert_eq!(
+            screenshot_file_name(at, "Google Chrome/Helper"),
+            "20250314T091502.000Z_Google-Chrome-Helper.png"
+        );
+        assert_eq!(
+            screenshot_file_name(at, " "),
+            "20250314T091502.000Z_unknown.png"
+        );
+
+        let (sent, captured) = mpsc::channel();
+        let mut screenshotter = WindowScreenshotter::with_capture(
+            PathBuf::from("/tmp/shots"),
+            Duration::from_secs(30),
+            Box::new(MockLookup),
+            Box::new(MockCapture(sent)),
+        );
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        let mut first = switch("Chrome", Some("Docs"), at(0));
+        screenshotter.enrich(&mut first);
+        let path = first.screenshot_path.clone().unwrap();
+        assert!(path.starts_with("/tmp/shots/") && path.ends_with("_com.google.Chrome.png"));
+
+        // Within the interval: skipped
+        let mut second = switch("Chrome", Some("Docs"), at(10));
+        screenshotter.enrich(&mut second);
+        assert_eq!(second.screenshot_path, None);
+
+        // Private windows are never captured and do not use up the interval
+        let mut private = switch(
+            "Chrome",
+            Some("New Tab - Google Chrome (Incognito)"),
+            at(40),
+        );
+        screenshotter.enrich(&mut private);
+        assert_eq!(private.screenshot_path, None);
+        let mut third = switch("Chrome", Some("Mail"), at(41));
+        screenshotter.enrich(&mut third);
+        assert!(third.screenshot_path.is_some());
+
+        let wait = Duration::from_secs(5);
+        let (bounds, written) = captured.recv_timeout(wait).unwrap();
+        assert_eq!(bounds.width, 1200.0);
+        assert_eq!(written.display().to_string(), path);
+        let (_, written) = captured.recv_timeout(wait).unwrap();
+        assert_eq!(
+            written.display().to_string(),
+            third.screenshot_path.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unknown_private_state_is_not_captured() {
+        let (sent, captured) = mpsc::channel();
+        let mut screenshotter = WindowScreenshotter::with_capture(
+            PathBuf::from("/tmp/shots"),
+            Duration::ZERO,
+            Box::new(MockLookup),
+            Box::new(MockCapture(sent)),
+        )
+        .with_title_exclusion(
+            TitleExclusion::from_config(&TitleExclusionConfig {
+                patterns: vec!["Payroll".to_string()],
+            })
+            .unwrap(),
+        );
+        let now = Instant::now();
+
+        // A private window without a title marker, and a browser that
+        // cannot tell
+        for name in ["Private", "Unknown"] {
+            let mut event = switch(name, Some("Docs"), now);
+            screenshotter.enrich(&mut event);
+            assert_eq!(event.screenshot_path, None, "{}", name);
+        }
+        // Without a title, exclusion cannot be checked
+        let mut untitled = switch("Chrome", None, now);
+        screenshotter.enrich(&mut untitled);
+        assert_eq!(untitled.screenshot_path, None);
+
+        let mut known = switch("Chrome", Some("Docs"), now);
+        screenshotter.enrich(&mut known);
+        assert!(known.screenshot_path.is_some());
+        drop(screenshotter);
+        assert_eq!(captured.iter().count(), 1);
+    }
+
+    /// Fixed permission states, counting prompts
+    struct MockPermission {
+        granted: bool,
+        granted_on_request: bool,
+        requests: Cell<u32>,
+    }
+
+    impl MockPermission {
+        fn new(granted: bool, granted_on_request: bool) -> Self {
+            Self {
+                granted,
+                granted_on_request,
+                requests: Cell::new(0),
+            }
+        }
+    }
+
+    impl ScreenRecordingPermission for MockPermission {
+        fn preflight(&self) -> bool {
+            self.granted
+        }
+
+        fn request(&self) -> bool {
+            self.requests.set(self.requests.get() + 1);
+            self.granted_on_request
+        }
+    }
+
+    #[test]
+    fn test_capture_needs_screen_recording() {
+        // Not asked for: no prompt, whatever the permission
+        let denied = MockPermission::new(false, false);
+        assert_eq!(
+            ScreenshotCapability::resolve(false, &denied),
+            ScreenshotCapability::Off
+        );
+        assert_eq!(denied.requests.get(), 0);
+
+        // Already granted: no prompt
+        let granted = MockPermission::new(true, false);
+        assert!(ScreenshotCapability::resolve(true, &granted).is_enabled());
+        assert_eq!(granted.requests.get(), 0);
+
+        // Granted from the prompt
+        let prompted = MockPermission::new(false, true);
+        assert!(ScreenshotCapability::resolve(true, &prompted).is_enabled());
+        assert_eq!(prompted.requests.get(), 1);
+
+        // Refused: capture stays off
+        let capability = ScreenshotCapability::resolve(true, &denied);
+        assert_eq!(capability, ScreenshotCapability::PermissionDenied);
+        assert!(!capability.is_enabled());
+        assert_eq!(denied.requests.get(), 1);
+    }
+}
@@ -0,0 +1,320 @@
+// src/core/xcode.rs
+//! Active file, project and scheme in Xcode
+//!
+//! Xcode's AppleScript dictionary exposes the active workspace document, its
+//! active scheme and the open source documents, which give absolute paths.
+//! All three are read with one `osascript` run, reused while the window title
+//! stays the same. When scripting is unavailable (automation permission denied, Xcode busy)
+//! the window title still carries the file and project:
+//! `filename — ProjectName`, with ` — Edited` appended while unsaved.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::core::accessibility::AccessibilityContext;
+use crate::core::browser_strategy::{
+    parse_applescript_output, run_applescript, APPLESCRIPT_CACHE_TTL,
+};
+
+pub const XCODE_BUNDLE_ID: &str = "com.apple.dt.Xcode";
+
+/// Separator Xcode uses in window titles (em dash)
+const TITLE_SEPARATOR: &str = " \u{2014} ";
+
+/// Parsed Xcode window title
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XcodeTitle {
+    pub file: String,
+    pub project: Option<String>,
+    /// The title ends with `— Edited`
+    pub edited: bool,
+}
+
+/// Parse `filename — ProjectName[ — Edited]`
+pub fn parse_xcode_title(title: &str) -> Option<XcodeTitle> {
+    let mut parts: Vec<&str> = title.trim().split(TITLE_SEPARATOR).map(str::trim).collect();
+    let edited = parts.len() > 1 && parts.last() == Some(&"Edited");
+    if edited {
+        parts.pop();
+    }
+    let file = parts.first().filter(|f| !f.is_empty())?;
+    Some(XcodeTitle {
+        file: file.to_string(),
+        project: parts
+            .get(1)
+            .filter(|p| !p.is_empty())
+            .map(|p| p.to_string()),
+        edited,
+    })
+}
+
+/// Quote a value for inclusion in an AppleScript string literal
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Project name from a `.xcodeproj` / `.xcworkspace` path
+fn project_name_from_path(path: &str) -> Option<String> {
+    Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+}
+
+/// What Xcode reports over AppleScript
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XcodeScriptResult {
+    pub document_path: Option<String>,
+    pub workspace_path: Option<String>,
+    pub scheme: Option<String>,
+}
+
+/// One script for document path, workspace path and scheme, one per line;
+/// each read is wrapped in `try` so a failure leaves only its line empty
+fn xcode_script(file: Option<&str>) -> String {
+    let document = match file {
+        Some(file) => format!(
+            "try\n\t\tset docPath to path of first source document whose name is {}\n\tend try",
+            applescript_string(file)
+        ),
+        None => String::new(),
+    };
+    format!(
+        "tell application \"Xcode\"\n\
+         \tset docPath to \"\"\n\
+         \t{}\n\
+         \tset wsPath to \"\"\n\
+         \ttry\n\
+         \t\tset wsPath to path of active workspace document\n\
+         \tend try\n\
+         \tset schemeName to \"\"\n\
+         \ttry\n\
+         \t\tset schemeName to name of active scheme of active workspace document\n\
+         \tend try\n\
+         \treturn docPath & linefeed & wsPath & linefeed & schemeName\n\
+         end tell",
+        document
+    )
+}
+
+/// Split the script's output into its three lines
+pub fn parse_xcode_script_output(output: &str) -> XcodeScriptResult {
+    let mut lines = output.trim_end_matches('\n').split('\n');
+    let mut next = || lines.next().and_then(parse_applescript_output);
+    XcodeScriptResult {
+        document_path: next(),
+        workspace_path: next(),
+        scheme: next(),
+    }
+}
+
+/// Fills file, project and scheme for Xcode windows
+pub struct XcodeExtractor {
+    ttl: Duration,
+    /// Window title the result was read for, and when
+    cached: Option<(String, Instant, XcodeScriptResult)>,
+}
+
+impl Default for XcodeExtractor {
+    fn default() -> Self {
+        Self::new(APPLESCRIPT_CACHE_TTL)
+    }
+}
+
+impl XcodeExtractor {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, cached: None }
+    }
+
+    pub fn applies_to(bundle_id: &str) -> bool {
+        bundle_id == XCODE_BUNDLE_ID
+    }
+
+    /// Populate `active_file_path`, `project_name` and `is_document_modified`
+    pub fn apply(&mut self, context: &mut AccessibilityContext) {
+        let parsed = context.window_title.as_deref().and_then(parse_xcode_title);
+        if let Some(parsed) = &parsed {
+            apply_title(context, parsed);
+        }
+
+        let file = parsed.as_ref().map(|p| p.file.as_str());
+        let result = self.get_or_fetch(context.window_title.as_deref(), Instant::now(), || {
+            run_applescript(&xcode_script(file))
+                .map(|out| parse_xcode_script_output(&out))
+                .unwrap_or_default()
+        });
+
+        if let Some(path) = result.document_path {
+            context.active_file_path = Some(path);
+        }
+        if let Some(workspace) = result.workspace_path {
+            if let Some(project) = project_name_from_path(&workspace) {
+                context.project_name = Some(project);
+            }
+            context
+                .raw_attributes
+                .insert("xcode_workspace".to_string(), workspace);
+        }
+        if let Some(scheme) = result.scheme {
+            context
+                .raw_attributes
+                .insert("xcode_scheme".to_string(), scheme);
+        }
+    }
+
+    /// The cached result if read for `window_title` within the TTL,
+    /// otherwise the result of `fetch`; without a title nothing is cached
+    fn get_or_fetch(
+        &mut self,
+        window_title: Option<&str>,
+        now: Instant,
+        fetch: impl FnOnce() -> XcodeScriptResult,
+    ) -> XcodeScriptResult {
+        let Some(window_title) = window_title else {
+            self.cached = None;
+            return fetch();
+        };
+        if let Some((title, at, result)) = &self.cached {
+            if title == window_title && now.saturating_duration_since(*at) < self.ttl {
+                return result.clone();
+            }
+        }
+        let result = fetch();
+        self.cached = Some((window_title.to_string(), now, result.clone()));
+        result
+    }
+}
+
+/// Title-only fallback; Xcode always marks unsaved files, so no marker means saved
+pub fn apply_title(context: &mut AccessibilityContext, title: &XcodeTitle) {
+    context.active_file_path = Some(title.file.clone());
+    context.project_name = title.project.clone();
+    context.is_document_modified = Some(title.edited);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn title(file: &str, project: Option<&str>, edited: bool) -> Option<XcodeTitle> {
+        Some(XcodeTitle {
+            file: file.to_string(),
+            project: project.map(str::to_string),
+            edited,
+        })
+    }
+
+    #[test]
+    fn test_title_fallback() {
+        assert_eq!(
+            parse_xcode_title("ContentView.swift \u{2014} Weather"),
+            title("ContentView.swift", Some("Weather"), false)
+        );
+        assert_eq!(
+            parse_xcode_title("ContentView.swift \u{2014} Weather \u{2014} Edited"),
+            title("ContentView.swift", Some("Weather"), true)
+        );
+        assert_eq!(
+            parse_xcode_title("Weather.xcodeproj"),
+            title("Weather.xcodeproj", None, false)
+        );
+        assert_eq!(parse_xcode_title(""), None);
+    }
+
+    #[test]
+    fn test_edited_marker_sets_document_modified() {
+        use crate::core::app_switcher_types::AppInfo;
+        use std::collections::HashMap;
+
+        let title = "main.swift \u{2014} CLI \u{2014} Edited";
+        let mut context = AccessibilityContext {
+            app_info: AppInfo::new("Xcode".to_string(), XCODE_BUNDLE_ID.to_string(), 1),
+            window_title: Some(title.to_string()),
+            document_path: None,
+            document_path_confidence: None,
+            is_document_modified: None,
+            current_url: None,
+            page_title: None,
+            tab_count: None,
+            browser_profile: None,
+            sheet_name: None,
+            selected_range: None,
+            mail_folder: None,
+            conversation: None,
+            current_reference: None,
+            reference_collection: None,
+            terminal_command: None,
+            active_file_path: None,
+            project_name: None,
+            selected_text: None,
+            focused_element: None,
+            ui_path: Vec::new(),
+            raw_attributes: HashMap::new(),
+            windows: Vec::new(),
+            custom_fields: HashMap::new(),
+        };
+        apply_title(&mut context, &parse_xcode_title(title).unwrap());
+        assert_eq!(context.is_document_modified, Some(true));
+        assert_eq!(context.active_file_path.as_deref(), Some("main.swift"));
+        assert_eq!(context.project_name.as_deref(), Some("CLI"));
+
+        apply_title(
+            &mut context,
+            &parse_xcode_title("main.swift \u{2014} CLI").unwrap(),
+        );
+        assert_eq!(context.is_document_modified, Some(false));
+    }
+
+    #[test]
+    fn test_one_script_run_per_window_title() {
+        let mut extractor = XcodeExtractor::new(Duration::from_secs(1));
+        let start = Instant::now();
+        let runs = std::cell::Cell::new(0);
+        let fetch = || {
+            runs.set(runs.get() + 1);
+            parse_xcode_script_output("/p/main.swift\n/p/CLI.xcodeproj\nCLI\n")
+        };
+
+        let first = extractor.get_or_fetch(Some("main.swift \u{2014} CLI"), start, fetch);
+        assert_eq!(first.scheme.as_deref(), Some("CLI"));
+        extractor.get_or_fetch(Some("main.swift \u{2014} CLI"), start, fetch);
+        assert_eq!(runs.get(), 1);
+
+        extractor.get_or_fetch(Some("lib.swift \u{2014} CLI"), start, fetch);
+        extractor.get_or_fetch(
+            Some("lib.swift \u{2014} CLI"),
+            start + Duration::from_secs(2),
+            fetch,
+        );
+        assert_eq!(runs.get(), 3);
+    }
+
+    #[test]
+    fn test_script_output_lines() {
+        assert_eq!(
+            parse_xcode_script_output("/p/main.swift\n/p/CLI.xcodeproj\nCLI\n"),
+            XcodeScriptResult {
+                document_path: Some("/p/main.swift".to_string()),
+                workspace_path: Some("/p/CLI.xcodeproj".to_string()),
+                scheme: Some("CLI".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_xcode_script_output("\n/p/CLI.xcodeproj\n\n"),
+            XcodeScriptResult {
+                workspace_path: Some("/p/CLI.xcodeproj".to_string()),
+                ..XcodeScriptResult::default()
+            }
+        );
+        assert!(xcode_script(None).contains("active scheme"));
+        assert!(xcode_script(Some("a.swift")).contains("whose name is \"a.swift\""));
+    }
+
+    #[test]
+    fn test_script_helpers() {
+        assert_eq!(applescript_string(r#"a "b".swift"#), r#""a \"b\".swift""#);
+        assert_eq!(
+            project_name_from_path("/Users/me/Weather/Weather.xcworkspace"),
+            Some("Weather".to_string())
+        );
+    }
+}
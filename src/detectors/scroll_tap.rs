@@ -84,6 +84,7 @@ extern "C" {
     fn CFRelease(cf: *const c_void);
 }
 
+/// Scrolling since the previous event; deltas of throttled events are summed
 #[derive(Debug, Clone)]
 pub struct ScrollEvent {
     pub timestamp: Instant,
@@ -102,6 +103,8 @@ struct ScrollState {
     listeners: Vec<Box<dyn ScrollListener>>,
     min_interval: Duration,
     last_emit: Instant,
+    pending_vertical: i64,
+    pending_horizontal: i64,
 }
 
 static mut GLOBAL_STATE: Option<Arc<Mutex<ScrollState>>> = None;
@@ -116,6 +119,8 @@ impl ScrollTap {
                     listeners: Vec::new(),
                     min_interval,
                     last_emit: Instant::now(),
+                    pending_vertical: 0,
+                    pending_horizontal: 0,
                 })));
             }
         }
@@ -186,13 +191,15 @@ extern "C" fn tap_callback(
 
         if let Some(st) = &GLOBAL_STATE {
             if let Ok(mut s) = st.lock() {
+                s.pending_vertical += v;
+                s.pending_horizontal += h;
                 if s.last_emit.elapsed() >= s.min_interval {
                     s.last_emit = Instant::now();
                     let (pid, bundle_id, name) = frontmost_triplet();
                     let ev = ScrollEvent {
                         timestamp: Instant::now(),
-                        vertical_px: v,
-                        horizontal_px: h,
+                        vertical_px: std::mem::take(&mut s.pending_vertical),
+                        horizontal_px: std::mem::take(&mut s.pending_horizontal),
                         frontmost_pid: pid,
                         frontmost_bundle_id: bundle_id,
                         frontmost_name: name,
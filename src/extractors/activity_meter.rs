@@ -0,0 +1,183 @@
+// src/extractors/activity_meter.rs
+//! Input activity level for engagement scoring
+//!
+//! Counts keystrokes, clicks and scroll distance from the event tap and turns
+//! them into an engagement score between 0 and 100. Only the *fact* that a
+//! key was pressed is recorded - never which key - so the meter can run
+//! alongside everything else without capturing typed content.
+//!
+//! Score formula, per minute of input:
+//! - keys:   keystrokes / 180 (about 3 keys/sec of steady typing)
+//! - clicks: clicks / 30
+//! - scroll: scroll distance / 300 (lines)
+//!
+//! Each channel is clamped to 1.0 and combined as `1 - Π(1 - channel)`, so a
+//! single saturated channel already means full engagement while several
+//! light channels add up. The result is scaled to 0-100.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::core::event_tap::{EventInfo, MouseAction};
+
+const KEYS_PER_MINUTE_FULL: f64 = 180.0;
+const CLICKS_PER_MINUTE_FULL: f64 = 30.0;
+const SCROLL_PER_MINUTE_FULL: f64 = 300.0;
+
+/// A single input signal, stripped of its content
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputSignal {
+    Keystroke,
+    Click,
+    Scroll { distance: f64 },
+}
+
+impl InputSignal {
+    /// Map an event tap event to a signal, dropping keycodes and positions
+    pub fn from_event(event: &EventInfo) -> Option<Self> {
+        match event {
+            EventInfo::Keyboard(_) => Some(InputSignal::Keystroke),
+            EventInfo::Mouse {
+                action: MouseAction::Down,
+                ..
+            } => Some(InputSignal::Click),
+            EventInfo::Mouse { .. } => None,
+            EventInfo::Scroll {
+                delta_x, delta_y, ..
+            } => Some(InputSignal::Scroll {
+                distance: delta_x.hypot(*delta_y),
+            }),
+        }
+    }
+}
+
+struct MeterState {
+    retention: Duration,
+    signals: VecDeque<(Instant, InputSignal)>,
+}
+
+/// Rolling keyboard/mouse activity, shared between the event tap and listeners
+#[derive(Clone)]
+pub struct ActivityMeter {
+    state: Arc<Mutex<MeterState>>,
+}
+
+impl ActivityMeter {
+    /// Create a meter that keeps signals for `retention` (the longest span
+    /// that can be scored)
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MeterState {
+                retention,
+                signals: VecDeque::new(),
+            })),
+        }
+    }
+
+    pub fn record_at(&self, signal: InputSignal, at: Instant) {
+        let mut state = self.state.lock().unwrap();
+        state.signals.push_back((at, signal));
+        let retention = state.retention;
+        while let Some((ts, _)) = state.signals.front() {
+            if at.saturating_duration_since(*ts) > retention {
+                state.signals.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn record_event(&self, event: &EventInfo) {
+        if let Some(signal) = InputSignal::from_event(event) {
+            self.record_at(signal, Instant::now());
+        }
+    }
+
+    /// Engagement over the last minute
+    pub fn engagement_score(&self, now: Instant) -> f64 {
+        let start = now.checked_sub(Duration::from_secs(60)).unwrap_or(now);
+        self.score_between(start, now)
+    }
+
+    /// Engagement over an arbitrary span, normalized to per-minute rates
+    pub fn score_between(&self, start: Instant, end: Instant) -> f64 {
+        let minutes = end.saturating_duration_since(start).as_secs_f64() / 60.0;
+        if minutes <= 0.0 {
+            return 0.0;
+        }
+
+        let (mut keys, mut clicks, mut scroll) = (0.0, 0.0, 0.0);
+        let state = self.state.lock().unwrap();
+        for (ts, signal) in state.signals.iter() {
+            if *ts < start || *ts > end {
+                continue;
+            }
+            match signal {
+                InputSignal::Keystroke => keys += 1.0,
+                InputSignal::Click => clicks += 1.0,
+                InputSignal::Scroll { distance } => scroll += distance,
+            }
+        }
+
+        let channel = |value: f64, full: f64| (value / minutes / full).min(1.0);
+        let idle = (1.0 - channel(keys, KEYS_PER_MINUTE_FULL))
+            * (1.0 - channel(clicks, CLICKS_PER_MINUTE_FULL))
+            * (1.0 - channel(scroll, SCROLL_PER_MINUTE_FULL));
+        (1.0 - idle) * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_reflects_intensity() {
+        let meter = ActivityMeter::new(Duration::from_secs(3600));
+        let start = Instant::now();
+        let at = |secs: f64| start + Duration::from_secs_f64(secs);
+
+        // Minute 1: idle. Minute 2: light reading. Minute 3: heavy typing.
+        for i in 0..6 {
+            meter.record_at(
+                InputSignal::Scroll { distance: 5.0 },
+                at(60.0 + i as f64 * 10.0),
+            );
+        }
+        for i in 0..150 {
+            meter.record_at(InputSignal::Keystroke, at(120.0 + i as f64 * 0.4));
+        }
+
+        let idle = meter.score_between(at(0.0), at(59.0));
+        let reading = meter.score_between(at(60.0), at(120.0));
+        let typing = meter.engagement_score(at(180.0));
+
+        assert_eq!(idle, 0.0);
+        assert!(
+            reading > 0.0 && reading < typing,
+            "{} vs {}",
+            reading,
+            typing
+        );
+        assert!(typing > 80.0, "{}", typing);
+        assert!(typing <= 100.0);
+    }
+
+    #[test]
+    fn test_signals_carry_no_keycodes() {
+        use crate::core::event_tap::{CGEventFlags, KeyboardEvent};
+
+        let event = EventInfo::Keyboard(KeyboardEvent {
+            keycode: 12,
+            flags: CGEventFlags(0),
+            timestamp: Instant::now(),
+            is_shortcut: false,
+            shortcut_type: None,
+        });
+        assert_eq!(
+            InputSignal::from_event(&event),
+            Some(InputSignal::Keystroke)
+        );
+    }
+}
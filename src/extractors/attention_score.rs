@@ -0,0 +1,173 @@
+// src/extractors/attention_score.rs
+//! Attention score from switch frequency and dwell times
+//!
+//! How fragmented attention was over the last few minutes: many short visits
+//! spread over many apps score low, long stays in one or two apps score high.
+//! The score is computed over a rolling window (10 minutes by default) from
+//! the foreground switches in it, and is attached to each focus block as it
+//! closes.
+//!
+//! Formula, over the window:
+//! - switches: switches per minute / 4, clamped to 1.0
+//! - dwell:    1 - median dwell / 5 minutes, clamped to 0.0
+//! - spread:   Shannon entropy of the time share per app, divided by the
+//!   entropy of an even split over the same apps (0.0 for a single app)
+//!
+//! Fragmentation is the mean of the three, and the score is
+//! `100 * (1 - fragmentation)`: 100 for one uninterrupted app, near 0 for
+//! switching between many apps every few seconds. An empty window scores 100.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Default span the score is computed over
+pub const DEFAULT_ATTENTION_WINDOW: Duration = Duration::from_secs(600);
+
+/// Switch rate at which the switch component saturates
+const SWITCHES_PER_MINUTE_FRAGMENTED: f64 = 4.0;
+
+/// Median dwell at which the dwell component reaches zero
+const FOCUSED_DWELL_SECS: f64 = 300.0;
+
+/// Rolling attention score over the foreground switches in a window
+pub struct AttentionScorer {
+    window: Duration,
+    /// Foreground switches, oldest first: when, and to which app
+    switches: VecDeque<(Instant, String)>,
+}
+
+impl Default for AttentionScorer {
+    fn default() -> Self {
+        Self::new(DEFAULT_ATTENTION_WINDOW)
+    }
+}
+
+impl AttentionScorer {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            switches: VecDeque::new(),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Record a switch to `app`; switches older than the window are dropped,
+    /// except the last one, which tells what was in focus at its start
+    pub fn record_switch_at(&mut self, app: &str, at: Instant) {
+        self.switches.push_back((at, app.to_string()));
+        while self.switches.len() > 1
+            && at.saturating_duration_since(self.switches[1].0) > self.window
+        {
+            self.switches.pop_front();
+        }
+    }
+
+    /// Dwell periods in the window ending at `now`, clipped to the window
+    fn dwells(&self, now: Instant) -> Vec<(&str, Duration)> {
+        let start = now.checked_sub(self.window).unwrap_or(now);
+        let mut dwells = Vec::new();
+        for (i, (at, app)) in self.switches.iter().enumerate() {
+            let end = self.switches.get(i + 1).map_or(now, |(next, _)| *next);
+            let from = (*at).max(start);
+            if end > from {
+                dwells.push((app.as_str(), end - from));
+            }
+        }
+        dwells
+    }
+
+    /// 0-100; higher is more focused
+    pub fn score_at(&self, now: Instant) -> f64 {
+        let dwells = self.dwells(now);
+        let total: f64 = dwells.iter().map(|(_, d)| d.as_secs_f64()).sum();
+        if total <= 0.0 {
+            return 100.0;
+        }
+        let start = now.checked_sub(self.window).unwrap_or(now);
+        let switch_count = self
+            .switches
+            .iter()
+            .filter(|(at, _)| *at > start && *at <= now)
+            .count();
+        let minutes = self.window.as_secs_f64() / 60.0;
+        let switches = (switch_count as f64 / minutes / SWITCHES_PER_MINUTE_FRAGMENTED).min(1.0);
+
+        let mut secs: Vec<f64> = dwells.iter().map(|(_, d)| d.as_secs_f64()).collect();
+        secs.sort_by(f64::total_cmp);
+        let median = if secs.len() % 2 == 1 {
+            secs[secs.len() / 2]
+        } else {
+            (secs[secs.len() / 2 - 1] + secs[secs.len() / 2]) / 2.0
+        };
+        let dwell = (1.0 - median / FOCUSED_DWELL_SECS).max(0.0);
+
+        let mut per_app: HashMap<&str, f64> = HashMap::new();
+        for (app, d) in &dwells {
+            *per_app.entry(app).or_default() += d.as_secs_f64();
+        }
+        let spread = if per_app.len() > 1 {
+            let entropy: f64 = per_app
+                .values()
+                .map(|secs| secs / total)
+                .filter(|p| *p > 0.0)
+                .map(|p| -p * p.ln())
+                .sum();
+            entropy / (per_app.len() as f64).ln()
+        } else {
+            0.0
+        };
+
+        let fragmentation = (switches + dwell + spread) / 3.0;
+        100.0 * (1.0 - fragmentation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focused_scores_above_thrashing() {
+        let window = Duration::from_secs(600);
+        let t0 = Instant::now();
+        let at = |secs: u64| t0 + Duration::from_secs(secs);
+        let end = at(600);
+
+        // Two long stretches in an editor with one look at the docs
+        let mut focused = AttentionScorer::new(window);
+        focused.record_switch_at("editor", at(0));
+        focused.record_switch_at("browser", at(280));
+        focused.record_switch_at("editor", at(320));
+
+        // A different app every 10 seconds
+        let mut thrashing = AttentionScorer::new(window);
+        for (i, app) in ["mail", "chat", "browser", "editor", "terminal"]
+            .iter()
+            .cycle()
+            .take(60)
+            .enumerate()
+        {
+            thrashing.record_switch_at(app, at(i as u64 * 10));
+        }
+
+        let focused_score = focused.score_at(end);
+        let thrashing_score = thrashing.score_at(end);
+        assert!(
+            focused_score > thrashing_score,
+            "{} <= {}",
+            focused_score,
+            thrashing_score
+        );
+        assert!(focused_score > 50.0);
+        assert!(thrashing_score < 20.0);
+
+        // A single uninterrupted app and an empty window are fully focused
+        let mut single = AttentionScorer::new(window);
+        single.record_switch_at("editor", t0);
+        assert_eq!(single.score_at(end + window), 100.0);
+        assert_eq!(AttentionScorer::new(window).score_at(end), 100.0);
+    }
+}
@@ -0,0 +1,294 @@
+// src/extractors/clipboard_stats.rs
+//! Copy-paste statistics
+//!
+//! Quantifies copy-paste workflows over a session: how many copies and
+//! pastes there were, which content types were copied, which apps were
+//! copied from most, how long a copy waited before its first paste, and how
+//! many copies were pasted into a different app than they came from
+//! (cross-app flows).
+//!
+//! Copies come from the clipboard monitor. The pasteboard does not report
+//! reads, so pastes are Cmd+V shortcuts from the event tap, attributed to the
+//! app in the foreground at the time. Only types and app ids are counted,
+//! never content. The statistics are written as JSON when monitoring stops.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchListener};
+use crate::core::clipboard::{ClipboardEvent, ClipboardListener};
+use crate::core::event_tap::{EventInfo, ShortcutType};
+
+/// Source apps listed in the exported statistics
+pub const DEFAULT_TOP_SOURCE_APPS: usize = 5;
+
+/// Copies from one app
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceAppCount {
+    pub app: String,
+    pub copies: u64,
+}
+
+/// The exported statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClipboardStatsReport {
+    pub copies: u64,
+    pub pastes: u64,
+    /// Copies carrying each pasteboard type; one copy usually has several
+    pub types: BTreeMap<String, u64>,
+    /// Most copied-from apps, most copies first
+    pub top_source_apps: Vec<SourceAppCount>,
+    /// Mean time from a copy to its first paste
+    pub average_copy_to_paste_secs: Option<f64>,
+    /// Copies pasted into another app than their source
+    pub cross_app_flows: u64,
+}
+
+/// The latest copy and what happened to it since
+struct PendingCopy {
+    at: DateTime<Utc>,
+    source: Option<String>,
+    pasted: bool,
+    crossed: bool,
+}
+
+#[derive(Default)]
+struct Tally {
+    copies: u64,
+    pastes: u64,
+    types: BTreeMap<String, u64>,
+    source_apps: HashMap<String, u64>,
+    last_copy: Option<PendingCopy>,
+    copy_to_paste_secs: f64,
+    pasted_copies: u64,
+    cross_app_flows: u64,
+    /// Bundle id of the app pastes go to
+    frontmost: Option<String>,
+}
+
+/// Aggregates copies and pastes, shared between the clipboard monitor, the
+/// event tap and the switcher
+#[derive(Clone)]
+pub struct ClipboardStats {
+    tally: Arc<Mutex<Tally>>,
+    top_source_apps: usize,
+    output: Option<PathBuf>,
+}
+
+impl ClipboardStats {
+    pub fn new() -> Self {
+        Self {
+            tally: Arc::new(Mutex::new(Tally::default())),
+            top_source_apps: DEFAULT_TOP_SOURCE_APPS,
+            output: None,
+        }
+    }
+
+    /// Write the statistics to `path` when monitoring stops
+    pub fn with_output(mut self, path: &Path) -> Self {
+        self.output = Some(path.to_path_buf());
+        self
+    }
+
+    pub fn record_copy(&self, event: &ClipboardEvent) {
+        let mut tally = self.tally.lock().unwrap();
+        tally.copies += 1;
+        for pasteboard_type in &event.types {
+            *tally.types.entry(pasteboard_type.clone()).or_default() += 1;
+        }
+        let source = event
+            .source_bundle_id
+            .clone()
+            .filter(|id| !id.trim().is_empty())
+            .or_else(|| event.source_app.clone());
+        if let Some(source) = &source {
+            *tally.source_apps.entry(source.clone()).or_default() += 1;
+        }
+        tally.last_copy = Some(PendingCopy {
+            at: event.timestamp,
+            source,
+            pasted: false,
+            crossed: false,
+        });
+    }
+
+    /// A paste into the foreground app at `at`
+    pub fn record_paste(&self, at: DateTime<Utc>) {
+        let mut tally = self.tally.lock().unwrap();
+        tally.pastes += 1;
+        let frontmost = tally.frontmost.clone();
+        let Some(copy) = tally.last_copy.as_mut() else {
+            return;
+        };
+        let first_paste = !copy.pasted;
+        let gap = (at - copy.at).to_std().unwrap_or_default().as_secs_f64();
+        copy.pasted = true;
+        let crossed = !copy.crossed
+            && copy.source.is_some()
+            && frontmost.is_some()
+            && copy.source != frontmost;
+        copy.crossed |= crossed;
+        if first_paste {
+            tally.copy_to_paste_secs += gap;
+            tally.pasted_copies += 1;
+        }
+        if crossed {
+            tally.cross_app_flows += 1;
+        }
+    }
+
+    /// Count Cmd+V shortcuts from the event tap as pastes
+    pub fn record_input(&self, event: &EventInfo) {
+        if let EventInfo::Keyboard(key) = event {
+            if key.shortcut_type == Some(ShortcutType::Paste) {
+                self.record_paste(Utc::now());
+            }
+        }
+    }
+
+    pub fn report(&self) -> ClipboardStatsReport {
+        let tally = self.tally.lock().unwrap();
+        let mut top_source_apps: Vec<SourceAppCount> = tally
+            .source_apps
+            .iter()
+            .map(|(app, copies)| SourceAppCount {
+                app: app.clone(),
+                copies: *copies,
+            })
+            .collect();
+        top_source_apps.sort_by(|a, b| b.copies.cmp(&a.copies).then_with(|| a.app.cmp(&b.app)));
+        top_source_apps.truncate(self.top_source_apps);
+        ClipboardStatsReport {
+            copies: tally.copies,
+            pastes: tally.pastes,
+            types: tally.types.clone(),
+            top_source_apps,
+            average_copy_to_paste_secs: (tally.pasted_copies > 0)
+                .then(|| tally.copy_to_paste_secs / tally.pasted_copies as f64),
+            cross_app_flows: tally.cross_app_flows,
+        }
+    }
+
+    pub fn write_to(&self, path: &Path) -> io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(&self.report())?)
+    }
+}
+
+impl Default for ClipboardStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClipboardListener for ClipboardStats {
+    fn on_clipboard_change(&mut self, event: &ClipboardEvent) {
+        self.record_copy(event);
+    }
+}
+
+impl AppSwitchListener for ClipboardStats {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        if event.event_type.is_foreground() {
+            self.tally.lock().unwrap().frontmost = Some(event.app_info.bundle_id.clone());
+        }
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        if let Some(path) = &self.output {
+            if let Err(e) = self.write_to(path) {
+                eprintln!("⚠️  Failed to write clipboard statistics: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType};
+
+    fn copy(secs: i64, bundle_id: &str, types: &[&str]) -> ClipboardEvent {
+        ClipboardEvent {
+            timestamp: at(secs),
+            change_count: secs as isize,
+            types: types.iter().map(|t| t.to_string()).collect(),
+            source_app: None,
+            source_bundle_id: Some(bundle_id.to_string()),
+            image: None,
+            sensitive: None,
+            content_length: None,
+            context: None,
+        }
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn focus(stats: &mut ClipboardStats, bundle_id: &str) {
+        stats.on_app_switch(&AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new(bundle_id.to_string(), bundle_id.to_string(), 1),
+        ));
+    }
+
+    #[test]
+    fn test_copy_paste_statistics() {
+        let mut stats = ClipboardStats::new();
+        let text = ["public.utf8-plain-text"];
+        let rich = ["public.utf8-plain-text", "public.html"];
+
+        // Safari → Word, pasted twice; only the first paste is timed
+        focus(&mut stats, "com.apple.Safari");
+        stats.on_clipboard_change(&copy(0, "com.apple.Safari", &rich));
+        focus(&mut stats, "com.microsoft.Word");
+        stats.record_paste(at(10));
+        stats.record_paste(at(50));
+
+        // Copied and pasted within Word: not a cross-app flow
+        stats.on_clipboard_change(&copy(60, "com.microsoft.Word", &text));
+        stats.record_paste(at(62));
+
+        // Safari → Notes, then copied again and never pasted
+        focus(&mut stats, "com.apple.Safari");
+        stats.on_clipboard_change(&copy(100, "com.apple.Safari", &text));
+        focus(&mut stats, "com.apple.Notes");
+        stats.record_paste(at(106));
+        stats.on_clipboard_change(&copy(200, "com.apple.Safari", &rich));
+
+        let report = stats.report();
+        assert_eq!(report.copies, 4);
+        assert_eq!(report.pastes, 4);
+        assert_eq!(report.types["public.utf8-plain-text"], 4);
+        assert_eq!(report.types["public.html"], 2);
+        assert_eq!(
+            report.top_source_apps,
+            vec![
+                SourceAppCount {
+                    app: "com.apple.Safari".to_string(),
+                    copies: 3
+                },
+                SourceAppCount {
+                    app: "com.microsoft.Word".to_string(),
+                    copies: 1
+                },
+            ]
+        );
+        // (10 + 2 + 6) / 3 pasted copies
+        assert_eq!(report.average_copy_to_paste_secs, Some(6.0));
+        assert_eq!(report.cross_app_flows, 2);
+
+        // Nothing copied yet: pastes count, nothing else does
+        let empty = ClipboardStats::new();
+        empty.record_paste(at(0));
+        let report = empty.report();
+        assert_eq!((report.copies, report.pastes), (0, 1));
+        assert_eq!(report.average_copy_to_paste_secs, None);
+        assert_eq!(report.cross_app_flows, 0);
+    }
+}
@@ -0,0 +1,132 @@
+// src/extractors/dwell_period.rs
+//! Dwell periods reduced to one record each
+//!
+//! Several trackers collect samples while an app is frontmost and, on the
+//! next foreground switch, reduce them to a single record for that dwell
+//! period. `DwellLog` holds the part they share: the open period, the
+//! minimum duration, the JSON lines output and the records emitted so far.
+//! Each tracker supplies the reduction.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs::File;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::core::app_switcher_types::AppInfo;
+use crate::core::buffered_sink::{BufferedSink, FlushPolicy};
+
+/// A finished dwell period, handed to the tracker's reduction
+#[derive(Debug, Clone)]
+pub struct DwellPeriod {
+    pub app: AppInfo,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub duration: Duration,
+}
+
+/// The open dwell period and the records of closed ones
+pub struct DwellLog<T> {
+    /// What a record is, for error messages
+    what: &'static str,
+    min_duration: Duration,
+    output: Option<BufferedSink<File>>,
+
+    current: Option<(AppInfo, Instant, DateTime<Utc>)>,
+    records: Vec<T>,
+}
+
+impl<T: Serialize> DwellLog<T> {
+    pub fn new(what: &'static str, min_duration: Duration) -> Self {
+        Self {
+            what,
+            min_duration,
+            output: None,
+            current: None,
+            records: Vec::new(),
+        }
+    }
+
+    /// Append records to a JSON lines file
+    pub fn open_output(&mut self, path: &Path, policy: FlushPolicy) -> std::io::Result<()> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        self.output = Some(BufferedSink::new(file, policy));
+        Ok(())
+    }
+
+    pub fn records(&self) -> &[T] {
+        &self.records
+    }
+
+    /// Start a period for `app`, closing the previous one
+    ///
+    /// A closed period at least `min_duration` long is passed to `reduce`;
+    /// the record it returns, if any, is kept and written out.
+    pub fn switch_to_at(
+        &mut self,
+        app: Option<&AppInfo>,
+        now: Instant,
+        wall: DateTime<Utc>,
+        reduce: impl FnOnce(DwellPeriod) -> Option<T>,
+    ) {
+        if let Some((prev, started, started_wall)) = self.current.take() {
+            let duration = now.saturating_duration_since(started);
+            if duration >= self.min_duration {
+                let period = DwellPeriod {
+                    app: prev,
+                    start: started_wall,
+                    end: wall,
+                    duration,
+                };
+                if let Some(record) = reduce(period) {
+                    self.emit(record);
+                }
+            }
+        }
+        self.current = app.map(|a| (a.clone(), now, wall));
+    }
+
+    fn emit(&mut self, record: T) {
+        if let Some(sink) = &self.output {
+            match serde_json::to_string(&record) {
+                Ok(line) => {
+                    if let Err(e) = sink.write_line(&line) {
+                        eprintln!("⚠️  Failed to write {}: {}", self.what, e);
+                    }
+                }
+                Err(e) => eprintln!("⚠️  Failed to serialize {}: {}", self.what, e),
+            }
+        }
+        self.records.push(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_periods_are_not_reduced() {
+        let mut log = DwellLog::new("period", Duration::from_secs(5));
+        let start = Instant::now();
+        let wall = Utc::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        // Nothing is open yet, so nothing is reduced
+        log.switch_to_at(
+            Some(&AppInfo::example("a")),
+            at(0),
+            wall,
+            |_| -> Option<String> { panic!("no period was open") },
+        );
+        log.switch_to_at(Some(&AppInfo::example("b")), at(10), wall, |period| {
+            Some(format!("{} {}", period.app.name, period.duration.as_secs()))
+        });
+        log.switch_to_at(None, at(12), wall, |_| panic!("below the minimum"));
+
+        assert_eq!(log.records(), ["a 10".to_string()]);
+    }
+}
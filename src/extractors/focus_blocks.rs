@@ -0,0 +1,223 @@
+// src/extractors/focus_blocks.rs
+//! Focus blocks
+//!
+//! A focus block is one uninterrupted stretch of a single app in the
+//! foreground. Blocks are closed on the next foreground switch, annotated with
+//! whatever aggregate metrics are available (engagement from the
+//! `ActivityMeter`, the rolling attention score from the `AttentionScorer`),
+//! and appended as JSON lines to an output file.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent, AppSwitchListener};
+use crate::core::buffered_sink::{BufferedSink, FlushPolicy};
+use crate::extractors::activity_meter::ActivityMeter;
+use crate::extractors::attention_score::AttentionScorer;
+
+/// One uninterrupted period in a single app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusBlock {
+    pub app_name: String,
+    pub bundle_id: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub duration_secs: f64,
+
+    /// 0-100 input engagement during the block, when the activity meter runs
+    pub engagement_score: Option<f64>,
+
+    /// 0-100 attention score over the window ending with the block, when
+    /// attention scoring is enabled
+    pub attention_score: Option<f64>,
+}
+
+/// Splits the switch stream into focus blocks
+pub struct FocusBlockTracker {
+    min_duration: Duration,
+    activity: Option<ActivityMeter>,
+    attention: Option<AttentionScorer>,
+    output: Option<BufferedSink<File>>,
+
+    current: Option<(AppInfo, Instant, DateTime<Utc>)>,
+    blocks: Vec<FocusBlock>,
+}
+
+impl FocusBlockTracker {
+    pub fn new(min_duration: Duration) -> Self {
+        Self {
+            min_duration,
+            activity: None,
+            attention: None,
+            output: None,
+            current: None,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Score each block with input engagement
+    pub fn with_activity(mut self, meter: ActivityMeter) -> Self {
+        self.activity = Some(meter);
+        self
+    }
+
+    /// Score each block with how fragmented attention was over `window`
+    pub fn with_attention(mut self, window: Duration) -> Self {
+        self.attention = Some(AttentionScorer::new(window));
+        self
+    }
+
+    /// Append finished blocks to a JSON lines file
+    pub fn with_output(mut self, path: &Path, policy: FlushPolicy) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        self.output = Some(BufferedSink::new(file, policy));
+        Ok(self)
+    }
+
+    pub fn blocks(&self) -> &[FocusBlock] {
+        &self.blocks
+    }
+
+    /// Start a block for `app`, closing the previous one
+    pub fn switch_to_at(&mut self, app: Option<&AppInfo>, now: Instant, wall: DateTime<Utc>) {
+        if let Some((prev, started, started_wall)) = self.current.take() {
+            let duration = now.saturating_duration_since(started);
+            if duration >= self.min_duration {
+                let block = FocusBlock {
+                    app_name: prev.name,
+                    bundle_id: prev.bundle_id,
+                    start: started_wall,
+                    end: wall,
+                    duration_secs: duration.as_secs_f64(),
+                    engagement_score: self
+                        .activity
+                        .as_ref()
+                        .map(|meter| meter.score_between(started, now)),
+                    attention_score: self.attention.as_ref().map(|scorer| scorer.score_at(now)),
+                };
+                self.emit(block);
+            }
+        }
+        if let (Some(scorer), Some(app)) = (&mut self.attention, app) {
+            scorer.record_switch_at(&app.bundle_id, now);
+        }
+        self.current = app.map(|a| (a.clone(), now, wall));
+    }
+
+    /// Close the open block without starting another, e.g. on sleep or
+    /// pause; the `Foreground` event after wake or resume opens the next
+    pub fn close_at(&mut self, now: Instant, wall: DateTime<Utc>) {
+        self.switch_to_at(None, now, wall);
+    }
+
+    fn emit(&mut self, block: FocusBlock) {
+        if let Some(sink) = &self.output {
+            match serde_json::to_string(&block) {
+                Ok(line) => {
+                    if let Err(e) = sink.write_line(&line) {
+                        eprintln!("⚠️  Failed to write focus block: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("⚠️  Failed to serialize focus block: {}", e),
+            }
+        }
+        self.blocks.push(block);
+    }
+}
+
+impl AppSwitchListener for FocusBlockTracker {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        if event.event_type.is_foreground() {
+            self.switch_to_at(Some(&event.app_info), Instant::now(), Utc::now());
+        }
+    }
+
+    fn on_monitoring_paused(&mut self) {
+        self.close_at(Instant::now(), Utc::now());
+    }
+
+    fn on_system_sleep(&mut self) {
+        self.close_at(Instant::now(), Utc::now());
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        self.close_at(Instant::now(), Utc::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractors::activity_meter::InputSignal;
+
+    #[test]
+    fn test_blocks_carry_engagement() {
+        let meter = ActivityMeter::new(Duration::from_secs(3600));
+        let mut tracker =
+            FocusBlockTracker::new(Duration::from_secs(5)).with_activity(meter.clone());
+        let start = Instant::now();
+        let wall = Utc::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        tracker.switch_to_at(Some(&AppInfo::example("Editor")), at(0), wall);
+        for i in 0..120 {
+            meter.record_at(InputSignal::Keystroke, at(i / 2));
+        }
+        tracker.switch_to_at(Some(&AppInfo::example("Video")), at(60), wall);
+        tracker.switch_to_at(Some(&AppInfo::example("Flash")), at(120), wall);
+        tracker.switch_to_at(None, at(122), wall);
+
+        let blocks = tracker.blocks();
+        assert_eq!(blocks.len(), 2, "sub-minimum block is dropped");
+        assert_eq!(blocks[0].app_name, "Editor");
+        assert_eq!(blocks[1].app_name, "Video");
+        assert!(blocks[0].engagement_score.unwrap() > 50.0);
+        assert_eq!(blocks[1].engagement_score, Some(0.0));
+        assert_eq!(blocks[0].attention_score, None);
+    }
+
+    #[test]
+    fn test_sleep_closes_the_open_block() {
+        let mut tracker = FocusBlockTracker::new(Duration::from_secs(5));
+        let start = Instant::now();
+        let wall = Utc::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        tracker.switch_to_at(Some(&AppInfo::example("Editor")), at(0), wall);
+        // Asleep for an hour, then the wake's Foreground event
+        tracker.close_at(at(60), wall);
+        tracker.switch_to_at(Some(&AppInfo::example("Editor")), at(3660), wall);
+        tracker.close_at(at(3720), wall);
+
+        let durations: Vec<f64> = tracker.blocks().iter().map(|b| b.duration_secs).collect();
+        assert_eq!(durations, vec![60.0, 60.0]);
+    }
+
+    #[test]
+    fn test_blocks_carry_attention_score() {
+        let mut tracker =
+            FocusBlockTracker::new(Duration::from_secs(1)).with_attention(Duration::from_secs(600));
+        let start = Instant::now();
+        let wall = Utc::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        tracker.switch_to_at(Some(&AppInfo::example("Editor")), at(0), wall);
+        tracker.switch_to_at(Some(&AppInfo::example("Mail")), at(300), wall);
+        for i in 0..20 {
+            let name = if i % 2 == 0 { "Chat" } else { "Mail" };
+            tracker.switch_to_at(Some(&AppInfo::example(name)), at(305 + i * 5), wall);
+        }
+        tracker.switch_to_at(None, at(410), wall);
+
+        let blocks = tracker.blocks();
+        let first = blocks[0].attention_score.unwrap();
+        let last = blocks.last().unwrap().attention_score.unwrap();
+        assert!(first > last, "{} <= {}", first, last);
+    }
+}
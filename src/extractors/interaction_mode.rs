@@ -0,0 +1,271 @@
+// src/extractors/interaction_mode.rs
+//! Interaction modes
+//!
+//! The role of the focused UI element says a lot about what the user is doing
+//! without looking at any content: a text area that keeps focus means writing,
+//! a web area means reading, buttons and lists mean moving around. A sampler
+//! thread records the focused role of the frontmost app at a fixed interval;
+//! on each foreground switch the samples of the dwell period are reduced to a
+//! single coarse mode and appended as JSON lines to an output file.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::core::accessibility::ax_focused_role;
+use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent, AppSwitchListener};
+use crate::core::buffered_sink::FlushPolicy;
+use crate::core::running_apps::frontmost_app;
+use crate::extractors::dwell_period::DwellLog;
+
+/// Share of samples a category needs to define the period's mode
+const DOMINANT_SHARE: f64 = 0.6;
+
+/// Coarse description of what the user was doing in a dwell period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionMode {
+    /// Typing into text areas or fields
+    Composing,
+    /// Focus on web content or scrollable documents
+    Reading,
+    /// Buttons, lists, tables and menus
+    Navigating,
+    /// No category dominated
+    Mixed,
+}
+
+impl InteractionMode {
+    /// Category of a single focused-element role; unknown roles are ignored
+    pub fn for_role(role: &str) -> Option<Self> {
+        match role {
+            "AXTextArea" | "AXTextField" | "AXComboBox" | "AXSearchField" => Some(Self::Composing),
+            "AXWebArea" | "AXScrollArea" | "AXStaticText" | "AXDocument" => Some(Self::Reading),
+            "AXButton" | "AXList" | "AXOutline" | "AXTable" | "AXRow" | "AXCell" | "AXLink"
+            | "AXMenuItem" | "AXTabGroup" | "AXRadioButton" | "AXPopUpButton" | "AXCheckBox" => {
+                Some(Self::Navigating)
+            }
+            _ => None,
+        }
+    }
+
+    /// Reduce a dwell period's role samples to one mode
+    pub fn classify<S: AsRef<str>>(roles: &[S]) -> Option<Self> {
+        let categories: Vec<Self> = roles
+            .iter()
+            .filter_map(|role| Self::for_role(role.as_ref()))
+            .collect();
+        if categories.is_empty() {
+            return None;
+        }
+        let (mode, count) = [Self::Composing, Self::Reading, Self::Navigating]
+            .into_iter()
+            .map(|mode| (mode, categories.iter().filter(|c| **c == mode).count()))
+            .max_by_key(|(_, count)| *count)?;
+        if count as f64 / categories.len() as f64 >= DOMINANT_SHARE {
+            Some(mode)
+        } else {
+            Some(Self::Mixed)
+        }
+    }
+}
+
+/// One dwell period reduced to its interaction mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InteractionPeriod {
+    pub app_name: String,
+    pub bundle_id: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub duration_secs: f64,
+    pub mode: InteractionMode,
+    /// Number of role samples the mode was derived from
+    pub samples: usize,
+}
+
+/// Samples focused-element roles into an `InteractionModeTracker`
+pub struct RoleSampler {
+    roles: Arc<Mutex<Vec<String>>>,
+    sampling: Arc<AtomicBool>,
+}
+
+impl RoleSampler {
+    /// Sample the frontmost app's focused role on a background thread
+    pub fn start(&self, interval: Duration) {
+        if self.sampling.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let roles = Arc::clone(&self.roles);
+        let sampling = Arc::clone(&self.sampling);
+
+        std::thread::spawn(move || {
+            while sampling.load(Ordering::SeqCst) {
+                std::thread::sleep(interval);
+                if let Some(role) = frontmost_app().and_then(|app| ax_focused_role(app.pid)) {
+                    roles.lock().unwrap().push(role);
+                }
+            }
+        });
+    }
+}
+
+/// Records focused-element roles and emits a mode per dwell period
+pub struct InteractionModeTracker {
+    log: DwellLog<InteractionPeriod>,
+    roles: Arc<Mutex<Vec<String>>>,
+    sampling: Arc<AtomicBool>,
+}
+
+impl InteractionModeTracker {
+    pub fn new(min_duration: Duration) -> Self {
+        Self {
+            log: DwellLog::new("interaction period", min_duration),
+            roles: Arc::new(Mutex::new(Vec::new())),
+            sampling: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Append finished periods to a JSON lines file
+    pub fn with_output(mut self, path: &Path, policy: FlushPolicy) -> std::io::Result<Self> {
+        self.log.open_output(path, policy)?;
+        Ok(self)
+    }
+
+    /// Sample the frontmost app's focused role on a background thread
+    pub fn start_sampling(&self, interval: Duration) {
+        self.sampler().start(interval);
+    }
+
+    /// Handle that starts sampling into this tracker later
+    pub fn sampler(&self) -> RoleSampler {
+        RoleSampler {
+            roles: Arc::clone(&self.roles),
+            sampling: Arc::clone(&self.sampling),
+        }
+    }
+
+    pub fn stop_sampling(&self) {
+        self.sampling.store(false, Ordering::SeqCst);
+    }
+
+    /// Record one focused-element role sample for the current period
+    pub fn record_role(&self, role: &str) {
+        self.roles.lock().unwrap().push(role.to_string());
+    }
+
+    pub fn periods(&self) -> &[InteractionPeriod] {
+        self.log.records()
+    }
+
+    /// Start a period for `app`, closing the previous one
+    pub fn switch_to_at(&mut self, app: Option<&AppInfo>, now: Instant, wall: DateTime<Utc>) {
+        let roles = std::mem::take(&mut *self.roles.lock().unwrap());
+        self.log.switch_to_at(app, now, wall, |period| {
+            InteractionMode::classify(&roles).map(|mode| InteractionPeriod {
+                app_name: period.app.name,
+                bundle_id: period.app.bundle_id,
+                start: period.start,
+                end: period.end,
+                duration_secs: period.duration.as_secs_f64(),
+                mode,
+                samples: roles.len(),
+            })
+        });
+    }
+}
+
+impl AppSwitchListener for InteractionModeTracker {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        if event.event_type.is_foreground() {
+            self.switch_to_at(Some(&event.app_info), Instant::now(), Utc::now());
+        }
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        self.stop_sampling();
+        self.switch_to_at(None, Instant::now(), Utc::now());
+    }
+}
+
+impl Drop for InteractionModeTracker {
+    fn drop(&mut self) {
+        self.stop_sampling();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_samples_map_to_modes() {
+        let composing = [
+            "AXTextArea",
+            "AXTextArea",
+            "AXTextArea",
+            "AXButton",
+            "AXTextArea",
+        ];
+        assert_eq!(
+            InteractionMode::classify(&composing),
+            Some(InteractionMode::Composing)
+        );
+
+        let reading = ["AXWebArea", "AXWebArea", "AXGroup", "AXWebArea", "AXLink"];
+        assert_eq!(
+            InteractionMode::classify(&reading),
+            Some(InteractionMode::Reading)
+        );
+
+        let navigating = ["AXList", "AXButton", "AXRow", "AXTextField"];
+        assert_eq!(
+            InteractionMode::classify(&navigating),
+            Some(InteractionMode::Navigating)
+        );
+
+        let mixed = [
+            "AXTextArea",
+            "AXWebArea",
+            "AXButton",
+            "AXTextArea",
+            "AXWebArea",
+        ];
+        assert_eq!(
+            InteractionMode::classify(&mixed),
+            Some(InteractionMode::Mixed)
+        );
+
+        assert_eq!(InteractionMode::classify(&["AXGroup", "AXWindow"]), None);
+        assert_eq!(InteractionMode::classify::<&str>(&[]), None);
+    }
+
+    #[test]
+    fn test_periods_use_samples_from_their_dwell() {
+        let mut tracker = InteractionModeTracker::new(Duration::from_secs(5));
+        let start = Instant::now();
+        let wall = Utc::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        tracker.switch_to_at(Some(&AppInfo::example("Notes")), at(0), wall);
+        for _ in 0..10 {
+            tracker.record_role("AXTextArea");
+        }
+        tracker.switch_to_at(Some(&AppInfo::example("Safari")), at(60), wall);
+        for _ in 0..4 {
+            tracker.record_role("AXWebArea");
+        }
+        tracker.switch_to_at(Some(&AppInfo::example("Finder")), at(90), wall);
+        tracker.record_role("AXList");
+        tracker.switch_to_at(None, at(91), wall);
+
+        let periods = tracker.periods();
+        assert_eq!(periods.len(), 2, "sub-minimum period is dropped");
+        assert_eq!(periods[0].app_name, "Notes");
+        assert_eq!(periods[0].mode, InteractionMode::Composing);
+        assert_eq!(periods[0].samples, 10);
+        assert_eq!(periods[1].mode, InteractionMode::Reading);
+    }
+}
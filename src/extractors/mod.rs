@@ -1,11 +1,34 @@
 // src/extractors/mod.rs
+pub mod activity_meter;
+pub mod attention_score;
+pub mod clipboard_stats;
+pub mod dwell_period;
+pub mod focus_blocks;
+pub mod interaction_mode;
+#[cfg(feature = "otel")]
+pub mod otel_sessions;
+pub mod recent_files;
+pub mod scroll_summary;
+pub mod streak_reminder;
+pub mod tag_aggregator;
 pub mod time_tracker;
+pub mod transition_graph;
+pub mod url_time_tracker;
 
 use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchListener};
+use crate::core::preview::truncate_preview;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub use activity_meter::ActivityMeter;
+pub use focus_blocks::{FocusBlock, FocusBlockTracker};
+pub use interaction_mode::{InteractionMode, InteractionModeTracker, InteractionPeriod};
+pub use scroll_summary::{ScrollSummary, ScrollSummaryTracker};
+pub use streak_reminder::{ReminderConfig, StreakReminder};
+pub use tag_aggregator::{TagConfig, TagTimeAggregator};
 pub use time_tracker::{TimeTracker, TimeTrackerConfig, AppSession, AppStatistics};
+pub use url_time_tracker::UrlTimeTracker;
 
 /// Enhanced context information extracted from applications
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,7 +46,7 @@ pub struct BasicAppInfo {
     pub timestamp: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum ContextValue {
     Text(String),
     Number(f64),
@@ -63,7 +86,7 @@ impl AppSwitchListener for SimpleLogger {
             LogFormat::Json => {
                 let json_event = serde_json::json!({
                     "timestamp": event.timestamp.elapsed().as_millis(),
-                    "event_type": format!("{:?}", event.event_type),
+                    "event_type": event.event_type.as_str(),
                     "app": {
                         "name": event.app_info.name,
                         "bundle_id": event.app_info.bundle_id,
@@ -81,7 +104,10 @@ impl AppSwitchListener for SimpleLogger {
                 println!("{}", serde_json::to_string_pretty(&json_event).unwrap());
             }
             LogFormat::Human => match event.event_type {
-                crate::core::app_switcher_types::AppSwitchType::Foreground => {
+                crate::core::app_switcher_types::AppSwitchType::Foreground
+                | crate::core::app_switcher_types::AppSwitchType::SpaceChange
+                | crate::core::app_switcher_types::AppSwitchType::SessionChange
+                | crate::core::app_switcher_types::AppSwitchType::Wake => {
                     println!(
                         "\n🔥 SWITCHED TO: {} ({})",
                         event.app_info.name, event.app_info.bundle_id
@@ -93,8 +119,13 @@ impl AppSwitchListener for SimpleLogger {
                 crate::core::app_switcher_types::AppSwitchType::Background => {
                     println!("📱 {} went to background", event.app_info.name);
                 }
-                _ => {
-                    println!("📋 {:?}: {}", event.event_type, event.app_info.name);
+                crate::core::app_switcher_types::AppSwitchType::Launch
+                | crate::core::app_switcher_types::AppSwitchType::Terminate
+                | crate::core::app_switcher_types::AppSwitchType::Hide
+                | crate::core::app_switcher_types::AppSwitchType::Unhide
+                | crate::core::app_switcher_types::AppSwitchType::Sleep
+                | crate::core::app_switcher_types::AppSwitchType::Heartbeat => {
+                    println!("📋 {}: {}", event.event_type.as_str(), event.app_info.name);
                 }
             },
         }
@@ -128,7 +159,7 @@ impl ContextAwareListener {
         let mut context = HashMap::new();
 
         for extractor in &self.extractors {
-            if extractor.applies_to(&app_info.bundle_id) {
+            if extractor.applies_to(app_info.match_key()) {
                 let extracted = extractor.extract_context(app_info);
                 context.extend(extracted);
             }
@@ -169,11 +200,7 @@ impl AppSwitchListener for ContextAwareListener {
                     for (key, value) in &app_context.enhanced_context {
                         match value {
                             ContextValue::Text(text) => {
-                                let preview = if text.len() > 100 {
-                                    format!("{}...", &text[..100])
-                                } else {
-                                    text.clone()
-                                };
+                                let preview = truncate_preview(text, 100);
                                 println!("     {}: {}", key, preview);
                             }
                             ContextValue::List(items) => {
@@ -0,0 +1,413 @@
+// src/extractors/otel_sessions.rs
+//! Focus sessions as OpenTelemetry spans
+//!
+//! Each focus session — the time between switching to an app and switching
+//! away from it — becomes a `focus_session` span carrying the app, its
+//! supported-apps category, the URL it started on and its duration. Within a
+//! session, every change of page or window (a foreground or heartbeat event
+//! for the same app with a different URL or title) becomes a child `switch`
+//! span, so a trace viewer shows what was done in each session.
+//!
+//! Spans are exported over OTLP/HTTP to the endpoint given with
+//! `--otel-endpoint`. Event times are monotonic, so span timestamps are
+//! anchored to the wall clock when the span is opened.
+
+use std::time::{Instant, SystemTime};
+
+use opentelemetry::trace::{Span, TraceContextExt, Tracer, TracerProvider};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+
+use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent, AppSwitchListener, AppSwitchType};
+use crate::core::supported_apps::SupportedApps;
+use crate::extractors::transition_graph::UNCATEGORIZED;
+
+/// Service name reported with every span
+const SERVICE_NAME: &str = "research-assistant-tracker";
+
+/// The page or window shown within a session
+#[derive(Debug, Clone, PartialEq)]
+struct Location {
+    url: Option<String>,
+    title: Option<String>,
+}
+
+impl Location {
+    fn of(event: &AppSwitchEvent) -> Self {
+        let ws = event.workspace.as_ref();
+        let enh = event.enhanced.as_ref();
+        Self {
+            url: ws
+                .and_then(|w| w.primary_url.clone())
+                .or_else(|| enh.and_then(|e| e.url.clone())),
+            title: ws
+                .and_then(|w| w.focused_title.clone())
+                .or_else(|| enh.and_then(|e| e.front_window_title.clone())),
+        }
+    }
+
+    fn attributes(&self) -> Vec<KeyValue> {
+        let mut attributes = Vec::new();
+        if let Some(url) = &self.url {
+            attributes.push(KeyValue::new("url.full", url.clone()));
+        }
+        if let Some(title) = &self.title {
+            attributes.push(KeyValue::new("window.title", title.clone()));
+        }
+        attributes
+    }
+}
+
+/// Child span for one location within the session
+struct OpenSwitch {
+    location: Location,
+    started: Instant,
+    span: opentelemetry_sdk::trace::Span,
+}
+
+/// The session span of the app in focus
+struct OpenSession {
+    app_key: String,
+    started: Instant,
+    context: Context,
+    switch: OpenSwitch,
+}
+
+/// Exports focus sessions as spans
+pub struct OtelSessionExporter {
+    provider: SdkTracerProvider,
+    tracer: SdkTracer,
+    categories: SupportedApps,
+    current: Option<OpenSession>,
+}
+
+impl OtelSessionExporter {
+    /// Export to an OTLP/HTTP collector, e.g. `http://localhost:4318/v1/traces`
+    pub fn new(endpoint: &str) -> Result<Self, String> {
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(endpoint)
+            .build()
+            .map_err(|e| format!("Invalid OTLP endpoint '{}': {}", endpoint, e))?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+            .build();
+        Ok(Self::with_provider(provider))
+    }
+
+    /// Export through an already configured provider
+    pub fn with_provider(provider: SdkTracerProvider) -> Self {
+        let tracer = provider.tracer("focus_sessions");
+        Self {
+            provider,
+            tracer,
+            categories: SupportedApps::builtin(),
+            current: None,
+        }
+    }
+
+    fn category(&self, app: &AppInfo) -> String {
+        self.categories
+            .category(&app.bundle_id)
+            .unwrap_or(UNCATEGORIZED)
+            .to_string()
+    }
+
+    fn open_switch(
+        tracer: &SdkTracer,
+        parent: &Context,
+        location: Location,
+        at: Instant,
+    ) -> OpenSwitch {
+        let span = tracer
+            .span_builder("switch")
+            .with_start_time(wall_clock(at))
+            .with_attributes(location.attributes())
+            .start_with_context(tracer, parent);
+        OpenSwitch {
+            location,
+            started: at,
+            span,
+        }
+    }
+
+    fn open_session(&mut self, event: &AppSwitchEvent) {
+        let app = &event.app_info;
+        let location = Location::of(event);
+        let mut attributes = vec![
+            KeyValue::new("app.name", app.name.clone()),
+            KeyValue::new("app.bundle_id", app.bundle_id.clone()),
+            KeyValue::new("app.category", self.category(app)),
+        ];
+        if let Some(url) = &location.url {
+            attributes.push(KeyValue::new("url.full", url.clone()));
+        }
+        let span = self
+            .tracer
+            .span_builder("focus_session")
+            .with_start_time(wall_clock(event.timestamp))
+            .with_attributes(attributes)
+            .start_with_context(&self.tracer, &Context::new());
+        let context = Context::new().with_span(span);
+        let switch = Self::open_switch(&self.tracer, &context, location, event.timestamp);
+        self.current = Some(OpenSession {
+            app_key: app.match_key().to_string(),
+            started: event.timestamp,
+            context,
+            switch,
+        });
+    }
+
+    fn close_switch(mut switch: OpenSwitch, at: Instant, end: SystemTime) {
+        let duration = at.saturating_duration_since(switch.started);
+        switch
+            .span
+            .set_attribute(KeyValue::new("duration_secs", duration.as_secs_f64()));
+        switch.span.end_with_timestamp(end);
+    }
+
+    fn close_session(&mut self, at: Instant) {
+        let Some(session) = self.current.take() else {
+            return;
+        };
+        let end = wall_clock(at);
+        Self::close_switch(session.switch, at, end);
+        let duration = at.saturating_duration_since(session.started);
+        let span = session.context.span();
+        span.set_attribute(KeyValue::new("duration_secs", duration.as_secs_f64()));
+        span.end_with_timestamp(end);
+    }
+
+    /// A new location in the app in focus starts a new child span
+    fn observe_location(&mut self, event: &AppSwitchEvent) {
+        let location = Location::of(event);
+        let Some(session) = self.current.as_mut() else {
+            return;
+        };
+        if location == session.switch.location {
+            return;
+        }
+        let next = Self::open_switch(&self.tracer, &session.context, location, event.timestamp);
+        let previous = std::mem::replace(&mut session.switch, next);
+        Self::close_switch(previous, event.timestamp, wall_clock(event.timestamp));
+    }
+
+    fn in_session(&self, app: &AppInfo) -> bool {
+        self.current
+            .as_ref()
+            .is_some_and(|s| s.app_key == app.match_key())
+    }
+}
+
+/// The wall-clock time of a monotonic event timestamp
+fn wall_clock(at: Instant) -> SystemTime {
+    let now = Instant::now();
+    if at <= now {
+        SystemTime::now() - now.duration_since(at)
+    } else {
+        SystemTime::now() + at.duration_since(now)
+    }
+}
+
+impl AppSwitchListener for OtelSessionExporter {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        match event.event_type {
+            AppSwitchType::Foreground
+            | AppSwitchType::SpaceChange
+            | AppSwitchType::SessionChange
+            | AppSwitchType::Wake
+                if self.in_session(&event.app_info) =>
+            {
+                self.observe_location(event)
+            }
+            AppSwitchType::Foreground
+            | AppSwitchType::SpaceChange
+            | AppSwitchType::SessionChange
+            | AppSwitchType::Wake => {
+                self.close_session(event.timestamp);
+                self.open_session(event);
+            }
+            AppSwitchType::Heartbeat if self.in_session(&event.app_info) => {
+                self.observe_location(event)
+            }
+            AppSwitchType::Terminate if self.in_session(&event.app_info) => {
+                self.close_session(event.timestamp)
+            }
+            AppSwitchType::Heartbeat | AppSwitchType::Terminate => {}
+            AppSwitchType::Background
+            | AppSwitchType::Launch
+            | AppSwitchType::Hide
+            | AppSwitchType::Unhide
+            | AppSwitchType::Sleep => {}
+        }
+    }
+
+    fn name(&self) -> &str {
+        "otel_sessions"
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        self.close_session(Instant::now());
+        if let Err(e) = self.provider.force_flush() {
+            tracing::warn!("Failed to flush focus session spans: {}", e);
+        }
+    }
+
+    fn on_monitoring_paused(&mut self) {
+        self.close_session(Instant::now());
+    }
+
+    fn on_system_sleep(&mut self) {
+        self.close_session(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::WorkspaceSummary;
+    use opentelemetry::Value;
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SpanData};
+    use std::time::Duration;
+
+    fn switch(
+        event_type: AppSwitchType,
+        name: &str,
+        bundle_id: &str,
+        url: Option<&str>,
+        at: Instant,
+    ) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            event_type,
+            AppInfo::new(name.to_string(), bundle_id.to_string(), 1),
+        );
+        event.timestamp = at;
+        event.workspace = Some(WorkspaceSummary {
+            window_count: 1,
+            focused_title: Some(format!("{} window", name)),
+            total_screen_coverage: None,
+            is_fullscreen: None,
+            is_minimized: None,
+            tab_titles: Vec::new(),
+            active_file_paths: Vec::new(),
+            primary_url: url.map(str::to_string),
+        });
+        event
+    }
+
+    fn attribute(span: &SpanData, key: &str) -> Option<Value> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.clone())
+    }
+
+    #[test]
+    fn test_sessions_are_spans_with_switches_as_children() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let mut sessions = OtelSessionExporter::with_provider(provider);
+
+        let t0 = Instant::now();
+        let secs = |n| t0 + Duration::from_secs(n);
+        for event in [
+            switch(
+                AppSwitchType::Foreground,
+                "Safari",
+                "com.apple.Safari",
+                Some("https://a.example/"),
+                secs(0),
+            ),
+            switch(
+                AppSwitchType::Heartbeat,
+                "Safari",
+                "com.apple.Safari",
+                Some("https://a.example/"),
+                secs(10),
+            ),
+            switch(
+                AppSwitchType::Heartbeat,
+                "Safari",
+                "com.apple.Safari",
+                Some("https://b.example/"),
+                secs(30),
+            ),
+            switch(
+                AppSwitchType::Foreground,
+                "Terminal",
+                "com.apple.Terminal",
+                None,
+                secs(50),
+            ),
+            switch(
+                AppSwitchType::Terminate,
+                "Terminal",
+                "com.apple.Terminal",
+                None,
+                secs(65),
+            ),
+        ] {
+            sessions.on_app_switch(&event);
+        }
+        sessions.on_monitoring_stopped();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let named =
+            |name: &str| -> Vec<&SpanData> { spans.iter().filter(|s| s.name == name).collect() };
+        let focus = named("focus_session");
+        assert_eq!(focus.len(), 2);
+        let (safari, terminal) = (focus[0], focus[1]);
+        assert_eq!(attribute(safari, "app.name"), Some(Value::from("Safari")));
+        assert_eq!(
+            attribute(safari, "app.category"),
+            Some(Value::from("browsers"))
+        );
+        assert_eq!(
+            attribute(safari, "url.full"),
+            Some(Value::from("https://a.example/"))
+        );
+        assert_eq!(attribute(safari, "duration_secs"), Some(Value::F64(50.0)));
+        assert_eq!(
+            attribute(terminal, "app.category"),
+            Some(Value::from("terminals"))
+        );
+        assert_eq!(attribute(terminal, "url.full"), None);
+        assert_eq!(attribute(terminal, "duration_secs"), Some(Value::F64(15.0)));
+        assert_ne!(
+            safari.span_context.trace_id(),
+            terminal.span_context.trace_id()
+        );
+
+        // The heartbeat on the same page did not start a new switch
+        let children: Vec<&SpanData> = named("switch")
+            .into_iter()
+            .filter(|s| s.parent_span_id == safari.span_context.span_id())
+            .collect();
+        assert_eq!(children.len(), 2);
+        assert_eq!(
+            attribute(children[0], "url.full"),
+            Some(Value::from("https://a.example/"))
+        );
+        assert_eq!(
+            attribute(children[0], "duration_secs"),
+            Some(Value::F64(30.0))
+        );
+        assert_eq!(
+            attribute(children[1], "url.full"),
+            Some(Value::from("https://b.example/"))
+        );
+        assert_eq!(
+            attribute(children[1], "duration_secs"),
+            Some(Value::F64(20.0))
+        );
+        assert_eq!(children[1].end_time, safari.end_time);
+        assert!(named("switch")
+            .iter()
+            .any(|s| s.parent_span_id == terminal.span_context.span_id()));
+    }
+}
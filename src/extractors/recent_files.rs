@@ -0,0 +1,296 @@
+// src/extractors/recent_files.rs
+//! Recently used files across IDEs and document apps
+//!
+//! The active file of each editor, IDE or document app goes into one list,
+//! most recently used first, with the time it was last in focus and the total
+//! time spent in it. Switching from a file in VS Code to a PDF in Preview and
+//! back shows both, whichever app they were opened in: a cross-app "what was
+//! I working on" view.
+//!
+//! Paths are normalized before they are counted: `file://` URLs and `~` are
+//! expanded, `.` and `..` are resolved, and for files that exist, symlinks are
+//! followed, so the same file opened through different paths is one entry.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchListener, AppSwitchType};
+use crate::core::supported_apps::SupportedApps;
+
+/// Supported-apps categories whose active files are collected
+const FILE_CATEGORIES: &[&str] = &[
+    "development",
+    "documents",
+    "notes",
+    "research",
+    "publishing",
+];
+
+/// A file and how it was used
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentFile {
+    pub path: String,
+    /// When the file was last seen in focus
+    pub last_access: DateTime<Utc>,
+    /// Total time the file was in focus
+    pub edit_time: Duration,
+    /// Bundle IDs of the apps it was open in, first use first
+    pub apps: Vec<String>,
+}
+
+/// Collects the active files of editors and document apps
+pub struct RecentFilesTracker {
+    categories: SupportedApps,
+
+    /// Normalized path currently in focus and when it became active
+    current: Option<(String, Instant)>,
+
+    files: HashMap<String, RecentFile>,
+}
+
+impl Default for RecentFilesTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecentFilesTracker {
+    pub fn new() -> Self {
+        Self {
+            categories: SupportedApps::builtin(),
+            current: None,
+            files: HashMap::new(),
+        }
+    }
+
+    /// The `n` most recently used files, most recent first
+    pub fn recent_files(&self, n: usize) -> Vec<RecentFile> {
+        let mut files: Vec<RecentFile> = self.files.values().cloned().collect();
+        files.sort_by(|a, b| {
+            b.last_access
+                .cmp(&a.last_access)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        files.truncate(n);
+        files
+    }
+
+    /// The file an event shows, when it comes from an editor or document app
+    fn active_file(&self, event: &AppSwitchEvent) -> Option<String> {
+        let category = self.categories.category(&event.app_info.bundle_id)?;
+        if !FILE_CATEGORIES.contains(&category) {
+            return None;
+        }
+        let path = event.workspace.as_ref()?.active_file_paths.first()?;
+        normalize_path(path)
+    }
+
+    /// Track the file an event shows, ending the previous one
+    pub fn observe(&mut self, event: &AppSwitchEvent, now: DateTime<Utc>) {
+        let file = self.active_file(event);
+        if let Some(path) = &file {
+            let entry = self
+                .files
+                .entry(path.clone())
+                .or_insert_with(|| RecentFile {
+                    path: path.clone(),
+                    last_access: now,
+                    edit_time: Duration::ZERO,
+                    apps: Vec::new(),
+                });
+            entry.last_access = now;
+            let bundle_id = &event.app_info.bundle_id;
+            if !entry.apps.contains(bundle_id) {
+                entry.apps.push(bundle_id.clone());
+            }
+        }
+        if self.current.as_ref().map(|(path, _)| path) == file.as_ref() {
+            return;
+        }
+        self.end_current(event.timestamp);
+        self.current = file.map(|path| (path, event.timestamp));
+    }
+
+    fn end_current(&mut self, at: Instant) {
+        if let Some((path, since)) = self.current.take() {
+            if let Some(file) = self.files.get_mut(&path) {
+                file.edit_time += at.saturating_duration_since(since);
+            }
+        }
+    }
+
+    pub fn print_summary(&self) {
+        println!("\n📄 Recent files");
+        println!("═══════════════════════════════════════");
+        for file in self.recent_files(20) {
+            println!(
+                "{}  {:>6.1}m  {}",
+                file.last_access
+                    .with_timezone(&chrono::Local)
+                    .format("%H:%M"),
+                file.edit_time.as_secs_f64() / 60.0,
+                file.path
+            );
+        }
+        println!("═══════════════════════════════════════");
+    }
+}
+
+impl AppSwitchListener for RecentFilesTracker {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        match event.event_type {
+            AppSwitchType::Foreground
+            | AppSwitchType::SpaceChange
+            | AppSwitchType::SessionChange
+            | AppSwitchType::Wake
+            | AppSwitchType::Heartbeat => self.observe(event, Utc::now()),
+            AppSwitchType::Background
+            | AppSwitchType::Launch
+            | AppSwitchType::Terminate
+            | AppSwitchType::Hide
+            | AppSwitchType::Unhide
+            | AppSwitchType::Sleep => {}
+        }
+    }
+
+    fn name(&self) -> &str {
+        "recent_files"
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        self.end_current(Instant::now());
+        if !self.files.is_empty() {
+            self.print_summary();
+        }
+    }
+
+    fn on_monitoring_paused(&mut self) {
+        self.end_current(Instant::now());
+    }
+
+    fn on_system_sleep(&mut self) {
+        self.end_current(Instant::now());
+    }
+}
+
+/// One spelling per file; `None` for values that are not absolute paths,
+/// such as the "Untitled" of an unsaved document
+pub fn normalize_path(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let path = if raw.starts_with("file://") {
+        url::Url::parse(raw).ok()?.to_file_path().ok()?
+    } else if let Some(rest) = raw.strip_prefix("~/") {
+        PathBuf::from(std::env::var_os("HOME")?).join(rest)
+    } else {
+        PathBuf::from(raw)
+    };
+    if !path.is_absolute() {
+        return None;
+    }
+    let path = std::fs::canonicalize(&path).unwrap_or_else(|_| lexical_normalize(&path));
+    Some(path.to_string_lossy().into_owned())
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+fn lexical_normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, WorkspaceSummary};
+
+    fn switch(bundle_id: &str, file: &Path, at: Instant) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new(bundle_id.to_string(), bundle_id.to_string(), 1),
+        );
+        event.timestamp = at;
+        event.workspace = Some(WorkspaceSummary {
+            window_count: 1,
+            focused_title: None,
+            total_screen_coverage: None,
+            is_fullscreen: None,
+            is_minimized: None,
+            tab_titles: Vec::new(),
+            active_file_paths: vec![file.to_string_lossy().into_owned()],
+            primary_url: None,
+        });
+        event
+    }
+
+    #[test]
+    fn test_recent_files_order_and_time_across_apps() {
+        let dir = std::env::temp_dir().join(format!("recent_files_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let notes = dir.join("notes.md");
+        let paper = dir.join("paper.pdf");
+        std::fs::write(&notes, "notes").unwrap();
+        std::fs::write(&paper, "paper").unwrap();
+        let link = dir.join("latest.md");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&notes, &link).unwrap();
+
+        let mut tracker = RecentFilesTracker::new();
+        let t0 = Instant::now();
+        let at = |secs| t0 + Duration::from_secs(secs);
+        let now = Utc::now();
+        let wall = |secs| now + chrono::Duration::seconds(secs);
+
+        tracker.observe(&switch("com.microsoft.VSCode", &notes, at(0)), wall(0));
+        tracker.observe(&switch("com.apple.Preview", &paper, at(60)), wall(60));
+        // The same file through a symlink and a `..` detour, in another app
+        let detour = dir
+            .join("..")
+            .join(dir.file_name().unwrap())
+            .join("latest.md");
+        tracker.observe(&switch("md.obsidian", &detour, at(90)), wall(90));
+        // Non-document apps end the file's time without adding an entry
+        tracker.observe(&switch("com.apple.Terminal", &paper, at(100)), wall(100));
+
+        let recent = tracker.recent_files(10);
+        assert_eq!(recent.len(), 2);
+        let canonical = |p: &Path| {
+            std::fs::canonicalize(p)
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        };
+        assert_eq!(recent[0].path, canonical(&notes));
+        assert_eq!(recent[0].last_access, wall(90));
+        assert_eq!(recent[0].edit_time, Duration::from_secs(70));
+        assert_eq!(recent[0].apps, vec!["com.microsoft.VSCode", "md.obsidian"]);
+        assert_eq!(recent[1].path, canonical(&paper));
+        assert_eq!(recent[1].edit_time, Duration::from_secs(30));
+        assert_eq!(tracker.recent_files(1).len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("Untitled"), None);
+        assert_eq!(
+            normalize_path("/nonexistent/a/./b/../c.txt").as_deref(),
+            Some("/nonexistent/a/c.txt")
+        );
+        assert_eq!(
+            normalize_path("file:///nonexistent/My%20Notes.md").as_deref(),
+            Some("/nonexistent/My Notes.md")
+        );
+    }
+}
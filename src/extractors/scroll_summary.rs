@@ -0,0 +1,302 @@
+// src/extractors/scroll_summary.rs
+//! Scroll behaviour per dwell period
+//!
+//! How someone scrolls says whether they are reading: steady, modest scrolls
+//! with pauses in between versus long flicks through a page. Scroll deltas
+//! from the scroll tap are collected for the frontmost app; on each
+//! foreground switch the period's deltas are reduced to a `ScrollSummary`
+//! (dominant direction, distance, velocity while scrolling and a reading vs
+//! fast-scan classification) and appended as JSON lines to an output file.
+//!
+//! Deltas use the scroll wheel's sign convention: positive vertical values
+//! scroll up, positive horizontal values scroll left.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent, AppSwitchListener};
+use crate::core::buffered_sink::FlushPolicy;
+use crate::extractors::dwell_period::DwellLog;
+
+/// Gaps longer than this between samples are pauses, not scrolling time
+pub const IDLE_GAP: Duration = Duration::from_millis(1000);
+
+/// Scrolling time credited to an isolated sample
+pub const SAMPLE_SPAN: Duration = Duration::from_millis(250);
+
+/// Average speeds at or above this (points per second) are a fast scan
+pub const FAST_SCAN_PX_PER_SEC: f64 = 1500.0;
+
+/// One scroll delta, in points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollSample {
+    pub at: Instant,
+    pub vertical_px: i64,
+    pub horizontal_px: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollStyle {
+    /// Modest speed while scrolling; consistent with reading along
+    Reading,
+    /// High speed while scrolling; skimming or looking for something
+    FastScan,
+}
+
+/// Direction, distance and speed of a set of samples
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScrollMetrics {
+    /// Direction with the most distance travelled
+    pub direction: ScrollDirection,
+    pub up_px: u64,
+    pub down_px: u64,
+    pub left_px: u64,
+    pub right_px: u64,
+    /// Time spent scrolling, excluding pauses longer than `IDLE_GAP`
+    pub active_secs: f64,
+    /// Total distance over active time
+    pub velocity_px_per_sec: f64,
+    pub style: ScrollStyle,
+    pub samples: usize,
+}
+
+impl ScrollMetrics {
+    /// Reduce samples (in time order) to metrics; `None` without movement
+    pub fn from_samples(samples: &[ScrollSample]) -> Option<Self> {
+        let mut up = 0u64;
+        let mut down = 0u64;
+        let mut left = 0u64;
+        let mut right = 0u64;
+        for sample in samples {
+            match sample.vertical_px {
+                v if v > 0 => up += v as u64,
+                v => down += v.unsigned_abs(),
+            }
+            match sample.horizontal_px {
+                h if h > 0 => left += h as u64,
+                h => right += h.unsigned_abs(),
+            }
+        }
+        let total = up + down + left + right;
+        if total == 0 {
+            return None;
+        }
+
+        let active = SAMPLE_SPAN
+            + samples
+                .windows(2)
+                .map(|pair| pair[1].at.saturating_duration_since(pair[0].at))
+                .map(|gap| if gap > IDLE_GAP { SAMPLE_SPAN } else { gap })
+                .sum::<Duration>();
+        let velocity = total as f64 / active.as_secs_f64();
+
+        let direction = [
+            (ScrollDirection::Down, down),
+            (ScrollDirection::Up, up),
+            (ScrollDirection::Right, right),
+            (ScrollDirection::Left, left),
+        ]
+        .into_iter()
+        .max_by_key(|(_, px)| *px)
+        .map(|(direction, _)| direction)?;
+
+        Some(Self {
+            direction,
+            up_px: up,
+            down_px: down,
+            left_px: left,
+            right_px: right,
+            active_secs: active.as_secs_f64(),
+            velocity_px_per_sec: velocity,
+            style: if velocity >= FAST_SCAN_PX_PER_SEC {
+                ScrollStyle::FastScan
+            } else {
+                ScrollStyle::Reading
+            },
+            samples: samples.len(),
+        })
+    }
+}
+
+/// Scrolling in one dwell period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrollSummary {
+    pub app_name: String,
+    pub bundle_id: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub duration_secs: f64,
+    #[serde(flatten)]
+    pub metrics: ScrollMetrics,
+}
+
+/// Handle the scroll tap feeds samples through
+#[derive(Clone, Default)]
+pub struct ScrollRecorder(Arc<Mutex<Vec<ScrollSample>>>);
+
+impl ScrollRecorder {
+    pub fn record(&self, at: Instant, vertical_px: i64, horizontal_px: i64) {
+        self.0.lock().unwrap().push(ScrollSample {
+            at,
+            vertical_px,
+            horizontal_px,
+        });
+    }
+
+    fn take(&self) -> Vec<ScrollSample> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+/// Collects scroll samples and emits a summary per dwell period
+pub struct ScrollSummaryTracker {
+    log: DwellLog<ScrollSummary>,
+    recorder: ScrollRecorder,
+}
+
+impl ScrollSummaryTracker {
+    pub fn new(min_duration: Duration) -> Self {
+        Self {
+            log: DwellLog::new("scroll summary", min_duration),
+            recorder: ScrollRecorder::default(),
+        }
+    }
+
+    /// Append finished summaries to a JSON lines file
+    pub fn with_output(mut self, path: &Path, policy: FlushPolicy) -> std::io::Result<Self> {
+        self.log.open_output(path, policy)?;
+        Ok(self)
+    }
+
+    /// Where scroll samples for the current period are recorded
+    pub fn recorder(&self) -> ScrollRecorder {
+        self.recorder.clone()
+    }
+
+    pub fn summaries(&self) -> &[ScrollSummary] {
+        self.log.records()
+    }
+
+    /// Start a period for `app`, closing the previous one
+    pub fn switch_to_at(&mut self, app: Option<&AppInfo>, now: Instant, wall: DateTime<Utc>) {
+        let samples = self.recorder.take();
+        self.log.switch_to_at(app, now, wall, |period| {
+            ScrollMetrics::from_samples(&samples).map(|metrics| ScrollSummary {
+                app_name: period.app.name,
+                bundle_id: period.app.bundle_id,
+                start: period.start,
+                end: period.end,
+                duration_secs: period.duration.as_secs_f64(),
+                metrics,
+            })
+        });
+    }
+}
+
+impl AppSwitchListener for ScrollSummaryTracker {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        if event.event_type.is_foreground() {
+            self.switch_to_at(Some(&event.app_info), Instant::now(), Utc::now());
+        }
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        self.switch_to_at(None, Instant::now(), Utc::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `count` samples `interval_ms` apart, each moving `vertical_px`
+    fn steady(start: Instant, count: u64, interval_ms: u64, vertical_px: i64) -> Vec<ScrollSample> {
+        (0..count)
+            .map(|i| ScrollSample {
+                at: start + Duration::from_millis(i * interval_ms),
+                vertical_px,
+                horizontal_px: 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_classification_thresholds() {
+        let start = Instant::now();
+
+        // 100pt every 250ms = 400pt/s downwards
+        let reading = ScrollMetrics::from_samples(&steady(start, 20, 250, -100)).unwrap();
+        assert_eq!(reading.direction, ScrollDirection::Down);
+        assert_eq!(reading.down_px, 2000);
+        assert_eq!(reading.style, ScrollStyle::Reading);
+        assert!((reading.velocity_px_per_sec - 400.0).abs() < 1e-6);
+
+        // 500pt every 250ms = 2000pt/s upwards
+        let scan = ScrollMetrics::from_samples(&steady(start, 20, 250, 500)).unwrap();
+        assert_eq!(scan.direction, ScrollDirection::Up);
+        assert_eq!(scan.style, ScrollStyle::FastScan);
+
+        // Just below and at the threshold
+        let below = ScrollMetrics::from_samples(&steady(start, 8, 250, -374)).unwrap();
+        assert_eq!(below.style, ScrollStyle::Reading);
+        let at = ScrollMetrics::from_samples(&steady(start, 8, 250, -375)).unwrap();
+        assert_eq!(at.style, ScrollStyle::FastScan);
+
+        assert!(ScrollMetrics::from_samples(&steady(start, 3, 250, 0)).is_none());
+    }
+
+    #[test]
+    fn test_pauses_do_not_count_as_scrolling_time() {
+        let start = Instant::now();
+        let mut samples = steady(start, 4, 250, -100);
+        // Ten seconds of reading, then another short burst
+        samples.extend(steady(start + Duration::from_secs(11), 4, 250, -100));
+
+        let metrics = ScrollMetrics::from_samples(&samples).unwrap();
+        assert!((metrics.active_secs - 2.0).abs() < 1e-6);
+        assert_eq!(metrics.style, ScrollStyle::Reading);
+
+        let sideways = [ScrollSample {
+            at: start,
+            vertical_px: -10,
+            horizontal_px: -300,
+        }];
+        assert_eq!(
+            ScrollMetrics::from_samples(&sideways).unwrap().direction,
+            ScrollDirection::Right
+        );
+    }
+
+    #[test]
+    fn test_summary_per_dwell_period() {
+        let mut tracker = ScrollSummaryTracker::new(Duration::from_secs(5));
+        let recorder = tracker.recorder();
+        let start = Instant::now();
+        let wall = Utc::now();
+        let app = AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 1);
+
+        tracker.switch_to_at(Some(&app), start, wall);
+        recorder.record(start, -120, 0);
+        recorder.record(start + Duration::from_millis(250), -80, 0);
+        tracker.switch_to_at(None, start + Duration::from_secs(30), wall);
+
+        let summaries = tracker.summaries();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].app_name, "Safari");
+        assert_eq!(summaries[0].metrics.down_px, 200);
+        assert_eq!(summaries[0].metrics.samples, 2);
+    }
+}
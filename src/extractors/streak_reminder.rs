@@ -0,0 +1,308 @@
+// src/extractors/streak_reminder.rs
+//! Focus streak and distraction reminders
+//!
+//! Two nudges built on the switch stream: a "you've been in X for a while"
+//! reminder once the same app stays frontmost past a threshold, and a
+//! distraction warning when the user switches apps too often within a short
+//! window. Delivery goes through the `ReminderNotifier` trait so the triggers
+//! can be tested without posting real notifications.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchListener};
+
+/// Reminder thresholds, read from the `[reminders]` config section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReminderConfig {
+    pub enabled: bool,
+
+    /// Minutes in a single app before the streak reminder fires
+    pub streak_minutes: u64,
+
+    /// Number of switches that counts as thrashing
+    pub thrash_switches: usize,
+
+    /// Window over which switches are counted
+    pub thrash_window_secs: u64,
+}
+
+impl Default for ReminderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            streak_minutes: 50,
+            thrash_switches: 10,
+            thrash_window_secs: 60,
+        }
+    }
+}
+
+impl ReminderConfig {
+    pub fn streak_threshold(&self) -> Duration {
+        Duration::from_secs(self.streak_minutes * 60)
+    }
+
+    pub fn thrash_window(&self) -> Duration {
+        Duration::from_secs(self.thrash_window_secs)
+    }
+}
+
+/// Delivery channel for reminders
+pub trait ReminderNotifier: Send {
+    fn notify(&mut self, title: &str, message: &str);
+}
+
+/// Posts reminders to Notification Center via `osascript`
+pub struct OsascriptNotifier;
+
+impl ReminderNotifier for OsascriptNotifier {
+    fn notify(&mut self, title: &str, message: &str) {
+        let script = format!(
+            "display notification \"{}\" with title \"{}\"",
+            applescript_escape(message),
+            applescript_escape(title)
+        );
+        if let Err(e) = Command::new("osascript").arg("-e").arg(script).output() {
+            eprintln!("⚠️  Failed to post reminder: {}", e);
+        }
+    }
+}
+
+fn applescript_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+struct ReminderState {
+    config: ReminderConfig,
+    notifier: Box<dyn ReminderNotifier>,
+
+    /// Current app name and when it became frontmost
+    current: Option<(String, Instant)>,
+    streak_notified: bool,
+
+    /// Timestamps of recent foreground switches
+    recent_switches: VecDeque<Instant>,
+    last_thrash_warning: Option<Instant>,
+}
+
+impl ReminderState {
+    fn record_switch(&mut self, app_name: &str, at: Instant) {
+        let same_app = self
+            .current
+            .as_ref()
+            .map(|(name, _)| name == app_name)
+            .unwrap_or(false);
+        if same_app {
+            // A wake, Space change or resample is not a switch
+            self.check_streak(at);
+            return;
+        }
+        self.current = Some((app_name.to_string(), at));
+        self.streak_notified = false;
+
+        self.recent_switches.push_back(at);
+        self.prune_switches(at);
+
+        let cooled_down = self
+            .last_thrash_warning
+            .map(|t| at.saturating_duration_since(t) >= self.config.thrash_window())
+            .unwrap_or(true);
+        if self.config.thrash_switches > 0
+            && self.recent_switches.len() >= self.config.thrash_switches
+            && cooled_down
+        {
+            let message = format!(
+                "You've switched apps {} times in the last {}s",
+                self.recent_switches.len(),
+                self.config.thrash_window_secs
+            );
+            self.notifier.notify("Lots of switching", &message);
+            self.last_thrash_warning = Some(at);
+        }
+
+        self.check_streak(at);
+    }
+
+    fn check_streak(&mut self, now: Instant) {
+        if self.streak_notified {
+            return;
+        }
+        if let Some((name, since)) = &self.current {
+            if now.saturating_duration_since(*since) >= self.config.streak_threshold() {
+                let message = format!(
+                    "You've been in {} for {} minutes",
+                    name, self.config.streak_minutes
+                );
+                self.notifier.notify("Focus streak", &message);
+                self.streak_notified = true;
+            }
+        }
+    }
+
+    fn prune_switches(&mut self, now: Instant) {
+        let window = self.config.thrash_window();
+        while let Some(front) = self.recent_switches.front() {
+            if now.saturating_duration_since(*front) > window {
+                self.recent_switches.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+/// Emits streak and thrash reminders from the switch stream
+///
+/// Streaks only become visible while nothing is happening, so the state is
+/// shared with an optional ticker thread started via `start_ticker`, which
+/// runs until monitoring stops.
+#[derive(Clone)]
+pub struct StreakReminder {
+    state: Arc<Mutex<ReminderState>>,
+    ticking: Arc<AtomicBool>,
+}
+
+impl StreakReminder {
+    pub fn new(config: ReminderConfig) -> Self {
+        Self::with_notifier(config, Box::new(OsascriptNotifier))
+    }
+
+    pub fn with_notifier(config: ReminderConfig, notifier: Box<dyn ReminderNotifier>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ReminderState {
+                config,
+                notifier,
+                current: None,
+                streak_notified: false,
+                recent_switches: VecDeque::new(),
+                last_thrash_warning: None,
+            })),
+            ticking: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Record a foreground switch at the given instant
+    pub fn record_switch_at(&self, app_name: &str, at: Instant) {
+        self.state.lock().unwrap().record_switch(app_name, at);
+    }
+
+    /// Re-evaluate the streak threshold without a switch
+    pub fn tick(&self, now: Instant) {
+        self.state.lock().unwrap().check_streak(now);
+    }
+
+    /// Periodically check for streaks on a background thread
+    pub fn start_ticker(&self, interval: Duration) {
+        if self.ticking.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let reminder = self.clone();
+        std::thread::spawn(move || {
+            while reminder.ticking.load(Ordering::SeqCst) {
+                std::thread::sleep(interval);
+                reminder.tick(Instant::now());
+            }
+        });
+    }
+
+    pub fn stop_ticker(&self) {
+        self.ticking.store(false, Ordering::SeqCst);
+    }
+}
+
+impl AppSwitchListener for StreakReminder {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        if event.event_type.is_foreground() {
+            self.record_switch_at(&event.app_info.name, Instant::now());
+        }
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        self.stop_ticker();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RecordingNotifier(Arc<Mutex<Vec<String>>>);
+
+    impl ReminderNotifier for RecordingNotifier {
+        fn notify(&mut self, title: &str, _message: &str) {
+            self.0.lock().unwrap().push(title.to_string());
+        }
+    }
+
+    fn reminder(config: ReminderConfig) -> (StreakReminder, Arc<Mutex<Vec<String>>>) {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let notifier = Box::new(RecordingNotifier(sent.clone()));
+        (StreakReminder::with_notifier(config, notifier), sent)
+    }
+
+    #[test]
+    fn test_streak_fires_once_past_threshold() {
+        let config = ReminderConfig {
+            enabled: true,
+            streak_minutes: 30,
+            ..ReminderConfig::default()
+        };
+        let (reminder, sent) = reminder(config);
+        let start = Instant::now();
+
+        reminder.record_switch_at("Xcode", start);
+        reminder.tick(start + Duration::from_secs(29 * 60));
+        assert!(sent.lock().unwrap().is_empty());
+
+        reminder.tick(start + Duration::from_secs(30 * 60));
+        reminder.tick(start + Duration::from_secs(45 * 60));
+        assert_eq!(*sent.lock().unwrap(), vec!["Focus streak".to_string()]);
+    }
+
+    #[test]
+    fn test_thrash_warning() {
+        let config = ReminderConfig {
+            enabled: true,
+            thrash_switches: 5,
+            thrash_window_secs: 60,
+            ..ReminderConfig::default()
+        };
+        let (reminder, sent) = reminder(config);
+        let start = Instant::now();
+
+        // Five switches spread beyond the window never trigger
+        for (i, app) in ["Slack", "Mail", "Slack", "Mail", "Slack"].iter().enumerate() {
+            reminder.record_switch_at(app, start + Duration::from_secs(i as u64 * 40));
+        }
+        assert!(sent.lock().unwrap().is_empty());
+
+        // Repeated events for the app in front are not switches
+        let repeats = start + Duration::from_secs(300);
+        for i in 0..5 {
+            reminder.record_switch_at("Notes", repeats + Duration::from_secs(i));
+        }
+        assert!(sent.lock().unwrap().is_empty());
+
+        let burst = start + Duration::from_secs(600);
+        for (i, app) in ["Safari", "Slack", "Mail", "Safari", "Notes"].iter().enumerate() {
+            reminder.record_switch_at(app, burst + Duration::from_secs(i as u64 * 5));
+        }
+        assert_eq!(*sent.lock().unwrap(), vec!["Lots of switching".to_string()]);
+    }
+
+    #[test]
+    fn test_ticker_stops_with_monitoring() {
+        let (mut reminder, _) = reminder(ReminderConfig::default());
+        reminder.start_ticker(Duration::from_millis(10));
+        assert!(reminder.ticking.load(Ordering::SeqCst));
+
+        reminder.on_monitoring_stopped();
+        assert!(!reminder.ticking.load(Ordering::SeqCst));
+    }
+}
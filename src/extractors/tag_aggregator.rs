@@ -0,0 +1,287 @@
+// src/extractors/tag_aggregator.rs
+//! Work/personal tagging of application time
+//!
+//! Each application is mapped to a free-form tag ("work", "personal", ...)
+//! through the `[tags]` section of the config file, either by bundle id or by
+//! its inferred category (`browser`, `ide`, ...), and focus time is totalled
+//! per tag per calendar day. This powers the daily work-vs-personal split
+//! printed when monitoring stops.
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent, AppSwitchListener, AppSwitchType};
+use crate::core::process_cache::ProcessIdentity;
+use crate::core::running_apps::infer_category;
+use crate::extractors::time_tracker::AppSession;
+
+/// Mapping of applications to tags
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagConfig {
+    /// Tag per bundle id, e.g. `"com.tinyspeck.slackmacgap" = "work"`
+    pub apps: HashMap<String, String>,
+
+    /// Tag per app category, e.g. `ide = "work"`; a bundle id entry wins
+    pub categories: HashMap<String, String>,
+
+    /// Tag used for applications without an explicit mapping
+    pub default_tag: String,
+}
+
+impl Default for TagConfig {
+    fn default() -> Self {
+        Self {
+            apps: HashMap::new(),
+            categories: HashMap::new(),
+            default_tag: "neutral".to_string(),
+        }
+    }
+}
+
+impl TagConfig {
+    /// Resolve the tag for a bundle id, then for its category, falling back
+    /// to the default bucket
+    pub fn tag_for(&self, bundle_id: &str) -> &str {
+        self.apps
+            .get(bundle_id)
+            .or_else(|| self.categories.get(infer_category(bundle_id)))
+            .map(String::as_str)
+            .unwrap_or(&self.default_tag)
+    }
+}
+
+/// Per-tag totals for a single day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyTagBreakdown {
+    pub date: NaiveDate,
+    pub minutes: BTreeMap<String, f64>,
+}
+
+/// Aggregates focus time per tag per day
+pub struct TagTimeAggregator {
+    config: TagConfig,
+
+    /// Current foreground app and when it became active
+    current_session: Option<(AppInfo, Instant, DateTime<Local>)>,
+
+    /// Totals keyed by day, then by tag
+    daily_totals: BTreeMap<NaiveDate, BTreeMap<String, Duration>>,
+}
+
+impl TagTimeAggregator {
+    pub fn new(config: TagConfig) -> Self {
+        Self {
+            config,
+            current_session: None,
+            daily_totals: BTreeMap::new(),
+        }
+    }
+
+    /// Record a finished session, splitting it at midnight when it spans days
+    pub fn record_session(&mut self, bundle_id: &str, start: DateTime<Local>, duration: Duration) {
+        let tag = self.config.tag_for(bundle_id).to_string();
+        let mut remaining = ChronoDuration::from_std(duration).unwrap_or_default();
+        let mut cursor = start;
+
+        while remaining > ChronoDuration::zero() {
+            let next_midnight = cursor
+                .date_naive()
+                .succ_opt()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .and_then(|dt| Local.from_local_datetime(&dt).earliest());
+
+            let chunk = match next_midnight {
+                Some(midnight) if midnight > cursor && midnight - cursor < remaining => {
+                    midnight - cursor
+                }
+                _ => remaining,
+            };
+
+            *self
+                .daily_totals
+                .entry(cursor.date_naive())
+                .or_default()
+                .entry(tag.clone())
+                .or_insert(Duration::ZERO) += chunk.to_std().unwrap_or_default();
+
+            cursor += chunk;
+            remaining -= chunk;
+        }
+    }
+
+    /// Fold sessions recorded by the time tracker into the tag totals
+    pub fn ingest_sessions(&mut self, sessions: &[AppSession]) {
+        for session in sessions {
+            self.record_session(
+                &session.bundle_id,
+                session.start_time.with_timezone(&Local),
+                session.duration,
+            );
+        }
+    }
+
+    /// Totals per tag for a given day
+    pub fn totals_for(&self, date: NaiveDate) -> Option<&BTreeMap<String, Duration>> {
+        self.daily_totals.get(&date)
+    }
+
+    /// Daily breakdown in minutes, oldest day first
+    pub fn daily_breakdown(&self) -> Vec<DailyTagBreakdown> {
+        self.daily_totals
+            .iter()
+            .map(|(date, tags)| DailyTagBreakdown {
+                date: *date,
+                minutes: tags
+                    .iter()
+                    .map(|(tag, d)| (tag.clone(), d.as_secs_f64() / 60.0))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// End the current session and add it to the totals
+    fn end_current_session(&mut self, end_instant: Instant) {
+        if let Some((app_info, start_instant, start_time)) = self.current_session.take() {
+            let duration = end_instant.duration_since(start_instant);
+            self.record_session(&app_info.bundle_id, start_time, duration);
+        }
+    }
+
+    /// Print the work/personal split for every tracked day
+    pub fn print_daily_breakdown(&self) {
+        println!("\n🏷️  Daily Tag Breakdown");
+        println!("═══════════════════════════════════════");
+        for day in self.daily_breakdown() {
+            let parts: Vec<String> = day
+                .minutes
+                .iter()
+                .map(|(tag, minutes)| format!("{} {:.0}m", tag, minutes))
+                .collect();
+            println!("{}: {}", day.date, parts.join(" | "));
+        }
+        println!("═══════════════════════════════════════");
+    }
+}
+
+impl AppSwitchListener for TagTimeAggregator {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        let now = Instant::now();
+
+        match event.event_type {
+            AppSwitchType::Foreground
+            | AppSwitchType::SpaceChange
+            | AppSwitchType::SessionChange
+            | AppSwitchType::Wake => {
+                self.end_current_session(now);
+                self.current_session = Some((event.app_info.clone(), now, Local::now()));
+            }
+            AppSwitchType::Background | AppSwitchType::Terminate => {
+                if let Some((ref current_app, _, _)) = self.current_session {
+                    if ProcessIdentity::of(current_app)
+                        .same_process(&ProcessIdentity::of(&event.app_info))
+                    {
+                        self.end_current_session(now);
+                    }
+                }
+            }
+            AppSwitchType::Launch
+            | AppSwitchType::Hide
+            | AppSwitchType::Unhide
+            | AppSwitchType::Sleep
+            | AppSwitchType::Heartbeat => {}
+        }
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        self.end_current_session(Instant::now());
+        self.print_daily_breakdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TagConfig {
+        let mut apps = HashMap::new();
+        apps.insert("com.microsoft.VSCode".to_string(), "work".to_string());
+        apps.insert("com.spotify.client".to_string(), "personal".to_string());
+        let mut categories = HashMap::new();
+        categories.insert("communication".to_string(), "work".to_string());
+        categories.insert("ide".to_string(), "personal".to_string());
+        TagConfig {
+            apps,
+            categories,
+            default_tag: "neutral".to_string(),
+        }
+    }
+
+    fn session(bundle_id: &str, start: DateTime<Local>, minutes: u64) -> AppSession {
+        let duration = Duration::from_secs(minutes * 60);
+        AppSession {
+            app_name: bundle_id.to_string(),
+            bundle_id: bundle_id.to_string(),
+            start_time: start.with_timezone(&chrono::Utc),
+            end_time: Some((start + ChronoDuration::from_std(duration).unwrap()).into()),
+            duration,
+            pid: 1,
+        }
+    }
+
+    #[test]
+    fn test_per_tag_totals() {
+        let mut aggregator = TagTimeAggregator::new(config());
+        let day = Local.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+
+        aggregator.record_session("com.microsoft.VSCode", day, Duration::from_secs(1800));
+        aggregator.record_session("com.spotify.client", day, Duration::from_secs(600));
+        aggregator.record_session("com.microsoft.VSCode", day, Duration::from_secs(600));
+        aggregator.record_session("com.apple.finder", day, Duration::from_secs(120));
+
+        let totals = aggregator.totals_for(day.date_naive()).unwrap();
+        assert_eq!(totals["work"], Duration::from_secs(2400));
+        assert_eq!(totals["personal"], Duration::from_secs(600));
+        assert_eq!(totals["neutral"], Duration::from_secs(120));
+
+        let breakdown = aggregator.daily_breakdown();
+        assert_eq!(breakdown.len(), 1);
+        assert_eq!(breakdown[0].minutes["work"], 40.0);
+    }
+
+    #[test]
+    fn test_category_tags_and_ingested_sessions() {
+        let mut aggregator = TagTimeAggregator::new(config());
+        let day = Local.with_ymd_and_hms(2024, 3, 5, 10, 0, 0).unwrap();
+
+        aggregator.ingest_sessions(&[
+            // Tagged by category
+            session("com.tinyspeck.slackmacgap", day, 20),
+            // The bundle id entry wins over the "ide" category
+            session("com.microsoft.VSCode", day, 15),
+            session("com.jetbrains.intellij", day, 5),
+            session("com.apple.finder", day, 3),
+        ]);
+
+        let totals = aggregator.totals_for(day.date_naive()).unwrap();
+        assert_eq!(totals["work"], Duration::from_secs(35 * 60));
+        assert_eq!(totals["personal"], Duration::from_secs(5 * 60));
+        assert_eq!(totals["neutral"], Duration::from_secs(3 * 60));
+    }
+
+    #[test]
+    fn test_session_split_at_midnight() {
+        let mut aggregator = TagTimeAggregator::new(config());
+        let late = Local.with_ymd_and_hms(2024, 3, 4, 23, 50, 0).unwrap();
+
+        aggregator.record_session("com.microsoft.VSCode", late, Duration::from_secs(1200));
+
+        let first = aggregator.totals_for(late.date_naive()).unwrap();
+        assert_eq!(first["work"], Duration::from_secs(600));
+        let next_day = late.date_naive().succ_opt().unwrap();
+        let second = aggregator.totals_for(next_day).unwrap();
+        assert_eq!(second["work"], Duration::from_secs(600));
+    }
+}
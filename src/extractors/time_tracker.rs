@@ -10,7 +10,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
-use crate::core::app_switcher_types::{AppInfo, AppSwitchEvent, AppSwitchListener, AppSwitchType};
+use crate::core::app_switcher_types::{
+    AppInfo, AppSwitchEvent, AppSwitchListener, AppSwitchType, SleepPeriod,
+};
+use crate::core::process_cache::ProcessIdentity;
 
 /// Represents a single session of app usage
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +52,9 @@ pub struct TimeTracker {
     /// Aggregated statistics per application
     app_statistics: HashMap<String, AppStatistics>,
 
+    /// Intervals the system spent asleep, kept apart from app time
+    system_asleep: Vec<SleepPeriod>,
+
     /// Total tracking start time
     tracking_started: Option<Instant>,
 
@@ -95,6 +101,7 @@ impl TimeTracker {
             current_session: None,
             session_history: Vec::new(),
             app_statistics: HashMap::new(),
+            system_asleep: Vec::new(),
             tracking_started: None,
             config,
         }
@@ -141,7 +148,7 @@ impl TimeTracker {
     fn update_statistics(&mut self, app_info: &AppInfo, session: &AppSession) {
         let stats = self
             .app_statistics
-            .entry(app_info.bundle_id.clone())
+            .entry(app_info.match_key().to_string())
             .or_insert_with(|| AppStatistics {
                 app_name: session.app_name.clone(),
                 app_path: app_info.path.clone().unwrap_or_default(),
@@ -187,9 +194,9 @@ impl TimeTracker {
         stats
     }
 
-    /// Get statistics for a specific application
-    pub fn get_app_statistics(&self, bundle_id: &str) -> Option<&AppStatistics> {
-        self.app_statistics.get(bundle_id)
+    /// Get statistics for a specific application, by its `AppInfo::match_key`
+    pub fn get_app_statistics(&self, match_key: &str) -> Option<&AppStatistics> {
+        self.app_statistics.get(match_key)
     }
 
     /// Get the complete session history
@@ -225,6 +232,7 @@ impl TimeTracker {
         TimeTrackingReport {
             tracking_duration: total_tracked_time,
             total_active_time,
+            system_asleep: self.system_asleep.iter().map(SleepPeriod::duration).sum(),
             total_sessions: self.session_history.len(),
             unique_apps: self.app_statistics.len(),
             top_apps: self.get_top_apps(5),
@@ -271,6 +279,7 @@ impl TimeTracker {
                 unique_apps: self.app_statistics.len(),
             },
             sessions: self.session_history.clone(),
+            system_asleep: self.system_asleep.clone(),
             statistics: self.get_all_statistics(),
         };
 
@@ -278,12 +287,13 @@ impl TimeTracker {
     }
 }
 
-impl AppSwitchListener for TimeTracker {
-    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
-        let now = Instant::now();
-
+impl TimeTracker {
+    fn handle_event_at(&mut self, event: &AppSwitchEvent, now: Instant) {
         match event.event_type {
-            AppSwitchType::Foreground => {
+            AppSwitchType::Foreground
+            | AppSwitchType::SpaceChange
+            | AppSwitchType::SessionChange
+            | AppSwitchType::Wake => {
                 // End previous session if exists
                 self.end_current_session(now);
 
@@ -296,8 +306,11 @@ impl AppSwitchListener for TimeTracker {
             }
             AppSwitchType::Background => {
                 // Only end session if it's the current app going to background
+                // (compared by pid + launch date so a reused pid doesn't match)
                 if let Some((ref current_app, _, _)) = self.current_session {
-                    if current_app.pid == event.app_info.pid {
+                    if ProcessIdentity::of(current_app)
+                        .same_process(&ProcessIdentity::of(&event.app_info))
+                    {
                         self.end_current_session(now);
                     }
                 }
@@ -305,15 +318,66 @@ impl AppSwitchListener for TimeTracker {
             AppSwitchType::Terminate => {
                 // End session if this app was active
                 if let Some((ref current_app, _, _)) = self.current_session {
-                    if current_app.pid == event.app_info.pid {
+                    if ProcessIdentity::of(current_app)
+                        .same_process(&ProcessIdentity::of(&event.app_info))
+                    {
                         self.end_current_session(now);
                     }
                 }
             }
-            _ => {}
+            AppSwitchType::Launch
+            | AppSwitchType::Hide
+            | AppSwitchType::Unhide
+            | AppSwitchType::Sleep
+            | AppSwitchType::Heartbeat => {}
         }
     }
 
+    /// Close the current session; nothing is tracked until the next
+    /// foreground event, so the paused interval counts as idle
+    fn pause_at(&mut self, now: Instant) {
+        self.end_current_session(now);
+        if self.config.print_updates {
+            println!("⏸️  Time tracking paused");
+        }
+    }
+
+    /// Close the current session before the system sleeps; the next
+    /// foreground event after wake starts a fresh one
+    fn sleep_at(&mut self, now: Instant) {
+        self.end_current_session(now);
+        if self.config.print_updates {
+            println!("💤 System going to sleep");
+        }
+    }
+
+    fn record_wake(&mut self, asleep: &SleepPeriod) {
+        self.system_asleep.push(asleep.clone());
+        if self.config.max_history_size > 0
+            && self.system_asleep.len() > self.config.max_history_size
+        {
+            self.system_asleep.remove(0);
+        }
+    }
+}
+
+impl AppSwitchListener for TimeTracker {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        self.handle_event_at(event, Instant::now());
+    }
+
+    fn on_monitoring_paused(&mut self) {
+        self.pause_at(Instant::now());
+    }
+
+    fn on_system_sleep(&mut self) {
+        self.sleep_at(Instant::now());
+    }
+
+    fn on_system_wake(&mut self, asleep: &SleepPeriod) {
+        self.record_wake(asleep);
+    }
+
     fn on_monitoring_started(&mut self) {
         self.tracking_started = Some(Instant::now());
         println!("⏰ Time tracking started");
@@ -337,6 +401,9 @@ impl AppSwitchListener for TimeTracker {
             "Total active time: {}m",
             report.total_active_time.as_secs() / 60
         );
+        if !report.system_asleep.is_zero() {
+            println!("System asleep: {}m", report.system_asleep.as_secs() / 60);
+        }
         println!("Total sessions: {}", report.total_sessions);
         println!("Unique applications: {}", report.unique_apps);
 
@@ -365,6 +432,8 @@ impl AppSwitchListener for TimeTracker {
 pub struct TimeTrackingReport {
     pub tracking_duration: Duration,
     pub total_active_time: Duration,
+    /// Time the system spent asleep; not counted as any app's time
+    pub system_asleep: Duration,
     pub total_sessions: usize,
     pub unique_apps: usize,
     pub top_apps: Vec<(String, Duration, f64)>,
@@ -376,6 +445,8 @@ pub struct TimeTrackingReport {
 pub struct TimeTrackingExport {
     pub metadata: ExportMetadata,
     pub sessions: Vec<AppSession>,
+    #[serde(default)]
+    pub system_asleep: Vec<SleepPeriod>,
     pub statistics: Vec<AppStatistics>,
 }
 
@@ -386,3 +457,64 @@ pub struct ExportMetadata {
     pub total_sessions: usize,
     pub unique_apps: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paused_interval_is_not_tracked() {
+        let mut tracker = TimeTracker::with_config(TimeTrackerConfig {
+            print_updates: false,
+            ..TimeTrackerConfig::default()
+        });
+        let safari = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 42),
+        );
+        let start = Instant::now();
+
+        tracker.handle_event_at(&safari, start);
+        tracker.pause_at(start + Duration::from_secs(10));
+        // Resuming re-announces the frontmost app a minute later
+        tracker.handle_event_at(&safari, start + Duration::from_secs(70));
+        tracker.end_current_session(start + Duration::from_secs(80));
+
+        let stats = tracker.get_app_statistics("com.apple.Safari").unwrap();
+        assert_eq!(stats.session_count, 2);
+        assert_eq!(stats.total_time, Duration::from_secs(20));
+    }
+
+    #[test]
+    fn test_sleep_splits_session_and_is_not_app_time() {
+        let mut tracker = TimeTracker::with_config(TimeTrackerConfig {
+            print_updates: false,
+            ..TimeTrackerConfig::default()
+        });
+        let safari = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 42),
+        );
+        let start = Instant::now();
+        let slept_at = Utc::now();
+
+        tracker.handle_event_at(&safari, start);
+        tracker.sleep_at(start + Duration::from_secs(30));
+        // Eight hours asleep; Safari is still frontmost on wake
+        let asleep = SleepPeriod {
+            start: slept_at,
+            end: slept_at + chrono::Duration::hours(8),
+        };
+        tracker.record_wake(&asleep);
+        tracker.handle_event_at(&safari, start + Duration::from_secs(8 * 3600 + 30));
+        tracker.end_current_session(start + Duration::from_secs(8 * 3600 + 45));
+
+        let stats = tracker.get_app_statistics("com.apple.Safari").unwrap();
+        assert_eq!(stats.session_count, 2);
+        assert_eq!(stats.total_time, Duration::from_secs(45));
+
+        let report = tracker.generate_report();
+        assert_eq!(report.system_asleep, Duration::from_secs(8 * 3600));
+        assert_eq!(report.total_active_time, Duration::from_secs(45));
+    }
+}
@@ -0,0 +1,420 @@
+// src/extractors/transition_graph.rs
+//! Focus transition graph
+//!
+//! Every time focus moves from one node to another, the edge between them
+//! gains a transition and the time spent in the node being left. Nodes are
+//! apps, or app categories as used by `--filter`. With URL nodes enabled,
+//! browser events that carry a URL also move focus between nodes for the
+//! canonical pages, so navigation within the browser shows up as url→url
+//! edges next to the app→app ones.
+//!
+//! The graph is written when monitoring stops, as Graphviz DOT (`.dot`,
+//! `.gv`) or as adjacency-list JSON (any other extension).
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchListener, AppSwitchType};
+use crate::core::running_apps::infer_app_category;
+use crate::core::url_canonicalizer::UrlCanonicalizer;
+
+/// Node for apps without a category
+pub const UNCATEGORIZED: &str = "other";
+
+/// What the nodes of the graph stand for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphNodes {
+    /// One node per bundle ID
+    #[default]
+    App,
+    /// One node per app category (`browser`, `ide`, ...)
+    Category,
+}
+
+impl fmt::Display for GraphNodes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphNodes::App => write!(f, "app"),
+            GraphNodes::Category => write!(f, "category"),
+        }
+    }
+}
+
+impl FromStr for GraphNodes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "app" => Ok(GraphNodes::App),
+            "category" => Ok(GraphNodes::Category),
+            other => Err(format!(
+                "Invalid graph node kind '{}' (expected app or category)",
+                other
+            )),
+        }
+    }
+}
+
+/// Transitions from one node to another
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransitionEdge {
+    pub to: String,
+    pub count: u64,
+    /// Time spent in the source node before these transitions
+    pub total_secs: f64,
+}
+
+/// The exported graph: node labels and outgoing edges per node
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransitionGraph {
+    pub nodes: BTreeMap<String, String>,
+    pub adjacency: BTreeMap<String, Vec<TransitionEdge>>,
+}
+
+impl TransitionGraph {
+    pub fn edge(&self, from: &str, to: &str) -> Option<&TransitionEdge> {
+        self.adjacency.get(from)?.iter().find(|edge| edge.to == to)
+    }
+
+    /// Graphviz DOT with transition counts as edge weights
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph transitions {\n    rankdir=LR;\n");
+        for (id, label) in &self.nodes {
+            dot.push_str(&format!(
+                "    {} [label={}];\n",
+                dot_quote(id),
+                dot_quote(label)
+            ));
+        }
+        for (from, edges) in &self.adjacency {
+            for edge in edges {
+                dot.push_str(&format!(
+                    "    {} -> {} [label=\"{} ({:.0}s)\", weight={}];\n",
+                    dot_quote(from),
+                    dot_quote(&edge.to),
+                    edge.count,
+                    edge.total_secs,
+                    edge.count
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Write DOT for `.dot` and `.gv` paths, JSON otherwise
+    pub fn write_to(&self, path: &Path) -> io::Result<()> {
+        let is_dot = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("dot") || ext.eq_ignore_ascii_case("gv"));
+        let contents = if is_dot {
+            self.to_dot()
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+        std::fs::write(path, contents)
+    }
+}
+
+fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[derive(Debug, Default)]
+struct EdgeStats {
+    count: u64,
+    total: Duration,
+}
+
+/// Builds the transition graph from the switch stream
+pub struct TransitionGraphTracker {
+    nodes: GraphNodes,
+    /// Canonicalizes page URLs when URL nodes are enabled
+    urls: Option<UrlCanonicalizer>,
+    output: Option<PathBuf>,
+
+    /// App or category node in focus and when it gained focus
+    current: Option<(String, Instant)>,
+    /// Page node in focus, while a browser showing a URL is frontmost
+    current_page: Option<(String, Instant)>,
+    labels: BTreeMap<String, String>,
+    edges: HashMap<(String, String), EdgeStats>,
+}
+
+impl TransitionGraphTracker {
+    pub fn new(nodes: GraphNodes) -> Self {
+        Self {
+            nodes,
+            urls: None,
+            output: None,
+            current: None,
+            current_page: None,
+            labels: BTreeMap::new(),
+            edges: HashMap::new(),
+        }
+    }
+
+    /// Use the canonical page URL as the node for events that carry one
+    pub fn with_urls(mut self, canonicalizer: UrlCanonicalizer) -> Self {
+        self.urls = Some(canonicalizer);
+        self
+    }
+
+    /// Write the graph to this file when monitoring stops
+    pub fn with_output(mut self, path: &Path) -> Self {
+        self.output = Some(path.to_path_buf());
+        self
+    }
+
+    /// App or category node id and label for an event
+    fn node_for(&self, event: &AppSwitchEvent) -> (String, String) {
+        let app = &event.app_info;
+        match self.nodes {
+            GraphNodes::App => (app.match_key().to_string(), app.name.clone()),
+            GraphNodes::Category => {
+                let category = infer_app_category(app);
+                (category.to_string(), category.to_string())
+            }
+        }
+    }
+
+    /// Canonical page URL of an event, when URL nodes are enabled
+    fn page_for(&self, event: &AppSwitchEvent) -> Option<String> {
+        let canonicalizer = self.urls.as_ref()?;
+        let url = event
+            .workspace
+            .as_ref()
+            .and_then(|w| w.primary_url.clone())
+            .or_else(|| event.enhanced.as_ref().and_then(|e| e.url.clone()))?;
+        Some(canonicalizer.canonicalize(&url))
+    }
+
+    /// Move focus to the event's nodes, counting the edges whose node changed
+    pub fn record(&mut self, event: &AppSwitchEvent) {
+        let (node, label) = self.node_for(event);
+        self.labels.entry(node.clone()).or_insert(label);
+        let mut current = self.current.take();
+        self.move_focus(&mut current, node, event.timestamp);
+        self.current = current;
+
+        // Leaving the browser ends the page without an edge; the app edge
+        // already records the switch
+        let mut current_page = self.current_page.take();
+        if let Some(page) = self.page_for(event) {
+            self.labels
+                .entry(page.clone())
+                .or_insert_with(|| page.clone());
+            self.move_focus(&mut current_page, page, event.timestamp);
+            self.current_page = current_page;
+        }
+    }
+
+    fn move_focus(&mut self, current: &mut Option<(String, Instant)>, node: String, at: Instant) {
+        match current.take() {
+            Some((from, since)) if from == node => *current = Some((from, since)),
+            Some((from, since)) => {
+                let stats = self.edges.entry((from, node.clone())).or_default();
+                stats.count += 1;
+                stats.total += at.saturating_duration_since(since);
+                *current = Some((node, at));
+            }
+            None => *current = Some((node, at)),
+        }
+    }
+
+    pub fn graph(&self) -> TransitionGraph {
+        let mut adjacency: BTreeMap<String, Vec<TransitionEdge>> = BTreeMap::new();
+        for ((from, to), stats) in &self.edges {
+            adjacency
+                .entry(from.clone())
+                .or_default()
+                .push(TransitionEdge {
+                    to: to.clone(),
+                    count: stats.count,
+                    total_secs: stats.total.as_secs_f64(),
+                });
+        }
+        for edges in adjacency.values_mut() {
+            edges.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.to.cmp(&b.to)));
+        }
+        TransitionGraph {
+            nodes: self.labels.clone(),
+            adjacency,
+        }
+    }
+}
+
+impl AppSwitchListener for TransitionGraphTracker {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        match event.event_type {
+            // Heartbeats only matter when the page changed under them
+            AppSwitchType::Foreground
+            | AppSwitchType::SpaceChange
+            | AppSwitchType::SessionChange
+            | AppSwitchType::Wake
+            | AppSwitchType::Heartbeat => self.record(event),
+            AppSwitchType::Background
+            | AppSwitchType::Launch
+            | AppSwitchType::Terminate
+            | AppSwitchType::Hide
+            | AppSwitchType::Unhide
+            | AppSwitchType::Sleep => {}
+        }
+    }
+
+    fn name(&self) -> &str {
+        "transition_graph"
+    }
+
+    fn on_monitoring_paused(&mut self) {
+        self.current = None;
+        self.current_page = None;
+    }
+
+    fn on_system_sleep(&mut self) {
+        self.current = None;
+        self.current_page = None;
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        self.current = None;
+        self.current_page = None;
+        if let Some(path) = &self.output {
+            if let Err(e) = self.graph().write_to(path) {
+                eprintln!("⚠️  Failed to write transition graph: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, EnhancedSummary};
+
+    fn switch(name: &str, bundle_id: &str, at: Instant) -> AppSwitchEvent {
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new(name.to_string(), bundle_id.to_string(), 1),
+        );
+        event.timestamp = at;
+        event
+    }
+
+    fn on_page(mut event: AppSwitchEvent, url: &str) -> AppSwitchEvent {
+        event.enhanced = Some(EnhancedSummary {
+            url: Some(url.to_string()),
+            ..EnhancedSummary::default()
+        });
+        event
+    }
+
+    #[test]
+    fn test_edges_count_transitions_and_time() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut tracker = TransitionGraphTracker::new(GraphNodes::App);
+        for event in [
+            switch("Safari", "com.apple.Safari", at(0)),
+            switch("Terminal", "com.apple.Terminal", at(95)),
+            switch("Safari", "com.apple.Safari", at(155)),
+            switch("Terminal", "com.apple.Terminal", at(200)),
+            switch("Xcode", "com.apple.dt.Xcode", at(260)),
+        ] {
+            tracker.on_app_switch(&event);
+        }
+        // A heartbeat in the same app is not a transition
+        let mut heartbeat = switch("Xcode", "com.apple.dt.Xcode", at(320));
+        heartbeat.event_type = AppSwitchType::Heartbeat;
+        tracker.on_app_switch(&heartbeat);
+
+        let graph = tracker.graph();
+        assert_eq!(graph.nodes["com.apple.Terminal"], "Terminal");
+        let edge = |from, to| graph.edge(from, to).cloned().unwrap();
+        assert_eq!(
+            edge("com.apple.Safari", "com.apple.Terminal"),
+            TransitionEdge {
+                to: "com.apple.Terminal".to_string(),
+                count: 2,
+                total_secs: 140.0,
+            }
+        );
+        assert_eq!(edge("com.apple.Terminal", "com.apple.Safari").count, 1);
+        assert_eq!(
+            edge("com.apple.Terminal", "com.apple.Safari").total_secs,
+            60.0
+        );
+        assert_eq!(edge("com.apple.Terminal", "com.apple.dt.Xcode").count, 1);
+        assert_eq!(graph.adjacency.values().flatten().count(), 3);
+        assert!(graph
+            .edge("com.apple.dt.Xcode", "com.apple.dt.Xcode")
+            .is_none());
+
+        let dot = graph.to_dot();
+        assert!(dot.contains(
+            "\"com.apple.Safari\" -> \"com.apple.Terminal\" [label=\"2 (140s)\", weight=2];"
+        ));
+        assert!(dot.contains("\"com.apple.Safari\" [label=\"Safari\"];"));
+
+        let json = serde_json::to_value(&graph).unwrap();
+        assert_eq!(
+            json["adjacency"]["com.apple.Safari"][0]["total_secs"],
+            140.0
+        );
+    }
+
+    #[test]
+    fn test_category_and_url_nodes() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        let mut by_category = TransitionGraphTracker::new(GraphNodes::Category);
+        for event in [
+            switch("Safari", "com.apple.Safari", at(0)),
+            switch("Chrome", "com.google.Chrome", at(10)),
+            switch("Xcode", "com.apple.dt.Xcode", at(20)),
+            switch("Lab", "com.example.lab", at(50)),
+        ] {
+            by_category.on_app_switch(&event);
+        }
+        let graph = by_category.graph();
+        assert_eq!(graph.edge("browser", "ide").unwrap().count, 1);
+        assert_eq!(graph.edge("browser", "ide").unwrap().total_secs, 20.0);
+        assert_eq!(graph.edge("ide", UNCATEGORIZED).unwrap().count, 1);
+        assert_eq!(graph.nodes.len(), 3);
+
+        let mut by_page =
+            TransitionGraphTracker::new(GraphNodes::App).with_urls(UrlCanonicalizer::default());
+        let safari = |secs, url| on_page(switch("Safari", "com.apple.Safari", at(secs)), url);
+        let mut navigated = safari(30, "https://example.com/b#top");
+        navigated.event_type = AppSwitchType::Heartbeat;
+        for event in [
+            safari(0, "https://www.example.com/a?utm_source=feed"),
+            navigated,
+            switch("Terminal", "com.apple.Terminal", at(50)),
+        ] {
+            by_page.on_app_switch(&event);
+        }
+        let graph = by_page.graph();
+        let page_edge = graph
+            .edge("https://example.com/a", "https://example.com/b")
+            .unwrap();
+        assert_eq!((page_edge.count, page_edge.total_secs), (1, 30.0));
+        // The browser's app edges are kept next to the page edges
+        let leave = graph
+            .edge("com.apple.Safari", "com.apple.Terminal")
+            .unwrap();
+        assert_eq!((leave.count, leave.total_secs), (1, 50.0));
+        assert!(graph
+            .edge("https://example.com/b", "com.apple.Terminal")
+            .is_none());
+    }
+}
@@ -0,0 +1,146 @@
+// src/extractors/url_time_tracker.rs
+//! Time spent per web page
+//!
+//! Foreground time is attributed to the URL reported for the event (workspace
+//! first, enhanced as fallback). URLs go through the `UrlCanonicalizer` first
+//! so that tracking parameters, fragments and `www.` variants of a page are
+//! counted as one.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::core::app_switcher_types::{AppSwitchEvent, AppSwitchListener};
+use crate::core::url_canonicalizer::UrlCanonicalizer;
+
+/// Aggregates focus time per canonical URL
+pub struct UrlTimeTracker {
+    canonicalizer: UrlCanonicalizer,
+
+    /// Canonical URL currently in focus and when it became active
+    current: Option<(String, Instant)>,
+
+    totals: HashMap<String, Duration>,
+}
+
+impl UrlTimeTracker {
+    pub fn new(canonicalizer: UrlCanonicalizer) -> Self {
+        Self {
+            canonicalizer,
+            current: None,
+            totals: HashMap::new(),
+        }
+    }
+
+    /// Add time for a URL, canonicalizing it first
+    pub fn record(&mut self, url: &str, duration: Duration) {
+        let canonical = self.canonicalizer.canonicalize(url);
+        *self.totals.entry(canonical).or_insert(Duration::ZERO) += duration;
+    }
+
+    /// Total time for a URL (any variant of it)
+    pub fn total_for(&self, url: &str) -> Duration {
+        self.totals
+            .get(&self.canonicalizer.canonicalize(url))
+            .copied()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// URLs sorted by time spent, longest first
+    pub fn top_urls(&self, limit: usize) -> Vec<(String, Duration)> {
+        let mut urls: Vec<(String, Duration)> =
+            self.totals.iter().map(|(u, d)| (u.clone(), *d)).collect();
+        urls.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        urls.truncate(limit);
+        urls
+    }
+
+    fn end_current(&mut self, now: Instant) {
+        if let Some((url, since)) = self.current.take() {
+            *self.totals.entry(url).or_insert(Duration::ZERO) += now.duration_since(since);
+        }
+    }
+
+    pub fn print_summary(&self) {
+        println!("\n🌐 Time per URL");
+        println!("═══════════════════════════════════════");
+        for (url, duration) in self.top_urls(20) {
+            println!("{:>8.1}m  {}", duration.as_secs_f64() / 60.0, url);
+        }
+        println!("═══════════════════════════════════════");
+    }
+}
+
+impl AppSwitchListener for UrlTimeTracker {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        if !event.event_type.is_foreground() {
+            return;
+        }
+        let now = Instant::now();
+        self.end_current(now);
+
+        let url = event
+            .workspace
+            .as_ref()
+            .and_then(|w| w.primary_url.clone())
+            .or_else(|| event.enhanced.as_ref().and_then(|e| e.url.clone()));
+        self.current = url.map(|u| (self.canonicalizer.canonicalize(&u), now));
+    }
+
+    // Sleep and pause are not time on the page; the `Foreground` event after
+    // wake or resume starts counting again
+    fn on_monitoring_paused(&mut self) {
+        self.end_current(Instant::now());
+    }
+
+    fn on_system_sleep(&mut self) {
+        self.end_current(Instant::now());
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        self.end_current(Instant::now());
+        if !self.totals.is_empty() {
+            self.print_summary();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::app_switcher_types::{AppInfo, AppSwitchType, EnhancedSummary};
+
+    #[test]
+    fn test_variants_aggregate_together() {
+        let mut tracker = UrlTimeTracker::new(UrlCanonicalizer::default());
+        tracker.record("https://www.example.com/post/?utm_source=x", Duration::from_secs(60));
+        tracker.record("https://example.com/post#top", Duration::from_secs(30));
+        tracker.record("https://example.com/other", Duration::from_secs(10));
+
+        assert_eq!(tracker.total_for("https://example.com/post"), Duration::from_secs(90));
+        let top = tracker.top_urls(1);
+        assert_eq!(top, vec![("https://example.com/post".to_string(), Duration::from_secs(90))]);
+    }
+
+    #[test]
+    fn test_sleep_ends_the_current_page() {
+        let mut tracker = UrlTimeTracker::new(UrlCanonicalizer::default());
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 1),
+        );
+        event.enhanced = Some(EnhancedSummary {
+            url: Some("https://example.com/post".to_string()),
+            ..EnhancedSummary::default()
+        });
+
+        tracker.on_app_switch(&event);
+        tracker.on_system_sleep();
+        assert!(tracker.current.is_none());
+        let before = tracker.total_for("https://example.com/post");
+
+        // Nothing is open, so time until stop is not counted
+        std::thread::sleep(Duration::from_millis(20));
+        tracker.on_monitoring_stopped();
+        assert_eq!(tracker.total_for("https://example.com/post"), before);
+    }
+}
@@ -160,7 +160,7 @@ fn run_main_thread_service(
                     pid: prev.pid,
                     path: prev.path.clone(),
                 }),
-                event_type: format!("{:?}", event.event_type),
+                event_type: event.event_type.as_str().to_string(),
                 window_title: event.workspace.as_ref()
                     .and_then(|w| w.focused_title.clone())
                     .or_else(|| event.enhanced.as_ref()
@@ -280,7 +280,7 @@ fn run_simplified_service(
                                 path: Some(format!("/Applications/TestApp{}.app", counter)),
                             },
                             previous_app: None,
-                            event_type: "Foreground".to_string(),
+                            event_type: "foreground".to_string(),
                             window_title: Some(format!("Test Window {}", counter)),
                             url: Some("https://example.com".to_string()),
                         };
@@ -8,6 +8,7 @@
 
 mod frb_generated; /* AUTO INJECTED BY flutter_rust_bridge. This line may not be accurate, and you can change it according to your needs. */
 
+pub mod config;
 pub mod core;
 pub mod extractors;
 // pub mod ffi_api;  // Temporarily disabled to avoid conflicts with new API
@@ -8,26 +8,102 @@
 
 #![deny(unsafe_op_in_unsafe_fn)]
 
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use clap::Parser;
 use objc2::MainThreadMarker;
 use objc2_app_kit::{NSApplication, NSApplicationActivationPolicy};
 use objc2_foundation::NSAutoreleasePool;
 // use tokio::signal;  // no longer used; CFRunLoop drives the runloop
-use core_foundation::runloop::CFRunLoopRun;
+use core_foundation::runloop::{CFRunLoop, CFRunLoopRun};
 use tracing::{error, info, warn};
 
-use research_assistant_tracker::core::accessibility::AccessibilityContextExtractor;
+use research_assistant_tracker::config::TrackerConfig;
+use research_assistant_tracker::core::accessibility::{
+    AccessibilityContextExtractor, WindowListCapture, DEFAULT_MAX_WINDOWS,
+};
 use research_assistant_tracker::core::app_switcher::{
     initialize_app_switcher, AppSwitchEvent, AppSwitchListener, AppSwitchType, AppSwitcher,
+    SleepPeriod,
 };
 // Optional non-AX scroll trigger (use local module wrapper to avoid crate path issues)
 mod detectors;
 use crate::detectors::scroll_tap::{ScrollEvent, ScrollListener, ScrollTap};
+use research_assistant_tracker::core::buffered_sink::{BufferedSink, FlushPolicy};
+use research_assistant_tracker::core::clipboard::{
+    AxCopyContext, ClipboardEvent, ClipboardListener, ClipboardMonitor,
+};
+use research_assistant_tracker::core::compaction::{compact_file, DEFAULT_KEY_FIELDS};
+use research_assistant_tracker::core::confidence::{parse_confidence, ConfidenceFilter};
+use research_assistant_tracker::core::consent::{
+    default_consent_path, ConsentRecord, ConsentScope, ContentConsent,
+};
+use research_assistant_tracker::core::context_diff::{without_diffed_fields, ContextDiffer};
+use research_assistant_tracker::core::display_config::{
+    DisplayConfigChanged, DisplayConfigListener, DisplayConfigObserver,
+};
+use research_assistant_tracker::core::appearance::{
+    AppearanceChange, AppearanceListener, AppearanceObserver,
+};
+use research_assistant_tracker::core::event_record::EventRecord;
+use research_assistant_tracker::core::event_socket::{follow, FollowFormat, SocketEventLogger};
+#[cfg(feature = "otel")]
+use research_assistant_tracker::extractors::otel_sessions::OtelSessionExporter;
+use research_assistant_tracker::core::event_tap::{EventCallback, EventInfo, EventTap};
+use research_assistant_tracker::core::extraction_bench::run_extraction_bench;
+use research_assistant_tracker::core::flat_json::flatten as flatten_json;
+use research_assistant_tracker::core::heartbeat::HeartbeatEmitter;
+use research_assistant_tracker::core::input_source::{
+    InputSourceChange, InputSourceListener, InputSourceObserver,
+};
+use research_assistant_tracker::core::listener_worker::{
+    ListenerWorker, OverflowPolicy, DEFAULT_QUEUE_CAPACITY,
+};
+use research_assistant_tracker::core::menu_context::MenuContextObserver;
+use research_assistant_tracker::core::navigation::{NavigationFilter, NavigationPolicy};
+use research_assistant_tracker::core::notification_observer::{
+    NotificationEvent, NotificationListener, NotificationObserver,
+};
+use research_assistant_tracker::core::path_redaction::PathRedactor;
+use research_assistant_tracker::core::power_state::{
+    PowerMonitor, PowerStateChanged, PowerStateListener,
+};
+use research_assistant_tracker::core::previous_context::PreviousContextTracker;
+use research_assistant_tracker::core::running_apps::{
+    format_app_json, format_app_table, list_running_apps,
+};
+use research_assistant_tracker::core::quiet_hours::QuietHoursGate;
+use research_assistant_tracker::core::self_exclusion::SelfExclusion;
+use research_assistant_tracker::core::session::{new_session_id, SessionManifest, SessionTagger};
+use research_assistant_tracker::core::session_deadline::{parse_duration, SessionDeadline};
+use research_assistant_tracker::core::significant_change::{ResamplePolicy, SignificantChangeFilter};
+use research_assistant_tracker::core::timestamp_format::TimestampFormat;
+use research_assistant_tracker::core::title_exclusion::TitleExclusion;
+use research_assistant_tracker::core::window_geometry::{
+    WindowGeometryChanged, WindowGeometryListener, WindowGeometryObserver, DEFAULT_GEOMETRY_SETTLE,
+};
+use research_assistant_tracker::core::url_canonicalizer::UrlCanonicalizer;
+use research_assistant_tracker::core::window_screenshot::{
+    ScreenRecordingPermission, ScreenshotCapability, SystemScreenRecording, WindowScreenshotter,
+    DEFAULT_SCREENSHOT_INTERVAL,
+};
+use research_assistant_tracker::extractors::activity_meter::ActivityMeter;
+use research_assistant_tracker::extractors::focus_blocks::FocusBlockTracker;
+use research_assistant_tracker::extractors::interaction_mode::InteractionModeTracker;
+use research_assistant_tracker::extractors::scroll_summary::{ScrollRecorder, ScrollSummaryTracker};
+use research_assistant_tracker::extractors::streak_reminder::StreakReminder;
+use research_assistant_tracker::extractors::tag_aggregator::TagTimeAggregator;
+use research_assistant_tracker::extractors::clipboard_stats::ClipboardStats;
 use research_assistant_tracker::extractors::time_tracker::{TimeTracker, TimeTrackerConfig};
+use research_assistant_tracker::extractors::recent_files::RecentFilesTracker;
+use research_assistant_tracker::extractors::transition_graph::{
+    GraphNodes, TransitionGraphTracker,
+};
+use research_assistant_tracker::extractors::url_time_tracker::UrlTimeTracker;
 
 /// Command line interface for the research assistant tracker
 ///
@@ -56,6 +132,14 @@ struct Args {
     #[arg(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
 
+    /// Per-module log levels on top of the verbosity level
+    #[arg(
+        long,
+        value_name = "DIRECTIVES",
+        help = "Log levels per module, e.g. research_assistant_tracker::core::accessibility=debug (overrides RUST_LOG; a bare level replaces the -v level)"
+    )]
+    log: Option<String>,
+
     /// Run in background mode (no interactive prompts)
     #[arg(long, help = "Run without prompting for permissions")]
     background: bool,
@@ -71,9 +155,496 @@ struct Args {
     #[arg(long, help = "Write structured events to file")]
     output_file: Option<std::path::PathBuf>,
 
+    /// Unix socket for live followers
+    #[arg(
+        long,
+        help = "Publish events as JSON lines on this Unix socket for `follow` clients"
+    )]
+    socket: Option<std::path::PathBuf>,
+
+    /// OTLP collector for focus session spans
+    #[cfg(feature = "otel")]
+    #[arg(
+        long,
+        help = "Export focus sessions as OpenTelemetry spans to this OTLP/HTTP endpoint (e.g. http://localhost:4318/v1/traces)"
+    )]
+    otel_endpoint: Option<String>,
+
     /// Check permissions and exit
     #[arg(long, help = "Check required permissions and exit")]
     check_permissions: bool,
+
+    /// TOML configuration file
+    #[arg(long, help = "Load tags, rules and other settings from a TOML file")]
+    config: Option<std::path::PathBuf>,
+
+    /// Where content-capture consent is recorded
+    #[arg(
+        long,
+        help = "File recording which content (urls, titles, clipboard, selection) may be captured; defaults to ~/Library/Application Support/research-assistant-tracker/consent.json"
+    )]
+    consent_file: Option<std::path::PathBuf>,
+
+    /// Record consent without the first-run prompt
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Record consent for these content scopes (urls,titles,clipboard,selection) in the consent file and start"
+    )]
+    grant_consent: Vec<ConsentScope>,
+
+    /// Flush policy for file outputs
+    #[arg(
+        long,
+        default_value = "100ms",
+        help = "When file outputs are flushed: per-event, <N>events or <N>ms"
+    )]
+    flush_policy: FlushPolicy,
+
+    /// Deliver events to loggers and trackers on a worker thread
+    #[arg(
+        long,
+        default_value_t = DEFAULT_QUEUE_CAPACITY,
+        help = "Events queued for the listener worker thread; 0 delivers on the run loop thread"
+    )]
+    listener_queue: usize,
+
+    /// What to do when the listener queue is full
+    #[arg(
+        long,
+        default_value = "block",
+        help = "When the listener queue is full: block, drop-oldest or drop-new"
+    )]
+    queue_overflow: OverflowPolicy,
+
+    /// Re-announce the current app while the user stays in it
+    #[arg(
+        long,
+        value_name = "SECS",
+        num_args = 0..=1,
+        default_missing_value = "60",
+        help = "Emit a Heartbeat event with the current context after SECS seconds without events (default 60)"
+    )]
+    heartbeat: Option<u64>,
+
+    /// Stop on our own after a fixed session length
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        help = "Stop monitoring, flush outputs and print the summary after this long, e.g. 90m, 2h or 1h30m"
+    )]
+    max_duration: Option<Duration>,
+
+    /// How timestamps are written in every output format
+    #[arg(
+        long,
+        default_value = "rfc3339",
+        help = "Timestamp format: rfc3339, local or epoch-ms; rfc3339/local take -s, -ms, -us or -ns for precision"
+    )]
+    timestamp_format: TimestampFormat,
+
+    /// Write switch events as single-level objects with dotted keys
+    #[arg(
+        long,
+        help = "With --format json, write each switch as one flat object with dotted keys (app.bundle_id, enhanced.url, ...); every key is present, null when unset"
+    )]
+    json_flat: bool,
+
+    /// Quiet period before merged updates to one app are emitted
+    #[arg(
+        long,
+        default_value_t = 150,
+        help = "Merge title/URL updates to the same app arriving within this many milliseconds"
+    )]
+    coalesce_ms: u64,
+
+    /// Grace period for enhanced extraction to warm up before the first event
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Hold the first event for this many milliseconds after startup and re-extract the frontmost app halfway through, so it carries full enhanced context"
+    )]
+    startup_warmup_ms: u64,
+
+    /// Drop events the sources are less sure about
+    #[arg(
+        long,
+        default_value_t = 0.0,
+        value_parser = parse_confidence,
+        help = "Drop switch events with a confidence below this value (0.0-1.0); e.g. 0.9 drops resamples after Space changes and wake"
+    )]
+    min_confidence: f32,
+
+    /// Which URL changes within an app count as a new page
+    #[arg(
+        long,
+        default_value = "strict",
+        help = "Emit URL changes within an app only when the canonical URL changed (strict) or on any change (any)"
+    )]
+    navigation_policy: NavigationPolicy,
+
+    /// Which resamples of the app in focus are emitted
+    #[arg(
+        long,
+        default_value = "always",
+        help = "Emit every resample of the app in focus (always) or only those whose URL, title, file or selection changed (significant)"
+    )]
+    resample_policy: ResamplePolicy,
+
+    /// Write focus blocks (uninterrupted time in one app) as JSON lines
+    #[arg(long, help = "Append focus blocks to a JSON lines file")]
+    focus_blocks: Option<std::path::PathBuf>,
+
+    /// Score focus blocks with keyboard/mouse engagement
+    #[arg(
+        long,
+        help = "Measure input engagement for focus blocks (counts only, never keys) - requires Input Monitoring permission"
+    )]
+    engagement: bool,
+
+    /// Span the attention score of focus blocks is computed over
+    #[arg(
+        long,
+        default_value_t = 600,
+        help = "Score each focus block's attention (switch rate, median dwell, app spread) over the last SECS seconds"
+    )]
+    attention_window: u64,
+
+    /// Write the interaction mode (composing/reading/navigating) of each dwell period
+    #[arg(
+        long,
+        help = "Append interaction modes derived from focused-element roles to a JSON lines file - requires accessibility permissions"
+    )]
+    interaction_modes: Option<std::path::PathBuf>,
+
+    /// Write scroll direction, speed and reading/scanning style per dwell period
+    #[arg(
+        long,
+        help = "Append per-app scroll summaries (direction, velocity, reading vs fast scan) to a JSON lines file"
+    )]
+    scroll_summaries: Option<std::path::PathBuf>,
+
+    /// Write the graph of focus transitions between apps on exit
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write app-to-app transition counts and times on exit; DOT for .dot/.gv paths, adjacency-list JSON otherwise"
+    )]
+    transition_graph: Option<std::path::PathBuf>,
+
+    /// Nodes of the transition graph
+    #[arg(
+        long,
+        default_value = "app",
+        help = "Transition graph nodes: app or category"
+    )]
+    transition_graph_nodes: GraphNodes,
+
+    /// Split browser nodes per page in the transition graph
+    #[arg(
+        long,
+        help = "Use the page URL as the transition graph node for browser events, adding url-to-url edges"
+    )]
+    transition_graph_urls: bool,
+
+    /// Summarize recently used files on exit
+    #[arg(
+        long,
+        help = "Print the files last used in editors and document apps, with time spent in each, on exit"
+    )]
+    recent_files: bool,
+
+    /// Report notification banners (source app and time only)
+    #[arg(
+        long,
+        help = "Report which apps post notification banners - requires accessibility permissions"
+    )]
+    notifications: bool,
+
+    /// Report switches between light and dark mode
+    #[arg(long, help = "Report when the system switches between light and dark mode")]
+    appearance: bool,
+
+    /// Report keyboard layout and input method switches
+    #[arg(
+        long,
+        help = "Report keyboard layout / input method switches and add the current input source to events"
+    )]
+    input_source: bool,
+
+    /// Report display reconfiguration (docking, mirroring, arrangement)
+    #[arg(
+        long,
+        help = "Report display configuration changes with the resolution and arrangement of every display"
+    )]
+    display_config: bool,
+
+    /// Report settled window moves and resizes of the frontmost app
+    #[arg(
+        long,
+        help = "Report where windows settle after being moved or resized (one event per drag) - requires accessibility permissions"
+    )]
+    window_geometry: bool,
+
+    /// Attach the open menu path to events
+    #[arg(
+        long,
+        help = "Attach the path of the open menu item (e.g. File › Export › PDF), including menu-bar-only apps, to events - requires accessibility permissions"
+    )]
+    menu_context: bool,
+
+    /// Record power source, battery level and Low Power Mode
+    #[arg(
+        long,
+        help = "Record power source (AC or battery), battery percentage and Low Power Mode in the session manifest and on events, and report when the laptop is plugged in or unplugged"
+    )]
+    capture_power: bool,
+
+    /// Write power source and Low Power Mode changes as JSON lines
+    #[arg(
+        long,
+        help = "Append power source and Low Power Mode changes to a JSON lines file (implies --capture-power)"
+    )]
+    power_changes: Option<std::path::PathBuf>,
+
+    /// Report clipboard changes (types, source app and, for allowed image types, an image summary)
+    #[arg(
+        long,
+        help = "Report clipboard changes and the app they were copied from"
+    )]
+    clipboard: bool,
+
+    /// Keep a small PNG thumbnail of copied images
+    #[arg(
+        long,
+        help = "Decode copied images into 128px thumbnails (implies --clipboard and allows the image types)"
+    )]
+    capture_clipboard_thumbnails: bool,
+
+    /// Store a perceptual hash of copied images
+    #[arg(
+        long,
+        help = "Record a perceptual hash (dHash) of copied images to follow them across apps (implies --clipboard and allows the image types)"
+    )]
+    clipboard_image_hash: bool,
+
+    /// Record the source window at each copy
+    #[arg(
+        long,
+        help = "Record the source window's title, URL and file with each copy, and its selection with --capture-content (implies --clipboard)"
+    )]
+    clipboard_context: bool,
+
+    /// Write copy/paste statistics on exit
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write copy and paste counts, copied types, top source apps, copy-to-paste time and cross-app flows as JSON on exit (implies --clipboard; pastes need accessibility permissions)"
+    )]
+    clipboard_stats: Option<std::path::PathBuf>,
+
+    /// Save a screenshot of the focused window on foreground switches
+    #[arg(
+        long,
+        help = "Save a PNG of the focused window on each switch and add its path to events (rate-limited, skips private windows) - requires Screen Recording permission"
+    )]
+    capture_screenshots: bool,
+
+    /// Directory the screenshots are written to
+    #[arg(long, default_value = "screenshots")]
+    screenshot_dir: std::path::PathBuf,
+
+    /// Minimum seconds between two screenshots
+    #[arg(
+        long,
+        default_value_t = DEFAULT_SCREENSHOT_INTERVAL.as_secs(),
+        help = "Take at most one screenshot every SECS seconds (with --capture-screenshots)"
+    )]
+    screenshot_interval: u64,
+
+    /// Include all windows of the foreground app in JSON events
+    #[arg(
+        long,
+        help = "Capture every window of the foreground app (title, bounds, minimized, main)"
+    )]
+    capture_windows: bool,
+
+    /// Read focused-element content (AXValue, AXSelectedText)
+    #[arg(
+        long,
+        help = "Read text content of the focused element - off by default for privacy"
+    )]
+    capture_content: bool,
+
+    /// Maximum number of windows captured per app
+    #[arg(long, default_value_t = DEFAULT_MAX_WINDOWS)]
+    max_windows: usize,
+
+    /// Send the fields changed since the previous switch in place of the
+    /// url/title/file/space context
+    #[arg(
+        long,
+        help = "Send only url/title/file/space changes since the previous switch"
+    )]
+    emit_diffs: bool,
+
+    /// Attach the previous app's last-known context to each switch
+    #[arg(
+        long,
+        help = "Add the url/title/file the previous app was last showing to each switch"
+    )]
+    previous_context: bool,
+
+    /// Write a JSON manifest describing this session (version, OS, host, displays)
+    #[arg(long, help = "Write a session manifest to this path at startup")]
+    session_manifest: Option<std::path::PathBuf>,
+
+    /// Store a SHA-256 of the hostname in the session manifest
+    #[arg(long)]
+    hash_hostname: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+impl Args {
+    /// Names of the optional features enabled on the command line
+    fn enabled_features(&self) -> Vec<String> {
+        #[cfg(feature = "otel")]
+        let otel = self.otel_endpoint.is_some();
+        #[cfg(not(feature = "otel"))]
+        let otel = false;
+        [
+            ("enhanced", self.enhanced),
+            ("output_file", self.output_file.is_some()),
+            ("json_flat", self.json_flat),
+            ("socket", self.socket.is_some()),
+            ("focus_blocks", self.focus_blocks.is_some()),
+            ("engagement", self.engagement),
+            ("interaction_modes", self.interaction_modes.is_some()),
+            ("scroll_summaries", self.scroll_summaries.is_some()),
+            ("transition_graph", self.transition_graph.is_some()),
+            ("recent_files", self.recent_files),
+            ("notifications", self.notifications),
+            ("appearance", self.appearance),
+            ("input_source", self.input_source),
+            ("display_config", self.display_config),
+            ("window_geometry", self.window_geometry),
+            ("menu_context", self.menu_context),
+            ("capture_power", self.capture_power || self.power_changes.is_some()),
+            ("power_changes", self.power_changes.is_some()),
+            ("heartbeat", self.heartbeat.is_some()),
+            ("max_duration", self.max_duration.is_some()),
+            (
+                "clipboard",
+                self.clipboard
+                    || self.capture_clipboard_thumbnails
+                    || self.clipboard_image_hash
+                    || self.clipboard_context
+                    || self.clipboard_stats.is_some(),
+            ),
+            ("clipboard_thumbnails", self.capture_clipboard_thumbnails),
+            ("clipboard_image_hash", self.clipboard_image_hash),
+            ("clipboard_context", self.clipboard_context),
+            ("clipboard_stats", self.clipboard_stats.is_some()),
+            ("capture_screenshots", self.capture_screenshots),
+            ("capture_windows", self.capture_windows),
+            ("capture_content", self.capture_content),
+            ("emit_diffs", self.emit_diffs),
+            ("previous_context", self.previous_context),
+            ("otel", otel),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name.to_string())
+        .collect()
+    }
+}
+
+/// Switch off capture the recorded consent does not cover
+///
+/// Image capture flags imply `--clipboard`; clipboard monitoring stays on
+/// with metadata only when they are switched off.
+fn restrict_to_consent(config: &mut Args, settings: &mut TrackerConfig, consent: &ContentConsent) {
+    if !consent.allows(ConsentScope::Selection) {
+        config.capture_content = false;
+        settings.attributes.capture_content = false;
+    }
+    if !consent.allows(ConsentScope::Clipboard) {
+        config.clipboard |= config.capture_clipboard_thumbnails || config.clipboard_image_hash;
+        config.capture_clipboard_thumbnails = false;
+        config.clipboard_image_hash = false;
+        settings.clipboard.capture_thumbnails = false;
+        settings.clipboard.perceptual_hash = false;
+    }
+    if !consent.allows(ConsentScope::Titles) {
+        config.recent_files = false;
+        config.window_geometry = false;
+        config.menu_context = false;
+    }
+    if !consent.allows(ConsentScope::Screenshots) {
+        config.capture_screenshots = false;
+    }
+}
+
+/// One-shot commands that run instead of the tracker
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// List running applications with their bundle ids (no permissions needed)
+    ListApps {
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+
+        /// Include background/agent processes, not just Dock apps
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Time repeated context extraction against the frontmost app
+    BenchExtract {
+        /// Number of extractions to run
+        #[arg(long, default_value = "20")]
+        iterations: usize,
+    },
+
+    /// Collapse runs of repeated consecutive records in a JSONL output file
+    Compact {
+        /// JSONL file written by --output-file
+        input: std::path::PathBuf,
+
+        /// Where to write the compacted records
+        output: std::path::PathBuf,
+
+        /// Dotted fields that must match for records to collapse
+        #[arg(long, value_delimiter = ',')]
+        keys: Vec<String>,
+    },
+
+    /// Print the JSON Schema for records written by --output-file
+    Schema,
+
+    /// Check a TOML config file and print the configuration the tracker would use
+    ValidateConfig {
+        /// File passed to the tracker's --config
+        #[arg(long)]
+        config: std::path::PathBuf,
+    },
+
+    /// Print the live event stream of a tracker started with --socket
+    Follow {
+        /// Socket passed to the tracker's --socket
+        socket: std::path::PathBuf,
+
+        /// Output format: human or json
+        #[arg(long, default_value = "human")]
+        format: FollowFormat,
+
+        /// Exit when the tracker stops instead of waiting for it to restart
+        #[arg(long)]
+        no_reconnect: bool,
+    },
 }
 
 #[derive(Debug, Clone, clap::ValueEnum)]
@@ -93,7 +664,20 @@ enum OutputFormat {
 struct TrackerApp {
     app_switcher: Arc<Mutex<AppSwitcher>>,
     config: Args,
+    settings: TrackerConfig,
+    /// Content scopes the user agreed to; everything else is timing only
+    consent: ContentConsent,
     start_time: std::time::Instant,
+    /// Input tap feeding the activity meter; boxed so its address stays stable
+    event_tap: Option<Box<EventTap>>,
+    notification_observer: Option<NotificationObserver>,
+    appearance_observer: Option<AppearanceObserver>,
+    input_source_observer: Option<InputSourceObserver>,
+    display_config_observer: Option<DisplayConfigObserver>,
+    window_geometry_observer: Option<WindowGeometryObserver>,
+    menu_context_observer: Option<MenuContextObserver>,
+    power_monitor: Option<PowerMonitor>,
+    clipboard_monitor: Option<ClipboardMonitor>,
 }
 
 impl TrackerApp {
@@ -101,7 +685,7 @@ impl TrackerApp {
     ///
     /// This constructor sets up all the necessary components and validates
     /// that we have the required permissions to operate.
-    async fn new(config: Args) -> Result<Self> {
+    async fn new(mut config: Args) -> Result<Self> {
         let start_time = std::time::Instant::now();
 
         // Initialize logging based on verbosity
@@ -125,16 +709,99 @@ impl TrackerApp {
             return Err(anyhow::anyhow!("This application only runs on macOS"));
         }
 
+        // Load file-based settings; every section falls back to defaults
+        let mut settings = match &config.config {
+            Some(path) => TrackerConfig::load(path).map_err(|e| anyhow::anyhow!(e))?,
+            None => TrackerConfig::default(),
+        };
+
+        // Content capture needs recorded consent, whatever the flags say
+        let consent = Self::resolve_consent(&config)?;
+        restrict_to_consent(&mut config, &mut settings, &consent);
+
         // Set up the core app switcher
         let app_switcher = Arc::new(Mutex::new(AppSwitcher::new()));
 
         Ok(Self {
             app_switcher,
             config,
+            settings,
+            consent,
             start_time,
+            event_tap: None,
+            notification_observer: None,
+            appearance_observer: None,
+            input_source_observer: None,
+            display_config_observer: None,
+            window_geometry_observer: None,
+            menu_context_observer: None,
+            power_monitor: None,
+            clipboard_monitor: None,
         })
     }
 
+    /// Record `--grant-consent`, or load the recorded consent and ask for it
+    /// on the first interactive run; without any, content capture stays off
+    fn resolve_consent(config: &Args) -> Result<ContentConsent> {
+        let Some(path) = config.consent_file.clone().or_else(default_consent_path) else {
+            warn!("⚠️  No location for the consent file; recording apps and timing only");
+            return Ok(ContentConsent::default());
+        };
+
+        let record = if !config.grant_consent.is_empty() {
+            let record = ConsentRecord::new(config.grant_consent.iter().copied(), Utc::now());
+            record.save(&path).map_err(|e| anyhow::anyhow!(e))?;
+            info!("✅ Consent recorded in {}", path.display());
+            Some(record)
+        } else {
+            match ConsentRecord::load(&path).map_err(|e| anyhow::anyhow!(e))? {
+                Some(record) => Some(record),
+                None if io::stdin().is_terminal() => {
+                    let record = Self::prompt_for_consent()?;
+                    record.save(&path).map_err(|e| anyhow::anyhow!(e))?;
+                    Some(record)
+                }
+                None => None,
+            }
+        };
+
+        let consent = ContentConsent::from_record(record.as_ref());
+        if consent.is_timing_only() {
+            info!("🔒 No content-capture consent recorded; recording apps and timing only (see --grant-consent)");
+        } else if let Some(record) = &record {
+            let scopes: Vec<String> = record.scopes.iter().map(|s| s.to_string()).collect();
+            info!(
+                "🔓 Content capture consented on {}: {}",
+                record.acknowledged_at.format("%Y-%m-%d"),
+                scopes.join(", ")
+            );
+        }
+        Ok(consent)
+    }
+
+    /// Ask once whether content may be captured; a refusal is recorded too
+    /// so the question is not repeated on every start
+    fn prompt_for_consent() -> Result<ConsentRecord> {
+        eprintln!("This tracker can record the content you work with:");
+        eprintln!("  urls       page addresses and domains");
+        eprintln!("  titles     window, tab and document titles, file paths");
+        eprintln!("  clipboard  copied images");
+        eprintln!("  selection  text of the focused field and selection");
+        eprintln!("  screenshots  images of the focused window");
+        eprintln!("Without consent only apps, categories and timing are recorded.");
+        eprint!("Allow content capture for all of these? [y/N] ");
+        io::stderr().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        let scopes = if matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            ConsentScope::ALL.to_vec()
+        } else {
+            Vec::new()
+        };
+        Ok(ConsentRecord::new(scopes, Utc::now()))
+    }
+
     /// Run the tracker application
     ///
     /// This is the main event loop that coordinates all the different
@@ -160,7 +827,34 @@ impl TrackerApp {
 
         info!("👀 Monitoring started. Press Ctrl+C to stop gracefully.");
 
-        // Run until interrupted
+        // The first AppleScript call often times out; extract again once it
+        // has warmed up so the held first event picks up the context
+        if self.config.startup_warmup_ms > 0 {
+            Self::spawn_startup_reextraction(
+                Arc::clone(&self.app_switcher),
+                Duration::from_millis(self.config.startup_warmup_ms / 2),
+            );
+        }
+
+        // SIGUSR1 toggles enhanced extraction on the live switcher
+        if self.config.enhanced {
+            Self::spawn_enhanced_toggle(Arc::clone(&self.app_switcher))?;
+        }
+
+        // A deadline stops the run loop so shutdown below runs as usual
+        let _deadline_timer = self.config.max_duration.map(|max_duration| {
+            info!("⏱️  Monitoring stops after {}s", max_duration.as_secs());
+            let deadline = SessionDeadline::new(Instant::now(), max_duration, || {
+                info!("⏱️  Maximum duration reached");
+                CFRunLoop::get_main().stop();
+            });
+            deadline.schedule_on_main_run_loop()
+        });
+
+        // Ctrl+C and SIGTERM stop the run loop so shutdown below runs
+        Self::spawn_shutdown_signals()?;
+
+        // Run until interrupted or the deadline passes
         self.run_until_interrupted().await?;
 
         // Graceful shutdown
@@ -218,54 +912,494 @@ impl TrackerApp {
     /// conditionally enable different types of monitoring based on
     /// user preferences and available permissions.
     async fn setup_listeners(&mut self) -> Result<()> {
+        // Copies come from the clipboard monitor, pastes from the input tap
+        let clipboard_stats = self
+            .config
+            .clipboard_stats
+            .as_ref()
+            .map(|path| ClipboardStats::new().with_output(path));
+
+        // Engagement scoring and paste counting need a live input tap
+        let engagement = self.config.focus_blocks.is_some() && self.config.engagement;
+        let activity = if engagement || clipboard_stats.is_some() {
+            self.start_input_tap(engagement, clipboard_stats.clone())
+        } else {
+            None
+        };
+
         let mut switcher = self.app_switcher.lock().unwrap();
 
-        // Always add basic logging
-        let basic_logger = BasicEventLogger::new(self.config.format.clone());
-        switcher.add_listener(basic_logger);
+        switcher.set_coalesce_window(Duration::from_millis(self.config.coalesce_ms));
+        switcher.set_startup_warmup(Duration::from_millis(self.config.startup_warmup_ms));
 
-        // Always add time tracking - this is core functionality
-        let time_tracker_config = TimeTrackerConfig {
-            print_updates: self.config.verbose > 0,
-            min_session_duration: Duration::from_secs(2),
-            track_background: false,
-            max_history_size: 10000,
-        };
-        let time_tracker = TimeTracker::with_config(time_tracker_config);
-        switcher.add_listener(time_tracker);
-        info!("⏰ Time tracking enabled");
+        if self.config.min_confidence > 0.0 {
+            switcher.set_confidence_filter(ConfidenceFilter::new(self.config.min_confidence));
+            info!("🎯 Minimum confidence: {:.2}", self.config.min_confidence);
+        }
 
-        // Add enhanced context extraction if requested
-        if self.config.enhanced {
-            match AccessibilityContextExtractor::new() {
-                Ok(extractor) => {
-                    info!("🔍 Enhanced context extraction enabled");
-                    switcher.add_listener(extractor);
-                }
-                Err(e) => {
-                    if self.config.background {
-                        error!(
-                            "❌ Enhanced context requires accessibility permissions: {}",
-                            e
-                        );
-                        return Err(anyhow::anyhow!("Accessibility permissions required"));
-                    } else {
-                        warn!("⚠️  Enhanced context unavailable: {}", e);
-                        warn!("💡 Enable in: System Settings → Privacy & Security → Accessibility");
-                    }
-                }
+        switcher.set_navigation_filter(NavigationFilter::new(
+            self.config.navigation_policy,
+            UrlCanonicalizer::new(self.settings.urls.clone()),
+        ));
+        switcher.set_significant_change_filter(SignificantChangeFilter::new(
+            self.config.resample_policy,
+        ));
+
+        // Keep the tracker (and optionally its terminal) out of the data
+        let exclusion = SelfExclusion::from_config(&self.settings.exclusion);
+        if !exclusion.is_empty() {
+            switcher.set_self_exclusion(exclusion);
+            info!("🙈 Self-exclusion enabled");
+        }
+
+        // Power source and battery, followed as they change and stamped on events
+        if self.config.capture_power || self.config.power_changes.is_some() {
+            let mut monitor = PowerMonitor::new();
+            monitor.add_listener(PowerStatePrinter {
+                format: self.config.format.clone(),
+                timestamps: self.config.timestamp_format,
+            });
+            if let Some(path) = &self.config.power_changes {
+                monitor.add_listener(
+                    PowerChangeLogger::new(path, self.config.flush_policy)?
+                        .with_timestamps(self.config.timestamp_format),
+                );
+                info!("🔌 Power changes enabled: {}", path.display());
             }
+            monitor.start();
+            switcher.add_enrichment_stage(monitor.tagger());
+            info!("🔋 Power capture enabled ({:?})", monitor.latest());
+            self.power_monitor = Some(monitor);
         }
 
-        // Add file output if specified
-        if let Some(output_path) = &self.config.output_file {
-            let file_logger = FileEventLogger::new(output_path.clone())?;
-            switcher.add_listener(file_logger);
-            info!("📁 File output enabled: {}", output_path.display());
+        // Keyboard layout switches, reported and stamped on events
+        if self.config.input_source {
+            let mut observer = InputSourceObserver::new();
+            observer.add_listener(InputSourcePrinter {
+                format: self.config.format.clone(),
+                timestamps: self.config.timestamp_format,
+            });
+            observer.start();
+            switcher.add_enrichment_stage(observer.tagger());
+            info!(
+                "⌨️  Input source tracking enabled ({})",
+                observer
+                    .input_source()
+                    .map(|source| source.label)
+                    .unwrap_or_else(|| "unknown".to_string())
+            );
+            self.input_source_observer = Some(observer);
         }
 
-        Ok(())
-    }
+        // Every event carries the session id; the manifest describes the session
+        let session_id = new_session_id();
+        switcher.add_enrichment_stage(SessionTagger::new(&session_id));
+        info!("🆔 Session {}", session_id);
+        if let Some(path) = &self.config.session_manifest {
+            SessionManifest::collect(
+                &session_id,
+                self.config.enabled_features(),
+                self.config.hash_hostname,
+            )
+            .with_power(self.power_monitor.as_ref().and_then(|m| m.latest()))
+            .write_to(path)
+            .context("Failed to write session manifest")?;
+            info!("🧾 Session manifest written: {}", path.display());
+        }
+
+        // Excluded windows lose their content first, while their titles are intact
+        let title_exclusion = TitleExclusion::from_config(&self.settings.title_exclusion)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        if !self.settings.title_exclusion.is_empty() {
+            switcher.add_enrichment_stage(title_exclusion.clone());
+            info!(
+                "🚫 Title exclusion enabled ({} patterns)",
+                self.settings.title_exclusion.patterns.len()
+            );
+        }
+
+        // Content without recorded consent is dropped before any logger sees it
+        switcher.add_enrichment_stage(self.consent.clone());
+
+        // Content is dropped centrally so no logger can record it in quiet hours
+        let quiet_hours = if self.settings.quiet_hours.is_empty() {
+            None
+        } else {
+            let gate = QuietHoursGate::from_config(&self.settings.quiet_hours)
+                .map_err(|e| anyhow::anyhow!(e))?;
+            switcher.add_enrichment_stage(gate.clone());
+            info!("🌙 Quiet hours enabled");
+            Some(gate)
+        };
+
+        // File paths are truncated or hashed before any logger sees them
+        let path_redactor = if self.settings.paths.is_empty() {
+            None
+        } else {
+            let redactor = PathRedactor::for_session(&self.settings.paths);
+            switcher.add_enrichment_stage(redactor.clone());
+            info!("🗂️  File path redaction enabled");
+            Some(redactor)
+        };
+
+        // Enrichment stages; they run before any listener sees the event
+        if self.settings.urls.apply_to_logs {
+            switcher.add_enrichment_stage(UrlCanonicalizer::new(self.settings.urls.clone()));
+        }
+        // Without Screen Recording the images would only show the desktop
+        let screenshots =
+            ScreenshotCapability::resolve(self.config.capture_screenshots, &SystemScreenRecording);
+        match screenshots {
+            ScreenshotCapability::Off => {}
+            ScreenshotCapability::Enabled => {
+                std::fs::create_dir_all(&self.config.screenshot_dir).with_context(|| {
+                    format!(
+                        "Failed to create screenshot directory {}",
+                        self.config.screenshot_dir.display()
+                    )
+                })?;
+                let mut screenshotter = WindowScreenshotter::new(
+                    self.config.screenshot_dir.clone(),
+                    Duration::from_secs(self.config.screenshot_interval),
+                );
+                if let Some(gate) = &quiet_hours {
+                    screenshotter = screenshotter.with_quiet_hours(gate.clone());
+                }
+                if !self.settings.title_exclusion.is_empty() {
+                    screenshotter = screenshotter.with_title_exclusion(title_exclusion.clone());
+                }
+                switcher.add_enrichment_stage(screenshotter);
+                info!(
+                    "📸 Window screenshots enabled ({})",
+                    self.config.screenshot_dir.display()
+                );
+            }
+            ScreenshotCapability::PermissionDenied => {
+                warn!("⚠️  Screen Recording permission not granted; window screenshots are off");
+                warn!("💡 Enable in: System Settings → Privacy & Security → Screen Recording");
+                warn!("   and restart the tracker");
+            }
+        }
+        if self.config.capture_windows {
+            switcher.add_enrichment_stage(WindowListCapture::new(self.config.max_windows));
+            info!("🪟 Window list capture enabled (max {})", self.config.max_windows);
+        }
+        if self.config.emit_diffs {
+            switcher.add_enrichment_stage(ContextDiffer::new());
+            info!("🔀 Context diffs enabled");
+        }
+        if self.config.previous_context {
+            switcher.add_enrichment_stage(PreviousContextTracker::new());
+            info!("↩️  Previous app context enabled");
+        }
+
+        // Sinks run on a worker thread so a slow one cannot stall the run loop
+        let mut listeners = build_listeners(&self.config, &self.settings, activity)?.start();
+        if let Some(stats) = &clipboard_stats {
+            listeners.push(Box::new(stats.clone()));
+        }
+        if let Some(secs) = self.config.heartbeat {
+            let emitter = HeartbeatEmitter::new(Duration::from_secs(secs), listeners);
+            emitter.start_ticker();
+            listeners = vec![Box::new(emitter)];
+            info!("💓 Heartbeat every {}s", secs);
+        }
+        if self.config.listener_queue > 0 {
+            switcher.add_listener(ListenerWorker::spawn(
+                listeners,
+                self.config.listener_queue,
+                self.config.queue_overflow,
+            ));
+            info!(
+                "🧵 Listener worker enabled (queue {}, {})",
+                self.config.listener_queue, self.config.queue_overflow
+            );
+        } else {
+            for listener in listeners {
+                switcher.add_boxed_listener(listener);
+            }
+        }
+
+        // Notification banners as a separate event stream
+        if self.config.notifications {
+            let observer = NotificationObserver::new();
+            observer.add_listener(NotificationPrinter {
+                format: self.config.format.clone(),
+                timestamps: self.config.timestamp_format,
+            });
+            observer.start(Duration::from_millis(500));
+            self.notification_observer = Some(observer);
+            info!("🔔 Notification observer enabled");
+        }
+
+        // Light/dark mode switches as a separate event stream
+        if self.config.appearance {
+            let mut observer = AppearanceObserver::new();
+            observer.add_listener(AppearancePrinter {
+                format: self.config.format.clone(),
+                timestamps: self.config.timestamp_format,
+            });
+            observer.start();
+            info!("🌗 Appearance observer enabled ({:?})", observer.appearance());
+            self.appearance_observer = Some(observer);
+        }
+
+        // Docking, mirroring and display arrangement as a separate event stream
+        if self.config.display_config {
+            let mut observer = DisplayConfigObserver::new();
+            observer.add_listener(DisplayConfigPrinter {
+                format: self.config.format.clone(),
+                timestamps: self.config.timestamp_format,
+            });
+            match observer.start() {
+                Ok(()) => {
+                    info!("🖥️  Display configuration observer enabled");
+                    self.display_config_observer = Some(observer);
+                }
+                Err(e) => warn!("{}", e),
+            }
+        }
+
+        // Settled window moves/resizes as a separate event stream
+        if self.config.window_geometry {
+            let mut observer = WindowGeometryObserver::new(DEFAULT_GEOMETRY_SETTLE);
+            // Titles are withheld as they are on app switch events
+            if let Some(gate) = &quiet_hours {
+                observer = observer.with_quiet_hours(gate.clone());
+            }
+            if !self.settings.title_exclusion.is_empty() {
+                observer = observer.with_title_exclusion(title_exclusion.clone());
+            }
+            observer.add_listener(WindowGeometryPrinter {
+                format: self.config.format.clone(),
+                timestamps: self.config.timestamp_format,
+            });
+            switcher.add_listener(observer.follower());
+            observer.start();
+            self.window_geometry_observer = Some(observer);
+            info!("🪟 Window geometry observer enabled");
+        }
+
+        // Open menu path, attached to events by the switcher
+        if self.config.menu_context {
+            let observer = MenuContextObserver::new(switcher.menu_context());
+            switcher.add_listener(observer.follower());
+            observer.start();
+            self.menu_context_observer = Some(observer);
+            info!("📋 Menu context enabled");
+        }
+
+        // Clipboard changes as a separate event stream
+        if self.config.clipboard
+            || self.config.capture_clipboard_thumbnails
+            || self.config.clipboard_image_hash
+            || self.config.clipboard_context
+            || clipboard_stats.is_some()
+        {
+            let mut clipboard_config = self.settings.clipboard.clone();
+            // Thumbnails and hashes need the image types, which are not read by default
+            if self.config.capture_clipboard_thumbnails {
+                clipboard_config.capture_thumbnails = true;
+                clipboard_config.allow_image_types();
+            }
+            if self.config.clipboard_image_hash {
+                clipboard_config.perceptual_hash = true;
+                clipboard_config.allow_image_types();
+            }
+            clipboard_config.capture_context |= self.config.clipboard_context;
+            let image_feature = if clipboard_config.allows_image_types() {
+                None
+            } else if clipboard_config.capture_thumbnails {
+                Some("thumbnails")
+            } else if clipboard_config.perceptual_hash {
+                Some("image hashes")
+            } else {
+                None
+            };
+            if let Some(feature) = image_feature {
+                warn!("⚠️  Clipboard {} are on but clipboard.types has no image type", feature);
+                warn!("💡 Add public.png or public.tiff to clipboard.types");
+            }
+            let thumbnails = clipboard_config.capture_thumbnails;
+            let capture_context = clipboard_config.capture_context;
+
+            let monitor = ClipboardMonitor::new(clipboard_config);
+            if capture_context {
+                monitor.set_context_source(AxCopyContext {
+                    capture_content: self.config.capture_content
+                        || self.settings.attributes.capture_content,
+                    path_redactor: path_redactor.clone(),
+                });
+            }
+            let printer = ClipboardPrinter {
+                format: self.config.format.clone(),
+                timestamps: self.config.timestamp_format,
+            };
+            let printer = title_exclusion.clipboard_listener(printer);
+            let printer = self.consent.clipboard_listener(printer);
+            match &quiet_hours {
+                Some(gate) => monitor.add_listener(gate.clipboard_listener(printer)),
+                None => monitor.add_listener(printer),
+            }
+            if let Some(stats) = &clipboard_stats {
+                monitor.add_listener(stats.clone());
+                info!("📊 Clipboard statistics enabled");
+            }
+            monitor.start(Duration::from_millis(250));
+            self.clipboard_monitor = Some(monitor);
+            info!(
+                "📋 Clipboard monitor enabled{}",
+                if thumbnails { " (with image thumbnails)" } else { "" }
+            );
+        }
+
+        // The extractor prints the URLs, titles and documents it reads
+        let context_consent = self.consent.allows(ConsentScope::Urls)
+            && self.consent.allows(ConsentScope::Titles);
+
+        // Add enhanced context extraction if requested
+        if self.config.enhanced && !context_consent {
+            info!("🔒 Accessibility context extraction needs consent for urls and titles");
+        } else if self.config.enhanced {
+            match AccessibilityContextExtractor::new() {
+                Ok(mut extractor) => {
+                    if self.config.capture_windows {
+                        extractor = extractor.with_window_capture(self.config.max_windows);
+                    }
+                    let mut allowlist = self.settings.attributes.clone();
+                    allowlist.capture_content |= self.config.capture_content;
+                    if allowlist.capture_content {
+                        info!("📝 Content attributes (AXValue, AXSelectedText) will be read");
+                    }
+                    extractor = extractor
+                        .with_attribute_allowlist(allowlist)
+                        .with_ax_availability(switcher.ax_availability());
+                    if let Some(redactor) = &path_redactor {
+                        extractor = extractor.with_path_redactor(redactor.clone());
+                    }
+                    if let Some(gate) = &quiet_hours {
+                        extractor = extractor.with_quiet_hours(gate.clone());
+                    }
+                    if !self.settings.title_exclusion.is_empty() {
+                        extractor = extractor.with_title_exclusion(title_exclusion.clone());
+                    }
+                    if !self.settings.extraction.apps.is_empty() {
+                        self.settings
+                            .extraction
+                            .validate()
+                            .map_err(|e| anyhow::anyhow!(e))?;
+                        extractor =
+                            extractor.with_extraction_rules(self.settings.extraction.clone());
+                        info!(
+                            "🧩 Custom extraction rules for {} apps",
+                            self.settings.extraction.apps.len()
+                        );
+                    }
+                    info!("🔍 Enhanced context extraction enabled (SIGUSR1 toggles)");
+                    switcher.add_enhanced_listener(extractor);
+                }
+                Err(e) => {
+                    if self.config.background {
+                        error!(
+                            "❌ Enhanced context requires accessibility permissions: {}",
+                            e
+                        );
+                        return Err(anyhow::anyhow!("Accessibility permissions required"));
+                    } else {
+                        warn!("⚠️  Enhanced context unavailable: {}", e);
+                        warn!("💡 Enable in: System Settings → Privacy & Security → Accessibility");
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Start the input tap behind engagement scoring and paste counting;
+    /// the meter is `None` without permission or without `engagement`
+    fn start_input_tap(
+        &mut self,
+        engagement: bool,
+        pastes: Option<ClipboardStats>,
+    ) -> Option<ActivityMeter> {
+        let meter = engagement.then(|| ActivityMeter::new(Duration::from_secs(4 * 3600)));
+        let sink = meter.clone();
+        let counting_pastes = pastes.is_some();
+        let callback: EventCallback = Arc::new(Mutex::new(move |event: EventInfo| {
+            if let Some(meter) = &sink {
+                meter.record_event(&event);
+            }
+            if let Some(stats) = &pastes {
+                stats.record_input(&event);
+            }
+        }));
+        let mut tap = Box::new(EventTap::new(callback));
+        match tap.start_monitoring(engagement, true, engagement) {
+            Ok(()) => {
+                self.event_tap = Some(tap);
+                if engagement {
+                    info!("⌨️  Engagement metering enabled");
+                }
+                if counting_pastes {
+                    info!("📋 Paste counting enabled");
+                }
+                meter
+            }
+            Err(e) => {
+                warn!("⚠️  Input tap unavailable, no engagement or paste counts: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Toggle enhanced extraction on every SIGUSR1 (`kill -USR1 <pid>`)
+    fn spawn_enhanced_toggle(switcher: Arc<Mutex<AppSwitcher>>) -> Result<()> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut signals = signal(SignalKind::user_defined1())
+            .context("Failed to install SIGUSR1 handler")?;
+        tokio::spawn(async move {
+            while signals.recv().await.is_some() {
+                let switcher = switcher.lock().unwrap();
+                let enabled = !switcher.is_enhanced_enabled();
+                switcher.set_enhanced_enabled(enabled);
+                info!(
+                    "🔍 Enhanced context extraction {}",
+                    if enabled { "resumed" } else { "paused" }
+                );
+            }
+        });
+        Ok(())
+    }
+
+    /// Stop the main run loop on the first SIGINT or SIGTERM
+    fn spawn_shutdown_signals() -> Result<()> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut interrupt =
+            signal(SignalKind::interrupt()).context("Failed to install SIGINT handler")?;
+        let mut terminate =
+            signal(SignalKind::terminate()).context("Failed to install SIGTERM handler")?;
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = interrupt.recv() => info!("🛑 Interrupted"),
+                _ = terminate.recv() => info!("🛑 Terminated"),
+            }
+            CFRunLoop::get_main().stop();
+        });
+        Ok(())
+    }
+
+    /// Resample the frontmost app once `after` has passed
+    fn spawn_startup_reextraction(switcher: Arc<Mutex<AppSwitcher>>, after: Duration) {
+        tokio::spawn(async move {
+            tokio::time::sleep(after).await;
+            if let Ok(switcher) = switcher.lock() {
+                switcher.resample_now();
+            }
+        });
+    }
 
     /// Run the main event loop until interrupted
     ///
@@ -273,7 +1407,7 @@ impl TrackerApp {
     /// with the NSRunLoop-based objc2 event system.
     async fn run_until_interrupted(&self) -> Result<()> {
         // Pump the CoreFoundation run loop on the main thread so AppKit/NSWorkspace notifications fire.
-        // Our helper scripts send SIGTERM to exit; CFRunLoopRun will be interrupted by process kill.
+        // SIGINT, SIGTERM and the session deadline stop it; see `spawn_shutdown_signals`.
         let _pool = unsafe { NSAutoreleasePool::new() };
         unsafe { CFRunLoopRun() };
         Ok(())
@@ -311,6 +1445,33 @@ impl TrackerApp {
             let mut switcher = self.app_switcher.lock().unwrap();
             switcher.stop_monitoring();
         }
+        if let Some(tap) = &mut self.event_tap {
+            tap.stop_monitoring();
+        }
+        if let Some(observer) = &self.notification_observer {
+            observer.stop();
+        }
+        if let Some(observer) = &mut self.appearance_observer {
+            observer.stop();
+        }
+        if let Some(observer) = &mut self.input_source_observer {
+            observer.stop();
+        }
+        if let Some(observer) = &mut self.display_config_observer {
+            observer.stop();
+        }
+        if let Some(observer) = &self.window_geometry_observer {
+            observer.stop();
+        }
+        if let Some(observer) = &self.menu_context_observer {
+            observer.stop();
+        }
+        if let Some(monitor) = &mut self.power_monitor {
+            monitor.stop();
+        }
+        if let Some(monitor) = &self.clipboard_monitor {
+            monitor.stop();
+        }
 
         // Give async tasks time to complete
         tokio::time::sleep(Duration::from_millis(100)).await;
@@ -325,14 +1486,13 @@ impl TrackerApp {
     fn setup_logging(config: &Args) -> Result<()> {
         use tracing_subscriber::{fmt, EnvFilter};
 
-        let level = match config.verbose {
-            0 => "info",
-            1 => "debug",
-            _ => "trace",
+        let filter = match &config.log {
+            Some(directives) => EnvFilter::try_new(log_filter(config.verbose, Some(directives)))
+                .map_err(|e| anyhow::anyhow!("Invalid --log directives: {}", e))?,
+            None => EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new(log_filter(config.verbose, None))),
         };
 
-        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
-
         fmt()
             .with_env_filter(filter)
             .with_target(config.verbose > 1)
@@ -369,6 +1529,17 @@ impl TrackerApp {
             }
         };
 
+        // Check screen recording permissions (window screenshots)
+        let screen_recording = SystemScreenRecording.preflight();
+
+        if screen_recording {
+            println!("✅ Screen Recording: Granted");
+        } else {
+            println!("❌ Screen Recording: Not granted");
+            println!("   Enable in: System Settings → Privacy & Security → Screen Recording");
+            println!("   Needed only for --capture-screenshots");
+        }
+
         if basic_app_access {
             println!("✅ Application Framework: Available");
         } else {
@@ -392,6 +1563,14 @@ impl TrackerApp {
                 "❌ Requires accessibility"
             }
         );
+        println!(
+            "   Window screenshots: {}",
+            if screen_recording {
+                "✅ Available"
+            } else {
+                "❌ Requires screen recording"
+            }
+        );
 
         if !accessibility_trusted {
             println!("\n💡 To enable enhanced context extraction:");
@@ -405,55 +1584,330 @@ impl TrackerApp {
     }
 }
 
+/// EnvFilter directives for the `-v` count and `--log`
+///
+/// The count sets the default level and `--log` directives follow it, so
+/// they only change the modules they name. A bare level in `--log`
+/// replaces the default instead.
+fn log_filter(verbose: u8, directives: Option<&str>) -> String {
+    let level = match verbose {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    let directives: Vec<&str> = directives
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .collect();
+    let has_default = directives
+        .iter()
+        .any(|directive| directive.parse::<tracing::level_filters::LevelFilter>().is_ok());
+
+    let mut filter: Vec<&str> = Vec::new();
+    if !has_default {
+        filter.push(level);
+    }
+    filter.extend(directives);
+    filter.join(",")
+}
+
+/// Listeners built from the arguments, with their background work not yet started
+struct ListenerSet {
+    listeners: Vec<Box<dyn AppSwitchListener>>,
+    /// Tickers, samplers and taps the listeners need once registered
+    starters: Vec<Box<dyn FnOnce()>>,
+}
+
+impl ListenerSet {
+    /// Start the background work and hand back the listeners
+    fn start(self) -> Vec<Box<dyn AppSwitchListener>> {
+        for starter in self.starters {
+            starter();
+        }
+        self.listeners
+    }
+}
+
+/// Build the app switch listeners enabled by the arguments and config file
+///
+/// Listeners are returned rather than registered so the set can be checked
+/// in tests and fed from sources other than the live switcher. Nothing runs
+/// in the background until `ListenerSet::start`; only output files and the
+/// event socket are opened here. Enhanced extraction needs Accessibility
+/// permission and is registered separately.
+fn build_listeners(
+    args: &Args,
+    settings: &TrackerConfig,
+    activity: Option<ActivityMeter>,
+) -> Result<ListenerSet> {
+    let mut listeners: Vec<Box<dyn AppSwitchListener>> = Vec::new();
+    let mut starters: Vec<Box<dyn FnOnce()>> = Vec::new();
+
+    // Always add basic logging
+    listeners.push(Box::new(
+        BasicEventLogger::new(io::stdout(), args.format.clone(), args.timestamp_format)
+            .with_flat_json(args.json_flat),
+    ));
+
+    // Always add time tracking - this is core functionality
+    let time_tracker_config = TimeTrackerConfig {
+        print_updates: args.verbose > 0,
+        min_session_duration: Duration::from_secs(2),
+        track_background: false,
+        max_history_size: 10000,
+    };
+    listeners.push(Box::new(TimeTracker::with_config(time_tracker_config)));
+    info!("⏰ Time tracking enabled");
+
+    // Per-page time, with URL variants collapsed by the canonicalizer
+    let canonicalizer = UrlCanonicalizer::new(settings.urls.clone());
+    listeners.push(Box::new(UrlTimeTracker::new(canonicalizer)));
+    info!("🌐 URL time tracking enabled");
+
+    // Work/personal split when the config maps apps or categories to tags
+    if !settings.tags.apps.is_empty() || !settings.tags.categories.is_empty() {
+        listeners.push(Box::new(TagTimeAggregator::new(settings.tags.clone())));
+        info!(
+            "🏷️  Tag tracking enabled for {} apps and {} categories",
+            settings.tags.apps.len(),
+            settings.tags.categories.len()
+        );
+    }
+
+    // Streak/distraction reminders
+    if settings.reminders.enabled {
+        let reminder = StreakReminder::new(settings.reminders.clone());
+        let ticker = reminder.clone();
+        starters.push(Box::new(move || ticker.start_ticker(Duration::from_secs(30))));
+        listeners.push(Box::new(reminder));
+        info!(
+            "🔔 Reminders enabled (streak {}m, {} switches/{}s)",
+            settings.reminders.streak_minutes,
+            settings.reminders.thrash_switches,
+            settings.reminders.thrash_window_secs
+        );
+    }
+
+    // Focus blocks, optionally scored with input engagement
+    if let Some(path) = &args.focus_blocks {
+        let mut tracker = FocusBlockTracker::new(Duration::from_secs(2))
+            .with_attention(Duration::from_secs(args.attention_window))
+            .with_output(path, args.flush_policy)
+            .context("Failed to open focus block file")?;
+        if let Some(meter) = activity {
+            tracker = tracker.with_activity(meter);
+        }
+        listeners.push(Box::new(tracker));
+        info!("🧱 Focus blocks enabled: {}", path.display());
+    }
+
+    // Interaction modes from sampled focused-element roles
+    if let Some(path) = &args.interaction_modes {
+        let tracker = InteractionModeTracker::new(Duration::from_secs(2))
+            .with_output(path, args.flush_policy)
+            .context("Failed to open interaction mode file")?;
+        let sampler = tracker.sampler();
+        starters.push(Box::new(move || sampler.start(Duration::from_secs(1))));
+        listeners.push(Box::new(tracker));
+        info!("✍️  Interaction modes enabled: {}", path.display());
+    }
+
+    // Scroll behaviour per dwell period, fed by the scroll tap
+    if let Some(path) = &args.scroll_summaries {
+        let tracker = ScrollSummaryTracker::new(Duration::from_secs(2))
+            .with_output(path, args.flush_policy)
+            .context("Failed to open scroll summary file")?;
+        let recorder = tracker.recorder();
+        starters.push(Box::new(move || ScrollTap.add_listener(RecordScroll(recorder))));
+        listeners.push(Box::new(tracker));
+        info!("📜 Scroll summaries enabled: {}", path.display());
+    }
+
+    // Transition graph, written on shutdown
+    if let Some(path) = &args.transition_graph {
+        let mut tracker =
+            TransitionGraphTracker::new(args.transition_graph_nodes).with_output(path);
+        if args.transition_graph_urls {
+            tracker = tracker.with_urls(UrlCanonicalizer::new(settings.urls.clone()));
+        }
+        listeners.push(Box::new(tracker));
+        info!("🕸️  Transition graph enabled: {}", path.display());
+    }
+
+    // Cross-app recently used files, printed on shutdown
+    if args.recent_files {
+        listeners.push(Box::new(RecentFilesTracker::new()));
+        info!("📄 Recent files enabled");
+    }
+
+    // Add file output if specified
+    if let Some(output_path) = &args.output_file {
+        let file_logger = FileEventLogger::new(output_path.clone(), args.flush_policy)?
+            .with_timestamps(args.timestamp_format);
+        listeners.push(Box::new(file_logger));
+        info!("📁 File output enabled: {}", output_path.display());
+    }
+
+    // Live stream for `follow` clients
+    if let Some(socket_path) = &args.socket {
+        let socket_logger = SocketEventLogger::bind(socket_path)
+            .with_context(|| format!("Failed to listen on {}", socket_path.display()))?
+            .with_timestamps(args.timestamp_format);
+        listeners.push(Box::new(socket_logger));
+        info!("📡 Event socket enabled: {}", socket_path.display());
+    }
+
+    // Focus sessions as spans for an OpenTelemetry collector
+    #[cfg(feature = "otel")]
+    if let Some(endpoint) = &args.otel_endpoint {
+        let exporter = OtelSessionExporter::new(endpoint).map_err(|e| anyhow::anyhow!(e))?;
+        listeners.push(Box::new(exporter));
+        info!("🔭 OpenTelemetry export enabled: {}", endpoint);
+    }
+
+    Ok(ListenerSet {
+        listeners,
+        starters,
+    })
+}
+
+/// Forwards scroll tap deltas to a scroll summary tracker
+struct RecordScroll(ScrollRecorder);
+
+impl ScrollListener for RecordScroll {
+    fn on_scroll(&mut self, event: &ScrollEvent) {
+        self.0.record(event.timestamp, event.vertical_px, event.horizontal_px);
+    }
+}
+
+/// Keys of every `--json-flat` switch event; sections enabled by flags
+/// (`screenshot_path`, `windows`, `diff`, `previous_context`) and extracted
+/// context add more
+const FLAT_JSON_KEYS: &[&str] = &[
+    "event_number",
+    "timestamp",
+    "event_type",
+    "session_id",
+    "confidence",
+    "input_source",
+    "power.source",
+    "power.battery_percent",
+    "power.charging",
+    "power.low_power_mode",
+    "app.name",
+    "app.bundle_id",
+    "app.has_bundle_id",
+    "app.pid",
+    "app.path",
+    "app.icon_path",
+    "previous_app.name",
+    "previous_app.bundle_id",
+    "previous_app.pid",
+    "previous_app.duration_seconds",
+    "workspace.window_count",
+    "workspace.focused_title",
+    "workspace.primary_url",
+    "enhanced.activation_count",
+    "enhanced.front_window_title",
+    "enhanced.cpu_usage",
+    "enhanced.memory_bytes",
+    "enhanced.session_active",
+    "enhanced.screen_locked",
+    "enhanced.display_count",
+    "enhanced.display_id",
+    "enhanced.space_id",
+    "enhanced.is_fullscreen",
+    "enhanced.window_on_active_space",
+    "enhanced.has_floating_window",
+    "enhanced.document_edited",
+    "enhanced.app_locale",
+    "enhanced.ax_available",
+    "enhanced.menu_context",
+    "enhanced.url",
+    "enhanced.domain",
+    "enhanced.tab_title",
+    "enhanced.browser_profile",
+];
+
 /// Basic event logger that prints to stdout
 ///
 /// This demonstrates how to implement the AppSwitchListener trait
-/// for different output formats.
+/// for different output formats. Output goes through a generic writer so
+/// the formats can be checked against golden files.
 struct BasicEventLogger {
+    out: Box<dyn Write + Send + Sync>,
     format: OutputFormat,
+    timestamps: TimestampFormat,
+    /// JSON switch events as dotted keys, see `FLAT_JSON_KEYS`
+    flat_json: bool,
     event_count: usize,
     last_switch_at: Option<Instant>,
     last_app: Option<research_assistant_tracker::core::app_switcher::AppInfo>,
 }
 
 impl BasicEventLogger {
-    fn new(format: OutputFormat) -> Self {
+    fn new<W: Write + Send + Sync + 'static>(
+        out: W,
+        format: OutputFormat,
+        timestamps: TimestampFormat,
+    ) -> Self {
         Self {
+            out: Box::new(out),
             format,
+            timestamps,
+            flat_json: false,
             event_count: 0,
             last_switch_at: None,
             last_app: None,
         }
     }
-}
 
-impl AppSwitchListener for BasicEventLogger {
-    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
-        self.event_count += 1;
+    fn with_flat_json(mut self, flat_json: bool) -> Self {
+        self.flat_json = flat_json;
+        self
+    }
+
+    /// Write one event; `now` stamps JSON and research lines, and dwell time
+    /// is measured between event timestamps
+    fn write_event(&mut self, event: &AppSwitchEvent, now: DateTime<Utc>) -> io::Result<()> {
+        // Heartbeats re-announce the current app and are not counted
+        let heartbeat = event.event_type == AppSwitchType::Heartbeat;
+        if !heartbeat {
+            self.event_count += 1;
+        }
 
-        let now = Instant::now();
         let prev_app = event.previous_app.clone().or_else(|| self.last_app.clone());
         let prev_duration = self
             .last_switch_at
-            .map(|t| now.saturating_duration_since(t))
+            .map(|t| event.timestamp.saturating_duration_since(t))
             .unwrap_or(Duration::from_secs(0));
 
         match self.format {
             OutputFormat::Human => match event.event_type {
-                AppSwitchType::Foreground => {
-                    println!(
+                AppSwitchType::Foreground
+                | AppSwitchType::SpaceChange
+                | AppSwitchType::SessionChange
+                | AppSwitchType::Wake => {
+                    writeln!(
+                        self.out,
                         "\n🔥 #{} SWITCHED TO: {} ({})",
                         self.event_count, event.app_info.name, event.app_info.bundle_id
-                    );
+                    )?;
                     if let Some(prev) = &prev_app {
                         let secs = prev_duration.as_secs_f32();
-                        println!("   From: {} (pid: {}, {:.1}s)", prev.name, prev.pid, secs);
+                        writeln!(
+                            self.out,
+                            "   From: {} (pid: {}, {:.1}s)",
+                            prev.name, prev.pid, secs
+                        )?;
                     }
                     if let Some(path) = &event.app_info.path {
-                        println!("   Path: {}", path);
+                        writeln!(self.out, "   Path: {}", path)?;
                     }
                     if let Some(icon_path) = &event.app_info.icon_path {
-                        println!("   Icon path: {}", icon_path);
+                        writeln!(self.out, "   Icon path: {}", icon_path)?;
                     }
                     let window_title = event
                         .workspace
@@ -466,7 +1920,7 @@ impl AppSwitchListener for BasicEventLogger {
                                 .and_then(|e| e.front_window_title.clone())
                         });
                     if let Some(title) = window_title {
-                        println!("   Window: {}", title);
+                        writeln!(self.out, "   Window: {}", title)?;
                     }
                     // Prefer workspace URL; fall back to enhanced URL if available
                     if let Some(url) = event
@@ -475,18 +1929,18 @@ impl AppSwitchListener for BasicEventLogger {
                         .and_then(|w| w.primary_url.clone())
                         .or_else(|| event.enhanced.as_ref().and_then(|e| e.url.clone()))
                     {
-                        println!("   URL: {}", url);
+                        writeln!(self.out, "   URL: {}", url)?;
                     }
                     // Display / Space info
                     if let Some(enh) = &event.enhanced {
                         if let Some(dc) = enh.display_count {
-                            println!("   Displays: {}", dc);
+                            writeln!(self.out, "   Displays: {}", dc)?;
                         }
                         if let Some(did) = enh.display_id {
-                            println!("   Display ID: {}", did);
+                            writeln!(self.out, "   Display ID: {}", did)?;
                         }
                         if let Some(space) = enh.space_id {
-                            println!("   Space (ID): {}", space);
+                            writeln!(self.out, "   Space (ID): {}", space)?;
                         }
                         if enh.space_index.is_some()
                             || enh.space_type.is_some()
@@ -494,31 +1948,93 @@ impl AppSwitchListener for BasicEventLogger {
                             || enh.space_uuid.is_some()
                             || enh.space_label.is_some()
                         {
-                            println!(
+                            writeln!(
+                                self.out,
                                 "   Space info: index={:?} type={:?} name={:?} label={:?} uuid={:?}",
                                 enh.space_index, enh.space_type, enh.space_name, enh.space_label, enh.space_uuid
-                            );
+                            )?;
+                        }
+                        if enh.window_on_active_space == Some(false) {
+                            writeln!(
+                                self.out,
+                                "   ⚠️  Focused window is on a background Space"
+                            )?;
+                        }
+                        if enh.has_floating_window == Some(true) {
+                            writeln!(
+                                self.out,
+                                "   🪟 Floating window (Picture-in-Picture or toolbar)"
+                            )?;
+                        }
+                        if enh.document_edited == Some(true) {
+                            writeln!(self.out, "   💾 Unsaved changes")?;
+                        }
+                        if let Some(menu) = &enh.menu_context {
+                            writeln!(self.out, "   📋 Menu: {}", menu)?;
                         }
                     }
                 }
                 AppSwitchType::Background => {
-                    println!("📱 {} went to background", event.app_info.name);
+                    writeln!(self.out, "📱 {} went to background", event.app_info.name)?;
                 }
-                _ => {
-                    println!(
-                        "📋 #{} {:?}: {}",
-                        self.event_count, event.event_type, event.app_info.name
-                    );
+                AppSwitchType::Launch => {
+                    writeln!(
+                        self.out,
+                        "🚀 #{} LAUNCHED: {} ({}, pid: {})",
+                        self.event_count,
+                        event.app_info.name,
+                        event.app_info.bundle_id,
+                        event.app_info.pid
+                    )?;
+                }
+                AppSwitchType::Terminate => {
+                    writeln!(
+                        self.out,
+                        "🛑 #{} TERMINATED: {} ({}, pid: {})",
+                        self.event_count,
+                        event.app_info.name,
+                        event.app_info.bundle_id,
+                        event.app_info.pid
+                    )?;
+                }
+                AppSwitchType::Heartbeat => {
+                    writeln!(
+                        self.out,
+                        "💓 Still in {} ({:.0}s)",
+                        event.app_info.name,
+                        prev_duration.as_secs_f32()
+                    )?;
+                }
+                AppSwitchType::Hide | AppSwitchType::Unhide | AppSwitchType::Sleep => {
+                    writeln!(
+                        self.out,
+                        "📋 #{} {}: {}",
+                        self.event_count,
+                        event.event_type.as_str(),
+                        event.app_info.name
+                    )?;
                 }
             },
             OutputFormat::Json => {
-                let json_event = serde_json::json!({
+                // With a diff, the unchanged context was in an earlier line
+                let compact;
+                let event = if event.diff.is_some() {
+                    compact = without_diffed_fields(event);
+                    &compact
+                } else {
+                    event
+                };
+                let mut json_event = serde_json::json!({
                     "event_number": self.event_count,
-                    "timestamp": chrono::Utc::now().to_rfc3339(),
-                    "event_type": format!("{:?}", event.event_type),
+                    "timestamp": self.timestamps.json(now),
+                    "event_type": event.event_type.as_str(),
+                    "session_id": event.session_id,
+                    "power": event.power,
+                    "input_source": event.input_source,
                     "app": {
                         "name": event.app_info.name,
                         "bundle_id": event.app_info.bundle_id,
+                        "has_bundle_id": event.app_info.has_bundle_id(),
                         "pid": event.app_info.pid,
                         "path": event.app_info.path,
                         "icon_path": event.app_info.icon_path,
@@ -546,20 +2062,46 @@ impl AppSwitchListener for BasicEventLogger {
                         "display_count": e.display_count,
                         "display_id": e.display_id,
                         "space_id": e.space_id,
+                        "is_fullscreen": e.is_fullscreen,
+                        "window_on_active_space": e.window_on_active_space,
+                        "has_floating_window": e.has_floating_window,
+                        "document_edited": e.document_edited,
+                        "app_locale": e.app_locale,
+                        "ax_available": e.ax_available,
+                        "menu_context": e.menu_context,
                         "url": e.url,
+                        "domain": e.domain,
                         "tab_title": e.tab_title,
+                        "browser_profile": e.browser_profile,
+                        "extracted_context": e.extracted_context,
                     })),
                     "confidence": event.confidence
                 });
-                println!("{}", serde_json::to_string(&json_event).unwrap());
+                if let Some(path) = &event.screenshot_path {
+                    json_event["screenshot_path"] = serde_json::json!(path);
+                }
+                if let Some(windows) = &event.windows {
+                    json_event["windows"] = serde_json::json!(windows);
+                }
+                if let Some(diff) = &event.diff {
+                    json_event["diff"] = serde_json::json!(diff.changes);
+                }
+                if let Some(previous) = &event.previous_context {
+                    json_event["previous_context"] = serde_json::json!(previous);
+                }
+                if self.flat_json {
+                    json_event = flatten_json(&json_event, FLAT_JSON_KEYS).into();
+                }
+                writeln!(self.out, "{}", serde_json::to_string(&json_event).unwrap())?;
             }
             OutputFormat::Research => {
                 // Optimized format for research analysis
-                let timestamp = chrono::Utc::now().to_rfc3339();
-                println!(
-                    "RESEARCH|{}|{:?}|{}|{}|{}|prev_pid={}|prev_secs={:.1}|title={}|url={}|display_count={}|space={}",
+                let timestamp = self.timestamps.format(now);
+                writeln!(
+                    self.out,
+                    "RESEARCH|{}|{}|{}|{}|{}|prev_pid={}|prev_secs={:.1}|title={}|url={}|display_count={}|space={}|display_id={}",
                     timestamp,
-                    event.event_type,
+                    event.event_type.as_str(),
                     event.app_info.name,
                     event.app_info.bundle_id,
                     event.app_info.pid,
@@ -588,111 +2130,628 @@ impl AppSwitchListener for BasicEventLogger {
                         .as_ref()
                         .and_then(|e| e.space_id)
                         .map(|s| s.to_string())
+                        .unwrap_or_default(),
+                    event
+                        .enhanced
+                        .as_ref()
+                        .and_then(|e| e.display_id)
+                        .map(|d| d.to_string())
                         .unwrap_or_default()
-                );
+                )?;
             }
         }
 
-        // Update dwell tracking
-        self.last_switch_at = Some(now);
-        self.last_app = Some(event.app_info.clone());
+        // Update dwell tracking; launches, quits and heartbeats do not change focus
+        if !matches!(
+            event.event_type,
+            AppSwitchType::Launch | AppSwitchType::Terminate | AppSwitchType::Heartbeat
+        ) {
+            self.last_switch_at = Some(event.timestamp);
+            self.last_app = Some(event.app_info.clone());
+        }
+        Ok(())
     }
 
-    fn on_monitoring_started(&mut self) {
+    fn write_wake(&mut self, asleep: &SleepPeriod) -> io::Result<()> {
+        let secs = asleep.duration().as_secs();
+        match self.format {
+            OutputFormat::Human => {
+                writeln!(self.out, "\n💤 System asleep for {}m {}s", secs / 60, secs % 60)?;
+            }
+            OutputFormat::Json => {
+                let sleep_event = serde_json::json!({
+                    "event_type": "system_asleep",
+                    "timestamp": self.timestamps.json(asleep.end),
+                    "start": self.timestamps.json(asleep.start),
+                    "end": self.timestamps.json(asleep.end),
+                    "duration_secs": secs,
+                });
+                writeln!(self.out, "{}", serde_json::to_string(&sleep_event).unwrap())?;
+            }
+            OutputFormat::Research => {
+                writeln!(
+                    self.out,
+                    "RESEARCH|{}|system_asleep|{}",
+                    self.timestamps.format(asleep.end),
+                    secs
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_started(&mut self, now: DateTime<Utc>) -> io::Result<()> {
         match self.format {
             OutputFormat::Human => {
-                println!("🚀 Basic event logging started");
+                writeln!(self.out, "🚀 Basic event logging started")?;
             }
             OutputFormat::Json => {
                 let start_event = serde_json::json!({
                     "event_type": "monitoring_started",
-                    "timestamp": chrono::Utc::now().to_rfc3339(),
+                    "timestamp": self.timestamps.json(now),
                 });
-                println!("{}", serde_json::to_string(&start_event).unwrap());
+                writeln!(self.out, "{}", serde_json::to_string(&start_event).unwrap())?;
             }
             OutputFormat::Research => {
-                println!(
+                writeln!(
+                    self.out,
                     "RESEARCH|{}|monitoring_started",
-                    chrono::Utc::now().to_rfc3339()
-                );
+                    self.timestamps.format(now)
+                )?;
             }
         }
+        Ok(())
     }
 }
 
-/// File-based event logger for persistent storage
-///
-/// This shows how to implement file output for long-term research data collection.
-struct FileEventLogger {
-    file: std::fs::File,
-}
-
-impl FileEventLogger {
-    fn new(path: std::path::PathBuf) -> Result<Self> {
-        use std::fs::OpenOptions;
-
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)
-            .context("Failed to open output file")?;
+impl AppSwitchListener for BasicEventLogger {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        if let Err(e) = self.write_event(event, Utc::now()) {
+            error!("Failed to write event: {}", e);
+        }
+    }
 
-        Ok(Self { file })
+    fn on_system_sleep(&mut self) {
+        // The next switch after wake must not count the sleep as dwell time
+        self.last_switch_at = None;
     }
-}
 
-impl AppSwitchListener for FileEventLogger {
-    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
-        use std::io::Write;
-
-        let json_event = serde_json::json!({
-            "timestamp": chrono::Utc::now().to_rfc3339(),
-            "event_type": format!("{:?}", event.event_type),
-            "app": {
-                "name": event.app_info.name,
-                "bundle_id": event.app_info.bundle_id,
-                "pid": event.app_info.pid,
-                "path": event.app_info.path,
-                "icon_path": event.app_info.icon_path,
-                "launch_date": event.app_info.launch_date.map(|_| chrono::Utc::now().to_rfc3339())
-            },
-            "previous_app": event.previous_app.as_ref().map(|app| {
-                serde_json::json!({
-                    "name": app.name,
-                    "bundle_id": app.bundle_id,
-                    "pid": app.pid
-                })
-            }),
-            "workspace": event.workspace.as_ref().map(|w| serde_json::json!({
-                "window_count": w.window_count,
-                "focused_title": w.focused_title,
-                "primary_url": w.primary_url,
-            })),
-            "enhanced": event.enhanced.as_ref().map(|e| serde_json::json!({
-                "activation_count": e.activation_count,
-                "front_window_title": e.front_window_title,
-                "cpu_usage": e.cpu_usage,
-                "memory_bytes": e.memory_bytes,
-                "session_active": e.session_active,
-                "screen_locked": e.screen_locked,
-            })),
-            "confidence": event.confidence
-        });
+    fn on_system_wake(&mut self, asleep: &SleepPeriod) {
+        if let Err(e) = self.write_wake(asleep) {
+            error!("Failed to write event: {}", e);
+        }
+    }
 
-        if let Err(e) = writeln!(self.file, "{}", serde_json::to_string(&json_event).unwrap()) {
-            error!("Failed to write to output file: {}", e);
+    fn on_monitoring_started(&mut self) {
+        if let Err(e) = self.write_started(Utc::now()) {
+            error!("Failed to write event: {}", e);
         }
     }
 }
 
-/// Application entry point
-///
-/// This demonstrates the modern async main pattern with proper error handling.
-#[tokio::main]
-async fn main() -> Result<()> {
-    let args = Args::parse();
+/// Prints notification banners in the configured output format
+struct NotificationPrinter {
+    format: OutputFormat,
+    timestamps: TimestampFormat,
+}
 
-    // Create and run the tracker application
+impl NotificationListener for NotificationPrinter {
+    fn on_notification(&mut self, event: &NotificationEvent) {
+        match self.format {
+            OutputFormat::Human => {
+                println!("🔔 Notification from {}", event.app_name);
+            }
+            OutputFormat::Json => {
+                let json_event = serde_json::json!({
+                    "event_type": "notification",
+                    "timestamp": self.timestamps.json(event.timestamp),
+                    "app_name": event.app_name,
+                    "bundle_id": event.bundle_id,
+                });
+                println!("{}", serde_json::to_string(&json_event).unwrap());
+            }
+            OutputFormat::Research => {
+                println!(
+                    "RESEARCH|{}|notification|{}|{}",
+                    self.timestamps.format(event.timestamp),
+                    event.app_name,
+                    event.bundle_id.as_deref().unwrap_or_default()
+                );
+            }
+        }
+    }
+}
+
+/// Prints light/dark mode switches in the configured output format
+struct AppearancePrinter {
+    format: OutputFormat,
+    timestamps: TimestampFormat,
+}
+
+impl AppearanceListener for AppearancePrinter {
+    fn on_appearance_change(&mut self, event: &AppearanceChange) {
+        match self.format {
+            OutputFormat::Human => {
+                println!("🌗 Appearance changed to {:?}", event.appearance);
+            }
+            OutputFormat::Json => {
+                let json_event = serde_json::json!({
+                    "event_type": "appearance_change",
+                    "timestamp": self.timestamps.json(event.timestamp),
+                    "previous": event.previous,
+                    "appearance": event.appearance,
+                });
+                println!("{}", serde_json::to_string(&json_event).unwrap());
+            }
+            OutputFormat::Research => {
+                println!(
+                    "RESEARCH|{}|appearance_change|{}",
+                    self.timestamps.format(event.timestamp),
+                    serde_json::to_value(event.appearance)
+                        .ok()
+                        .and_then(|v| v.as_str().map(str::to_string))
+                        .unwrap_or_default()
+                );
+            }
+        }
+    }
+}
+
+/// Prints keyboard layout and input method switches in the configured output format
+struct InputSourcePrinter {
+    format: OutputFormat,
+    timestamps: TimestampFormat,
+}
+
+impl InputSourceListener for InputSourcePrinter {
+    fn on_input_source_change(&mut self, event: &InputSourceChange) {
+        match self.format {
+            OutputFormat::Human => {
+                println!("⌨️  Input source changed to {}", event.current.label);
+            }
+            OutputFormat::Json => {
+                let json_event = serde_json::json!({
+                    "event_type": "input_source_change",
+                    "timestamp": self.timestamps.json(event.timestamp),
+                    "previous": event.previous,
+                    "input_source": event.current,
+                });
+                println!("{}", serde_json::to_string(&json_event).unwrap());
+            }
+            OutputFormat::Research => {
+                println!(
+                    "RESEARCH|{}|input_source_change|{}|{}",
+                    self.timestamps.format(event.timestamp),
+                    event.current.id,
+                    event.current.label
+                );
+            }
+        }
+    }
+}
+
+/// A power change as a JSON object, for stdout and the power changes file
+fn power_change_json(
+    event: &PowerStateChanged,
+    timestamps: &TimestampFormat,
+) -> serde_json::Value {
+    serde_json::json!({
+        "event_type": "power_state_changed",
+        "timestamp": timestamps.json(event.timestamp),
+        "changes": event.changes,
+        "previous": event.previous,
+        "current": event.current,
+    })
+}
+
+/// Prints power source and Low Power Mode changes in the configured output format
+struct PowerStatePrinter {
+    format: OutputFormat,
+    timestamps: TimestampFormat,
+}
+
+impl PowerStateListener for PowerStatePrinter {
+    fn on_power_state_changed(&mut self, event: &PowerStateChanged) {
+        let battery = event
+            .current
+            .battery_percent
+            .map(|p| format!("{}%", p))
+            .unwrap_or_else(|| "-".to_string());
+        match self.format {
+            OutputFormat::Human => {
+                println!(
+                    "🔋 Power {}: {:?}, battery {}{}",
+                    event.changes.join(", "),
+                    event.current.source,
+                    battery,
+                    if event.current.low_power_mode {
+                        ", Low Power Mode"
+                    } else {
+                        ""
+                    }
+                );
+            }
+            OutputFormat::Json => {
+                let json_event = power_change_json(event, &self.timestamps);
+                println!("{}", serde_json::to_string(&json_event).unwrap());
+            }
+            OutputFormat::Research => {
+                println!(
+                    "RESEARCH|{}|power_state_changed|changes={}|battery={}|low_power={}",
+                    self.timestamps.format(event.timestamp),
+                    event.changes.join(","),
+                    battery,
+                    event.current.low_power_mode
+                );
+            }
+        }
+    }
+}
+
+/// Appends power source and Low Power Mode changes to a JSON lines file
+struct PowerChangeLogger {
+    sink: BufferedSink<std::fs::File>,
+    timestamps: TimestampFormat,
+}
+
+impl PowerChangeLogger {
+    fn new(path: &std::path::Path, flush_policy: FlushPolicy) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .context("Failed to open power changes file")?;
+        Ok(Self {
+            sink: BufferedSink::new(file, flush_policy),
+            timestamps: TimestampFormat::default(),
+        })
+    }
+
+    fn with_timestamps(mut self, timestamps: TimestampFormat) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+}
+
+impl PowerStateListener for PowerChangeLogger {
+    fn on_power_state_changed(&mut self, event: &PowerStateChanged) {
+        let json_event = power_change_json(event, &self.timestamps);
+        let line = serde_json::to_string(&json_event).unwrap();
+        if let Err(e) = self.sink.write_line(&line) {
+            error!("Failed to write power change: {}", e);
+        }
+    }
+}
+
+/// Prints display configuration changes in the configured output format
+struct DisplayConfigPrinter {
+    format: OutputFormat,
+    timestamps: TimestampFormat,
+}
+
+impl DisplayConfigListener for DisplayConfigPrinter {
+    fn on_display_config_changed(&mut self, event: &DisplayConfigChanged) {
+        let arrangement = event
+            .displays
+            .iter()
+            .map(|d| {
+                format!(
+                    "{}:{}x{}@{:.0},{:.0}",
+                    d.display_id, d.pixel_width, d.pixel_height, d.x, d.y
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+        match self.format {
+            OutputFormat::Human => {
+                let ids: Vec<String> = event.display_ids.iter().map(u32::to_string).collect();
+                println!(
+                    "🖥️  Display {} {} ({} display{})",
+                    ids.join(", "),
+                    event.changes.join(", "),
+                    event.display_count,
+                    if event.display_count == 1 { "" } else { "s" }
+                );
+                for d in &event.displays {
+                    println!(
+                        "   {} {}×{} at {:.0},{:.0}{}{}",
+                        d.display_id,
+                        d.pixel_width,
+                        d.pixel_height,
+                        d.x,
+                        d.y,
+                        if d.is_main { " (main)" } else { "" },
+                        if d.is_builtin { " (built-in)" } else { "" }
+                    );
+                }
+            }
+            OutputFormat::Json => {
+                let json_event = serde_json::json!({
+                    "event_type": "display_config_changed",
+                    "timestamp": self.timestamps.json(event.timestamp),
+                    "display_ids": event.display_ids,
+                    "changes": event.changes,
+                    "display_count": event.display_count,
+                    "displays": event.displays,
+                });
+                println!("{}", serde_json::to_string(&json_event).unwrap());
+            }
+            OutputFormat::Research => {
+                let ids: Vec<String> = event.display_ids.iter().map(u32::to_string).collect();
+                println!(
+                    "RESEARCH|{}|display_config_changed|display_ids={}|changes={}|display_count={}|displays={}",
+                    self.timestamps.format(event.timestamp),
+                    ids.join(","),
+                    event.changes.join(","),
+                    event.display_count,
+                    arrangement
+                );
+            }
+        }
+    }
+}
+
+/// Prints settled window moves and resizes in the configured output format
+struct WindowGeometryPrinter {
+    format: OutputFormat,
+    timestamps: TimestampFormat,
+}
+
+impl WindowGeometryListener for WindowGeometryPrinter {
+    fn on_window_geometry_changed(&mut self, event: &WindowGeometryChanged) {
+        let b = &event.bounds;
+        match self.format {
+            OutputFormat::Human => {
+                let change = match (event.moved, event.resized) {
+                    (true, true) => "moved and resized",
+                    (false, true) => "resized",
+                    _ => "moved",
+                };
+                println!(
+                    "🪟 {} window {} to {:.0},{:.0} {:.0}×{:.0}",
+                    event.app_name, change, b.x, b.y, b.width, b.height
+                );
+            }
+            OutputFormat::Json => {
+                let json_event = serde_json::json!({
+                    "event_type": "window_geometry_changed",
+                    "timestamp": self.timestamps.json(event.timestamp),
+                    "app_name": event.app_name,
+                    "bundle_id": event.bundle_id,
+                    "pid": event.pid,
+                    "window_title": event.window_title,
+                    "bounds": event.bounds,
+                    "previous": event.previous,
+                    "moved": event.moved,
+                    "resized": event.resized,
+                });
+                println!("{}", serde_json::to_string(&json_event).unwrap());
+            }
+            OutputFormat::Research => {
+                println!(
+                    "RESEARCH|{}|window_geometry_changed|{}|{}|{:.0},{:.0},{:.0},{:.0}",
+                    self.timestamps.format(event.timestamp),
+                    event.app_name,
+                    event.bundle_id,
+                    b.x,
+                    b.y,
+                    b.width,
+                    b.height
+                );
+            }
+        }
+    }
+}
+
+/// Prints clipboard changes in the configured output format
+struct ClipboardPrinter {
+    format: OutputFormat,
+    timestamps: TimestampFormat,
+}
+
+impl ClipboardListener for ClipboardPrinter {
+    fn on_clipboard_change(&mut self, event: &ClipboardEvent) {
+        let source = event.source_app.as_deref().unwrap_or("Unknown");
+        match self.format {
+            OutputFormat::Human => {
+                println!(
+                    "📋 Clipboard changed in {} ({} types)",
+                    source,
+                    event.types.len()
+                );
+                if event.sensitive.is_some() {
+                    println!(
+                        "   🔒 Likely a password; content not recorded ({} bytes)",
+                        event.content_length.unwrap_or_default()
+                    );
+                }
+                if let Some(image) = &event.image {
+                    println!(
+                        "   🖼️  {} {}x{} ({} bytes){}",
+                        image.format,
+                        image.width.unwrap_or_default(),
+                        image.height.unwrap_or_default(),
+                        image.byte_size,
+                        if image.thumbnail_base64.is_some() {
+                            ", thumbnail captured"
+                        } else {
+                            ""
+                        }
+                    );
+                    if let Some(hash) = &image.dhash {
+                        println!("      dHash: {}", hash);
+                    }
+                }
+                if let Some(context) = &event.context {
+                    let location = context.url.as_ref().or(context.file.as_ref());
+                    match (&context.window_title, location) {
+                        (Some(title), Some(location)) => {
+                            println!("   📍 From: {} ({})", title, location)
+                        }
+                        (Some(title), None) => println!("   📍 From: {}", title),
+                        (None, Some(location)) => println!("   📍 From: {}", location),
+                        (None, None) => {}
+                    }
+                    if let Some(selection) = &context.selection {
+                        println!("   ✂️  Selection: {} chars", selection.chars().count());
+                    }
+                }
+            }
+            OutputFormat::Json => {
+                let mut json_event = serde_json::to_value(event).unwrap();
+                json_event["event_type"] = serde_json::json!("clipboard");
+                json_event["timestamp"] = self.timestamps.json(event.timestamp);
+                println!("{}", serde_json::to_string(&json_event).unwrap());
+            }
+            OutputFormat::Research => {
+                println!(
+                    "RESEARCH|{}|clipboard|{}|{}",
+                    self.timestamps.format(event.timestamp),
+                    source,
+                    event.types.join(",")
+                );
+            }
+        }
+    }
+}
+
+/// File-based event logger for persistent storage
+///
+/// This shows how to implement file output for long-term research data collection.
+struct FileEventLogger<W: Write + Send + 'static = std::fs::File> {
+    sink: BufferedSink<W>,
+    timestamps: TimestampFormat,
+    /// Sleep reported by the last wake, written with the next event
+    asleep: Option<SleepPeriod>,
+}
+
+impl FileEventLogger {
+    fn new(path: std::path::PathBuf, flush_policy: FlushPolicy) -> Result<Self> {
+        use std::fs::OpenOptions;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .context("Failed to open output file")?;
+
+        Ok(Self::from_writer(file, flush_policy))
+    }
+}
+
+impl<W: Write + Send + 'static> FileEventLogger<W> {
+    fn from_writer(writer: W, flush_policy: FlushPolicy) -> Self {
+        Self {
+            sink: BufferedSink::new(writer, flush_policy),
+            timestamps: TimestampFormat::default(),
+            asleep: None,
+        }
+    }
+
+    fn with_timestamps(mut self, timestamps: TimestampFormat) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    fn write_event(&mut self, event: &AppSwitchEvent, now: DateTime<Utc>) -> io::Result<()> {
+        let mut record = EventRecord::new(event, &self.timestamps, now);
+        if let Some(asleep) = self.asleep.take() {
+            record = record.with_system_asleep(&asleep, &self.timestamps);
+        }
+        self.sink
+            .write_line(&serde_json::to_string(&record).unwrap())
+    }
+}
+
+impl<W: Write + Send + 'static> AppSwitchListener for FileEventLogger<W> {
+    fn on_app_switch(&mut self, event: &AppSwitchEvent) {
+        if let Err(e) = self.write_event(event, Utc::now()) {
+            error!("Failed to write to output file: {}", e);
+        }
+    }
+
+    fn on_system_wake(&mut self, asleep: &SleepPeriod) {
+        self.asleep = Some(asleep.clone());
+    }
+
+    fn on_monitoring_stopped(&mut self) {
+        if let Err(e) = self.sink.flush() {
+            error!("Failed to flush output file: {}", e);
+        }
+    }
+}
+
+/// Application entry point
+///
+/// This demonstrates the modern async main pattern with proper error handling.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    // Subcommands are one-shot and skip tracker setup entirely
+    if let Some(command) = &args.command {
+        match command {
+            Command::ListApps { json, all } => {
+                let apps = list_running_apps(*all);
+                if *json {
+                    println!("{}", format_app_json(&apps));
+                } else {
+                    print!("{}", format_app_table(&apps));
+                }
+            }
+            Command::BenchExtract { iterations } => {
+                let report = run_extraction_bench(*iterations).map_err(|e| anyhow::anyhow!(e))?;
+                print!("{}", report.format());
+            }
+            Command::Compact {
+                input,
+                output,
+                keys,
+            } => {
+                let keys = if keys.is_empty() {
+                    DEFAULT_KEY_FIELDS.iter().map(|k| k.to_string()).collect()
+                } else {
+                    keys.clone()
+                };
+                let report =
+                    compact_file(input, output, &keys).map_err(|e| anyhow::anyhow!(e))?;
+                println!(
+                    "Compacted {} records into {}: {}",
+                    report.records_in,
+                    report.records_out,
+                    output.display()
+                );
+            }
+            Command::Schema => {
+                println!("{}", serde_json::to_string_pretty(&EventRecord::schema())?);
+            }
+            Command::ValidateConfig { config } => {
+                let validation =
+                    TrackerConfig::validate_file(config).map_err(|e| anyhow::anyhow!(e))?;
+                for diagnostic in &validation.diagnostics {
+                    eprintln!("{}", diagnostic);
+                }
+                if validation.has_errors() {
+                    return Err(anyhow::anyhow!("{} is not valid", config.display()));
+                }
+                if let Some(effective) = &validation.config {
+                    println!("{}", serde_json::to_string_pretty(effective)?);
+                }
+            }
+            Command::Follow {
+                socket,
+                format,
+                no_reconnect,
+            } => {
+                let reconnect = (!*no_reconnect).then_some(Duration::from_secs(1));
+                follow(socket, &mut io::stdout(), *format, reconnect)
+                    .with_context(|| format!("Failed to follow {}", socket.display()))?;
+            }
+        }
+        return Ok(());
+    }
+
+    // Create and run the tracker application
     let app = TrackerApp::new(args)
         .await
         .context("Failed to initialize tracker application")?;
@@ -701,3 +2760,425 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use research_assistant_tracker::core::app_switcher::{AppInfo, WorkspaceSummary};
+
+    /// Writer whose contents stay readable after a logger takes ownership
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn fixture_start() -> DateTime<Utc> {
+        "2026-01-05T09:00:00Z".parse().unwrap()
+    }
+
+    /// A launch, two switches, a heartbeat and a quit, with the wall-clock
+    /// time each one is logged at
+    fn fixture() -> Vec<(AppSwitchEvent, DateTime<Utc>)> {
+        let safari = AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 42);
+        let terminal = AppInfo::new("Terminal".to_string(), "com.apple.Terminal".to_string(), 7);
+
+        let launch = AppSwitchEvent::new(AppSwitchType::Launch, safari.clone());
+        let mut to_safari = AppSwitchEvent::with_previous(
+            AppSwitchType::Foreground,
+            safari.clone(),
+            terminal.clone(),
+        );
+        to_safari.workspace = Some(WorkspaceSummary {
+            window_count: 2,
+            focused_title: Some("Docs".to_string()),
+            total_screen_coverage: None,
+            is_fullscreen: None,
+            is_minimized: None,
+            tab_titles: Vec::new(),
+            active_file_paths: Vec::new(),
+            primary_url: Some("https://example.com/docs".to_string()),
+        });
+        to_safari.confidence = Some(1.0);
+        let to_terminal = AppSwitchEvent::with_previous(
+            AppSwitchType::Foreground,
+            terminal.clone(),
+            safari.clone(),
+        );
+        let heartbeat = AppSwitchEvent::new(AppSwitchType::Heartbeat, terminal);
+        let quit = AppSwitchEvent::new(AppSwitchType::Terminate, safari);
+
+        let start = Instant::now();
+        [(launch, 0), (to_safari, 0), (to_terminal, 95), (heartbeat, 155), (quit, 200)]
+            .into_iter()
+            .map(|(mut event, secs)| {
+                event.timestamp = start + Duration::from_secs(secs);
+                (event, fixture_start() + chrono::Duration::seconds(secs as i64))
+            })
+            .collect()
+    }
+
+    /// Compare with `tests/golden/<name>`; JSON lines are compared as values
+    /// so key order does not matter. `UPDATE_GOLDEN=1` rewrites the file.
+    fn assert_golden(name: &str, actual: &str) {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(name);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!("{}: {} (run with UPDATE_GOLDEN=1)", path.display(), e)
+        });
+        if name.ends_with(".jsonl") {
+            let parse = |text: &str| -> Vec<serde_json::Value> {
+                text.lines()
+                    .map(|line| serde_json::from_str(line).unwrap())
+                    .collect()
+            };
+            pretty_assertions::assert_eq!(parse(actual), parse(&expected), "{}", name);
+        } else {
+            pretty_assertions::assert_eq!(actual, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_basic_logger_matches_golden_output() {
+        for (format, golden) in [
+            (OutputFormat::Human, "basic_human.txt"),
+            (OutputFormat::Json, "basic_json.jsonl"),
+            (OutputFormat::Research, "basic_research.txt"),
+        ] {
+            let buffer = SharedBuffer::default();
+            let mut logger =
+                BasicEventLogger::new(buffer.clone(), format, TimestampFormat::default());
+            logger.write_started(fixture_start()).unwrap();
+            for (event, now) in fixture() {
+                logger.write_event(&event, now).unwrap();
+            }
+            assert_golden(golden, &buffer.contents());
+        }
+    }
+
+    #[test]
+    fn test_flat_json_has_dotted_keys() {
+        let buffer = SharedBuffer::default();
+        let mut logger =
+            BasicEventLogger::new(buffer.clone(), OutputFormat::Json, TimestampFormat::default())
+                .with_flat_json(true);
+        for (event, now) in fixture().into_iter().take(2) {
+            logger.write_event(&event, now).unwrap();
+        }
+        let records: Vec<serde_json::Map<String, serde_json::Value>> = buffer
+            .contents()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let to_safari = &records[1];
+        assert_eq!(to_safari["app.bundle_id"], "com.apple.Safari");
+        assert_eq!(to_safari["app.pid"], 42);
+        assert_eq!(to_safari["previous_app.bundle_id"], "com.apple.Terminal");
+        assert_eq!(to_safari["workspace.focused_title"], "Docs");
+        assert_eq!(to_safari["workspace.primary_url"], "https://example.com/docs");
+        assert!(to_safari.values().all(|v| !v.is_object()));
+
+        // The launch has no workspace or previous app; the keys are still there
+        let launch = &records[0];
+        assert_eq!(launch["event_type"], "launch");
+        assert!(launch["workspace.focused_title"].is_null());
+        assert!(launch["previous_app.bundle_id"].is_null());
+        assert!(!launch.contains_key("workspace") && !launch.contains_key("enhanced"));
+        for record in &records {
+            assert!(FLAT_JSON_KEYS.iter().all(|key| record.contains_key(*key)));
+        }
+    }
+
+    #[test]
+    fn test_file_logger_reports_sleep_on_the_next_event() {
+        let buffer = SharedBuffer::default();
+        let mut logger = FileEventLogger::from_writer(buffer.clone(), FlushPolicy::PerEvent);
+        let mut events = fixture().into_iter();
+        let (launch, now) = events.next().unwrap();
+        logger.write_event(&launch, now).unwrap();
+        logger.on_system_wake(&SleepPeriod {
+            start: fixture_start(),
+            end: fixture_start() + chrono::Duration::minutes(30),
+        });
+        for (event, now) in events.take(2) {
+            logger.write_event(&event, now).unwrap();
+        }
+
+        let records: Vec<serde_json::Value> = buffer
+            .contents()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert!(records[0].get("system_asleep").is_none());
+        assert_eq!(records[1]["system_asleep"]["duration_secs"], 1800);
+        assert_eq!(records[1]["system_asleep"]["end"], "2026-01-05T09:30:00+00:00");
+        assert!(records[2].get("system_asleep").is_none());
+    }
+
+    #[test]
+    fn test_file_logger_flushes_when_monitoring_stops() {
+        let buffer = SharedBuffer::default();
+        let mut logger =
+            FileEventLogger::from_writer(buffer.clone(), FlushPolicy::EveryEvents(100));
+        let (event, now) = fixture().remove(1);
+        logger.write_event(&event, now).unwrap();
+        assert!(buffer.contents().is_empty());
+
+        logger.on_monitoring_stopped();
+        assert_eq!(buffer.contents().lines().count(), 1);
+    }
+
+    #[test]
+    fn test_file_logger_writes_the_launch_date() {
+        let buffer = SharedBuffer::default();
+        let mut logger = FileEventLogger::from_writer(buffer.clone(), FlushPolicy::PerEvent);
+        let (mut event, now) = fixture().remove(2);
+        event.app_info.launch_date = event.timestamp.checked_sub(Duration::from_secs(90));
+        logger.write_event(&event, now).unwrap();
+
+        let record: serde_json::Value = serde_json::from_str(&buffer.contents()).unwrap();
+        assert_eq!(record["timestamp"], "2026-01-05T09:01:35+00:00");
+        assert_eq!(record["app"]["launch_date"], "2026-01-05T09:00:05+00:00");
+    }
+
+    #[test]
+    fn test_file_logger_matches_golden_output() {
+        let buffer = SharedBuffer::default();
+        let mut logger = FileEventLogger::from_writer(buffer.clone(), FlushPolicy::PerEvent);
+        for (event, now) in fixture() {
+            logger.write_event(&event, now).unwrap();
+        }
+        assert_golden("file_logger.jsonl", &buffer.contents());
+    }
+
+    #[test]
+    fn test_build_listeners_for_file_json_and_time_tracking() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("events.jsonl");
+        let args = Args::parse_from([
+            "research-tracker",
+            "--format",
+            "json",
+            "--output-file",
+            output.to_str().unwrap(),
+        ]);
+
+        let set = build_listeners(&args, &TrackerConfig::default(), None).unwrap();
+        assert!(set.starters.is_empty());
+        let names: Vec<&str> = set
+            .listeners
+            .iter()
+            .map(|l| l.name().rsplit("::").next().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "BasicEventLogger",
+                "TimeTracker",
+                "UrlTimeTracker",
+                "FileEventLogger"
+            ]
+        );
+
+    }
+
+    #[test]
+    fn test_build_listeners_defers_background_work() {
+        let dir = tempfile::tempdir().unwrap();
+        let modes = dir.path().join("modes.jsonl");
+        let args = Args::parse_from([
+            "research-tracker",
+            "--interaction-modes",
+            modes.to_str().unwrap(),
+        ]);
+        let mut settings = TrackerConfig::default();
+        settings.reminders.enabled = true;
+
+        // A ticker for the reminder and a sampler for interaction modes,
+        // neither started until the set is
+        let set = build_listeners(&args, &settings, None).unwrap();
+        assert_eq!(set.starters.len(), 2);
+        assert!(modes.exists());
+    }
+
+    #[test]
+    fn test_build_listeners_tags_by_category_alone() {
+        let args = Args::parse_from(["research-tracker"]);
+        let mut settings = TrackerConfig::default();
+        settings
+            .tags
+            .categories
+            .insert("ide".to_string(), "work".to_string());
+
+        let set = build_listeners(&args, &settings, None).unwrap();
+        assert!(set
+            .listeners
+            .iter()
+            .any(|l| l.name().ends_with("TagTimeAggregator")));
+    }
+
+    #[test]
+    fn test_schema_validates_file_logger_output() {
+        use research_assistant_tracker::core::accessibility::{WindowFrame, WindowInfo};
+        use research_assistant_tracker::core::app_switcher::{
+            AppInfo, EnhancedSummary, WorkspaceSummary,
+        };
+        use research_assistant_tracker::extractors::ContextValue;
+
+        let output =
+            std::env::temp_dir().join(format!("schema_sample_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&output);
+
+        let mut event = AppSwitchEvent::new(
+            AppSwitchType::Foreground,
+            AppInfo::new("Safari".to_string(), "com.apple.Safari".to_string(), 42),
+        );
+        event.previous_app = Some(AppInfo::new(
+            "Terminal".to_string(),
+            "com.apple.Terminal".to_string(),
+            7,
+        ));
+        event.workspace = Some(WorkspaceSummary {
+            window_count: 2,
+            focused_title: Some("Docs".to_string()),
+            total_screen_coverage: None,
+            is_fullscreen: None,
+            is_minimized: None,
+            tab_titles: Vec::new(),
+            active_file_paths: Vec::new(),
+            primary_url: Some("https://example.com/".to_string()),
+        });
+        let mut enhanced = EnhancedSummary::default();
+        enhanced
+            .extracted_context
+            .insert("lab.notebook".to_string(), ContextValue::Number(3.0));
+        event.enhanced = Some(enhanced);
+        event.confidence = Some(1.0);
+        event.windows = Some(vec![WindowInfo {
+            title: Some("Docs".to_string()),
+            bounds: Some(WindowFrame {
+                x: 0.0,
+                y: 25.0,
+                width: 1280.0,
+                height: 775.0,
+            }),
+            minimized: false,
+            main: true,
+        }]);
+
+        let mut logger = FileEventLogger::new(output.clone(), FlushPolicy::PerEvent).unwrap();
+        logger.on_app_switch(&event);
+        drop(logger);
+
+        let line = std::fs::read_to_string(&output).unwrap();
+        let record: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        let schema = serde_json::to_value(EventRecord::schema()).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        let errors: Vec<String> = validator
+            .iter_errors(&record)
+            .map(|e| e.to_string())
+            .collect();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        // And the schema is strict enough to reject a record without an app
+        let mut broken = record.clone();
+        broken.as_object_mut().unwrap().remove("app");
+        assert!(!validator.is_valid(&broken));
+
+        let _ = std::fs::remove_file(output);
+    }
+
+    #[test]
+    fn test_log_filter_merges_verbosity_and_directives() {
+        let accessibility = "research_assistant_tracker::core::accessibility=debug";
+        assert_eq!(log_filter(0, None), "info");
+        assert_eq!(log_filter(1, None), "debug");
+        assert_eq!(log_filter(3, None), "trace");
+
+        // Module directives follow the verbosity level
+        assert_eq!(
+            log_filter(0, Some(accessibility)),
+            format!("info,{}", accessibility)
+        );
+        assert_eq!(
+            log_filter(2, Some(" hyper=warn , ,sqlx=error ")),
+            "trace,hyper=warn,sqlx=error"
+        );
+
+        // A bare level replaces it
+        assert_eq!(
+            log_filter(2, Some(&format!("{},warn", accessibility))),
+            format!("{},warn", accessibility)
+        );
+        assert_eq!(log_filter(1, Some("off")), "off");
+        assert_eq!(log_filter(1, Some("")), "debug");
+
+        for verbose in 0..3 {
+            let filter = log_filter(verbose, Some(accessibility));
+            assert!(
+                tracing_subscriber::EnvFilter::try_new(&filter).is_ok(),
+                "{}",
+                filter
+            );
+        }
+    }
+
+    #[test]
+    fn test_content_flags_need_consent() {
+        let argv = [
+            "research-tracker",
+            "--capture-content",
+            "--capture-clipboard-thumbnails",
+            "--clipboard-image-hash",
+            "--recent-files",
+            "--window-geometry",
+            "--menu-context",
+            "--capture-screenshots",
+        ];
+        let mut args = Args::parse_from(argv);
+        let mut settings = TrackerConfig::default();
+        settings.attributes.capture_content = true;
+        restrict_to_consent(&mut args, &mut settings, &ContentConsent::default());
+        assert!(!args.capture_content && !settings.attributes.capture_content);
+        assert!(!args.capture_clipboard_thumbnails && !args.clipboard_image_hash);
+        assert!(!args.recent_files && !args.capture_screenshots);
+        // Window titles and menu paths are titles too
+        assert!(!args.window_geometry && !args.menu_context);
+        // The clipboard is still watched, for metadata only
+        assert!(args.clipboard);
+
+        let granted = Args::parse_from(
+            argv.into_iter()
+                .chain(["--grant-consent", "selection,clipboard,titles"]),
+        );
+        let record = ConsentRecord::new(granted.grant_consent.iter().copied(), Utc::now());
+        let mut args = Args::parse_from(argv);
+        restrict_to_consent(
+            &mut args,
+            &mut TrackerConfig::default(),
+            &ContentConsent::from_record(Some(&record)),
+        );
+        assert!(args.capture_content && args.capture_clipboard_thumbnails && args.recent_files);
+        assert!(args.window_geometry && args.menu_context);
+        assert!(!args.clipboard);
+    }
+}